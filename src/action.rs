@@ -2,7 +2,12 @@ use {
     super::component_name::ComponentName,
     crate::{
         app_error::AppError,
-        tg::td_enums::{TdChatList, TdMessageReplyToMessage},
+        audio_player::AudioTrack,
+        color_scheme::ColorScheme,
+        components::{popup_window::Popup, status_bar::FocusState},
+        local_state::UploadDescriptor,
+        tg::attachments::SelfDestruct,
+        tg::td_enums::{TdChatList, TdMessageReplyToMessage, TdMessageSender, TdTextQuote},
     },
     crossterm::event::{KeyCode, KeyModifiers},
     ratatui::layout::Rect,
@@ -66,6 +71,205 @@ impl From<Modifiers> for KeyModifiers {
     }
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `ChatDeletion` is an enum that represents the way the history of a chat is
+/// deleted.
+pub enum ChatDeletion {
+    /// Delete the messages of the chat only for the user.
+    ClearHistory,
+    /// Delete the messages of the chat for the user and the other members.
+    ClearHistoryForEveryone,
+    /// Leave the group and remove it from the chat list.
+    LeaveAndDelete,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `ChatBatchOperation` is an enum that represents an operation applied to
+/// several chats of the chat list at once.
+pub enum ChatBatchOperation {
+    /// Mark all the messages of the chats as read.
+    MarkAsRead,
+    /// Mute the notifications of the chats.
+    Mute,
+    /// Unmute the notifications of the chats.
+    Unmute,
+    /// Move the chats to the archive.
+    Archive,
+    /// Add the chats to the chat folder with the given identifier.
+    AddToFolder(i32),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `ChatFolderIncludeType` is an enum that represents a type of chats that
+/// a chat folder can include as a whole.
+pub enum ChatFolderIncludeType {
+    /// The chats with the contacts of the user.
+    Contacts,
+    /// The chats with the users that are not contacts.
+    NonContacts,
+    /// The basic groups and the supergroups.
+    Groups,
+    /// The channels.
+    Channels,
+    /// The chats with the bots.
+    Bots,
+}
+/// Implementation of the `ChatFolderIncludeType` enum.
+impl ChatFolderIncludeType {
+    /// All the types, in the order they are shown.
+    pub const ALL: [Self; 5] = [
+        Self::Contacts,
+        Self::NonContacts,
+        Self::Groups,
+        Self::Channels,
+        Self::Bots,
+    ];
+    /// Get the name of the type.
+    ///
+    /// # Returns
+    /// * `&'static str` - The name of the type.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Contacts => "Contacts",
+            Self::NonContacts => "Non-contacts",
+            Self::Groups => "Groups",
+            Self::Channels => "Channels",
+            Self::Bots => "Bots",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `ChatFolderEdit` is an enum that represents a change of a chat folder.
+pub enum ChatFolderEdit {
+    /// Change the name of the folder.
+    Rename(String),
+    /// Include or stop including a type of chats.
+    ToggleIncludeType(ChatFolderIncludeType),
+    /// Include a chat in the folder.
+    IncludeChat(i64),
+    /// Exclude a chat from the folder.
+    ExcludeChat(i64),
+    /// Remove a chat from the included and the excluded chats.
+    RemoveChat(i64),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `StickerSetEdit` is an enum that represents a change of a sticker set.
+pub enum StickerSetEdit {
+    /// Install the set.
+    Install,
+    /// Remove the set from the installed sets.
+    Remove,
+    /// Move the set before the previous installed set.
+    MoveUp,
+    /// Move the set after the next installed set.
+    MoveDown,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `Moderation` is an enum that represents a quick action of a group admin on
+/// a message and its sender.
+pub enum Moderation {
+    /// Delete the message for everyone and ban its sender from the group.
+    DeleteAndBan,
+    /// Delete all the messages of the sender of the message.
+    DeleteAllFromSender,
+    /// Report the message to Telegram as spam.
+    Report,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `ChatMemberEdit` is an enum that represents an action of a group admin on
+/// a member, picked in the members panel.
+pub enum ChatMemberEdit {
+    /// Make the member an admin, who can delete the messages, restrict the
+    /// members and invite users.
+    Promote,
+    /// Let the member only read the messages.
+    Restrict,
+    /// Remove the member from the group, who can join it again.
+    Remove,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `DraftResolution` is an enum that represents how a draft changed on
+/// another device while the user was typing in the prompt is resolved.
+pub enum DraftResolution {
+    /// Keep the text of the prompt, which replaces the other draft.
+    KeepMine,
+    /// Replace the text of the prompt with the other draft.
+    TakeTheirs,
+    /// Show the difference between the two drafts, without resolving.
+    ViewDiff,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `NewChatKind` is an enum that represents the kind of a chat created by the
+/// user.
+pub enum NewChatKind {
+    /// A group, with some contacts of the user as its first members.
+    Group,
+    /// A channel, whose messages are sent only by its admins.
+    Channel,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `NewChat` is a struct that represents a chat being created by the user,
+/// filled step by step before it is created.
+pub struct NewChat {
+    /// The kind of the chat.
+    pub kind: NewChatKind,
+    /// The title of the chat.
+    pub title: String,
+    /// The ids of the users added to a group.
+    pub member_ids: Vec<i64>,
+    /// The description of a channel.
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `CloudPasswordEdit` is an enum that represents a change of the cloud
+/// password of the current user, the password of the two-step verification.
+pub enum CloudPasswordEdit {
+    /// Set a password when there is none.
+    Set,
+    /// Change the current password.
+    Change,
+    /// Remove the current password.
+    Remove,
+    /// Set or change the recovery email address of the password.
+    SetRecoveryEmail,
+}
+
+impl CloudPasswordEdit {
+    /// Get the fields the user is asked for to make the change, in order.
+    ///
+    /// # Returns
+    /// * `&[(&str, bool)]` - The title of each field and whether it is a
+    ///   secret that is not shown while it is typed.
+    pub fn fields(&self) -> &'static [(&'static str, bool)] {
+        match self {
+            CloudPasswordEdit::Set => &[
+                ("New password", true),
+                ("Repeat the new password", true),
+                ("Hint for the password (can be empty)", false),
+                ("Recovery email (can be empty)", false),
+            ],
+            CloudPasswordEdit::Change => &[
+                ("Current password", true),
+                ("New password", true),
+                ("Repeat the new password", true),
+                ("Hint for the new password (can be empty)", false),
+            ],
+            CloudPasswordEdit::Remove => &[("Current password", true)],
+            CloudPasswordEdit::SetRecoveryEmail => {
+                &[("Current password", true), ("New recovery email", false)]
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 // Action` is an enum that represents an action that can be handled by the
 /// main application loop and the components of the user interface.
@@ -90,6 +294,11 @@ pub enum Action {
     FocusLost,
     /// Focus Gained action.
     FocusGained,
+    /// SetOnline action with a `bool`.
+    /// It is used to tell Telegram whether the user is online, when they go
+    /// away after some time without input or come back. The parameter is
+    /// `online`.
+    SetOnline(bool),
 
     /// GetMe action.
     GetMe,
@@ -104,6 +313,14 @@ pub enum Action {
     SendMessageEdited(i64, String),
     /// GetChatHistory action.
     GetChatHistory,
+    /// GetNewerChatHistory action.
+    /// It is used to load the messages newer than the loaded ones of the
+    /// open chat, if they were dropped or not loaded yet.
+    GetNewerChatHistory,
+    /// GetLatestChatHistory action.
+    /// It is used to load the latest messages of the open chat again, if the
+    /// newer ones were dropped or not loaded yet.
+    GetLatestChatHistory,
     /// DeleteMessages action.
     /// The first parameter is the `message_ids` and the second parameter is the `revoke`.
     /// If `revoke` is true, the message will be deleted for everyone.
@@ -111,6 +328,9 @@ pub enum Action {
     DeleteMessages(Vec<i64>, bool),
     /// ViewAllMessages action.
     ViewAllMessages,
+    /// ViewMessages action with the `message_ids` of the open chat that
+    /// scrolled into view, to mark them as read.
+    ViewMessages(Vec<i64>),
 
     /// Focus action with a `ComponentName`.
     FocusComponent(ComponentName),
@@ -118,6 +338,10 @@ pub enum Action {
     UnfocusComponent,
     /// Toggle ChatList action.
     ToggleChatList,
+    /// ToggleMemoryHud action.
+    /// It is used to show or hide the memory used and the messages kept in
+    /// it in the status bar.
+    ToggleMemoryHud,
     /// Increase ChatList size action.
     IncreaseChatListSize,
     /// Decrease ChatList size action.
@@ -126,35 +350,569 @@ pub enum Action {
     IncreasePromptSize,
     /// Decrease Prompt size action.
     DecreasePromptSize,
+    /// ToggleMarkdownPreview action.
+    /// It is used to show the message typed in the prompt as it will be
+    /// sent, with its Markdown formatted, or as it is typed.
+    ToggleMarkdownPreview,
     /// Key action with a key code.
     Key(KeyCode, Modifiers),
     /// Update area action with a rectangular area.
     UpdateArea(Rect),
+    /// UpdateFocusState action with the state of the focused component.
+    /// It is used by the status bar to show the key hints.
+    UpdateFocusState(FocusState),
     /// ShowChatWindowReply action.
     ShowChatWindowReply,
     /// HideChatWindowReply action.
     HideChatWindowReply,
+    /// ChatWindowQuoteReply action.
+    /// It is used to select a part of the selected message to quote in a
+    /// reply.
+    ChatWindowQuoteReply,
+    /// ChatWindowOpenOrigin action.
+    /// It is used to open the original message of the selected forwarded
+    /// message.
+    ChatWindowOpenOrigin,
+    /// ChatWindowQueueAudio action.
+    /// It is used to add the selected audio message to the playlist.
+    ChatWindowQueueAudio,
+    /// ChatWindowShowDetails action.
+    /// It is used to show the details of the selected message.
+    ChatWindowShowDetails,
+    /// ChatWindowModerate action.
+    /// It is used to show the moderation actions of the selected message.
+    ChatWindowModerate,
+    /// ChatWindowPin action.
+    /// It is used to pin or unpin the selected message.
+    ChatWindowPin,
+    /// ChatWindowShowProfile action.
+    /// It is used to show the profile of the sender of the selected message.
+    ChatWindowShowProfile,
+    /// ChatWindowSpeak action.
+    /// It is used to read the selected message aloud.
+    ChatWindowSpeak,
+    /// ChatWindowReact action.
+    /// It is used to add a reaction to the selected message, or remove one.
+    ChatWindowReact,
+    /// ChatWindowOpenMedia action.
+    /// It is used to open the photo or the video of the selected message.
+    ChatWindowOpenMedia,
+    /// ChatWindowOpenComments action.
+    /// It is used to open the comments of the selected channel post.
+    ChatWindowOpenComments,
+    /// OpenComments action with an `i64`.
+    /// It is used to open the discussion group of the open channel at a
+    /// post, replying to it to comment it. The parameter is the
+    /// `message_id` of the post.
+    OpenComments(i64),
+    /// ChatWindowJumpToDate action.
+    /// It is used to ask for a date to jump to in the history of the open
+    /// chat.
+    ChatWindowJumpToDate,
+    /// JumpToDate action with a `String`.
+    /// It is used to open the chat at the last message sent before a date.
+    /// The parameter is the `date`, as typed by the user.
+    JumpToDate(String),
+    /// ChatWindowButtons action.
+    /// It is used to select the buttons of the inline keyboard of the
+    /// selected message of a bot.
+    ChatWindowButtons,
+    /// PressInlineButton action with an `i64` and two `usize`.
+    /// It is used to press a button of the inline keyboard of a message of
+    /// the open chat. The first parameter is the `message_id`, the second
+    /// parameter is the `row` and the third parameter is the `column` of the
+    /// button.
+    PressInlineButton(i64, usize, usize),
+    /// OpenUrl action with a `String`.
+    /// It is used to open a link with the URL open command. The parameter is
+    /// the `url`.
+    OpenUrl(String),
+    /// ShowPopup action with a `Popup`.
+    /// It is used to show a popup on top of the other components.
+    ShowPopup(Popup),
+    /// HidePopup action.
+    HidePopup,
+    /// SetPopupStatus action with two `String`.
+    /// The first parameter is the `text` the status refers to and the second
+    /// parameter is the `status` shown under the text of an input popup.
+    /// The status is shown only while the typed text is still `text`.
+    SetPopupStatus(String, String),
+
+    /// OpenChat action with a `i64`.
+    /// The parameter is the `chat_id` of the chat to open.
+    OpenChat(i64),
+    /// ReopenLastChat action.
+    /// It is used to reopen the last closed or switched-away chat.
+    ReopenLastChat,
+    /// NextFavoriteChat action.
+    /// It is used to open the favorite chat that follows the open chat.
+    NextFavoriteChat,
+    /// ShowRecentChats action.
+    /// It is used to show a popup with the recently closed chats.
+    ShowRecentChats,
+    /// ShowSendFile action.
+    /// It is used to show a file picker to choose a file to send.
+    ShowSendFile,
+    /// SendFile action with a `String` and an `Option<String>`.
+    /// The first parameter is the `path` of the file to send to the open chat
+    /// and the second parameter is its `caption`, if any. The JPEG and PNG
+    /// images are sent as photos.
+    SendFile(String, Option<String>),
+    /// SplitAndSendFile action with a `String`.
+    /// The parameter is the `path` of a file that exceeds the upload size
+    /// limit. The file is split with the configured external command and the
+    /// parts are sent to the open chat.
+    SplitAndSendFile(String),
+    /// ShowAttachments action.
+    /// It is used to show the attachments staged to be sent with the next
+    /// message, that can be removed, and to stage new ones.
+    ShowAttachments,
+    /// StageAttachment action with a `String`.
+    /// The parameter is the `path` of a file staged to be sent with the next
+    /// message.
+    StageAttachment(String),
+    /// StageClipboardImage action.
+    /// It is used to stage the image copied in the clipboard.
+    StageClipboardImage,
+    /// RecordVoiceNote action.
+    /// It is used to record a voice note with the configured external
+    /// command and to stage it.
+    RecordVoiceNote,
+    /// UnstageAttachment action with a `usize`.
+    /// The parameter is the `index` of the staged attachment to remove.
+    UnstageAttachment(usize),
+    /// ClearAttachments action.
+    /// It is used to remove all the staged attachments.
+    ClearAttachments,
+    /// ShowSelfDestruct action.
+    /// It is used to choose the self-destruction of the staged photos and
+    /// videos.
+    ShowSelfDestruct,
+    /// SetSelfDestruct action with an `Option<SelfDestruct>`.
+    /// The parameter is the self-destruction of the staged photos and
+    /// videos, or `None` to send them as usual.
+    SetSelfDestruct(Option<SelfDestruct>),
+    /// SendAttachments action with a `String`.
+    /// It is used to send the staged attachments to the open chat. The
+    /// parameter is the `caption`, sent with the last attachment.
+    SendAttachments(String),
+    /// ProcessSendQueue action.
+    /// It is used to start the queued uploads when a lane is free.
+    ProcessSendQueue,
+    /// ShowInterruptedUploads action.
+    /// It is used to offer to resume or discard the uploads that were not
+    /// completed when the application was closed.
+    ShowInterruptedUploads,
+    /// ResumeUploads action with a `Vec<UploadDescriptor>`.
+    /// It is used to send again interrupted uploads.
+    ResumeUploads(Vec<UploadDescriptor>),
+    /// DiscardUploads action with a `Vec<UploadDescriptor>`.
+    /// It is used to forget interrupted uploads.
+    DiscardUploads(Vec<UploadDescriptor>),
+    /// ShowMyProfile action.
+    /// It is used to show the "My Profile" settings screen.
+    ShowMyProfile,
+    /// SetUsername action with a `String`.
+    /// The parameter is the new `username` of the current user.
+    SetUsername(String),
+    /// CheckUsername action with a `String`.
+    /// The parameter is the `username` whose availability is checked while
+    /// the user is typing it.
+    CheckUsername(String),
+    /// SetBio action with a `String`.
+    /// The parameter is the new `bio` of the current user.
+    SetBio(String),
+    /// ShowEmojiStatuses action.
+    /// It is used to show a popup with the available emoji statuses.
+    ShowEmojiStatuses,
+    /// SetEmojiStatus action with an `Option<i64>`.
+    /// The parameter is the `custom_emoji_id` of the new emoji status, or
+    /// `None` to remove the emoji status.
+    SetEmojiStatus(Option<i64>),
+    /// ToggleOnlineVisibility action.
+    /// It is used to toggle whether everybody or nobody can see when the
+    /// current user is online.
+    ToggleOnlineVisibility,
+    /// ShowCloudPassword action.
+    /// It is used to show the two-step verification settings, to set, change
+    /// or remove the cloud password and its recovery email address.
+    ShowCloudPassword,
+    /// EditCloudPassword action with a `CloudPasswordEdit`, a `Vec<String>`
+    /// and a `String`.
+    /// It is used to ask the fields of a change of the cloud password one by
+    /// one. The first parameter is the change, the second parameter is the
+    /// fields already entered and the third parameter is the field being
+    /// entered.
+    EditCloudPassword(CloudPasswordEdit, Vec<String>, String),
+    /// CheckRecoveryEmailCode action with a `String`.
+    /// The parameter is the `code` sent to the new recovery email address.
+    CheckRecoveryEmailCode(String),
+    /// ShowMessageSenders action.
+    /// It is used to pick the identity the messages of the open chat are sent
+    /// as, like a channel or anonymously as an admin.
+    ShowMessageSenders,
+    /// SetMessageSender action with an `i64` and a `TdMessageSender`.
+    /// It is used to set the identity the messages of a chat are sent as.
+    /// The first parameter is the `chat_id` and the second parameter is the
+    /// `sender`.
+    SetMessageSender(i64, TdMessageSender),
+    /// ShowAccounts action.
+    /// It is used to show the accounts of the Telegram configuration, to
+    /// switch to another one.
+    ShowAccounts,
+    /// SwitchAccount action with a `String`.
+    /// It is used to restart the application with the database of another
+    /// account. The parameter is the `name` of the account.
+    SwitchAccount(String),
+    /// ShowChatInfo action.
+    /// It is used to show the info of the open chat, like the auto-delete
+    /// timer and, for secret chats, the fingerprint of the encryption key.
+    ShowChatInfo,
+    /// ShowConfigDiagnostics action.
+    /// It is used to show the problems found in the configuration files when
+    /// the application started.
+    ShowConfigDiagnostics,
+    /// CheckForUpdates action.
+    /// It is used to check GitHub for a newer version of `tgt` and show its
+    /// changelog.
+    CheckForUpdates,
+    /// FetchUrlPreview action with a `String`.
+    /// The parameter is the `url` of a link without a Telegram preview, whose
+    /// title and description are fetched locally.
+    FetchUrlPreview(String),
+    /// LoadImage action with an `i32`.
+    /// The parameter is the `file_id` of a photo or a sticker that is
+    /// downloaded and decoded in the background, to be drawn in the chat.
+    LoadImage(i32),
+    /// RunCommand action with two `String`.
+    /// The first parameter is the `name` of a slash command typed in the
+    /// prompt and the second parameter is the `args` typed after it.
+    RunCommand(String, String),
+    /// CompleteCommand action with a `String`.
+    /// The parameter is the `name` of the slash command that replaces the
+    /// text of the prompt.
+    CompleteCommand(String),
+    /// CompleteEmoji action with a `String`.
+    /// The parameter is the `emoji` that replaces the `:shortcode` typed
+    /// before the cursor of the prompt.
+    CompleteEmoji(String),
+    /// InsertText action with a `String`.
+    /// The parameter is the `text` inserted in the prompt at the cursor.
+    InsertText(String),
+    /// ShowTemplates action.
+    /// It is used to show a popup to pick a template of a message among those
+    /// available in the open chat.
+    ShowTemplates,
+    /// InsertTemplate action with a `String`.
+    /// The parameter is the `text` of a template inserted in the prompt,
+    /// whose variables are replaced when the message is sent.
+    InsertTemplate(String),
+    /// ShowEmojiPicker action.
+    /// It is used to show a popup to search and pick an emoji.
+    ShowEmojiPicker,
+    /// PickEmoji action with a `String`.
+    /// The parameter is the `emoji` inserted in the prompt at the cursor.
+    PickEmoji(String),
 
     /// ChatListNext action.
     ChatListNext,
     /// ChatListPrevious action.
     ChatListPrevious,
+    /// ChatListFirst action.
+    /// It is used to select the first chat of the list.
+    ChatListFirst,
+    /// ChatListLast action.
+    /// It is used to select the last loaded chat of the list.
+    ChatListLast,
+    /// ChatListHalfPageDown action.
+    /// It is used to move the selection down by half of the list height.
+    ChatListHalfPageDown,
+    /// ChatListHalfPageUp action.
+    /// It is used to move the selection up by half of the list height.
+    ChatListHalfPageUp,
     /// ChatListSelect action.
     ChatListUnselect,
+    /// PreviewChat action with an `i64`.
+    /// It is used to load the last messages of the chat highlighted in the
+    /// chat list. The parameter is the `chat_id`.
+    PreviewChat(i64),
     /// ChatListOpen action.
     ChatListOpen,
+    /// ChatListToggleFavorite action.
+    /// It is used to add or remove the selected chat from the favorites.
+    ChatListToggleFavorite,
+    /// ChatListNextTab action.
+    /// It is used to switch to the next tab of the chat list: all the chats,
+    /// the favorite chats or the chats of a folder.
+    ChatListNextTab,
+    /// ChatListPreviousTab action.
+    /// It is used to switch to the previous tab of the chat list.
+    ChatListPreviousTab,
+    /// ChatListToggleMark action.
+    /// It is used to add or remove the selected chat from the chats marked
+    /// for a batch operation.
+    ChatListToggleMark,
+    /// ChatListBatchActions action.
+    /// It is used to show the operations that can be applied to the marked
+    /// chats.
+    ChatListBatchActions,
+    /// ChatListDelete action.
+    /// It is used to show the ways to clear the history of the selected chat
+    /// or to leave it.
+    ChatListDelete,
+    /// ChatListShowProfile action.
+    /// It is used to show the profile of the selected chat, or of its user.
+    ChatListShowProfile,
+    /// ShowChatDeletion action with an `i64`.
+    /// It is used to show the ways to clear the history of a chat or to leave
+    /// it, each of them is confirmed. The parameter is the `chat_id`.
+    ShowChatDeletion(i64),
+    /// ToggleChatPin action.
+    /// It is used to pin the selected chat at the top of the chat list shown,
+    /// or to unpin it.
+    ToggleChatPin,
+    /// SetChatPinned action with an `i64`, a `TdChatList` and a `bool`.
+    /// It is used to pin a chat at the top of a chat list or to unpin it.
+    /// The first parameter is the `chat_id`, the second parameter is the
+    /// `chat_list` and the third parameter is `is_pinned`.
+    SetChatPinned(i64, TdChatList, bool),
+    /// ChatListSearch action.
+    /// It is used to start typing a search of the messages of all the chats.
+    ChatListSearch,
+    /// SearchMessages action with a `String`.
+    /// It is used to search the messages of all the chats. The parameter is
+    /// the `query`.
+    SearchMessages(String),
+    /// ShowMentions action.
+    /// It is used to show the unread mentions of the user in all the chats,
+    /// including the replies to their messages.
+    ShowMentions,
+    /// DismissMention action with two `i64`.
+    /// It is used to mark an unread mention as read without opening its
+    /// chat. The first parameter is the `chat_id` and the second parameter is
+    /// the `message_id`.
+    DismissMention(i64, i64),
+    /// ShowContacts action.
+    /// It is used to show the contacts of the user in place of the chat.
+    ShowContacts,
+    /// AddContact action with a `String`.
+    /// The parameter is the phone number and the name of the new contact,
+    /// like `+441234567890 Ada Lovelace`.
+    AddContact(String),
+    /// DeleteContact action with an `i64`.
+    /// The parameter is the `user_id` of the contact to delete.
+    DeleteContact(i64),
+    /// ShowNewChat action.
+    /// It is used to pick the kind of a chat to create, a group or a channel.
+    ShowNewChat,
+    /// SetNewChatTitle action with a `NewChatKind` and a `String`.
+    /// The first parameter is the kind of the chat being created and the
+    /// second parameter is its `title`.
+    SetNewChatTitle(NewChatKind, String),
+    /// ShowNewChatMembers action with a `NewChat`.
+    /// It is used to pick the contacts added to the group being created.
+    ShowNewChatMembers(NewChat),
+    /// CreateNewChat action with a `NewChat`.
+    /// It is used to create the chat and open it. The description of a
+    /// channel is typed in an input popup before.
+    CreateNewChat(NewChat),
+    /// OpenContactChat action with an `i64`.
+    /// It is used to open the private chat with a contact, which is created
+    /// if there is none. The parameter is the `user_id`.
+    OpenContactChat(i64),
+    /// ShowMembers action.
+    /// It is used to show the members of the open group in a panel next to
+    /// the chat, or to hide them.
+    ShowMembers,
+    /// ShowChatMember action with an `i64`.
+    /// It is used to show what can be done with a member of the members
+    /// panel. The parameter is the `user_id`.
+    ShowChatMember(i64),
+    /// ShowMemberProfile action with an `i64`.
+    /// It is used to show the profile of a member of the members panel. The
+    /// parameter is the `user_id`.
+    ShowMemberProfile(i64),
+    /// EditChatMember action with an `i64` and a `ChatMemberEdit`.
+    /// It is used to promote, restrict or remove a member of the group of
+    /// the members panel. The first parameter is the `user_id` and the second
+    /// parameter is the `edit`.
+    EditChatMember(i64, ChatMemberEdit),
+    /// RefreshChat action.
+    /// It is used to request the open chat and its recent messages again from
+    /// Telegram, for when they look stale.
+    RefreshChat,
+    /// SearchChatMessages action with a `String`.
+    /// It is used to search the messages of the open chat, including those
+    /// not loaded. The parameter is the `query`.
+    SearchChatMessages(String),
+    /// ToggleChatMute action.
+    /// It is used to unmute the selected chat, or to show for how long to
+    /// mute it.
+    ToggleChatMute,
+    /// SetChatMuteFor action with an `i64` and an `i32`.
+    /// It is used to mute the notifications of a chat for a time, or to
+    /// unmute them with 0. The first parameter is the `chat_id` and the
+    /// second parameter is `mute_for`, in seconds.
+    SetChatMuteFor(i64, i32),
+    /// DeleteChat action with an `i64` and a `ChatDeletion`.
+    /// It is used to clear the history of a chat or to leave it. The first
+    /// parameter is the `chat_id` and the second parameter is the `deletion`.
+    DeleteChat(i64, ChatDeletion),
+    /// BatchChats action with a `Vec<i64>` and a `ChatBatchOperation`.
+    /// It is used to apply an operation to several chats at once.
+    /// The first parameter is the `chat_ids` and the second parameter is the
+    /// `operation`.
+    BatchChats(Vec<i64>, ChatBatchOperation),
+    /// ShowChatFolders action.
+    /// It is used to show the chat folders, that can be edited, and to
+    /// create a new folder.
+    ShowChatFolders,
+    /// ShowChatFolder action with an `i32`.
+    /// It is used to show the editor of a chat folder. The parameter is the
+    /// `chat_folder_id`.
+    ShowChatFolder(i32),
+    /// ShowChatFolderPicker action with an `i32` and a `bool`.
+    /// It is used to pick a chat to include in a folder or to exclude from
+    /// it. The first parameter is the `chat_folder_id` and the second
+    /// parameter is whether the chat is `included`.
+    ShowChatFolderPicker(i32, bool),
+    /// CreateChatFolder action with a `String` and an `Option<i64>`.
+    /// It is used to create a chat folder. The first parameter is the `name`
+    /// and the second parameter is the first chat included in the folder,
+    /// picked after the name is typed.
+    CreateChatFolder(String, Option<i64>),
+    /// EditChatFolder action with an `i32` and a `ChatFolderEdit`.
+    /// It is used to change a chat folder. The first parameter is the
+    /// `chat_folder_id` and the second parameter is the `edit`.
+    EditChatFolder(i32, ChatFolderEdit),
+    /// ShowStickerSets action.
+    /// It is used to show the installed sticker sets, that can be managed,
+    /// and to add a set from a link.
+    ShowStickerSets,
+    /// ShowStickerSet action with an `i64`.
+    /// It is used to show the actions of a sticker set. The parameter is the
+    /// `set_id`.
+    ShowStickerSet(i64),
+    /// ShowStickerSetPreview action with an `i64`.
+    /// It is used to show the stickers of a set in a grid. The parameter is
+    /// the `set_id`.
+    ShowStickerSetPreview(i64),
+    /// AddStickerSet action with a `String`.
+    /// It is used to find a sticker set from a `t.me/addstickers/...` link or
+    /// from its short name. The parameter is the `link`.
+    AddStickerSet(String),
+    /// EditStickerSet action with an `i64` and a `StickerSetEdit`.
+    /// It is used to install, remove or move a sticker set. The first
+    /// parameter is the `set_id` and the second parameter is the `edit`.
+    EditStickerSet(i64, StickerSetEdit),
+    /// ShowStickerPicker action.
+    /// It is used to show a popup to search and send a sticker to the open
+    /// chat, among the favorite, the recent and the installed stickers.
+    ShowStickerPicker,
+    /// SendSticker action with an `i32` and a `String`.
+    /// It is used to send a sticker to the open chat. The first parameter is
+    /// the `file_id` of the sticker and the second parameter is its `emoji`.
+    SendSticker(i32, String),
+    /// ShowAnimationPicker action.
+    /// It is used to show a popup to pick a saved animation, or to search
+    /// GIFs, and send it to the open chat.
+    ShowAnimationPicker,
+    /// SearchAnimations action with a `String`.
+    /// It is used to search GIFs with the `@gif` bot and show them in a
+    /// popup. The parameter is the `query`.
+    SearchAnimations(String),
+    /// SendAnimation action with an `i32`.
+    /// It is used to send an animation to the open chat. The parameter is
+    /// the `file_id` of the animation.
+    SendAnimation(i32),
+    /// ShowBlocklist action.
+    /// It is used to export the blocked users to a file or to import a
+    /// blocklist from a file.
+    ShowBlocklist,
+    /// ExportBlocklist action with a `String`.
+    /// The parameter is the `path` of the file the blocked users are
+    /// written to.
+    ExportBlocklist(String),
+    /// ImportBlocklist action with a `String`.
+    /// The parameter is the `path` of the file whose users and chats are
+    /// blocked.
+    ImportBlocklist(String),
+    /// ShowMemory action.
+    /// It is used to show the memory used by the process and the number of
+    /// messages, chats and users kept in memory.
+    ShowMemory,
+    /// ShowStorage action.
+    /// It is used to show the space used by the cache of the files and the
+    /// database.
+    ShowStorage,
+    /// SetChatNote action with an `i64` and a `String`.
+    /// The parameters are the `chat_id` and the local note of the chat. An
+    /// empty note removes it.
+    SetChatNote(i64, String),
+    /// EditContactNote action.
+    /// It is used to edit the personal note of the user of the open private
+    /// or secret chat.
+    EditContactNote,
+    /// SetContactNote action with an `i64` and a `String`.
+    /// The parameters are the `user_id` and the personal note of the
+    /// contact. An empty note removes it.
+    SetContactNote(i64, String),
+    /// ColorSchemeChanged action with a `ColorScheme`.
+    /// It is used to switch to the theme of the color scheme of the system,
+    /// when it changes.
+    ColorSchemeChanged(ColorScheme),
 
     /// ChatWindowNext action.
     ChatWindowNext,
     /// ChatWindowPrevious action.
     ChatWindowPrevious,
+    /// ChatWindowFirst action.
+    /// It is used to select the oldest loaded message, at the top.
+    ChatWindowFirst,
+    /// ChatWindowLast action.
+    /// It is used to select the most recent message, at the bottom.
+    ChatWindowLast,
+    /// ChatWindowHalfPageDown action.
+    /// It is used to move the selection down by half of the chat height.
+    ChatWindowHalfPageDown,
+    /// ChatWindowHalfPageUp action.
+    /// It is used to move the selection up by half of the chat height.
+    ChatWindowHalfPageUp,
+    /// ChatWindowSearch action.
+    /// It is used to start typing a search over the loaded messages.
+    ChatWindowSearch,
+    /// ChatWindowSearchNext action.
+    /// It is used to select the next older message matching the search.
+    ChatWindowSearchNext,
+    /// ChatWindowSearchPrevious action.
+    /// It is used to select the next newer message matching the search.
+    ChatWindowSearchPrevious,
+    /// ChatWindowToggleCompact action.
+    /// It is used to toggle the compact mode of the chat, one line per
+    /// message.
+    ChatWindowToggleCompact,
+    /// ChatWindowEditNote action.
+    /// It is used to edit the local note of the chat.
+    ChatWindowEditNote,
+    /// ChatWindowToggleNote action.
+    /// It is used to show or hide the note of the chat above the messages.
+    ChatWindowToggleNote,
+    /// ChatWindowToggleMark action.
+    /// It is used to mark the selected message, or unmark it, to forward
+    /// several messages at once.
+    ChatWindowToggleMark,
+    /// ChatWindowForward action.
+    /// It is used to forward the marked messages, or the selected one.
+    ChatWindowForward,
     /// ChatWindowUnselect action.
     ChatWindowUnselect,
     /// ChatWindowDeleteForEveryone action.
-    /// It is used to delete a message for everyone.
+    /// It is used to ask to delete the marked messages, or the selected one,
+    /// for everyone.
     ChatWindowDeleteForEveryone,
     /// ChatWindowDeleteForMe action.
-    /// It is used to delete a message only for the current user.
+    /// It is used to ask to delete the marked messages, or the selected one,
+    /// only for the current user.
     ChatWindowDeleteForMe,
     /// ChatWindowCopy action.
     ChatWindowCopy,
@@ -165,10 +923,154 @@ pub enum Action {
     /// This action is used to edit a message.
     /// The first parameter is the `message_id` and the second parameter is the `text`.
     EditMessage(i64, String),
-    /// ReplyMessage event with a `String`.
+    /// ReplyMessage event with a `String` and an `Option<TdTextQuote>`.
     /// This event is used to reply to a message.
-    /// The first parameter is the `message_id` and the second parameter is the `text`.
-    ReplyMessage(i64, String),
+    /// The first parameter is the `message_id`, the second parameter is the `text`
+    /// and the third parameter is the quoted part of the message, if any.
+    ReplyMessage(i64, String, Option<TdTextQuote>),
+    /// QuoteReplyMessage action with an `i32` and a `String`.
+    /// This action is used to reply to a message quoting only a part of it.
+    /// The first parameter is the `message_id`, the second parameter is the
+    /// `position` of the quote in UTF-16 code units and the third parameter is
+    /// the quoted `text`.
+    QuoteReplyMessage(i64, i32, String),
+    /// OpenMessage action with two `i64`.
+    /// It is used to open a chat at a message, if the message is accessible.
+    /// The first parameter is the `chat_id` and the second parameter is the
+    /// `message_id`.
+    OpenMessage(i64, i64),
+    /// SaveChatDraft action with an `i64`, a `String` and an
+    /// `Option<TdMessageReplyToMessage>`.
+    /// It is used to save the unsent text of the prompt as the draft of a
+    /// chat when another chat is opened. The first parameter is the
+    /// `chat_id`, the second parameter is the `text`, which clears the draft
+    /// if empty, and the third parameter is the replied message, if any.
+    SaveChatDraft(i64, String, Option<TdMessageReplyToMessage>),
+    /// SendTypingAction action with an `i64`.
+    /// It is used to show to the other members of a chat that the user is
+    /// typing. The parameter is the `chat_id`.
+    SendTypingAction(i64),
+    /// LoadDraftReply action with two `i64`.
+    /// It is used to show the reply bar of the draft loaded in the prompt.
+    /// The first parameter is the `chat_id` and the second parameter is the
+    /// `message_id` of the replied message.
+    LoadDraftReply(i64, i64),
+    /// ChatDraftChanged action with an `i64`.
+    /// It is used to tell the prompt that the draft of the open chat changed,
+    /// like on another device. The parameter is the `chat_id`.
+    ChatDraftChanged(i64),
+    /// ResolveDraftConflict action with a `DraftResolution`.
+    /// It is used to resolve the conflict between the text of the prompt and
+    /// the draft changed on another device.
+    ResolveDraftConflict(DraftResolution),
+    /// ShowMessageDetails action with an `i64`.
+    /// It is used to show the exact times, the delivery state, the forward
+    /// origin and the views of a message of the open chat. The parameter is
+    /// the `message_id`.
+    ShowMessageDetails(i64),
+    /// ShowModeration action with an `i64`.
+    /// It is used to show the moderation actions of a message of the open
+    /// chat. The parameter is the `message_id`.
+    ShowModeration(i64),
+    /// ShowPinMessage action with an `i64`.
+    /// It is used to confirm the pinning of a message of the open chat, with
+    /// or without notifying the members of a group, or its unpinning. The
+    /// parameter is the `message_id`.
+    ShowPinMessage(i64),
+    /// ShowProfile action with an `i64`.
+    /// It is used to show the profile of a user or of a chat, with its full
+    /// info and its photo. The parameter is the `chat_id` of the chat, or
+    /// the `user_id` of the user.
+    ShowProfile(i64),
+    /// ForwardMessages action with a `Vec<i64>`.
+    /// It is used to pick the chat the messages of the open chat are
+    /// forwarded to. The parameter is the `message_ids`.
+    ForwardMessages(Vec<i64>),
+    /// ShowReactions action with an `i64`.
+    /// It is used to pick a reaction for a message of the open chat. The
+    /// parameter is the `message_id`.
+    ShowReactions(i64),
+    /// ShowOpenMedia action with an `i64`.
+    /// It is used to open the photo or the video of a message of the open
+    /// chat, after a confirmation if it self-destructs. The parameter is the
+    /// `message_id`.
+    ShowOpenMedia(i64),
+    /// OpenMedia action with an `i64`.
+    /// It is used to open the photo or the video of a message of the open
+    /// chat with the media viewer command. The parameter is the
+    /// `message_id`.
+    OpenMedia(i64),
+    /// SendReaction action with an `i64` and a `String`.
+    /// It is used to add a reaction to a message of the open chat, or remove
+    /// it if it was already added. The first parameter is the `message_id`
+    /// and the second parameter is the `emoji`.
+    SendReaction(i64, String),
+    /// ShowMessageDeletion action with a `Vec<i64>` and a `bool`.
+    /// It is used to confirm the deletion of messages of the open chat, for
+    /// the current user or for everyone, as allowed by Telegram. The first
+    /// parameter is the `message_ids` and the second parameter is whether the
+    /// deletion for everyone is proposed first.
+    ShowMessageDeletion(Vec<i64>, bool),
+    /// SendForwardedMessages action with an `i64`, an `i64` and a `Vec<i64>`.
+    /// It is used to forward messages to a chat. The parameters are the
+    /// `chat_id` of the destination, the `chat_id` of the messages and the
+    /// `message_ids`.
+    SendForwardedMessages(i64, i64, Vec<i64>),
+    /// ModerateMessage action with an `i64` and a `Moderation`.
+    /// It is used to apply a confirmed moderation action to a message of the
+    /// open chat and its sender. The first parameter is the `message_id` and
+    /// the second parameter is the `moderation`.
+    ModerateMessage(i64, Moderation),
+    /// PinMessage action with an `i64` and a `bool`.
+    /// It is used to pin a message of the open chat. The first parameter is
+    /// the `message_id` and the second parameter is whether the members are
+    /// notified.
+    PinMessage(i64, bool),
+    /// UnpinMessage action with an `i64`.
+    /// It is used to unpin a message of the open chat. The parameter is the
+    /// `message_id`.
+    UnpinMessage(i64),
+    /// LoadPinnedMessage action.
+    /// It is used to load the most recently pinned message of the open chat,
+    /// shown above its messages.
+    LoadPinnedMessage,
+
+    /// QueueAudio action with an `AudioTrack`.
+    /// It is used to add an audio message to the playlist, that starts
+    /// playing if nothing is being played.
+    QueueAudio(AudioTrack),
+    /// AudioNext action.
+    /// It is used to play the next track of the playlist.
+    AudioNext,
+    /// AudioPrevious action.
+    /// It is used to play the previous track of the playlist.
+    AudioPrevious,
+    /// AudioStop action.
+    /// It is used to stop the playback and empty the playlist.
+    AudioStop,
+    /// AudioTogglePause action.
+    /// It is used to pause the track being played, or to resume it.
+    AudioTogglePause,
+    /// AudioSeekForward action.
+    /// It is used to move the track being played forward by a few seconds.
+    AudioSeekForward,
+    /// AudioSeekBackward action.
+    /// It is used to move the track being played backward by a few seconds.
+    AudioSeekBackward,
+    /// AudioTrackFinished action with an `u64`.
+    /// It is sent when the command of the player exits, to play the next
+    /// track. The parameter is the `generation` of the playback.
+    AudioTrackFinished(u64),
+    /// Speak action with a `String`.
+    /// It is used to read a text aloud, after the ones being read.
+    Speak(String),
+    /// SpeechStop action.
+    /// It is used to stop reading aloud and empty the queue.
+    SpeechStop,
+    /// SpeechFinished action with an `u64`.
+    /// It is sent when a text was read aloud, so that the next one is read.
+    /// The parameter is the generation of the speech.
+    SpeechFinished(u64),
 }
 /// Implement the `Action` enum.
 impl Action {
@@ -183,6 +1085,122 @@ impl Action {
     pub fn from_key_event(key: KeyCode, modifiers: KeyModifiers) -> Self {
         Action::Key(key, Modifiers::from(modifiers))
     }
+    /// Check whether an action moves the selection of a list, so that it can
+    /// be repeated by typing a count before its key, like `5j`.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the action can be repeated.
+    pub fn is_repeatable(&self) -> bool {
+        matches!(
+            self,
+            Action::ChatListNext
+                | Action::ChatListPrevious
+                | Action::ChatListHalfPageDown
+                | Action::ChatListHalfPageUp
+                | Action::ChatWindowNext
+                | Action::ChatWindowPrevious
+                | Action::ChatWindowHalfPageDown
+                | Action::ChatWindowHalfPageUp
+                | Action::ChatWindowSearchNext
+                | Action::ChatWindowSearchPrevious
+        )
+    }
+    /// Set the text typed by the user in an action that expects it.
+    /// It is used by the input popup to build the action to send.
+    ///
+    /// # Arguments
+    /// * `text` - The text typed by the user.
+    ///
+    /// # Returns
+    /// * `Action` - The action with the text, or the action unchanged if it
+    ///   does not expect a text.
+    pub fn with_text(self, text: String) -> Self {
+        match self {
+            Action::SendFile(_, caption) => Action::SendFile(text, caption),
+            Action::StageAttachment(_) => Action::StageAttachment(text),
+            Action::SetUsername(_) => Action::SetUsername(text),
+            Action::SetBio(_) => Action::SetBio(text),
+            Action::CreateChatFolder(_, chat_id) => Action::CreateChatFolder(text, chat_id),
+            Action::EditChatFolder(chat_folder_id, ChatFolderEdit::Rename(_)) => {
+                Action::EditChatFolder(chat_folder_id, ChatFolderEdit::Rename(text))
+            }
+            Action::AddStickerSet(_) => Action::AddStickerSet(text),
+            Action::SearchAnimations(_) => Action::SearchAnimations(text),
+            Action::ExportBlocklist(_) => Action::ExportBlocklist(text),
+            Action::ImportBlocklist(_) => Action::ImportBlocklist(text),
+            Action::SetChatNote(chat_id, _) => Action::SetChatNote(chat_id, text),
+            Action::SetContactNote(user_id, _) => Action::SetContactNote(user_id, text),
+            Action::JumpToDate(_) => Action::JumpToDate(text),
+            Action::EditCloudPassword(edit, fields, _) => {
+                Action::EditCloudPassword(edit, fields, text)
+            }
+            Action::CheckRecoveryEmailCode(_) => Action::CheckRecoveryEmailCode(text),
+            Action::AddContact(_) => Action::AddContact(text),
+            Action::SetNewChatTitle(kind, _) => Action::SetNewChatTitle(kind, text),
+            Action::CreateNewChat(mut new_chat) => {
+                new_chat.description = text;
+                Action::CreateNewChat(new_chat)
+            }
+            action => action,
+        }
+    }
+    /// Get the text an input popup starts with, for the actions that edit an
+    /// existing text.
+    ///
+    /// # Returns
+    /// * `Option<&str>` - The text to edit, if any.
+    pub fn initial_text(&self) -> Option<&str> {
+        match self {
+            Action::SetChatNote(_, text) | Action::SetContactNote(_, text) => Some(text),
+            _ => None,
+        }
+    }
+    /// Check whether the text typed in an input popup for the action is a
+    /// secret, like a password, that is not shown.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the text is a secret.
+    pub fn is_secret(&self) -> bool {
+        match self {
+            Action::EditCloudPassword(edit, fields, _) => edit
+                .fields()
+                .get(fields.len())
+                .is_some_and(|(_, secret)| *secret),
+            _ => false,
+        }
+    }
+    /// Set the text selected by the user in an action that expects it.
+    /// It is used by the select popup to build the action to send.
+    ///
+    /// # Arguments
+    /// * `position` - The position of the selected text, in UTF-16 code units.
+    /// * `text` - The text selected by the user.
+    ///
+    /// # Returns
+    /// * `Action` - The action with the selected text, or the action unchanged
+    ///   if it does not expect a selection.
+    pub fn with_selection(self, position: i32, text: String) -> Self {
+        match self {
+            Action::QuoteReplyMessage(message_id, ..) => {
+                Action::QuoteReplyMessage(message_id, position, text)
+            }
+            action => action,
+        }
+    }
+    /// Get the action that checks the text typed by the user in an input
+    /// popup while it is being typed, if the action expects a check.
+    ///
+    /// # Arguments
+    /// * `text` - The text typed by the user.
+    ///
+    /// # Returns
+    /// * `Option<Action>` - The action that checks the text, if any.
+    pub fn check_text(&self, text: &str) -> Option<Action> {
+        match self {
+            Action::SetUsername(_) => Some(Action::CheckUsername(text.to_string())),
+            _ => None,
+        }
+    }
 }
 
 /// Implement the `FromStr` trait for `Action`.
@@ -199,22 +1217,93 @@ impl FromStr for Action {
             "focus_prompt" => Ok(Action::FocusComponent(ComponentName::Prompt)),
             "unfocus_component" => Ok(Action::UnfocusComponent),
             "toggle_chat_list" => Ok(Action::ToggleChatList),
+            "toggle_memory_hud" => Ok(Action::ToggleMemoryHud),
             "increase_chat_list_size" => Ok(Action::IncreaseChatListSize),
             "decrease_chat_list_size" => Ok(Action::DecreaseChatListSize),
             "increase_prompt_size" => Ok(Action::IncreasePromptSize),
+            "toggle_markdown_preview" => Ok(Action::ToggleMarkdownPreview),
             "decrease_prompt_size" => Ok(Action::DecreasePromptSize),
             "chat_list_next" => Ok(Action::ChatListNext),
             "chat_list_previous" => Ok(Action::ChatListPrevious),
+            "chat_list_first" => Ok(Action::ChatListFirst),
+            "chat_list_last" => Ok(Action::ChatListLast),
+            "chat_list_half_page_down" => Ok(Action::ChatListHalfPageDown),
+            "chat_list_half_page_up" => Ok(Action::ChatListHalfPageUp),
             "chat_list_unselect" => Ok(Action::ChatListUnselect),
             "chat_list_open" => Ok(Action::ChatListOpen),
+            "chat_list_toggle_favorite" => Ok(Action::ChatListToggleFavorite),
+            "chat_list_next_tab" => Ok(Action::ChatListNextTab),
+            "chat_list_previous_tab" => Ok(Action::ChatListPreviousTab),
+            "chat_list_toggle_mark" => Ok(Action::ChatListToggleMark),
+            "chat_list_batch_actions" => Ok(Action::ChatListBatchActions),
+            "chat_list_delete" => Ok(Action::ChatListDelete),
+            "chat_list_show_profile" => Ok(Action::ChatListShowProfile),
+            "toggle_chat_pin" => Ok(Action::ToggleChatPin),
+            "toggle_chat_mute" => Ok(Action::ToggleChatMute),
+            "chat_list_search" => Ok(Action::ChatListSearch),
             "chat_window_next" => Ok(Action::ChatWindowNext),
             "chat_window_previous" => Ok(Action::ChatWindowPrevious),
+            "chat_window_first" => Ok(Action::ChatWindowFirst),
+            "chat_window_last" => Ok(Action::ChatWindowLast),
+            "chat_window_half_page_down" => Ok(Action::ChatWindowHalfPageDown),
+            "chat_window_half_page_up" => Ok(Action::ChatWindowHalfPageUp),
+            "chat_window_search" => Ok(Action::ChatWindowSearch),
+            "chat_window_search_next" => Ok(Action::ChatWindowSearchNext),
+            "chat_window_search_previous" => Ok(Action::ChatWindowSearchPrevious),
+            "chat_window_toggle_compact" => Ok(Action::ChatWindowToggleCompact),
+            "chat_window_edit_note" => Ok(Action::ChatWindowEditNote),
+            "chat_window_toggle_note" => Ok(Action::ChatWindowToggleNote),
+            "chat_window_toggle_mark" => Ok(Action::ChatWindowToggleMark),
+            "chat_window_forward" => Ok(Action::ChatWindowForward),
             "chat_window_unselect" => Ok(Action::ChatWindowUnselect),
             "chat_window_delete_for_everyone" => Ok(Action::ChatWindowDeleteForEveryone),
             "chat_window_delete_for_me" => Ok(Action::ChatWindowDeleteForMe),
             "chat_window_copy" => Ok(Action::ChatWindowCopy),
             "chat_window_edit" => Ok(Action::ChatWindowEdit),
             "chat_window_reply" => Ok(Action::ShowChatWindowReply),
+            "chat_window_quote_reply" => Ok(Action::ChatWindowQuoteReply),
+            "chat_window_open_origin" => Ok(Action::ChatWindowOpenOrigin),
+            "chat_window_queue_audio" => Ok(Action::ChatWindowQueueAudio),
+            "chat_window_show_details" => Ok(Action::ChatWindowShowDetails),
+            "chat_window_moderate" => Ok(Action::ChatWindowModerate),
+            "chat_window_pin" => Ok(Action::ChatWindowPin),
+            "chat_window_show_profile" => Ok(Action::ChatWindowShowProfile),
+            "chat_window_speak" => Ok(Action::ChatWindowSpeak),
+            "chat_window_open_media" => Ok(Action::ChatWindowOpenMedia),
+            "chat_window_react" => Ok(Action::ChatWindowReact),
+            "chat_window_buttons" => Ok(Action::ChatWindowButtons),
+            "chat_window_jump_to_date" => Ok(Action::ChatWindowJumpToDate),
+            "chat_window_open_comments" => Ok(Action::ChatWindowOpenComments),
+            "reopen_last_chat" => Ok(Action::ReopenLastChat),
+            "next_favorite_chat" => Ok(Action::NextFavoriteChat),
+            "show_recent_chats" => Ok(Action::ShowRecentChats),
+            "send_file" => Ok(Action::ShowSendFile),
+            "show_attachments" => Ok(Action::ShowAttachments),
+            "show_emoji_picker" => Ok(Action::ShowEmojiPicker),
+            "show_templates" => Ok(Action::ShowTemplates),
+            "show_my_profile" => Ok(Action::ShowMyProfile),
+            "show_chat_folders" => Ok(Action::ShowChatFolders),
+            "show_sticker_sets" => Ok(Action::ShowStickerSets),
+            "show_sticker_picker" => Ok(Action::ShowStickerPicker),
+            "show_animation_picker" => Ok(Action::ShowAnimationPicker),
+            "show_chat_info" => Ok(Action::ShowChatInfo),
+            "show_message_senders" => Ok(Action::ShowMessageSenders),
+            "show_accounts" => Ok(Action::ShowAccounts),
+            "show_mentions" => Ok(Action::ShowMentions),
+            "show_contacts" => Ok(Action::ShowContacts),
+            "show_members" => Ok(Action::ShowMembers),
+            "refresh_chat" => Ok(Action::RefreshChat),
+            "new_chat" => Ok(Action::ShowNewChat),
+            "edit_contact_note" => Ok(Action::EditContactNote),
+            "show_config_diagnostics" => Ok(Action::ShowConfigDiagnostics),
+            "check_for_updates" => Ok(Action::CheckForUpdates),
+            "audio_next" => Ok(Action::AudioNext),
+            "audio_previous" => Ok(Action::AudioPrevious),
+            "audio_stop" => Ok(Action::AudioStop),
+            "audio_toggle_pause" => Ok(Action::AudioTogglePause),
+            "audio_seek_forward" => Ok(Action::AudioSeekForward),
+            "audio_seek_backward" => Ok(Action::AudioSeekBackward),
+            "speech_stop" => Ok(Action::SpeechStop),
             _ => Err(AppError::InvalidAction(s.to_string())),
         }
     }
@@ -1,13 +1,21 @@
 use crate::{
     action::Action,
+    audio_player::AudioPlayer,
     cli::CliArgs,
+    commands::CommandRegistry,
     configs::custom::{
         app_custom::AppConfig, keymap_custom::KeymapConfig, palette_custom::PaletteConfig,
         telegram_custom::TelegramConfig, theme_custom::ThemeConfig,
     },
-    tg::tg_context::TgContext,
+    image_cache::ImageCache,
+    local_state::LocalState,
+    speech::Speaker,
+    tg::{accounts::AccountManager, tg_context::TgContext},
+    update_check::ReleaseInfo,
+    url_preview::UrlPreviewState,
 };
 use ratatui::style::Style;
+use std::collections::HashMap;
 use std::sync::{atomic::AtomicBool, Arc, Mutex, MutexGuard};
 use std::{io, sync::atomic::Ordering};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
@@ -84,13 +92,33 @@ pub struct AppContext {
     /// A boolean flag that represents whether the application should quit or
     /// not.
     quit: AtomicBool,
+    /// A boolean flag that represents whether the application should start
+    /// again once it quits, like after switching the account.
+    restart: AtomicBool,
+    /// The accounts of the Telegram configuration and the active one.
+    account_manager: Mutex<AccountManager>,
     /// The Telegram context.
     tg_context: Arc<TgContext>,
     /// The CLI arguments for the application.
     cli_args: Mutex<CliArgs>,
+    /// The local state of the application that is persisted on disk.
+    local_state: Mutex<LocalState>,
+    /// The previews of the links fetched locally, by URL.
+    url_previews: Mutex<HashMap<String, UrlPreviewState>>,
+    /// The images shown inline in the chat, downloaded in the background.
+    images: Mutex<ImageCache>,
+    /// The slash commands that can be run from the prompt.
+    command_registry: Mutex<CommandRegistry>,
+    /// The playlist of the audio messages.
+    audio_player: Mutex<AudioPlayer>,
+    /// The queue of the messages read aloud.
+    speaker: Mutex<Speaker>,
+    /// The newer release of `tgt`, if the check for updates found one.
+    available_update: Mutex<Option<ReleaseInfo>>,
 }
 /// Implementation of the `AppContext` struct.
 impl AppContext {
+    #[allow(clippy::too_many_arguments)]
     /// Create a new instance of the `App` struct.
     ///
     /// # Arguments
@@ -100,6 +128,9 @@ impl AppContext {
     /// * `palette_config` - The palette configuration.
     /// * `telegram_config` - The Telegram configuration.
     /// * `tg_context` - The Telegram context.
+    /// * `cli_args` - The CLI arguments.
+    /// * `local_state` - The local state loaded from disk.
+    /// * `account_manager` - The accounts and the active one.
     ///
     /// # Returns
     /// * `Result<Self, io::Error>` - An Ok result containing the new instance
@@ -112,9 +143,18 @@ impl AppContext {
         telegram_config: TelegramConfig,
         tg_context: TgContext,
         cli_args: CliArgs,
+        local_state: LocalState,
+        account_manager: AccountManager,
     ) -> Result<Self, io::Error> {
         let (action_tx, action_rx) = tokio::sync::mpsc::unbounded_channel::<Action>();
         let quit = false;
+        let command_registry = CommandRegistry::from_config(&app_config.commands);
+        tg_context
+            .send_queue()
+            .set_max_parallel_uploads(app_config.max_parallel_uploads);
+        tg_context
+            .presence()
+            .set_away_after_minutes(app_config.auto_away_minutes);
         Ok(Self {
             app_config: Mutex::new(app_config),
             keymap_config: Mutex::new(keymap_config),
@@ -124,8 +164,17 @@ impl AppContext {
             action_rx: Mutex::new(action_rx),
             action_tx: Mutex::new(action_tx),
             quit: AtomicBool::new(quit),
+            restart: AtomicBool::new(false),
+            account_manager: Mutex::new(account_manager),
             tg_context: Arc::new(tg_context),
             cli_args: Mutex::new(cli_args),
+            local_state: Mutex::new(local_state),
+            url_previews: Mutex::new(HashMap::new()),
+            images: Mutex::new(ImageCache::default()),
+            command_registry: Mutex::new(command_registry),
+            audio_player: Mutex::new(AudioPlayer::default()),
+            speaker: Mutex::new(Speaker::default()),
+            available_update: Mutex::new(None),
         })
     }
     /// Get the application configuration.
@@ -214,6 +263,29 @@ impl AppContext {
     pub fn quit_store(&self, value: bool) {
         self.quit.store(value, Ordering::Release);
     }
+    /// Get the restart flag.
+    /// This function returns the value of the restart flag.
+    /// The restart flag is a shared resource and is protected by an atomic
+    /// boolean.
+    ///
+    /// # Returns
+    /// * `bool` - The value of the restart flag.
+    pub fn restart_acquire(&self) -> bool {
+        self.restart.load(Ordering::Acquire)
+    }
+    /// Set the restart flag.
+    /// This function sets the value of the restart flag.
+    /// The restart flag is a shared resource and is protected by an atomic
+    /// boolean.
+    pub fn restart_store(&self, value: bool) {
+        self.restart.store(value, Ordering::Release);
+    }
+    /// Get the accounts of the Telegram configuration.
+    /// This function takes the lock on the accounts and returns them.
+    /// The accounts are a shared resource and are protected by a mutex.
+    pub fn account_manager(&self) -> MutexGuard<'_, AccountManager> {
+        self.account_manager.lock().unwrap()
+    }
     /// Get the Telegram context.
     /// This function returns the Telegram context.
     /// The Telegram context is a shared resource and the contained variables are
@@ -228,6 +300,58 @@ impl AppContext {
     pub fn cli_args(&self) -> MutexGuard<'_, CliArgs> {
         self.cli_args.lock().unwrap()
     }
+    /// Get the local state.
+    /// This function takes the lock on the local state and returns the local
+    /// state.
+    /// The local state is a shared resource and is protected by a mutex.
+    pub fn local_state(&self) -> MutexGuard<'_, LocalState> {
+        self.local_state.lock().unwrap()
+    }
+    /// Get the previews of the links fetched locally.
+    /// This function takes the lock on the previews and returns them.
+    /// The previews are a shared resource and are protected by a mutex.
+    pub fn url_previews(&self) -> MutexGuard<'_, HashMap<String, UrlPreviewState>> {
+        self.url_previews.lock().unwrap()
+    }
+    /// Get the images shown inline in the chat.
+    /// This function takes the lock on the images and returns them.
+    /// The images are a shared resource and are protected by a mutex.
+    pub fn images(&self) -> MutexGuard<'_, ImageCache> {
+        self.images.lock().unwrap()
+    }
+    /// Get the registry of the slash commands.
+    /// This function takes the lock on the registry and returns it.
+    /// The registry is a shared resource and is protected by a mutex, so that
+    /// other parts of the application can register their own commands.
+    pub fn command_registry(&self) -> MutexGuard<'_, CommandRegistry> {
+        self.command_registry.lock().unwrap()
+    }
+    /// Get the audio player.
+    /// This function takes the lock on the audio player and returns it.
+    /// The audio player is a shared resource and is protected by a mutex.
+    pub fn audio_player(&self) -> MutexGuard<'_, AudioPlayer> {
+        self.audio_player.lock().unwrap()
+    }
+    /// Get the speaker that reads the messages aloud.
+    /// This function takes the lock on the speaker and returns it.
+    /// The speaker is a shared resource and is protected by a mutex.
+    pub fn speaker(&self) -> MutexGuard<'_, Speaker> {
+        self.speaker.lock().unwrap()
+    }
+    /// Get the newer release of `tgt` found by the check for updates.
+    /// This function takes the lock on the release and returns it.
+    /// The release is a shared resource and is protected by a mutex.
+    pub fn available_update(&self) -> MutexGuard<'_, Option<ReleaseInfo>> {
+        self.available_update.lock().unwrap()
+    }
+    /// Save the local state to disk.
+    /// Errors are logged and otherwise ignored, because losing the local state
+    /// should never prevent the application from working.
+    pub fn save_local_state(&self) {
+        if let Err(e) = self.local_state().save() {
+            tracing::error!("Failed to save the local state: {}", e);
+        }
+    }
 
     // ===== COMMON ======
     theme_style_generate!(
@@ -253,6 +377,16 @@ impl AppContext {
         chat_list,
         item_unread_counter
     );
+    theme_style_generate!(
+        style_chat_list_item_failed_send,
+        chat_list,
+        item_failed_send
+    );
+    theme_style_generate!(
+        style_chat_list_item_unread_mention,
+        chat_list,
+        item_unread_mention
+    );
 
     // ===== CHAT =====
     theme_style_generate!(style_chat, chat, self);
@@ -341,4 +475,9 @@ impl AppContext {
     theme_style_generate!(style_title_bar_title1, title_bar, title1);
     theme_style_generate!(style_title_bar_title2, title_bar, title2);
     theme_style_generate!(style_title_bar_title3, title_bar, title3);
+
+    // ===== POPUP =====
+    theme_style_generate!(style_popup, popup, self);
+    theme_style_generate!(style_popup_item_selected, popup, item_selected);
+    theme_style_generate!(style_popup_input_text, popup, input_text);
 }
@@ -0,0 +1,495 @@
+use crate::utils::{shell_command, shell_quote};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// The placeholder replaced with the path of the audio file in the command
+/// of the player.
+pub const FILE_PLACEHOLDER: &str = "{file}";
+/// The placeholder replaced with the position the playback starts from, in
+/// seconds, in the command of the player.
+pub const START_PLACEHOLDER: &str = "{start}";
+/// The time by which the playback is moved forward or backward when seeking.
+pub const SEEK_STEP: Duration = Duration::from_secs(10);
+/// The number of cells of the progress bar of the track being played.
+const PROGRESS_BAR_WIDTH: usize = 20;
+/// The interval at which the audio output checks whether the playback is
+/// stopped.
+#[cfg(feature = "audio-output")]
+const OUTPUT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `AudioMetadata` is a struct that represents the metadata of an audio file
+/// sent as music.
+pub struct AudioMetadata {
+    /// The TDLib id of the file.
+    pub file_id: i32,
+    /// The title of the track, as defined by the sender.
+    pub title: String,
+    /// The performer of the track, as defined by the sender.
+    pub performer: String,
+    /// The original name of the file.
+    pub file_name: String,
+    /// The duration of the track in seconds.
+    pub duration: i32,
+}
+/// Implementation of the `AudioMetadata` struct.
+impl AudioMetadata {
+    /// Get the name of the track, like "Artist – Title".
+    /// The name of the file is used if the title is not defined.
+    ///
+    /// # Returns
+    /// * `String` - The name of the track.
+    pub fn name(&self) -> String {
+        match (self.performer.is_empty(), self.title.is_empty()) {
+            (false, false) => format!("{} – {}", self.performer, self.title),
+            (true, false) => self.title.clone(),
+            _ if !self.file_name.is_empty() => self.file_name.clone(),
+            (false, true) => self.performer.clone(),
+            (true, true) => "Unknown track".to_string(),
+        }
+    }
+    /// Get the duration of the track, like `3:25` or `1:02:03`.
+    ///
+    /// # Returns
+    /// * `String` - The formatted duration.
+    pub fn duration(&self) -> String {
+        format_duration(self.duration.max(0) as u64)
+    }
+}
+/// Implementation of the `From` trait for the `AudioMetadata` struct.
+impl From<&tdlib_rs::types::VoiceNote> for AudioMetadata {
+    fn from(voice_note: &tdlib_rs::types::VoiceNote) -> Self {
+        Self {
+            file_id: voice_note.voice.id,
+            title: "Voice note".to_string(),
+            performer: String::new(),
+            file_name: String::new(),
+            duration: voice_note.duration,
+        }
+    }
+}
+/// Implementation of the `From` trait for the `AudioMetadata` struct.
+impl From<&tdlib_rs::types::Audio> for AudioMetadata {
+    fn from(audio: &tdlib_rs::types::Audio) -> Self {
+        Self {
+            file_id: audio.audio.id,
+            title: audio.title.clone(),
+            performer: audio.performer.clone(),
+            file_name: audio.file_name.clone(),
+            duration: audio.duration,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `AudioTrack` is a struct that represents an audio message in the
+/// playlist.
+pub struct AudioTrack {
+    /// The id of the chat of the message.
+    pub chat_id: i64,
+    /// The id of the message.
+    pub message_id: i64,
+    /// The metadata of the audio file.
+    pub metadata: AudioMetadata,
+}
+
+#[derive(Debug, Default)]
+/// `AudioPlayer` is a struct that represents a simple playlist of audio
+/// messages, played one after the other with an external command, or with
+/// the audio output of the system when the `audio-output` feature is
+/// enabled and no command is set.
+/// Pausing stops the playback and resuming or seeking starts it again from
+/// the position reached, so that any command supporting `{start}` can be
+/// used.
+pub struct AudioPlayer {
+    /// The tracks of the playlist, in order.
+    queue: Vec<AudioTrack>,
+    /// The index of the track being played, if any.
+    current: Option<usize>,
+    /// The generation of the playback, increased every time a track is
+    /// started or stopped, so that the end of an old track is ignored.
+    generation: u64,
+    /// The channel used to stop the command of the track being played.
+    stop_tx: Option<oneshot::Sender<()>>,
+    /// The path of the downloaded file of the track being played.
+    path: Option<String>,
+    /// The position of the track when it was last started or paused.
+    offset: Duration,
+    /// The instant the track was last started, `None` while it is paused or
+    /// downloaded.
+    started_at: Option<Instant>,
+}
+/// Implementation of the `AudioPlayer` struct.
+impl AudioPlayer {
+    /// Add a track at the end of the playlist.
+    /// A track that is already in the playlist is not added again.
+    ///
+    /// # Arguments
+    /// * `track` - The track to add.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the track was added.
+    pub fn enqueue(&mut self, track: AudioTrack) -> bool {
+        if self
+            .queue
+            .iter()
+            .any(|queued| queued.chat_id == track.chat_id && queued.message_id == track.message_id)
+        {
+            return false;
+        }
+        self.queue.push(track);
+        true
+    }
+    /// Move to the next track of the playlist.
+    /// If nothing is playing, the first track is selected.
+    ///
+    /// # Returns
+    /// * `Option<AudioTrack>` - The next track or `None` at the end of the
+    ///   playlist.
+    pub fn next_track(&mut self) -> Option<AudioTrack> {
+        let next = self.current.map_or(0, |i| i + 1);
+        self.select(next)
+    }
+    /// Move to the previous track of the playlist.
+    ///
+    /// # Returns
+    /// * `Option<AudioTrack>` - The previous track, or the first one if the
+    ///   first track is being played.
+    pub fn previous_track(&mut self) -> Option<AudioTrack> {
+        let previous = self.current.map_or(0, |i| i.saturating_sub(1));
+        self.select(previous)
+    }
+    /// Select a track of the playlist, from its beginning.
+    fn select(&mut self, index: usize) -> Option<AudioTrack> {
+        let track = self.queue.get(index)?.clone();
+        self.current = Some(index);
+        self.path = None;
+        self.offset = Duration::ZERO;
+        self.started_at = None;
+        Some(track)
+    }
+    /// Get the track being played and its position in the playlist.
+    ///
+    /// # Returns
+    /// * `Option<(usize, usize, &AudioTrack)>` - The position, starting from
+    ///   1, the number of tracks and the track.
+    pub fn current(&self) -> Option<(usize, usize, &AudioTrack)> {
+        let index = self.current?;
+        self.queue
+            .get(index)
+            .map(|track| (index + 1, self.queue.len(), track))
+    }
+    /// Stop the playback and empty the playlist.
+    pub fn clear(&mut self) {
+        self.stop();
+        self.queue.clear();
+        self.current = None;
+        self.path = None;
+        self.offset = Duration::ZERO;
+        self.started_at = None;
+    }
+    /// Stop the command of the track being played, if any.
+    /// The end of the tracks started before is ignored from now on.
+    ///
+    /// # Returns
+    /// * `u64` - The generation of the playback after stopping it.
+    pub fn stop(&mut self) -> u64 {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        self.generation += 1;
+        self.generation
+    }
+    /// Start playing a downloaded file with the command of the player.
+    /// Nothing is played if another track was started or the playback was
+    /// stopped while the file was being downloaded.
+    ///
+    /// # Arguments
+    /// * `generation` - The generation returned by `stop` when the track was
+    ///   selected.
+    /// * `command_line` - The command of the player, with `{file}` and
+    ///   optionally `{start}`. If empty, the audio output is used.
+    /// * `path` - The path of the downloaded audio file.
+    /// * `on_end` - The function called with the generation of the playback
+    ///   when the command exits by itself.
+    ///
+    /// # Returns
+    /// * `std::io::Result<()>` - An Ok result or an error if the command can
+    ///   not be started.
+    pub fn play(
+        &mut self,
+        generation: u64,
+        command_line: &str,
+        path: &str,
+        on_end: impl FnOnce(u64) + Send + 'static,
+    ) -> std::io::Result<()> {
+        if !self.is_current_generation(generation) {
+            return Ok(());
+        }
+        let (stop_tx, stop_rx) = oneshot::channel();
+        if command_line.is_empty() {
+            play_with_output(path, self.offset, stop_rx, move || on_end(generation))?;
+        } else {
+            play_with_command(command_line, path, self.offset, stop_rx, move || {
+                on_end(generation)
+            })?;
+        }
+        self.stop_tx = Some(stop_tx);
+        self.path = Some(path.to_string());
+        self.started_at = Some(Instant::now());
+        Ok(())
+    }
+    /// Pause the track being played, remembering its position.
+    ///
+    /// # Returns
+    /// * `bool` - Whether a track was being played.
+    pub fn pause(&mut self) -> bool {
+        if self.started_at.is_none() {
+            return false;
+        }
+        self.offset = self.position();
+        self.started_at = None;
+        self.stop();
+        true
+    }
+    /// Prepare to resume the paused track from the position it reached.
+    ///
+    /// # Returns
+    /// * `Option<(u64, String)>` - The generation of the playback and the
+    ///   path of the file to play, or `None` if no track is paused.
+    pub fn resume(&mut self) -> Option<(u64, String)> {
+        if !self.is_paused() {
+            return None;
+        }
+        let path = self.path.clone()?;
+        Some((self.stop(), path))
+    }
+    /// Move the position of the track forward or backward. A paused track
+    /// stays paused at the new position.
+    ///
+    /// # Arguments
+    /// * `forward` - Whether the position is moved forward.
+    ///
+    /// # Returns
+    /// * `Option<(u64, String)>` - The generation of the playback and the
+    ///   path of the file to play again from the new position, or `None` if
+    ///   no track is being played.
+    pub fn seek(&mut self, forward: bool) -> Option<(u64, String)> {
+        let (_, _, track) = self.current()?;
+        let duration = Duration::from_secs(track.metadata.duration.max(0) as u64);
+        let path = self.path.clone()?;
+        let position = self.position();
+        self.offset = if forward {
+            (position + SEEK_STEP).min(duration)
+        } else {
+            position.saturating_sub(SEEK_STEP)
+        };
+        self.started_at.take()?;
+        Some((self.stop(), path))
+    }
+    /// Get the position reached in the track being played.
+    ///
+    /// # Returns
+    /// * `Duration` - The position, never beyond the end of the track.
+    pub fn position(&self) -> Duration {
+        let elapsed = self
+            .started_at
+            .map_or(Duration::ZERO, |started_at| started_at.elapsed());
+        let duration = self.current().map_or(Duration::ZERO, |(_, _, track)| {
+            Duration::from_secs(track.metadata.duration.max(0) as u64)
+        });
+        (self.offset + elapsed).min(duration)
+    }
+    /// Check whether the track being played is paused.
+    ///
+    /// # Returns
+    /// * `bool` - Whether a downloaded track is selected and not playing.
+    pub fn is_paused(&self) -> bool {
+        self.current.is_some() && self.path.is_some() && self.started_at.is_none()
+    }
+    /// Check whether a playback is still the current one, so that its end
+    /// must start the next track.
+    ///
+    /// # Arguments
+    /// * `generation` - The generation of the playback.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the playback is still the current one.
+    pub fn is_current_generation(&self, generation: u64) -> bool {
+        self.generation == generation
+    }
+    /// Check whether a track is being played or downloaded.
+    ///
+    /// # Returns
+    /// * `bool` - Whether a track is selected.
+    pub fn is_playing(&self) -> bool {
+        self.current.is_some()
+    }
+}
+
+/// Format a duration, like `3:25` or `1:02:03`.
+///
+/// # Arguments
+/// * `seconds` - The duration in seconds.
+///
+/// # Returns
+/// * `String` - The formatted duration.
+pub fn format_duration(seconds: u64) -> String {
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+/// Get the progress bar of the track being played, like
+/// `▶ 0:42 ━━━━━━────────────── 3:25`.
+///
+/// # Arguments
+/// * `position` - The position reached in the track.
+/// * `duration` - The duration of the track in seconds.
+/// * `paused` - Whether the track is paused.
+///
+/// # Returns
+/// * `String` - The progress bar.
+pub fn progress_bar(position: Duration, duration: i32, paused: bool) -> String {
+    let duration = duration.max(0) as u64;
+    let filled = if duration == 0 {
+        0
+    } else {
+        (position.as_secs().min(duration) as usize * PROGRESS_BAR_WIDTH) / duration as usize
+    };
+    format!(
+        "{} {} {}{} {}",
+        if paused { "⏸" } else { "▶" },
+        format_duration(position.as_secs()),
+        "━".repeat(filled),
+        "─".repeat(PROGRESS_BAR_WIDTH - filled),
+        format_duration(duration)
+    )
+}
+
+/// Check whether the audio messages can be played.
+///
+/// # Arguments
+/// * `command_line` - The command of the player.
+///
+/// # Returns
+/// * `bool` - Whether the command is set or the audio output is available.
+pub fn is_available(command_line: &str) -> bool {
+    !command_line.is_empty() || cfg!(feature = "audio-output")
+}
+
+/// Play a file with an external command, in the background.
+///
+/// # Arguments
+/// * `command_line` - The command of the player.
+/// * `path` - The path of the audio file.
+/// * `start` - The position the playback starts from.
+/// * `stop_rx` - The channel that stops the command.
+/// * `on_end` - The function called when the command exits by itself.
+///
+/// # Returns
+/// * `std::io::Result<()>` - An Ok result or an error if the command can not
+///   be started.
+fn play_with_command(
+    command_line: &str,
+    path: &str,
+    start: Duration,
+    stop_rx: oneshot::Receiver<()>,
+    on_end: impl FnOnce() + Send + 'static,
+) -> std::io::Result<()> {
+    let command_line = command_line
+        .replace(FILE_PLACEHOLDER, &shell_quote(path))
+        .replace(START_PLACEHOLDER, &start.as_secs().to_string());
+    tracing::info!("Playing audio with: {}", command_line);
+    let mut child = shell_command(&command_line)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+    tokio::spawn(async move {
+        tokio::select! {
+            status = child.wait() => {
+                tracing::info!("Audio player exited with {:?}", status);
+                on_end();
+            }
+            _ = stop_rx => {
+                if let Err(e) = child.kill().await {
+                    tracing::warn!("Failed to stop the audio player: {}", e);
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Play a file with the audio output of the system, in a thread, since the
+/// output stream can not be moved across threads.
+///
+/// # Arguments
+/// * `path` - The path of the audio file.
+/// * `start` - The position the playback starts from.
+/// * `stop_rx` - The channel that stops the playback.
+/// * `on_end` - The function called when the end of the file is reached, or
+///   when the file can not be decoded.
+///
+/// # Returns
+/// * `std::io::Result<()>` - An Ok result or an error if the file can not be
+///   opened.
+#[cfg(feature = "audio-output")]
+fn play_with_output(
+    path: &str,
+    start: Duration,
+    mut stop_rx: oneshot::Receiver<()>,
+    on_end: impl FnOnce() + Send + 'static,
+) -> std::io::Result<()> {
+    use rodio::{Decoder, OutputStream, Sink, Source};
+
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+    std::thread::spawn(move || {
+        let played = (|| -> Result<bool, Box<dyn std::error::Error>> {
+            let (_stream, handle) = OutputStream::try_default()?;
+            let sink = Sink::try_new(&handle)?;
+            sink.append(Decoder::new(file)?.skip_duration(start));
+            while !sink.empty() {
+                if !matches!(stop_rx.try_recv(), Err(oneshot::error::TryRecvError::Empty)) {
+                    sink.stop();
+                    return Ok(false);
+                }
+                std::thread::sleep(OUTPUT_POLL_INTERVAL);
+            }
+            Ok(true)
+        })();
+        match played {
+            Ok(true) => on_end(),
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!("Failed to play the audio: {}", e);
+                on_end();
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Report that the audio output is not available, since `tgt` is built
+/// without the `audio-output` feature.
+#[cfg(not(feature = "audio-output"))]
+fn play_with_output(
+    _path: &str,
+    _start: Duration,
+    _stop_rx: oneshot::Receiver<()>,
+    _on_end: impl FnOnce() + Send + 'static,
+) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "tgt is built without the audio-output feature, set `audio_player_command` in app.toml",
+    ))
+}
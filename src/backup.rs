@@ -0,0 +1,459 @@
+use crate::{
+    cli::LocalDataCli,
+    configs::custom::{logger_custom::LoggerConfig, telegram_custom::TelegramConfig},
+    utils::{tgt_data_dir, tgt_dir},
+};
+use argon2::Argon2;
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+/// The magic bytes at the start of a backup archive.
+const MAGIC: &[u8; 8] = b"TGTBAK01";
+/// The length of the salt used to derive the key from the password.
+const SALT_LEN: usize = 16;
+/// The length of the nonce used to encrypt the archive.
+const NONCE_LEN: usize = 24;
+/// The environment variable that can hold the password of the archive.
+pub const TGT_BACKUP_PASSWORD: &str = "TGT_BACKUP_PASSWORD";
+
+/// Run the backup or the restore requested from the command line.
+/// The password is taken from the `TGT_BACKUP_PASSWORD` environment variable
+/// or asked on the terminal.
+///
+/// # Arguments
+/// * `local_data_cli` - The local data CLI arguments.
+/// * `telegram_config` - The Telegram configuration, used to skip the TDLib
+///   database.
+/// * `logger_config` - The logger configuration, used to skip the logs.
+///
+/// # Returns
+/// * `i32` - The exit code of the application.
+pub fn run_cli(
+    local_data_cli: &LocalDataCli,
+    telegram_config: &TelegramConfig,
+    logger_config: &LoggerConfig,
+) -> i32 {
+    let password = match std::env::var(TGT_BACKUP_PASSWORD) {
+        Ok(password) => password,
+        Err(_) => match rpassword::prompt_password("Backup password: ") {
+            Ok(password) => password,
+            Err(e) => {
+                eprintln!("Failed to read the password: {}", e);
+                return 1;
+            }
+        },
+    };
+    let result = if let Some(archive) = local_data_cli.backup() {
+        let exclude = excluded_paths(telegram_config, logger_config);
+        backup(Path::new(archive), &password, &exclude)
+            .map(|n| format!("Backed up {} files into {}", n, archive))
+    } else if let Some(archive) = local_data_cli.restore() {
+        restore(Path::new(archive), &password)
+            .map(|n| format!("Restored {} files from {}", n, archive))
+    } else {
+        return 0;
+    };
+    match result {
+        Ok(message) => {
+            tracing::info!("{}", message);
+            println!("{}", message);
+            0
+        }
+        Err(e) => {
+            tracing::error!("Local data backup/restore failed: {}", e);
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+/// Get the paths that are not backed up: the TDLib databases, of the
+/// configuration and of every account, and the logs.
+///
+/// # Arguments
+/// * `telegram_config` - The Telegram configuration.
+/// * `logger_config` - The logger configuration.
+///
+/// # Returns
+/// * `Vec<PathBuf>` - The paths, absolute or relative to the directory of
+///   `tgt`.
+fn excluded_paths(telegram_config: &TelegramConfig, logger_config: &LoggerConfig) -> Vec<PathBuf> {
+    let mut exclude = vec![PathBuf::from(&telegram_config.database_dir)];
+    exclude.extend(
+        telegram_config
+            .accounts
+            .iter()
+            .filter_map(|account| account.database_dir.as_ref().map(PathBuf::from)),
+    );
+    // The log file may be in the directory of `tgt` itself, which must not be
+    // skipped as a whole.
+    if let Some(parent) = Path::new(&telegram_config.log_path)
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        exclude.push(parent.to_path_buf());
+    }
+    exclude.push(PathBuf::from(&logger_config.log_dir));
+    exclude
+}
+
+/// Back up the local data of `tgt` into a single encrypted archive.
+/// The archive contains all the files of the local data directory, like the
+/// local state, except the TDLib database and logs, which are managed by
+/// TDLib itself.
+///
+/// # Arguments
+/// * `archive` - The path of the archive to create.
+/// * `password` - The password used to encrypt the archive.
+/// * `exclude` - The paths that must not be included in the archive,
+///   absolute or relative to the directory of `tgt`.
+///
+/// # Returns
+/// * `io::Result<usize>` - The number of files in the archive or an error.
+pub fn backup(archive: &Path, password: &str, exclude: &[PathBuf]) -> io::Result<usize> {
+    backup_dir(&tgt_data_dir()?, &tgt_dir()?, archive, password, exclude)
+}
+
+/// Back up the files of a data directory into a single encrypted archive.
+///
+/// # Arguments
+/// * `data_dir` - The data directory.
+/// * `base_dir` - The directory the relative excluded paths are resolved
+///   against.
+/// * `archive` - The path of the archive to create.
+/// * `password` - The password used to encrypt the archive.
+/// * `exclude` - The paths that must not be included in the archive.
+///
+/// # Returns
+/// * `io::Result<usize>` - The number of files in the archive or an error.
+fn backup_dir(
+    data_dir: &Path,
+    base_dir: &Path,
+    archive: &Path,
+    password: &str,
+    exclude: &[PathBuf],
+) -> io::Result<usize> {
+    // The files are compared with absolute paths, so that the default
+    // `.data/tg` database is skipped too.
+    let exclude = exclude
+        .iter()
+        .map(|path| base_dir.join(path))
+        .collect::<Vec<PathBuf>>();
+    let mut files = vec![];
+    collect_files(data_dir, &exclude, &mut files)?;
+
+    let mut entries = vec![];
+    for file in files.iter() {
+        let relative = file
+            .strip_prefix(data_dir)
+            .map_err(io::Error::other)?
+            .to_string_lossy()
+            .replace('\\', "/");
+        entries.push((relative, fs::read(file)?));
+    }
+    fs::write(archive, encrypt(&encode(&entries), password)?)?;
+    Ok(files.len())
+}
+
+/// Restore the local data of `tgt` from an encrypted archive created with
+/// `backup`. Existing files with the same name are overwritten.
+/// The whole archive is checked before a file is written.
+///
+/// # Arguments
+/// * `archive` - The path of the archive to restore.
+/// * `password` - The password used to encrypt the archive.
+///
+/// # Returns
+/// * `io::Result<usize>` - The number of restored files or an error.
+pub fn restore(archive: &Path, password: &str) -> io::Result<usize> {
+    let payload = decrypt(&fs::read(archive)?, password)?;
+    let entries = decode(&payload)?;
+
+    let data_dir = tgt_data_dir()?;
+    for (relative, content) in entries.iter() {
+        let path = data_dir.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+    }
+    Ok(entries.len())
+}
+
+/// Encode the files of an archive, each as the length of its path, the path,
+/// the length of its content and the content.
+///
+/// # Arguments
+/// * `entries` - The relative paths and the contents of the files.
+///
+/// # Returns
+/// * `Vec<u8>` - The payload of the archive.
+fn encode(entries: &[(String, Vec<u8>)]) -> Vec<u8> {
+    let mut payload = vec![];
+    for (relative, content) in entries {
+        payload.extend_from_slice(&(relative.len() as u32).to_le_bytes());
+        payload.extend_from_slice(relative.as_bytes());
+        payload.extend_from_slice(&(content.len() as u64).to_le_bytes());
+        payload.extend_from_slice(content);
+    }
+    payload
+}
+
+/// Decode the files of an archive encoded with `encode`.
+/// A path that is not relative to the local data directory, like an
+/// absolute one or one with `..`, is rejected.
+///
+/// # Arguments
+/// * `payload` - The payload of the archive.
+///
+/// # Returns
+/// * `io::Result<Vec<(PathBuf, &[u8])>>` - The relative paths and the
+///   contents of the files or an error.
+fn decode(payload: &[u8]) -> io::Result<Vec<(PathBuf, &[u8])>> {
+    let mut entries = vec![];
+    let mut rest = payload;
+    while !rest.is_empty() {
+        let path_len = u32::from_le_bytes(take(&mut rest, 4)?.try_into().unwrap()) as usize;
+        let relative = String::from_utf8(take(&mut rest, path_len)?.to_vec())
+            .map_err(|_| invalid_data("invalid file name in the archive"))?;
+        let content_len =
+            usize::try_from(u64::from_le_bytes(take(&mut rest, 8)?.try_into().unwrap()))
+                .map_err(|_| invalid_data("truncated archive"))?;
+        let content = take(&mut rest, content_len)?;
+
+        let relative = PathBuf::from(relative);
+        let mut components = relative.components().peekable();
+        if components.peek().is_none()
+            || !components.all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(invalid_data("unsafe file name in the archive"));
+        }
+        entries.push((relative, content));
+    }
+    Ok(entries)
+}
+
+/// Encrypt the payload of an archive, prefixed by the magic bytes, the salt
+/// of the key and the nonce.
+///
+/// # Arguments
+/// * `payload` - The payload of the archive.
+/// * `password` - The password.
+///
+/// # Returns
+/// * `io::Result<Vec<u8>>` - The content of the archive or an error.
+fn encrypt(payload: &[u8], password: &str) -> io::Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = cipher(password, &salt)?;
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let encrypted = cipher
+        .encrypt(&nonce, payload)
+        .map_err(|_| io::Error::other("failed to encrypt the backup"))?;
+
+    let mut content = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + encrypted.len());
+    content.extend_from_slice(MAGIC);
+    content.extend_from_slice(&salt);
+    content.extend_from_slice(&nonce);
+    content.extend_from_slice(&encrypted);
+    Ok(content)
+}
+
+/// Decrypt the content of an archive created with `encrypt`.
+///
+/// # Arguments
+/// * `content` - The content of the archive.
+/// * `password` - The password.
+///
+/// # Returns
+/// * `io::Result<Vec<u8>>` - The payload of the archive or an error.
+fn decrypt(content: &[u8], password: &str) -> io::Result<Vec<u8>> {
+    let header_len = MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if content.len() < header_len || &content[..MAGIC.len()] != MAGIC {
+        return Err(invalid_data("not a tgt backup archive"));
+    }
+    let salt = &content[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = XNonce::from_slice(&content[MAGIC.len() + SALT_LEN..header_len]);
+    cipher(password, salt)?
+        .decrypt(nonce, &content[header_len..])
+        .map_err(|_| invalid_data("wrong password or corrupted archive"))
+}
+
+/// Create the cipher used to encrypt and decrypt an archive.
+/// The key is derived from the password with Argon2.
+///
+/// # Arguments
+/// * `password` - The password.
+/// * `salt` - The salt of the archive.
+///
+/// # Returns
+/// * `io::Result<XChaCha20Poly1305>` - The cipher or an error.
+fn cipher(password: &str, salt: &[u8]) -> io::Result<XChaCha20Poly1305> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+/// Collect recursively the files of a directory.
+///
+/// # Arguments
+/// * `dir` - The directory.
+/// * `exclude` - The paths that must be skipped.
+/// * `files` - The vector where the files are collected.
+fn collect_files(dir: &Path, exclude: &[PathBuf], files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if exclude.iter().any(|excluded| path.starts_with(excluded)) {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, exclude, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Take the first `len` bytes of a slice, advancing it.
+///
+/// # Arguments
+/// * `rest` - The slice.
+/// * `len` - The number of bytes.
+///
+/// # Returns
+/// * `io::Result<&[u8]>` - The bytes or an error if the slice is too short.
+fn take<'a>(rest: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+    if rest.len() < len {
+        return Err(invalid_data("truncated archive"));
+    }
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+    Ok(head)
+}
+
+/// Create an invalid data error.
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<(String, Vec<u8>)> {
+        vec![
+            ("local_state.json".to_string(), b"{}".to_vec()),
+            ("nested/file.txt".to_string(), vec![0, 1, 2, 255]),
+            ("empty".to_string(), vec![]),
+        ]
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let content = encrypt(&encode(&entries()), "password").unwrap();
+        let payload = decrypt(&content, "password").unwrap();
+        let decoded = decode(&payload).unwrap();
+        assert_eq!(decoded.len(), 3);
+        for ((path, content), (expected_path, expected_content)) in decoded.iter().zip(entries()) {
+            assert_eq!(*path, PathBuf::from(expected_path));
+            assert_eq!(*content, expected_content.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_backup_skips_relative_database_dirs() {
+        let base_dir = std::env::temp_dir().join(format!("tgt-backup-{}", std::process::id()));
+        let data_dir = base_dir.join(".data");
+        fs::create_dir_all(data_dir.join("tg/db")).unwrap();
+        fs::create_dir_all(data_dir.join("work")).unwrap();
+        fs::write(data_dir.join("local_state.json"), b"{}").unwrap();
+        fs::write(data_dir.join("tg/db/td.binlog"), b"database").unwrap();
+        fs::write(data_dir.join("work/td.binlog"), b"database").unwrap();
+        let archive = base_dir.join("backup.tgt");
+        let exclude = vec![PathBuf::from(".data/tg"), PathBuf::from("./.data/work")];
+        let count = backup_dir(&data_dir, &base_dir, &archive, "password", &exclude);
+        let content = fs::read(&archive);
+        fs::remove_dir_all(&base_dir).unwrap();
+        assert_eq!(count.unwrap(), 1);
+        let payload = decrypt(&content.unwrap(), "password").unwrap();
+        let decoded = decode(&payload).unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].0, PathBuf::from("local_state.json"));
+    }
+
+    #[test]
+    fn test_wrong_password() {
+        let content = encrypt(&encode(&entries()), "password").unwrap();
+        assert!(decrypt(&content, "other").is_err());
+    }
+
+    #[test]
+    fn test_tampered_archive() {
+        let mut content = encrypt(&encode(&entries()), "password").unwrap();
+        let last = content.len() - 1;
+        content[last] ^= 1;
+        assert!(decrypt(&content, "password").is_err());
+
+        let mut content = encrypt(&encode(&entries()), "password").unwrap();
+        content[0] = b'X';
+        assert!(decrypt(&content, "password").is_err());
+    }
+
+    #[test]
+    fn test_truncated_archive() {
+        let content = encrypt(&encode(&entries()), "password").unwrap();
+        for len in [
+            0,
+            MAGIC.len(),
+            MAGIC.len() + SALT_LEN + NONCE_LEN,
+            content.len() - 1,
+        ] {
+            assert!(decrypt(&content[..len], "password").is_err());
+        }
+    }
+
+    #[test]
+    fn test_truncated_payload() {
+        let payload = encode(&entries());
+        for len in 1..payload.len() {
+            // The cuts between two files are complete payloads.
+            if decode(&payload[..len]).is_ok() {
+                continue;
+            }
+            assert_eq!(
+                decode(&payload[..len]).unwrap_err().kind(),
+                io::ErrorKind::InvalidData
+            );
+        }
+        // A length larger than the payload is an error, not a panic.
+        let mut payload = encode(&entries());
+        payload[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(decode(&payload).is_err());
+    }
+
+    #[test]
+    fn test_unsafe_paths() {
+        for path in [
+            "../outside",
+            "nested/../../outside",
+            "/etc/passwd",
+            "./file",
+            "",
+        ] {
+            let payload = encode(&[(path.to_string(), b"content".to_vec())]);
+            assert!(decode(&payload).is_err(), "{} is accepted", path);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use unicode_bidi::{BidiInfo, Level};
+
+/// Check whether a paragraph is written right to left, that is whether its
+/// first strong character is, for example, Arabic or Hebrew.
+///
+/// # Arguments
+/// * `text` - The paragraph.
+///
+/// # Returns
+/// * `bool` - Whether the paragraph is right to left.
+pub fn is_rtl_paragraph(text: &str) -> bool {
+    let bidi_info = BidiInfo::new(text, None);
+    bidi_info.has_rtl()
+        && bidi_info
+            .paragraphs
+            .first()
+            .is_some_and(|paragraph| paragraph.level.is_rtl())
+}
+
+/// Get the visual order of the units of a line, like its grapheme clusters,
+/// by applying the bidirectional algorithm.
+/// The units are never split, so a cluster keeps its characters in order.
+///
+/// # Arguments
+/// * `units` - The units of the line, in logical order.
+/// * `rtl` - Whether the paragraph of the line is right to left.
+///
+/// # Returns
+/// * `Vec<(usize, bool)>` - The indexes of the units in visual order, from
+///   left to right, each with whether it is in a right to left run.
+pub fn visual_order<S: AsRef<str>>(units: &[S], rtl: bool) -> Vec<(usize, bool)> {
+    let mut text = String::new();
+    let mut starts = Vec::with_capacity(units.len());
+    for unit in units {
+        starts.push(text.len());
+        text.push_str(unit.as_ref());
+    }
+    let level = if rtl { Level::rtl() } else { Level::ltr() };
+    let bidi_info = BidiInfo::new(&text, Some(level));
+    if !bidi_info.has_rtl() && !rtl {
+        return (0..units.len()).map(|i| (i, false)).collect();
+    }
+    let mut order = Vec::with_capacity(units.len());
+    for paragraph in bidi_info.paragraphs.iter() {
+        let (levels, runs) = bidi_info.visual_runs(paragraph, paragraph.range.clone());
+        for run in runs {
+            let is_rtl = levels[run.start].is_rtl();
+            let mut run_units = starts
+                .iter()
+                .enumerate()
+                .filter(|(_, start)| run.contains(*start))
+                .map(|(i, _)| (i, is_rtl))
+                .collect::<Vec<(usize, bool)>>();
+            if is_rtl {
+                run_units.reverse();
+            }
+            order.extend(run_units);
+        }
+    }
+    order
+}
+
+/// Get the visual position of the cursor of a line written in logical order.
+/// In a right to left run, the cursor placed before a unit is shown on its
+/// right.
+///
+/// # Arguments
+/// * `units` - The units of the line, in logical order.
+/// * `rtl` - Whether the paragraph of the line is right to left.
+/// * `cursor` - The index of the unit before which the cursor is placed, or
+///   the number of units when the cursor is at the end of the line.
+/// * `width` - The function that gives the number of columns of a unit.
+///
+/// # Returns
+/// * `usize` - The column of the cursor.
+pub fn visual_cursor_column<S: AsRef<str>>(
+    units: &[S],
+    rtl: bool,
+    cursor: usize,
+    width: impl Fn(&str) -> usize,
+) -> usize {
+    let order = visual_order(units, rtl);
+    // The unit next to which the cursor is shown and whether it is shown on
+    // the right of that unit.
+    let (unit, on_right) = if cursor < units.len() {
+        let is_rtl = order.iter().any(|(i, is_rtl)| *i == cursor && *is_rtl);
+        (cursor, is_rtl)
+    } else if let Some(last) = units.len().checked_sub(1) {
+        let is_rtl = order.iter().any(|(i, is_rtl)| *i == last && *is_rtl);
+        (last, !is_rtl)
+    } else {
+        return 0;
+    };
+    let mut column = 0;
+    for (i, _) in order {
+        if i == unit {
+            if on_right {
+                column += width(units[i].as_ref());
+            }
+            break;
+        }
+        column += width(units[i].as_ref());
+    }
+    column
+}
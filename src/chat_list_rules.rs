@@ -0,0 +1,351 @@
+use crate::configs::{config_theme::ThemeStyle, raw::app_raw::ChatListStyleEntry};
+use ratatui::style::{Modifier, Style};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `ChatKind` is an enum that represents the kind of a chat of the chat list,
+/// as the rules of the styles of the chat list see it.
+pub enum ChatKind {
+    /// A private chat with a user.
+    Private,
+    /// A private chat with a bot.
+    Bot,
+    /// A basic group or a supergroup.
+    Group,
+    /// A channel.
+    Channel,
+    /// A secret chat.
+    Secret,
+}
+
+#[derive(Debug, Clone, Copy)]
+/// `ChatFacts` is a struct that represents what the rules of the styles of
+/// the chat list know about a chat.
+pub struct ChatFacts<'a> {
+    /// The title of the chat.
+    pub title: &'a str,
+    /// The username of the chat, empty if it has none.
+    pub username: &'a str,
+    /// The kind of the chat.
+    pub kind: ChatKind,
+    /// Whether the notifications of the chat are muted.
+    pub muted: bool,
+    /// Whether the chat has unread messages.
+    pub unread: bool,
+    /// Whether the chat has unread mentions of the user.
+    pub mentioned: bool,
+    /// Whether the chat is pinned.
+    pub pinned: bool,
+    /// Whether the chat is marked as favorite.
+    pub favorite: bool,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `Condition` is an enum that represents a word of the `match` of a rule.
+enum Condition {
+    /// The chat is muted.
+    Muted,
+    /// The chat has unread messages.
+    Unread,
+    /// The chat has unread mentions.
+    Mentioned,
+    /// The chat is pinned.
+    Pinned,
+    /// The chat is a favorite.
+    Favorite,
+    /// The chat is of a kind.
+    Kind(ChatKind),
+    /// The username of the chat matches a pattern, like `@work-*`.
+    Username(String),
+    /// The title of the chat matches a pattern, like `title:*news*`.
+    Title(String),
+    /// The condition does not hold, like `!muted`.
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// Parse a word of the `match` of a rule.
+    ///
+    /// # Arguments
+    /// * `word` - The word.
+    ///
+    /// # Returns
+    /// * `Result<Self, String>` - The condition, or the error.
+    fn parse(word: &str) -> Result<Self, String> {
+        if let Some(word) = word.strip_prefix('!') {
+            return Ok(Self::Not(Box::new(Self::parse(word)?)));
+        }
+        if let Some(pattern) = word.strip_prefix('@') {
+            return Ok(Self::Username(pattern.to_lowercase()));
+        }
+        if let Some(pattern) = word.strip_prefix("title:") {
+            return Ok(Self::Title(pattern.to_lowercase()));
+        }
+        // The kinds can be written in the plural, like `muted channels`.
+        match word.to_lowercase().trim_end_matches('s') {
+            "muted" => Ok(Self::Muted),
+            "unread" => Ok(Self::Unread),
+            "mentioned" => Ok(Self::Mentioned),
+            "pinned" => Ok(Self::Pinned),
+            "favorite" => Ok(Self::Favorite),
+            "private" => Ok(Self::Kind(ChatKind::Private)),
+            "bot" => Ok(Self::Kind(ChatKind::Bot)),
+            "group" => Ok(Self::Kind(ChatKind::Group)),
+            "channel" => Ok(Self::Kind(ChatKind::Channel)),
+            "secret" => Ok(Self::Kind(ChatKind::Secret)),
+            _ => Err(format!("unknown condition `{}`", word)),
+        }
+    }
+
+    /// Check whether a chat meets the condition.
+    ///
+    /// # Arguments
+    /// * `facts` - What is known about the chat.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the chat meets the condition.
+    fn holds(&self, facts: &ChatFacts) -> bool {
+        match self {
+            Self::Muted => facts.muted,
+            Self::Unread => facts.unread,
+            Self::Mentioned => facts.mentioned,
+            Self::Pinned => facts.pinned,
+            Self::Favorite => facts.favorite,
+            Self::Kind(kind) => facts.kind == *kind,
+            Self::Username(pattern) => {
+                !facts.username.is_empty() && glob_matches(pattern, &facts.username.to_lowercase())
+            }
+            Self::Title(pattern) => glob_matches(pattern, &facts.title.to_lowercase()),
+            Self::Not(condition) => !condition.holds(facts),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// `ChatListStyleRule` is a struct that represents a rule of the styles of
+/// the chat list, like `muted channels` shown `dim`. It is parsed once, when
+/// the configuration is loaded, and evaluated for each chat shown.
+pub struct ChatListStyleRule {
+    /// The conditions a chat must all meet.
+    conditions: Vec<Condition>,
+    /// The style of the chats that meet them.
+    style: Style,
+}
+
+impl ChatListStyleRule {
+    /// Parse a rule of the styles of the chat list.
+    ///
+    /// # Arguments
+    /// * `conditions` - The words the chats must all match, like
+    ///   `muted channels` or `@work-* unread`.
+    /// * `style` - The words of the style, like `bold cyan` or
+    ///   `dim on black`.
+    ///
+    /// # Returns
+    /// * `Result<Self, String>` - The rule, or the error.
+    pub fn parse(conditions: &str, style: &str) -> Result<Self, String> {
+        let conditions = conditions
+            .split_whitespace()
+            .map(Condition::parse)
+            .collect::<Result<Vec<Condition>, String>>()?;
+        Ok(Self {
+            conditions,
+            style: parse_style(style)?,
+        })
+    }
+
+    /// Check whether a chat matches the rule.
+    ///
+    /// # Arguments
+    /// * `facts` - What is known about the chat.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the chat meets all the conditions.
+    pub fn matches(&self, facts: &ChatFacts) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.holds(facts))
+    }
+}
+
+/// Parse the rules of the styles of the chat list of the configuration. The
+/// invalid rules are skipped with a warning.
+///
+/// # Arguments
+/// * `entries` - The rules of the configuration.
+///
+/// # Returns
+/// * `Vec<ChatListStyleRule>` - The valid rules, in order.
+pub fn parse_rules(entries: &[ChatListStyleEntry]) -> Vec<ChatListStyleRule> {
+    entries
+        .iter()
+        .filter_map(
+            |entry| match ChatListStyleRule::parse(&entry.chats, &entry.style) {
+                Ok(rule) => Some(rule),
+                Err(e) => {
+                    tracing::warn!("Invalid chat list style of `{}`: {}", entry.chats, e);
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// Get the style of a chat of the chat list from the rules it matches. The
+/// later rules are applied over the earlier ones.
+///
+/// # Arguments
+/// * `rules` - The rules, in the order of the configuration.
+/// * `facts` - What is known about the chat.
+///
+/// # Returns
+/// * `Option<Style>` - The style, `None` if the chat matches no rule.
+pub fn chat_style(rules: &[ChatListStyleRule], facts: &ChatFacts) -> Option<Style> {
+    rules
+        .iter()
+        .filter(|rule| rule.matches(facts))
+        .map(|rule| rule.style)
+        .reduce(|style, other| style.patch(other))
+}
+
+/// Parse the words of a style, like `bold cyan` or `italic red on #202020`.
+/// A color is the foreground, or the background after `on`.
+///
+/// # Arguments
+/// * `style` - The words of the style.
+///
+/// # Returns
+/// * `Result<Style, String>` - The style, or the error.
+fn parse_style(style: &str) -> Result<Style, String> {
+    let mut result = Style::default();
+    let mut background = false;
+    for word in style.split_whitespace() {
+        let modifier = match word.to_lowercase().as_str() {
+            "on" => {
+                background = true;
+                continue;
+            }
+            "bold" => Modifier::BOLD,
+            "dim" => Modifier::DIM,
+            "italic" => Modifier::ITALIC,
+            "underline" | "underlined" => Modifier::UNDERLINED,
+            "reversed" => Modifier::REVERSED,
+            "crossed_out" => Modifier::CROSSED_OUT,
+            color => {
+                let color = ThemeStyle::str_to_color(color)
+                    .map_err(|_| format!("unknown style `{}`", word))?;
+                result = if background {
+                    result.bg(color)
+                } else {
+                    result.fg(color)
+                };
+                background = false;
+                continue;
+            }
+        };
+        result = result.add_modifier(modifier);
+    }
+    Ok(result)
+}
+
+/// Check whether a text matches a pattern, where `*` is any text and `?` is
+/// any character.
+///
+/// # Arguments
+/// * `pattern` - The pattern.
+/// * `text` - The text.
+///
+/// # Returns
+/// * `bool` - Whether the whole text matches the pattern.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // The position of the last `*` of the pattern, and of the text it was
+    // tried at, to try again one character later.
+    let mut star = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    fn facts<'a>(title: &'a str, username: &'a str, kind: ChatKind, muted: bool) -> ChatFacts<'a> {
+        ChatFacts {
+            title,
+            username,
+            kind,
+            muted,
+            unread: false,
+            mentioned: false,
+            pinned: false,
+            favorite: false,
+        }
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("work-*", "work-backend"));
+        assert!(glob_matches("*news*", "daily news digest"));
+        assert!(glob_matches("a?c", "abc"));
+        assert!(!glob_matches("work-*", "homework-1"));
+        assert!(!glob_matches("a?c", "ac"));
+    }
+
+    #[test]
+    fn test_rules_match_and_the_later_ones_win() {
+        let rules = vec![
+            ChatListStyleRule::parse("@work-*", "bold cyan").unwrap(),
+            ChatListStyleRule::parse("muted channels", "dim").unwrap(),
+            ChatListStyleRule::parse("title:*alerts* !muted", "red on black").unwrap(),
+        ];
+        let work = facts("Backend", "Work-Backend", ChatKind::Group, false);
+        assert_eq!(
+            chat_style(&rules, &work),
+            Some(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            )
+        );
+        let news = facts("News", "", ChatKind::Channel, true);
+        assert_eq!(
+            chat_style(&rules, &news),
+            Some(Style::default().add_modifier(Modifier::DIM))
+        );
+        let alerts = facts("Alerts", "work-alerts", ChatKind::Channel, false);
+        assert_eq!(
+            chat_style(&rules, &alerts),
+            Some(
+                Style::default()
+                    .fg(Color::Red)
+                    .bg(Color::Black)
+                    .add_modifier(Modifier::BOLD)
+            )
+        );
+        let friend = facts("Ada", "", ChatKind::Private, false);
+        assert_eq!(chat_style(&rules, &friend), None);
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(ChatListStyleRule::parse("archived", "bold").is_err());
+        assert!(ChatListStyleRule::parse("muted", "blinking").is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{
+    app_context::AppContext,
+    configs::raw::app_raw::ChatLogEntry,
+    tg::message_entry::{DateTimeEntry, MessageEntry},
+    utils::expand_placeholders,
+};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// The line written for each message when the log has no `format`.
+pub const DEFAULT_FORMAT: &str = "- {time} **{sender}**: {text}";
+/// The maximum number of messages of a chat appended when `tgt` starts.
+/// The older messages received while `tgt` was closed are not appended.
+pub const SYNC_LIMIT: usize = 100;
+/// The characters that can not be used in the name of a file, replaced in
+/// the title of the chat.
+const INVALID_FILE_CHARS: [char; 9] = ['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Get the log of a chat, if it is configured.
+///
+/// # Arguments
+/// * `app_context` - The application context.
+/// * `chat_id` - The id of the chat.
+///
+/// # Returns
+/// * `Option<ChatLogEntry>` - The log whose `chat` is the title or the id of
+///   the chat.
+pub fn log_of_chat(app_context: &AppContext, chat_id: i64) -> Option<ChatLogEntry> {
+    let title = app_context.tg_context().name_from_chats(chat_id);
+    app_context
+        .app_config()
+        .chat_logs
+        .iter()
+        .find(|entry| {
+            let chat = entry.chat.trim();
+            chat == chat_id.to_string() || title.as_deref() == Some(chat)
+        })
+        .cloned()
+}
+
+/// Get the ids of the loaded chats that are logged.
+///
+/// # Arguments
+/// * `app_context` - The application context.
+///
+/// # Returns
+/// * `Vec<i64>` - The ids of the chats.
+pub fn logged_chat_ids(app_context: &AppContext) -> Vec<i64> {
+    if app_context.app_config().chat_logs.is_empty() {
+        return vec![];
+    }
+    let chat_ids: Vec<i64> = app_context.tg_context().chats().keys().copied().collect();
+    chat_ids
+        .into_iter()
+        .filter(|chat_id| log_of_chat(app_context, *chat_id).is_some())
+        .collect()
+}
+
+/// Get the path of the file a message is appended to.
+///
+/// # Arguments
+/// * `path` - The path of the log, with its variables.
+/// * `chat` - The title of the chat.
+/// * `date` - The date of the message.
+///
+/// # Returns
+/// * `PathBuf` - The path, with a leading `~` replaced with the home
+///   directory.
+fn file_path(path: &str, chat: &str, date: &str) -> PathBuf {
+    let chat: String = chat
+        .chars()
+        .map(|c| {
+            if INVALID_FILE_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let path = expand_placeholders(path, &[("{chat}", chat.as_str()), ("{date}", date)]);
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Format a message as a line of a log.
+/// The next lines of the text are indented by two spaces, so that a message
+/// stays a single item of a Markdown list.
+///
+/// # Arguments
+/// * `format` - The format of the line.
+/// * `chat` - The title of the chat.
+/// * `sender` - The name of the sender.
+/// * `message` - The message.
+///
+/// # Returns
+/// * `String` - The line, without the final new line.
+fn format_message(format: &str, chat: &str, sender: &str, message: &MessageEntry) -> String {
+    let timestamp = message.timestamp().timestamp;
+    let text = message.message_content_to_string().replace('\n', "\n  ");
+    let date = DateTimeEntry::convert_date(timestamp);
+    let time = DateTimeEntry::convert_short_time(timestamp);
+    let id = message.id().to_string();
+    // The format is expanded in one pass, so that the variables in a name or
+    // typed in a message are kept as they are.
+    expand_placeholders(
+        format,
+        &[
+            ("{date}", date.as_str()),
+            ("{time}", time.as_str()),
+            ("{sender}", sender),
+            ("{chat}", chat),
+            ("{id}", id.as_str()),
+            ("{text}", text.as_str()),
+        ],
+    )
+}
+
+/// Append messages to the log of a chat, if it is configured. The messages
+/// already appended are skipped, and the last one is remembered in the local
+/// state.
+///
+/// # Arguments
+/// * `app_context` - The application context.
+/// * `chat_id` - The id of the chat.
+/// * `messages` - The messages, the oldest first.
+///
+/// # Returns
+/// * `io::Result<()>` - An Ok result or an error.
+pub fn append(app_context: &AppContext, chat_id: i64, messages: &[MessageEntry]) -> io::Result<()> {
+    let Some(log) = log_of_chat(app_context, chat_id) else {
+        return Ok(());
+    };
+    let chat = app_context
+        .tg_context()
+        .name_from_chats(chat_id)
+        .unwrap_or_default();
+    let format = log.format.as_deref().unwrap_or(DEFAULT_FORMAT);
+    let last_position = app_context.local_state().chat_log_position(chat_id);
+    let mut position = last_position;
+    for message in messages {
+        if position.is_some_and(|position| message.id() <= position) {
+            continue;
+        }
+        let date = DateTimeEntry::convert_date(message.timestamp().timestamp);
+        let path = file_path(&log.path, &chat, &date);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let line = format_message(format, &chat, &message.sender_name(app_context), message);
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(file, "{}", line)?;
+        position = Some(message.id());
+    }
+    if let Some(position) = position.filter(|_| position != last_position) {
+        app_context
+            .local_state()
+            .set_chat_log_position(chat_id, position);
+        app_context.save_local_state();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_with_variables_in_values() {
+        let line = expand_placeholders(
+            DEFAULT_FORMAT,
+            &[
+                ("{time}", "10:30"),
+                ("{sender}", "{text}"),
+                ("{text}", "hello {sender}"),
+            ],
+        );
+        assert_eq!(line, "- 10:30 **{text}**: hello {sender}");
+    }
+
+    #[test]
+    fn test_file_path_with_variables_in_chat() {
+        let path = file_path("logs/{chat}/{date}.md", "a/{date}", "2024-01-02");
+        assert_eq!(path, PathBuf::from("logs/a_{date}/2024-01-02.md"));
+    }
+}
@@ -7,6 +7,14 @@ use clap::Parser;
 pub struct CliArgs {
     #[command(flatten)]
     telegram_cli: TelegramCli,
+    #[command(flatten)]
+    local_data_cli: LocalDataCli,
+    #[arg(
+        long,
+        help = "Start with the default configuration and theme, without custom commands and with verbose logging",
+        default_value_t = false
+    )]
+    safe_mode: bool,
     // #[command(subcommand)]
     // telegram: Option<Telegram>,
 }
@@ -16,6 +24,17 @@ impl CliArgs {
     pub fn telegram_cli(&self) -> &TelegramCli {
         &self.telegram_cli
     }
+    /// Get the local data CLI arguments.
+    pub fn local_data_cli(&self) -> &LocalDataCli {
+        &self.local_data_cli
+    }
+    /// Get the safe mode flag.
+    /// In safe mode the custom configuration files are ignored, except
+    /// `telegram.toml`, so that the application can start even if they are
+    /// broken.
+    pub fn safe_mode(&self) -> bool {
+        self.safe_mode
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -50,6 +69,41 @@ impl TelegramCli {
     pub fn send_message(&self) -> Option<&Vec<String>> {
         self.send_message.as_ref()
     }
+    /// Whether a one-shot command is run, after which the application exits
+    /// without starting the TUI.
+    pub fn is_one_shot(&self) -> bool {
+        self.send_message.is_some()
+    }
+}
+
+#[derive(Parser, Debug)]
+/// The commands to back up and restore the local data of `tgt`.
+pub struct LocalDataCli {
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "restore",
+        help = "Back up the local data of tgt into an encrypted archive"
+    )]
+    backup: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Restore the local data from an encrypted archive"
+    )]
+    restore: Option<String>,
+}
+
+impl LocalDataCli {
+    /// Get the path of the archive to create.
+    pub fn backup(&self) -> Option<&String> {
+        self.backup.as_ref()
+    }
+    /// Get the path of the archive to restore.
+    pub fn restore(&self) -> Option<&String> {
+        self.restore.as_ref()
+    }
 }
 
 // #[derive(Parser, Debug)]
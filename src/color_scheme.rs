@@ -0,0 +1,278 @@
+use crate::{action::Action, app_context::AppContext, configs::custom::app_custom::AppConfig};
+use std::{
+    io::{self, IsTerminal, Read, Write},
+    sync::{mpsc, Arc},
+    time::Duration,
+};
+
+/// The maximum time waited for the terminal to answer the query of its
+/// background color.
+const TERMINAL_QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+/// The interval between two checks of the color scheme of the system, used to
+/// switch the theme when it changes.
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `ColorScheme` is an enum that represents whether the terminal or the
+/// system uses dark or light colors.
+pub enum ColorScheme {
+    /// Light text on a dark background.
+    Dark,
+    /// Dark text on a light background.
+    Light,
+}
+impl ColorScheme {
+    /// Get the color scheme of a background color.
+    ///
+    /// # Arguments
+    /// * `r` - The red component, from 0 to 1.
+    /// * `g` - The green component, from 0 to 1.
+    /// * `b` - The blue component, from 0 to 1.
+    ///
+    /// # Returns
+    /// * `Self` - `Light` if the background is bright, `Dark` otherwise.
+    pub fn from_background(r: f64, g: f64, b: f64) -> Self {
+        let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        if luminance > 0.5 {
+            Self::Light
+        } else {
+            Self::Dark
+        }
+    }
+}
+
+/// Check whether the theme follows the color scheme, that is whether a dark
+/// or a light theme is configured.
+///
+/// # Arguments
+/// * `app_config` - The application configuration.
+///
+/// # Returns
+/// * `bool` - Whether the color scheme is detected.
+pub fn is_enabled(app_config: &AppConfig) -> bool {
+    !app_config.theme_dark_filename.is_empty() || !app_config.theme_light_filename.is_empty()
+}
+
+/// Get the theme file of a color scheme.
+///
+/// # Arguments
+/// * `app_config` - The application configuration.
+/// * `scheme` - The color scheme.
+///
+/// # Returns
+/// * `&str` - The configured theme of the color scheme, or `theme_filename`
+///   if there is none.
+pub fn theme_filename(app_config: &AppConfig, scheme: ColorScheme) -> &str {
+    let filename = match scheme {
+        ColorScheme::Dark => &app_config.theme_dark_filename,
+        ColorScheme::Light => &app_config.theme_light_filename,
+    };
+    if filename.is_empty() {
+        &app_config.theme_filename
+    } else {
+        filename
+    }
+}
+
+/// Parse the answer of the terminal to the OSC 11 query, like
+/// `\x1b]11;rgb:ffff/ffff/ffff\x1b\\`.
+///
+/// # Arguments
+/// * `answer` - The answer of the terminal.
+///
+/// # Returns
+/// * `Option<ColorScheme>` - The color scheme of the background, if the
+///   answer contains it.
+pub fn parse_background_answer(answer: &str) -> Option<ColorScheme> {
+    let start = answer.find("rgb:")? + "rgb:".len();
+    let rgb = &answer[start..];
+    let end = rgb
+        .find(|c: char| !c.is_ascii_hexdigit() && c != '/')
+        .unwrap_or(rgb.len());
+    let mut components = rgb[..end].split('/').map(|component| {
+        // Each component has from 1 to 4 hexadecimal digits.
+        let max = 16_f64.powi(component.len() as i32) - 1.0;
+        u32::from_str_radix(component, 16)
+            .ok()
+            .filter(|_| (1..=4).contains(&component.len()))
+            .map(|value| f64::from(value) / max)
+    });
+    let (r, g, b) = (
+        components.next()??,
+        components.next()??,
+        components.next()??,
+    );
+    Some(ColorScheme::from_background(r, g, b))
+}
+
+/// Ask the terminal for its background color with the OSC 11 query.
+/// It must be called before the events of the terminal are read, since the
+/// answer is read from the standard input.
+///
+/// # Returns
+/// * `Option<ColorScheme>` - The color scheme of the background, if the
+///   terminal answered.
+pub fn query_terminal() -> Option<ColorScheme> {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return None;
+    }
+    crossterm::terminal::enable_raw_mode().ok()?;
+    // The device attributes query is answered by all the terminals, so the
+    // answer is read until it arrives, even if the background is not.
+    let written = io::stdout()
+        .lock()
+        .write_all(b"\x1b]11;?\x1b\\\x1b[c")
+        .and_then(|_| io::stdout().flush());
+    let (tx, rx) = mpsc::channel();
+    if written.is_ok() {
+        std::thread::spawn(move || {
+            let mut answer = Vec::new();
+            let mut byte = [0; 1];
+            while io::stdin().read(&mut byte).is_ok_and(|n| n == 1) {
+                answer.push(byte[0]);
+                let text = String::from_utf8_lossy(&answer);
+                if let Some(da) = text.rfind("\x1b[?") {
+                    if text[da..].ends_with('c') {
+                        break;
+                    }
+                }
+            }
+            let _ = tx.send(String::from_utf8_lossy(&answer).into_owned());
+        });
+    }
+    let answer = rx.recv_timeout(TERMINAL_QUERY_TIMEOUT);
+    let _ = crossterm::terminal::disable_raw_mode();
+    match answer {
+        Ok(answer) => parse_background_answer(&answer),
+        Err(_) => {
+            tracing::warn!("The terminal did not answer the background color query");
+            None
+        }
+    }
+}
+
+/// Get the output of a command.
+///
+/// # Arguments
+/// * `program` - The program.
+/// * `args` - The arguments of the program.
+///
+/// # Returns
+/// * `Option<(bool, String)>` - Whether the command succeeded and its
+///   output, or `None` if it could not be run.
+async fn command_output(program: &str, args: &[&str]) -> Option<(bool, String)> {
+    let output = tokio::process::Command::new(program)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .await
+        .ok()?;
+    Some((
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).trim().to_string(),
+    ))
+}
+
+/// Get the color scheme preferred by the operating system: the appearance on
+/// macOS, the app mode on Windows and the GNOME color scheme elsewhere.
+///
+/// # Returns
+/// * `Option<ColorScheme>` - The preferred color scheme, if it is known.
+pub async fn system_color_scheme() -> Option<ColorScheme> {
+    if cfg!(target_os = "macos") {
+        // The key is missing when the light appearance is used.
+        let (success, output) =
+            command_output("defaults", &["read", "-g", "AppleInterfaceStyle"]).await?;
+        Some(if success && output.eq_ignore_ascii_case("dark") {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        })
+    } else if cfg!(target_os = "windows") {
+        let (success, output) = command_output(
+            "reg",
+            &[
+                "query",
+                r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+                "/v",
+                "AppsUseLightTheme",
+            ],
+        )
+        .await?;
+        match (success, output.split_whitespace().last()?) {
+            (true, "0x0") => Some(ColorScheme::Dark),
+            (true, "0x1") => Some(ColorScheme::Light),
+            _ => None,
+        }
+    } else {
+        let (success, output) = command_output(
+            "gsettings",
+            &["get", "org.gnome.desktop.interface", "color-scheme"],
+        )
+        .await?;
+        match (success, output.trim_matches('\'')) {
+            (true, "prefer-dark") => Some(ColorScheme::Dark),
+            (true, "prefer-light" | "default") => Some(ColorScheme::Light),
+            _ => None,
+        }
+    }
+}
+
+/// Get the color scheme from the `COLORFGBG` environment variable, set by
+/// some terminals like `rxvt` and `konsole`, like `15;0`.
+///
+/// # Returns
+/// * `Option<ColorScheme>` - The color scheme of the background, if the
+///   variable is set.
+pub fn env_color_scheme() -> Option<ColorScheme> {
+    let value = std::env::var("COLORFGBG").ok()?;
+    let background = value.rsplit(';').next()?.parse::<u8>().ok()?;
+    // The colors 7 and 9 to 15 are the bright ones of the 16 colors palette.
+    Some(if background == 7 || (9..=15).contains(&background) {
+        ColorScheme::Light
+    } else {
+        ColorScheme::Dark
+    })
+}
+
+/// Detect the color scheme when the application starts, asking the terminal
+/// first, then the operating system and the environment.
+///
+/// # Returns
+/// * `Option<ColorScheme>` - The detected color scheme, if any.
+pub async fn detect() -> Option<ColorScheme> {
+    let scheme = match query_terminal() {
+        Some(scheme) => Some(scheme),
+        None => system_color_scheme().await.or_else(env_color_scheme),
+    };
+    tracing::info!("Detected color scheme: {:?}", scheme);
+    scheme
+}
+
+/// Check the color scheme of the operating system periodically, and send the
+/// `ColorSchemeChanged` action when it changes.
+///
+/// # Arguments
+/// * `app_context` - An Arc wrapped AppContext struct.
+pub fn watch(app_context: Arc<AppContext>) {
+    tokio::spawn(async move {
+        let mut last = system_color_scheme().await;
+        if last.is_none() {
+            tracing::info!("The color scheme of the system is unknown, it is not watched");
+            return;
+        }
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+            let scheme = system_color_scheme().await;
+            if scheme.is_some() && scheme != last {
+                last = scheme;
+                if let Some(scheme) = scheme {
+                    let _ = app_context
+                        .action_tx()
+                        .send(Action::ColorSchemeChanged(scheme));
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,258 @@
+use crate::{action::Action, configs::raw::app_raw::CommandEntry};
+use std::{collections::BTreeMap, str::FromStr};
+
+/// The placeholder replaced with the arguments typed after a command.
+pub const ARGS_PLACEHOLDER: &str = "{args}";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `CommandExpansion` is an enum that represents what a slash command expands
+/// to when it is run from the prompt.
+pub enum CommandExpansion {
+    /// A list of actions sent in order, for example a single action or a
+    /// macro made of several actions.
+    Actions(Vec<Action>),
+    /// A text inserted in the prompt.
+    Snippet(String),
+    /// An external command whose output is inserted in the prompt.
+    Exec(String),
+    /// The name of a chat to open.
+    OpenChat(String),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `Command` is a struct that represents a slash command of the prompt.
+pub struct Command {
+    /// The name of the command, without the leading `/`.
+    pub name: String,
+    /// The description of the command, shown in the completion popup.
+    pub description: String,
+    /// What the command expands to.
+    pub expansion: CommandExpansion,
+}
+/// Implementation of the `Command` struct.
+impl Command {
+    /// Create a new instance of the `Command` struct.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the command, with or without the leading `/`.
+    /// * `description` - The description of the command.
+    /// * `expansion` - What the command expands to.
+    ///
+    /// # Returns
+    /// * `Self` - The new instance of the `Command` struct.
+    pub fn new(
+        name: impl AsRef<str>,
+        description: impl AsRef<str>,
+        expansion: CommandExpansion,
+    ) -> Self {
+        Self {
+            name: name.as_ref().trim_start_matches('/').to_string(),
+            description: description.as_ref().to_string(),
+            expansion,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// `CommandRegistry` is a struct that contains the slash commands that can be
+/// run from the prompt.
+/// The commands defined in the application configuration are registered at
+/// startup, and other parts of the application can register their own
+/// commands with `register`.
+pub struct CommandRegistry {
+    /// The commands, by name.
+    commands: BTreeMap<String, Command>,
+}
+/// Implementation of the `CommandRegistry` struct.
+impl CommandRegistry {
+    /// Create a new registry with the built-in commands and the commands
+    /// defined in the application configuration.
+    /// A command of the configuration replaces a built-in command with the
+    /// same name.
+    ///
+    /// # Arguments
+    /// * `entries` - The commands defined in the application configuration.
+    ///
+    /// # Returns
+    /// * `Self` - The new registry.
+    pub fn from_config(entries: &[CommandEntry]) -> Self {
+        let mut registry = Self::default();
+        registry.register_builtins();
+        for entry in entries {
+            match Self::command_from_entry(entry) {
+                Some(command) => registry.register(command),
+                None => tracing::warn!("Invalid command in the app configuration: {:?}", entry),
+            }
+        }
+        registry
+    }
+    /// Register the built-in commands.
+    fn register_builtins(&mut self) {
+        for (name, description, action) in [
+            (
+                "profile",
+                "Show the My Profile settings",
+                Action::ShowMyProfile,
+            ),
+            (
+                "recent",
+                "Show the recently closed chats",
+                Action::ShowRecentChats,
+            ),
+            (
+                "reopen",
+                "Reopen the last closed chat",
+                Action::ReopenLastChat,
+            ),
+            ("file", "Send a file to the open chat", Action::ShowSendFile),
+            (
+                "config",
+                "Show the problems of the configuration files",
+                Action::ShowConfigDiagnostics,
+            ),
+            (
+                "folders",
+                "Create and edit the chat folders",
+                Action::ShowChatFolders,
+            ),
+            (
+                "stickers",
+                "Manage the installed sticker sets",
+                Action::ShowStickerSets,
+            ),
+            (
+                "sticker",
+                "Send a sticker to the open chat",
+                Action::ShowStickerPicker,
+            ),
+            (
+                "gif",
+                "Send a saved animation or a GIF to the open chat",
+                Action::ShowAnimationPicker,
+            ),
+            (
+                "blocklist",
+                "Export or import the blocked users",
+                Action::ShowBlocklist,
+            ),
+            (
+                "storage",
+                "Show the space used by the cache and the database",
+                Action::ShowStorage,
+            ),
+            (
+                "memory",
+                "Show the memory used and the messages kept in it",
+                Action::ShowMemory,
+            ),
+            (
+                "contactnote",
+                "Edit the personal note of the contact of the open chat",
+                Action::EditContactNote,
+            ),
+            (
+                "templates",
+                "Insert a template of a message",
+                Action::ShowTemplates,
+            ),
+            (
+                "refresh",
+                "Reload the open chat and its recent messages from Telegram",
+                Action::RefreshChat,
+            ),
+            (
+                "update",
+                "Check for a newer version of tgt",
+                Action::CheckForUpdates,
+            ),
+        ] {
+            self.register(Command::new(
+                name,
+                description,
+                CommandExpansion::Actions(vec![action]),
+            ));
+        }
+    }
+    /// Convert a command of the application configuration.
+    /// Exactly one of `actions`, `text`, `exec` and `chat` must be set.
+    ///
+    /// # Arguments
+    /// * `entry` - The command of the application configuration.
+    ///
+    /// # Returns
+    /// * `Option<Command>` - The command or `None` if the entry is invalid.
+    fn command_from_entry(entry: &CommandEntry) -> Option<Command> {
+        let expansion = match (&entry.actions, &entry.text, &entry.exec, &entry.chat) {
+            (Some(actions), None, None, None) if !actions.is_empty() => {
+                let actions = actions
+                    .iter()
+                    .map(|action| Action::from_str(action).ok())
+                    .collect::<Option<Vec<Action>>>()?;
+                CommandExpansion::Actions(actions)
+            }
+            (None, Some(text), None, None) => CommandExpansion::Snippet(text.clone()),
+            (None, None, Some(exec), None) => CommandExpansion::Exec(exec.clone()),
+            (None, None, None, Some(chat)) => CommandExpansion::OpenChat(chat.clone()),
+            _ => return None,
+        };
+        let name = entry.name.trim().trim_start_matches('/');
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return None;
+        }
+        Some(Command::new(
+            name,
+            entry.description.clone().unwrap_or_default(),
+            expansion,
+        ))
+    }
+    /// Register a command, replacing the command with the same name, if any.
+    ///
+    /// # Arguments
+    /// * `command` - The command to register.
+    pub fn register(&mut self, command: Command) {
+        self.commands.insert(command.name.clone(), command);
+    }
+    /// Get a command by name.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the command, without the leading `/`.
+    ///
+    /// # Returns
+    /// * `Option<&Command>` - The command, if any.
+    pub fn get(&self, name: &str) -> Option<&Command> {
+        self.commands.get(name)
+    }
+    /// Get the commands whose name starts with a prefix, sorted by name.
+    ///
+    /// # Arguments
+    /// * `prefix` - The prefix, without the leading `/`.
+    ///
+    /// # Returns
+    /// * `Vec<&Command>` - The matching commands.
+    pub fn complete(&self, prefix: &str) -> Vec<&Command> {
+        self.commands
+            .range(prefix.to_string()..)
+            .take_while(|(name, _)| name.starts_with(prefix))
+            .map(|(_, command)| command)
+            .collect()
+    }
+    /// Split a text typed in the prompt into the name of a registered command
+    /// and its arguments.
+    ///
+    /// # Arguments
+    /// * `text` - The text typed in the prompt.
+    ///
+    /// # Returns
+    /// * `Option<(String, String)>` - The name of the command and the
+    ///   arguments, or `None` if the text is not a registered command, for
+    ///   example a bot command that must be sent as a message.
+    pub fn parse(&self, text: &str) -> Option<(String, String)> {
+        let text = text.trim().strip_prefix('/')?;
+        let (name, args) = match text.split_once(char::is_whitespace) {
+            Some((name, args)) => (name, args.trim()),
+            None => (text, ""),
+        };
+        self.get(name)
+            .map(|command| (command.name.clone(), args.to_string()))
+    }
+}
@@ -14,6 +14,14 @@ pub enum ComponentName {
     Prompt,
     /// The reply message window.
     ReplyMessage,
+    /// The popup window.
+    Popup,
+    /// The results of the search of the messages of all the chats.
+    SearchResults,
+    /// The contacts of the user.
+    Contacts,
+    /// The members of the open group.
+    Members,
     /// The title bar.
     TitleBar,
     /// The status bar.
@@ -30,6 +38,10 @@ impl Display for ComponentName {
             ComponentName::TitleBar => write!(f, "Title Bar"),
             ComponentName::StatusBar => write!(f, "Status Bar"),
             ComponentName::ReplyMessage => write!(f, "Reply Message"),
+            ComponentName::Popup => write!(f, "Popup"),
+            ComponentName::SearchResults => write!(f, "Search"),
+            ComponentName::Contacts => write!(f, "Contacts"),
+            ComponentName::Members => write!(f, "Members"),
         }
     }
 }
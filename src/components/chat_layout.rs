@@ -0,0 +1,41 @@
+use crate::tg::message_entry::MessageEntry;
+use ratatui::{
+    layout::Alignment,
+    style::Style,
+    text::{Line, Span},
+};
+
+/// Check whether a message is shown under the header of the message before
+/// it, because they are sent by the same sender within a short time.
+///
+/// # Arguments
+/// * `previous` - The message sent before, if any.
+/// * `message` - The message.
+/// * `minutes` - The maximum time between the two messages, in minutes. Zero
+///   disables the grouping.
+///
+/// # Returns
+/// * `bool` - Whether the header of the message is hidden.
+pub fn is_grouped(previous: Option<&MessageEntry>, message: &MessageEntry, minutes: u64) -> bool {
+    let Some(previous) = previous else {
+        return false;
+    };
+    let elapsed =
+        i64::from(message.timestamp().timestamp) - i64::from(previous.timestamp().timestamp);
+    minutes > 0
+        && previous.sender_id() == message.sender_id()
+        && (0..=minutes as i64 * 60).contains(&elapsed)
+}
+
+/// Get a line that divides the messages of the chat, like the one that marks
+/// the messages received while offline.
+///
+/// # Arguments
+/// * `text` - The text of the divider.
+/// * `style` - The style of the divider.
+///
+/// # Returns
+/// * `Line<'static>` - The centered divider.
+pub fn divider_line(text: &str, style: Style) -> Line<'static> {
+    Line::from(Span::styled(format!("── {} ──", text), style)).alignment(Alignment::Center)
+}
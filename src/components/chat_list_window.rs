@@ -1,9 +1,13 @@
-use crate::action::Action;
+use crate::action::{Action, ChatBatchOperation};
 use crate::app_context::AppContext;
-use crate::component_name::ComponentName::Prompt;
+use crate::chat_list_rules::{chat_style, ChatFacts, ChatKind};
 use crate::components::component_traits::{Component, HandleFocus};
+use crate::components::half_page_items;
+use crate::components::popup_window::{Popup, PopupItem};
 use crate::event::Event;
+use crate::text_width::{display_width, truncate_to_width};
 use crate::tg::message_entry::MessageEntry;
+use crate::tg::tg_backend::MUTE_FOREVER;
 use ratatui::layout::Rect;
 use ratatui::symbols::border::PLAIN;
 use ratatui::text::{Line, Span, Text};
@@ -13,7 +17,7 @@ use ratatui::widgets::{List, ListDirection, ListState};
 use ratatui::Frame;
 use std::sync::Arc;
 use tdlib_rs::enums::{ChatList, UserStatus};
-use tdlib_rs::types::User;
+use tdlib_rs::types::{ChatFolderInfo, ChatListFolder, User};
 use tokio::sync::mpsc::UnboundedSender;
 
 #[derive(Debug)]
@@ -24,10 +28,26 @@ pub struct ChatListEntry {
     user: Option<User>,
     is_marked_as_unread: bool,
     unread_count: i32,
+    /// Number of unread messages with a mention/reply in the chat
+    unread_mention_count: i32,
+    /// Whether the chat has outgoing messages that failed to be sent
+    has_failed_messages: bool,
     /// Identifier of the last read incoming message
     last_read_inbox_message_id: Option<i64>,
     /// Identifier of the last read outgoing message
     last_read_outbox_message_id: Option<i64>,
+    /// Whether the chat is marked as favorite
+    is_favorite: bool,
+    /// Whether the chat is marked for a batch operation
+    is_marked: bool,
+    /// Whether the chat is pinned in the chat list shown
+    is_pinned: bool,
+    /// Whether the notifications of the chat are muted
+    is_muted: bool,
+    /// The kind of the chat, for the styles of the chat list
+    kind: ChatKind,
+    /// The username of the chat, empty if it has none
+    username: String,
 }
 impl Default for ChatListEntry {
     fn default() -> Self {
@@ -43,8 +63,16 @@ impl ChatListEntry {
             user: None,
             is_marked_as_unread: false,
             unread_count: 0,
+            unread_mention_count: 0,
+            has_failed_messages: false,
             last_read_inbox_message_id: None,
             last_read_outbox_message_id: None,
+            is_favorite: false,
+            is_marked: false,
+            is_pinned: false,
+            is_muted: false,
+            kind: ChatKind::Private,
+            username: String::new(),
         }
     }
 
@@ -66,14 +94,44 @@ impl ChatListEntry {
     pub fn set_unread_count(&mut self, unread_count: i32) {
         self.unread_count = unread_count;
     }
+    pub fn set_unread_mention_count(&mut self, unread_mention_count: i32) {
+        self.unread_mention_count = unread_mention_count;
+    }
+    pub fn set_has_failed_messages(&mut self, has_failed_messages: bool) {
+        self.has_failed_messages = has_failed_messages;
+    }
     pub fn set_last_read_inbox_message_id(&mut self, last_read_inbox_message_id: i64) {
         self.last_read_inbox_message_id = Some(last_read_inbox_message_id);
     }
     pub fn set_last_read_outbox_message_id(&mut self, last_read_outbox_message_id: i64) {
         self.last_read_outbox_message_id = Some(last_read_outbox_message_id);
     }
+    pub fn set_is_favorite(&mut self, is_favorite: bool) {
+        self.is_favorite = is_favorite;
+    }
+    pub fn set_is_marked(&mut self, is_marked: bool) {
+        self.is_marked = is_marked;
+    }
+    pub fn set_is_pinned(&mut self, is_pinned: bool) {
+        self.is_pinned = is_pinned;
+    }
+    pub fn set_is_muted(&mut self, is_muted: bool) {
+        self.is_muted = is_muted;
+    }
+    pub fn set_kind(&mut self, kind: ChatKind) {
+        self.kind = kind;
+    }
+    pub fn set_username(&mut self, username: String) {
+        self.username = username;
+    }
 
-    fn get_text_styled(&self, app_context: &AppContext) -> Text {
+    /// Get the text of the chat in the chat list. The name of the chat is
+    /// truncated so that the counters and the time after it stay visible.
+    ///
+    /// # Arguments
+    /// * `app_context` - The application context.
+    /// * `width` - The number of columns available to the entry.
+    fn get_text_styled(&self, app_context: &AppContext, width: usize) -> Text<'_> {
         let mut online_symbol = "";
         let mut verificated_symbol = "";
         if let Some(user) = &self.user {
@@ -92,27 +150,77 @@ impl ChatListEntry {
         } else {
             "".to_string()
         };
+        let failed_send_symbol = if self.has_failed_messages { "!" } else { "" };
+        let muted_symbol = if self.is_muted { "🔇" } else { "" };
+        let favorite_symbol = if self.is_favorite { "★ " } else { "" };
+        let marked_symbol = if self.is_marked { "▣ " } else { "" };
+        let pinned_symbol = if self.is_pinned { "📌 " } else { "" };
+        let unread_mention_symbol = if self.unread_mention_count > 0 {
+            "@"
+        } else {
+            ""
+        };
 
         let preview_lines = -1;
         let mut entry = Text::default();
-        entry.extend(vec![Line::from(vec![
+        let mut first_line = Line::from(vec![
+            Span::raw(marked_symbol),
+            Span::raw(pinned_symbol),
+            Span::raw(favorite_symbol),
             Span::raw(online_symbol),
-            Span::styled(
-                self.chat_name.clone(),
-                app_context.style_chat_list_item_chat_name(),
-            ),
+            // The name is truncated below, to the columns left by the other
+            // spans.
+            Span::styled("", app_context.style_chat_list_item_chat_name()),
             Span::raw(" "),
             Span::styled(
                 unread_info,
                 app_context.style_chat_list_item_unread_counter(),
             ),
             Span::raw(" "),
+            Span::styled(
+                failed_send_symbol,
+                app_context.style_chat_list_item_failed_send(),
+            ),
+            Span::styled(
+                unread_mention_symbol,
+                app_context.style_chat_list_item_unread_mention(),
+            ),
+            Span::raw(" "),
+            Span::raw(muted_symbol),
             Span::raw(verificated_symbol),
             Span::raw(" | "),
             self.last_message.as_ref().map_or_else(Span::default, |e| {
                 e.timestamp().get_span_styled(app_context)
             }),
-        ])]);
+        ]);
+        const CHAT_NAME_SPAN: usize = 4;
+        let others_width = first_line
+            .spans
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != CHAT_NAME_SPAN)
+            .map(|(_, span)| display_width(&span.content))
+            .sum::<usize>();
+        first_line.spans[CHAT_NAME_SPAN].content =
+            truncate_to_width(&self.chat_name, width.saturating_sub(others_width)).into();
+        // The styles of the rules of the configuration go over the styles of
+        // the theme, on the name of the chat and on the whole entry.
+        let facts = ChatFacts {
+            title: &self.chat_name,
+            username: &self.username,
+            kind: self.kind,
+            muted: self.is_muted,
+            unread: self.unread_count > 0 || self.is_marked_as_unread,
+            mentioned: self.unread_mention_count > 0,
+            pinned: self.is_pinned,
+            favorite: self.is_favorite,
+        };
+        let rule_style = chat_style(&app_context.app_config().chat_list_styles, &facts);
+        if let Some(style) = rule_style {
+            let name = &mut first_line.spans[CHAT_NAME_SPAN];
+            name.style = name.style.patch(style);
+        }
+        entry.extend(vec![first_line]);
         entry.extend(self.last_message.as_ref().map_or_else(Line::default, |e| {
             e.get_lines_styled_with_style(
                 app_context.style_chat_list_item_message_content(),
@@ -121,9 +229,84 @@ impl ChatListEntry {
             .clone()
         }));
 
-        entry
+        match rule_style {
+            Some(style) => entry.patch_style(style),
+            None => entry,
+        }
+    }
+}
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// `ChatListTab` is an enum that represents the chats shown in the chat list.
+pub enum ChatListTab {
+    /// All the chats.
+    #[default]
+    All,
+    /// The chats marked as favorite.
+    Favorites,
+    /// The chats of a Telegram folder, with the id of the folder.
+    Folder(i32),
+}
+/// Implementation of the `ChatListTab` enum.
+impl ChatListTab {
+    /// Get the tabs, in the order they are shown: all the chats, the
+    /// favorite chats and then the folders of the user.
+    ///
+    /// # Arguments
+    /// * `folders` - The chat folders of the user.
+    ///
+    /// # Returns
+    /// * `Vec<Self>` - The tabs.
+    fn tabs(folders: &[ChatFolderInfo]) -> Vec<Self> {
+        let mut tabs = vec![Self::All, Self::Favorites];
+        tabs.extend(folders.iter().map(|folder| Self::Folder(folder.id)));
+        tabs
+    }
+    /// Get the title of the tab.
+    ///
+    /// # Arguments
+    /// * `folders` - The chat folders of the user.
+    ///
+    /// # Returns
+    /// * `String` - The title.
+    fn title(&self, folders: &[ChatFolderInfo]) -> String {
+        match self {
+            Self::All => "All".to_string(),
+            Self::Favorites => "★ Favorites".to_string(),
+            Self::Folder(id) => folders
+                .iter()
+                .find(|folder| folder.id == *id)
+                .map_or_else(String::new, |folder| folder.title.clone()),
+        }
+    }
+    /// Get the tab next to this one, wrapping around. The first tab is
+    /// returned if this one is no longer shown, like a deleted folder.
+    ///
+    /// # Arguments
+    /// * `folders` - The chat folders of the user.
+    /// * `forward` - Whether the tab after this one is returned, or the one
+    ///   before.
+    ///
+    /// # Returns
+    /// * `Self` - The next tab.
+    fn next(&self, folders: &[ChatFolderInfo], forward: bool) -> Self {
+        let tabs = Self::tabs(folders);
+        match tabs.iter().position(|tab| tab == self) {
+            Some(i) if forward => tabs[(i + 1) % tabs.len()],
+            Some(i) => tabs[(i + tabs.len() - 1) % tabs.len()],
+            None => Self::All,
+        }
+    }
+    /// Get the list of TDLib the chats of the tab are loaded from.
+    fn chat_list(&self) -> ChatList {
+        match self {
+            Self::All | Self::Favorites => ChatList::Main,
+            Self::Folder(id) => ChatList::Folder(ChatListFolder {
+                chat_folder_id: *id,
+            }),
+        }
     }
 }
+
 /// `ChatListWindow` is a struct that represents a window for displaying a list
 /// of chat items. It is responsible for managing the layout and rendering of
 /// the chat list.
@@ -140,6 +323,15 @@ pub struct ChatListWindow {
     chat_list_state: ListState,
     /// Indicates whether the `ChatListWindow` is focused or not.
     focused: bool,
+    /// The chats shown in the list.
+    tab: ChatListTab,
+    /// The chats marked for a batch operation, in the order they were
+    /// marked.
+    marked_chats: Vec<i64>,
+    /// The heights of the chat items, as last drawn.
+    item_heights: Vec<usize>,
+    /// The number of rows of the list, as last drawn.
+    list_height: usize,
 }
 /// Implementation of the `ChatListWindow` struct.
 impl ChatListWindow {
@@ -156,6 +348,10 @@ impl ChatListWindow {
         let chat_list = vec![];
         let chat_list_state = ListState::default();
         let focused = false;
+        let tab = ChatListTab::default();
+        let marked_chats = vec![];
+        let item_heights = vec![];
+        let list_height = 0;
 
         ChatListWindow {
             app_context,
@@ -164,6 +360,10 @@ impl ChatListWindow {
             chat_list,
             chat_list_state,
             focused,
+            tab,
+            marked_chats,
+            item_heights,
+            list_height,
         }
     }
     /// Set the name of the `ChatListWindow`.
@@ -182,11 +382,7 @@ impl ChatListWindow {
         let i = match self.chat_list_state.selected() {
             Some(i) => {
                 if i == self.chat_list.len() / 2 {
-                    if let Some(event_tx) = self.app_context.tg_context().event_tx().as_ref() {
-                        event_tx
-                            .send(Event::LoadChats(ChatList::Main.into(), 20))
-                            .unwrap();
-                    }
+                    self.load_more_chats();
                 }
 
                 if i >= self.chat_list.len() - 1 {
@@ -199,6 +395,55 @@ impl ChatListWindow {
         };
         self.chat_list_state.select(Some(i));
     }
+    /// Load more chats, when the second half of the list is reached.
+    fn load_more_chats(&self) {
+        if let Some(event_tx) = self.app_context.tg_context().event_tx().as_ref() {
+            event_tx
+                .send(Event::LoadChats(self.tab.chat_list().into(), 20))
+                .unwrap();
+        }
+    }
+    /// Select the first chat item in the list.
+    fn first(&mut self) {
+        if !self.chat_list.is_empty() {
+            self.chat_list_state.select(Some(0));
+        }
+    }
+    /// Select the last loaded chat item in the list and load more chats.
+    fn last(&mut self) {
+        if let Some(last) = self.chat_list.len().checked_sub(1) {
+            self.load_more_chats();
+            self.chat_list_state.select(Some(last));
+        }
+    }
+    /// Move the selection down by half of the height of the list.
+    fn half_page_down(&mut self) {
+        let Some(last) = self.chat_list.len().checked_sub(1) else {
+            return;
+        };
+        let i = self.chat_list_state.selected().unwrap_or(0);
+        let step = half_page_items(
+            self.item_heights.iter().skip(i + 1).copied(),
+            self.list_height,
+        );
+        let next = (i + step).min(last);
+        if i < self.chat_list.len() / 2 && next >= self.chat_list.len() / 2 {
+            self.load_more_chats();
+        }
+        self.chat_list_state.select(Some(next));
+    }
+    /// Move the selection up by half of the height of the list.
+    fn half_page_up(&mut self) {
+        if self.chat_list.is_empty() {
+            return;
+        }
+        let i = self.chat_list_state.selected().unwrap_or(0);
+        let step = half_page_items(
+            self.item_heights.iter().take(i).rev().copied(),
+            self.list_height,
+        );
+        self.chat_list_state.select(Some(i.saturating_sub(step)));
+    }
     /// Select the previous chat item in the list.
     fn previous(&mut self) {
         let i = match self.chat_list_state.selected() {
@@ -216,29 +461,214 @@ impl ChatListWindow {
     /// Unselect the chat item in the list.
     fn unselect(&mut self) {
         self.chat_list_state.select(None);
+        self.app_context.tg_context().set_preview_chat_id(0);
+    }
+    /// Preview the selected chat item in the list, if the preview is enabled
+    /// and the chat is not already open.
+    fn preview_selected(&self) {
+        if !self.app_context.app_config().chat_list_preview {
+            return;
+        }
+        let tg_context = self.app_context.tg_context();
+        let chat_id = self
+            .chat_list_state
+            .selected()
+            .and_then(|i| self.chat_list.get(i))
+            .map(|chat| chat.chat_id)
+            .filter(|chat_id| *chat_id != tg_context.open_chat_id())
+            .unwrap_or(0);
+        if chat_id == tg_context.preview_chat_id() {
+            return;
+        }
+        tg_context.set_preview_chat_id(chat_id);
+        if chat_id != 0 {
+            self.app_context
+                .action_tx()
+                .send(Action::PreviewChat(chat_id))
+                .unwrap();
+        }
+    }
+    /// Add the selected chat to the favorites or remove it.
+    fn toggle_favorite(&mut self) {
+        let Some(chat) = self
+            .chat_list_state
+            .selected()
+            .and_then(|i| self.chat_list.get_mut(i))
+        else {
+            return;
+        };
+        chat.is_favorite = self
+            .app_context
+            .local_state()
+            .toggle_favorite_chat(chat.chat_id);
+        self.app_context.save_local_state();
+    }
+    /// Pin the selected chat at the top of the list shown or unpin it. The
+    /// favorites are pinned in the main list.
+    fn toggle_pin(&self) {
+        let Some(chat) = self
+            .chat_list_state
+            .selected()
+            .and_then(|i| self.chat_list.get(i))
+        else {
+            return;
+        };
+        self.app_context
+            .action_tx()
+            .send(Action::SetChatPinned(
+                chat.chat_id,
+                self.tab.chat_list().into(),
+                !chat.is_pinned,
+            ))
+            .unwrap();
+    }
+    /// Unmute the selected chat, or show for how long to mute it.
+    fn toggle_mute(&self) {
+        let Some(chat) = self
+            .chat_list_state
+            .selected()
+            .and_then(|i| self.chat_list.get(i))
+        else {
+            return;
+        };
+        let action = if chat.is_muted {
+            Action::SetChatMuteFor(chat.chat_id, 0)
+        } else {
+            let mute_for = |title: &str, seconds| {
+                PopupItem::new(title, Action::SetChatMuteFor(chat.chat_id, seconds))
+            };
+            Action::ShowPopup(Popup::List(
+                format!("Mute {}", chat.chat_name),
+                vec![
+                    mute_for("For 1 hour", 60 * 60),
+                    mute_for("For 8 hours", 8 * 60 * 60),
+                    mute_for("For 2 days", 2 * 24 * 60 * 60),
+                    mute_for("Forever", MUTE_FOREVER),
+                ],
+            ))
+        };
+        self.app_context.action_tx().send(action).unwrap();
+    }
+    /// Switch to the next or the previous tab of the list. The chats of a
+    /// folder are loaded when its tab is shown.
+    ///
+    /// # Arguments
+    /// * `forward` - Whether the next tab is shown, or the previous one.
+    fn next_tab(&mut self, forward: bool) {
+        self.tab = self
+            .tab
+            .next(&self.app_context.tg_context().chat_folders(), forward);
+        self.unselect();
+        if let ChatListTab::Folder(_) = self.tab {
+            self.load_more_chats();
+        }
+    }
+    /// Mark the selected chat for a batch operation or unmark it.
+    fn toggle_mark(&mut self) {
+        let Some(chat_id) = self
+            .chat_list_state
+            .selected()
+            .and_then(|i| self.chat_list.get(i))
+            .map(|chat| chat.chat_id)
+        else {
+            return;
+        };
+        match self.marked_chats.iter().position(|id| *id == chat_id) {
+            Some(i) => {
+                self.marked_chats.remove(i);
+            }
+            None => self.marked_chats.push(chat_id),
+        }
+    }
+    /// Show the operations that can be applied to the marked chats.
+    /// Archiving the chats must be confirmed.
+    fn show_batch_actions(&self) {
+        let chat_ids = self.marked_chats.clone();
+        let popup = if chat_ids.is_empty() {
+            Popup::Info(
+                "Batch actions".to_string(),
+                "Mark the chats to act on first.".to_string(),
+            )
+        } else {
+            let count = match chat_ids.len() {
+                1 => "1 chat".to_string(),
+                n => format!("{} chats", n),
+            };
+            let batch = |operation| Action::BatchChats(chat_ids.clone(), operation);
+            let mut items = vec![
+                PopupItem::new(
+                    format!("Mark {} as read", count),
+                    batch(ChatBatchOperation::MarkAsRead),
+                ),
+                PopupItem::new(format!("Mute {}", count), batch(ChatBatchOperation::Mute)),
+                PopupItem::new(
+                    format!("Unmute {}", count),
+                    batch(ChatBatchOperation::Unmute),
+                ),
+                PopupItem::new(
+                    format!("Archive {}", count),
+                    Action::ShowPopup(Popup::List(
+                        format!("Archive {}?", count),
+                        vec![
+                            PopupItem::new("Archive", batch(ChatBatchOperation::Archive)),
+                            PopupItem::new("Cancel", Action::HidePopup),
+                        ],
+                    )),
+                ),
+            ];
+            items.extend(
+                self.app_context
+                    .tg_context()
+                    .chat_folders()
+                    .iter()
+                    .map(|folder| {
+                        PopupItem::new(
+                            format!("Add {} to the folder {}", count, folder.title),
+                            batch(ChatBatchOperation::AddToFolder(folder.id)),
+                        )
+                    }),
+            );
+            Popup::List(format!("Batch actions on {}", count), items)
+        };
+        self.app_context
+            .action_tx()
+            .send(Action::ShowPopup(popup))
+            .unwrap();
+    }
+    /// Show the ways to clear the history of the selected chat or to leave it.
+    fn delete_selected(&self) {
+        if let Some(chat) = self
+            .chat_list_state
+            .selected()
+            .and_then(|i| self.chat_list.get(i))
+        {
+            self.app_context
+                .action_tx()
+                .send(Action::ShowChatDeletion(chat.chat_id))
+                .unwrap();
+        }
+    }
+    /// Show the profile of the selected chat item in the list.
+    fn show_profile_selected(&self) {
+        if let Some(chat) = self
+            .chat_list_state
+            .selected()
+            .and_then(|i| self.chat_list.get(i))
+        {
+            self.app_context
+                .action_tx()
+                .send(Action::ShowProfile(chat.chat_id))
+                .unwrap();
+        }
     }
     /// Confirm the selection of the chat item in the list.
     fn confirm_selection(&mut self) {
         if let Some(i) = self.chat_list_state.selected() {
             if let Some(chat) = self.chat_list.get(i) {
-                self.app_context
-                    .tg_context()
-                    .set_open_chat_user(chat.user.clone());
-                self.app_context.tg_context().set_open_chat_id(chat.chat_id);
-                self.app_context.tg_context().clear_open_chat_messages();
                 self.app_context
                     .action_tx()
-                    .send(Action::FocusComponent(Prompt))
+                    .send(Action::OpenChat(chat.chat_id))
                     .unwrap();
-
-                if let Some(event_tx) = self.app_context.tg_context().event_tx().as_ref() {
-                    self.app_context.tg_context().set_from_message_id(0);
-                    // Load chat history
-                    event_tx.send(Event::GetChatHistory).unwrap();
-
-                    // Mark all unread messages as read
-                    event_tx.send(Event::ViewAllMessages).unwrap();
-                }
             }
         }
     }
@@ -252,8 +682,10 @@ impl HandleFocus for ChatListWindow {
         self.focused = true;
     }
     /// Set the `focused` flag for the `ChatListWindow`.
+    /// The preview of the selected chat, if any, is closed.
     fn unfocus(&mut self) {
         self.focused = false;
+        self.app_context.tg_context().set_preview_chat_id(0);
     }
 }
 
@@ -266,10 +698,43 @@ impl Component for ChatListWindow {
 
     fn update(&mut self, action: Action) {
         match action {
-            Action::ChatListNext => self.next(),
-            Action::ChatListPrevious => self.previous(),
+            Action::ChatListNext => {
+                self.next();
+                self.preview_selected();
+            }
+            Action::ChatListPrevious => {
+                self.previous();
+                self.preview_selected();
+            }
+            Action::ChatListFirst => {
+                self.first();
+                self.preview_selected();
+            }
+            Action::ChatListLast => {
+                self.last();
+                self.preview_selected();
+            }
+            Action::ChatListHalfPageDown => {
+                self.half_page_down();
+                self.preview_selected();
+            }
+            Action::ChatListHalfPageUp => {
+                self.half_page_up();
+                self.preview_selected();
+            }
             Action::ChatListUnselect => self.unselect(),
             Action::ChatListOpen => self.confirm_selection(),
+            Action::ChatListToggleFavorite => self.toggle_favorite(),
+            Action::ChatListNextTab => self.next_tab(true),
+            Action::ChatListPreviousTab => self.next_tab(false),
+            Action::ChatListToggleMark => self.toggle_mark(),
+            Action::ChatListBatchActions => self.show_batch_actions(),
+            Action::ChatListDelete => self.delete_selected(),
+            Action::ChatListShowProfile => self.show_profile_selected(),
+            Action::ToggleChatPin => self.toggle_pin(),
+            Action::ToggleChatMute => self.toggle_mute(),
+            // The operation has been applied, the chats are unmarked.
+            Action::BatchChats(..) => self.marked_chats.clear(),
             _ => {}
         }
     }
@@ -280,18 +745,65 @@ impl Component for ChatListWindow {
         } else {
             self.app_context.style_chat_list()
         };
-        if let Ok(Some(items)) = self.app_context.tg_context().get_chats_index() {
+        let folders = self.app_context.tg_context().chat_folders().clone();
+        // The tab of a deleted folder is no longer shown.
+        if !ChatListTab::tabs(&folders).contains(&self.tab) {
+            self.tab = ChatListTab::All;
+        }
+        let chats_index = match self.tab {
+            ChatListTab::Folder(id) => self.app_context.tg_context().get_chat_folder_index(id),
+            _ => self.app_context.tg_context().get_chats_index(),
+        };
+        if let Ok(Some(mut items)) = chats_index {
+            {
+                let local_state = self.app_context.local_state();
+                for item in items.iter_mut() {
+                    item.set_is_favorite(local_state.is_favorite_chat(item.chat_id));
+                    item.set_is_marked(self.marked_chats.contains(&item.chat_id));
+                }
+            }
+            if self.tab == ChatListTab::Favorites {
+                items.retain(|item| item.is_favorite);
+            }
             self.chat_list = items;
         }
-        let items = self
+        // The selected chat may have been removed from the favorites.
+        if let Some(i) = self.chat_list_state.selected() {
+            if i >= self.chat_list.len() {
+                self.chat_list_state
+                    .select(self.chat_list.len().checked_sub(1));
+            }
+        }
+        // The left border is not part of the entries.
+        let width = area.width.saturating_sub(1) as usize;
+        let items: Vec<Text> = self
             .chat_list
             .iter()
-            .map(|item| item.get_text_styled(&self.app_context));
+            .map(|item| item.get_text_styled(&self.app_context, width))
+            .collect();
+        self.item_heights = items.iter().map(Text::height).collect();
+        // The top and the bottom borders are not part of the list.
+        self.list_height = area.height.saturating_sub(2) as usize;
+        let mut title = vec![Span::raw(self.name.as_str()), Span::raw(" ")];
+        for tab in ChatListTab::tabs(&folders) {
+            let style = if tab == self.tab {
+                self.app_context.style_chat_list_item_selected()
+            } else {
+                self.app_context.style_chat_list()
+            };
+            title.push(Span::styled(format!(" {} ", tab.title(&folders)), style));
+        }
+        if !self.marked_chats.is_empty() {
+            title.push(Span::styled(
+                format!(" {} selected ", self.marked_chats.len()),
+                self.app_context.style_chat_list_item_unread_counter(),
+            ));
+        }
         let block = Block::default()
             .border_set(PLAIN)
             .border_style(style_border_focused)
             .borders(Borders::TOP | Borders::LEFT | Borders::BOTTOM)
-            .title(Title::from(self.name.as_str()));
+            .title(Title::from(Line::from(title)));
 
         let list = List::new(items)
             .block(block)
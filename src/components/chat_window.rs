@@ -1,23 +1,57 @@
 use crate::{
-    action::Action,
+    action::{Action, Modifiers},
     app_context::AppContext,
-    components::component_traits::{Component, HandleFocus},
+    audio_player::{self, AudioTrack},
+    components::{
+        chat_layout::{divider_line, is_grouped},
+        component_traits::{Component, HandleFocus},
+        half_page_items,
+        popup_window::Popup,
+        scroll_step,
+    },
     event::Event,
-    tg::message_entry::MessageEntry,
+    image_cache::ImageState,
+    text_width::display_width,
+    tg::{
+        inline_keyboard::{move_cursor, GridMove, InlineButton},
+        message_entry::MessageEntry,
+        td_enums::TdMessageOrigin,
+    },
+    utils::find_ignore_case,
 };
 use arboard::Clipboard;
+use crossterm::event::KeyCode;
+use image::DynamicImage;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
     symbols::{
         border::{self, Set},
         line,
     },
-    text::{Line, Span},
-    widgets::{Block, Borders, List, ListDirection, ListItem, ListState, Paragraph},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, List, ListDirection, ListItem, ListState, Paragraph, Wrap},
 };
-use std::sync::Arc;
+use ratatui_image::{picker::Picker, protocol::StatefulProtocol, StatefulImage};
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::mpsc::UnboundedSender;
 
+/// The maximum number of lines of the note of the chat shown above the
+/// messages.
+const MAX_NOTE_LINES: usize = 3;
+/// The maximum width of an image drawn inline, in cells.
+const IMAGE_MAX_WIDTH: u16 = 40;
+/// The maximum height of an image drawn inline, in cells.
+const IMAGE_MAX_HEIGHT: u16 = 12;
+/// The frames of the spinner shown while older messages are loaded.
+const LOADING_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+/// The duration of a frame of the spinner, in milliseconds.
+const LOADING_FRAME_MILLIS: i64 = 100;
+
+/// An image drawn in a message item: the id of its file, its size in cells
+/// and whether it is aligned on the right.
+type ItemImage = (i32, (u16, u16), bool);
+
 /// `ChatWindow` is a struct that represents a window for displaying a chat.
 /// It is responsible for managing the layout and rendering of the chat window.
 pub struct ChatWindow {
@@ -33,6 +67,40 @@ pub struct ChatWindow {
     message_list_state: ListState,
     /// Indicates whether the `ChatWindow` is focused or not.
     focused: bool,
+    /// The heights of the message items, as last drawn.
+    item_heights: Vec<usize>,
+    /// The number of rows of the list of messages, as last drawn.
+    list_height: usize,
+    /// The text searched in the loaded messages. It is empty when there is
+    /// no search.
+    search_query: String,
+    /// Indicates whether the search is being typed.
+    search_typing: bool,
+    /// The message item selected when the search was started. The search
+    /// starts from it and it is selected again if the search is cancelled.
+    search_origin: Option<usize>,
+    /// Indicates whether the messages are shown in the compact mode, one line
+    /// per message.
+    compact: bool,
+    /// Indicates whether the local note of the chat is shown above the
+    /// messages.
+    show_note: bool,
+    /// The ids of the messages marked to be forwarded together.
+    marked: Vec<i64>,
+    /// The picker of the graphics protocol used to draw the images.
+    picker: Picker,
+    /// The images drawn inline, encoded for the graphics protocol, by id of
+    /// their file.
+    image_protocols: HashMap<i32, Box<dyn StatefulProtocol>>,
+    /// The open chat and its newest incoming message marked as read, so that
+    /// the messages shown are marked as read once.
+    viewed: (i64, i64),
+    /// The message item the selection is scrolled to over the next frames,
+    /// when the scrolling is smooth.
+    scroll_target: Option<usize>,
+    /// The row and the column of the button selected in the inline keyboard
+    /// of the selected message, while its buttons are selected.
+    button_cursor: Option<(usize, usize)>,
 }
 /// Implementation of the `ChatWindow` struct.
 impl ChatWindow {
@@ -49,6 +117,22 @@ impl ChatWindow {
         let message_list = vec![];
         let message_list_state = ListState::default();
         let focused = false;
+        let item_heights = vec![];
+        let list_height = 0;
+        let search_query = String::new();
+        let search_typing = false;
+        let search_origin = None;
+        let compact = app_context.app_config().compact_chat;
+        let show_note = true;
+        let marked = vec![];
+        // The protocol is guessed from the environment, and the unicode half
+        // blocks are used if the terminal supports no graphics protocol.
+        let mut picker = Picker::new((8, 12));
+        picker.guess_protocol();
+        let image_protocols = HashMap::new();
+        let viewed = (0, 0);
+        let scroll_target = None;
+        let button_cursor = None;
         ChatWindow {
             app_context,
             name,
@@ -56,6 +140,19 @@ impl ChatWindow {
             message_list,
             message_list_state,
             focused,
+            item_heights,
+            list_height,
+            search_query,
+            search_typing,
+            search_origin,
+            compact,
+            show_note,
+            marked,
+            picker,
+            image_protocols,
+            viewed,
+            scroll_target,
+            button_cursor,
         }
     }
     /// Set the name of the `ChatWindow`.
@@ -72,14 +169,9 @@ impl ChatWindow {
 
     /// Select the next message item in the list.
     fn next(&mut self) {
+        self.scroll_target = None;
         let i = match self.message_list_state.selected() {
             Some(i) => {
-                if i == self.message_list.len() / 2 {
-                    if let Some(event_tx) = self.app_context.tg_context().event_tx().as_ref() {
-                        event_tx.send(Event::GetChatHistory).unwrap();
-                    }
-                }
-
                 if i == 0 {
                     0
                 } else {
@@ -89,10 +181,12 @@ impl ChatWindow {
             None => 0,
         };
         self.message_list_state.select(Some(i));
+        self.load_newer_messages(i);
     }
 
     /// Select the previous message item in the list.
     fn previous(&mut self) {
+        self.scroll_target = None;
         let i = match self.message_list_state.selected() {
             Some(i) => {
                 if i == self.message_list.len() / 2 {
@@ -102,6 +196,11 @@ impl ChatWindow {
                 }
 
                 if i >= self.message_list.len() - 1 {
+                    // The older messages are asked for again at the top,
+                    // unless they are being loaded.
+                    if !self.app_context.tg_context().history_pages().is_loading() {
+                        self.load_older_messages();
+                    }
                     i
                 } else {
                     i + 1
@@ -112,28 +211,357 @@ impl ChatWindow {
         self.message_list_state.select(Some(i));
     }
 
+    /// Ask for older messages of the chat.
+    fn load_older_messages(&self) {
+        if let Some(event_tx) = self.app_context.tg_context().event_tx().as_ref() {
+            event_tx.send(Event::GetChatHistory).unwrap();
+        }
+    }
+
+    /// Ask for the messages newer than the loaded ones, once the newest
+    /// loaded message is selected, if they were dropped or not loaded yet.
+    /// The older messages not requested yet are no longer loaded, since the
+    /// messages are scrolled the other way.
+    ///
+    /// # Arguments
+    /// * `selected` - The index of the selected message item.
+    fn load_newer_messages(&self, selected: usize) {
+        let tg_context = self.app_context.tg_context();
+        tg_context.history_pages().stop();
+        if selected != 0 || !tg_context.has_newer_messages() {
+            return;
+        }
+        let event_tx = tg_context.event_tx();
+        if let Some(event_tx) = event_tx.as_ref() {
+            event_tx.send(Event::GetNewerChatHistory).unwrap();
+        }
+    }
+
+    /// Select the oldest loaded message item in the list, at the top, and
+    /// ask for older messages.
+    fn first(&mut self) {
+        self.scroll_target = None;
+        if let Some(last) = self.message_list.len().checked_sub(1) {
+            self.load_older_messages();
+            self.message_list_state.select(Some(last));
+        }
+    }
+
+    /// Select the most recent message item in the list, at the bottom. The
+    /// latest messages are loaded again if the newer ones were dropped.
+    fn last(&mut self) {
+        self.scroll_target = None;
+        if !self.message_list.is_empty() {
+            self.message_list_state.select(Some(0));
+        }
+        let tg_context = self.app_context.tg_context();
+        tg_context.history_pages().stop();
+        if tg_context.has_newer_messages() {
+            if let Some(event_tx) = tg_context.event_tx().as_ref() {
+                event_tx.send(Event::GetLatestChatHistory).unwrap();
+            }
+        }
+    }
+
+    /// Move the selection down, towards the most recent messages, by half of
+    /// the height of the list.
+    fn half_page_down(&mut self) {
+        if self.message_list.is_empty() {
+            return;
+        }
+        let i = self.scrolled_to().unwrap_or(0);
+        let step = half_page_items(
+            self.item_heights.iter().take(i).rev().copied(),
+            self.list_height,
+        );
+        let i = i.saturating_sub(step);
+        self.scroll_to(i);
+        self.load_newer_messages(i);
+    }
+
+    /// Move the selection up, towards the oldest messages, by half of the
+    /// height of the list.
+    fn half_page_up(&mut self) {
+        let Some(last) = self.message_list.len().checked_sub(1) else {
+            return;
+        };
+        let i = self.scrolled_to().unwrap_or(0);
+        let step = half_page_items(
+            self.item_heights.iter().skip(i + 1).copied(),
+            self.list_height,
+        );
+        let previous = (i + step).min(last);
+        if i < self.message_list.len() / 2 && previous >= self.message_list.len() / 2 {
+            self.load_older_messages();
+        }
+        self.scroll_to(previous);
+    }
+
+    /// Get the message item the selection is scrolled to, or the selected
+    /// one when it is not scrolling.
+    ///
+    /// # Returns
+    /// * `Option<usize>` - The index of the message item, if any.
+    fn scrolled_to(&self) -> Option<usize> {
+        self.scroll_target.or(self.message_list_state.selected())
+    }
+
+    /// Scroll the selection to a message item, over the next frames if the
+    /// scrolling is smooth, or at once.
+    ///
+    /// # Arguments
+    /// * `target` - The index of the message item.
+    fn scroll_to(&mut self, target: usize) {
+        if self.app_context.app_config().smooth_scrolling
+            && self.message_list_state.selected().is_some()
+        {
+            self.scroll_target = Some(target);
+        } else {
+            self.scroll_target = None;
+            self.message_list_state.select(Some(target));
+        }
+    }
+
+    /// Move the selection a step towards the message item it is scrolled
+    /// to, once per frame.
+    fn step_scroll(&mut self) {
+        let Some(target) = self.scroll_target else {
+            return;
+        };
+        let Some(selected) = self.message_list_state.selected() else {
+            self.scroll_target = None;
+            return;
+        };
+        // The list may have changed while scrolling.
+        let target = target.min(self.message_list.len().saturating_sub(1));
+        let next = scroll_step(&self.item_heights, selected, target);
+        self.message_list_state.select(Some(next));
+        if next == target {
+            self.scroll_target = None;
+        }
+    }
+
+    /// Check whether a message item matches the search.
+    ///
+    /// # Arguments
+    /// * `message_entry` - The message item.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the message item contains the searched text.
+    fn is_search_match(&self, message_entry: &MessageEntry) -> bool {
+        !self.search_query.is_empty()
+            && !find_ignore_case(
+                &message_entry.message_content_to_string(),
+                &self.search_query,
+            )
+            .is_empty()
+    }
+
+    /// Find the message item matching the search, starting from an index and
+    /// wrapping around the list, like vim does.
+    ///
+    /// # Arguments
+    /// * `from` - The index of the first message item to check.
+    /// * `older` - Whether to go towards the oldest messages, at the top.
+    ///
+    /// # Returns
+    /// * `Option<usize>` - The index of the matching message item, if any.
+    fn find_search_match(&self, from: usize, older: bool) -> Option<usize> {
+        let len = self.message_list.len();
+        if len == 0 {
+            return None;
+        }
+        let from = from % len;
+        (0..len)
+            .map(|step| {
+                if older {
+                    (from + step) % len
+                } else {
+                    (from + len - step) % len
+                }
+            })
+            .find(|i| self.is_search_match(&self.message_list[*i]))
+    }
+
+    /// Start typing a search over the loaded messages.
+    fn start_search(&mut self) {
+        self.search_query.clear();
+        self.app_context.tg_context().chat_search_results().clear();
+        self.search_typing = true;
+        self.search_origin = self.message_list_state.selected();
+    }
+
+    /// Handle a key typed in the search. The closest older message matching
+    /// the search is selected at each change.
+    ///
+    /// # Arguments
+    /// * `key_code` - The typed key.
+    /// * `modifiers` - The modifiers of the typed key.
+    fn type_search(&mut self, key_code: KeyCode, modifiers: Modifiers) {
+        match key_code {
+            KeyCode::Char(c) if !modifiers.control && !modifiers.alt => {
+                self.search_query.push(c);
+            }
+            KeyCode::Backspace => {
+                self.search_query.pop();
+            }
+            KeyCode::Enter => {
+                // The messages that are not loaded are searched once the
+                // search is confirmed.
+                self.search_typing = false;
+                if !self.search_query.is_empty() {
+                    if let Some(action_tx) = self.action_tx.as_ref() {
+                        action_tx
+                            .send(Action::SearchChatMessages(self.search_query.clone()))
+                            .unwrap();
+                    }
+                }
+                return;
+            }
+            _ => return,
+        }
+        let selected = self
+            .find_search_match(self.search_origin.unwrap_or(0), true)
+            .or(self.search_origin);
+        self.message_list_state.select(selected);
+    }
+
+    /// Cancel the search being typed and select again the message item that
+    /// was selected before it.
+    fn cancel_search(&mut self) {
+        self.search_query.clear();
+        self.app_context.tg_context().chat_search_results().clear();
+        self.search_typing = false;
+        self.scroll_target = None;
+        self.message_list_state.select(self.search_origin.take());
+    }
+
+    /// Select the next message item matching the search.
+    ///
+    /// # Arguments
+    /// * `older` - Whether to go towards the oldest messages, at the top.
+    fn search_next(&mut self, older: bool) {
+        if self.search_next_result(older) {
+            return;
+        }
+        let len = self.message_list.len();
+        let from = match self.message_list_state.selected() {
+            Some(i) if older => i + 1,
+            Some(i) => i + len - 1,
+            None => 0,
+        };
+        if let Some(i) = self.find_search_match(from, older) {
+            self.scroll_to(i);
+        }
+    }
+
+    /// Select the next message of the open chat found by the search of the
+    /// chat, wrapping around the results. The chat is opened at the message
+    /// if it is not loaded.
+    ///
+    /// # Arguments
+    /// * `older` - Whether to go towards the oldest messages.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the search of the chat found messages.
+    fn search_next_result(&mut self, older: bool) -> bool {
+        let results = self.app_context.tg_context().chat_search_results().clone();
+        if results.is_empty() {
+            return false;
+        }
+        let selected_id = self
+            .message_list_state
+            .selected()
+            .and_then(|i| self.message_list.get(i))
+            .map(MessageEntry::id);
+        // The results are sorted from the newest.
+        let next = match selected_id {
+            Some(id) if older => results.iter().find(|result| **result < id),
+            Some(id) => results.iter().rev().find(|result| **result > id),
+            None => None,
+        };
+        let next = next.or(if older {
+            results.first()
+        } else {
+            results.last()
+        });
+        let Some(message_id) = next.copied() else {
+            return false;
+        };
+        match self.message_list.iter().position(|m| m.id() == message_id) {
+            Some(i) => self.scroll_to(i),
+            None => {
+                let chat_id = self.app_context.tg_context().open_chat_id();
+                if let Some(action_tx) = self.action_tx.as_ref() {
+                    action_tx
+                        .send(Action::OpenMessage(chat_id, message_id))
+                        .unwrap();
+                }
+            }
+        }
+        true
+    }
+
     /// Unselect the message item in the list.
     fn unselect(&mut self) {
+        self.scroll_target = None;
         self.message_list_state.select(None);
+        self.marked.clear();
+    }
+
+    /// Mark the selected message item in the list, or unmark it if it is
+    /// already marked.
+    fn toggle_mark_selected(&mut self) {
+        let Some(selected) = self.message_list_state.selected() else {
+            return;
+        };
+        let message_id = self.message_list[selected].id();
+        if self.marked.contains(&message_id) {
+            self.marked.retain(|id| *id != message_id);
+        } else {
+            self.marked.push(message_id);
+        }
+    }
+
+    /// Forward the marked message items, or the selected one if none is
+    /// marked.
+    fn forward_selected(&mut self) {
+        let message_ids = if self.marked.is_empty() {
+            match self.message_list_state.selected() {
+                Some(selected) => vec![self.message_list[selected].id()],
+                None => return,
+            }
+        } else {
+            std::mem::take(&mut self.marked)
+        };
+        if let Some(action_tx) = self.action_tx.as_ref() {
+            action_tx
+                .send(Action::ForwardMessages(message_ids))
+                .unwrap();
+        }
     }
 
-    /// Delete the selected message item in the list.
+    /// Ask to delete the marked message items, or the selected one if none is
+    /// marked.
     ///
     /// # Arguments
-    /// * `revoke` - A boolean flag indicating whether the message should be revoked or not.
+    /// * `revoke` - A boolean flag indicating whether the deletion for
+    ///   everyone is proposed first.
     fn delete_selected(&mut self, revoke: bool) {
-        if let Some(selected) = self.message_list_state.selected() {
-            if let Some(event_tx) = self.app_context.tg_context().event_tx().as_ref() {
-                let sender_id = self.message_list[selected].sender_id();
-                if sender_id != self.app_context.tg_context().me() {
-                    return;
-                }
-                let message_id = self.message_list[selected].id();
-                event_tx
-                    .send(Event::DeleteMessages(vec![message_id], revoke))
-                    .unwrap();
-                self.app_context.tg_context().delete_message(message_id);
+        let message_ids = if self.marked.is_empty() {
+            match self.message_list_state.selected() {
+                Some(selected) => vec![self.message_list[selected].id()],
+                None => return,
             }
+        } else {
+            // The marks are kept if the deletion is cancelled, and dropped
+            // with the messages once they are deleted.
+            self.marked.clone()
+        };
+        if let Some(action_tx) = self.action_tx.as_ref() {
+            action_tx
+                .send(Action::ShowMessageDeletion(message_ids, revoke))
+                .unwrap();
         }
     }
 
@@ -156,10 +584,15 @@ impl ChatWindow {
             }
             let message = self.message_list[selected].message_content_to_string();
             let message_id = self.message_list[selected].id();
+            // The caption of a media message is edited as Markdown, which
+            // is written by TDLib.
+            let event = if self.message_list[selected].caption().is_some() {
+                Event::EditCaption(message_id)
+            } else {
+                Event::EditMessage(message_id, message)
+            };
             if let Some(event_tx) = self.app_context.tg_context().event_tx().as_ref() {
-                event_tx
-                    .send(Event::EditMessage(message_id, message))
-                    .unwrap();
+                event_tx.send(event).unwrap();
             }
         }
     }
@@ -171,11 +604,374 @@ impl ChatWindow {
             let text = self.message_list[selected].message_content_to_string();
             if let Some(event_tx) = self.app_context.tg_context().event_tx().as_ref() {
                 event_tx
-                    .send(Event::ReplyMessage(message_id, text))
+                    .send(Event::ReplyMessage(message_id, text, None))
+                    .unwrap();
+            }
+        }
+    }
+
+    /// Open the original message of the selected forwarded message item in
+    /// the list, if it is accessible.
+    fn open_origin_selected(&self) {
+        let Some(selected) = self.message_list_state.selected() else {
+            return;
+        };
+        let Some(forward_info) = self.message_list[selected].forward_info() else {
+            return;
+        };
+        let action = match (forward_info.origin_message(), &forward_info.origin) {
+            (Some((chat_id, message_id)), _) => Action::OpenMessage(chat_id, message_id),
+            (None, TdMessageOrigin::HiddenUser(_)) => Action::ShowPopup(Popup::Info(
+                "Forwarded message".to_string(),
+                "The original sender hid their account, so the original message can not be opened."
+                    .to_string(),
+            )),
+            (None, _) => Action::ShowPopup(Popup::Info(
+                "Forwarded message".to_string(),
+                "The original message is not accessible.".to_string(),
+            )),
+        };
+        if let Some(action_tx) = self.action_tx.as_ref() {
+            action_tx.send(action).unwrap();
+        }
+    }
+
+    /// Show the details of the selected message item in the list.
+    fn show_details_selected(&self) {
+        if let Some(selected) = self.message_list_state.selected() {
+            let message_id = self.message_list[selected].id();
+            if let Some(action_tx) = self.action_tx.as_ref() {
+                action_tx
+                    .send(Action::ShowMessageDetails(message_id))
+                    .unwrap();
+            }
+        }
+    }
+
+    /// Show the reactions that can be added to the selected message item in
+    /// the list.
+    fn react_selected(&self) {
+        if let Some(selected) = self.message_list_state.selected() {
+            let message_id = self.message_list[selected].id();
+            if let Some(action_tx) = self.action_tx.as_ref() {
+                action_tx.send(Action::ShowReactions(message_id)).unwrap();
+            }
+        }
+    }
+
+    /// Open the photo or the video of the selected message item in the list.
+    fn open_media_selected(&self) {
+        if let Some(selected) = self.message_list_state.selected() {
+            let message_id = self.message_list[selected].id();
+            if let Some(action_tx) = self.action_tx.as_ref() {
+                action_tx.send(Action::ShowOpenMedia(message_id)).unwrap();
+            }
+        }
+    }
+
+    /// Open the comments of the selected channel post in the list.
+    fn open_comments_selected(&self) {
+        let Some(selected) = self.message_list_state.selected() else {
+            return;
+        };
+        let message = &self.message_list[selected];
+        let action = match message.comment_count() {
+            Some(_) => Action::OpenComments(message.id()),
+            None => Action::ShowPopup(Popup::Info(
+                "Comments".to_string(),
+                "Only the posts of the channels with a discussion group have comments.".to_string(),
+            )),
+        };
+        if let Some(action_tx) = self.action_tx.as_ref() {
+            action_tx.send(action).unwrap();
+        }
+    }
+
+    /// Show the moderation actions of the selected message item in the list.
+    fn moderate_selected(&self) {
+        if let Some(selected) = self.message_list_state.selected() {
+            let message_id = self.message_list[selected].id();
+            if let Some(action_tx) = self.action_tx.as_ref() {
+                action_tx.send(Action::ShowModeration(message_id)).unwrap();
+            }
+        }
+    }
+
+    /// Pin or unpin the selected message item in the list.
+    fn pin_selected(&self) {
+        if let Some(selected) = self.message_list_state.selected() {
+            let message_id = self.message_list[selected].id();
+            if let Some(action_tx) = self.action_tx.as_ref() {
+                action_tx.send(Action::ShowPinMessage(message_id)).unwrap();
+            }
+        }
+    }
+
+    /// Show the profile of the sender of the selected message item in the
+    /// list.
+    fn show_profile_selected(&self) {
+        if let Some(selected) = self.message_list_state.selected() {
+            let sender_id = self.message_list[selected].sender_id();
+            if let Some(action_tx) = self.action_tx.as_ref() {
+                action_tx.send(Action::ShowProfile(sender_id)).unwrap();
+            }
+        }
+    }
+
+    /// Read the selected message item in the list aloud, with its sender.
+    fn speak_selected(&self) {
+        let Some(selected) = self.message_list_state.selected() else {
+            return;
+        };
+        let message = &self.message_list[selected];
+        let text = format!(
+            "{}: {}",
+            message.sender_name(&self.app_context),
+            message.message_content_to_string()
+        );
+        if let Some(action_tx) = self.action_tx.as_ref() {
+            action_tx.send(Action::Speak(text)).unwrap();
+        }
+    }
+
+    /// Add the selected audio message item in the list to the playlist.
+    fn queue_audio_selected(&self) {
+        let Some(selected) = self.message_list_state.selected() else {
+            return;
+        };
+        let message = &self.message_list[selected];
+        let action = match message.audio() {
+            Some(audio) => Action::QueueAudio(AudioTrack {
+                chat_id: self.app_context.tg_context().open_chat_id(),
+                message_id: message.id(),
+                metadata: audio.clone(),
+            }),
+            None => Action::ShowPopup(Popup::Info(
+                "Audio player".to_string(),
+                "Only the audio and voice messages can be added to the playlist.".to_string(),
+            )),
+        };
+        if let Some(action_tx) = self.action_tx.as_ref() {
+            action_tx.send(action).unwrap();
+        }
+    }
+
+    /// Select the first button of the inline keyboard of the selected message
+    /// item in the list, if it has one.
+    fn select_buttons(&mut self) {
+        let has_buttons = self
+            .message_list_state
+            .selected()
+            .and_then(|i| self.message_list.get(i))
+            .is_some_and(|message_entry| !message_entry.inline_keyboard().is_empty());
+        if has_buttons {
+            self.button_cursor = Some((0, 0));
+        }
+    }
+
+    /// Handle a key pressed while the buttons of the selected message item
+    /// are selected. The selected button is pressed with `enter`.
+    ///
+    /// # Arguments
+    /// * `key_code` - The pressed key.
+    fn navigate_buttons(&mut self, key_code: KeyCode) {
+        let (Some(cursor), Some(selected)) =
+            (self.button_cursor, self.message_list_state.selected())
+        else {
+            return;
+        };
+        let Some(message_entry) = self.message_list.get(selected) else {
+            return;
+        };
+        let direction = match key_code {
+            KeyCode::Left | KeyCode::Char('h') => GridMove::Left,
+            KeyCode::Right | KeyCode::Char('l') => GridMove::Right,
+            KeyCode::Up | KeyCode::Char('k') => GridMove::Up,
+            KeyCode::Down | KeyCode::Char('j') => GridMove::Down,
+            KeyCode::Enter => {
+                let (row, column) = cursor;
+                self.button_cursor = None;
+                if let Some(action_tx) = self.action_tx.as_ref() {
+                    action_tx
+                        .send(Action::PressInlineButton(message_entry.id(), row, column))
+                        .unwrap();
+                }
+                return;
+            }
+            _ => return,
+        };
+        self.button_cursor = Some(move_cursor(
+            message_entry.inline_keyboard(),
+            cursor,
+            direction,
+        ));
+    }
+
+    /// Edit the local note of the open chat in an input popup.
+    fn edit_note(&mut self) {
+        let chat_id = self.app_context.tg_context().open_chat_id();
+        if chat_id == 0 {
+            return;
+        }
+        let note = self
+            .app_context
+            .local_state()
+            .chat_note(chat_id)
+            .unwrap_or_default()
+            .to_string();
+        // The note is shown again, since it is being edited.
+        self.show_note = true;
+        if let Some(action_tx) = self.action_tx.as_ref() {
+            action_tx
+                .send(Action::ShowPopup(Popup::Input(
+                    "Note of the chat (empty to remove it)".to_string(),
+                    Box::new(Action::SetChatNote(chat_id, note)),
+                )))
+                .unwrap();
+        }
+    }
+
+    /// Ask for a date to jump to in the history of the open chat.
+    fn jump_to_date(&self) {
+        if self.app_context.tg_context().open_chat_id() == 0 {
+            return;
+        }
+        if let Some(action_tx) = self.action_tx.as_ref() {
+            action_tx
+                .send(Action::ShowPopup(Popup::Input(
+                    "Go to date (2024-05-01 or 2024-05-01 14:30)".to_string(),
+                    Box::new(Action::JumpToDate(String::new())),
+                )))
+                .unwrap();
+        }
+    }
+
+    /// Select a part of the selected message item in the list to quote in a
+    /// reply.
+    fn quote_reply_selected(&self) {
+        if let Some(selected) = self.message_list_state.selected() {
+            let message_id = self.message_list[selected].id();
+            let text = self.message_list[selected].message_content_to_string();
+            if let Some(action_tx) = self.action_tx.as_ref() {
+                action_tx
+                    .send(Action::ShowPopup(Popup::Select(
+                        "Select the text to quote (shift+arrows)".to_string(),
+                        text,
+                        Box::new(Action::QuoteReplyMessage(message_id, 0, String::new())),
+                    )))
                     .unwrap();
             }
         }
     }
+
+    /// Mark the unread incoming messages of the open chat that are shown as
+    /// read, once the list is drawn.
+    ///
+    /// # Arguments
+    /// * `preview_chat_id` - The chat being previewed, or 0.
+    fn view_shown_messages(&mut self, preview_chat_id: i64) {
+        let tg_context = self.app_context.tg_context();
+        let chat_id = tg_context.open_chat_id();
+        if preview_chat_id != 0 || chat_id == 0 {
+            return;
+        }
+        let me = tg_context.me();
+        let mut last_read = tg_context.last_read_inbox_message_id();
+        if self.viewed.0 == chat_id {
+            last_read = last_read.max(self.viewed.1);
+        }
+        let mut rows = 0;
+        let message_ids: Vec<i64> = self
+            .message_list
+            .iter()
+            .zip(&self.item_heights)
+            .skip(self.message_list_state.offset())
+            .take_while(|(_, height)| {
+                let shown = rows < self.list_height;
+                rows += **height;
+                shown
+            })
+            .filter(|(message_entry, _)| {
+                message_entry.sender_id() != me && message_entry.id() > last_read
+            })
+            .map(|(message_entry, _)| message_entry.id())
+            .collect();
+        let Some(newest) = message_ids.iter().max().copied() else {
+            return;
+        };
+        self.viewed = (chat_id, newest);
+        if let Err(e) = self
+            .app_context
+            .action_tx()
+            .send(Action::ViewMessages(message_ids))
+        {
+            tracing::error!("Failed to mark the messages as read: {}", e);
+        }
+    }
+
+    /// Draw the images of the message items over the empty lines reserved at
+    /// their end, once the list is drawn.
+    ///
+    /// # Arguments
+    /// * `frame` - The frame to draw on.
+    /// * `area` - The area of the list, with its borders.
+    /// * `images` - The images of the message items, in the order of the
+    ///   items.
+    fn draw_images(
+        &mut self,
+        frame: &mut ratatui::Frame<'_>,
+        area: Rect,
+        images: &[Option<ItemImage>],
+    ) {
+        // The list has no bottom border and is drawn from the bottom, from
+        // the item at its offset, with the items that fit entirely.
+        let inner = Rect {
+            x: area.x + 1,
+            y: area.y + 1,
+            width: area.width.saturating_sub(2),
+            height: area.height.saturating_sub(1),
+        };
+        let mut bottom = inner.bottom();
+        let mut shown = vec![];
+        for (i, height) in self
+            .item_heights
+            .iter()
+            .enumerate()
+            .skip(self.message_list_state.offset())
+        {
+            let height = *height as u16;
+            if bottom < inner.y + height {
+                break;
+            }
+            bottom -= height;
+            let Some((file_id, (width, rows), myself)) = images.get(i).copied().flatten() else {
+                continue;
+            };
+            if !self.image_protocols.contains_key(&file_id) {
+                let Some(ImageState::Ready(image)) =
+                    self.app_context.images().get(file_id).cloned()
+                else {
+                    continue;
+                };
+                self.image_protocols
+                    .insert(file_id, self.picker.new_resize_protocol(image));
+            }
+            let x = if myself {
+                inner.right().saturating_sub(width)
+            } else {
+                inner.x
+            };
+            let image_area = Rect::new(x, bottom + height - rows, width, rows);
+            if let Some(protocol) = self.image_protocols.get_mut(&file_id) {
+                frame.render_stateful_widget(StatefulImage::new(None), image_area, protocol);
+                shown.push(file_id);
+            }
+        }
+        // The images scrolled out of the chat are encoded again when they
+        // are shown again, for the size of their area.
+        self.image_protocols
+            .retain(|file_id, _| shown.contains(file_id));
+    }
 }
 
 /// Implement the `HandleFocus` trait for the `ChatWindow` struct.
@@ -188,6 +984,8 @@ impl HandleFocus for ChatWindow {
     /// Set the `focused` flag for the `ChatWindow`.
     fn unfocus(&mut self) {
         self.focused = false;
+        self.search_typing = false;
+        self.button_cursor = None;
     }
 }
 
@@ -198,16 +996,59 @@ impl Component for ChatWindow {
         Ok(())
     }
 
+    fn is_typing(&self) -> bool {
+        self.search_typing || self.button_cursor.is_some()
+    }
+
+    fn has_selection(&self) -> bool {
+        self.message_list_state.selected().is_some()
+    }
+
     fn update(&mut self, action: Action) {
         match action {
+            Action::Key(key_code, modifiers) if self.search_typing => {
+                self.type_search(key_code, modifiers)
+            }
+            Action::UnfocusComponent if self.search_typing => self.cancel_search(),
+            Action::Key(key_code, _) if self.button_cursor.is_some() => {
+                self.navigate_buttons(key_code)
+            }
+            Action::UnfocusComponent if self.button_cursor.is_some() => self.button_cursor = None,
+            Action::ChatWindowSearch => self.start_search(),
+            Action::ChatWindowSearchNext => self.search_next(true),
+            Action::ChatWindowSearchPrevious => self.search_next(false),
+            Action::ChatWindowToggleCompact => self.compact = !self.compact,
+            Action::ChatWindowEditNote => self.edit_note(),
+            Action::ChatWindowToggleNote => self.show_note = !self.show_note,
+            Action::ChatWindowToggleMark => self.toggle_mark_selected(),
+            Action::ChatWindowForward => self.forward_selected(),
             Action::ChatWindowNext => self.next(),
             Action::ChatWindowPrevious => self.previous(),
+            Action::ChatWindowFirst => self.first(),
+            Action::ChatWindowLast => self.last(),
+            Action::ChatWindowHalfPageDown => self.half_page_down(),
+            Action::ChatWindowHalfPageUp => self.half_page_up(),
             Action::ChatWindowUnselect => self.unselect(),
             Action::ChatWindowDeleteForEveryone => self.delete_selected(true),
             Action::ChatWindowDeleteForMe => self.delete_selected(false),
             Action::ChatWindowCopy => self.copy_selected(),
             Action::ChatWindowEdit => self.edit_selected(),
-            Action::ShowChatWindowReply => self.reply_selected(),
+            // The reply bar is also shown for a quote or a draft, once the
+            // prompt is focused.
+            Action::ShowChatWindowReply if self.focused => self.reply_selected(),
+            Action::ChatWindowQuoteReply => self.quote_reply_selected(),
+            Action::ChatWindowOpenOrigin => self.open_origin_selected(),
+            Action::ChatWindowQueueAudio => self.queue_audio_selected(),
+            Action::ChatWindowShowDetails => self.show_details_selected(),
+            Action::ChatWindowModerate => self.moderate_selected(),
+            Action::ChatWindowPin => self.pin_selected(),
+            Action::ChatWindowShowProfile => self.show_profile_selected(),
+            Action::ChatWindowSpeak => self.speak_selected(),
+            Action::ChatWindowOpenMedia => self.open_media_selected(),
+            Action::ChatWindowReact => self.react_selected(),
+            Action::ChatWindowButtons => self.select_buttons(),
+            Action::ChatWindowOpenComments => self.open_comments_selected(),
+            Action::ChatWindowJumpToDate => self.jump_to_date(),
             _ => {}
         }
     }
@@ -216,13 +1057,139 @@ impl Component for ChatWindow {
         if !self.focused {
             self.message_list_state.select(None);
         }
+        self.step_scroll();
 
-        self.message_list
-            .clone_from(&self.app_context.tg_context().open_chat_messages());
+        // The selected message is kept selected when messages are inserted
+        // or dropped below it, for example those received while offline or
+        // dropped to limit the memory, so that the viewport does not jump.
+        let selected = self.message_list_state.selected().and_then(|i| {
+            self.message_list
+                .get(i)
+                .map(|message_entry| (i, message_entry.id()))
+        });
+
+        // The chat highlighted in the chat list is previewed, if any.
+        let preview_chat_id = self.app_context.tg_context().preview_chat_id();
+        if preview_chat_id != 0 {
+            self.message_list
+                .clone_from(&self.app_context.tg_context().preview_chat_messages());
+        } else {
+            self.message_list
+                .clone_from(&self.app_context.tg_context().open_chat_messages());
+        }
+        if let Some((old_index, message_id)) = selected {
+            if let Some(new_index) = self.message_list.iter().position(|m| m.id() == message_id) {
+                let offset = self.message_list_state.offset_mut();
+                if new_index > old_index {
+                    *offset += new_index - old_index;
+                } else {
+                    *offset = offset.saturating_sub(old_index - new_index);
+                }
+                self.message_list_state.select(Some(new_index));
+            }
+        }
+        // The message jumped to, like a result of the global search, is
+        // selected once it is loaded.
+        let jump_message_id = self.app_context.tg_context().jump_message_id();
+        if jump_message_id != 0 && preview_chat_id == 0 {
+            if let Some(i) = self
+                .message_list
+                .iter()
+                .position(|m| m.id() == jump_message_id)
+            {
+                self.scroll_target = None;
+                self.message_list_state.select(Some(i));
+                self.app_context.tg_context().set_jump_message_id(0);
+            }
+        }
+        // The buttons are no longer selected once the selected message has
+        // none, for example when the keyboard is removed by its bot.
+        let button_message_id = self
+            .message_list_state
+            .selected()
+            .and_then(|i| self.message_list.get(i))
+            .filter(|message_entry| !message_entry.inline_keyboard().is_empty())
+            .map(MessageEntry::id);
+        if button_message_id.is_none() {
+            self.button_cursor = None;
+        }
+        // The selected message of the open chat is not dropped to limit the
+        // memory.
+        let selected_message_id = self
+            .message_list_state
+            .selected()
+            .filter(|_| preview_chat_id == 0)
+            .and_then(|i| self.message_list.get(i))
+            .map_or(0, MessageEntry::id);
+        self.app_context
+            .tg_context()
+            .set_selected_message_id(selected_message_id);
+        // The marks of the messages no longer shown, like those of another
+        // chat, are dropped.
+        let message_list = &self.message_list;
+        self.marked.retain(|id| {
+            message_list
+                .iter()
+                .any(|message_entry| message_entry.id() == *id)
+        });
+        let offline_gap = (*self.app_context.tg_context().offline_gap()).filter(|gap| {
+            preview_chat_id == 0 && gap.chat_id == self.app_context.tg_context().open_chat_id()
+        });
+
+        // The note of the previewed chat is shown while previewing.
+        let note_chat_id = if preview_chat_id != 0 {
+            preview_chat_id
+        } else {
+            self.app_context.tg_context().open_chat_id()
+        };
+        let note = self
+            .app_context
+            .local_state()
+            .chat_note(note_chat_id)
+            .filter(|_| self.show_note)
+            .map(str::to_string);
+        // The borders are not part of the width of the note, and the top
+        // border is part of its height.
+        let note_height = note.as_ref().map_or(0, |note| {
+            let width = area.width.saturating_sub(2).max(1) as usize;
+            display_width(note).div_ceil(width).clamp(1, MAX_NOTE_LINES) as u16 + 1
+        });
+
+        // The pinned bar shows the most recently pinned message of the open
+        // chat, with who pinned it once the message pinning it is loaded.
+        let pinned = self
+            .app_context
+            .tg_context()
+            .pinned_message()
+            .clone()
+            .filter(|_| preview_chat_id == 0)
+            .map(|pinned| {
+                let pinned_by = self
+                    .message_list
+                    .iter()
+                    .find(|message_entry| message_entry.pinned_message_id() == Some(pinned.id()))
+                    .map(|message_entry| message_entry.sender_name(&self.app_context));
+                let text = pinned
+                    .message_content_to_string()
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                (
+                    format!("{}: {}", pinned.sender_name(&self.app_context), text),
+                    pinned_by,
+                )
+            });
+        let pinned_height = if pinned.is_some() { 2 } else { 0 };
 
         let chat_layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(2), Constraint::Percentage(100)])
+            .constraints([
+                Constraint::Min(2),
+                Constraint::Length(pinned_height),
+                Constraint::Length(note_height),
+                Constraint::Percentage(100),
+            ])
             .split(area);
 
         let border = Set {
@@ -237,54 +1204,247 @@ impl Component for ChatWindow {
             self.app_context.style_chat()
         };
 
-        let mut is_unread_outbox = true;
-        let mut is_unread_inbox = true;
+        let last_read_outbox_message_id =
+            self.app_context.tg_context().last_read_outbox_message_id();
+        // The unread separator is shown above the oldest incoming message
+        // that was unread when the chat was opened.
+        let me = self.app_context.tg_context().me();
+        let unread_boundary = self.app_context.tg_context().unread_boundary_message_id();
+        let first_unread_id = self
+            .message_list
+            .iter()
+            .filter(|message_entry| {
+                preview_chat_id == 0
+                    && unread_boundary != 0
+                    && message_entry.sender_id() != me
+                    && message_entry.id() > unread_boundary
+            })
+            .map(MessageEntry::id)
+            .min();
         let wrap_width = (area.width / 2) as i32;
-        let items = self.message_list.iter().map(|message_entry| {
-            let (myself, name_style, content_style, alignment) = if message_entry.sender_id()
-                == self.app_context.tg_context().me()
-            {
-                if message_entry.id() == self.app_context.tg_context().last_read_outbox_message_id()
-                {
-                    is_unread_outbox = false;
-                }
-                (
-                    true,
-                    self.app_context.style_chat_message_myself_name(),
-                    self.app_context.style_chat_message_myself_content(),
-                    Alignment::Right,
-                )
-            } else {
-                if message_entry.id() == self.app_context.tg_context().last_read_inbox_message_id()
-                {
-                    is_unread_inbox = false;
-                }
-                (
-                    false,
-                    self.app_context.style_chat_message_other_name(),
-                    self.app_context.style_chat_message_other_content(),
-                    Alignment::Left,
-                )
-            };
-            return ListItem::new(
-                message_entry
-                    .get_text_styled(
-                        myself,
+        // The messages sent by a sender shortly after the previous one are
+        // shown under its header, unless they start the offline gap.
+        let group_minutes = self.app_context.app_config().group_messages_minutes;
+        let grouped = self
+            .message_list
+            .iter()
+            .enumerate()
+            .map(|(i, message_entry)| {
+                is_grouped(self.message_list.get(i + 1), message_entry, group_minutes)
+                    && offline_gap.map(|gap| gap.first_message_id) != Some(message_entry.id())
+                    && first_unread_id != Some(message_entry.id())
+            })
+            .collect::<Vec<bool>>();
+        let show_images = self.app_context.app_config().show_images && !self.compact;
+        let image_max_size = (IMAGE_MAX_WIDTH.min(wrap_width as u16), IMAGE_MAX_HEIGHT);
+        let font_size = self.picker.font_size;
+        // The images drawn in the message items, with their size in cells
+        // and whether they are aligned on the right.
+        let mut images: Vec<Option<ItemImage>> = vec![];
+        // The progress of the audio message of the open chat being played,
+        // shown under it.
+        let playing = {
+            let audio_player = self.app_context.audio_player();
+            audio_player
+                .current()
+                .filter(|(_, _, track)| {
+                    track.chat_id == self.app_context.tg_context().open_chat_id()
+                })
+                .map(|(_, _, track)| {
+                    (
+                        track.message_id,
+                        audio_player::progress_bar(
+                            audio_player.position(),
+                            track.metadata.duration,
+                            audio_player.is_paused(),
+                        ),
+                    )
+                })
+        };
+        let items = self
+            .message_list
+            .iter()
+            .zip(grouped)
+            .map(|(message_entry, grouped)| {
+                let (myself, name_style, content_style, alignment) =
+                    if message_entry.sender_id() == me {
+                        (
+                            true,
+                            self.app_context.style_chat_message_myself_name(),
+                            self.app_context.style_chat_message_myself_content(),
+                            Alignment::Right,
+                        )
+                    } else {
+                        (
+                            false,
+                            self.app_context.style_chat_message_other_name(),
+                            self.app_context.style_chat_message_other_content(),
+                            Alignment::Left,
+                        )
+                    };
+                let mut text = if self.compact {
+                    // The border is not part of the width.
+                    let width = area.width.saturating_sub(2) as i32;
+                    message_entry.get_compact_text_styled(
                         &self.app_context,
-                        is_unread_outbox,
                         name_style,
                         content_style,
-                        wrap_width,
+                        width,
+                        !grouped,
                     )
-                    .alignment(alignment),
-            );
-        });
+                } else {
+                    message_entry
+                        .get_text_styled(
+                            myself,
+                            &self.app_context,
+                            message_entry.id() > last_read_outbox_message_id,
+                            name_style,
+                            content_style,
+                            wrap_width,
+                            !grouped,
+                        )
+                        .alignment(alignment)
+                };
+                // The buttons are shown under the text, since the image is
+                // drawn over the last lines of the message item.
+                let cursor = self
+                    .button_cursor
+                    .filter(|_| button_message_id == Some(message_entry.id()));
+                text.lines.extend(inline_keyboard_lines(
+                    message_entry.inline_keyboard(),
+                    cursor,
+                    content_style,
+                    self.app_context.style_item_selected(),
+                ));
+                let mut image = None;
+                if let Some(file_id) = message_entry.image_file_id().filter(|_| show_images) {
+                    let mut image_cache = self.app_context.images();
+                    match image_cache.get(file_id) {
+                        Some(ImageState::Ready(ready)) => {
+                            // The image is drawn over empty lines at the end
+                            // of the message item, once the list is drawn.
+                            let size = image_size(ready, font_size, image_max_size);
+                            text.lines.extend((0..size.1).map(|_| Line::default()));
+                            image = Some((file_id, size, myself));
+                        }
+                        Some(ImageState::Loading) => text.lines.push(Line::from(Span::styled(
+                            "🖼 Loading the image…",
+                            self.app_context.style_timestamp(),
+                        ))),
+                        Some(ImageState::Failed) => {}
+                        None => {
+                            image_cache.insert(file_id, ImageState::Loading);
+                            if let Err(e) = self
+                                .app_context
+                                .action_tx()
+                                .send(Action::LoadImage(file_id))
+                            {
+                                tracing::error!("Failed to load the image {}: {}", file_id, e);
+                            }
+                        }
+                    }
+                }
+                images.push(image);
+                if let Some((_, progress)) = playing
+                    .as_ref()
+                    .filter(|(message_id, _)| *message_id == message_entry.id())
+                {
+                    text.lines.push(Line::from(Span::styled(
+                        progress.clone(),
+                        self.app_context.style_timestamp(),
+                    )));
+                }
+                if let Some(gap) =
+                    offline_gap.filter(|gap| gap.first_message_id == message_entry.id())
+                {
+                    text.lines.insert(
+                        0,
+                        divider_line(&gap.text(), self.app_context.style_timestamp()),
+                    );
+                }
+                if first_unread_id == Some(message_entry.id()) {
+                    text.lines.insert(
+                        0,
+                        divider_line("unread", self.app_context.style_timestamp()),
+                    );
+                }
+                if self.is_search_match(message_entry) {
+                    highlight_matches(&mut text, &self.search_query);
+                }
+                if self.marked.contains(&message_entry.id()) {
+                    if let Some(line) = text.lines.first_mut() {
+                        line.spans.insert(
+                            0,
+                            Span::styled("✔ ", self.app_context.style_item_selected()),
+                        );
+                    }
+                }
+                ListItem::new(text)
+            });
+        let mut items: Vec<ListItem> = items.collect();
+        self.item_heights = items.iter().map(ListItem::height).collect();
+        // A row above the oldest message shows that older messages are
+        // loaded, or that the start of the chat is reached. It is not a
+        // message item, so it is not part of the heights.
+        if preview_chat_id == 0 && !self.message_list.is_empty() {
+            let tg_context = self.app_context.tg_context();
+            let history_pages = tg_context.history_pages();
+            if history_pages.is_loading() {
+                items.push(ListItem::new(divider_line(
+                    &format!("{} Loading older messages", loading_frame()),
+                    self.app_context.style_timestamp(),
+                )));
+            } else if history_pages.is_at_oldest() {
+                items.push(ListItem::new(divider_line(
+                    "start of the chat",
+                    self.app_context.style_timestamp(),
+                )));
+            }
+        }
+        // The top border is not part of the list.
+        self.list_height = chat_layout[3].height.saturating_sub(1) as usize;
 
-        let block = Block::new()
+        let mut block = Block::new()
             .border_set(border)
             .border_style(style_border_focused)
             .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT)
             .style(self.app_context.style_chat());
+        if self.search_typing || !self.search_query.is_empty() {
+            let matches = self
+                .message_list
+                .iter()
+                .filter(|message_entry| self.is_search_match(message_entry))
+                .count();
+            let matches = match matches {
+                0 => "no match".to_string(),
+                1 => "1 match".to_string(),
+                n => format!("{} matches", n),
+            };
+            // The position of the selected message in the results of the
+            // search of the chat, which includes the messages not loaded.
+            let results = self.app_context.tg_context().chat_search_results().clone();
+            let matches = if results.is_empty() {
+                matches
+            } else {
+                let position = self
+                    .message_list_state
+                    .selected()
+                    .and_then(|i| self.message_list.get(i))
+                    .and_then(|m| results.iter().position(|result| *result == m.id()));
+                match position {
+                    Some(position) => format!("{}/{} in the chat", position + 1, results.len()),
+                    None => format!("{} in the chat", results.len()),
+                }
+            };
+            let cursor = if self.search_typing { "▏" } else { "" };
+            block = block.title(
+                Line::from(Span::styled(
+                    format!(" /{}{} ({}) ", self.search_query, cursor, matches),
+                    self.app_context.style_timestamp(),
+                ))
+                .alignment(Alignment::Right),
+            );
+        }
         let list = List::new(items)
             .block(block)
             .style(self.app_context.style_chat())
@@ -303,17 +1463,32 @@ impl Component for ChatWindow {
             .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT)
             .style(self.app_context.style_chat())
             .title(self.name.as_str());
-        let header = Paragraph::new(Line::from(vec![
-            Span::styled(
+        let chat_name = if preview_chat_id != 0 {
+            format!(
+                "{} (preview)",
                 self.app_context
                     .tg_context()
-                    .name_of_open_chat_id()
-                    .unwrap_or_default(),
-                self.app_context.style_chat_chat_name(),
-            ),
+                    .name_from_chats(preview_chat_id)
+                    .unwrap_or_default()
+            )
+        } else {
+            self.app_context
+                .tg_context()
+                .name_of_open_chat_id()
+                .unwrap_or_default()
+        };
+        let header = Paragraph::new(Line::from(vec![
+            Span::styled(chat_name, self.app_context.style_chat_chat_name()),
             Span::raw(" "),
             Span::styled(
-                self.app_context.tg_context().open_chat_user_status(),
+                if preview_chat_id != 0 {
+                    String::new()
+                } else {
+                    let tg_context = self.app_context.tg_context();
+                    tg_context
+                        .typing_text(tg_context.open_chat_id())
+                        .unwrap_or_else(|| tg_context.open_chat_user_status())
+                },
                 self.app_context.style_timestamp(),
             ),
         ]))
@@ -321,8 +1496,143 @@ impl Component for ChatWindow {
         .alignment(Alignment::Center);
 
         frame.render_widget(header, chat_layout[0]);
-        frame.render_stateful_widget(list, chat_layout[1], &mut self.message_list_state);
+        if let Some((text, pinned_by)) = pinned {
+            let title = match pinned_by {
+                Some(name) => format!(" 📌 Pinned by {} ", name),
+                None => " 📌 Pinned ".to_string(),
+            };
+            let block_pinned = Block::new()
+                .border_set(border)
+                .border_style(style_border_focused)
+                .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT)
+                .style(self.app_context.style_chat())
+                .title(Span::styled(title, self.app_context.style_timestamp()));
+            let paragraph = Paragraph::new(text)
+                .block(block_pinned)
+                .style(self.app_context.style_chat());
+            frame.render_widget(paragraph, chat_layout[1]);
+        }
+        if let Some(note) = note {
+            let block_note = Block::new()
+                .border_set(border)
+                .border_style(style_border_focused)
+                .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT)
+                .style(self.app_context.style_chat())
+                .title(Span::styled(" Note ", self.app_context.style_timestamp()));
+            let paragraph = Paragraph::new(note)
+                .block(block_note)
+                .style(self.app_context.style_chat())
+                .wrap(Wrap { trim: true });
+            frame.render_widget(paragraph, chat_layout[2]);
+        }
+        frame.render_stateful_widget(list, chat_layout[3], &mut self.message_list_state);
+        self.draw_images(frame, chat_layout[3], &images);
+        self.view_shown_messages(preview_chat_id);
 
         Ok(())
     }
 }
+
+/// Get the frame of the spinner shown while older messages are loaded.
+///
+/// # Returns
+/// * `char` - The frame for the current time.
+fn loading_frame() -> char {
+    let frame = chrono::Utc::now().timestamp_millis() / LOADING_FRAME_MILLIS;
+    LOADING_FRAMES[frame.rem_euclid(LOADING_FRAMES.len() as i64) as usize]
+}
+
+/// Get the lines of the inline keyboard of a message item, a line per row of
+/// buttons.
+///
+/// # Arguments
+/// * `rows` - The rows of buttons.
+/// * `cursor` - The row and the column of the selected button, if any.
+/// * `style` - The style of the buttons.
+/// * `style_selected` - The style of the selected button.
+///
+/// # Returns
+/// * `Vec<Line<'static>>` - The lines of the buttons.
+fn inline_keyboard_lines(
+    rows: &[Vec<InlineButton>],
+    cursor: Option<(usize, usize)>,
+    style: Style,
+    style_selected: Style,
+) -> Vec<Line<'static>> {
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut line = Line::default();
+            for (j, button) in row.iter().enumerate() {
+                if j > 0 {
+                    line.spans.push(Span::raw(" "));
+                }
+                let style = if cursor == Some((i, j)) {
+                    style_selected
+                } else {
+                    style
+                };
+                line.spans
+                    .push(Span::styled(format!("[ {} ]", button.text), style));
+            }
+            line
+        })
+        .collect()
+}
+
+/// Highlight the occurrences of the searched text in a message item.
+///
+/// # Arguments
+/// * `text` - The text of the message item.
+/// * `query` - The searched text.
+fn highlight_matches(text: &mut Text<'_>, query: &str) {
+    for line in text.lines.iter_mut() {
+        let spans = std::mem::take(&mut line.spans);
+        for span in spans {
+            let ranges = find_ignore_case(&span.content, query);
+            if ranges.is_empty() {
+                line.spans.push(span);
+                continue;
+            }
+            let mut start = 0;
+            for range in ranges {
+                if range.start > start {
+                    line.spans.push(Span::styled(
+                        span.content[start..range.start].to_string(),
+                        span.style,
+                    ));
+                }
+                line.spans.push(Span::styled(
+                    span.content[range.clone()].to_string(),
+                    span.style.add_modifier(Modifier::REVERSED),
+                ));
+                start = range.end;
+            }
+            if start < span.content.len() {
+                line.spans
+                    .push(Span::styled(span.content[start..].to_string(), span.style));
+            }
+        }
+    }
+}
+
+/// Get the size of an image drawn inline, in cells, keeping its aspect ratio.
+///
+/// # Arguments
+/// * `image` - The image.
+/// * `font_size` - The size of a cell, in pixels.
+/// * `max_size` - The maximum width and height of the image, in cells.
+///
+/// # Returns
+/// * `(u16, u16)` - The width and the height of the image, in cells.
+fn image_size(image: &DynamicImage, font_size: (u16, u16), max_size: (u16, u16)) -> (u16, u16) {
+    let width = image.width() as f64 / font_size.0.max(1) as f64;
+    let height = image.height() as f64 / font_size.1.max(1) as f64;
+    let scale = (max_size.0 as f64 / width)
+        .min(max_size.1 as f64 / height)
+        .min(1.0);
+    (
+        ((width * scale).ceil() as u16).clamp(1, max_size.0.max(1)),
+        ((height * scale).ceil() as u16).clamp(1, max_size.1.max(1)),
+    )
+}
@@ -83,6 +83,23 @@ pub trait Component: HandleFocus {
     fn handle_mouse_events(&mut self, mouse: event::MouseEvent) -> io::Result<Option<Action>> {
         Ok(None)
     }
+    /// Check whether the component takes the typed keys as text, like a
+    /// search being typed, so that they are not handled as key bindings.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether the component takes the typed keys as text.
+    fn is_typing(&self) -> bool {
+        false
+    }
+    /// Check whether an item of the component is selected, like a message.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - Whether an item of the component is selected.
+    fn has_selection(&self) -> bool {
+        false
+    }
     /// Update the state of the component based on a received action. (REQUIRED)
     ///
     /// # Arguments
@@ -0,0 +1,326 @@
+use crate::{
+    action::{Action, Modifiers},
+    app_context::AppContext,
+    components::{
+        component_traits::{Component, HandleFocus},
+        popup_window::{Popup, PopupItem},
+    },
+};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    symbols::border::PLAIN,
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListState},
+    Frame,
+};
+use std::sync::Arc;
+use tdlib_rs::types::User;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// `ContactsWindow` is a struct that represents the contacts of the user. It
+/// is shown in place of the chat, and the contacts can be searched, added by
+/// phone number, deleted and chatted with.
+pub struct ContactsWindow {
+    /// The application context.
+    app_context: Arc<AppContext>,
+    /// The name of the `ContactsWindow`.
+    name: String,
+    /// An unbounded sender that send action for processing.
+    action_tx: Option<UnboundedSender<Action>>,
+    /// Indicates whether the `ContactsWindow` is focused or not.
+    focused: bool,
+    /// The text searched in the names, the usernames and the phone numbers
+    /// of the contacts.
+    query: String,
+    /// Indicates whether the search is being typed.
+    typing: bool,
+    /// The state of the list of the contacts.
+    list_state: ListState,
+}
+/// Implementation of the `ContactsWindow` struct.
+impl ContactsWindow {
+    /// Create a new instance of the `ContactsWindow` struct.
+    ///
+    /// # Arguments
+    /// * `app_context` - An Arc wrapped AppContext struct.
+    ///
+    /// # Returns
+    /// * `Self` - The new instance of the `ContactsWindow` struct.
+    pub fn new(app_context: Arc<AppContext>) -> Self {
+        let name = "".to_string();
+        let action_tx = None;
+        let focused = false;
+        let query = String::new();
+        let typing = false;
+        let list_state = ListState::default();
+
+        ContactsWindow {
+            app_context,
+            name,
+            action_tx,
+            focused,
+            query,
+            typing,
+            list_state,
+        }
+    }
+    /// Set the name of the `ContactsWindow`.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the `ContactsWindow`.
+    ///
+    /// # Returns
+    /// * `Self` - The modified instance of the `ContactsWindow`.
+    pub fn with_name(mut self, name: impl AsRef<str>) -> Self {
+        self.name = name.as_ref().to_string();
+        self
+    }
+    /// Show the contacts, loaded in the telegram context, from the first one
+    /// and without search.
+    fn show_contacts(&mut self) {
+        self.query.clear();
+        self.typing = false;
+        self.list_state.select(Some(0));
+    }
+    /// Get the contacts matching the search, in order.
+    ///
+    /// # Returns
+    /// * `Vec<User>` - The users of the contacts.
+    fn contacts(&self) -> Vec<User> {
+        let tg_context = self.app_context.tg_context();
+        let users = tg_context.users();
+        let query = self.query.trim().to_lowercase();
+        let contacts = tg_context.contacts();
+        contacts
+            .iter()
+            .filter_map(|user_id| users.get(user_id))
+            .filter(|user| {
+                let text = format!("{}{}", full_name(user), contact_details(user));
+                query.is_empty() || text.to_lowercase().contains(&query)
+            })
+            .cloned()
+            .collect()
+    }
+    /// Get the selected contact.
+    ///
+    /// # Returns
+    /// * `Option<User>` - The user of the contact, if any is selected.
+    fn selected_contact(&self) -> Option<User> {
+        let i = self.list_state.selected()?;
+        self.contacts().into_iter().nth(i)
+    }
+    /// Handle a key typed in the search. The contacts are filtered while the
+    /// search is typed.
+    ///
+    /// # Arguments
+    /// * `key_code` - The typed key.
+    /// * `modifiers` - The modifiers of the typed key.
+    fn type_search(&mut self, key_code: KeyCode, modifiers: Modifiers) {
+        match key_code {
+            KeyCode::Char(c) if !modifiers.control && !modifiers.alt => {
+                self.query.push(c);
+                self.list_state.select(Some(0));
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.list_state.select(Some(0));
+            }
+            KeyCode::Enter => self.typing = false,
+            _ => {}
+        }
+    }
+    /// Handle a key pressed in the list of the contacts.
+    ///
+    /// # Arguments
+    /// * `key_code` - The pressed key.
+    fn navigate(&mut self, key_code: KeyCode) {
+        let len = self.contacts().len();
+        match key_code {
+            KeyCode::Down | KeyCode::Char('j') if len > 0 => {
+                let i = self
+                    .list_state
+                    .selected()
+                    .map_or(0, |i| (i + 1).min(len - 1));
+                self.list_state.select(Some(i));
+            }
+            KeyCode::Up | KeyCode::Char('k') if len > 0 => {
+                let i = self
+                    .list_state
+                    .selected()
+                    .map_or(0, |i| i.saturating_sub(1));
+                self.list_state.select(Some(i));
+            }
+            KeyCode::Char('/') => self.typing = true,
+            KeyCode::Char('a') => self.add_contact(),
+            KeyCode::Char('d') => self.delete_selected(),
+            KeyCode::Enter => self.open_selected(),
+            _ => {}
+        }
+    }
+    /// Open the private chat with the selected contact.
+    fn open_selected(&self) {
+        if let Some(user) = self.selected_contact() {
+            self.app_context
+                .action_tx()
+                .send(Action::OpenContactChat(user.id))
+                .unwrap();
+        }
+    }
+    /// Ask for the phone number and the name of a new contact.
+    fn add_contact(&self) {
+        self.app_context
+            .action_tx()
+            .send(Action::ShowPopup(Popup::Input(
+                "Phone number and name, like +441234567890 Ada Lovelace".to_string(),
+                Box::new(Action::AddContact(String::new())),
+            )))
+            .unwrap();
+    }
+    /// Ask to confirm the deletion of the selected contact.
+    fn delete_selected(&self) {
+        if let Some(user) = self.selected_contact() {
+            self.app_context
+                .action_tx()
+                .send(Action::ShowPopup(Popup::List(
+                    format!("Delete {} from the contacts?", full_name(&user)),
+                    vec![
+                        PopupItem::new("Delete the contact", Action::DeleteContact(user.id)),
+                        PopupItem::new("Cancel", Action::HidePopup),
+                    ],
+                )))
+                .unwrap();
+        }
+    }
+}
+
+/// Get the full name of a user.
+///
+/// # Arguments
+/// * `user` - The user.
+///
+/// # Returns
+/// * `String` - The first name and the last name, if any.
+fn full_name(user: &User) -> String {
+    if user.last_name.is_empty() {
+        user.first_name.clone()
+    } else {
+        format!("{} {}", user.first_name, user.last_name)
+    }
+}
+
+/// Get the details a contact is listed and searched with after its name:
+/// its username and its phone number, if any.
+///
+/// # Arguments
+/// * `user` - The user of the contact.
+///
+/// # Returns
+/// * `String` - The details of the contact.
+fn contact_details(user: &User) -> String {
+    let mut details = String::new();
+    if let Some(username) = user
+        .usernames
+        .as_ref()
+        .and_then(|usernames| usernames.active_usernames.first())
+    {
+        details.push_str(&format!(" @{}", username));
+    }
+    if !user.phone_number.is_empty() {
+        details.push_str(&format!(" +{}", user.phone_number));
+    }
+    details
+}
+
+/// Implement the `HandleFocus` trait for the `ContactsWindow` struct.
+/// This trait allows the `ContactsWindow` to be focused or unfocused.
+impl HandleFocus for ContactsWindow {
+    /// Set the `focused` flag for the `ContactsWindow`.
+    fn focus(&mut self) {
+        self.focused = true;
+    }
+    /// Set the `focused` flag for the `ContactsWindow`.
+    fn unfocus(&mut self) {
+        self.focused = false;
+        self.typing = false;
+    }
+}
+
+/// Implement the `Component` trait for the `ContactsWindow` struct.
+impl Component for ContactsWindow {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> std::io::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn is_typing(&self) -> bool {
+        self.typing
+    }
+
+    fn has_selection(&self) -> bool {
+        self.list_state.selected().is_some()
+    }
+
+    fn update(&mut self, action: Action) {
+        match action {
+            Action::ShowContacts => self.show_contacts(),
+            Action::Key(key_code, modifiers) if self.typing => {
+                self.type_search(key_code, modifiers)
+            }
+            Action::Key(key_code, _) => self.navigate(key_code),
+            Action::UnfocusComponent if self.typing => self.typing = false,
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame<'_>, area: Rect) -> std::io::Result<()> {
+        let contacts = self.contacts();
+        // The selection stays on a contact when the list gets shorter, like
+        // after a deletion.
+        if let Some(i) = self.list_state.selected() {
+            if i >= contacts.len() {
+                self.list_state.select(contacts.len().checked_sub(1));
+            }
+        }
+        let items: Vec<Line> = contacts
+            .iter()
+            .map(|user| {
+                Line::from(vec![
+                    Span::styled(
+                        full_name(user),
+                        self.app_context.style_chat_list_item_chat_name(),
+                    ),
+                    Span::styled(
+                        contact_details(user),
+                        self.app_context.style_chat_list_item_message_content(),
+                    ),
+                ])
+            })
+            .collect();
+
+        let cursor = if self.typing { "▏" } else { "" };
+        let title = format!(
+            " {}: {}{} ({}) - a to add, d to delete ",
+            self.name,
+            self.query,
+            cursor,
+            contacts.len()
+        );
+        let style_border = if self.focused {
+            self.app_context.style_border_component_focused()
+        } else {
+            self.app_context.style_chat()
+        };
+        let block = Block::default()
+            .border_set(PLAIN)
+            .border_style(style_border)
+            .borders(Borders::ALL)
+            .title(title);
+        let list = List::new(items)
+            .block(block)
+            .style(self.app_context.style_chat())
+            .highlight_style(self.app_context.style_chat_list_item_selected());
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+        Ok(())
+    }
+}
@@ -7,18 +7,30 @@ use crate::{
         chat_list_window::ChatListWindow,
         chat_window::ChatWindow,
         component_traits::{Component, HandleFocus},
+        contacts_window::ContactsWindow,
+        members_window::MembersWindow,
+        popup_window::PopupWindow,
         prompt_window::PromptWindow,
+        search_results::SearchResults,
+        status_bar::FocusState,
     },
     components::{MAX_CHAT_LIST_SIZE, MAX_PROMPT_SIZE, MIN_CHAT_LIST_SIZE, MIN_PROMPT_SIZE},
-    configs::custom::keymap_custom::ActionBinding,
     event::Event,
+    key_sequence::{KeyOutcome, KeySequence},
 };
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Constraint, Direction, Layout, Position, Rect};
 use std::{collections::HashMap, io, sync::Arc};
 use tokio::sync::mpsc::UnboundedSender;
 
 use super::reply_message::ReplyMessage;
 
+/// The size of the chat list, in percent of the width, when it was never
+/// changed.
+const DEFAULT_CHAT_LIST_SIZE: u16 = 20;
+/// The width of the members panel, in columns.
+const MEMBERS_PANEL_WIDTH: u16 = 36;
+
 /// `CoreWindow` is a struct that represents the core window of the application.
 /// It is responsible for managing the layout and rendering of the core window.
 pub struct CoreWindow {
@@ -47,6 +59,30 @@ pub struct CoreWindow {
     focused: bool,
     /// Indicates whether the reply message should be shown.
     show_reply_message: bool,
+    /// Indicates whether the popup should be shown.
+    show_popup: bool,
+    /// The component shown in the area of the chat: the chat, or a list like
+    /// the results of the global search or the contacts.
+    chat_area: ComponentName,
+    /// Indicates whether the members of the open group are shown in a panel
+    /// next to the chat.
+    show_members: bool,
+    /// The component that had focus before the popup was shown. The focus is
+    /// given back to it when the popup is hidden.
+    component_focused_before_popup: Option<ComponentName>,
+    /// The keys typed in the focused component that are not yet resolved to
+    /// an action, like the first `g` of `gg` or a count.
+    key_sequence: KeySequence,
+    /// The state of the focused component last sent to the status bar.
+    focus_state: FocusState,
+    /// The areas where the components were last drawn, to find the one
+    /// clicked with the mouse.
+    areas: HashMap<ComponentName, Rect>,
+    /// The area where the `CoreWindow` was last drawn.
+    area: Rect,
+    /// Whether the border between the chat list and the chat is dragged
+    /// with the mouse.
+    resizing: bool,
 }
 
 impl CoreWindow {
@@ -83,6 +119,30 @@ impl CoreWindow {
                     .with_name(ComponentName::ReplyMessage.to_string())
                     .new_boxed(),
             ),
+            (
+                ComponentName::Popup,
+                PopupWindow::new(Arc::clone(&app_context))
+                    .with_name(ComponentName::Popup.to_string())
+                    .new_boxed(),
+            ),
+            (
+                ComponentName::SearchResults,
+                SearchResults::new(Arc::clone(&app_context))
+                    .with_name(ComponentName::SearchResults.to_string())
+                    .new_boxed(),
+            ),
+            (
+                ComponentName::Contacts,
+                ContactsWindow::new(Arc::clone(&app_context))
+                    .with_name(ComponentName::Contacts.to_string())
+                    .new_boxed(),
+            ),
+            (
+                ComponentName::Members,
+                MembersWindow::new(Arc::clone(&app_context))
+                    .with_name(ComponentName::Members.to_string())
+                    .new_boxed(),
+            ),
         ];
 
         let app_context = app_context;
@@ -92,11 +152,23 @@ impl CoreWindow {
             components_iter.into_iter().collect();
         let size_prompt = 3;
         let size_message_reply = 2;
-        let size_chat_list = 20;
+        let size_chat_list = app_context
+            .local_state()
+            .chat_list_size
+            .unwrap_or(DEFAULT_CHAT_LIST_SIZE);
         let small_area = false;
         let component_focused = None;
         let focused = true;
         let show_reply_message = false;
+        let show_popup = false;
+        let chat_area = ComponentName::Chat;
+        let show_members = false;
+        let component_focused_before_popup = None;
+        let key_sequence = KeySequence::default();
+        let focus_state = FocusState::default();
+        let areas = HashMap::new();
+        let area = Rect::default();
+        let resizing = false;
 
         CoreWindow {
             app_context,
@@ -110,6 +182,15 @@ impl CoreWindow {
             component_focused,
             focused,
             show_reply_message,
+            show_popup,
+            chat_area,
+            show_members,
+            component_focused_before_popup,
+            key_sequence,
+            focus_state,
+            areas,
+            area,
+            resizing,
         }
     }
     /// Set the name of the `CoreWindow`.
@@ -132,9 +213,92 @@ impl CoreWindow {
         self.small_area = small_area;
     }
 
+    /// Focus a component and unfocus all the others.
+    ///
+    /// # Arguments
+    /// * `component_name` - The name of the component to focus.
+    fn focus_component(&mut self, component_name: ComponentName) {
+        self.component_focused = Some(component_name);
+        self.components
+            .get_mut(&component_name)
+            .unwrap_or_else(|| panic!("Failed to get component: {}", component_name))
+            .focus();
+        self.components
+            .iter_mut()
+            .filter(|(name, _)| *name != &component_name)
+            .for_each(|(_, component)| component.unfocus());
+    }
+    /// Unfocus all the components.
+    fn unfocus_components(&mut self) {
+        self.component_focused = None;
+        for (_, component) in self.components.iter_mut() {
+            component.unfocus();
+        }
+    }
+    /// Hide the popup and give the focus back to the component that had it
+    /// before the popup was shown.
+    fn hide_popup(&mut self) {
+        self.show_popup = false;
+        match self.component_focused_before_popup.take() {
+            Some(component_name) => self.focus_component(component_name),
+            None => self.unfocus_components(),
+        }
+    }
+    /// Hide the members panel. The focus goes to the chat if the panel had
+    /// it.
+    fn hide_members(&mut self) {
+        self.show_members = false;
+        self.app_context.tg_context().set_members_chat_id(0);
+        if self.component_focused == Some(ComponentName::Members) {
+            self.focus_component(ComponentName::Chat);
+        }
+    }
+    /// Send the state of the focused component to the status bar, if it
+    /// changed, so that the status bar shows the key hints of the component.
+    fn send_focus_state(&mut self) {
+        let focused = self
+            .component_focused
+            .and_then(|component_name| self.components.get(&component_name));
+        let focus_state = FocusState {
+            component: self.component_focused,
+            has_selection: focused.is_some_and(|component| component.has_selection()),
+            is_typing: focused.is_some_and(|component| component.is_typing()),
+        };
+        if focus_state != self.focus_state {
+            self.focus_state = focus_state;
+            if let Some(action_tx) = self.action_tx.as_ref() {
+                action_tx
+                    .send(Action::UpdateFocusState(focus_state))
+                    .unwrap();
+            }
+        }
+    }
+    /// Check whether the focused component takes the typed keys as text.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the focused component takes the typed keys as text.
+    fn is_typing(&self) -> bool {
+        self.component_focused
+            .and_then(|component_name| self.components.get(&component_name))
+            .is_some_and(|component| component.is_typing())
+    }
+
     /// Toggle the chat list component.
     pub fn toggle_chat_list(&mut self) {
-        self.size_chat_list = if self.size_chat_list == 0 { 20 } else { 0 };
+        self.size_chat_list = if self.size_chat_list == 0 {
+            self.app_context
+                .local_state()
+                .chat_list_size
+                .unwrap_or(DEFAULT_CHAT_LIST_SIZE)
+        } else {
+            0
+        };
+    }
+    /// Save the size of the chat list, so that it is restored at the next
+    /// start.
+    fn save_chat_list_size(&self) {
+        self.app_context.local_state().chat_list_size = Some(self.size_chat_list);
+        self.app_context.save_local_state();
     }
     /// Increase the size of the chat list component.
     pub fn increase_chat_list_size(&mut self) {
@@ -142,6 +306,7 @@ impl CoreWindow {
             return;
         }
         self.size_chat_list += 1;
+        self.save_chat_list_size();
     }
     /// Increase the size of the chat list component.
     pub fn increase_size_prompt(&mut self) {
@@ -156,6 +321,7 @@ impl CoreWindow {
             return;
         }
         self.size_chat_list -= 1;
+        self.save_chat_list_size();
     }
     /// Decrease the size of the chat list component.
     pub fn decrease_size_prompt(&mut self) {
@@ -164,6 +330,77 @@ impl CoreWindow {
         }
         self.size_prompt -= 1;
     }
+    /// Check whether a position is on the border between the chat list and
+    /// the chat.
+    ///
+    /// # Arguments
+    /// * `column` - The column of the position.
+    /// * `row` - The row of the position.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the position is on the border.
+    fn is_on_divider(&self, column: u16, row: u16) -> bool {
+        let (Some(chat_list), Some(chat)) = (
+            self.areas.get(&ComponentName::ChatList),
+            self.areas.get(&self.chat_area),
+        ) else {
+            return false;
+        };
+        chat_list.width > 0
+            && row >= chat_list.y
+            && row < chat_list.bottom()
+            && (column + 1 == chat_list.right() || column == chat.x)
+    }
+    /// Focus the component clicked, or start or end dragging the border
+    /// between the chat list and the chat to resize them.
+    ///
+    /// # Arguments
+    /// * `mouse` - The mouse event.
+    ///
+    /// # Returns
+    /// * `Option<Action>` - The action to focus the clicked component, if
+    ///   any.
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> Option<Action> {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if !self.show_popup => {
+                if self.is_on_divider(mouse.column, mouse.row) {
+                    self.resizing = true;
+                    return None;
+                }
+                let position = Position::new(mouse.column, mouse.row);
+                [
+                    ComponentName::ChatList,
+                    ComponentName::Chat,
+                    ComponentName::SearchResults,
+                    ComponentName::Contacts,
+                    ComponentName::Members,
+                    ComponentName::Prompt,
+                ]
+                .into_iter()
+                .find(|name| {
+                    self.areas
+                        .get(name)
+                        .is_some_and(|area| area.contains(position))
+                })
+                .filter(|name| self.component_focused != Some(*name))
+                .map(Action::FocusComponent)
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.resizing => {
+                if self.area.width > 0 {
+                    let column = mouse.column.saturating_sub(self.area.x) + 1;
+                    let size = (u32::from(column) * 100 / u32::from(self.area.width)) as u16;
+                    self.size_chat_list = size.clamp(MIN_CHAT_LIST_SIZE, MAX_CHAT_LIST_SIZE);
+                }
+                None
+            }
+            MouseEventKind::Up(MouseButton::Left) if self.resizing => {
+                self.resizing = false;
+                self.save_chat_list_size();
+                None
+            }
+            _ => None,
+        }
+    }
 }
 /// Implement the `HandleFocus` trait for the `CoreWindow` struct.
 /// This trait allows the `CoreWindow` to be focused or unfocused.
@@ -189,41 +426,113 @@ impl Component for CoreWindow {
     }
 
     fn handle_events(&mut self, event: Option<Event>) -> Result<Option<Action>, AppError<Action>> {
+        let event = event.unwrap();
+        if let Event::Mouse(mouse) = event {
+            return Ok(Some(self.handle_mouse(mouse).unwrap_or(Action::Unknown)));
+        }
+        if !matches!(event, Event::Key(..)) {
+            return Ok(Some(Action::Unknown));
+        }
+        if self.is_typing() {
+            self.key_sequence.reset();
+            return Ok(Some(Action::Unknown));
+        }
         let binding = self.app_context.keymap_config();
         let map = binding.get_map_of(self.component_focused);
-        if let Some(action_binding) = map.get(&event.unwrap()) {
-            match action_binding {
-                ActionBinding::Single { action, .. } => {
-                    return Ok(Some(action.clone()));
-                }
-                ActionBinding::Multiple(_map_event_action) => {
-                    tracing::warn!("Multiple action bindings are not supported yet. They are supported only for default key bindings because there are not the app context to handle them here.");
-                    todo!();
+        // The digits start a count only in the lists, since they are text in
+        // the prompt.
+        let allow_count = matches!(
+            self.component_focused,
+            Some(ComponentName::ChatList | ComponentName::Chat)
+        );
+        match self.key_sequence.feed(&event, map, allow_count) {
+            KeyOutcome::Action(action, count) => {
+                if action.is_repeatable() {
+                    if let Some(action_tx) = self.action_tx.as_ref() {
+                        for _ in 1..count {
+                            action_tx.send(action.clone()).unwrap();
+                        }
+                    }
                 }
+                Ok(Some(action))
             }
+            KeyOutcome::Pending => Ok(None),
+            KeyOutcome::Unbound => Ok(Some(Action::Unknown)),
         }
-        Ok(Some(Action::Unknown))
     }
 
     fn update(&mut self, action: Action) {
+        // The selection of the components changes also when they are drawn,
+        // so the state is checked at each action, like the render ones.
+        self.send_focus_state();
         match action {
             Action::FocusComponent(component_name) => {
-                self.component_focused = Some(component_name);
-                self.components
-                    .get_mut(&component_name)
-                    .unwrap_or_else(|| panic!("Failed to get component: {}", component_name))
-                    .focus();
-                self.components
-                    .iter_mut()
-                    .filter(|(name, _)| *name != &component_name)
-                    .for_each(|(_, component)| component.unfocus());
+                if component_name == ComponentName::Members {
+                    self.show_members = true;
+                } else if component_name != ComponentName::Popup {
+                    self.show_popup = false;
+                    self.component_focused_before_popup = None;
+                    self.chat_area = match component_name {
+                        ComponentName::SearchResults | ComponentName::Contacts => component_name,
+                        _ => ComponentName::Chat,
+                    };
+                }
+                self.focus_component(component_name);
+            }
+            Action::ChatListSearch | Action::ShowMentions => {
+                self.chat_area = ComponentName::SearchResults;
+                self.focus_component(ComponentName::SearchResults);
+            }
+            Action::ShowContacts => {
+                self.chat_area = ComponentName::Contacts;
+                self.focus_component(ComponentName::Contacts);
+            }
+            // The members are loaded and focused by the handler of the
+            // action, unless the panel of the open group is shown already.
+            Action::ShowMembers => {
+                let tg_context = self.app_context.tg_context();
+                if self.show_members && tg_context.members_chat_id() == tg_context.open_chat_id() {
+                    self.hide_members();
+                }
             }
             Action::UnfocusComponent => {
-                self.component_focused = None;
-                self.show_reply_message = false;
-                for (_, component) in self.components.iter_mut() {
-                    component.unfocus();
+                if self.show_popup {
+                    self.hide_popup();
+                    return;
                 }
+                // The results of the search or the contacts are closed, back
+                // to the chat list.
+                if matches!(
+                    self.component_focused,
+                    Some(ComponentName::SearchResults | ComponentName::Contacts)
+                ) && !self.is_typing()
+                {
+                    self.chat_area = ComponentName::Chat;
+                    self.focus_component(ComponentName::ChatList);
+                    return;
+                }
+                if self.component_focused == Some(ComponentName::Members) {
+                    self.hide_members();
+                    return;
+                }
+                // The component cancels what is typed in it, like a search.
+                if !self.is_typing() {
+                    self.show_reply_message = false;
+                    self.unfocus_components();
+                }
+            }
+            Action::ShowPopup(_) => {
+                if !self.show_popup {
+                    self.component_focused_before_popup = self.component_focused;
+                }
+                self.show_popup = true;
+                self.focus_component(ComponentName::Popup);
+            }
+            Action::HidePopup => {
+                if self.show_popup {
+                    self.hide_popup();
+                }
+                return;
             }
             Action::ToggleChatList => {
                 self.toggle_chat_list();
@@ -240,14 +549,16 @@ impl Component for CoreWindow {
             Action::DecreasePromptSize => {
                 self.decrease_size_prompt();
             }
-            Action::TryQuit => {
-                if self.component_focused != Some(ComponentName::Prompt) {
-                    self.action_tx
-                        .as_ref()
-                        .unwrap_or_else(|| panic!("Failed to get action_tx on CoreWindow"))
-                        .send(Action::Quit)
-                        .unwrap_or_else(|_| panic!("Failed to send action Quit from CoreWindow"));
-                }
+            Action::TryQuit
+                if self.component_focused != Some(ComponentName::Prompt)
+                    && self.component_focused != Some(ComponentName::Popup)
+                    && !self.is_typing() =>
+            {
+                self.action_tx
+                    .as_ref()
+                    .unwrap_or_else(|| panic!("Failed to get action_tx on CoreWindow"))
+                    .send(Action::Quit)
+                    .unwrap_or_else(|_| panic!("Failed to send action Quit from CoreWindow"));
             }
             Action::ShowChatWindowReply => {
                 self.show_reply_message = true;
@@ -267,6 +578,7 @@ impl Component for CoreWindow {
     }
 
     fn draw(&mut self, frame: &mut ratatui::Frame<'_>, area: Rect) -> io::Result<()> {
+        self.area = area;
         let core_layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
@@ -279,6 +591,7 @@ impl Component for CoreWindow {
             .get_mut(&ComponentName::ChatList)
             .unwrap_or_else(|| panic!("Failed to get component: {}", ComponentName::ChatList))
             .draw(frame, core_layout[0])?;
+        self.areas.insert(ComponentName::ChatList, core_layout[0]);
 
         let sub_core_layout = Layout::default()
             .direction(Direction::Vertical)
@@ -295,10 +608,36 @@ impl Component for CoreWindow {
             ])
             .split(core_layout[1]);
 
+        let chat_layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Fill(1),
+                if self.show_members {
+                    Constraint::Length(MEMBERS_PANEL_WIDTH)
+                } else {
+                    Constraint::Length(0)
+                },
+            ])
+            .split(sub_core_layout[0]);
+
+        let chat = self.chat_area;
         self.components
-            .get_mut(&ComponentName::Chat)
-            .unwrap_or_else(|| panic!("Failed to get component: {}", ComponentName::Chat))
-            .draw(frame, sub_core_layout[0])?;
+            .get_mut(&chat)
+            .unwrap_or_else(|| panic!("Failed to get component: {}", chat))
+            .draw(frame, chat_layout[0])?;
+        self.areas.remove(&ComponentName::SearchResults);
+        self.areas.remove(&ComponentName::Contacts);
+        self.areas.remove(&ComponentName::Chat);
+        self.areas.remove(&ComponentName::Members);
+        self.areas.insert(chat, chat_layout[0]);
+
+        if self.show_members {
+            self.components
+                .get_mut(&ComponentName::Members)
+                .unwrap_or_else(|| panic!("Failed to get component: {}", ComponentName::Members))
+                .draw(frame, chat_layout[1])?;
+            self.areas.insert(ComponentName::Members, chat_layout[1]);
+        }
 
         if self.show_reply_message {
             self.components
@@ -312,6 +651,20 @@ impl Component for CoreWindow {
             .get_mut(&ComponentName::Prompt)
             .unwrap_or_else(|| panic!("Failed to get component: {}", ComponentName::Prompt))
             .draw(frame, sub_core_layout[2])?;
+        self.areas.insert(ComponentName::Prompt, sub_core_layout[2]);
+
+        if self.show_popup {
+            let popup_area = Rect {
+                x: area.x + area.width / 5,
+                y: area.y + area.height / 5,
+                width: area.width * 3 / 5,
+                height: area.height * 3 / 5,
+            };
+            self.components
+                .get_mut(&ComponentName::Popup)
+                .unwrap_or_else(|| panic!("Failed to get component: {}", ComponentName::Popup))
+                .draw(frame, popup_area)?;
+        }
 
         Ok(())
     }
@@ -0,0 +1,239 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    components::component_traits::{Component, HandleFocus},
+    tg::tg_context::TgContext,
+};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    symbols::border::PLAIN,
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListState},
+    Frame,
+};
+use std::sync::Arc;
+use tdlib_rs::enums::{ChatMemberStatus, MessageSender};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// `MembersWindow` is a struct that represents the members of the open group,
+/// in a panel next to the chat. The admins of the group can promote,
+/// restrict and remove the members from it.
+pub struct MembersWindow {
+    /// The application context.
+    app_context: Arc<AppContext>,
+    /// The name of the `MembersWindow`.
+    name: String,
+    /// An unbounded sender that send action for processing.
+    action_tx: Option<UnboundedSender<Action>>,
+    /// Indicates whether the `MembersWindow` is focused or not.
+    focused: bool,
+    /// The state of the list of the members.
+    list_state: ListState,
+}
+/// Implementation of the `MembersWindow` struct.
+impl MembersWindow {
+    /// Create a new instance of the `MembersWindow` struct.
+    ///
+    /// # Arguments
+    /// * `app_context` - An Arc wrapped AppContext struct.
+    ///
+    /// # Returns
+    /// * `Self` - The new instance of the `MembersWindow` struct.
+    pub fn new(app_context: Arc<AppContext>) -> Self {
+        let name = "".to_string();
+        let action_tx = None;
+        let focused = false;
+        let list_state = ListState::default();
+
+        MembersWindow {
+            app_context,
+            name,
+            action_tx,
+            focused,
+            list_state,
+        }
+    }
+    /// Set the name of the `MembersWindow`.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the `MembersWindow`.
+    ///
+    /// # Returns
+    /// * `Self` - The modified instance of the `MembersWindow`.
+    pub fn with_name(mut self, name: impl AsRef<str>) -> Self {
+        self.name = name.as_ref().to_string();
+        self
+    }
+    /// Handle a key pressed in the list of the members.
+    ///
+    /// # Arguments
+    /// * `key_code` - The pressed key.
+    fn navigate(&mut self, key_code: KeyCode) {
+        let len = self.app_context.tg_context().chat_members().len();
+        match key_code {
+            KeyCode::Down | KeyCode::Char('j') if len > 0 => {
+                let i = self
+                    .list_state
+                    .selected()
+                    .map_or(0, |i| (i + 1).min(len - 1));
+                self.list_state.select(Some(i));
+            }
+            KeyCode::Up | KeyCode::Char('k') if len > 0 => {
+                let i = self
+                    .list_state
+                    .selected()
+                    .map_or(0, |i| i.saturating_sub(1));
+                self.list_state.select(Some(i));
+            }
+            KeyCode::Enter => self.show_selected(),
+            _ => {}
+        }
+    }
+    /// Show what can be done with the selected member, if it is a user.
+    fn show_selected(&self) {
+        let member_id = self.list_state.selected().and_then(|i| {
+            self.app_context
+                .tg_context()
+                .chat_members()
+                .get(i)
+                .map(|member| member.member_id.clone())
+        });
+        if let Some(MessageSender::User(user)) = member_id {
+            self.app_context
+                .action_tx()
+                .send(Action::ShowChatMember(user.user_id))
+                .unwrap();
+        }
+    }
+}
+
+/// Get the name of a member of a group.
+///
+/// # Arguments
+/// * `tg_context` - The telegram context.
+/// * `member_id` - The member, a user or a chat.
+///
+/// # Returns
+/// * `String` - The full name of the user, or the title of the chat.
+pub fn member_name(tg_context: &TgContext, member_id: &MessageSender) -> String {
+    match member_id {
+        MessageSender::User(user) => tg_context.users().get(&user.user_id).map_or_else(
+            || user.user_id.to_string(),
+            |user| {
+                format!("{} {}", user.first_name, user.last_name)
+                    .trim_end()
+                    .to_string()
+            },
+        ),
+        MessageSender::Chat(chat) => tg_context
+            .name_from_chats(chat.chat_id)
+            .unwrap_or_else(|| chat.chat_id.to_string()),
+    }
+}
+
+/// Get the role of a member of a group, as shown next to their name.
+///
+/// # Arguments
+/// * `status` - The status of the member.
+///
+/// # Returns
+/// * `String` - The custom title of an owner or an admin, if any, or the
+///   role.
+pub fn member_role(status: &ChatMemberStatus) -> String {
+    match status {
+        ChatMemberStatus::Creator(creator) if !creator.custom_title.is_empty() => {
+            creator.custom_title.clone()
+        }
+        ChatMemberStatus::Creator(_) => "owner".to_string(),
+        ChatMemberStatus::Administrator(admin) if !admin.custom_title.is_empty() => {
+            admin.custom_title.clone()
+        }
+        ChatMemberStatus::Administrator(_) => "admin".to_string(),
+        ChatMemberStatus::Restricted(_) => "restricted".to_string(),
+        ChatMemberStatus::Banned(_) => "banned".to_string(),
+        ChatMemberStatus::Left => "left".to_string(),
+        _ => "member".to_string(),
+    }
+}
+
+/// Implement the `HandleFocus` trait for the `MembersWindow` struct.
+/// This trait allows the `MembersWindow` to be focused or unfocused.
+impl HandleFocus for MembersWindow {
+    /// Set the `focused` flag for the `MembersWindow`.
+    fn focus(&mut self) {
+        self.focused = true;
+    }
+    /// Set the `focused` flag for the `MembersWindow`.
+    fn unfocus(&mut self) {
+        self.focused = false;
+    }
+}
+
+/// Implement the `Component` trait for the `MembersWindow` struct.
+impl Component for MembersWindow {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> std::io::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn has_selection(&self) -> bool {
+        self.list_state.selected().is_some()
+    }
+
+    fn update(&mut self, action: Action) {
+        if let Action::Key(key_code, _) = action {
+            self.navigate(key_code);
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame<'_>, area: Rect) -> std::io::Result<()> {
+        let tg_context = self.app_context.tg_context();
+        let members = tg_context.chat_members().clone();
+        // The selection stays on a member when the list gets shorter, like
+        // after a removal.
+        match self.list_state.selected() {
+            Some(i) if i >= members.len() => {
+                self.list_state.select(members.len().checked_sub(1));
+            }
+            None if !members.is_empty() => self.list_state.select(Some(0)),
+            _ => {}
+        }
+        let items: Vec<Line> = members
+            .iter()
+            .map(|member| {
+                Line::from(vec![
+                    Span::styled(
+                        member_name(&tg_context, &member.member_id),
+                        self.app_context.style_chat_list_item_chat_name(),
+                    ),
+                    Span::styled(
+                        format!(" {}", member_role(&member.status)),
+                        self.app_context.style_chat_list_item_message_content(),
+                    ),
+                ])
+            })
+            .collect();
+
+        let chat_title = tg_context
+            .name_from_chats(tg_context.members_chat_id())
+            .unwrap_or_default();
+        let title = format!(" {} of {} ({}) ", self.name, chat_title, members.len());
+        let style_border = if self.focused {
+            self.app_context.style_border_component_focused()
+        } else {
+            self.app_context.style_chat()
+        };
+        let block = Block::default()
+            .border_set(PLAIN)
+            .border_style(style_border)
+            .borders(Borders::ALL)
+            .title(title);
+        let list = List::new(items)
+            .block(block)
+            .style(self.app_context.style_chat())
+            .highlight_style(self.app_context.style_chat_list_item_selected());
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+        Ok(())
+    }
+}
@@ -5,11 +5,73 @@ pub const MIN_CHAT_LIST_SIZE: u16 = 10;
 pub const MAX_PROMPT_SIZE: u16 = 20;
 pub const MIN_PROMPT_SIZE: u16 = 3;
 
+/// Get the number of items to move the selection of a list by to scroll it
+/// by half of its height, like `ctrl+d` and `ctrl+u` in vim.
+///
+/// # Arguments
+/// * `heights` - The heights of the items in the direction of the move,
+///   starting from the item after the selected one.
+/// * `list_height` - The number of rows of the list.
+///
+/// # Returns
+/// * `usize` - The number of items, at least one.
+pub fn half_page_items(heights: impl Iterator<Item = usize>, list_height: usize) -> usize {
+    let half = (list_height / 2).max(1);
+    let mut rows = 0;
+    let mut items = 0;
+    for height in heights {
+        if items > 0 && rows + height > half {
+            break;
+        }
+        rows += height;
+        items += 1;
+    }
+    items.max(1)
+}
+
+/// The part of the rows left that a step of a smooth scrolling covers, so
+/// that the scrolling slows down as it gets closer to its target.
+const SCROLL_EASING: usize = 4;
+
+/// Get the item to select in the next frame of a smooth scrolling of a list.
+///
+/// # Arguments
+/// * `heights` - The heights of the items of the list.
+/// * `from` - The index of the selected item.
+/// * `to` - The index of the item the list is scrolled to.
+///
+/// # Returns
+/// * `usize` - The index of the item to select, at least one item closer to
+///   the target.
+pub fn scroll_step(heights: &[usize], from: usize, to: usize) -> usize {
+    let items: Vec<usize> = if to > from {
+        (from + 1..=to).collect()
+    } else {
+        (to..from).rev().collect()
+    };
+    let height = |i: usize| heights.get(i).copied().unwrap_or(1);
+    let rows: usize = items.iter().map(|i| height(*i)).sum();
+    let step = rows.div_ceil(SCROLL_EASING);
+    let mut covered = 0;
+    for i in items {
+        covered += height(i);
+        if covered >= step {
+            return i;
+        }
+    }
+    to
+}
+
+pub mod chat_layout;
 pub mod chat_list_window;
 pub mod chat_window;
 pub mod component_traits;
+pub mod contacts_window;
 pub mod core_window;
+pub mod members_window;
+pub mod popup_window;
 pub mod prompt_window;
 pub mod reply_message;
+pub mod search_results;
 pub mod status_bar;
 pub mod title_bar;
@@ -0,0 +1,846 @@
+use {
+    crate::{
+        action::Action,
+        app_context::AppContext,
+        components::component_traits::{Component, HandleFocus},
+        emoji::{picker_emojis, skin_tone_label, EmojiTab, SKIN_TONES},
+        image_cache::ImageState,
+        text_width::display_width,
+        utils::fuzzy_match,
+    },
+    crossterm::event::KeyCode,
+    ratatui::{
+        layout::{Alignment, Constraint, Direction, Layout, Rect},
+        symbols::border::PLAIN,
+        text::{Line, Span},
+        widgets::{Block, Borders, Clear, List, ListState, Paragraph, Wrap},
+        Frame,
+    },
+    ratatui_image::{picker::Picker, protocol::StatefulProtocol, StatefulImage},
+    std::{
+        fs, io,
+        path::{Path, PathBuf},
+        sync::Arc,
+    },
+    tokio::sync::mpsc::UnboundedSender,
+};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `PopupItem` is a struct that represents an entry of a list popup.
+pub struct PopupItem {
+    /// The text displayed for the item.
+    pub label: String,
+    /// The action sent when the item is selected.
+    pub action: Action,
+}
+/// Implementation of the `PopupItem` struct.
+impl PopupItem {
+    /// Create a new instance of the `PopupItem` struct.
+    ///
+    /// # Arguments
+    /// * `label` - The text displayed for the item.
+    /// * `action` - The action sent when the item is selected.
+    ///
+    /// # Returns
+    /// * `Self` - The new instance of the `PopupItem` struct.
+    pub fn new(label: impl AsRef<str>, action: Action) -> Self {
+        Self {
+            label: label.as_ref().to_string(),
+            action,
+        }
+    }
+}
+
+/// The width of the preview of the selected item of a media popup, in
+/// characters.
+const MEDIA_PREVIEW_WIDTH: u16 = 24;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `MediaItem` is a struct that represents an item of a media popup, like a
+/// sticker or an animation.
+pub struct MediaItem {
+    /// The text displayed for the item, like the emoji and the set of a
+    /// sticker.
+    pub label: String,
+    /// The text shown in place of the image of the item when it can not be
+    /// drawn, like the emoji of a sticker.
+    pub fallback: String,
+    /// The id of the file of the image of the item, if it can be decoded.
+    pub image_file_id: Option<i32>,
+    /// The action sent when the item is selected.
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `Popup` is an enum that represents the content of the popup window.
+pub enum Popup {
+    /// A list of items that can be filtered with a fuzzy search.
+    /// The first parameter is the `title` and the second parameter is the
+    /// `items`.
+    List(String, Vec<PopupItem>),
+    /// A text input. When the user confirms, the typed text is set in the
+    /// action with `Action::with_text` and the action is sent.
+    /// The first parameter is the `title` and the second parameter is the
+    /// `action`.
+    Input(String, Box<Action>),
+    /// A message displayed to the user, for example an error.
+    /// The first parameter is the `title` and the second parameter is the
+    /// `message`.
+    Info(String, String),
+    /// A text in which the user selects a part. When the user confirms, the
+    /// selected text is set in the action with `Action::with_selection` and
+    /// the action is sent.
+    /// The first parameter is the `title`, the second parameter is the `text`
+    /// and the third parameter is the `action`.
+    Select(String, String, Box<Action>),
+    /// An emoji picker with a tab for each category, a search by name and a
+    /// skin tone. When the user confirms, the emoji is sent with
+    /// `Action::PickEmoji`.
+    /// The parameter is the `title`.
+    Emoji(String),
+    /// A file picker that browses the filesystem, with a fuzzy search in the
+    /// entries of the current directory. When the user confirms a file, its
+    /// path is set in the action with `Action::with_text` and the action is
+    /// sent.
+    /// The first parameter is the `title` and the second parameter is the
+    /// `action`.
+    Files(String, Box<Action>),
+    /// A picker of stickers or animations with a fuzzy search in the labels
+    /// of the items and a preview of the selected item, drawn with the
+    /// graphics protocol of the terminal or as its fallback.
+    /// The first parameter is the `title` and the second parameter is the
+    /// `items`.
+    Media(String, Vec<MediaItem>),
+}
+/// Implementation of the `Popup` enum.
+impl Popup {
+    /// Get the title of the popup.
+    ///
+    /// # Returns
+    /// * `&str` - The title of the popup.
+    pub fn title(&self) -> &str {
+        match self {
+            Popup::List(title, _)
+            | Popup::Input(title, _)
+            | Popup::Info(title, _)
+            | Popup::Select(title, ..)
+            | Popup::Emoji(title)
+            | Popup::Files(title, _)
+            | Popup::Media(title, _) => title,
+        }
+    }
+}
+
+/// `PopupWindow` is a struct that represents a window displayed on top of the
+/// other components. It is used to pick an item from a list, to ask the user
+/// for a text or to display a message.
+pub struct PopupWindow {
+    /// The application context.
+    app_context: Arc<AppContext>,
+    /// The name of the `PopupWindow`.
+    name: String,
+    /// An unbounded sender that send action for processing.
+    action_tx: Option<UnboundedSender<Action>>,
+    /// The content of the popup, if any.
+    popup: Option<Popup>,
+    /// The text typed by the user to filter the items.
+    filter: String,
+    /// The indexes of the items that match the filter, sorted by score.
+    filtered: Vec<usize>,
+    /// The status of the text typed in an input popup, like the result of a
+    /// check. The first element is the text the status refers to.
+    status: Option<(String, String)>,
+    /// The state of the list.
+    list_state: ListState,
+    /// The selection in a select popup, as the indexes of the characters
+    /// where the selection starts and where the cursor is.
+    selection: (usize, usize),
+    /// The emojis shown in an emoji popup and their names.
+    emojis: Vec<(String, String)>,
+    /// The index of the tab selected in an emoji popup.
+    emoji_tab: usize,
+    /// The index of the skin tone selected in an emoji popup.
+    skin_tone: usize,
+    /// The directory browsed in a file picker. It is kept when the popup is
+    /// closed, so the next file is picked from the same directory.
+    directory: PathBuf,
+    /// The entries of the browsed directory and whether they are
+    /// directories.
+    entries: Vec<(String, bool)>,
+    /// The picker of the graphics protocol used to draw the images of a
+    /// media popup.
+    picker: Picker,
+    /// The image of the item previewed in a media popup, encoded for
+    /// the graphics protocol, with the id of its file.
+    media_image: Option<(i32, Box<dyn StatefulProtocol>)>,
+    /// Indicates whether the `PopupWindow` is focused or not.
+    focused: bool,
+}
+/// Implementation of the `PopupWindow` struct.
+impl PopupWindow {
+    /// Create a new instance of the `PopupWindow` struct.
+    ///
+    /// # Arguments
+    /// * `app_context` - An Arc wrapped AppContext struct.
+    ///
+    /// # Returns
+    /// * `Self` - The new instance of the `PopupWindow` struct.
+    pub fn new(app_context: Arc<AppContext>) -> Self {
+        let name = "".to_string();
+        let action_tx = None;
+        let popup = None;
+        let filter = String::new();
+        let filtered = vec![];
+        let status = None;
+        let list_state = ListState::default();
+        let selection = (0, 0);
+        let emojis = vec![];
+        let emoji_tab = 0;
+        let skin_tone = 0;
+        let directory = std::env::current_dir().unwrap_or_default();
+        let entries = vec![];
+        let mut picker = Picker::new((8, 12));
+        picker.guess_protocol();
+        let media_image = None;
+        let focused = false;
+
+        PopupWindow {
+            app_context,
+            name,
+            action_tx,
+            popup,
+            filter,
+            filtered,
+            status,
+            list_state,
+            selection,
+            emojis,
+            emoji_tab,
+            skin_tone,
+            directory,
+            entries,
+            picker,
+            media_image,
+            focused,
+        }
+    }
+    /// Set the name of the `PopupWindow`.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the `PopupWindow`.
+    ///
+    /// # Returns
+    /// * `Self` - The modified instance of the `PopupWindow`.
+    pub fn with_name(mut self, name: impl AsRef<str>) -> Self {
+        self.name = name.as_ref().to_string();
+        self
+    }
+    /// Show a new popup, resetting the filter and the selection.
+    ///
+    /// # Arguments
+    /// * `popup` - The content of the popup.
+    fn show(&mut self, popup: Popup) {
+        self.filter = match &popup {
+            Popup::Input(_, action) => action.initial_text().unwrap_or_default().to_string(),
+            _ => String::new(),
+        };
+        if matches!(popup, Popup::Files(..)) {
+            self.entries = read_entries(&self.directory);
+        }
+        self.popup = Some(popup);
+        self.status = None;
+        self.selection = (0, 0);
+        // The recently used emojis are shown first, if any.
+        self.emoji_tab = if self.app_context.local_state().recent_emojis.is_empty() {
+            1
+        } else {
+            0
+        };
+        self.apply_filter();
+    }
+    /// Handle a change of the typed text.
+    /// The list items are filtered again and, in an input popup, the action
+    /// that checks the text is sent, if any.
+    fn text_changed(&mut self) {
+        self.apply_filter();
+        if let Some(Popup::Input(_, action)) = &self.popup {
+            if let Some(check) = action.check_text(&self.filter) {
+                if let Some(tx) = self.action_tx.as_ref() {
+                    tx.send(check).unwrap();
+                }
+            }
+        }
+    }
+    /// Update the list of items that match the filter.
+    fn apply_filter(&mut self) {
+        self.filtered = match &self.popup {
+            Some(Popup::List(_, items)) => {
+                let mut scored: Vec<(usize, i64)> = items
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, item)| {
+                        fuzzy_match(&self.filter, &item.label).map(|score| (i, score))
+                    })
+                    .collect();
+                // The sort is stable, so items with the same score keep their
+                // original order.
+                scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+                scored.into_iter().map(|(i, _)| i).collect()
+            }
+            Some(Popup::Media(_, items)) => {
+                let mut scored: Vec<(usize, i64)> = items
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, item)| {
+                        fuzzy_match(&self.filter, &item.label).map(|score| (i, score))
+                    })
+                    .collect();
+                scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+                scored.into_iter().map(|(i, _)| i).collect()
+            }
+            Some(Popup::Files(..)) => {
+                // The hidden entries are shown only when the filter starts
+                // with a dot.
+                let show_hidden = self.filter.starts_with('.');
+                let mut scored: Vec<(usize, i64)> = self
+                    .entries
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (name, _))| show_hidden || name == ".." || !name.starts_with('.'))
+                    .filter_map(|(i, (name, _))| {
+                        fuzzy_match(&self.filter, name).map(|score| (i, score))
+                    })
+                    .collect();
+                scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+                scored.into_iter().map(|(i, _)| i).collect()
+            }
+            Some(Popup::Emoji(_)) => {
+                self.emojis = picker_emojis(
+                    EmojiTab::ALL[self.emoji_tab].0,
+                    &self.filter,
+                    &self.app_context.local_state().recent_emojis,
+                    SKIN_TONES[self.skin_tone],
+                );
+                (0..self.emojis.len()).collect()
+            }
+            _ => vec![],
+        };
+        self.list_state.select(if self.filtered.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+    /// Select the next item in the list.
+    fn next(&mut self) {
+        if let Some(i) = self.list_state.selected() {
+            if i + 1 < self.filtered.len() {
+                self.list_state.select(Some(i + 1));
+            }
+        }
+    }
+    /// Select the previous item in the list.
+    fn previous(&mut self) {
+        if let Some(i) = self.list_state.selected() {
+            self.list_state.select(Some(i.saturating_sub(1)));
+        }
+    }
+    /// Select the next or the previous tab of an emoji popup.
+    ///
+    /// # Arguments
+    /// * `forward` - Whether the next tab is selected.
+    fn switch_emoji_tab(&mut self, forward: bool) {
+        let tabs = EmojiTab::ALL.len();
+        self.emoji_tab = if forward {
+            (self.emoji_tab + 1) % tabs
+        } else {
+            (self.emoji_tab + tabs - 1) % tabs
+        };
+        self.apply_filter();
+    }
+    /// Select the next skin tone of an emoji popup.
+    fn next_skin_tone(&mut self) {
+        self.skin_tone = (self.skin_tone + 1) % SKIN_TONES.len();
+        self.apply_filter();
+    }
+    /// Browse a directory in a file picker, resetting the filter.
+    ///
+    /// # Arguments
+    /// * `directory` - The path of the directory.
+    fn open_directory(&mut self, directory: PathBuf) {
+        self.directory = directory.canonicalize().unwrap_or(directory);
+        self.entries = read_entries(&self.directory);
+        self.filter.clear();
+        self.apply_filter();
+    }
+    /// Handle a key of a popup with a filter, like a list popup.
+    /// The arrows move the selection and the typed characters change the
+    /// filter.
+    ///
+    /// # Arguments
+    /// * `action` - The key action.
+    fn update_list(&mut self, action: Action) {
+        let Action::Key(key_code, modifiers) = action else {
+            return;
+        };
+        match key_code {
+            KeyCode::Down => self.next(),
+            KeyCode::Up => self.previous(),
+            KeyCode::Enter => self.confirm_selection(),
+            // In a file picker, backspace with an empty filter goes to the
+            // parent directory.
+            KeyCode::Backspace
+                if self.filter.is_empty() && matches!(self.popup, Some(Popup::Files(..))) =>
+            {
+                self.open_directory(self.directory.join(".."));
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.text_changed();
+            }
+            KeyCode::Char(c) if !modifiers.control && !modifiers.alt => {
+                self.filter.push(c);
+                self.text_changed();
+            }
+            _ => {}
+        }
+    }
+    /// Get the range of the characters selected in a select popup.
+    ///
+    /// # Returns
+    /// * `std::ops::Range<usize>` - The range, that always contains the
+    ///   character under the cursor.
+    fn selection_range(&self) -> std::ops::Range<usize> {
+        let (anchor, cursor) = self.selection;
+        anchor.min(cursor)..anchor.max(cursor) + 1
+    }
+    /// Move the cursor of a select popup and, if `extend` is false, collapse
+    /// the selection on it.
+    /// Moving by word puts the cursor on the start of the previous or the
+    /// next word.
+    ///
+    /// # Arguments
+    /// * `text` - The text of the popup.
+    /// * `forward` - Whether the cursor moves forward.
+    /// * `by_word` - Whether the cursor moves by word instead of by character.
+    /// * `extend` - Whether the selection is extended.
+    fn move_cursor(&mut self, text: &str, forward: bool, by_word: bool, extend: bool) {
+        let chars: Vec<char> = text.chars().collect();
+        let last = chars.len().saturating_sub(1);
+        let (anchor, mut cursor) = self.selection;
+        let is_word_start =
+            |i: usize| i == 0 || (!chars[i].is_whitespace() && chars[i - 1].is_whitespace());
+        if forward {
+            cursor = (cursor + 1).min(last);
+            while by_word && cursor < last && !is_word_start(cursor) {
+                cursor += 1;
+            }
+        } else {
+            cursor = cursor.saturating_sub(1);
+            while by_word && cursor > 0 && !is_word_start(cursor) {
+                cursor -= 1;
+            }
+        }
+        self.selection = (if extend { anchor } else { cursor }, cursor);
+    }
+    /// Confirm the selected item or the typed text, close the popup and send
+    /// the related action.
+    /// In a file picker, confirming a directory opens it and keeps the popup.
+    fn confirm_selection(&mut self) {
+        if let (Some(Popup::Files(..)), Some(i)) = (&self.popup, self.list_state.selected()) {
+            if let Some((name, true)) = self.filtered.get(i).and_then(|i| self.entries.get(*i)) {
+                let directory = self.directory.join(name);
+                self.open_directory(directory);
+                return;
+            }
+        }
+        let action = match (&self.popup, self.list_state.selected()) {
+            (Some(Popup::List(_, items)), Some(i)) => self
+                .filtered
+                .get(i)
+                .and_then(|index| items.get(*index))
+                .map(|item| item.action.clone()),
+            (Some(Popup::Media(_, items)), Some(i)) => self
+                .filtered
+                .get(i)
+                .and_then(|index| items.get(*index))
+                .map(|item| item.action.clone()),
+            (Some(Popup::Emoji(_)), Some(i)) => self
+                .emojis
+                .get(i)
+                .map(|(emoji, _)| Action::PickEmoji(emoji.clone())),
+            (Some(Popup::Input(_, action)), _) => {
+                Some(action.as_ref().clone().with_text(self.filter.clone()))
+            }
+            (Some(Popup::Files(_, action)), Some(i)) => self
+                .filtered
+                .get(i)
+                .and_then(|index| self.entries.get(*index))
+                .map(|(name, _)| {
+                    action
+                        .as_ref()
+                        .clone()
+                        .with_text(self.directory.join(name).to_string_lossy().to_string())
+                }),
+            (Some(Popup::Select(_, text, action)), _) => {
+                let range = self.selection_range();
+                let position = text
+                    .chars()
+                    .take(range.start)
+                    .map(char::len_utf16)
+                    .sum::<usize>();
+                let selected = text
+                    .chars()
+                    .skip(range.start)
+                    .take(range.len())
+                    .collect::<String>();
+                Some(
+                    action
+                        .as_ref()
+                        .clone()
+                        .with_selection(position as i32, selected),
+                )
+            }
+            _ => None,
+        };
+        if let Some(tx) = self.action_tx.as_ref() {
+            tx.send(Action::HidePopup).unwrap();
+            if let Some(action) = action {
+                tx.send(action).unwrap();
+            }
+        }
+    }
+}
+
+/// Implement the `HandleFocus` trait for the `PopupWindow` struct.
+/// This trait allows the `PopupWindow` to be focused or unfocused.
+impl HandleFocus for PopupWindow {
+    /// Set the `focused` flag for the `PopupWindow`.
+    fn focus(&mut self) {
+        self.focused = true;
+    }
+    /// Set the `focused` flag for the `PopupWindow`.
+    fn unfocus(&mut self) {
+        self.focused = false;
+    }
+}
+
+/// Implement the `Component` trait for the `PopupWindow` struct.
+impl Component for PopupWindow {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> io::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) {
+        match action {
+            Action::ShowPopup(popup) => self.show(popup),
+            Action::Key(key_code, modifiers) if matches!(self.popup, Some(Popup::Select(..))) => {
+                let Some(Popup::Select(_, text, _)) = self.popup.clone() else {
+                    return;
+                };
+                match key_code {
+                    KeyCode::Left => self.move_cursor(&text, false, false, modifiers.shift),
+                    KeyCode::Right => self.move_cursor(&text, true, false, modifiers.shift),
+                    KeyCode::Up => self.move_cursor(&text, false, true, modifiers.shift),
+                    KeyCode::Down => self.move_cursor(&text, true, true, modifiers.shift),
+                    KeyCode::Enter => self.confirm_selection(),
+                    _ => {}
+                }
+            }
+            Action::Key(key_code, _) if matches!(self.popup, Some(Popup::Emoji(_))) => {
+                match key_code {
+                    KeyCode::Left => self.switch_emoji_tab(false),
+                    KeyCode::Right => self.switch_emoji_tab(true),
+                    KeyCode::Tab => self.next_skin_tone(),
+                    _ => self.update_list(action),
+                }
+            }
+            Action::Key(..) => self.update_list(action),
+            Action::SetPopupStatus(text, status) => self.status = Some((text, status)),
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame<'_>, area: Rect) -> io::Result<()> {
+        let Some(popup) = &self.popup else {
+            return Ok(());
+        };
+        frame.render_widget(Clear, area);
+
+        let block = Block::default()
+            .border_set(PLAIN)
+            .borders(Borders::ALL)
+            .border_style(self.app_context.style_border_component_focused())
+            .style(self.app_context.style_popup())
+            .title(popup.title());
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        // A secret, like a password, is not shown while it is typed. The
+        // bullets take the columns of the typed text, so that wide
+        // characters do not shift the rest of the line.
+        let typed = match popup {
+            Popup::Input(_, action) if action.is_secret() => {
+                "•".repeat(display_width(&self.filter))
+            }
+            _ => self.filter.clone(),
+        };
+        let input = Paragraph::new(Line::from(vec![
+            Span::raw("> "),
+            Span::styled(typed, self.app_context.style_popup_input_text()),
+        ]));
+        match popup {
+            Popup::List(_, items) => {
+                let layout = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(1), Constraint::Fill(1)])
+                    .split(inner);
+                frame.render_widget(input, layout[0]);
+
+                let list = List::new(
+                    self.filtered
+                        .iter()
+                        .filter_map(|i| items.get(*i))
+                        .map(|item| item.label.as_str()),
+                )
+                .style(self.app_context.style_popup())
+                .highlight_style(self.app_context.style_popup_item_selected());
+                frame.render_stateful_widget(list, layout[1], &mut self.list_state);
+            }
+            Popup::Media(_, items) => {
+                let layout = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(1), Constraint::Fill(1)])
+                    .split(inner);
+                frame.render_widget(input, layout[0]);
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Fill(1), Constraint::Length(MEDIA_PREVIEW_WIDTH)])
+                    .split(layout[1]);
+
+                let list = List::new(
+                    self.filtered
+                        .iter()
+                        .filter_map(|i| items.get(*i))
+                        .map(|item| item.label.as_str()),
+                )
+                .style(self.app_context.style_popup())
+                .highlight_style(self.app_context.style_popup_item_selected());
+                frame.render_stateful_widget(list, columns[0], &mut self.list_state);
+
+                let selected = self
+                    .list_state
+                    .selected()
+                    .and_then(|i| self.filtered.get(i))
+                    .and_then(|i| items.get(*i));
+                if let Some(item) = selected {
+                    draw_media_preview(
+                        &self.app_context,
+                        &mut self.picker,
+                        &mut self.media_image,
+                        item,
+                        frame,
+                        columns[1],
+                    );
+                }
+            }
+            Popup::Files(..) => {
+                let layout = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(1),
+                        Constraint::Length(1),
+                        Constraint::Fill(1),
+                    ])
+                    .split(inner);
+                frame.render_widget(input, layout[0]);
+                frame.render_widget(
+                    Paragraph::new(self.directory.to_string_lossy())
+                        .style(self.app_context.style_popup()),
+                    layout[1],
+                );
+
+                let list = List::new(
+                    self.filtered
+                        .iter()
+                        .filter_map(|i| self.entries.get(*i))
+                        .map(|(name, is_dir)| {
+                            if *is_dir {
+                                format!("{}/", name)
+                            } else {
+                                name.clone()
+                            }
+                        }),
+                )
+                .style(self.app_context.style_popup())
+                .highlight_style(self.app_context.style_popup_item_selected());
+                frame.render_stateful_widget(list, layout[2], &mut self.list_state);
+            }
+            Popup::Input(..) => {
+                let layout = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(1), Constraint::Fill(1)])
+                    .split(inner);
+                frame.render_widget(input, layout[0]);
+
+                // A status of a previous text is outdated, so it is not shown.
+                if let Some((_, status)) = self
+                    .status
+                    .as_ref()
+                    .filter(|(text, _)| *text == self.filter)
+                {
+                    let paragraph = Paragraph::new(status.as_str())
+                        .style(self.app_context.style_popup())
+                        .wrap(Wrap { trim: false });
+                    frame.render_widget(paragraph, layout[1]);
+                }
+            }
+            Popup::Emoji(_) => {
+                let layout = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(1),
+                        Constraint::Length(1),
+                        Constraint::Fill(1),
+                    ])
+                    .split(inner);
+                let mut tabs = EmojiTab::ALL
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (_, label))| {
+                        let style = if i == self.emoji_tab && self.filter.is_empty() {
+                            self.app_context.style_popup_item_selected()
+                        } else {
+                            self.app_context.style_popup()
+                        };
+                        Span::styled(format!(" {} ", label), style)
+                    })
+                    .collect::<Vec<Span>>();
+                tabs.push(Span::raw(format!(
+                    " | Skin tone: {} (tab)",
+                    skin_tone_label(SKIN_TONES[self.skin_tone])
+                )));
+                frame.render_widget(Paragraph::new(Line::from(tabs)), layout[0]);
+                frame.render_widget(input, layout[1]);
+
+                let list = List::new(
+                    self.emojis
+                        .iter()
+                        .map(|(emoji, name)| format!("{}  {}", emoji, name)),
+                )
+                .style(self.app_context.style_popup())
+                .highlight_style(self.app_context.style_popup_item_selected());
+                frame.render_stateful_widget(list, layout[2], &mut self.list_state);
+            }
+            Popup::Info(_, message) => {
+                let paragraph = Paragraph::new(message.as_str())
+                    .style(self.app_context.style_popup())
+                    .wrap(Wrap { trim: false });
+                frame.render_widget(paragraph, inner);
+            }
+            Popup::Select(_, text, _) => {
+                let range = self.selection_range();
+                let mut lines = vec![Line::default()];
+                for (i, c) in text.chars().enumerate() {
+                    if c == '\n' {
+                        lines.push(Line::default());
+                        continue;
+                    }
+                    let style = if range.contains(&i) {
+                        self.app_context.style_popup_item_selected()
+                    } else {
+                        self.app_context.style_popup()
+                    };
+                    if let Some(line) = lines.last_mut() {
+                        line.spans.push(Span::styled(c.to_string(), style));
+                    }
+                }
+                let paragraph = Paragraph::new(lines)
+                    .style(self.app_context.style_popup())
+                    .wrap(Wrap { trim: false });
+                frame.render_widget(paragraph, inner);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Draw the preview of an item of a media popup. The image of the item is
+/// loaded the first time it is previewed and, until it is ready or if it can
+/// not be drawn, its fallback is shown.
+///
+/// # Arguments
+/// * `app_context` - The application context.
+/// * `picker` - The picker of the graphics protocol.
+/// * `media_image` - The image of the previously previewed item, kept
+///   encoded while the same item is selected.
+/// * `item` - The item.
+/// * `frame` - The frame to draw on.
+/// * `area` - The area of the preview.
+fn draw_media_preview(
+    app_context: &AppContext,
+    picker: &mut Picker,
+    media_image: &mut Option<(i32, Box<dyn StatefulProtocol>)>,
+    item: &MediaItem,
+    frame: &mut Frame<'_>,
+    area: Rect,
+) {
+    let file_id = item
+        .image_file_id
+        .filter(|_| app_context.app_config().show_images);
+    if let Some(file_id) = file_id {
+        if media_image.as_ref().map(|(id, _)| *id) != Some(file_id) {
+            let state = app_context.images().get(file_id).cloned();
+            *media_image = match state {
+                Some(ImageState::Ready(image)) => {
+                    Some((file_id, picker.new_resize_protocol(image)))
+                }
+                Some(_) => None,
+                None => {
+                    app_context.images().insert(file_id, ImageState::Loading);
+                    if let Err(e) = app_context.action_tx().send(Action::LoadImage(file_id)) {
+                        tracing::error!("Failed to load the image {}: {}", file_id, e);
+                    }
+                    None
+                }
+            };
+        }
+        if let Some((_, protocol)) = media_image.as_mut() {
+            frame.render_stateful_widget(StatefulImage::new(None), area, protocol);
+            return;
+        }
+    }
+    let paragraph = Paragraph::new(vec![Line::default(), Line::from(item.fallback.as_str())])
+        .alignment(Alignment::Center)
+        .style(app_context.style_popup());
+    frame.render_widget(paragraph, area);
+}
+
+/// Read the entries of a directory for a file picker.
+///
+/// # Arguments
+/// * `directory` - The path of the directory.
+///
+/// # Returns
+/// * `Vec<(String, bool)>` - The names of the entries and whether they are
+///   directories, with the parent directory first, then the directories and
+///   the files sorted by name.
+fn read_entries(directory: &Path) -> Vec<(String, bool)> {
+    let mut entries: Vec<(String, bool)> = fs::read_dir(directory)
+        .map(|read_dir| {
+            read_dir
+                .flatten()
+                .map(|entry| {
+                    (
+                        entry.file_name().to_string_lossy().to_string(),
+                        entry.path().is_dir(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    entries.insert(0, ("..".to_string(), true));
+    entries
+}
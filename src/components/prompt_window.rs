@@ -1,15 +1,28 @@
 use crate::{
-    action::{Action, Modifiers},
+    action::{Action, DraftResolution, Modifiers},
     app_context::AppContext,
+    bidi::{is_rtl_paragraph, visual_cursor_column, visual_order},
     component_name::ComponentName,
-    components::component_traits::{Component, HandleFocus},
+    components::{
+        component_traits::{Component, HandleFocus},
+        popup_window::{Popup, PopupItem},
+    },
+    emoji::{complete_shortcode, is_shortcode_char},
     event::Event,
-    tg::td_enums::TdMessageReplyToMessage,
+    markdown,
+    templates::TemplateVariables,
+    text_diff::line_diff,
+    text_width::{display_width, grapheme_width},
+    tg::{
+        message_entry::MessageEntry,
+        td_enums::{TdMessageReplyToMessage, TdTextQuote},
+        typing::TYPING_ACTION_INTERVAL,
+    },
 };
 use arboard::Clipboard;
 use crossterm::event::KeyCode;
 use ratatui::{
-    layout::{Position, Rect},
+    layout::{Alignment, Position, Rect},
     symbols::{
         border::{Set, PLAIN},
         line::NORMAL,
@@ -18,8 +31,9 @@ use ratatui::{
     widgets::{block::Block, Borders, Paragraph},
     Frame,
 };
-use std::{io, sync::Arc};
+use std::{io, sync::Arc, time::Instant};
 use tokio::sync::mpsc::UnboundedSender;
+use unicode_segmentation::UnicodeSegmentation;
 
 /// `DirSelection` is an enum that represents the direction of the selection.
 /// It is used to keep track of the direction of the selection when the user
@@ -48,8 +62,9 @@ enum Mode {
     Edit(i64),
     /// The reply mode of the prompt.
     /// Usually, when the prompt is replying to a message.
-    /// The parameter is the message id of the message that is being replied.
-    Reply(i64),
+    /// The first parameter is the message id of the message that is being
+    /// replied and the second parameter is the quoted part of it, if any.
+    Reply(i64, Option<TdTextQuote>),
 }
 /// `InputCell` is a struct that represents a cell of the input.
 /// It is responsible for managing the input cell of the prompt.
@@ -89,6 +104,9 @@ struct Input {
     /// The mode of the input.
     /// It is used to keep track of the mode of the input.
     mode: Mode,
+    /// A flag indicating whether a template was inserted in the input, so
+    /// that its variables are replaced when the message is sent.
+    template: bool,
 }
 /// Implement the `Input` struct.
 impl Input {
@@ -99,9 +117,59 @@ impl Input {
     fn set_command_tx(&mut self, command_tx: UnboundedSender<Action>) {
         self.action_tx = Some(command_tx);
     }
-    /// Get the cursor x position of the `Input` struct.
-    fn cursor_x(&self) -> usize {
-        self.cursor.0
+    /// Get the display width of the text before the cursor of the `Input`
+    /// struct, in logical order.
+    fn width_before_cursor(&self) -> usize {
+        let before = self.text[self.cursor.1][..self.cursor.0]
+            .iter()
+            .map(|cell| cell.c)
+            .collect::<String>();
+        display_width(&before)
+    }
+    /// Get the terminal column of the cursor of the `Input` struct.
+    /// The line is shown in visual order, so in right to left text the cursor
+    /// moves to the left while typing.
+    fn cursor_column(&self) -> usize {
+        let graphemes = Self::graphemes(&self.text[self.cursor.1]);
+        let units = graphemes
+            .iter()
+            .map(|(grapheme, _)| grapheme.as_str())
+            .collect::<Vec<&str>>();
+        // The index of the grapheme cluster before which the cursor is. The
+        // cursor counts the characters of the line, its column is the width
+        // of the clusters before it.
+        let mut chars = 0;
+        let cursor = units
+            .iter()
+            .take_while(|grapheme| {
+                let before = chars < self.cursor.0;
+                chars += grapheme.chars().count();
+                before
+            })
+            .count();
+        let rtl = is_rtl_paragraph(&units.concat());
+        visual_cursor_column(&units, rtl, cursor, grapheme_width)
+    }
+    /// Group the cells of a line in grapheme clusters.
+    ///
+    /// # Arguments
+    /// * `line` - The cells of the line.
+    ///
+    /// # Returns
+    /// * `Vec<(String, bool)>` - The grapheme clusters in logical order, each
+    ///   with whether it is selected.
+    fn graphemes(line: &[InputCell]) -> Vec<(String, bool)> {
+        let text = line.iter().map(|cell| cell.c).collect::<String>();
+        let mut cells = line.iter();
+        text.graphemes(true)
+            .map(|grapheme| {
+                let selected = grapheme
+                    .chars()
+                    .filter_map(|_| cells.next())
+                    .any(|cell| cell.selected);
+                (grapheme.to_string(), selected)
+            })
+            .collect()
     }
     /// Get the cursor y position of the `Input` struct.
     fn cursor_y(&self) -> usize {
@@ -118,8 +186,11 @@ impl Input {
     /// # Arguments
     /// * `c` - The character to insert.
     fn insert(&mut self, c: char) {
-        // The -2 is to account the cursor at the end of the line.
-        if self.cursor.0 + 1 == (self.area_input.width - 2) as usize {
+        // The -2 is to account the cursor at the end of the line. A wide
+        // character, like a CJK one, takes two columns, and the newline that
+        // wraps the line never wraps it again.
+        let width = grapheme_width(c.encode_utf8(&mut [0; 4]));
+        if c != '\n' && self.width_before_cursor() + width >= (self.area_input.width - 2) as usize {
             self.insert_newline();
         }
         self.text[self.cursor.1].insert(self.cursor.0, InputCell { c, selected: false });
@@ -251,6 +322,57 @@ impl Input {
         line.drain(i..self.cursor.0);
         self.cursor.0 = i;
     }
+    /// Get the shortcode typed before the cursor, like `:smi`, that starts a
+    /// word.
+    ///
+    /// # Returns
+    /// * `Option<String>` - The typed part of the shortcode, without the
+    ///   colon, or `None` if no shortcode is typed before the cursor.
+    fn shortcode_before_cursor(&self) -> Option<String> {
+        let line = &self.text[self.cursor.1][..self.cursor.0];
+        let start = line
+            .iter()
+            .rposition(|cell| !is_shortcode_char(cell.c))
+            .filter(|i| line[*i].c == ':')?;
+        if start > 0 && !line[start - 1].c.is_whitespace() {
+            return None;
+        }
+        let shortcode = line[start + 1..]
+            .iter()
+            .map(|cell| cell.c)
+            .collect::<String>();
+        (!shortcode.is_empty()).then_some(shortcode)
+    }
+    /// Replace the shortcode typed before the cursor, with its colon, by an
+    /// emoji.
+    ///
+    /// # Arguments
+    /// * `emoji` - The emoji.
+    fn replace_shortcode(&mut self, emoji: &str) {
+        let Some(shortcode) = self.shortcode_before_cursor() else {
+            return;
+        };
+        let start = self.cursor.0 - shortcode.chars().count() - 1;
+        self.text[self.cursor.1].drain(start..self.cursor.0);
+        self.cursor.0 = start;
+        emoji.chars().for_each(|c| self.insert(c));
+    }
+    /// Replace a whole shortcode just closed with a colon, like `:smile:`,
+    /// by its emoji.
+    fn replace_closed_shortcode(&mut self) {
+        if self.cursor.0 == 0 || self.text[self.cursor.1][self.cursor.0 - 1].c != ':' {
+            return;
+        }
+        self.cursor.0 -= 1;
+        let emoji = self
+            .shortcode_before_cursor()
+            .and_then(|shortcode| emojis::get_by_shortcode(&shortcode));
+        self.cursor.0 += 1;
+        if let Some(emoji) = emoji {
+            self.backspace();
+            self.replace_shortcode(emoji.as_str());
+        }
+    }
     /// Restore the prompt size of the `Input` struct.
     /// It is used to restore the prompt size to the correct prompt size when
     /// the prompt window is focused.
@@ -456,29 +578,48 @@ impl Input {
     /// * `app_context` - An Arc wrapped AppContext struct.
     fn send_message(&mut self, app_context: Arc<AppContext>) {
         if let Some(event_tx) = app_context.tg_context().event_tx().as_ref() {
+            let text = self.text_to_send(&app_context);
             match self.mode {
                 Mode::Normal => {
-                    event_tx
-                        .send(Event::SendMessage(self.text_to_string(), None))
-                        .unwrap();
+                    let command = app_context.command_registry().parse(&text);
+                    if let Some((name, args)) = command {
+                        if let Some(tx) = self.action_tx.as_ref() {
+                            tx.send(Action::RunCommand(name, args)).unwrap();
+                        }
+                        self.text = vec![vec![]];
+                        self.set_prompt_size_to_one_focused();
+                        return;
+                    }
+                    // The staged attachments are sent together, with the
+                    // text as caption.
+                    if !app_context.tg_context().attachments().is_empty() {
+                        if let Some(tx) = self.action_tx.as_ref() {
+                            tx.send(Action::SendAttachments(text)).unwrap();
+                        }
+                        self.text = vec![vec![]];
+                        self.set_prompt_size_to_one_focused();
+                        return;
+                    }
+                    event_tx.send(Event::SendMessage(text, None)).unwrap();
                     self.text = vec![vec![]];
                     self.set_prompt_size_to_one_focused();
                 }
                 Mode::Edit(message_id) => {
                     event_tx
-                        .send(Event::SendMessageEdited(message_id, self.text_to_string()))
+                        .send(Event::SendMessageEdited(message_id, text))
                         .unwrap();
                     self.text = vec![vec![]];
                     self.set_prompt_size_to_one_focused();
                     self.mode = Mode::Normal;
                 }
-                Mode::Reply(message_id) => {
+                Mode::Reply(message_id, ref quote) => {
                     event_tx
                         .send(Event::SendMessage(
-                            self.text_to_string(),
+                            text,
                             Some(TdMessageReplyToMessage {
                                 chat_id: 0, // This must be  0 and not `app_context.tg_context().open_chat_id()` because the tdlib (maybe from the version 1.8.29 or before)  is able to know the chat id from the message_id; it will infer the chat id from the message_id.
                                 message_id,
+                                quote: quote.clone(),
                             }),
                         ))
                         .unwrap();
@@ -489,9 +630,44 @@ impl Input {
             }
         }
     }
+    /// Replace the text of the `Input` struct with a single line and move the
+    /// cursor to its end.
+    ///
+    /// # Arguments
+    /// * `line` - The new text.
+    fn set_line(&mut self, line: &str) {
+        self.set_prompt_size_to_one_focused();
+        self.text = vec![line
+            .chars()
+            .map(|c| InputCell { c, selected: false })
+            .collect()];
+        self.move_cursor_to_end();
+    }
+    /// Convert the text of the `Input` struct to the string to send.
+    /// If a template was inserted, its variables are replaced with the
+    /// values of the open chat, or of the sender of the replied message.
+    ///
+    /// # Arguments
+    /// * `app_context` - A reference to the AppContext struct.
+    fn text_to_send(&mut self, app_context: &AppContext) -> String {
+        let text = self.text_to_string();
+        if !std::mem::take(&mut self.template) {
+            return text;
+        }
+        let tg_context = app_context.tg_context();
+        let reply_sender_id = match self.mode {
+            Mode::Reply(message_id, _) => tg_context
+                .open_chat_messages()
+                .iter()
+                .find(|message_entry| message_entry.id() == message_id)
+                .map(|message_entry| message_entry.sender_id()),
+            _ => None,
+        };
+        TemplateVariables::of_chat(app_context, tg_context.open_chat_id(), reply_sender_id)
+            .expand(&text)
+    }
     /// Convert the text of the `Input` struct to a string.
     fn text_to_string(&mut self) -> String {
-        // TODO: Parse into markdown
         let mut message = String::new();
         self.text.iter().for_each(|e| {
             if e.is_empty() {
@@ -515,9 +691,19 @@ impl Default for Input {
             correct_prompt_size: 0,
             is_restored: true,
             mode: Mode::Normal,
+            template: false,
         }
     }
 }
+/// `DraftConflict` is a struct that represents a draft changed on another
+/// device while the user was typing another text in the prompt. Neither text
+/// is saved until the user picks one.
+struct DraftConflict {
+    /// The text typed in the prompt.
+    mine: String,
+    /// The text and the replied message of the draft of the other device.
+    theirs: (String, Option<i64>),
+}
 /// `PromptWindow` is a struct that represents a window for displaying a prompt.
 /// It is responsible for managing the layout and rendering of the prompt
 /// window.
@@ -534,6 +720,22 @@ pub struct PromptWindow {
     focused_keys: Vec<Event>,
     /// The current input of the `PromptWindow`.
     input: Input,
+    /// The id of the chat whose draft is in the input.
+    chat_id: i64,
+    /// The text and the replied message of the draft of the chat, as last
+    /// loaded or saved, so that it is saved only once changed.
+    draft: (String, Option<i64>),
+    /// A flag indicating whether the reply bar of the draft is shown once
+    /// the draft is loaded, after a chat is opened.
+    restore_reply: bool,
+    /// The time when the typing action was last sent, if any.
+    typing_sent_at: Option<Instant>,
+    /// The draft changed on another device that conflicts with the input,
+    /// if any.
+    draft_conflict: Option<DraftConflict>,
+    /// Indicates whether the message is shown as it will be sent, with its
+    /// Markdown formatted, instead of as it is typed.
+    preview: bool,
 }
 /// Implement the `PromptWindow` struct.
 impl PromptWindow {
@@ -562,6 +764,12 @@ impl PromptWindow {
             focused,
             focused_keys,
             input,
+            chat_id: 0,
+            draft: (String::new(), None),
+            restore_reply: false,
+            typing_sent_at: None,
+            draft_conflict: None,
+            preview: false,
         }
     }
     /// Set the name of the `PromptWindow`.
@@ -575,6 +783,299 @@ impl PromptWindow {
         self.name = name.as_ref().to_string();
         self
     }
+    /// Save the text of the input as the draft of its chat, with the replied
+    /// message, if it changed. The text of a message being edited is not a
+    /// draft.
+    fn save_draft(&mut self) {
+        if self.chat_id == 0 || matches!(self.input.mode, Mode::Edit(_)) {
+            return;
+        }
+        let text = self.input.text_to_string();
+        // Neither draft is saved while the conflict is not resolved.
+        if let Some(conflict) = self.draft_conflict.as_mut() {
+            conflict.mine = text;
+            return;
+        }
+        let reply_to = match &self.input.mode {
+            Mode::Reply(message_id, quote) => Some(TdMessageReplyToMessage {
+                chat_id: 0,
+                message_id: *message_id,
+                quote: quote.clone(),
+            }),
+            // The draft keeps its reply until the reply bar is shown again,
+            // when the chat is reopened.
+            _ => self.draft.1.map(|message_id| TdMessageReplyToMessage {
+                chat_id: 0,
+                message_id,
+                quote: None,
+            }),
+        }
+        .filter(|_| !text.trim().is_empty());
+        let draft = (text, reply_to.as_ref().map(|reply_to| reply_to.message_id));
+        if draft == self.draft {
+            return;
+        }
+        self.draft = draft.clone();
+        if let Some(tx) = self.action_tx.as_ref() {
+            tx.send(Action::SaveChatDraft(self.chat_id, draft.0, reply_to))
+                .unwrap();
+        }
+    }
+    /// Load the draft of the open chat in the input, if it is empty. The
+    /// reply bar of the draft is shown only after the chat is opened.
+    fn load_draft(&mut self) {
+        let tg_context = self.app_context.tg_context();
+        let chat_id = tg_context.open_chat_id();
+        if chat_id != self.chat_id {
+            self.chat_id = chat_id;
+            self.draft = (String::new(), None);
+        }
+        if chat_id == 0 || !self.input.text_to_string().is_empty() {
+            return;
+        }
+        if let Some(conflict) = self.draft_conflict.as_ref() {
+            self.input.paste(conflict.mine.clone());
+            return;
+        }
+        let restore_reply = std::mem::take(&mut self.restore_reply);
+        let Some((text, reply_to)) = tg_context.chat_draft(chat_id) else {
+            self.draft = (String::new(), None);
+            return;
+        };
+        self.input.paste(text.clone());
+        if let Some(message_id) = reply_to.filter(|_| restore_reply) {
+            if let Some(tx) = self.action_tx.as_ref() {
+                tx.send(Action::LoadDraftReply(chat_id, message_id))
+                    .unwrap();
+            }
+        }
+        self.draft = (text, reply_to);
+    }
+    /// Save the draft of the chat of the input and clear it for another
+    /// chat, whose draft is loaded once the prompt is focused.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the opened chat.
+    fn switch_chat(&mut self, chat_id: i64) {
+        if chat_id == self.chat_id {
+            return;
+        }
+        // A conflict left unresolved keeps the text of the user, like before
+        // the draft changed on another device.
+        if let Some(conflict) = self.draft_conflict.take() {
+            self.draft = conflict.theirs;
+            if !self.focused {
+                self.input.paste(conflict.mine);
+                self.save_draft();
+                self.input.text = vec![vec![]];
+            }
+        }
+        // The input of an unfocused prompt is already empty.
+        if self.focused {
+            self.save_draft();
+            self.input.set_line("");
+        }
+        self.input.mode = Mode::Normal;
+        self.input.template = false;
+        self.chat_id = chat_id;
+        self.draft = (String::new(), None);
+        self.restore_reply = true;
+        self.typing_sent_at = None;
+        if let Some(tx) = self.action_tx.as_ref() {
+            tx.send(Action::HideChatWindowReply).unwrap();
+        }
+        if self.focused {
+            self.load_draft();
+        }
+    }
+    /// Check the draft of the open chat, changed like on another device. It
+    /// replaces the input if the input was not changed since the draft was
+    /// loaded, otherwise the user picks the text to keep.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat whose draft changed.
+    fn check_draft_changed(&mut self, chat_id: i64) {
+        if !self.focused || chat_id != self.chat_id || matches!(self.input.mode, Mode::Edit(_)) {
+            return;
+        }
+        let theirs = self
+            .app_context
+            .tg_context()
+            .chat_draft(chat_id)
+            .unwrap_or_default();
+        // The draft saved by the prompt is sent back by TDLib.
+        if theirs == self.draft {
+            return;
+        }
+        let mine = self.input.text_to_string();
+        if mine == theirs.0 {
+            self.draft = theirs;
+        } else if mine == self.draft.0 {
+            self.input.set_line("");
+            self.input.paste(theirs.0.clone());
+            self.draft = theirs;
+        } else {
+            self.draft_conflict = Some(DraftConflict { mine, theirs });
+            self.show_draft_conflict();
+        }
+    }
+    /// Show the ways to resolve the conflict with the draft changed on
+    /// another device, if any.
+    fn show_draft_conflict(&self) {
+        if self.draft_conflict.is_none() {
+            return;
+        }
+        if let Some(tx) = self.action_tx.as_ref() {
+            tx.send(Action::ShowPopup(Popup::List(
+                "Draft changed on another device".to_string(),
+                vec![
+                    PopupItem::new(
+                        "Keep mine",
+                        Action::ResolveDraftConflict(DraftResolution::KeepMine),
+                    ),
+                    PopupItem::new(
+                        "Take theirs",
+                        Action::ResolveDraftConflict(DraftResolution::TakeTheirs),
+                    ),
+                    PopupItem::new(
+                        "View diff",
+                        Action::ResolveDraftConflict(DraftResolution::ViewDiff),
+                    ),
+                ],
+            )))
+            .unwrap();
+        }
+    }
+    /// Resolve the conflict with the draft changed on another device.
+    ///
+    /// # Arguments
+    /// * `resolution` - The way the conflict is resolved.
+    fn resolve_draft_conflict(&mut self, resolution: DraftResolution) {
+        let Some(conflict) = self.draft_conflict.as_ref() else {
+            return;
+        };
+        match resolution {
+            DraftResolution::KeepMine => {
+                // The other draft is replaced once the input is saved.
+                let conflict = self.draft_conflict.take().unwrap();
+                self.draft = conflict.theirs;
+                self.save_draft();
+            }
+            DraftResolution::TakeTheirs => {
+                let conflict = self.draft_conflict.take().unwrap();
+                self.input.set_line("");
+                self.input.paste(conflict.theirs.0.clone());
+                self.draft = conflict.theirs;
+            }
+            DraftResolution::ViewDiff => {
+                let diff = line_diff(&conflict.mine, &conflict.theirs.0)
+                    .iter()
+                    .map(|line| line.to_prefixed())
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                if let Some(tx) = self.action_tx.as_ref() {
+                    tx.send(Action::ShowPopup(Popup::Info(
+                        "Draft diff (- mine, + theirs)".to_string(),
+                        format!("{}\n\nPress ctrl+r in the prompt to resolve it.", diff),
+                    )))
+                    .unwrap();
+                }
+            }
+        }
+    }
+    /// Send the typing action of the user in the chat of the input, at most
+    /// once per `TYPING_ACTION_INTERVAL`, unless it is disabled. Nothing is
+    /// sent while a slash command or an edit is typed.
+    fn send_typing_action(&mut self) {
+        if self.chat_id == 0
+            || !self.app_context.app_config().send_typing_action
+            || matches!(self.input.mode, Mode::Edit(_))
+            || self
+                .typing_sent_at
+                .is_some_and(|sent_at| sent_at.elapsed() < TYPING_ACTION_INTERVAL)
+            || self.input.text_to_string().starts_with('/')
+        {
+            return;
+        }
+        self.typing_sent_at = Some(Instant::now());
+        if let Some(tx) = self.action_tx.as_ref() {
+            tx.send(Action::SendTypingAction(self.chat_id)).unwrap();
+        }
+    }
+    /// Complete the name of the slash command typed in the prompt.
+    /// If only one command matches, its name is completed, otherwise the
+    /// matching commands are shown in a popup.
+    fn complete_command(&mut self) {
+        let text = self.input.text_to_string();
+        let Some(prefix) = text.strip_prefix('/') else {
+            return;
+        };
+        if prefix.contains(char::is_whitespace) {
+            return;
+        }
+        let candidates: Vec<(String, String)> = self
+            .app_context
+            .command_registry()
+            .complete(prefix)
+            .into_iter()
+            .map(|command| (command.name.clone(), command.description.clone()))
+            .collect();
+        match candidates.as_slice() {
+            [] => {}
+            [(name, _)] => self.input.set_line(&format!("/{} ", name)),
+            _ => {
+                if let Some(tx) = self.action_tx.as_ref() {
+                    tx.send(Action::ShowPopup(Popup::List(
+                        "Commands".to_string(),
+                        candidates
+                            .into_iter()
+                            .map(|(name, description)| {
+                                PopupItem::new(
+                                    format!("/{} {}", name, description),
+                                    Action::CompleteCommand(name),
+                                )
+                            })
+                            .collect(),
+                    )))
+                    .unwrap();
+                }
+            }
+        }
+    }
+    /// Complete the `:shortcode` of an emoji typed before the cursor.
+    /// If only one emoji matches, the shortcode is replaced by it, otherwise
+    /// the matching emojis are shown in a popup.
+    ///
+    /// # Returns
+    /// * `bool` - Whether a shortcode is typed before the cursor.
+    fn complete_emoji(&mut self) -> bool {
+        let Some(shortcode) = self.input.shortcode_before_cursor() else {
+            return false;
+        };
+        let candidates = complete_shortcode(&shortcode);
+        match candidates.as_slice() {
+            [] => {}
+            [(emoji, _)] => self.input.replace_shortcode(emoji),
+            _ => {
+                if let Some(tx) = self.action_tx.as_ref() {
+                    tx.send(Action::ShowPopup(Popup::List(
+                        "Emoji".to_string(),
+                        candidates
+                            .into_iter()
+                            .map(|(emoji, shortcode)| {
+                                PopupItem::new(
+                                    format!("{} :{}:", emoji, shortcode),
+                                    Action::CompleteEmoji(emoji),
+                                )
+                            })
+                            .collect(),
+                    )))
+                    .unwrap();
+                }
+            }
+        }
+        true
+    }
     /// Update the input area of the `PromptWindow`.
     /// It is used to update the input area of the `PromptWindow` when a new
     /// line is inserted or deleted.
@@ -592,11 +1093,17 @@ impl PromptWindow {
 /// This trait allows the `PromptWindow` to be focused or unfocused.
 impl HandleFocus for PromptWindow {
     /// Set the `focused` flag for the `PromptWindow`.
+    /// The draft of the open chat is loaded in the input, if it is empty.
     fn focus(&mut self) {
         self.focused = true;
+        self.load_draft();
     }
     /// Set the `focused` flag for the `PromptWindow`.
+    /// The text of the input is saved as the draft of the chat.
     fn unfocus(&mut self) {
+        if self.focused {
+            self.save_draft();
+        }
         self.focused = false;
         self.input.mode = Mode::Normal;
         self.input.text = vec![vec![]];
@@ -720,9 +1227,17 @@ impl Component for PromptWindow {
                     self.input.move_cursor_to_next_word();
                 }
 
+                (KeyCode::Char('r'), Modifiers { control: true, .. }) => {
+                    self.show_draft_conflict();
+                }
+
                 (KeyCode::Enter, Modifiers { alt: true, .. }) => {
                     self.input.unselect_all();
+                    // The sent message clears both drafts.
+                    self.draft_conflict = None;
                     self.input.send_message(Arc::clone(&self.app_context));
+                    // TDLib stops the typing action once the message is sent.
+                    self.typing_sent_at = None;
                 }
 
                 (KeyCode::Backspace, Modifiers { control: true, .. })
@@ -745,6 +1260,10 @@ impl Component for PromptWindow {
                 ) => {
                     self.input.unselect_all();
                     self.input.insert(c);
+                    if c == ':' {
+                        self.input.replace_closed_shortcode();
+                    }
+                    self.send_typing_action();
                 }
 
                 (KeyCode::Backspace, ..) => {
@@ -757,9 +1276,17 @@ impl Component for PromptWindow {
                     self.input.delete();
                 }
 
+                (KeyCode::Tab, ..) => {
+                    self.input.unselect_all();
+                    if !self.complete_emoji() {
+                        self.complete_command();
+                    }
+                }
+
                 (KeyCode::Enter, ..) => {
                     self.input.unselect_all();
                     self.input.insert_newline();
+                    self.send_typing_action();
                 }
 
                 (KeyCode::Left, ..) => {
@@ -786,12 +1313,33 @@ impl Component for PromptWindow {
             Action::Paste(text) => {
                 self.input.unselect_all();
                 self.input.paste(text);
+                self.send_typing_action();
             }
+            Action::OpenChat(chat_id) => self.switch_chat(chat_id),
+            Action::ChatDraftChanged(chat_id) => self.check_draft_changed(chat_id),
+            Action::ResolveDraftConflict(resolution) => self.resolve_draft_conflict(resolution),
             Action::EditMessage(message_id, message) => {
                 self.input.edit_message(message_id, message);
             }
-            Action::ReplyMessage(message_id, _) => {
-                self.input.mode = Mode::Reply(message_id);
+            Action::ReplyMessage(message_id, _, quote) => {
+                self.input.mode = Mode::Reply(message_id, quote);
+            }
+            Action::CompleteCommand(name) => {
+                self.input.set_line(&format!("/{} ", name));
+            }
+            Action::ToggleMarkdownPreview => self.preview = !self.preview,
+            Action::CompleteEmoji(emoji) => {
+                self.input.unselect_all();
+                self.input.replace_shortcode(&emoji);
+            }
+            Action::InsertText(text) => {
+                self.input.unselect_all();
+                self.input.paste(text);
+            }
+            Action::InsertTemplate(text) => {
+                self.input.unselect_all();
+                self.input.paste(text);
+                self.input.template = true;
             }
             _ => {}
         }
@@ -811,19 +1359,34 @@ impl Component for PromptWindow {
             .text()
             .iter()
             .map(|line| {
+                // The line is shown in visual order and its grapheme clusters
+                // are grouped in spans with the same style, so that the
+                // characters of a cluster, like an emoji joined with a zero
+                // width joiner, are rendered together.
+                let graphemes = Input::graphemes(line);
+                let text = graphemes
+                    .iter()
+                    .map(|(grapheme, _)| grapheme.as_str())
+                    .collect::<Vec<&str>>();
+                let visual = visual_order(&text, is_rtl_paragraph(&text.concat()))
+                    .into_iter()
+                    .map(|(i, _)| &graphemes[i])
+                    .collect::<Vec<&(String, bool)>>();
                 Line::from(
-                    line.iter()
-                        .map(|cell| {
-                            if cell.selected {
+                    visual
+                        .chunk_by(|a, b| a.1 == b.1)
+                        .map(|graphemes| {
+                            let text = graphemes
+                                .iter()
+                                .map(|(grapheme, _)| grapheme.as_str())
+                                .collect::<String>();
+                            if graphemes[0].1 {
                                 Span::styled(
-                                    cell.c.to_string(),
+                                    text,
                                     self.app_context.style_prompt_message_text_selected(),
                                 )
                             } else {
-                                Span::styled(
-                                    cell.c.to_string(),
-                                    self.app_context.style_prompt_message_text(),
-                                )
+                                Span::styled(text, self.app_context.style_prompt_message_text())
                             }
                         })
                         .collect::<Vec<Span>>(),
@@ -833,6 +1396,11 @@ impl Component for PromptWindow {
 
         let (text, style_text, style_border_focused) = if self.focused {
             self.input.restore_prompt_size();
+            let text = if self.preview {
+                MessageEntry::format_message_content(&markdown::parse(&self.input.text_to_string()))
+            } else {
+                text
+            };
             (
                 text,
                 self.app_context.style_prompt(),
@@ -854,19 +1422,85 @@ impl Component for PromptWindow {
             )
         };
 
-        let block = Block::new()
+        // The staged attachments are shown as chips in the top border, above
+        // the text.
+        let chips = {
+            let tg_context = self.app_context.tg_context();
+            let attachments = tg_context.attachments();
+            let mut chips: Vec<String> = attachments
+                .items()
+                .iter()
+                .map(|attachment| attachment.chip())
+                .collect();
+            if attachments.is_recording() {
+                chips.push("🎤 recording…".to_string());
+            }
+            if let Some(self_destruct) = attachments.self_destruct() {
+                chips.push(format!("🔥 {}", self_destruct.label()));
+            }
+            chips
+        };
+
+        let mut block = Block::new()
             .border_set(collapsed_top_and_left_border_set)
             .border_style(style_border_focused)
             .borders(Borders::ALL)
             .title(self.name.as_str());
+        if !chips.is_empty() {
+            let mut spans = Vec::new();
+            for chip in chips {
+                if !spans.is_empty() {
+                    spans.push(Span::raw(" "));
+                }
+                spans.push(Span::styled(
+                    format!(" {} ", chip),
+                    self.app_context.style_prompt_message_text_selected(),
+                ));
+            }
+            block = block.title(Line::from(spans));
+        }
+        // The identity the messages are sent as is shown when it is not the
+        // user, like a channel or the group itself for an anonymous admin.
+        let send_as = {
+            let tg_context = self.app_context.tg_context();
+            tg_context.send_as_name(tg_context.open_chat_id())
+        };
+        if self.draft_conflict.is_some() {
+            block = block.title(
+                Line::from(Span::styled(
+                    " ⚠ draft changed on another device (ctrl+r) ",
+                    self.app_context.style_prompt_message_text_selected(),
+                ))
+                .alignment(Alignment::Right),
+            );
+        }
+        if self.preview && self.focused {
+            block = block.title(
+                Line::from(Span::styled(
+                    " preview ",
+                    self.app_context.style_prompt_message_text_selected(),
+                ))
+                .alignment(Alignment::Right),
+            );
+        }
+        if let Some(send_as) = send_as {
+            block = block.title(
+                Line::from(Span::styled(
+                    format!(" as {} ", send_as),
+                    self.app_context.style_prompt_message_text_selected(),
+                ))
+                .alignment(Alignment::Right),
+            );
+        }
 
         let input = Paragraph::new(text).style(style_text).block(block);
 
         frame.render_widget(input, area);
 
-        if self.focused {
+        // The cursor is hidden in the preview, where the text is not typed.
+        if self.focused && !self.preview {
             frame.set_cursor_position(Position {
-                x: area.x + self.input.cursor_x() as u16 + 1,
+                x: area.x + self.input.cursor_column() as u16 + 1,
                 y: area.y + self.input.cursor_y() as u16 + 1,
             });
         }
@@ -0,0 +1,282 @@
+use crate::{
+    action::{Action, Modifiers},
+    app_context::AppContext,
+    components::component_traits::{Component, HandleFocus},
+};
+use crossterm::event::KeyCode;
+use ratatui::{
+    layout::Rect,
+    symbols::border::PLAIN,
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, List, ListState},
+    Frame,
+};
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// `SearchResults` is a struct that represents the messages of all the chats
+/// found by a global search, or the unread mentions of the user. It is shown
+/// in place of the chat, and the message selected is opened in its chat.
+pub struct SearchResults {
+    /// The application context.
+    app_context: Arc<AppContext>,
+    /// The name of the `SearchResults`.
+    name: String,
+    /// An unbounded sender that send action for processing.
+    action_tx: Option<UnboundedSender<Action>>,
+    /// Indicates whether the `SearchResults` is focused or not.
+    focused: bool,
+    /// The text searched.
+    query: String,
+    /// Indicates whether the search is being typed.
+    typing: bool,
+    /// Indicates whether the unread mentions are shown instead of a search.
+    mentions: bool,
+    /// The state of the list of the results.
+    list_state: ListState,
+}
+/// Implementation of the `SearchResults` struct.
+impl SearchResults {
+    /// Create a new instance of the `SearchResults` struct.
+    ///
+    /// # Arguments
+    /// * `app_context` - An Arc wrapped AppContext struct.
+    ///
+    /// # Returns
+    /// * `Self` - The new instance of the `SearchResults` struct.
+    pub fn new(app_context: Arc<AppContext>) -> Self {
+        let name = "".to_string();
+        let action_tx = None;
+        let focused = false;
+        let query = String::new();
+        let typing = false;
+        let mentions = false;
+        let list_state = ListState::default();
+
+        SearchResults {
+            app_context,
+            name,
+            action_tx,
+            focused,
+            query,
+            typing,
+            mentions,
+            list_state,
+        }
+    }
+    /// Set the name of the `SearchResults`.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the `SearchResults`.
+    ///
+    /// # Returns
+    /// * `Self` - The modified instance of the `SearchResults`.
+    pub fn with_name(mut self, name: impl AsRef<str>) -> Self {
+        self.name = name.as_ref().to_string();
+        self
+    }
+    /// Start typing a new search, dropping the results of the previous one.
+    fn start_search(&mut self) {
+        self.query.clear();
+        self.typing = true;
+        self.mentions = false;
+        self.list_state.select(None);
+        self.app_context.tg_context().search_results().clear();
+    }
+    /// Show the unread mentions, loaded in place of the results.
+    fn show_mentions(&mut self) {
+        self.query.clear();
+        self.typing = false;
+        self.mentions = true;
+        self.list_state.select(Some(0));
+    }
+    /// Handle a key typed in the search. The messages are searched once the
+    /// search is confirmed with `enter`.
+    ///
+    /// # Arguments
+    /// * `key_code` - The typed key.
+    /// * `modifiers` - The modifiers of the typed key.
+    fn type_search(&mut self, key_code: KeyCode, modifiers: Modifiers) {
+        match key_code {
+            KeyCode::Char(c) if !modifiers.control && !modifiers.alt => self.query.push(c),
+            KeyCode::Backspace => {
+                self.query.pop();
+            }
+            KeyCode::Enter => {
+                self.typing = false;
+                if !self.query.trim().is_empty() {
+                    self.list_state.select(Some(0));
+                    self.app_context
+                        .action_tx()
+                        .send(Action::SearchMessages(self.query.clone()))
+                        .unwrap();
+                }
+            }
+            _ => {}
+        }
+    }
+    /// Handle a key pressed in the list of the results.
+    ///
+    /// # Arguments
+    /// * `key_code` - The pressed key.
+    fn navigate(&mut self, key_code: KeyCode) {
+        let len = self.app_context.tg_context().search_results().len();
+        match key_code {
+            KeyCode::Down | KeyCode::Char('j') if len > 0 => {
+                let i = self
+                    .list_state
+                    .selected()
+                    .map_or(0, |i| (i + 1).min(len - 1));
+                self.list_state.select(Some(i));
+            }
+            KeyCode::Up | KeyCode::Char('k') if len > 0 => {
+                let i = self
+                    .list_state
+                    .selected()
+                    .map_or(0, |i| i.saturating_sub(1));
+                self.list_state.select(Some(i));
+            }
+            KeyCode::Char('/') if !self.mentions => self.typing = true,
+            KeyCode::Char('d') if self.mentions => self.dismiss_selected(),
+            KeyCode::Enter => self.open_selected(),
+            _ => {}
+        }
+    }
+    /// Get the chat and the id of the selected message.
+    ///
+    /// # Returns
+    /// * `Option<(i64, i64)>` - The id of the chat and of the message, if any
+    ///   is selected.
+    fn selected_message(&self) -> Option<(i64, i64)> {
+        let i = self.list_state.selected()?;
+        self.app_context
+            .tg_context()
+            .search_results()
+            .get(i)
+            .map(|(chat_id, message_entry)| (*chat_id, message_entry.id()))
+    }
+    /// Open the selected message in its chat.
+    fn open_selected(&self) {
+        if let Some((chat_id, message_id)) = self.selected_message() {
+            self.app_context
+                .action_tx()
+                .send(Action::OpenMessage(chat_id, message_id))
+                .unwrap();
+        }
+    }
+    /// Dismiss the selected mention, which is marked as read.
+    fn dismiss_selected(&self) {
+        if let Some((chat_id, message_id)) = self.selected_message() {
+            self.app_context
+                .action_tx()
+                .send(Action::DismissMention(chat_id, message_id))
+                .unwrap();
+        }
+    }
+}
+
+/// Implement the `HandleFocus` trait for the `SearchResults` struct.
+/// This trait allows the `SearchResults` to be focused or unfocused.
+impl HandleFocus for SearchResults {
+    /// Set the `focused` flag for the `SearchResults`.
+    fn focus(&mut self) {
+        self.focused = true;
+    }
+    /// Set the `focused` flag for the `SearchResults`.
+    fn unfocus(&mut self) {
+        self.focused = false;
+        self.typing = false;
+    }
+}
+
+/// Implement the `Component` trait for the `SearchResults` struct.
+impl Component for SearchResults {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> std::io::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn is_typing(&self) -> bool {
+        self.typing
+    }
+
+    fn has_selection(&self) -> bool {
+        self.list_state.selected().is_some()
+    }
+
+    fn update(&mut self, action: Action) {
+        match action {
+            Action::ChatListSearch => self.start_search(),
+            Action::ShowMentions => self.show_mentions(),
+            Action::Key(key_code, modifiers) if self.typing => {
+                self.type_search(key_code, modifiers)
+            }
+            Action::Key(key_code, _) => self.navigate(key_code),
+            Action::UnfocusComponent if self.typing => self.typing = false,
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame<'_>, area: Rect) -> std::io::Result<()> {
+        let tg_context = self.app_context.tg_context();
+        let results = tg_context.search_results().clone();
+        // The first result is selected while the search is running.
+        if let Some(i) = self.list_state.selected() {
+            if i >= results.len() && !results.is_empty() {
+                self.list_state.select(results.len().checked_sub(1));
+            }
+        }
+        let items: Vec<Text> = results
+            .iter()
+            .map(|(chat_id, message_entry)| {
+                let chat_name = tg_context
+                    .name_from_chats(*chat_id)
+                    .unwrap_or_else(|| chat_id.to_string());
+                let mut text = Text::from(Line::from(vec![
+                    Span::styled(chat_name, self.app_context.style_chat_list_item_chat_name()),
+                    Span::raw(" | "),
+                    message_entry.timestamp().get_span_styled(&self.app_context),
+                ]));
+                text.extend(
+                    message_entry
+                        .get_lines_styled_with_style(
+                            self.app_context.style_chat_list_item_message_content(),
+                            -1,
+                        )
+                        .into_iter()
+                        .take(1),
+                );
+                text
+            })
+            .collect();
+
+        let cursor = if self.typing { "▏" } else { "" };
+        let title = if self.mentions {
+            format!(" Unread mentions ({}) - d to dismiss ", results.len())
+        } else {
+            format!(
+                " {}: {}{} ({}) ",
+                self.name,
+                self.query,
+                cursor,
+                results.len()
+            )
+        };
+        let style_border = if self.focused {
+            self.app_context.style_border_component_focused()
+        } else {
+            self.app_context.style_chat()
+        };
+        let block = Block::default()
+            .border_set(PLAIN)
+            .border_style(style_border)
+            .borders(Borders::ALL)
+            .title(title);
+        let list = List::new(items)
+            .block(block)
+            .style(self.app_context.style_chat())
+            .highlight_style(self.app_context.style_chat_list_item_selected());
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+        Ok(())
+    }
+}
@@ -2,18 +2,38 @@ use {
     crate::{
         action::Action,
         app_context::AppContext,
+        component_name::ComponentName,
         components::component_traits::{Component, HandleFocus},
         event::Event,
+        utils::{human_size, resident_memory},
     },
     ratatui::{
         layout::{Alignment, Rect},
         text::{Line, Span},
         widgets::{block::Block, Borders, Paragraph, Wrap},
     },
-    std::sync::Arc,
+    std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    },
     tokio::sync::mpsc::UnboundedSender,
 };
 
+/// How often the memory readout of the status bar is updated.
+const MEMORY_HUD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The state of the focused component, used by the status bar to show the
+/// keys of the most useful actions of the component.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct FocusState {
+    /// The focused component, if any.
+    pub component: Option<ComponentName>,
+    /// Whether an item of the focused component is selected.
+    pub has_selection: bool,
+    /// Whether the focused component takes the typed keys as text.
+    pub is_typing: bool,
+}
+
 /// `StatusBar` is a struct that represents a status bar.
 /// It is responsible for managing the layout and rendering of the status bar.
 pub struct StatusBar {
@@ -29,6 +49,12 @@ pub struct StatusBar {
     terminal_area: Rect,
     /// The last key pressed.
     last_key: Event,
+    /// The state of the focused component.
+    focus_state: FocusState,
+    /// Whether the memory used and the messages kept in it are shown.
+    show_memory_hud: bool,
+    /// The last memory readout and when it was taken.
+    memory_hud: Option<(Instant, String)>,
 }
 /// Implementation of `StatusBar` struct.
 impl StatusBar {
@@ -45,6 +71,7 @@ impl StatusBar {
         let terminal_area = Rect::default();
         let last_key = Event::Unknown;
         let focused = false;
+        let focus_state = FocusState::default();
 
         StatusBar {
             app_context,
@@ -53,6 +80,9 @@ impl StatusBar {
             terminal_area,
             last_key,
             focused,
+            focus_state,
+            show_memory_hud: false,
+            memory_hud: None,
         }
     }
     /// Set the name of the `StatusBar`.
@@ -66,6 +96,138 @@ impl StatusBar {
         self.name = name.as_ref().to_string();
         self
     }
+    /// Get the key hints of the focused component.
+    /// The keys are taken from the keymap, so that they follow the bindings of
+    /// the user, and the actions without a key are skipped.
+    ///
+    /// # Returns
+    /// * `Vec<(String, &'static str)>` - The keys and the labels of the
+    ///   actions.
+    fn key_hints(&self) -> Vec<(String, &'static str)> {
+        use ComponentName::{Chat, ChatList, CoreWindow, Popup, Prompt};
+        let hints = match self.focus_state {
+            FocusState {
+                is_typing: true, ..
+            } => vec![(CoreWindow, Action::UnfocusComponent, "cancel")],
+            FocusState {
+                component: Some(ChatList),
+                ..
+            } => vec![
+                (ChatList, Action::ChatListNext, "down"),
+                (ChatList, Action::ChatListPrevious, "up"),
+                (ChatList, Action::ChatListOpen, "open"),
+                (ChatList, Action::ChatListToggleFavorite, "favorite"),
+                (ChatList, Action::ChatListDelete, "delete"),
+                (CoreWindow, Action::FocusComponent(Prompt), "prompt"),
+            ],
+            FocusState {
+                component: Some(Chat),
+                has_selection: true,
+                ..
+            } => vec![
+                (Chat, Action::ShowChatWindowReply, "reply"),
+                (Chat, Action::ChatWindowEdit, "edit"),
+                (Chat, Action::ChatWindowCopy, "copy"),
+                (Chat, Action::ChatWindowDeleteForEveryone, "delete"),
+                (Chat, Action::ChatWindowShowDetails, "details"),
+                (Chat, Action::ChatWindowUnselect, "unselect"),
+            ],
+            FocusState {
+                component: Some(Chat),
+                ..
+            } => vec![
+                (Chat, Action::ChatWindowPrevious, "select"),
+                (Chat, Action::ChatWindowSearch, "search"),
+                (Chat, Action::ChatWindowSearchNext, "next match"),
+                (CoreWindow, Action::FocusComponent(Prompt), "prompt"),
+            ],
+            FocusState {
+                component: Some(Prompt),
+                ..
+            } => vec![
+                (CoreWindow, Action::ShowSendFile, "file"),
+                (CoreWindow, Action::ShowAttachments, "attachments"),
+                (CoreWindow, Action::ShowEmojiPicker, "emoji"),
+                (CoreWindow, Action::UnfocusComponent, "leave"),
+            ],
+            FocusState {
+                component: Some(Popup),
+                ..
+            } => vec![(CoreWindow, Action::UnfocusComponent, "close")],
+            _ => vec![
+                (CoreWindow, Action::FocusComponent(ChatList), "chats"),
+                (CoreWindow, Action::FocusComponent(Chat), "chat"),
+                (CoreWindow, Action::FocusComponent(Prompt), "prompt"),
+                (CoreWindow, Action::ShowRecentChats, "recent"),
+                (CoreWindow, Action::TryQuit, "quit"),
+            ],
+        };
+        let keymap_config = self.app_context.keymap_config();
+        let mut key_hints = Vec::new();
+        if self.focus_state.is_typing {
+            key_hints.push(("Enter".to_string(), "keep"));
+        }
+        for (component_name, action, label) in hints {
+            // The shortest key is shown when an action has several keys.
+            let key = keymap_config
+                .get_key_of_single_action(component_name, action)
+                .iter()
+                .map(Event::to_string)
+                .min_by_key(|key| (key.len(), key.clone()));
+            if let Some(key) = key {
+                key_hints.push((key, label));
+            }
+        }
+        key_hints
+    }
+
+    /// Get the memory used by the process and the number of messages kept in
+    /// it, read again at most every second.
+    ///
+    /// # Returns
+    /// * `String` - The memory readout.
+    fn memory_hud(&mut self) -> String {
+        if let Some((taken_at, readout)) = &self.memory_hud {
+            if taken_at.elapsed() < MEMORY_HUD_INTERVAL {
+                return readout.clone();
+            }
+        }
+        let process = resident_memory().map_or("?".to_string(), human_size);
+        let tg_context = self.app_context.tg_context();
+        let readout = format!(
+            "{} · {} messages · {} chats",
+            process,
+            tg_context.open_chat_messages().len() + tg_context.preview_chat_messages().len(),
+            tg_context.chats().len(),
+        );
+        self.memory_hud = Some((Instant::now(), readout.clone()));
+        readout
+    }
+
+    /// Get the name of the chat that the reopen action switches to, that is
+    /// the chat open before the current one, with the key of the action.
+    ///
+    /// # Returns
+    /// * `Option<String>` - The name of the previous chat and the key, if any.
+    fn previous_chat(&self) -> Option<String> {
+        let chat_id = *self.app_context.local_state().recent_chats(1).first()?;
+        let name = self
+            .app_context
+            .tg_context()
+            .name_from_chats(chat_id)
+            .unwrap_or_else(|| chat_id.to_string());
+        let key = self
+            .app_context
+            .keymap_config()
+            .get_key_of_single_action(ComponentName::CoreWindow, Action::ReopenLastChat)
+            .iter()
+            .map(Event::to_string)
+            .min_by_key(|key| (key.len(), key.clone()));
+        Some(match key {
+            Some(key) => format!("{} ({})", name, key),
+            None => name,
+        })
+    }
 }
 
 /// Implement the `HandleFocus` trait for the `StatusBar` struct.
@@ -94,6 +256,11 @@ impl Component for StatusBar {
                 self.terminal_area = area;
             }
             Action::Key(key, modifiers) => self.last_key = Event::Key(key, modifiers.into()),
+            Action::UpdateFocusState(focus_state) => self.focus_state = focus_state,
+            Action::ToggleMemoryHud => {
+                self.show_memory_hud = !self.show_memory_hud;
+                self.memory_hud = None;
+            }
             _ => {}
         }
     }
@@ -104,7 +271,7 @@ impl Component for StatusBar {
             .tg_context()
             .name_of_open_chat_id()
             .unwrap_or_default();
-        let text = vec![Line::from(vec![
+        let mut text = vec![Line::from(vec![
             Span::styled(
                 "Press ",
                 self.app_context.style_status_bar_message_quit_text(),
@@ -129,6 +296,32 @@ impl Component for StatusBar {
                 selected_chat,
                 self.app_context.style_status_bar_open_chat_name(),
             ),
+        ])];
+        if let Some(previous_chat) = self.previous_chat() {
+            text[0].spans.extend([
+                Span::styled(" ⇄ ", self.app_context.style_status_bar_open_chat_text()),
+                Span::styled(
+                    previous_chat,
+                    self.app_context.style_status_bar_open_chat_name(),
+                ),
+            ]);
+        }
+        let account = self
+            .app_context
+            .account_manager()
+            .active_name()
+            .map(str::to_string);
+        if let Some(account) = account {
+            text[0].spans.extend([
+                Span::raw("     "),
+                Span::styled(
+                    "Account: ",
+                    self.app_context.style_status_bar_open_chat_text(),
+                ),
+                Span::styled(account, self.app_context.style_status_bar_open_chat_name()),
+            ]);
+        }
+        text[0].spans.extend([
             //
             Span::raw("     "),
             Span::styled(
@@ -151,7 +344,127 @@ impl Component for StatusBar {
                 self.terminal_area.height.to_string(),
                 self.app_context.style_status_bar_size_info_numbers(),
             ),
-        ])];
+        ]);
+        if self.show_memory_hud {
+            let memory_hud = self.memory_hud();
+            text[0].spans.extend([
+                Span::raw("     "),
+                Span::styled(
+                    "Memory: ",
+                    self.app_context.style_status_bar_size_info_text(),
+                ),
+                Span::styled(
+                    memory_hud,
+                    self.app_context.style_status_bar_size_info_numbers(),
+                ),
+            ]);
+        }
+        let tg_context = self.app_context.tg_context();
+        let send_queue = tg_context.send_queue();
+        let (active, queued) = (send_queue.active().len(), send_queue.queued_len());
+        if active > 0 || queued > 0 {
+            let (uploaded, size) = send_queue.progress();
+            let mut uploads = format!("{} ({}%)", active, uploaded * 100 / size.max(1));
+            if queued > 0 {
+                uploads.push_str(&format!(" +{} queued", queued));
+            }
+            text[0].spans.extend([
+                Span::raw("     "),
+                Span::styled(
+                    "Uploads: ",
+                    self.app_context.style_status_bar_size_info_text(),
+                ),
+                Span::styled(
+                    uploads,
+                    self.app_context.style_status_bar_size_info_numbers(),
+                ),
+            ]);
+        }
+        let playing = {
+            let audio_player = self.app_context.audio_player();
+            let paused = if audio_player.is_paused() { "⏸ " } else { "" };
+            audio_player.current().map(|(position, len, track)| {
+                format!("{}{} ({}/{})", paused, track.metadata.name(), position, len)
+            })
+        };
+        let available_update = self
+            .app_context
+            .available_update()
+            .as_ref()
+            .map(|release| format!("tgt {} (/update)", release.version));
+        if let Some(available_update) = available_update {
+            text[0].spans.extend([
+                Span::raw("     "),
+                Span::styled(
+                    "Update available: ",
+                    self.app_context.style_status_bar_open_chat_text(),
+                ),
+                Span::styled(
+                    available_update,
+                    self.app_context.style_status_bar_open_chat_name(),
+                ),
+            ]);
+        }
+        let birthdays = tg_context
+            .birthdays_today()
+            .iter()
+            .filter_map(|user_id| tg_context.try_name_from_chats_or_users(*user_id))
+            .collect::<Vec<String>>();
+        if !birthdays.is_empty() {
+            text[0].spans.extend([
+                Span::raw("     "),
+                Span::styled("🎂 ", self.app_context.style_status_bar_open_chat_text()),
+                Span::styled(
+                    birthdays.join(", "),
+                    self.app_context.style_status_bar_open_chat_name(),
+                ),
+            ]);
+        }
+        let chats = tg_context.chats_index().len();
+        let sync_progress = tg_context.sync_state().progress(chats);
+        if let Some(sync_progress) = sync_progress {
+            text[0].spans.extend([
+                Span::raw("     "),
+                Span::styled(
+                    "⟳ Syncing: ",
+                    self.app_context.style_status_bar_open_chat_text(),
+                ),
+                Span::styled(
+                    sync_progress,
+                    self.app_context.style_status_bar_open_chat_name(),
+                ),
+            ]);
+        }
+        if tg_context.presence().is_away() {
+            text[0].spans.extend([
+                Span::raw("     "),
+                Span::styled("💤 ", self.app_context.style_status_bar_open_chat_text()),
+                Span::styled("away", self.app_context.style_status_bar_open_chat_name()),
+            ]);
+        }
+        if let Some(playing) = playing {
+            text[0].spans.extend([
+                Span::raw("     "),
+                Span::styled("♪ ", self.app_context.style_status_bar_open_chat_text()),
+                Span::styled(playing, self.app_context.style_status_bar_open_chat_name()),
+            ]);
+        }
+        if self.app_context.app_config().show_key_hints {
+            let mut hints = Line::default();
+            for (key, label) in self.key_hints() {
+                if !hints.spans.is_empty() {
+                    hints.spans.push(Span::raw("  "));
+                }
+                hints.spans.extend([
+                    Span::styled(key, self.app_context.style_status_bar_press_key_key()),
+                    Span::styled(
+                        format!(" {}", label),
+                        self.app_context.style_status_bar_press_key_text(),
+                    ),
+                ]);
+            }
+            text.push(hints);
+        }
 
         let paragraph = Paragraph::new(text)
             .block(Block::new().title(self.name.as_str()).borders(Borders::ALL))
@@ -3,9 +3,12 @@ use {
         action::Action,
         app_context::AppContext,
         components::component_traits::{Component, HandleFocus},
+        tg::message_entry::DateTimeEntry,
+        title_template::{TitlePart, TitleValues},
     },
     ratatui::{
         layout::{Alignment, Constraint, Direction, Layout, Rect},
+        style::Modifier,
         text::{Line, Span},
         widgets::{block::Block, Borders, Paragraph, Wrap},
     },
@@ -100,38 +103,52 @@ impl Component for TitleBar {
             .constraints([Constraint::Percentage(0), Constraint::Percentage(100)].as_ref())
             .split(area);
 
-        let name: Vec<char> = self.name.chars().collect::<Vec<char>>();
-        // Span::raw(" - A TUI for Telegram"),
-        let text = vec![Line::from(vec![
-            Span::styled(
-                name[0].to_string(),
-                self.app_context.style_title_bar_title1(),
-            ),
-            Span::styled(
-                name[1].to_string(),
-                self.app_context.style_title_bar_title2(),
-            ),
-            Span::styled(
-                name[2].to_string(),
-                self.app_context.style_title_bar_title3(),
-            ),
-            Span::styled(" - ", self.app_context.style_title_bar_title1()),
-            Span::styled("A", self.app_context.style_title_bar_title2()),
-            Span::styled(" T", self.app_context.style_title_bar_title3()),
-            Span::styled("U", self.app_context.style_title_bar_title1()),
-            Span::styled("I", self.app_context.style_title_bar_title2()),
-            Span::styled(" f", self.app_context.style_title_bar_title3()),
-            Span::styled("o", self.app_context.style_title_bar_title1()),
-            Span::styled("r", self.app_context.style_title_bar_title2()),
-            Span::styled(" T", self.app_context.style_title_bar_title3()),
-            Span::styled("e", self.app_context.style_title_bar_title1()),
-            Span::styled("l", self.app_context.style_title_bar_title2()),
-            Span::styled("e", self.app_context.style_title_bar_title3()),
-            Span::styled("g", self.app_context.style_title_bar_title1()),
-            Span::styled("r", self.app_context.style_title_bar_title2()),
-            Span::styled("a", self.app_context.style_title_bar_title3()),
-            Span::styled("m", self.app_context.style_title_bar_title1()),
-        ])];
+        let template = self.app_context.app_config().title_bar_template.clone();
+        let tg_context = self.app_context.tg_context();
+        let values = TitleValues {
+            app_name: self.name.clone(),
+            chat_title: if template.uses(&TitlePart::ChatTitle) {
+                tg_context.name_of_open_chat_id().unwrap_or_default()
+            } else {
+                String::new()
+            },
+            unread_total: if template.uses(&TitlePart::UnreadTotal) {
+                tg_context.unread_total()
+            } else {
+                0
+            },
+            connection: tg_context.connection_label().to_string(),
+            time: DateTimeEntry::now().format("%H:%M").to_string(),
+        };
+        let title = template.fill(&values);
+        // The characters take the three colors of the title in turn, the
+        // spaces take the color of the character after them.
+        let styles = [
+            self.app_context.style_title_bar_title1(),
+            self.app_context.style_title_bar_title2(),
+            self.app_context.style_title_bar_title3(),
+        ];
+        let mut spans = vec![];
+        let mut word = String::new();
+        for c in title.chars() {
+            word.push(c);
+            if !c.is_whitespace() {
+                let style = styles[spans.len() % styles.len()];
+                spans.push(Span::styled(std::mem::take(&mut word), style));
+            }
+        }
+        if !word.is_empty() {
+            spans.push(Span::raw(word));
+        }
+        if self.app_context.cli_args().safe_mode() {
+            spans.push(Span::styled(
+                "  SAFE MODE: custom configuration disabled ",
+                self.app_context
+                    .style_title_bar_title1()
+                    .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+            ));
+        }
+        let text = vec![Line::from(spans)];
         let block = Block::new().borders(Borders::ALL);
         let paragraph = Paragraph::new(text)
             .block(block.clone())
@@ -0,0 +1,912 @@
+use {
+    crate::{
+        action::Action,
+        configs::{
+            config_theme::ThemeStyle,
+            config_type::ConfigType,
+            custom::{self, telegram_custom::MESSAGE_UNLOAD_DELAY_RANGE},
+        },
+        event::Event,
+    },
+    lazy_static::lazy_static,
+    serde::de::DeserializeOwned,
+    std::{
+        fmt::{Display, Formatter},
+        ops::Range,
+        path::Path,
+        str::FromStr,
+        sync::Mutex,
+    },
+    toml::{Table, Value},
+};
+
+lazy_static! {
+    /// The diagnostics collected while loading the configuration files.
+    static ref DIAGNOSTICS: Mutex<Vec<ConfigDiagnostic>> = Mutex::new(vec![]);
+}
+
+/// The tables whose keys are chosen by the user, so they are not checked
+/// against the keys of the default configuration.
+const FREE_FORM_TABLES: &[&str] = &["palette"];
+/// The maximum edit distance between a misspelled name and a suggested name.
+const MAX_SUGGESTION_DISTANCE: usize = 3;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+/// `Severity` is an enum that represents how serious a problem of a
+/// configuration file is.
+pub enum Severity {
+    /// The file can not be used, so the default configuration is used
+    /// instead.
+    Error,
+    /// The file is used, but part of it is ignored.
+    Warning,
+}
+/// Implement the `Display` trait for the `Severity` enum.
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// `ConfigDiagnostic` is a struct that represents a problem found in a
+/// configuration file.
+pub struct ConfigDiagnostic {
+    /// The path of the configuration file.
+    pub file: String,
+    /// The line of the problem, starting from 1, if known.
+    pub line: Option<usize>,
+    /// The column of the problem, starting from 1, if known.
+    pub column: Option<usize>,
+    /// The offending key or value, if any.
+    pub key: Option<String>,
+    /// How serious the problem is.
+    pub severity: Severity,
+    /// The description of the problem.
+    pub message: String,
+    /// A suggested fix, if any.
+    pub suggestion: Option<String>,
+}
+/// Implementation of the `ConfigDiagnostic` struct.
+impl ConfigDiagnostic {
+    /// Create a new diagnostic.
+    ///
+    /// # Arguments
+    /// * `file` - The path of the configuration file.
+    /// * `severity` - How serious the problem is.
+    /// * `message` - The description of the problem.
+    ///
+    /// # Returns
+    /// * `Self` - The new diagnostic, without position, key and suggestion.
+    pub fn new(file: impl AsRef<str>, severity: Severity, message: impl AsRef<str>) -> Self {
+        Self {
+            file: file.as_ref().to_string(),
+            line: None,
+            column: None,
+            key: None,
+            severity,
+            message: message.as_ref().to_string(),
+            suggestion: None,
+        }
+    }
+    /// Set the position of the problem.
+    fn at(mut self, position: Option<(usize, usize)>) -> Self {
+        if let Some((line, column)) = position {
+            self.line = Some(line);
+            self.column = Some(column);
+        }
+        self
+    }
+    /// Set the offending key or value.
+    fn with_key(mut self, key: impl AsRef<str>) -> Self {
+        self.key = Some(key.as_ref().to_string());
+        self
+    }
+    /// Set the suggested fix.
+    fn with_suggestion(mut self, suggestion: Option<String>) -> Self {
+        self.suggestion = suggestion;
+        self
+    }
+}
+/// Implement the `Display` trait for the `ConfigDiagnostic` struct.
+/// The diagnostic is shown as `file:line:column: severity: message`, followed
+/// by the suggested fix on a new line.
+impl Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.file)?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        if let Some(column) = self.column {
+            write!(f, ":{}", column)?;
+        }
+        write!(f, ": {}: {}", self.severity, self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "\n  help: {}", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Record the diagnostics of a configuration file, so that they can be shown
+/// when the application starts. The same diagnostic is recorded once, since
+/// some files are loaded by more than one configuration, like the theme and
+/// the palette.
+///
+/// # Arguments
+/// * `diagnostics` - The diagnostics to record.
+pub fn record(diagnostics: &[ConfigDiagnostic]) {
+    let mut recorded = DIAGNOSTICS.lock().unwrap();
+    for diagnostic in diagnostics {
+        match diagnostic.severity {
+            Severity::Error => tracing::error!("{}", diagnostic),
+            Severity::Warning => tracing::warn!("{}", diagnostic),
+        }
+        if !recorded.contains(diagnostic) {
+            recorded.push(diagnostic.clone());
+        }
+    }
+}
+
+/// Get the diagnostics recorded while loading the configuration files.
+///
+/// # Returns
+/// * `Vec<ConfigDiagnostic>` - The recorded diagnostics.
+pub fn diagnostics() -> Vec<ConfigDiagnostic> {
+    DIAGNOSTICS.lock().unwrap().clone()
+}
+
+/// Check whether some diagnostics prevent a file from being used.
+///
+/// # Arguments
+/// * `diagnostics` - The diagnostics of the file.
+///
+/// # Returns
+/// * `bool` - Whether there is at least an error.
+pub fn has_errors(diagnostics: &[ConfigDiagnostic]) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
+}
+
+/// Validate a custom configuration file against the default configuration
+/// file of its type, which is used as the schema.
+///
+/// # Arguments
+/// * `config_type` - The type of the configuration.
+/// * `file_path` - The path of the custom configuration file.
+///
+/// # Returns
+/// * `Vec<ConfigDiagnostic>` - The problems found in the file.
+pub fn validate_file<R>(config_type: ConfigType, file_path: &Path) -> Vec<ConfigDiagnostic>
+where
+    R: DeserializeOwned,
+{
+    let file = file_path.display().to_string();
+    let content = match std::fs::read_to_string(file_path) {
+        Ok(content) => content,
+        Err(e) => {
+            return vec![ConfigDiagnostic::new(
+                file,
+                Severity::Error,
+                format!("the file can not be read: {}", e),
+            )]
+        }
+    };
+    let default_content = custom::default_config_file_path_of(config_type)
+        .and_then(std::fs::read_to_string)
+        .unwrap_or_default();
+    validate::<R>(config_type, &file, &content, &default_content)
+}
+
+/// Validate the content of a configuration file.
+/// The syntax is checked first, then the keys are compared with the keys of
+/// the default configuration, the values are checked against the types of
+/// the configuration and the commands, keys and colors are checked.
+///
+/// # Arguments
+/// * `config_type` - The type of the configuration.
+/// * `file` - The path of the configuration file, shown in the diagnostics.
+/// * `content` - The content of the configuration file.
+/// * `default_content` - The content of the default configuration file.
+///
+/// # Returns
+/// * `Vec<ConfigDiagnostic>` - The problems found in the file.
+pub fn validate<R>(
+    config_type: ConfigType,
+    file: &str,
+    content: &str,
+    default_content: &str,
+) -> Vec<ConfigDiagnostic>
+where
+    R: DeserializeOwned,
+{
+    let table = match toml::from_str::<Table>(content) {
+        Ok(table) => table,
+        Err(e) => {
+            return vec![ConfigDiagnostic::new(file, Severity::Error, e.message())
+                .at(e.span().map(|span| line_column(content, span.start)))
+                .with_suggestion(Some(
+                    "Check the quotes, the brackets and the commas near this position.".to_string(),
+                ))]
+        }
+    };
+    let schema = toml::from_str::<Table>(default_content).unwrap_or_default();
+
+    let mut diagnostics = vec![];
+    check_keys(&table, &schema, "", file, content, &mut diagnostics);
+    if let Err(e) = toml::from_str::<R>(content) {
+        diagnostics.push(type_diagnostic(&e, file, content, &schema));
+    }
+    match config_type {
+        ConfigType::Keymap => check_keymap(&table, &schema, file, content, &mut diagnostics),
+        ConfigType::Palette | ConfigType::Theme => {
+            check_colors(&table, &schema, file, content, &mut diagnostics)
+        }
+        ConfigType::Telegram => check_telegram(&table, &schema, file, content, &mut diagnostics),
+        _ => {}
+    }
+    diagnostics
+}
+
+/// Compare the keys of a table with the keys of the same table of the
+/// default configuration. Unknown keys are reported as warnings, since they
+/// are ignored.
+///
+/// # Arguments
+/// * `table` - The table of the custom configuration.
+/// * `schema` - The table of the default configuration.
+/// * `path` - The dotted path of the table, empty for the root.
+/// * `file` - The path of the configuration file.
+/// * `content` - The content of the configuration file.
+/// * `diagnostics` - The vector where the problems are collected.
+fn check_keys(
+    table: &Table,
+    schema: &Table,
+    path: &str,
+    file: &str,
+    content: &str,
+    diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+    for (key, value) in table {
+        let key_path = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+        let Some(default_value) = schema.get(key) else {
+            let message = if path.is_empty() {
+                format!("unknown key `{}`", key)
+            } else {
+                format!("unknown key `{}` in `{}`", key, path)
+            };
+            let suggestion = match suggest(key, schema.keys()) {
+                Some(name) => format!("Did you mean `{}`?", name),
+                None => "Remove it, it is ignored.".to_string(),
+            };
+            diagnostics.push(
+                ConfigDiagnostic::new(file, Severity::Warning, message)
+                    .at(locate_key(content, key))
+                    .with_key(&key_path)
+                    .with_suggestion(Some(suggestion)),
+            );
+            continue;
+        };
+        if FREE_FORM_TABLES.contains(&key.as_str()) {
+            continue;
+        }
+        match (value, default_value) {
+            (Value::Table(table), Value::Table(schema)) if !schema.is_empty() => {
+                check_keys(table, schema, &key_path, file, content, diagnostics)
+            }
+            (Value::Array(items), Value::Array(default_items)) => {
+                // The entries of an array share the keys of the entries of
+                // the default array.
+                let schema = default_items
+                    .iter()
+                    .filter_map(Value::as_table)
+                    .flat_map(|table| table.clone())
+                    .collect::<Table>();
+                if schema.is_empty() {
+                    continue;
+                }
+                for table in items.iter().filter_map(Value::as_table) {
+                    check_keys(table, &schema, &key_path, file, content, diagnostics);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Create the diagnostic of a value whose type does not match the
+/// configuration.
+///
+/// # Arguments
+/// * `error` - The deserialization error.
+/// * `file` - The path of the configuration file.
+/// * `content` - The content of the configuration file.
+/// * `schema` - The default configuration.
+///
+/// # Returns
+/// * `ConfigDiagnostic` - The diagnostic.
+fn type_diagnostic(
+    error: &toml::de::Error,
+    file: &str,
+    content: &str,
+    schema: &Table,
+) -> ConfigDiagnostic {
+    let key = error.span().and_then(|span| key_before(content, span));
+    let default_value = key.as_deref().and_then(|key| find_value(schema, key));
+    let suggestion = match (&key, default_value) {
+        (Some(key), Some(value)) => format!(
+            "Use a value of the same type as the default, for example `{} = {}`.",
+            key, value
+        ),
+        _ => "Check the type of the value, for example the quotes of a string.".to_string(),
+    };
+    let diagnostic = ConfigDiagnostic::new(file, Severity::Error, error.message())
+        .at(error.span().map(|span| line_column(content, span.start)))
+        .with_suggestion(Some(suggestion));
+    match key {
+        Some(key) => diagnostic.with_key(key),
+        None => diagnostic,
+    }
+}
+
+/// Check the commands and the keys of the entries of a keymap configuration.
+///
+/// # Arguments
+/// * `table` - The keymap configuration.
+/// * `schema` - The default keymap configuration.
+/// * `file` - The path of the configuration file.
+/// * `content` - The content of the configuration file.
+/// * `diagnostics` - The vector where the problems are collected.
+fn check_keymap(
+    table: &Table,
+    schema: &Table,
+    file: &str,
+    content: &str,
+    diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+    let keymap_entries = |table: &Table| -> Vec<Table> {
+        table
+            .values()
+            .filter_map(|section| section.get("keymap")?.as_array())
+            .flatten()
+            .filter_map(Value::as_table)
+            .cloned()
+            .collect()
+    };
+    let mut commands = keymap_entries(schema)
+        .iter()
+        .filter_map(|entry| entry.get("command")?.as_str().map(str::to_string))
+        .collect::<Vec<String>>();
+    commands.sort();
+    commands.dedup();
+
+    for entry in keymap_entries(table) {
+        if let Some(command) = entry.get("command").and_then(Value::as_str) {
+            if Action::from_str(command).is_err() {
+                let suggestion = match suggest(command, commands.iter()) {
+                    Some(name) => format!("Did you mean `{}`?", name),
+                    None => "See the default keymap.toml for the available commands.".to_string(),
+                };
+                diagnostics.push(
+                    ConfigDiagnostic::new(
+                        file,
+                        Severity::Error,
+                        format!("unknown command `{}`", command),
+                    )
+                    .at(locate_string(content, command))
+                    .with_key(command)
+                    .with_suggestion(Some(suggestion)),
+                );
+            }
+        }
+        let keys = entry.get("keys").and_then(Value::as_array);
+        for key in keys.into_iter().flatten().filter_map(Value::as_str) {
+            if Event::from_str(key).is_err() {
+                diagnostics.push(
+                    ConfigDiagnostic::new(file, Severity::Error, format!("unknown key `{}`", key))
+                        .at(locate_string(content, key))
+                        .with_key(key)
+                        .with_suggestion(Some(
+                            "Use a key like `q`, `enter`, `ctrl+c` or `alt+left`.".to_string(),
+                        )),
+                );
+            }
+        }
+    }
+}
+
+/// Check the values of the telegram configuration that TDLib accepts only in
+/// a range, and the databases that contradict each other. An invalid value is
+/// a warning, since the default of TDLib is used instead.
+///
+/// # Arguments
+/// * `table` - The telegram configuration.
+/// * `schema` - The default telegram configuration.
+/// * `file` - The path of the configuration file.
+/// * `content` - The content of the configuration file.
+/// * `diagnostics` - The vector where the problems are collected.
+fn check_telegram(
+    table: &Table,
+    schema: &Table,
+    file: &str,
+    content: &str,
+    diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+    const KEY: &str = "message_unload_delay";
+    if let Some(delay) = table.get(KEY).and_then(Value::as_integer) {
+        if delay != 0 && !MESSAGE_UNLOAD_DELAY_RANGE.contains(&delay) {
+            diagnostics.push(
+                ConfigDiagnostic::new(
+                    file,
+                    Severity::Warning,
+                    format!("`{}` is out of range, it is ignored", KEY),
+                )
+                .at(locate_key(content, KEY))
+                .with_key(KEY)
+                .with_suggestion(Some(format!(
+                    "Use a delay between {} and {} seconds, or 0 for the default of TDLib.",
+                    MESSAGE_UNLOAD_DELAY_RANGE.start(),
+                    MESSAGE_UNLOAD_DELAY_RANGE.end()
+                ))),
+            );
+        }
+    }
+
+    // The message database implies the chat info database, which implies
+    // the file database, so TDLib enables a disabled database that is
+    // implied by an enabled one.
+    let enabled = |key: &str| {
+        table
+            .get(key)
+            .or_else(|| schema.get(key))
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    };
+    let implications = [
+        ("use_message_database", "use_chat_info_database"),
+        ("use_chat_info_database", "use_file_database"),
+    ];
+    for (database, implied) in implications {
+        if enabled(database) && !enabled(implied) {
+            let key = if table.contains_key(implied) {
+                implied
+            } else {
+                database
+            };
+            diagnostics.push(
+                ConfigDiagnostic::new(
+                    file,
+                    Severity::Warning,
+                    format!(
+                        "`{}` implies `{}`, which is enabled anyway",
+                        database, implied
+                    ),
+                )
+                .at(locate_key(content, key))
+                .with_key(key)
+                .with_suggestion(Some(format!(
+                    "Set `{}` to true, or `{}` to false.",
+                    implied, database
+                ))),
+            );
+        }
+    }
+}
+
+/// Check the colors of the palette and of the styles of a theme
+/// configuration. An invalid color of the palette is an error, while an
+/// invalid color of a style is a warning, since the style is used without
+/// that color.
+///
+/// # Arguments
+/// * `table` - The theme configuration.
+/// * `schema` - The default theme configuration.
+/// * `file` - The path of the configuration file.
+/// * `content` - The content of the configuration file.
+/// * `diagnostics` - The vector where the problems are collected.
+fn check_colors(
+    table: &Table,
+    schema: &Table,
+    file: &str,
+    content: &str,
+    diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+    const COLOR_HELP: &str = "Use a color name like `red`, a hex color like `#ff0000`, \
+                              an RGB color like `255, 0, 0` or a color index like `42`.";
+    let palette_names = |table: &Table| -> Vec<String> {
+        table
+            .get("palette")
+            .and_then(Value::as_table)
+            .map(|palette| palette.keys().cloned().collect())
+            .unwrap_or_default()
+    };
+    let mut names = palette_names(schema);
+    names.extend(palette_names(table));
+
+    if let Some(palette) = table.get("palette").and_then(Value::as_table) {
+        for (name, color) in palette {
+            let Some(color) = color.as_str() else {
+                continue;
+            };
+            if ThemeStyle::str_to_color(color).is_err() {
+                diagnostics.push(
+                    ConfigDiagnostic::new(
+                        file,
+                        Severity::Error,
+                        format!("invalid color `{}` of `palette.{}`", color, name),
+                    )
+                    .at(locate_string(content, color))
+                    .with_key(format!("palette.{}", name))
+                    .with_suggestion(Some(COLOR_HELP.to_string())),
+                );
+            }
+        }
+    }
+    for (section, styles) in table {
+        if section == "palette" {
+            continue;
+        }
+        let Some(styles) = styles.as_table() else {
+            continue;
+        };
+        for (style, entry) in styles {
+            for field in ["fg", "bg"] {
+                let Some(color) = entry.get(field).and_then(Value::as_str) else {
+                    continue;
+                };
+                if color.is_empty()
+                    || names.iter().any(|name| name == color)
+                    || ThemeStyle::str_to_color(color).is_ok()
+                {
+                    continue;
+                }
+                let suggestion = match suggest(color, names.iter()) {
+                    Some(name) => format!("Did you mean `{}`?", name),
+                    None => format!("Use a color of the palette. {}", COLOR_HELP),
+                };
+                diagnostics.push(
+                    ConfigDiagnostic::new(
+                        file,
+                        Severity::Warning,
+                        format!(
+                            "unknown color `{}` of `{}.{}.{}`",
+                            color, section, style, field
+                        ),
+                    )
+                    .at(locate_string(content, color))
+                    .with_key(format!("{}.{}.{}", section, style, field))
+                    .with_suggestion(Some(suggestion)),
+                );
+            }
+        }
+    }
+}
+
+/// Find the closest name to a misspelled name.
+///
+/// # Arguments
+/// * `name` - The misspelled name.
+/// * `candidates` - The valid names.
+///
+/// # Returns
+/// * `Option<String>` - The closest valid name, if it is close enough.
+pub fn suggest<'a>(name: &str, candidates: impl Iterator<Item = &'a String>) -> Option<String> {
+    candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, candidate)| {
+            *distance <= MAX_SUGGESTION_DISTANCE.min(candidate.chars().count() / 2)
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.clone())
+}
+
+/// Compute the Levenshtein distance between two strings.
+///
+/// # Arguments
+/// * `a` - The first string.
+/// * `b` - The second string.
+///
+/// # Returns
+/// * `usize` - The number of insertions, deletions and substitutions needed
+///   to turn a string into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Convert a byte offset of a text into a line and a column, both starting
+/// from 1.
+///
+/// # Arguments
+/// * `content` - The text.
+/// * `offset` - The byte offset.
+///
+/// # Returns
+/// * `(usize, usize)` - The line and the column.
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/// Find the position of the first definition of a key, like `key = ...`,
+/// `key.other = ...` or `[key]`.
+///
+/// # Arguments
+/// * `content` - The content of the configuration file.
+/// * `key` - The key.
+///
+/// # Returns
+/// * `Option<(usize, usize)>` - The line and the column, if found.
+fn locate_key(content: &str, key: &str) -> Option<(usize, usize)> {
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let code = line.split('#').next().unwrap_or_default();
+        for (position, _) in code.match_indices(key) {
+            let before = code[..position].chars().last();
+            let after = code[position + key.len()..].trim_start().chars().next();
+            if !before.is_some_and(is_name_char) && matches!(after, Some('=' | '.' | ']')) {
+                return Some(line_column(content, offset + position));
+            }
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Find the position of the first occurrence of a quoted string.
+///
+/// # Arguments
+/// * `content` - The content of the configuration file.
+/// * `value` - The string, without quotes.
+///
+/// # Returns
+/// * `Option<(usize, usize)>` - The line and the column, if found.
+fn locate_string(content: &str, value: &str) -> Option<(usize, usize)> {
+    content
+        .find(&format!("\"{}\"", value))
+        .or_else(|| content.find(&format!("'{}'", value)))
+        .map(|offset| line_column(content, offset))
+}
+
+/// Get the key whose value starts at a span, like `key` in `key = "value"`.
+///
+/// # Arguments
+/// * `content` - The content of the configuration file.
+/// * `span` - The span of the value.
+///
+/// # Returns
+/// * `Option<String>` - The key, if found.
+fn key_before(content: &str, span: Range<usize>) -> Option<String> {
+    let before = content.get(..span.start)?;
+    let line = &before[before.rfind('\n').map_or(0, |i| i + 1)..];
+    let line = line.trim_end().strip_suffix('=')?.trim_end();
+    let start = line
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .map_or(0, |i| i + 1);
+    let key = &line[start..];
+    (!key.is_empty()).then(|| key.to_string())
+}
+
+/// Find the first value of a key in a table and in its nested tables and
+/// arrays.
+///
+/// # Arguments
+/// * `table` - The table.
+/// * `key` - The key.
+///
+/// # Returns
+/// * `Option<&Value>` - The value, if found.
+fn find_value<'a>(table: &'a Table, key: &str) -> Option<&'a Value> {
+    if let Some(value) = table.get(key) {
+        return Some(value);
+    }
+    table.values().find_map(|value| match value {
+        Value::Table(table) => find_value(table, key),
+        Value::Array(items) => items
+            .iter()
+            .filter_map(Value::as_table)
+            .find_map(|table| find_value(table, key)),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configs::{
+        config_diagnostics::{edit_distance, line_column, suggest, validate, Severity},
+        config_type::ConfigType,
+        raw::{
+            app_raw::AppRaw, keymap_raw::KeymapRaw, telegram_raw::TelegramRaw, theme_raw::ThemeRaw,
+        },
+    };
+
+    const DEFAULT_APP: &str = include_str!("../../config/app.toml");
+    const DEFAULT_KEYMAP: &str = include_str!("../../config/keymap.toml");
+    const DEFAULT_THEME: &str = include_str!("../../config/theme.toml");
+    const DEFAULT_TELEGRAM: &str = include_str!("../../config/telegram.toml");
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("frame_rate", "frame_rate"), 0);
+        assert_eq!(edit_distance("frame_rte", "frame_rate"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_suggest() {
+        let candidates = ["mouse_support".to_string(), "paste_support".to_string()];
+        assert_eq!(
+            suggest("mouse_suport", candidates.iter()),
+            Some("mouse_support".to_string())
+        );
+        assert_eq!(suggest("timezone", candidates.iter()), None);
+    }
+
+    #[test]
+    fn test_line_column() {
+        let content = "a = 1\nbb = 2\n";
+        assert_eq!(line_column(content, 0), (1, 1));
+        assert_eq!(line_column(content, 8), (2, 3));
+    }
+
+    #[test]
+    fn test_validate_valid_files() {
+        assert!(
+            validate::<AppRaw>(ConfigType::App, "app.toml", DEFAULT_APP, DEFAULT_APP).is_empty()
+        );
+        assert!(validate::<KeymapRaw>(
+            ConfigType::Keymap,
+            "keymap.toml",
+            DEFAULT_KEYMAP,
+            DEFAULT_KEYMAP
+        )
+        .is_empty());
+        assert!(validate::<ThemeRaw>(
+            ConfigType::Theme,
+            "theme.toml",
+            DEFAULT_THEME,
+            DEFAULT_THEME
+        )
+        .is_empty());
+        assert!(validate::<TelegramRaw>(
+            ConfigType::Telegram,
+            "telegram.toml",
+            DEFAULT_TELEGRAM,
+            DEFAULT_TELEGRAM
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_validate_syntax_error() {
+        let content = "show_status_bar = true\nshow_title_bar = \"false\n";
+        let diagnostics = validate::<AppRaw>(ConfigType::App, "app.toml", content, DEFAULT_APP);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].line, Some(2));
+    }
+
+    #[test]
+    fn test_validate_unknown_key() {
+        let content = "show_status_bar = true\nshow_titel_bar = false\n";
+        let diagnostics = validate::<AppRaw>(ConfigType::App, "app.toml", content, DEFAULT_APP);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert_eq!(diagnostics[0].column, Some(1));
+        assert_eq!(diagnostics[0].key, Some("show_titel_bar".to_string()));
+        assert_eq!(
+            diagnostics[0].suggestion,
+            Some("Did you mean `show_title_bar`?".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_invalid_type() {
+        let content = "mouse_support = true\nshow_title_bar = \"no\"\n";
+        let diagnostics = validate::<AppRaw>(ConfigType::App, "app.toml", content, DEFAULT_APP);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert_eq!(diagnostics[0].key, Some("show_title_bar".to_string()));
+        assert_eq!(
+            diagnostics[0].suggestion,
+            Some(
+                "Use a value of the same type as the default, for example `show_title_bar = true`."
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_validate_unknown_command() {
+        let content =
+            "[chat_list]\nkeymap = [\n  { keys = [\"j\"], command = \"chat_lst_next\" },\n]\n";
+        let diagnostics =
+            validate::<KeymapRaw>(ConfigType::Keymap, "keymap.toml", content, DEFAULT_KEYMAP);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].line, Some(3));
+        assert_eq!(
+            diagnostics[0].suggestion,
+            Some("Did you mean `chat_list_next`?".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_invalid_colors() {
+        let content =
+            "[palette]\nmy_red = \"#zz0000\"\n\n[common]\ntimestamp = { fg = \"primari\" }\n";
+        let diagnostics =
+            validate::<ThemeRaw>(ConfigType::Theme, "theme.toml", content, DEFAULT_THEME);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+        assert_eq!(diagnostics[1].line, Some(5));
+        assert_eq!(
+            diagnostics[1].suggestion,
+            Some("Did you mean `primary`?".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_message_unload_delay() {
+        let content = "use_test_dc = false\nmessage_unload_delay = 10\n";
+        let diagnostics = validate::<TelegramRaw>(
+            ConfigType::Telegram,
+            "telegram.toml",
+            content,
+            DEFAULT_TELEGRAM,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert_eq!(diagnostics[0].key, Some("message_unload_delay".to_string()));
+    }
+
+    #[test]
+    fn test_validate_implied_databases() {
+        let content = "use_file_database = false\nuse_chat_info_database = true\n";
+        let diagnostics = validate::<TelegramRaw>(
+            ConfigType::Telegram,
+            "telegram.toml",
+            content,
+            DEFAULT_TELEGRAM,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].line, Some(1));
+        assert_eq!(diagnostics[0].key, Some("use_file_database".to_string()));
+
+        let content = "use_file_database = false\nuse_chat_info_database = false\nuse_message_database = false\n";
+        let diagnostics = validate::<TelegramRaw>(
+            ConfigType::Telegram,
+            "telegram.toml",
+            content,
+            DEFAULT_TELEGRAM,
+        );
+        assert!(diagnostics.is_empty());
+    }
+}
@@ -1,11 +1,33 @@
 use {
-    crate::configs::{self, config_type::ConfigType},
+    crate::configs::{
+        self,
+        config_diagnostics::{self, ConfigDiagnostic, Severity},
+        config_type::ConfigType,
+    },
     crate::utils::{TGT, TGT_CONFIG_DIR},
     lazy_static::lazy_static,
     serde::de::DeserializeOwned,
-    std::path::PathBuf,
+    std::{
+        path::{Path, PathBuf},
+        sync::atomic::{AtomicBool, Ordering},
+    },
 };
 
+/// Whether the custom configuration files are ignored.
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable the safe mode.
+/// In safe mode the custom configuration files are ignored and only the
+/// default configuration files are used, except `telegram.toml`: its
+/// credentials and the location of the databases are kept, so that the same
+/// session is opened. It must be set before the configurations are loaded.
+///
+/// # Arguments
+/// * `safe_mode` - Whether the safe mode is enabled.
+pub fn set_safe_mode(safe_mode: bool) {
+    SAFE_MODE.store(safe_mode, Ordering::Relaxed);
+}
+
 lazy_static! {
     static ref CONFIG_DIR_HIERARCHY: Vec<PathBuf> = {
         let mut config_dirs = vec![];
@@ -80,18 +102,59 @@ pub trait ConfigFile: Sized + Default + Clone {
     ///
     /// # Returns
     /// The path to the first matching file or `None` if no matching file is
-    /// found.
+    /// found or the safe mode is enabled, unless it is `telegram.toml`.
     fn search_config_file(file_name: &str) -> Option<PathBuf> {
+        if SAFE_MODE.load(Ordering::Relaxed) && Self::get_type() != ConfigType::Telegram {
+            tracing::info!("Safe mode, ignoring the custom {}", file_name);
+            return None;
+        }
         CONFIG_DIR_HIERARCHY
             .iter()
             .map(|path| path.join(file_name))
             .find(|path| path.exists())
     }
+    /// Validate a configuration file and deserialize it.
+    /// The problems found in the file are recorded, so that they can be shown
+    /// when the application starts, instead of exiting or ignoring them.
+    ///
+    /// # Arguments
+    /// * `file_path` - The path of the configuration file.
+    ///
+    /// # Returns
+    /// The parsed configuration or `None` if the file has errors.
+    fn validate_and_deserialize<R>(file_path: &Path) -> Option<R>
+    where
+        R: DeserializeOwned,
+    {
+        let diagnostics = config_diagnostics::validate_file::<R>(Self::get_type(), file_path);
+        config_diagnostics::record(&diagnostics);
+        if config_diagnostics::has_errors(&diagnostics) {
+            tracing::error!(
+                "Ignoring {}, the default configuration is used",
+                file_path.display()
+            );
+            return None;
+        }
+        match configs::deserialize_to_config::<R>(file_path) {
+            Ok(s) => {
+                tracing::info!("Loaded config from {}", file_path.display());
+                Some(s)
+            }
+            Err(e) => {
+                config_diagnostics::record(&[ConfigDiagnostic::new(
+                    file_path.display().to_string(),
+                    Severity::Error,
+                    e.to_string(),
+                )]);
+                None
+            }
+        }
+    }
     /// Deserialize a custom configuration file into a configuration struct.
     /// This function searches the configuration directories for the specified
     /// file name and attempts to parse it. If the file is found and parsed
     /// successfully, the parsed configuration is returned. If the file is
-    /// not found or cannot be parsed, the process exits with an error message.
+    /// not found or has errors, `None` is returned and the errors are recorded.
     ///
     /// # Arguments
     /// * `file_name` - The name of the file (including the file extension) to
@@ -105,17 +168,7 @@ pub trait ConfigFile: Sized + Default + Clone {
         R: DeserializeOwned,
     {
         match Self::search_config_file(file_name) {
-            Some(file_path) => match configs::deserialize_to_config::<R>(&file_path) {
-                Ok(s) => {
-                    tracing::info!("Loaded config from {}", file_path.display());
-                    Some(s)
-                }
-                Err(e) => {
-                    tracing::error!("Failed to parse {}: {}", file_name, e);
-                    eprintln!("Failed to parse {}: {}", file_name, e);
-                    std::process::exit(1);
-                }
-            },
+            Some(file_path) => Self::validate_and_deserialize::<R>(&file_path),
             None => {
                 tracing::info!("No config file found for {}", file_name);
                 None
@@ -127,8 +180,8 @@ pub trait ConfigFile: Sized + Default + Clone {
     /// directories for the specified file name and attempts to parse it. If
     /// the file is found and parsed successfully, the parsed configuration is
     /// returned. If the file is not found, the default configuration is
-    /// returned. If the file is found but cannot be parsed, the program exits
-    /// with an error message.
+    /// returned. If the file is found but has errors, the errors are recorded
+    /// and the default configuration is returned.
     ///
     /// # Arguments
     /// * `file_name` - The name of the file (including the file extension) to
@@ -144,20 +197,9 @@ pub trait ConfigFile: Sized + Default + Clone {
     {
         // [TODO] Handle CLI arguments
         match Self::search_config_file(file_name) {
-            Some(file_path) => {
-                match configs::deserialize_to_config_into::<R, S>(&file_path) {
-                    Ok(s) => {
-                        tracing::info!("Loaded config from {}", file_path.display());
-                        s
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to parse {}: {}", file_name, e);
-                        eprintln!("Failed to parse {}: {}", file_name, e);
-                        std::process::exit(1);
-                        // S::default()
-                    }
-                }
-            }
+            Some(file_path) => Self::validate_and_deserialize::<R>(&file_path)
+                .map(|r| r.into())
+                .unwrap_or_default(),
             None => {
                 tracing::info!("No config file found for {}", file_name);
                 S::default()
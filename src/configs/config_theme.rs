@@ -88,19 +88,14 @@ impl ThemeStyle {
             "reset" | "" => Ok(Color::Reset),
             s if s.starts_with('#') => {
                 let hex = s.trim_start_matches('#');
+                let channel = |range: std::ops::Range<usize>| {
+                    hex.get(range)
+                        .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+                        .ok_or_else(|| AppError::InvalidColor(s.to_string()))
+                };
                 match hex.len() {
-                    3 => {
-                        let r = u8::from_str_radix(&hex[0..1], 16).unwrap();
-                        let g = u8::from_str_radix(&hex[1..2], 16).unwrap();
-                        let b = u8::from_str_radix(&hex[2..3], 16).unwrap();
-                        Ok(Color::Rgb(r, g, b))
-                    }
-                    6 => {
-                        let r = u8::from_str_radix(&hex[0..2], 16).unwrap();
-                        let g = u8::from_str_radix(&hex[2..4], 16).unwrap();
-                        let b = u8::from_str_radix(&hex[4..6], 16).unwrap();
-                        Ok(Color::Rgb(r, g, b))
-                    }
+                    3 => Ok(Color::Rgb(channel(0..1)?, channel(1..2)?, channel(2..3)?)),
+                    6 => Ok(Color::Rgb(channel(0..2)?, channel(2..4)?, channel(4..6)?)),
                     _ => Err(AppError::InvalidColor(s.to_string())),
                 }
             }
@@ -1,6 +1,15 @@
 use crate::{
     app_error::AppError,
-    configs::{self, config_file::ConfigFile, config_type::ConfigType, raw::app_raw::AppRaw},
+    chat_list_rules::{self, ChatListStyleRule},
+    configs::{
+        self,
+        config_file::ConfigFile,
+        config_type::ConfigType,
+        raw::app_raw::{
+            AppRaw, ChatLogEntry, CommandEntry, FolderNotificationEntry, TemplateEntry,
+        },
+    },
+    title_template::TitleTemplate,
 };
 use std::path::Path;
 
@@ -25,6 +34,129 @@ pub struct AppConfig {
     pub take_api_id_from_telegram_config: bool,
     /// Take the API HASH from the Telegram configuration.
     pub take_api_hash_from_telegram_config: bool,
+    /// The timezone used to display dates and times.
+    pub timezone: String,
+    /// The locale used to display dates and times.
+    pub locale: String,
+    /// The first day of the week used in calendars.
+    pub first_day_of_week: String,
+    /// The external command used to split files that exceed the upload size
+    /// limit.
+    pub file_split_command: String,
+    /// Fetch locally the previews of the links without a Telegram preview.
+    pub url_preview_fetch: bool,
+    /// The domains whose previews can be fetched locally.
+    pub url_preview_allowlist: Vec<String>,
+    /// The domains whose previews must never be fetched locally.
+    pub url_preview_denylist: Vec<String>,
+    /// The custom slash commands of the prompt.
+    pub commands: Vec<CommandEntry>,
+    /// The maximum number of files uploaded at the same time.
+    /// The other uploads wait in a queue, while text messages are always sent
+    /// immediately.
+    pub max_parallel_uploads: usize,
+    /// A flag that indicates if the large emoji of new single-emoji messages
+    /// are briefly animated.
+    pub emoji_animation: bool,
+    /// A flag that indicates if the last messages of the chat highlighted in the
+    /// chat list are shown in a read-only preview, without marking them as read.
+    pub chat_list_preview: bool,
+    /// The command used to play the audio messages of the playlist.
+    /// `{file}` is replaced with the path of the downloaded audio file and
+    /// `{start}` with the position the playback starts from, in seconds.
+    /// If empty, the audio output is used when the `audio-output` feature is
+    /// enabled, and otherwise the playback is disabled.
+    pub audio_player_command: String,
+    /// The command run when a new message is received.
+    /// `{chat}`, `{sender}` and `{text}` are replaced with the name of the chat,
+    /// the name of the sender and the text of the message.
+    /// If empty, no command is run.
+    pub notification_command: String,
+    /// The details of the message given to the notification command: `full`,
+    /// `sender` or `none`, for the notifications seen by other people.
+    pub notification_preview: String,
+    /// The maximum number of characters of the text of the message given to
+    /// the notification command. Zero does not limit the length.
+    pub notification_preview_length: usize,
+    /// Enable checking for a newer version of `tgt` at startup.
+    /// When a newer version exists, a hint is shown in the status bar.
+    pub check_for_updates: bool,
+    /// The command used to record a voice note. `{file}` is replaced with
+    /// the path of the recorded file, that is attached when the command exits.
+    /// If empty, the recording is disabled.
+    pub voice_note_command: String,
+    /// A boolean flag that represents whether the status bar shows the
+    /// keys of the most useful actions of the focused component.
+    pub show_key_hints: bool,
+    /// A boolean flag that represents whether the messages of the chat are
+    /// shown in the compact mode, one line per message like IRC clients,
+    /// instead of bubbles.
+    pub compact_chat: bool,
+    /// The maximum time, in minutes, between two consecutive messages of the
+    /// same sender for the second one to be shown under the header of the
+    /// first one. Zero disables the grouping.
+    pub group_messages_minutes: u64,
+    /// The way the links are shown in the messages: `full`, `domain` or
+    /// `footnotes`. The messages keep the full links, that are copied whole.
+    pub url_display: String,
+    /// The maximum size of the cache of the downloaded files, in megabytes.
+    /// Above it, the least recently used files are deleted in the background.
+    /// Zero does not limit the size.
+    pub max_cache_size: u64,
+    /// The theme filename used when the terminal or the system uses a dark
+    /// background. Empty to use `theme_filename`.
+    pub theme_dark_filename: String,
+    /// The theme filename used when the terminal or the system uses a light
+    /// background. Empty to use `theme_filename`.
+    pub theme_light_filename: String,
+    /// The command of the speech synthesizer that reads the messages aloud,
+    /// with `{text}` replaced by the text. Empty to disable it.
+    pub speech_command: String,
+    /// Whether the photos and the stickers are drawn inline in the chat,
+    /// with the graphics protocol of the terminal.
+    pub show_images: bool,
+    /// The templates of the messages, whose variables like `{first_name}`
+    /// are replaced when the message is sent.
+    pub templates: Vec<TemplateEntry>,
+    /// Whether the contacts whose birthday is today are shown in the status
+    /// bar.
+    pub birthday_reminder: bool,
+    /// The command used to open the photo or the video of a message, like a
+    /// view-once one. `{file}` is replaced with the path of the downloaded
+    /// file. If empty, the media can not be opened.
+    pub media_viewer_command: String,
+    /// The chats whose messages are appended to external files, like
+    /// Markdown notes, as they arrive. The messages received while `tgt` was
+    /// closed are appended when it starts.
+    pub chat_logs: Vec<ChatLogEntry>,
+    /// The notification rules of the chats of folders, applied to the chats
+    /// without their own notification settings. The first rule of a folder of
+    /// the chat that applies at the time of the message decides whether it is
+    /// notified.
+    pub folder_notifications: Vec<FolderNotificationEntry>,
+    /// Send the typing action of the user in the open chat while they type
+    /// in the prompt, so that the other members see that they are typing.
+    pub send_typing_action: bool,
+    /// The maximum number of messages of the open chat kept in memory. The
+    /// messages far from the view are dropped and loaded again from the
+    /// database of TDLib when scrolled to. It is `0` to keep all of them.
+    pub max_chat_messages: usize,
+    /// Scroll the messages of the chat smoothly, over a few frames, when they
+    /// are scrolled by half a page or jumped to, instead of at once.
+    pub smooth_scrolling: bool,
+    /// The template of the text of the title bar, whose placeholders like
+    /// `{chat_title}` are filled at each frame.
+    pub title_bar_template: TitleTemplate,
+    /// The command used to open a link, like the link of a button of a bot.
+    /// `{url}` is replaced with the link. If empty, the links are not opened.
+    pub url_open_command: String,
+    /// The number of minutes without input in the terminal after which the
+    /// user is no longer shown online, until the next input. It is `0` to stay
+    /// online while `tgt` is open.
+    pub auto_away_minutes: u64,
+    /// The rules of the styles of the chats of the chat list, parsed once.
+    /// The later rules are applied over the earlier ones.
+    pub chat_list_styles: Vec<ChatListStyleRule>,
 }
 /// The application configuration implementation.
 impl AppConfig {
@@ -86,6 +218,120 @@ impl ConfigFile for AppConfig {
                 {
                     self.take_api_hash_from_telegram_config = take_api_hash_from_telegram_config;
                 }
+                if let Some(timezone) = other.timezone {
+                    self.timezone = timezone;
+                }
+                if let Some(locale) = other.locale {
+                    self.locale = locale;
+                }
+                if let Some(first_day_of_week) = other.first_day_of_week {
+                    self.first_day_of_week = first_day_of_week;
+                }
+                if let Some(file_split_command) = other.file_split_command {
+                    self.file_split_command = file_split_command;
+                }
+                if let Some(url_preview_fetch) = other.url_preview_fetch {
+                    self.url_preview_fetch = url_preview_fetch;
+                }
+                if let Some(url_preview_allowlist) = other.url_preview_allowlist {
+                    self.url_preview_allowlist = url_preview_allowlist;
+                }
+                if let Some(url_preview_denylist) = other.url_preview_denylist {
+                    self.url_preview_denylist = url_preview_denylist;
+                }
+                if let Some(commands) = other.commands {
+                    self.commands = commands;
+                }
+                if let Some(max_parallel_uploads) = other.max_parallel_uploads {
+                    self.max_parallel_uploads = max_parallel_uploads;
+                }
+                if let Some(emoji_animation) = other.emoji_animation {
+                    self.emoji_animation = emoji_animation;
+                }
+                if let Some(chat_list_preview) = other.chat_list_preview {
+                    self.chat_list_preview = chat_list_preview;
+                }
+                if let Some(audio_player_command) = other.audio_player_command {
+                    self.audio_player_command = audio_player_command;
+                }
+                if let Some(notification_command) = other.notification_command {
+                    self.notification_command = notification_command;
+                }
+                if let Some(notification_preview) = other.notification_preview {
+                    self.notification_preview = notification_preview;
+                }
+                if let Some(notification_preview_length) = other.notification_preview_length {
+                    self.notification_preview_length = notification_preview_length;
+                }
+                if let Some(check_for_updates) = other.check_for_updates {
+                    self.check_for_updates = check_for_updates;
+                }
+                if let Some(voice_note_command) = other.voice_note_command {
+                    self.voice_note_command = voice_note_command;
+                }
+                if let Some(show_key_hints) = other.show_key_hints {
+                    self.show_key_hints = show_key_hints;
+                }
+                if let Some(compact_chat) = other.compact_chat {
+                    self.compact_chat = compact_chat;
+                }
+                if let Some(group_messages_minutes) = other.group_messages_minutes {
+                    self.group_messages_minutes = group_messages_minutes;
+                }
+                if let Some(url_display) = other.url_display {
+                    self.url_display = url_display;
+                }
+                if let Some(max_cache_size) = other.max_cache_size {
+                    self.max_cache_size = max_cache_size;
+                }
+                if let Some(theme_dark_filename) = other.theme_dark_filename {
+                    self.theme_dark_filename = theme_dark_filename;
+                }
+                if let Some(theme_light_filename) = other.theme_light_filename {
+                    self.theme_light_filename = theme_light_filename;
+                }
+                if let Some(speech_command) = other.speech_command {
+                    self.speech_command = speech_command;
+                }
+                if let Some(show_images) = other.show_images {
+                    self.show_images = show_images;
+                }
+                if let Some(templates) = other.templates {
+                    self.templates = templates;
+                }
+                if let Some(birthday_reminder) = other.birthday_reminder {
+                    self.birthday_reminder = birthday_reminder;
+                }
+                if let Some(media_viewer_command) = other.media_viewer_command {
+                    self.media_viewer_command = media_viewer_command;
+                }
+                if let Some(chat_logs) = other.chat_logs {
+                    self.chat_logs = chat_logs;
+                }
+                if let Some(folder_notifications) = other.folder_notifications {
+                    self.folder_notifications = folder_notifications;
+                }
+                if let Some(send_typing_action) = other.send_typing_action {
+                    self.send_typing_action = send_typing_action;
+                }
+                if let Some(max_chat_messages) = other.max_chat_messages {
+                    self.max_chat_messages = max_chat_messages;
+                }
+                if let Some(smooth_scrolling) = other.smooth_scrolling {
+                    self.smooth_scrolling = smooth_scrolling;
+                }
+                if let Some(title_bar_template) = other.title_bar_template {
+                    self.title_bar_template = TitleTemplate::parse(&title_bar_template);
+                }
+                if let Some(url_open_command) = other.url_open_command {
+                    self.url_open_command = url_open_command;
+                }
+                if let Some(auto_away_minutes) = other.auto_away_minutes {
+                    self.auto_away_minutes = auto_away_minutes;
+                }
+                if let Some(chat_list_styles) = other.chat_list_styles {
+                    self.chat_list_styles = chat_list_rules::parse_rules(&chat_list_styles);
+                }
                 self.clone()
             }
         }
@@ -111,14 +357,60 @@ impl From<AppRaw> for AppConfig {
             theme_filename: raw.theme_filename.unwrap(),
             take_api_id_from_telegram_config: raw.take_api_id_from_telegram_config.unwrap(),
             take_api_hash_from_telegram_config: raw.take_api_hash_from_telegram_config.unwrap(),
+            timezone: raw.timezone.unwrap(),
+            locale: raw.locale.unwrap(),
+            first_day_of_week: raw.first_day_of_week.unwrap(),
+            file_split_command: raw.file_split_command.unwrap(),
+            url_preview_fetch: raw.url_preview_fetch.unwrap(),
+            url_preview_allowlist: raw.url_preview_allowlist.unwrap(),
+            url_preview_denylist: raw.url_preview_denylist.unwrap(),
+            commands: raw.commands.unwrap(),
+            max_parallel_uploads: raw.max_parallel_uploads.unwrap(),
+            emoji_animation: raw.emoji_animation.unwrap(),
+            chat_list_preview: raw.chat_list_preview.unwrap(),
+            audio_player_command: raw.audio_player_command.unwrap(),
+            notification_command: raw.notification_command.unwrap(),
+            notification_preview: raw.notification_preview.unwrap(),
+            notification_preview_length: raw.notification_preview_length.unwrap(),
+            check_for_updates: raw.check_for_updates.unwrap(),
+            voice_note_command: raw.voice_note_command.unwrap(),
+            show_key_hints: raw.show_key_hints.unwrap(),
+            compact_chat: raw.compact_chat.unwrap(),
+            group_messages_minutes: raw.group_messages_minutes.unwrap(),
+            url_display: raw.url_display.unwrap(),
+            max_cache_size: raw.max_cache_size.unwrap(),
+            theme_dark_filename: raw.theme_dark_filename.unwrap(),
+            theme_light_filename: raw.theme_light_filename.unwrap(),
+            speech_command: raw.speech_command.unwrap(),
+            show_images: raw.show_images.unwrap(),
+            templates: raw.templates.unwrap(),
+            birthday_reminder: raw.birthday_reminder.unwrap(),
+            media_viewer_command: raw.media_viewer_command.unwrap(),
+            chat_logs: raw.chat_logs.unwrap(),
+            folder_notifications: raw.folder_notifications.unwrap(),
+            send_typing_action: raw.send_typing_action.unwrap(),
+            max_chat_messages: raw.max_chat_messages.unwrap(),
+            smooth_scrolling: raw.smooth_scrolling.unwrap(),
+            title_bar_template: TitleTemplate::parse(&raw.title_bar_template.unwrap()),
+            url_open_command: raw.url_open_command.unwrap(),
+            auto_away_minutes: raw.auto_away_minutes.unwrap(),
+            chat_list_styles: chat_list_rules::parse_rules(&raw.chat_list_styles.unwrap()),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::configs::{
-        config_file::ConfigFile, custom::app_custom::AppConfig, raw::app_raw::AppRaw,
+    use crate::{
+        configs::{
+            config_file::ConfigFile,
+            custom::app_custom::AppConfig,
+            raw::app_raw::{
+                AppRaw, ChatListStyleEntry, ChatLogEntry, CommandEntry, FolderNotificationEntry,
+                TemplateEntry,
+            },
+        },
+        title_template::TitleTemplate,
     };
 
     #[test]
@@ -133,6 +425,47 @@ mod tests {
         assert_eq!(app_config.theme_filename, "theme.toml");
         assert!(app_config.take_api_id_from_telegram_config);
         assert!(app_config.take_api_hash_from_telegram_config);
+        assert_eq!(app_config.timezone, "");
+        assert_eq!(app_config.locale, "en_US");
+        assert_eq!(app_config.first_day_of_week, "monday");
+        assert_eq!(app_config.file_split_command, "");
+        assert!(!app_config.url_preview_fetch);
+        assert!(app_config.url_preview_allowlist.is_empty());
+        assert!(app_config.url_preview_denylist.is_empty());
+        assert!(app_config.commands.is_empty());
+        assert_eq!(app_config.max_parallel_uploads, 2);
+        assert!(app_config.emoji_animation);
+        assert!(!app_config.chat_list_preview);
+        assert_eq!(app_config.audio_player_command, "");
+        assert_eq!(app_config.notification_command, "");
+        assert_eq!(app_config.notification_preview, "full");
+        assert_eq!(app_config.notification_preview_length, 100);
+        assert!(!app_config.check_for_updates);
+        assert_eq!(app_config.voice_note_command, "");
+        assert!(app_config.show_key_hints);
+        assert!(!app_config.compact_chat);
+        assert_eq!(app_config.group_messages_minutes, 0);
+        assert_eq!(app_config.url_display, "full");
+        assert_eq!(app_config.max_cache_size, 0);
+        assert_eq!(app_config.theme_dark_filename, "");
+        assert_eq!(app_config.theme_light_filename, "");
+        assert_eq!(app_config.speech_command, "");
+        assert!(app_config.show_images);
+        assert!(app_config.templates.is_empty());
+        assert!(app_config.birthday_reminder);
+        assert_eq!(app_config.media_viewer_command, "");
+        assert!(app_config.chat_logs.is_empty());
+        assert!(app_config.folder_notifications.is_empty());
+        assert!(app_config.send_typing_action);
+        assert_eq!(app_config.max_chat_messages, 1000);
+        assert!(app_config.smooth_scrolling);
+        assert_eq!(
+            app_config.title_bar_template,
+            TitleTemplate::parse("{app_name} - A TUI for Telegram")
+        );
+        assert_eq!(app_config.url_open_command, "xdg-open {url}");
+        assert_eq!(app_config.auto_away_minutes, 10);
+        assert!(app_config.chat_list_styles.is_empty());
     }
 
     #[test]
@@ -147,6 +480,67 @@ mod tests {
             theme_filename: Some("test".to_string()),
             take_api_id_from_telegram_config: Some(true),
             take_api_hash_from_telegram_config: Some(true),
+            timezone: Some("UTC".to_string()),
+            locale: Some("it_IT".to_string()),
+            first_day_of_week: Some("sunday".to_string()),
+            file_split_command: Some("split".to_string()),
+            url_preview_fetch: Some(true),
+            url_preview_allowlist: Some(vec!["github.com".to_string()]),
+            url_preview_denylist: Some(vec!["example.com".to_string()]),
+            commands: Some(vec![CommandEntry {
+                name: "shrug".to_string(),
+                actions: None,
+                text: Some("¯\\_(ツ)_/¯".to_string()),
+                exec: None,
+                chat: None,
+                description: None,
+            }]),
+            max_parallel_uploads: Some(3),
+            emoji_animation: Some(false),
+            chat_list_preview: Some(true),
+            audio_player_command: Some("mpv {file}".to_string()),
+            notification_command: Some("notify-send {chat} {text}".to_string()),
+            notification_preview: Some("sender".to_string()),
+            notification_preview_length: Some(40),
+            check_for_updates: Some(true),
+            voice_note_command: Some("rec {file}".to_string()),
+            show_key_hints: Some(false),
+            compact_chat: Some(true),
+            group_messages_minutes: Some(5),
+            url_display: Some("footnotes".to_string()),
+            max_cache_size: Some(500),
+            theme_dark_filename: Some("dark.toml".to_string()),
+            theme_light_filename: Some("light.toml".to_string()),
+            speech_command: Some("say {text}".to_string()),
+            show_images: Some(false),
+            templates: Some(vec![TemplateEntry {
+                name: "Greeting".to_string(),
+                text: "Hi {first_name}".to_string(),
+                chat_types: Some(vec!["private".to_string()]),
+            }]),
+            birthday_reminder: Some(false),
+            media_viewer_command: Some("mpv {file}".to_string()),
+            chat_logs: Some(vec![ChatLogEntry {
+                chat: "Family".to_string(),
+                path: "~/notes/{chat}.md".to_string(),
+                format: None,
+            }]),
+            folder_notifications: Some(vec![FolderNotificationEntry {
+                folder: "Work".to_string(),
+                policy: "silent".to_string(),
+                hours: Some("18:00-09:00".to_string()),
+                days: None,
+            }]),
+            send_typing_action: Some(false),
+            max_chat_messages: Some(500),
+            smooth_scrolling: Some(false),
+            title_bar_template: Some("{chat_title} {time}".to_string()),
+            url_open_command: Some("open {url}".to_string()),
+            auto_away_minutes: Some(15),
+            chat_list_styles: Some(vec![ChatListStyleEntry {
+                chats: "muted channels".to_string(),
+                style: "dim".to_string(),
+            }]),
         };
         let app_config = AppConfig::from(app_raw);
         assert!(app_config.mouse_support);
@@ -156,6 +550,47 @@ mod tests {
         assert!(app_config.show_title_bar);
         assert!(app_config.theme_enable);
         assert_eq!(app_config.theme_filename, "test");
+        assert_eq!(app_config.timezone, "UTC");
+        assert_eq!(app_config.locale, "it_IT");
+        assert_eq!(app_config.first_day_of_week, "sunday");
+        assert_eq!(app_config.file_split_command, "split");
+        assert!(app_config.url_preview_fetch);
+        assert_eq!(app_config.url_preview_allowlist, vec!["github.com"]);
+        assert_eq!(app_config.url_preview_denylist, vec!["example.com"]);
+        assert_eq!(app_config.commands[0].name, "shrug");
+        assert_eq!(app_config.max_parallel_uploads, 3);
+        assert!(!app_config.emoji_animation);
+        assert!(app_config.chat_list_preview);
+        assert_eq!(app_config.audio_player_command, "mpv {file}");
+        assert_eq!(app_config.notification_command, "notify-send {chat} {text}");
+        assert_eq!(app_config.notification_preview, "sender");
+        assert_eq!(app_config.notification_preview_length, 40);
+        assert!(app_config.check_for_updates);
+        assert_eq!(app_config.voice_note_command, "rec {file}");
+        assert!(!app_config.show_key_hints);
+        assert!(app_config.compact_chat);
+        assert_eq!(app_config.group_messages_minutes, 5);
+        assert_eq!(app_config.url_display, "footnotes");
+        assert_eq!(app_config.max_cache_size, 500);
+        assert_eq!(app_config.theme_dark_filename, "dark.toml");
+        assert_eq!(app_config.theme_light_filename, "light.toml");
+        assert_eq!(app_config.speech_command, "say {text}");
+        assert!(!app_config.show_images);
+        assert_eq!(app_config.templates[0].name, "Greeting");
+        assert!(!app_config.birthday_reminder);
+        assert_eq!(app_config.media_viewer_command, "mpv {file}");
+        assert_eq!(app_config.chat_logs[0].chat, "Family");
+        assert_eq!(app_config.folder_notifications[0].folder, "Work");
+        assert!(!app_config.send_typing_action);
+        assert_eq!(app_config.max_chat_messages, 500);
+        assert!(!app_config.smooth_scrolling);
+        assert_eq!(
+            app_config.title_bar_template,
+            TitleTemplate::parse("{chat_title} {time}")
+        );
+        assert_eq!(app_config.url_open_command, "open {url}");
+        assert_eq!(app_config.auto_away_minutes, 15);
+        assert_eq!(app_config.chat_list_styles.len(), 1);
     }
 
     #[test]
@@ -170,6 +605,67 @@ mod tests {
             theme_filename: Some("test".to_string()),
             take_api_id_from_telegram_config: Some(true),
             take_api_hash_from_telegram_config: Some(true),
+            timezone: Some("UTC".to_string()),
+            locale: Some("it_IT".to_string()),
+            first_day_of_week: Some("sunday".to_string()),
+            file_split_command: Some("split".to_string()),
+            url_preview_fetch: Some(true),
+            url_preview_allowlist: Some(vec!["github.com".to_string()]),
+            url_preview_denylist: Some(vec!["example.com".to_string()]),
+            commands: Some(vec![CommandEntry {
+                name: "shrug".to_string(),
+                actions: None,
+                text: Some("¯\\_(ツ)_/¯".to_string()),
+                exec: None,
+                chat: None,
+                description: None,
+            }]),
+            max_parallel_uploads: Some(3),
+            emoji_animation: Some(false),
+            chat_list_preview: Some(true),
+            audio_player_command: Some("mpv {file}".to_string()),
+            notification_command: Some("notify-send {chat} {text}".to_string()),
+            notification_preview: Some("sender".to_string()),
+            notification_preview_length: Some(40),
+            check_for_updates: Some(true),
+            voice_note_command: Some("rec {file}".to_string()),
+            show_key_hints: Some(false),
+            compact_chat: Some(true),
+            group_messages_minutes: Some(5),
+            url_display: Some("footnotes".to_string()),
+            max_cache_size: Some(500),
+            theme_dark_filename: Some("dark.toml".to_string()),
+            theme_light_filename: Some("light.toml".to_string()),
+            speech_command: Some("say {text}".to_string()),
+            show_images: Some(false),
+            templates: Some(vec![TemplateEntry {
+                name: "Greeting".to_string(),
+                text: "Hi {first_name}".to_string(),
+                chat_types: Some(vec!["private".to_string()]),
+            }]),
+            birthday_reminder: Some(false),
+            media_viewer_command: Some("mpv {file}".to_string()),
+            chat_logs: Some(vec![ChatLogEntry {
+                chat: "Family".to_string(),
+                path: "~/notes/{chat}.md".to_string(),
+                format: None,
+            }]),
+            folder_notifications: Some(vec![FolderNotificationEntry {
+                folder: "Work".to_string(),
+                policy: "silent".to_string(),
+                hours: Some("18:00-09:00".to_string()),
+                days: None,
+            }]),
+            send_typing_action: Some(false),
+            max_chat_messages: Some(500),
+            smooth_scrolling: Some(false),
+            title_bar_template: Some("{chat_title} {time}".to_string()),
+            url_open_command: Some("open {url}".to_string()),
+            auto_away_minutes: Some(15),
+            chat_list_styles: Some(vec![ChatListStyleEntry {
+                chats: "muted channels".to_string(),
+                style: "dim".to_string(),
+            }]),
         });
         let app_raw = AppRaw {
             mouse_support: Some(false),
@@ -181,6 +677,44 @@ mod tests {
             theme_filename: None,
             take_api_id_from_telegram_config: None,
             take_api_hash_from_telegram_config: None,
+            timezone: None,
+            locale: None,
+            first_day_of_week: None,
+            file_split_command: None,
+            url_preview_fetch: None,
+            url_preview_allowlist: None,
+            url_preview_denylist: None,
+            commands: None,
+            max_parallel_uploads: None,
+            emoji_animation: None,
+            chat_list_preview: None,
+            audio_player_command: None,
+            notification_command: None,
+            notification_preview: None,
+            notification_preview_length: None,
+            check_for_updates: None,
+            voice_note_command: None,
+            show_key_hints: None,
+            compact_chat: None,
+            group_messages_minutes: None,
+            url_display: None,
+            max_cache_size: None,
+            theme_dark_filename: None,
+            theme_light_filename: None,
+            speech_command: None,
+            show_images: None,
+            templates: None,
+            birthday_reminder: None,
+            media_viewer_command: None,
+            chat_logs: None,
+            folder_notifications: None,
+            send_typing_action: None,
+            max_chat_messages: None,
+            smooth_scrolling: None,
+            title_bar_template: None,
+            url_open_command: None,
+            auto_away_minutes: None,
+            chat_list_styles: None,
         };
         app_config = app_config.merge(Some(app_raw));
         assert!(!app_config.mouse_support);
@@ -190,6 +724,47 @@ mod tests {
         assert!(app_config.show_title_bar);
         assert!(app_config.theme_enable);
         assert_eq!(app_config.theme_filename, "test");
+        assert_eq!(app_config.timezone, "UTC");
+        assert_eq!(app_config.locale, "it_IT");
+        assert_eq!(app_config.first_day_of_week, "sunday");
+        assert_eq!(app_config.file_split_command, "split");
+        assert!(app_config.url_preview_fetch);
+        assert_eq!(app_config.url_preview_allowlist, vec!["github.com"]);
+        assert_eq!(app_config.url_preview_denylist, vec!["example.com"]);
+        assert_eq!(app_config.commands[0].name, "shrug");
+        assert_eq!(app_config.max_parallel_uploads, 3);
+        assert!(!app_config.emoji_animation);
+        assert!(app_config.chat_list_preview);
+        assert_eq!(app_config.audio_player_command, "mpv {file}");
+        assert_eq!(app_config.notification_command, "notify-send {chat} {text}");
+        assert_eq!(app_config.notification_preview, "sender");
+        assert_eq!(app_config.notification_preview_length, 40);
+        assert!(app_config.check_for_updates);
+        assert_eq!(app_config.voice_note_command, "rec {file}");
+        assert!(!app_config.show_key_hints);
+        assert!(app_config.compact_chat);
+        assert_eq!(app_config.group_messages_minutes, 5);
+        assert_eq!(app_config.url_display, "footnotes");
+        assert_eq!(app_config.max_cache_size, 500);
+        assert_eq!(app_config.theme_dark_filename, "dark.toml");
+        assert_eq!(app_config.theme_light_filename, "light.toml");
+        assert_eq!(app_config.speech_command, "say {text}");
+        assert!(!app_config.show_images);
+        assert_eq!(app_config.templates[0].name, "Greeting");
+        assert!(!app_config.birthday_reminder);
+        assert_eq!(app_config.media_viewer_command, "mpv {file}");
+        assert_eq!(app_config.chat_logs[0].chat, "Family");
+        assert_eq!(app_config.folder_notifications[0].folder, "Work");
+        assert!(!app_config.send_typing_action);
+        assert_eq!(app_config.max_chat_messages, 500);
+        assert!(!app_config.smooth_scrolling);
+        assert_eq!(
+            app_config.title_bar_template,
+            TitleTemplate::parse("{chat_title} {time}")
+        );
+        assert_eq!(app_config.url_open_command, "open {url}");
+        assert_eq!(app_config.auto_away_minutes, 15);
+        assert_eq!(app_config.chat_list_styles.len(), 1);
     }
 
     #[test]
@@ -210,6 +785,44 @@ mod tests {
             theme_filename: None,
             take_api_id_from_telegram_config: None,
             take_api_hash_from_telegram_config: None,
+            timezone: None,
+            locale: None,
+            first_day_of_week: None,
+            file_split_command: None,
+            url_preview_fetch: None,
+            url_preview_allowlist: None,
+            url_preview_denylist: None,
+            commands: None,
+            max_parallel_uploads: None,
+            emoji_animation: None,
+            chat_list_preview: None,
+            audio_player_command: None,
+            notification_command: None,
+            notification_preview: None,
+            notification_preview_length: None,
+            check_for_updates: None,
+            voice_note_command: None,
+            show_key_hints: None,
+            compact_chat: None,
+            group_messages_minutes: None,
+            url_display: None,
+            max_cache_size: None,
+            theme_dark_filename: None,
+            theme_light_filename: None,
+            speech_command: None,
+            show_images: None,
+            templates: None,
+            birthday_reminder: None,
+            media_viewer_command: None,
+            chat_logs: None,
+            folder_notifications: None,
+            send_typing_action: None,
+            max_chat_messages: None,
+            smooth_scrolling: None,
+            title_bar_template: None,
+            url_open_command: None,
+            auto_away_minutes: None,
+            chat_list_styles: None,
         };
         app_config = app_config.merge(Some(app_raw));
         assert!(app_config.mouse_support);
@@ -221,6 +834,47 @@ mod tests {
         assert_eq!(app_config.theme_filename, "theme.toml");
         assert!(app_config.take_api_id_from_telegram_config);
         assert!(app_config.take_api_hash_from_telegram_config);
+        assert_eq!(app_config.timezone, "");
+        assert_eq!(app_config.locale, "en_US");
+        assert_eq!(app_config.first_day_of_week, "monday");
+        assert_eq!(app_config.file_split_command, "");
+        assert!(!app_config.url_preview_fetch);
+        assert!(app_config.url_preview_allowlist.is_empty());
+        assert!(app_config.url_preview_denylist.is_empty());
+        assert!(app_config.commands.is_empty());
+        assert_eq!(app_config.max_parallel_uploads, 2);
+        assert!(app_config.emoji_animation);
+        assert!(!app_config.chat_list_preview);
+        assert_eq!(app_config.audio_player_command, "");
+        assert_eq!(app_config.notification_command, "");
+        assert_eq!(app_config.notification_preview, "full");
+        assert_eq!(app_config.notification_preview_length, 100);
+        assert!(!app_config.check_for_updates);
+        assert_eq!(app_config.voice_note_command, "");
+        assert!(app_config.show_key_hints);
+        assert!(!app_config.compact_chat);
+        assert_eq!(app_config.group_messages_minutes, 0);
+        assert_eq!(app_config.url_display, "full");
+        assert_eq!(app_config.max_cache_size, 0);
+        assert_eq!(app_config.theme_dark_filename, "");
+        assert_eq!(app_config.theme_light_filename, "");
+        assert_eq!(app_config.speech_command, "");
+        assert!(app_config.show_images);
+        assert!(app_config.templates.is_empty());
+        assert!(app_config.birthday_reminder);
+        assert_eq!(app_config.media_viewer_command, "");
+        assert!(app_config.chat_logs.is_empty());
+        assert!(app_config.folder_notifications.is_empty());
+        assert!(app_config.send_typing_action);
+        assert_eq!(app_config.max_chat_messages, 1000);
+        assert!(app_config.smooth_scrolling);
+        assert_eq!(
+            app_config.title_bar_template,
+            TitleTemplate::parse("{app_name} - A TUI for Telegram")
+        );
+        assert_eq!(app_config.url_open_command, "xdg-open {url}");
+        assert_eq!(app_config.auto_away_minutes, 10);
+        assert!(app_config.chat_list_styles.is_empty());
     }
 
     #[test]
@@ -15,7 +15,7 @@ pub mod theme_custom;
 ///
 /// # Returns
 /// The default configuration file path of the specified configuration type.
-fn default_config_file_path_of(config_type: ConfigType) -> io::Result<String> {
+pub fn default_config_file_path_of(config_type: ConfigType) -> io::Result<String> {
     Ok(tgt_config_dir()?
         .join(config_type.as_default_filename())
         .to_str()
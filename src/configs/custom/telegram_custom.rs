@@ -1,13 +1,21 @@
 use crate::{
     app_error::AppError,
     configs::{
-        self, config_file::ConfigFile, config_type::ConfigType, raw::telegram_raw::TelegramRaw,
+        self,
+        config_file::ConfigFile,
+        config_type::ConfigType,
+        raw::telegram_raw::{AccountEntry, TelegramRaw},
     },
     utils,
 };
+use std::ops::RangeInclusive;
 use std::path::Path;
 use std::path::PathBuf;
 
+/// The delays after which the messages of the closed chats can be unloaded,
+/// in seconds, as accepted by TDLib.
+pub const MESSAGE_UNLOAD_DELAY_RANGE: RangeInclusive<i64> = 60..=86400;
+
 #[derive(Clone, Debug)]
 /// The telegram configuration.
 pub struct TelegramConfig {
@@ -21,6 +29,9 @@ pub struct TelegramConfig {
     pub api_hash: String,
     /// The directory where the database is stored.
     pub database_dir: String,
+    /// The accounts that can be switched between. The database of the
+    /// active one is used instead of `database_dir`.
+    pub accounts: Vec<AccountEntry>,
     /// A flag that indicates if the user database should be used.
     pub use_file_database: bool,
     /// A flag that indicates if the chat info database should be used.
@@ -31,6 +42,13 @@ pub struct TelegramConfig {
     pub system_language_code: String,
     /// The model of the device.
     pub device_model: String,
+    /// A flag that indicates if the test environment should be used.
+    pub use_test_dc: bool,
+    /// A flag that indicates if the original names of the files should be ignored.
+    pub ignore_file_names: bool,
+    /// The delay after which the messages of the closed chats are unloaded, in seconds.
+    /// It is `0` to use the default delay of TDLib.
+    pub message_unload_delay: i64,
     /// The verbosity level of the logging.
     pub verbosity_level: i32,
     /// The path to the working directory.
@@ -79,6 +97,9 @@ impl ConfigFile for TelegramConfig {
                     }
                     self.database_dir = database_dir;
                 }
+                if let Some(accounts) = _other.accounts {
+                    self.accounts = accounts;
+                }
                 if let Some(use_file_database) = _other.use_file_database {
                     self.use_file_database = use_file_database;
                 }
@@ -94,6 +115,15 @@ impl ConfigFile for TelegramConfig {
                 if let Some(device_model) = _other.device_model {
                     self.device_model = device_model;
                 }
+                if let Some(use_test_dc) = _other.use_test_dc {
+                    self.use_test_dc = use_test_dc;
+                }
+                if let Some(ignore_file_names) = _other.ignore_file_names {
+                    self.ignore_file_names = ignore_file_names;
+                }
+                if let Some(message_unload_delay) = _other.message_unload_delay {
+                    self.message_unload_delay = message_unload_delay;
+                }
                 if let Some(verbosity_level) = _other.verbosity_level {
                     self.verbosity_level = verbosity_level;
                 }
@@ -144,11 +174,15 @@ impl From<TelegramRaw> for TelegramConfig {
             api_id: raw.api_id.unwrap(),
             api_hash: raw.api_hash.unwrap(),
             database_dir,
+            accounts: raw.accounts.unwrap(),
             use_file_database: raw.use_file_database.unwrap(),
             use_chat_info_database: raw.use_chat_info_database.unwrap(),
             use_message_database: raw.use_message_database.unwrap(),
             system_language_code: raw.system_language_code.unwrap(),
             device_model: raw.device_model.unwrap(),
+            use_test_dc: raw.use_test_dc.unwrap(),
+            ignore_file_names: raw.ignore_file_names.unwrap(),
+            message_unload_delay: raw.message_unload_delay.unwrap(),
             verbosity_level: raw.verbosity_level.unwrap(),
             log_path,
             redirect_stderr: raw.redirect_stderr.unwrap(),
@@ -160,8 +194,9 @@ impl From<TelegramRaw> for TelegramConfig {
 mod tests {
     use crate::{
         configs::{
-            config_file::ConfigFile, custom::telegram_custom::TelegramConfig,
-            raw::telegram_raw::TelegramRaw,
+            config_file::ConfigFile,
+            custom::telegram_custom::TelegramConfig,
+            raw::telegram_raw::{AccountEntry, TelegramRaw},
         },
         utils,
     };
@@ -171,6 +206,7 @@ mod tests {
         let telegram_config = TelegramConfig::default();
         assert_eq!(telegram_config.api_id, "94575");
         assert_eq!(telegram_config.api_hash, "a3406de8d171bb422bb6ddf3bbd800e2");
+        assert!(telegram_config.accounts.is_empty());
     }
 
     #[test]
@@ -179,11 +215,18 @@ mod tests {
             api_id: Some("api_id".to_string()),
             api_hash: Some("api_hash".to_string()),
             database_dir: Some(".data/tg".to_string()),
+            accounts: Some(vec![AccountEntry {
+                name: "work".to_string(),
+                database_dir: None,
+            }]),
             use_file_database: Some(true),
             use_chat_info_database: Some(true),
             use_message_database: Some(true),
             system_language_code: Some("system_language_code".to_string()),
             device_model: Some("device_model".to_string()),
+            use_test_dc: Some(true),
+            ignore_file_names: Some(true),
+            message_unload_delay: Some(3600),
             verbosity_level: Some(1),
             log_path: Some(".data/tdlib_rs/tdlib_rs.log".to_string()),
             redirect_stderr: Some(true),
@@ -199,11 +242,16 @@ mod tests {
                 .to_string_lossy()
                 .to_string()
         );
+        assert_eq!(telegram_config.accounts[0].name, "work");
+        assert_eq!(telegram_config.accounts[0].database_dir, None);
         assert!(telegram_config.use_file_database);
         assert!(telegram_config.use_chat_info_database);
         assert!(telegram_config.use_message_database);
         assert_eq!(telegram_config.system_language_code, "system_language_code");
         assert_eq!(telegram_config.device_model, "device_model");
+        assert!(telegram_config.use_test_dc);
+        assert!(telegram_config.ignore_file_names);
+        assert_eq!(telegram_config.message_unload_delay, 3600);
         assert_eq!(telegram_config.verbosity_level, 1);
         assert_eq!(
             telegram_config.log_path,
@@ -222,11 +270,15 @@ mod tests {
             api_id: "api_id".to_string(),
             api_hash: "api_hash".to_string(),
             database_dir: ".data/tg".to_string(),
+            accounts: vec![],
             use_file_database: false,
             use_chat_info_database: false,
             use_message_database: false,
             system_language_code: "system_language_code".to_string(),
             device_model: "device_model".to_string(),
+            use_test_dc: false,
+            ignore_file_names: false,
+            message_unload_delay: 0,
             verbosity_level: 1,
             log_path: ".data/tdlib_rs/tdlib_rs.log".to_string(),
             redirect_stderr: false,
@@ -235,11 +287,15 @@ mod tests {
             api_id: Some("api_id_2".to_string()),
             api_hash: Some("api_hash_2".to_string()),
             database_dir: None,
+            accounts: None,
             use_file_database: Some(true),
             use_chat_info_database: Some(true),
             use_message_database: Some(true),
             system_language_code: Some("system_language_code_2".to_string()),
             device_model: Some("device_model_2".to_string()),
+            use_test_dc: Some(true),
+            ignore_file_names: Some(true),
+            message_unload_delay: Some(3600),
             verbosity_level: Some(2),
             log_path: None,
             redirect_stderr: Some(true),
@@ -256,6 +312,9 @@ mod tests {
             "system_language_code_2"
         );
         assert_eq!(telegram_config.device_model, "device_model_2");
+        assert!(telegram_config.use_test_dc);
+        assert!(telegram_config.ignore_file_names);
+        assert_eq!(telegram_config.message_unload_delay, 3600);
         assert_eq!(telegram_config.verbosity_level, 2);
         assert_eq!(telegram_config.log_path, ".data/tdlib_rs/tdlib_rs.log");
         assert!(telegram_config.redirect_stderr);
@@ -267,11 +326,15 @@ mod tests {
             api_id: "api_id".to_string(),
             api_hash: "api_hash".to_string(),
             database_dir: ".data/tg".to_string(),
+            accounts: vec![],
             use_file_database: false,
             use_chat_info_database: false,
             use_message_database: false,
             system_language_code: "system_language_code".to_string(),
             device_model: "device_model".to_string(),
+            use_test_dc: false,
+            ignore_file_names: false,
+            message_unload_delay: 0,
             verbosity_level: 1,
             log_path: ".data/tdlib_rs/tdlib_rs.log".to_string(),
             redirect_stderr: false,
@@ -285,6 +348,9 @@ mod tests {
         assert!(!telegram_config.use_message_database);
         assert_eq!(telegram_config.system_language_code, "system_language_code");
         assert_eq!(telegram_config.device_model, "device_model");
+        assert!(!telegram_config.use_test_dc);
+        assert!(!telegram_config.ignore_file_names);
+        assert_eq!(telegram_config.message_unload_delay, 0);
         assert_eq!(telegram_config.verbosity_level, 1);
         assert_eq!(telegram_config.log_path, ".data/tdlib_rs/tdlib_rs.log");
         assert!(!telegram_config.redirect_stderr);
@@ -296,11 +362,15 @@ mod tests {
             api_id: "api_id".to_string(),
             api_hash: "api_hash".to_string(),
             database_dir: ".data/tg".to_string(),
+            accounts: vec![],
             use_file_database: false,
             use_chat_info_database: false,
             use_message_database: false,
             system_language_code: "system_language_code".to_string(),
             device_model: "device_model".to_string(),
+            use_test_dc: false,
+            ignore_file_names: false,
+            message_unload_delay: 0,
             verbosity_level: 1,
             log_path: ".data/tdlib_rs/tdlib_rs.log".to_string(),
             redirect_stderr: false,
@@ -309,11 +379,15 @@ mod tests {
             api_id: Some("api_id_2".to_string()),
             api_hash: None,
             database_dir: None,
+            accounts: None,
             use_file_database: None,
             use_chat_info_database: None,
             use_message_database: None,
             system_language_code: None,
             device_model: None,
+            use_test_dc: None,
+            ignore_file_names: None,
+            message_unload_delay: None,
             verbosity_level: None,
             log_path: None,
             redirect_stderr: Some(true),
@@ -327,6 +401,9 @@ mod tests {
         assert!(!telegram_config.use_message_database);
         assert_eq!(telegram_config.system_language_code, "system_language_code");
         assert_eq!(telegram_config.device_model, "device_model");
+        assert!(!telegram_config.use_test_dc);
+        assert!(!telegram_config.ignore_file_names);
+        assert_eq!(telegram_config.message_unload_delay, 0);
         assert_eq!(telegram_config.verbosity_level, 1);
         assert_eq!(telegram_config.log_path, ".data/tdlib_rs/tdlib_rs.log");
         assert!(telegram_config.redirect_stderr);
@@ -343,11 +420,15 @@ mod tests {
             api_id: "api_id".to_string(),
             api_hash: "api_hash".to_string(),
             database_dir: ".data/tg".to_string(),
+            accounts: vec![],
             use_file_database: false,
             use_chat_info_database: false,
             use_message_database: false,
             system_language_code: "system_language_code".to_string(),
             device_model: "device_model".to_string(),
+            use_test_dc: false,
+            ignore_file_names: false,
+            message_unload_delay: 0,
             verbosity_level: 1,
             log_path: ".data/tdlib_rs/tdlib_rs.log".to_string(),
             redirect_stderr: false,
@@ -356,11 +437,15 @@ mod tests {
             api_id: Some("api_id_2".to_string()),
             api_hash: Some("api_hash_2".to_string()),
             database_dir: None,
+            accounts: None,
             use_file_database: Some(true),
             use_chat_info_database: Some(true),
             use_message_database: Some(true),
             system_language_code: Some("system_language_code_2".to_string()),
             device_model: Some("device_model_2".to_string()),
+            use_test_dc: Some(true),
+            ignore_file_names: Some(true),
+            message_unload_delay: Some(3600),
             verbosity_level: Some(2),
             log_path: None,
             redirect_stderr: Some(true),
@@ -377,6 +462,9 @@ mod tests {
             "system_language_code_2"
         );
         assert_eq!(telegram_config.device_model, "device_model_2");
+        assert!(telegram_config.use_test_dc);
+        assert!(telegram_config.ignore_file_names);
+        assert_eq!(telegram_config.message_unload_delay, 3600);
         assert_eq!(telegram_config.verbosity_level, 2);
         assert_eq!(telegram_config.log_path, ".data/tdlib_rs/tdlib_rs.log");
         assert!(telegram_config.redirect_stderr);
@@ -27,6 +27,8 @@ pub struct ThemeConfig {
     pub title_bar: HashMap<String, ThemeStyle>,
     /// The theme configuration for the reply message.
     pub reply_message: HashMap<String, ThemeStyle>,
+    /// The theme configuration for the popup.
+    pub popup: HashMap<String, ThemeStyle>,
 }
 /// The theme configuration implementation.
 impl ThemeConfig {
@@ -39,6 +41,19 @@ impl ThemeConfig {
             &configs::custom::default_config_theme_file_path()?,
         ))
     }
+    /// Get the theme configuration of a theme file, over the default one.
+    /// It is used to switch the theme while the application is running.
+    ///
+    /// # Arguments
+    /// * `file_name` - The name of the theme file, in the configuration
+    ///   directory.
+    ///
+    /// # Returns
+    /// * `Self` - The theme configuration.
+    pub fn from_filename(file_name: &str) -> Self {
+        let mut default = Self::default();
+        default.merge(Self::deserialize_custom_config::<ThemeRaw>(file_name))
+    }
 }
 /// The implementation of the configuration file for the theme.
 impl ConfigFile for ThemeConfig {
@@ -56,11 +71,7 @@ impl ConfigFile for ThemeConfig {
     // The default value of theme_filename is "theme.toml".
     fn get_config() -> Self {
         if Self::override_fields() {
-            let mut default = Self::default();
-            default.merge(Self::deserialize_custom_config::<Self::Raw>(
-                // Self::get_type().as_default_filename().as_str(),
-                &APP_CONFIG.theme_filename,
-            ))
+            Self::from_filename(&APP_CONFIG.theme_filename)
         } else {
             Self::deserialize_config_or_default::<Self::Raw, Self>(
                 Self::get_type().as_default_filename().as_str(),
@@ -108,6 +119,11 @@ impl ConfigFile for ThemeConfig {
                         self.reply_message.insert(k, ThemeStyle::from(v));
                     });
                 }
+                if let Some(popup) = other.popup {
+                    popup.into_iter().for_each(|(k, v)| {
+                        self.popup.insert(k, ThemeStyle::from(v));
+                    });
+                }
                 self.clone()
             }
         }
@@ -164,6 +180,12 @@ impl From<ThemeRaw> for ThemeConfig {
             .into_iter()
             .map(|(k, v)| (k, ThemeStyle::from(v)))
             .collect();
+        let popup = raw
+            .popup
+            .unwrap()
+            .into_iter()
+            .map(|(k, v)| (k, ThemeStyle::from(v)))
+            .collect();
 
         Self {
             common,
@@ -173,6 +195,7 @@ impl From<ThemeRaw> for ThemeConfig {
             status_bar,
             title_bar,
             reply_message,
+            popup,
         }
     }
 }
@@ -194,11 +217,12 @@ mod tests {
     fn test_theme_config_default() {
         let theme_config = crate::configs::custom::theme_custom::ThemeConfig::default();
         assert_eq!(theme_config.common.len(), 3);
-        assert_eq!(theme_config.chat_list.len(), 5);
+        assert_eq!(theme_config.chat_list.len(), 7);
         assert_eq!(theme_config.chat.len(), 11);
         assert_eq!(theme_config.prompt.len(), 4);
         assert_eq!(theme_config.status_bar.len(), 9);
         assert_eq!(theme_config.title_bar.len(), 4);
+        assert_eq!(theme_config.popup.len(), 3);
     }
 
     #[test]
@@ -211,6 +235,7 @@ mod tests {
             status_bar: Some(HashMap::new()),
             title_bar: Some(HashMap::new()),
             reply_message: Some(HashMap::new()),
+            popup: Some(HashMap::new()),
         };
         let theme_config = ThemeConfig::from(theme_raw);
         assert_eq!(theme_config.common.len(), 0);
@@ -269,6 +294,7 @@ mod tests {
             status_bar: Some(HashMap::new()),
             title_bar: Some(HashMap::new()),
             reply_message: Some(HashMap::new()),
+            popup: Some(HashMap::new()),
         };
         let theme_config = ThemeConfig::from(theme_raw);
         assert_eq!(theme_config.common.len(), 2);
@@ -278,6 +304,7 @@ mod tests {
         assert_eq!(theme_config.status_bar.len(), 0);
         assert_eq!(theme_config.title_bar.len(), 0);
         assert_eq!(theme_config.reply_message.len(), 0);
+        assert_eq!(theme_config.popup.len(), 0);
     }
 
     #[test]
@@ -333,6 +360,7 @@ mod tests {
             status_bar: Some(HashMap::new()),
             title_bar: Some(HashMap::new()),
             reply_message: Some(HashMap::new()),
+            popup: Some(HashMap::new()),
         };
         let mut theme_config = ThemeConfig::from(theme_raw);
 
@@ -377,6 +405,7 @@ mod tests {
             status_bar: Some(HashMap::new()),
             title_bar: Some(HashMap::new()),
             reply_message: Some(HashMap::new()),
+            popup: Some(HashMap::new()),
         };
         let theme_config = theme_config.merge(Some(theme_raw));
         assert_eq!(theme_config.common.len(), 2);
@@ -386,6 +415,7 @@ mod tests {
         assert_eq!(theme_config.status_bar.len(), 0);
         assert_eq!(theme_config.title_bar.len(), 0);
         assert_eq!(theme_config.reply_message.len(), 0);
+        assert_eq!(theme_config.popup.len(), 0);
         assert_eq!(
             theme_config.common.get("default").unwrap().fg,
             Some(Color::Blue)
@@ -420,15 +450,17 @@ mod tests {
             status_bar: Some(HashMap::new()),
             title_bar: Some(HashMap::new()),
             reply_message: Some(HashMap::new()),
+            popup: Some(HashMap::new()),
         };
         theme_config = theme_config.merge(Some(theme_raw));
         assert_eq!(theme_config.common.len(), 3);
-        assert_eq!(theme_config.chat_list.len(), 5);
+        assert_eq!(theme_config.chat_list.len(), 7);
         assert_eq!(theme_config.chat.len(), 11);
         assert_eq!(theme_config.prompt.len(), 4);
         assert_eq!(theme_config.status_bar.len(), 9);
         assert_eq!(theme_config.title_bar.len(), 4);
         assert_eq!(theme_config.reply_message.len(), 2);
+        assert_eq!(theme_config.popup.len(), 3);
     }
 
     #[test]
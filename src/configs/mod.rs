@@ -8,6 +8,7 @@ use std::path::Path;
 pub mod custom;
 pub mod raw;
 
+pub mod config_diagnostics;
 pub mod config_file;
 pub mod config_theme;
 pub mod config_type;
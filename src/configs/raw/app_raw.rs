@@ -26,4 +26,150 @@ pub struct AppRaw {
     /// A boolean flag that represents whether the API_HASH should be taken from
     /// the Telegram configuration or from environment variables `API_HASH`.
     pub take_api_hash_from_telegram_config: Option<bool>,
+    /// The timezone used to display dates and times, for example
+    /// `Europe/Rome`, `UTC` or `+02:00`. If empty, the system local timezone
+    /// is used.
+    pub timezone: Option<String>,
+    /// The locale used to display dates and times, for example `en_US`.
+    pub locale: Option<String>,
+    /// The first day of the week used in calendars, for example `monday`.
+    pub first_day_of_week: Option<String>,
+    /// The external command used to split a file that exceeds the upload size
+    /// limit. If empty, splitting is disabled.
+    pub file_split_command: Option<String>,
+    /// A boolean flag that represents whether the title and the description of
+    /// the links without a Telegram preview should be fetched locally or not.
+    pub url_preview_fetch: Option<bool>,
+    /// The domains whose previews can be fetched locally. If empty, all the
+    /// domains are allowed.
+    pub url_preview_allowlist: Option<Vec<String>>,
+    /// The domains whose previews must never be fetched locally.
+    pub url_preview_denylist: Option<Vec<String>>,
+    /// The custom slash commands of the prompt.
+    pub commands: Option<Vec<CommandEntry>>,
+    /// The maximum number of files uploaded at the same time.
+    pub max_parallel_uploads: Option<usize>,
+    /// A flag that indicates if the large emoji of new messages are animated.
+    pub emoji_animation: Option<bool>,
+    /// A flag that indicates if the highlighted chat of the chat list is previewed.
+    pub chat_list_preview: Option<bool>,
+    /// The command used to play the audio messages of the playlist.
+    pub audio_player_command: Option<String>,
+    /// The command run when a new message is received.
+    pub notification_command: Option<String>,
+    /// The details of the message given to the notification command.
+    pub notification_preview: Option<String>,
+    /// The maximum number of characters of the text given to the notification command.
+    pub notification_preview_length: Option<usize>,
+    /// Enable checking for a newer version of `tgt` at startup.
+    pub check_for_updates: Option<bool>,
+    /// The command used to record a voice note, `{file}` is replaced with
+    /// the path of the recorded file.
+    pub voice_note_command: Option<String>,
+    /// The flag to show the key hints in the status bar.
+    pub show_key_hints: Option<bool>,
+    /// The flag to show the messages of the chat in the compact mode.
+    pub compact_chat: Option<bool>,
+    /// The maximum time between the grouped messages of a sender, in minutes.
+    pub group_messages_minutes: Option<u64>,
+    /// The way the links are shown in the messages.
+    pub url_display: Option<String>,
+    /// The maximum size of the cache of the downloaded files, in megabytes.
+    pub max_cache_size: Option<u64>,
+    /// The name of the file of the theme used with a dark background.
+    pub theme_dark_filename: Option<String>,
+    /// The name of the file of the theme used with a light background.
+    pub theme_light_filename: Option<String>,
+    /// The command of the speech synthesizer that reads the messages aloud.
+    pub speech_command: Option<String>,
+    /// Whether the photos and the stickers are drawn inline in the chat.
+    pub show_images: Option<bool>,
+    /// The templates of the messages, inserted in the prompt from a picker.
+    pub templates: Option<Vec<TemplateEntry>>,
+    /// Whether the birthdays of the contacts are reminded in the status bar.
+    pub birthday_reminder: Option<bool>,
+    /// The command used to open the photo or the video of a message,
+    /// `{file}` is replaced with the path of the downloaded file.
+    pub media_viewer_command: Option<String>,
+    /// The chats whose messages are appended to external files.
+    pub chat_logs: Option<Vec<ChatLogEntry>>,
+    /// The notification rules of the chats of folders.
+    pub folder_notifications: Option<Vec<FolderNotificationEntry>>,
+    /// Send the typing action while typing.
+    pub send_typing_action: Option<bool>,
+    /// The maximum number of messages of the open chat kept in memory.
+    pub max_chat_messages: Option<usize>,
+    /// Scroll the messages smoothly.
+    pub smooth_scrolling: Option<bool>,
+    /// The template of the text of the title bar.
+    pub title_bar_template: Option<String>,
+    /// The command used to open a link, `{url}` is replaced with the link.
+    pub url_open_command: Option<String>,
+    /// The minutes without input after which the user is no longer online.
+    pub auto_away_minutes: Option<u64>,
+    /// The rules of the styles of the chats of the chat list.
+    pub chat_list_styles: Option<Vec<ChatListStyleEntry>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+/// A custom slash command of the prompt.
+/// Exactly one of `actions`, `text`, `exec` and `chat` must be set.
+pub struct CommandEntry {
+    /// The name of the command, without the leading `/`.
+    pub name: String,
+    /// The commands of the keymap configuration to run in order.
+    pub actions: Option<Vec<String>>,
+    /// The text to insert in the prompt.
+    pub text: Option<String>,
+    /// The external command whose output is inserted in the prompt.
+    pub exec: Option<String>,
+    /// The name of the chat to open.
+    pub chat: Option<String>,
+    /// The description of the command.
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+/// A template of a message, inserted in the prompt from the template picker.
+pub struct TemplateEntry {
+    /// The name of the template, shown in the picker.
+    pub name: String,
+    /// The text of the template, with variables like `{first_name}`.
+    pub text: String,
+    /// The kinds of chats where the template is available, all if `None`.
+    pub chat_types: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+/// A chat whose messages are appended to an external file as they arrive.
+pub struct ChatLogEntry {
+    /// The title or the id of the chat.
+    pub chat: String,
+    /// The path of the file, with variables like `{chat}` and `{date}`.
+    pub path: String,
+    /// The line written for each message, the default one if `None`.
+    pub format: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+/// A rule of the styles of the chats of the chat list.
+pub struct ChatListStyleEntry {
+    /// The conditions the chats must all meet, like `muted channels`.
+    pub chats: String,
+    /// The style of the chats, like `bold cyan`.
+    pub style: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+/// A notification rule of the chats of a folder.
+pub struct FolderNotificationEntry {
+    /// The title of the folder.
+    pub folder: String,
+    /// `loud` to notify the messages of the chats, `silent` not to.
+    pub policy: String,
+    /// The time range the rule applies in, in the configured timezone, like
+    /// `09:00-18:00`.
+    pub hours: Option<String>,
+    /// The days the rule applies on, like `sat` or `sunday`.
+    pub days: Option<Vec<String>>,
 }
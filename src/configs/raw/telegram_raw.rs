@@ -13,6 +13,8 @@ pub struct TelegramRaw {
     pub api_hash: Option<String>,
     /// The directory where the database is stored.
     pub database_dir: Option<String>,
+    /// The accounts that can be switched between, each with its database.
+    pub accounts: Option<Vec<AccountEntry>>,
     /// A flag that indicates if the user database should be used.
     pub use_file_database: Option<bool>,
     /// A flag that indicates if the chat info database should be used.
@@ -23,6 +25,12 @@ pub struct TelegramRaw {
     pub system_language_code: Option<String>,
     /// The model of the device.
     pub device_model: Option<String>,
+    /// A flag that indicates if the test environment should be used.
+    pub use_test_dc: Option<bool>,
+    /// A flag that indicates if the original names of the files should be ignored.
+    pub ignore_file_names: Option<bool>,
+    /// The delay after which the messages of the closed chats are unloaded, in seconds.
+    pub message_unload_delay: Option<i64>,
     /// The verbosity level of the logging.
     pub verbosity_level: Option<i32>,
     /// The path to the working directory.
@@ -30,3 +38,13 @@ pub struct TelegramRaw {
     /// A flag that indicates if the log to stderr should be also redirected.
     pub redirect_stderr: Option<bool>,
 }
+
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+/// An account that can be switched to, with its own database.
+pub struct AccountEntry {
+    /// The name of the account, shown in the status bar.
+    pub name: String,
+    /// The directory where the database of the account is stored, a
+    /// directory named after the account in `database_dir` if `None`.
+    pub database_dir: Option<String>,
+}
@@ -33,4 +33,6 @@ pub struct ThemeRaw {
     pub title_bar: Option<HashMap<String, ThemeEntry>>,
     /// The theme for the reply message.
     pub reply_message: Option<HashMap<String, ThemeEntry>>,
+    /// The theme for the popup.
+    pub popup: Option<HashMap<String, ThemeEntry>>,
 }
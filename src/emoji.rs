@@ -0,0 +1,177 @@
+use crate::utils::fuzzy_match;
+use emojis::{Emoji, Group, SkinTone};
+
+/// The skin tones that can be selected in the emoji picker, in order.
+pub const SKIN_TONES: [SkinTone; 6] = [
+    SkinTone::Default,
+    SkinTone::Light,
+    SkinTone::MediumLight,
+    SkinTone::Medium,
+    SkinTone::MediumDark,
+    SkinTone::Dark,
+];
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `EmojiTab` is an enum that represents a tab of the emoji picker.
+pub enum EmojiTab {
+    /// The recently used emojis.
+    Recent,
+    /// The emojis of a category.
+    Group(Group),
+}
+/// Implementation of the `EmojiTab` enum.
+impl EmojiTab {
+    /// All the tabs with their short names, in the order they are shown.
+    pub const ALL: [(Self, &'static str); 10] = [
+        (Self::Recent, "Recent"),
+        (Self::Group(Group::SmileysAndEmotion), "Smileys"),
+        (Self::Group(Group::PeopleAndBody), "People"),
+        (Self::Group(Group::AnimalsAndNature), "Nature"),
+        (Self::Group(Group::FoodAndDrink), "Food"),
+        (Self::Group(Group::TravelAndPlaces), "Travel"),
+        (Self::Group(Group::Activities), "Activities"),
+        (Self::Group(Group::Objects), "Objects"),
+        (Self::Group(Group::Symbols), "Symbols"),
+        (Self::Group(Group::Flags), "Flags"),
+    ];
+}
+
+/// Get the name of a skin tone, shown in the title of the picker.
+///
+/// # Arguments
+/// * `skin_tone` - The skin tone.
+///
+/// # Returns
+/// * `&'static str` - The name of the skin tone.
+pub fn skin_tone_label(skin_tone: SkinTone) -> &'static str {
+    match skin_tone {
+        SkinTone::Light => "light",
+        SkinTone::MediumLight => "medium-light",
+        SkinTone::Medium => "medium",
+        SkinTone::MediumDark => "medium-dark",
+        SkinTone::Dark => "dark",
+        _ => "default",
+    }
+}
+
+/// Apply a skin tone to an emoji, if the emoji supports skin tones.
+///
+/// # Arguments
+/// * `emoji` - The emoji, with the default skin tone.
+/// * `skin_tone` - The skin tone.
+///
+/// # Returns
+/// * `&'static Emoji` - The emoji with the skin tone.
+fn with_skin_tone(emoji: &'static Emoji, skin_tone: SkinTone) -> &'static Emoji {
+    emoji.with_skin_tone(skin_tone).unwrap_or(emoji)
+}
+
+/// Get the emojis shown in the picker.
+/// When a query is typed, the emojis of all the categories whose name or
+/// shortcodes match it are returned, the best matches first.
+///
+/// # Arguments
+/// * `tab` - The selected tab.
+/// * `query` - The text typed to search the emojis.
+/// * `recent` - The recently used emojis, the most recent first.
+/// * `skin_tone` - The selected skin tone.
+///
+/// # Returns
+/// * `Vec<(String, String)>` - The emojis and their names.
+pub fn picker_emojis(
+    tab: EmojiTab,
+    query: &str,
+    recent: &[String],
+    skin_tone: SkinTone,
+) -> Vec<(String, String)> {
+    // The skin tone variants are reached from the emoji with the default tone.
+    let base_emojis = || {
+        emojis::iter()
+            .filter(|emoji| !matches!(emoji.skin_tone(), Some(tone) if tone != SkinTone::Default))
+    };
+    let entry = |emoji: &'static Emoji| {
+        let emoji = with_skin_tone(emoji, skin_tone);
+        (emoji.as_str().to_string(), emoji.name().to_string())
+    };
+    if !query.is_empty() {
+        let mut scored: Vec<(&'static Emoji, i64)> = base_emojis()
+            .filter_map(|emoji| {
+                std::iter::once(emoji.name())
+                    .chain(emoji.shortcodes())
+                    .filter_map(|text| fuzzy_match(query, text))
+                    .max()
+                    .map(|score| (emoji, score))
+            })
+            .collect();
+        // The sort is stable, so emojis with the same score keep the order of
+        // the categories.
+        scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        return scored.into_iter().map(|(emoji, _)| entry(emoji)).collect();
+    }
+    match tab {
+        EmojiTab::Recent => recent
+            .iter()
+            .map(|text| {
+                let name = emojis::get(text).map_or("", |emoji| emoji.name());
+                (text.clone(), name.to_string())
+            })
+            .collect(),
+        EmojiTab::Group(group) => base_emojis()
+            .filter(|emoji| emoji.group() == group)
+            .map(entry)
+            .collect(),
+    }
+}
+
+/// Check whether a character can be part of a shortcode, like `+1` or
+/// `thumbs_up`.
+///
+/// # Arguments
+/// * `c` - The character.
+///
+/// # Returns
+/// * `bool` - Whether it can be part of a shortcode.
+pub fn is_shortcode_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-')
+}
+
+/// Get the emojis whose shortcodes start with a prefix, to complete a
+/// `:shortcode` typed in the prompt. The shortest shortcodes are first, so
+/// that a whole shortcode comes before the longer ones it starts.
+///
+/// # Arguments
+/// * `prefix` - The typed part of the shortcode, without the colon.
+///
+/// # Returns
+/// * `Vec<(String, String)>` - The emojis and their matching shortcodes.
+pub fn complete_shortcode(prefix: &str) -> Vec<(String, String)> {
+    let prefix = prefix.to_lowercase();
+    let mut candidates: Vec<(String, String)> = emojis::iter()
+        .flat_map(|emoji| {
+            emoji
+                .shortcodes()
+                .filter(|shortcode| shortcode.starts_with(&prefix))
+                .map(move |shortcode| (emoji.as_str().to_string(), shortcode.to_string()))
+        })
+        .collect();
+    candidates.sort_by(|a, b| a.1.len().cmp(&b.1.len()).then_with(|| a.1.cmp(&b.1)));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_shortcode() {
+        let candidates = complete_shortcode("smile");
+        assert_eq!(
+            candidates.first(),
+            Some(&("😄".to_string(), "smile".to_string()))
+        );
+        assert!(candidates
+            .iter()
+            .all(|(_, shortcode)| shortcode.starts_with("smile")));
+        assert!(complete_shortcode("not_a_shortcode").is_empty());
+    }
+}
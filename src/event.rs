@@ -1,5 +1,6 @@
 use crate::app_error::AppError;
-use crate::tg::td_enums::{TdChatList, TdMessageReplyToMessage};
+use crate::local_state::UploadDescriptor;
+use crate::tg::td_enums::{TdChatList, TdMessageReplyToMessage, TdTextQuote};
 use crossterm::event::{KeyCode, KeyModifiers, MouseEvent};
 use ratatui::layout::Rect;
 use std::fmt::{self, Display, Formatter};
@@ -36,10 +37,15 @@ pub enum Event {
     /// This event is used to edit a message.
     /// The first parameter is the `message_id` and the second parameter is the `text`.
     EditMessage(i64, String),
-    /// ReplyMessage event with a `String`.
+    /// EditCaption event with an `i64`.
+    /// This event is used to edit the caption of a media message, written as
+    /// Markdown. The parameter is the `message_id`.
+    EditCaption(i64),
+    /// ReplyMessage event with a `String` and an `Option<TdTextQuote>`.
     /// This event is used to reply to a message.
-    /// The first parameter is the `message_id` and the second parameter is the `text`.
-    ReplyMessage(i64, String),
+    /// The first parameter is the `message_id`, the second parameter is the `text`
+    /// and the third parameter is the quoted part of the message, if any.
+    ReplyMessage(i64, String, Option<TdTextQuote>),
 
     /// GetMe event.
     GetMe,
@@ -55,6 +61,10 @@ pub enum Event {
     SendMessageEdited(i64, String),
     /// Get chat history event.
     GetChatHistory,
+    /// Get newer chat history event.
+    GetNewerChatHistory,
+    /// Get latest chat history event.
+    GetLatestChatHistory,
     /// Delete messages event with a `Vec<i64>` and a `bool`.
     /// The first parameter is the `message_ids` and the second parameter is the `revoke`.
     /// If `revoke` is true, the message will be deleted for everyone.
@@ -62,6 +72,42 @@ pub enum Event {
     DeleteMessages(Vec<i64>, bool),
     /// View all messages event.
     ViewAllMessages,
+    /// Process send queue event.
+    /// This event is used to start the queued uploads when a lane is free.
+    ProcessSendQueue,
+    /// Upload failed event with an `UploadDescriptor`.
+    /// This event is used to offer to retry an upload that could not be
+    /// completed.
+    UploadFailed(UploadDescriptor),
+    /// New message event with two `i64` and a `String`.
+    /// This event is used to run the notification command for an incoming
+    /// message. The first parameter is the `chat_id`, the second parameter is
+    /// the `sender_id` and the third parameter is the `text`.
+    NewMessage(i64, i64, String),
+    /// Log message event with two `i64`.
+    /// This event is used to append a message to the log of its chat, if it
+    /// is configured. The first parameter is the `chat_id` and the second
+    /// parameter is the `message_id`.
+    LogMessage(i64, i64),
+    /// Update chat action event with two `i64` and a `bool`.
+    /// This event is used to show the users typing in a chat. The first
+    /// parameter is the `chat_id`, the second parameter is the `user_id` and
+    /// the third parameter is whether the user is typing.
+    UpdateChatAction(i64, i64, bool),
+    /// Reconnected event with two `i64`.
+    /// This event is used to fetch the messages of the open chat received
+    /// while offline. The first parameter is the `chat_id` and the second
+    /// parameter is the id of the last message known before going offline.
+    Reconnected(i64, i64),
+    /// Chat draft changed event with an `i64`.
+    /// This event is used to tell the prompt that the draft of the open chat
+    /// changed, like on another device. The parameter is the `chat_id`.
+    ChatDraftChanged(i64),
+    /// Pinned message changed event with an `i64`.
+    /// This event is used to load the pinned message of the open chat again
+    /// when a message of it is pinned or unpinned. The parameter is the
+    /// `chat_id`.
+    PinnedMessageChanged(i64),
 }
 /// Implement the `Event` enum.
 impl Event {
@@ -190,18 +236,51 @@ impl Display for Event {
             Event::GetChatHistory => {
                 write!(f, "GetChatHistory")
             }
+            Event::GetNewerChatHistory => {
+                write!(f, "GetNewerChatHistory")
+            }
+            Event::GetLatestChatHistory => {
+                write!(f, "GetLatestChatHistory")
+            }
             Event::DeleteMessages(message_ids, revoke) => {
                 write!(f, "DeleteMessages({:?}, {})", message_ids, revoke)
             }
             Event::EditMessage(message_id, text) => {
                 write!(f, "EditMessage({}, {})", message_id, text)
             }
-            Event::ReplyMessage(message_id, text) => {
-                write!(f, "ReplyMessage({}, {})", message_id, text)
+            Event::EditCaption(message_id) => {
+                write!(f, "EditCaption({})", message_id)
+            }
+            Event::ReplyMessage(message_id, text, quote) => {
+                write!(f, "ReplyMessage({}, {}, {:?})", message_id, text, quote)
             }
             Event::ViewAllMessages => {
                 write!(f, "ViewAllMessages")
             }
+            Event::ProcessSendQueue => {
+                write!(f, "ProcessSendQueue")
+            }
+            Event::UploadFailed(upload) => {
+                write!(f, "UploadFailed({})", upload.path)
+            }
+            Event::NewMessage(chat_id, sender_id, text) => {
+                write!(f, "NewMessage({}, {}, {})", chat_id, sender_id, text)
+            }
+            Event::LogMessage(chat_id, message_id) => {
+                write!(f, "LogMessage({}, {})", chat_id, message_id)
+            }
+            Event::UpdateChatAction(chat_id, user_id, typing) => {
+                write!(f, "UpdateChatAction({}, {}, {})", chat_id, user_id, typing)
+            }
+            Event::Reconnected(chat_id, last_message_id) => {
+                write!(f, "Reconnected({}, {})", chat_id, last_message_id)
+            }
+            Event::ChatDraftChanged(chat_id) => {
+                write!(f, "ChatDraftChanged({})", chat_id)
+            }
+            Event::PinnedMessageChanged(chat_id) => {
+                write!(f, "PinnedMessageChanged({})", chat_id)
+            }
         }
     }
 }
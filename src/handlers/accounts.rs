@@ -0,0 +1,69 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::{Popup, PopupItem},
+    handlers::show_error,
+};
+
+/// Show the accounts of `telegram.toml`, to switch to the picked one.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_accounts(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    let items: Vec<PopupItem> = {
+        let account_manager = app_context.account_manager();
+        let active = account_manager.active_name();
+        account_manager
+            .accounts()
+            .iter()
+            .map(|account| {
+                let mark = if active == Some(account.name.as_str()) {
+                    "✔ "
+                } else {
+                    ""
+                };
+                PopupItem::new(
+                    format!("{}{}", mark, account.name),
+                    Action::SwitchAccount(account.name.clone()),
+                )
+            })
+            .collect()
+    };
+    if items.is_empty() {
+        return show_error(
+            app_context,
+            "Accounts",
+            "Add your accounts to `accounts` in telegram.toml to switch between them.",
+        );
+    }
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "Accounts".to_string(),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Switch to an account. The account is remembered and `tgt` is restarted
+/// with its database, asking to log in if it is new.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `name` - The name of the account.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn switch_account(app_context: &AppContext, name: String) -> Result<(), AppError<Action>> {
+    if app_context.account_manager().active_name() == Some(name.as_str()) {
+        return Ok(());
+    }
+    tracing::info!("Switching to the account {}", name);
+    app_context.local_state().active_account = Some(name);
+    app_context.save_local_state();
+    app_context.restart_store(true);
+    app_context.action_tx().send(Action::Quit)?;
+    Ok(())
+}
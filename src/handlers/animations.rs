@@ -0,0 +1,181 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::{MediaItem, Popup},
+    handlers::show_error,
+    tg::tg_backend::TgBackend,
+};
+use tdlib_rs::{enums::ThumbnailFormat, types::Animation};
+
+/// The username of the inline bot the GIFs are searched with.
+const GIF_BOT_USERNAME: &str = "gif";
+
+/// Get the file of the first frame of an animation that can be decoded: its
+/// thumbnail, if it is a still image or a GIF.
+///
+/// # Arguments
+/// * `animation` - The animation.
+///
+/// # Returns
+/// * `Option<i32>` - The id of the file, or `None` if the animation has no
+///   thumbnail that can be decoded.
+fn animation_image_file_id(animation: &Animation) -> Option<i32> {
+    animation
+        .thumbnail
+        .as_ref()
+        .filter(|thumbnail| {
+            matches!(
+                thumbnail.format,
+                ThumbnailFormat::Jpeg
+                    | ThumbnailFormat::Png
+                    | ThumbnailFormat::Webp
+                    | ThumbnailFormat::Gif
+            )
+        })
+        .map(|thumbnail| thumbnail.file.id)
+}
+
+/// Get the label of an animation in the animation picker: its name and its
+/// duration.
+///
+/// # Arguments
+/// * `file_name` - The name of the file of the animation.
+/// * `duration` - The duration of the animation, in seconds.
+///
+/// # Returns
+/// * `String` - The label.
+fn animation_label(file_name: &str, duration: i32) -> String {
+    let name = if file_name.is_empty() {
+        "Animation"
+    } else {
+        file_name
+    };
+    format!("🎞️ {} · {}s", name, duration)
+}
+
+/// Get the items of the animation picker, that send the animations to the
+/// open chat.
+///
+/// # Arguments
+/// * `animations` - The animations.
+///
+/// # Returns
+/// * `Vec<MediaItem>` - The items.
+fn animation_items(animations: Vec<Animation>) -> Vec<MediaItem> {
+    animations
+        .into_iter()
+        .map(|animation| MediaItem {
+            label: animation_label(&animation.file_name, animation.duration),
+            fallback: "🎞️".to_string(),
+            image_file_id: animation_image_file_id(&animation),
+            action: Action::SendAnimation(animation.animation.id),
+        })
+        .collect()
+}
+
+/// Show the animation picker, to send a saved animation to the open chat or
+/// to search GIFs with the `@gif` bot.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_animation_picker(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+) -> Result<(), AppError<Action>> {
+    if app_context.tg_context().open_chat_id() == 0 {
+        return show_error(app_context, "GIFs", "Open a chat before sending a GIF.");
+    }
+    let animations = match tg_backend.get_saved_animations().await {
+        Ok(animations) => animations,
+        Err(e) => return show_error(app_context, "GIFs", e.message),
+    };
+    let mut items = vec![MediaItem {
+        label: format!("🔎 Search GIFs with @{}", GIF_BOT_USERNAME),
+        fallback: "🔎".to_string(),
+        image_file_id: None,
+        action: Action::ShowPopup(Popup::Input(
+            "Search GIFs".to_string(),
+            Box::new(Action::SearchAnimations(String::new())),
+        )),
+    }];
+    items.extend(animation_items(animations));
+    app_context
+        .action_tx()
+        .send(Action::ShowPopup(Popup::Media("GIFs".to_string(), items)))?;
+    Ok(())
+}
+
+/// Search GIFs with the `@gif` bot and show them in the animation picker.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `query` - The text of the search.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn search_animations(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    query: &str,
+) -> Result<(), AppError<Action>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(());
+    }
+    let chat_id = app_context.tg_context().open_chat_id();
+    let animations = match tg_backend
+        .search_inline_animations(GIF_BOT_USERNAME, chat_id, query.to_string())
+        .await
+    {
+        Ok(animations) => animations,
+        Err(e) => return show_error(app_context, "GIFs", e.message),
+    };
+    if animations.is_empty() {
+        return show_error(app_context, "GIFs", format!("No GIFs of {} found.", query));
+    }
+    app_context
+        .action_tx()
+        .send(Action::ShowPopup(Popup::Media(
+            format!("GIFs of {}", query),
+            animation_items(animations),
+        )))?;
+    Ok(())
+}
+
+/// Send an animation to the open chat.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `file_id` - The id of the file of the animation.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn send_animation(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    file_id: i32,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    if let Err(e) = tg_backend.send_animation(chat_id, file_id).await {
+        return show_error(app_context, "Send GIF", e.message);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_animation_label() {
+        assert_eq!(animation_label("cat.mp4", 3), "🎞️ cat.mp4 · 3s");
+        assert_eq!(animation_label("", 0), "🎞️ Animation · 0s");
+    }
+}
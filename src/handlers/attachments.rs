@@ -0,0 +1,301 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::{Popup, PopupItem},
+    handlers::{show_error, uploads::send_file},
+    tg::attachments::{self, AttachmentKind, SelfDestruct, SELF_DESTRUCT_TIMERS},
+    utils::{expand_shell_placeholders, shell_command},
+};
+use arboard::Clipboard;
+use std::sync::Arc;
+use tdlib_rs::enums::ChatType;
+
+/// The maximum number of characters of the caption of a file. A longer
+/// caption is sent as a separate message.
+const CAPTION_MAX_LENGTH: usize = 1024;
+
+/// Show the attachments staged to be sent with the next message. Selecting
+/// an attachment removes it, and new files, images from the clipboard and
+/// voice notes can be staged.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_attachments(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    let (mut items, recording, self_destruct) = {
+        let tg_context = app_context.tg_context();
+        let attachments = tg_context.attachments();
+        let items: Vec<PopupItem> = attachments
+            .items()
+            .iter()
+            .enumerate()
+            .map(|(i, attachment)| {
+                PopupItem::new(
+                    format!("Remove {}", attachment.chip()),
+                    Action::UnstageAttachment(i),
+                )
+            })
+            .collect();
+        (
+            items,
+            attachments.is_recording(),
+            attachments.self_destruct(),
+        )
+    };
+    let has_attachments = !items.is_empty();
+    items.push(PopupItem::new(
+        "Attach a file",
+        Action::ShowPopup(Popup::Files(
+            "File to attach".to_string(),
+            Box::new(Action::StageAttachment(String::new())),
+        )),
+    ));
+    items.push(PopupItem::new(
+        "Attach the image in the clipboard",
+        Action::StageClipboardImage,
+    ));
+    if !recording {
+        items.push(PopupItem::new(
+            "Record a voice note",
+            Action::RecordVoiceNote,
+        ));
+    }
+    if has_attachments {
+        items.push(PopupItem::new(
+            format!(
+                "Self-destruction of the photos and videos: {}",
+                self_destruct.map_or("off".to_string(), |self_destruct| self_destruct.label())
+            ),
+            Action::ShowSelfDestruct,
+        ));
+        items.push(PopupItem::new("Remove all", Action::ClearAttachments));
+    }
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "Attachments".to_string(),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Show the self-destructions that can be chosen for the staged photos and
+/// videos. Telegram allows them only in the private chats.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_self_destruct(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    let is_private = {
+        let tg_context = app_context.tg_context();
+        let chats = tg_context.chats();
+        chats
+            .get(&tg_context.open_chat_id())
+            .is_some_and(|chat| matches!(chat.r#type, ChatType::Private(_)))
+    };
+    if !is_private {
+        return show_error(
+            app_context,
+            "Self-destruction",
+            "The photos and the videos can self-destruct only in the private chats.",
+        );
+    }
+    let mut items = vec![
+        PopupItem::new("Off", Action::SetSelfDestruct(None)),
+        PopupItem::new(
+            "View once",
+            Action::SetSelfDestruct(Some(SelfDestruct::ViewOnce)),
+        ),
+    ];
+    items.extend(SELF_DESTRUCT_TIMERS.iter().map(|seconds| {
+        let self_destruct = SelfDestruct::Timer(*seconds);
+        PopupItem::new(
+            format!("Delete {} after opening", self_destruct.label()),
+            Action::SetSelfDestruct(Some(self_destruct)),
+        )
+    }));
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "Self-destruction of the photos and videos".to_string(),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Stage a file to be sent with the next message.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `path` - The path of the file.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn stage_attachment(app_context: &AppContext, path: &str) -> Result<(), AppError<Action>> {
+    let path = path.trim();
+    if !std::path::Path::new(path).is_file() {
+        return show_error(
+            app_context,
+            "Attach a file",
+            format!("{} is not a file.", path),
+        );
+    }
+    app_context
+        .tg_context()
+        .attachments()
+        .push(path.to_string(), AttachmentKind::File);
+    Ok(())
+}
+
+/// Get the path of a new temporary file for an attachment, like an image
+/// pasted from the clipboard.
+///
+/// # Arguments
+/// * `name` - The name of the file, without extension.
+/// * `extension` - The extension of the file.
+///
+/// # Returns
+/// * `String` - The path of the file.
+fn attachment_path(name: &str, extension: &str) -> String {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S%3f");
+    std::env::temp_dir()
+        .join(format!("tgt-{}-{}.{}", name, timestamp, extension))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Save the image copied in the clipboard in a temporary PNG file and stage
+/// it to be sent with the next message.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn stage_clipboard_image(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    let image = match Clipboard::new().and_then(|mut clipboard| clipboard.get_image()) {
+        Ok(image) => image,
+        Err(e) => return show_error(app_context, "Attach an image", e.to_string()),
+    };
+    let Some(buffer) = image::RgbaImage::from_raw(
+        image.width as u32,
+        image.height as u32,
+        image.bytes.into_owned(),
+    ) else {
+        return show_error(
+            app_context,
+            "Attach an image",
+            "The image in the clipboard is not valid.",
+        );
+    };
+    let path = attachment_path("clipboard", "png");
+    if let Err(e) = buffer.save(&path) {
+        return show_error(app_context, "Attach an image", e.to_string());
+    }
+    app_context
+        .tg_context()
+        .attachments()
+        .push(path, AttachmentKind::Image);
+    Ok(())
+}
+
+/// Record a voice note with the configured external command in the
+/// background and stage it when the command exits.
+///
+/// # Arguments
+/// * `app_context` - An Arc wrapped AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn record_voice_note(app_context: Arc<AppContext>) -> Result<(), AppError<Action>> {
+    let command_line = app_context.app_config().voice_note_command.clone();
+    if command_line.is_empty() {
+        return show_error(
+            &app_context,
+            "Record a voice note",
+            "Set `voice_note_command` in app.toml to record voice notes.",
+        );
+    }
+    let path = attachment_path("voice-note", "ogg");
+    let command_line = expand_shell_placeholders(&command_line, &[("{file}", &path)]);
+    tracing::info!("Recording a voice note with: {}", command_line);
+    app_context.tg_context().attachments().set_recording(true);
+    tokio::spawn(async move {
+        let output = shell_command(&command_line).output().await;
+        app_context.tg_context().attachments().set_recording(false);
+        let result = match output {
+            Ok(output) if output.status.success() && std::path::Path::new(&path).is_file() => {
+                app_context
+                    .tg_context()
+                    .attachments()
+                    .push(path, AttachmentKind::VoiceNote);
+                Ok(())
+            }
+            Ok(output) => show_error(
+                &app_context,
+                "Record a voice note",
+                String::from_utf8_lossy(&output.stderr),
+            ),
+            Err(e) => show_error(&app_context, "Record a voice note", e.to_string()),
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to record a voice note: {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// Send the staged attachments to the open chat, in the order they were
+/// staged. The caption is sent with the last attachment or, if it is too
+/// long for a caption, as a separate message before the attachments.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `caption` - The text typed in the prompt.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn send_attachments(app_context: &AppContext, caption: &str) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    if chat_id == 0 {
+        return show_error(
+            app_context,
+            "Send attachments",
+            "Open a chat before sending the attachments.",
+        );
+    }
+    let (attachments, self_destruct) = {
+        let tg_context = app_context.tg_context();
+        let mut attachments = tg_context.attachments();
+        let self_destruct = attachments.self_destruct();
+        (attachments.take(), self_destruct)
+    };
+    let mut caption = if caption.trim().is_empty() {
+        None
+    } else if caption.chars().count() > CAPTION_MAX_LENGTH {
+        app_context
+            .action_tx()
+            .send(Action::SendMessage(caption.to_string(), None))?;
+        None
+    } else {
+        Some(caption.to_string())
+    };
+    let last = attachments.len().saturating_sub(1);
+    for (i, attachment) in attachments.iter().enumerate() {
+        let caption = if i == last { caption.take() } else { None };
+        // Only the photos and the videos can self-destruct, the other files
+        // are sent as usual.
+        let self_destruct = self_destruct.filter(|_| {
+            attachments::is_photo(&attachment.path) || attachments::is_video(&attachment.path)
+        });
+        send_file(
+            app_context,
+            chat_id,
+            &attachment.path,
+            caption,
+            self_destruct,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    audio_player::{self, AudioPlayer, AudioTrack},
+    handlers::show_error,
+    tg::tg_backend::TgBackend,
+};
+use std::sync::Arc;
+
+/// Add an audio message to the playlist and play it if nothing is being
+/// played.
+///
+/// # Arguments
+/// * `app_context` - An Arc wrapped AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `track` - The audio message to add.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn queue_audio(
+    app_context: Arc<AppContext>,
+    tg_backend: &TgBackend,
+    track: AudioTrack,
+) -> Result<(), AppError<Action>> {
+    if !audio_player::is_available(&app_context.app_config().audio_player_command) {
+        return show_error(
+            &app_context,
+            "Audio player",
+            "Set `audio_player_command` in app.toml to play the audio messages.",
+        );
+    }
+    let (added, is_playing) = {
+        let mut audio_player = app_context.audio_player();
+        (audio_player.enqueue(track), audio_player.is_playing())
+    };
+    if !added {
+        return show_error(
+            &app_context,
+            "Audio player",
+            "The audio message is already in the playlist.",
+        );
+    }
+    if !is_playing {
+        play_audio_track(app_context, tg_backend, AudioPlayer::next_track)?;
+    }
+    Ok(())
+}
+
+/// Play a track of the playlist, stopping the track being played.
+/// The file is downloaded in the background and then played with the
+/// configured external command. When the command exits, the next track is
+/// played. At the end of the playlist, the playlist is emptied.
+///
+/// # Arguments
+/// * `app_context` - An Arc wrapped AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `select` - The function that selects the track in the playlist.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn play_audio_track(
+    app_context: Arc<AppContext>,
+    tg_backend: &TgBackend,
+    select: fn(&mut AudioPlayer) -> Option<AudioTrack>,
+) -> Result<(), AppError<Action>> {
+    let command_line = app_context.app_config().audio_player_command.clone();
+    let (track, generation) = {
+        let mut audio_player = app_context.audio_player();
+        match select(&mut audio_player) {
+            Some(track) if audio_player::is_available(&command_line) => {
+                let generation = audio_player.stop();
+                (track, generation)
+            }
+            _ => {
+                audio_player.clear();
+                return Ok(());
+            }
+        }
+    };
+    let download = tg_backend.download_file(track.metadata.file_id);
+    tokio::spawn(async move {
+        let result = match download.await {
+            Some(path) => start_audio(&app_context, generation, &path),
+            None => show_error(
+                &app_context,
+                "Audio player",
+                format!("Failed to download {}.", track.metadata.name()),
+            ),
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to play {}: {}", track.metadata.name(), e);
+        }
+    });
+    Ok(())
+}
+
+/// Start playing the downloaded file of the selected track, from the position
+/// reached by the playlist. When the playback reaches the end, the next
+/// track is played.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `generation` - The generation of the playback.
+/// * `path` - The path of the downloaded file.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn start_audio(
+    app_context: &AppContext,
+    generation: u64,
+    path: &str,
+) -> Result<(), AppError<Action>> {
+    let command_line = app_context.app_config().audio_player_command.clone();
+    let action_tx = app_context.action_tx().clone();
+    let played =
+        app_context
+            .audio_player()
+            .play(generation, &command_line, path, move |generation| {
+                if let Err(e) = action_tx.send(Action::AudioTrackFinished(generation)) {
+                    tracing::error!("Failed to play the next track: {}", e);
+                }
+            });
+    match played {
+        Ok(()) => Ok(()),
+        Err(e) => show_error(app_context, "Audio player", e.to_string()),
+    }
+}
+
+/// Read the next text of the queue aloud with the speech command, if any.
+///
+/// # Arguments
+/// * `app_context` - An Arc wrapped AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn speak_next(app_context: Arc<AppContext>) -> Result<(), AppError<Action>> {
+    let command_line = app_context.app_config().speech_command.clone();
+    if command_line.is_empty() {
+        app_context.speaker().stop();
+        return show_error(
+            &app_context,
+            "Read aloud",
+            "Set `speech_command` in app.toml to read the messages aloud.",
+        );
+    }
+    let action_tx = app_context.action_tx().clone();
+    let spoken = {
+        let mut speaker = app_context.speaker();
+        let Some((text, generation)) = speaker.next_text() else {
+            return Ok(());
+        };
+        speaker.speak(generation, &command_line, &text, move |generation| {
+            if let Err(e) = action_tx.send(Action::SpeechFinished(generation)) {
+                tracing::error!("Failed to read the next text: {}", e);
+            }
+        })
+    };
+    match spoken {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            app_context.speaker().stop();
+            show_error(&app_context, "Read aloud", e.to_string())
+        }
+    }
+}
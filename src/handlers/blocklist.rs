@@ -0,0 +1,136 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::{Popup, PopupItem},
+    handlers::show_error,
+    tg::{blocklist, tg_backend::TgBackend},
+};
+use tdlib_rs::enums::MessageSender;
+
+/// Show the actions of the blocklist: export the blocked users to a file or
+/// import a blocklist from a file.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_blocklist(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "Blocklist".to_string(),
+        vec![
+            PopupItem::new(
+                "Export the blocked users to a file",
+                Action::ShowPopup(Popup::Input(
+                    "Path of the file to export the blocked users to".to_string(),
+                    Box::new(Action::ExportBlocklist(String::new())),
+                )),
+            ),
+            PopupItem::new(
+                "Block the users of a file",
+                Action::ShowPopup(Popup::Input(
+                    "Path of the blocklist to import".to_string(),
+                    Box::new(Action::ImportBlocklist(String::new())),
+                )),
+            ),
+        ],
+    )))?;
+    Ok(())
+}
+
+/// Write the blocked users and chats to a file, one per line, so that they
+/// can be imported in another account.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `path` - The path of the file.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn export_blocklist(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    path: &str,
+) -> Result<(), AppError<Action>> {
+    let senders = match tg_backend.get_blocked_senders().await {
+        Ok(senders) => senders,
+        Err(e) => return show_error(app_context, "Export blocklist", e.message),
+    };
+    let tg_context = app_context.tg_context();
+    let mut lines = vec![format!(
+        "# Blocked users exported by tgt on {}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M")
+    )];
+    for sender in senders.iter() {
+        let line = match sender {
+            MessageSender::User(user) => match tg_context.users().get(&user.user_id) {
+                Some(user) => blocklist::format_blocklist_line(
+                    user.id,
+                    user.usernames
+                        .as_ref()
+                        .and_then(|usernames| usernames.active_usernames.first())
+                        .map(String::as_str),
+                    format!("{} {}", user.first_name, user.last_name).trim(),
+                ),
+                None => blocklist::format_blocklist_line(user.user_id, None, ""),
+            },
+            MessageSender::Chat(chat) => {
+                let title = tg_context.name_from_chats(chat.chat_id).unwrap_or_default();
+                blocklist::format_blocklist_line(chat.chat_id, None, &title)
+            }
+        };
+        lines.push(line);
+    }
+    lines.push(String::new());
+    if let Err(e) = std::fs::write(path.trim(), lines.join("\n")) {
+        return show_error(app_context, "Export blocklist", e.to_string());
+    }
+    app_context.action_tx().send(Action::ShowPopup(Popup::Info(
+        "Export blocklist".to_string(),
+        format!(
+            "{} blocked users exported to {}.",
+            senders.len(),
+            path.trim()
+        ),
+    )))?;
+    Ok(())
+}
+
+/// Read a blocklist from a file and block its users and chats in the
+/// background.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `path` - The path of the file.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn import_blocklist(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    path: &str,
+) -> Result<(), AppError<Action>> {
+    let entries = match std::fs::read_to_string(path.trim()) {
+        Ok(text) => blocklist::parse_blocklist(&text),
+        Err(e) => return show_error(app_context, "Import blocklist", e.to_string()),
+    };
+    if entries.is_empty() {
+        return show_error(
+            app_context,
+            "Import blocklist",
+            format!("{} contains no user to block.", path.trim()),
+        );
+    }
+    app_context.action_tx().send(Action::ShowPopup(Popup::Info(
+        "Import blocklist".to_string(),
+        format!(
+            "Blocking {} users in the background, a summary is shown when it is done.",
+            entries.len()
+        ),
+    )))?;
+    tg_backend.import_blocklist(entries);
+    Ok(())
+}
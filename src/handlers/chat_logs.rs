@@ -0,0 +1,69 @@
+use crate::{
+    app_context::AppContext,
+    chat_log,
+    tg::{message_entry::MessageEntry, tg_backend::TgBackend},
+};
+
+/// Append a new message to the log of its chat, if it is configured.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `chat_id` - The id of the chat.
+/// * `message_id` - The id of the message.
+pub async fn log_message(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    chat_id: i64,
+    message_id: i64,
+) {
+    if chat_log::log_of_chat(app_context, chat_id).is_none() {
+        return;
+    }
+    let Some(message) = tg_backend.get_message(chat_id, message_id).await else {
+        return;
+    };
+    if let Err(e) = chat_log::append(app_context, chat_id, &[MessageEntry::from(&message)]) {
+        tracing::error!(
+            "Failed to log message {} of chat {}: {}",
+            message_id,
+            chat_id,
+            e
+        );
+    }
+}
+
+/// Append to the logs of the chats the messages received while `tgt` was
+/// closed, up to the last `chat_log::SYNC_LIMIT` of each chat.
+/// The log of a chat logged for the first time starts from its next
+/// message.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+pub async fn sync_chat_logs(app_context: &AppContext, tg_backend: &TgBackend) {
+    for chat_id in chat_log::logged_chat_ids(app_context) {
+        let position = app_context.local_state().chat_log_position(chat_id);
+        if position.is_none() {
+            let last_message_id = app_context
+                .tg_context()
+                .chats()
+                .get(&chat_id)
+                .and_then(|chat| chat.last_message.as_ref().map(|message| message.id));
+            if let Some(last_message_id) = last_message_id {
+                app_context
+                    .local_state()
+                    .set_chat_log_position(chat_id, last_message_id);
+                app_context.save_local_state();
+            }
+            continue;
+        }
+        let mut messages = tg_backend
+            .get_last_messages(chat_id, chat_log::SYNC_LIMIT)
+            .await;
+        messages.reverse();
+        if let Err(e) = chat_log::append(app_context, chat_id, &messages) {
+            tracing::error!("Failed to sync the log of chat {}: {}", chat_id, e);
+        }
+    }
+}
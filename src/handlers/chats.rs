@@ -0,0 +1,927 @@
+use crate::{
+    action::{Action, ChatBatchOperation, ChatDeletion, NewChat, NewChatKind},
+    app_context::AppContext,
+    app_error::AppError,
+    component_name::ComponentName::{self, Prompt},
+    components::popup_window::{Popup, PopupItem},
+    handlers::{contacts::load_contacts, show_error},
+    local_state::RECENT_CHATS_POPUP_SIZE,
+    tg::{
+        message_entry::{DateTimeEntry, MessageEntry},
+        td_enums::{TdChatList, TdMessageSender},
+        tg_backend::TgBackend,
+    },
+    utils::{decode_base64, format_key_fingerprint, human_duration, render_key_visualization},
+};
+use tdlib_rs::enums::{ChatList, ChatType, MessageSender, SecretChatState};
+use tdlib_rs::types::{MessageSenderChat, MessageSenderUser};
+
+/// Open a chat.
+/// The chat that was open before is pushed on top of the recently closed
+/// chats, so that it can be reopened later.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `chat_id` - The id of the chat to open.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn open_chat(app_context: &AppContext, chat_id: i64) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let previous_chat_id = tg_context.open_chat_id();
+    {
+        let mut local_state = app_context.local_state();
+        if previous_chat_id != 0 && previous_chat_id != chat_id {
+            local_state.push_recent_chat(previous_chat_id);
+        }
+        local_state.remove_recent_chat(chat_id);
+    }
+    app_context.save_local_state();
+
+    // The results of the search of the chat are kept while jumping to one
+    // of them.
+    if previous_chat_id != chat_id {
+        tg_context.chat_search_results().clear();
+    }
+    tg_context.set_preview_chat_id(0);
+    tg_context.set_open_chat_user(tg_context.user_of_chat(chat_id));
+    tg_context.set_open_chat_id(chat_id);
+    tg_context.clear_open_chat_messages();
+    tg_context.set_from_message_id(0);
+    tg_context.set_jump_message_id(0);
+    // The unread messages are marked as read once they are shown, and the
+    // unread separator stays where it was when the chat was opened.
+    let unread_boundary = tg_context
+        .chats()
+        .get(&chat_id)
+        .filter(|chat| chat.unread_count > 0)
+        .map_or(0, |chat| chat.last_read_inbox_message_id);
+    tg_context.set_unread_boundary_message_id(unread_boundary);
+    tg_context.set_pinned_message(None);
+
+    app_context
+        .action_tx()
+        .send(Action::FocusComponent(Prompt))?;
+    // Load chat history
+    app_context.action_tx().send(Action::GetChatHistory)?;
+    app_context.action_tx().send(Action::LoadPinnedMessage)?;
+    Ok(())
+}
+
+/// Open a chat at a message, for example the original message of a forwarded
+/// message. The history of the chat is loaded starting from the message.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A mutable reference to the TgBackend struct.
+/// * `chat_id` - The id of the chat.
+/// * `message_id` - The id of the message.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn open_message(
+    app_context: &AppContext,
+    tg_backend: &mut TgBackend,
+    chat_id: i64,
+    message_id: i64,
+) -> Result<(), AppError<Action>> {
+    if tg_backend.get_message(chat_id, message_id).await.is_none() {
+        return show_error(
+            app_context,
+            "Open message",
+            "The message is not accessible, it may have been deleted or the chat may be private.",
+        );
+    }
+    open_chat_at(app_context, chat_id, message_id)?;
+    app_context
+        .action_tx()
+        .send(Action::FocusComponent(ComponentName::Chat))?;
+    Ok(())
+}
+
+/// Open a chat with its history loaded starting from a message.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `chat_id` - The id of the chat.
+/// * `message_id` - The id of the message.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+fn open_chat_at(
+    app_context: &AppContext,
+    chat_id: i64,
+    message_id: i64,
+) -> Result<(), AppError<Action>> {
+    open_chat(app_context, chat_id)?;
+    // The history is loaded once the action is handled, starting from the
+    // message, and the newer messages once scrolled to. The message is
+    // selected once it is shown.
+    app_context.tg_context().set_from_message_id(message_id);
+    app_context.tg_context().set_has_newer_messages(true);
+    app_context.tg_context().set_jump_message_id(message_id);
+    Ok(())
+}
+
+/// Open the open chat at the last message sent before a date, loading the
+/// messages around it.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `date` - The date, as typed by the user.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn jump_to_date(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    date: &str,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    if chat_id == 0 {
+        return Ok(());
+    }
+    let Some(timestamp) = DateTimeEntry::parse_date_time(date) else {
+        return show_error(
+            app_context,
+            "Go to date",
+            format!(
+                "{} is not a valid date, type it like 2024-05-01 or 2024-05-01 14:30.",
+                date.trim()
+            ),
+        );
+    };
+    match tg_backend
+        .get_chat_message_by_date(chat_id, timestamp)
+        .await
+    {
+        Ok(message) => {
+            open_chat_at(app_context, chat_id, message.id)?;
+            app_context
+                .action_tx()
+                .send(Action::FocusComponent(ComponentName::Chat))?;
+            Ok(())
+        }
+        Err(_) => show_error(
+            app_context,
+            "Go to date",
+            "There is no message in the chat before this date.",
+        ),
+    }
+}
+
+/// Open the comments of a post of the open channel, in the discussion group
+/// of the channel. The comments are read from the post, and the prompt
+/// replies to it, so that the message sent is a comment.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the post.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn open_comments(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_id: i64,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    let thread = match tg_backend.get_message_thread(chat_id, message_id).await {
+        Ok(thread) => thread,
+        Err(e) => return show_error(app_context, "Comments", e.message),
+    };
+    let text = thread
+        .messages
+        .iter()
+        .find(|message| message.id == thread.message_thread_id)
+        .map(|message| MessageEntry::from(message).message_content_to_string())
+        .unwrap_or_default();
+    open_chat_at(app_context, thread.chat_id, thread.message_thread_id)?;
+    app_context.action_tx().send(Action::ShowChatWindowReply)?;
+    app_context
+        .action_tx()
+        .send(Action::ReplyMessage(thread.message_thread_id, text, None))?;
+    Ok(())
+}
+
+/// Show the reply bar of the draft loaded in the prompt, with the text of the
+/// replied message. Nothing is shown if the message is not accessible or if
+/// another chat was opened meanwhile.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A mutable reference to the TgBackend struct.
+/// * `chat_id` - The id of the chat of the draft.
+/// * `message_id` - The id of the replied message.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn load_draft_reply(
+    app_context: &AppContext,
+    tg_backend: &mut TgBackend,
+    chat_id: i64,
+    message_id: i64,
+) -> Result<(), AppError<Action>> {
+    let Some(message) = tg_backend.get_message(chat_id, message_id).await else {
+        return Ok(());
+    };
+    if app_context.tg_context().open_chat_id() != chat_id {
+        return Ok(());
+    }
+    let text = MessageEntry::from(&message).message_content_to_string();
+    app_context.action_tx().send(Action::ShowChatWindowReply)?;
+    app_context
+        .action_tx()
+        .send(Action::ReplyMessage(message_id, text, None))?;
+    Ok(())
+}
+
+/// Open the favorite chat that follows the open chat, in the order of the
+/// chat list. If the open chat is not a favorite, the first favorite chat is
+/// opened.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn open_next_favorite_chat(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let favorites = {
+        let local_state = app_context.local_state();
+        tg_context
+            .chats_index()
+            .iter()
+            .map(|chat| chat.chat_id)
+            .filter(|chat_id| local_state.is_favorite_chat(*chat_id))
+            .collect::<Vec<i64>>()
+    };
+    if favorites.is_empty() {
+        return show_error(
+            app_context,
+            "Favorites",
+            "There are no favorite chats, press `f` in the chat list to add one.",
+        );
+    }
+    let open_chat_id = tg_context.open_chat_id();
+    let next = match favorites
+        .iter()
+        .position(|chat_id| *chat_id == open_chat_id)
+    {
+        Some(i) => favorites[(i + 1) % favorites.len()],
+        None => favorites[0],
+    };
+    if next != open_chat_id {
+        app_context.action_tx().send(Action::OpenChat(next))?;
+    }
+    Ok(())
+}
+
+/// Show the recently closed chats in a popup, the most recent first, so
+/// that one of them can be reopened.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_recent_chats(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    let recent_chats = app_context
+        .local_state()
+        .recent_chats(RECENT_CHATS_POPUP_SIZE);
+    let items = recent_chats
+        .into_iter()
+        .map(|chat_id| {
+            let name = app_context
+                .tg_context()
+                .name_from_chats(chat_id)
+                .unwrap_or_else(|| chat_id.to_string());
+            PopupItem::new(name, Action::OpenChat(chat_id))
+        })
+        .collect();
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "Recent chats".to_string(),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Show the identities the messages of the open chat can be sent as, like
+/// the channels of the user or the group itself for its anonymous admins.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_message_senders(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    if chat_id == 0 {
+        return Ok(());
+    }
+    let senders = match tg_backend.get_chat_available_message_senders(chat_id).await {
+        Ok(senders) => senders,
+        Err(e) => return show_error(app_context, "Send as", e.message),
+    };
+    if senders.len() < 2 {
+        return show_error(
+            app_context,
+            "Send as",
+            "The messages of this chat can only be sent as yourself.",
+        );
+    }
+    let tg_context = app_context.tg_context();
+    let current = tg_context
+        .chats()
+        .get(&chat_id)
+        .and_then(|chat| chat.message_sender_id.clone());
+    let items = senders
+        .iter()
+        .map(|sender| {
+            let name = tg_context.message_sender_name(chat_id, &sender.sender);
+            let mark = if current.as_ref() == Some(&sender.sender) {
+                "✔ "
+            } else {
+                ""
+            };
+            let premium = if sender.needs_premium {
+                " (Telegram Premium)"
+            } else {
+                ""
+            };
+            let td_sender = match &sender.sender {
+                MessageSender::User(user) => TdMessageSender::User(user.user_id),
+                MessageSender::Chat(chat) => TdMessageSender::Chat(chat.chat_id),
+            };
+            PopupItem::new(
+                format!("{}{}{}", mark, name, premium),
+                Action::SetMessageSender(chat_id, td_sender),
+            )
+        })
+        .collect();
+    app_context
+        .action_tx()
+        .send(Action::ShowPopup(Popup::List("Send as".to_string(), items)))?;
+    Ok(())
+}
+
+/// Set the identity the messages of a chat are sent as.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `chat_id` - The id of the chat.
+/// * `sender` - The identity.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn set_message_sender(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    chat_id: i64,
+    sender: TdMessageSender,
+) -> Result<(), AppError<Action>> {
+    let sender = match sender {
+        TdMessageSender::User(user_id) => MessageSender::User(MessageSenderUser { user_id }),
+        TdMessageSender::Chat(chat_id) => MessageSender::Chat(MessageSenderChat { chat_id }),
+    };
+    if let Err(e) = tg_backend.set_chat_message_sender(chat_id, sender).await {
+        return show_error(app_context, "Send as", e.message);
+    }
+    Ok(())
+}
+
+/// Show the info of the open chat: its type and the auto-delete timer, the
+/// birthday and the personal note of the contact of a private chat and,
+/// for secret chats, the state of the encryption and the fingerprint of the
+/// key, that can be compared with the one shown on the device of the other
+/// user to verify the encryption.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_chat_info(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let chat_id = tg_context.open_chat_id();
+    let Some(chat) = tg_context.chats().get(&chat_id).cloned() else {
+        return show_error(app_context, "Chat info", "Open a chat to show its info.");
+    };
+    let mut lines = vec![format!("Title: {}", chat.title)];
+    let contact_id = match &chat.r#type {
+        ChatType::Private(private) => Some(private.user_id),
+        ChatType::Secret(secret) => Some(secret.user_id),
+        _ => None,
+    };
+    match chat.r#type {
+        ChatType::Private(_) => lines.push("Type: private chat".to_string()),
+        ChatType::BasicGroup(_) => lines.push("Type: group".to_string()),
+        ChatType::Supergroup(supergroup) if supergroup.is_channel => {
+            lines.push("Type: channel".to_string())
+        }
+        ChatType::Supergroup(_) => lines.push("Type: supergroup".to_string()),
+        ChatType::Secret(secret) => {
+            lines.push("Type: secret chat".to_string());
+            let secret_chat = tg_context
+                .secret_chats()
+                .get(&secret.secret_chat_id)
+                .cloned();
+            let state = match secret_chat.as_ref().map(|secret_chat| &secret_chat.state) {
+                Some(SecretChatState::Ready) => "ready",
+                Some(SecretChatState::Pending) => "waiting for the other user",
+                Some(SecretChatState::Closed) => "closed",
+                None => "unknown",
+            };
+            lines.push(format!("Encryption: {}", state));
+            let key_hash = secret_chat
+                .and_then(|secret_chat| decode_base64(&secret_chat.key_hash))
+                .unwrap_or_default();
+            if !key_hash.is_empty() {
+                lines.push(String::new());
+                lines.push("Encryption key:".to_string());
+                lines.push(render_key_visualization(&key_hash));
+                lines.push(format_key_fingerprint(&key_hash));
+                lines.push(String::new());
+                lines.push(
+                    "If this image and these numbers are the same on the device of the other \
+                     user, the chat is end-to-end encrypted."
+                        .to_string(),
+                );
+                lines.push(String::new());
+            }
+        }
+    }
+    let auto_delete = match chat.message_auto_delete_time {
+        0 => "off".to_string(),
+        seconds => human_duration(seconds as u64),
+    };
+    lines.push(format!("Auto-delete timer: {}", auto_delete));
+    if let Some(user_id) = contact_id {
+        let full_info = tg_context.users_full_info().get(&user_id).cloned();
+        let full_info = match full_info {
+            Some(full_info) => Some(full_info),
+            None => tg_backend.get_user_full_info(user_id).await,
+        };
+        if let Some(birthdate) = full_info.and_then(|full_info| full_info.birthdate) {
+            lines.push(format!(
+                "Birthday: {}",
+                DateTimeEntry::convert_birthdate(&birthdate)
+            ));
+        }
+        let note = app_context
+            .local_state()
+            .contact_note(user_id)
+            .map(str::to_string);
+        lines.push(match note {
+            Some(note) => format!("Note: {}", note),
+            None => "Note: none, add one with /contactnote".to_string(),
+        });
+    }
+    app_context.action_tx().send(Action::ShowPopup(Popup::Info(
+        "Chat info".to_string(),
+        lines.join("\n"),
+    )))?;
+    Ok(())
+}
+
+/// Apply an operation to several chats at once.
+/// The chats that could not be updated are listed in a popup.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `chat_ids` - The chats.
+/// * `operation` - The operation to apply.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn batch_chats(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    chat_ids: &[i64],
+    operation: ChatBatchOperation,
+) -> Result<(), AppError<Action>> {
+    let mut failures = vec![];
+    for chat_id in chat_ids {
+        let result = match operation {
+            ChatBatchOperation::MarkAsRead => tg_backend.mark_chat_as_read(*chat_id).await,
+            ChatBatchOperation::Mute => tg_backend.set_chat_muted(*chat_id, true).await,
+            ChatBatchOperation::Unmute => tg_backend.set_chat_muted(*chat_id, false).await,
+            ChatBatchOperation::Archive => {
+                tg_backend
+                    .add_chat_to_list(*chat_id, ChatList::Archive)
+                    .await
+            }
+            ChatBatchOperation::AddToFolder(chat_folder_id) => {
+                tg_backend
+                    .add_chat_to_list(*chat_id, TdChatList::Folder(chat_folder_id).into())
+                    .await
+            }
+        };
+        if let Err(e) = result {
+            tracing::error!(
+                "Failed to apply {:?} to chat {}: {:?}",
+                operation,
+                chat_id,
+                e
+            );
+            let name = app_context
+                .tg_context()
+                .name_from_chats(*chat_id)
+                .unwrap_or_else(|| chat_id.to_string());
+            failures.push(format!("{}: {}", name, e.message));
+        }
+    }
+    if failures.is_empty() {
+        return Ok(());
+    }
+    show_error(
+        app_context,
+        "Batch actions",
+        format!(
+            "{} of {} chats could not be updated:\n{}",
+            failures.len(),
+            chat_ids.len(),
+            failures.join("\n")
+        ),
+    )
+}
+
+/// Show the ways to clear the history of a chat or to leave it, depending on
+/// the type of the chat. Each of them must be confirmed, since the deleted
+/// messages can not be restored.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `chat_id` - The id of the chat.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_chat_deletion(app_context: &AppContext, chat_id: i64) -> Result<(), AppError<Action>> {
+    let Some(chat) = app_context.tg_context().chats().get(&chat_id).cloned() else {
+        return Ok(());
+    };
+    let name = &chat.title;
+    let confirm = |title: String, label: String, deletion: ChatDeletion| {
+        Action::ShowPopup(Popup::List(
+            title,
+            vec![
+                PopupItem::new(label, Action::DeleteChat(chat_id, deletion)),
+                PopupItem::new("Cancel", Action::HidePopup),
+            ],
+        ))
+    };
+    let mut items = vec![];
+    if chat.can_be_deleted_only_for_self || chat.can_be_deleted_for_all_users {
+        items.push(PopupItem::new(
+            "Clear the history for me",
+            confirm(
+                format!("Clear the history of {}?", name),
+                "Delete all the messages for me, they can not be restored".to_string(),
+                ChatDeletion::ClearHistory,
+            ),
+        ));
+    }
+    if chat.can_be_deleted_for_all_users {
+        items.push(PopupItem::new(
+            "Clear the history for everyone",
+            confirm(
+                format!("Clear the history of {} for everyone?", name),
+                format!(
+                    "Delete all the messages for me and for {} too, they can not be restored",
+                    name
+                ),
+                ChatDeletion::ClearHistoryForEveryone,
+            ),
+        ));
+    }
+    if matches!(
+        chat.r#type,
+        ChatType::BasicGroup(_) | ChatType::Supergroup(_)
+    ) {
+        items.push(PopupItem::new(
+            "Leave and delete the chat",
+            confirm(
+                format!("Leave {}?", name),
+                "Leave and remove it from the list, joining again may need an invite".to_string(),
+                ChatDeletion::LeaveAndDelete,
+            ),
+        ));
+    }
+    let popup = if items.is_empty() {
+        Popup::Info(
+            "Delete chat".to_string(),
+            format!("The history of {} can not be cleared.", name),
+        )
+    } else {
+        Popup::List(format!("Delete {}", name), items)
+    };
+    app_context.action_tx().send(Action::ShowPopup(popup))?;
+    Ok(())
+}
+
+/// Clear the history of a chat or leave it, once confirmed.
+/// A basic group is also removed from the chat list, while the other groups
+/// are removed by Telegram when they are left.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `chat_id` - The id of the chat.
+/// * `deletion` - The way the chat is deleted.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn delete_chat(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    chat_id: i64,
+    deletion: ChatDeletion,
+) -> Result<(), AppError<Action>> {
+    let result = match deletion {
+        ChatDeletion::ClearHistory => tg_backend.delete_chat_history(chat_id, false, false).await,
+        ChatDeletion::ClearHistoryForEveryone => {
+            tg_backend.delete_chat_history(chat_id, false, true).await
+        }
+        ChatDeletion::LeaveAndDelete => {
+            let is_basic_group = app_context
+                .tg_context()
+                .chats()
+                .get(&chat_id)
+                .is_some_and(|chat| matches!(chat.r#type, ChatType::BasicGroup(_)));
+            match tg_backend.leave_chat(chat_id).await {
+                Ok(()) if is_basic_group => {
+                    tg_backend.delete_chat_history(chat_id, true, false).await
+                }
+                result => result,
+            }
+        }
+    };
+    if let Err(e) = result {
+        return show_error(app_context, "Delete chat", e.message);
+    }
+    let tg_context = app_context.tg_context();
+    if tg_context.open_chat_id() == chat_id {
+        tg_context.clear_open_chat_messages();
+    }
+    Ok(())
+}
+
+/// Pin a chat at the top of a chat list or unpin it. The chat list is
+/// reordered when TDLib updates the position of the chat.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `chat_id` - The id of the chat.
+/// * `chat_list` - The chat list the chat is pinned in.
+/// * `is_pinned` - Whether the chat is pinned or unpinned.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn set_chat_pinned(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    chat_id: i64,
+    chat_list: TdChatList,
+    is_pinned: bool,
+) -> Result<(), AppError<Action>> {
+    if let Err(e) = tg_backend
+        .toggle_chat_is_pinned(chat_list.into(), chat_id, is_pinned)
+        .await
+    {
+        let title = if is_pinned { "Pin chat" } else { "Unpin chat" };
+        return show_error(app_context, title, e.message);
+    }
+    Ok(())
+}
+
+/// Mute the notifications of a chat for a time, or unmute them. The chat
+/// list shows the chat as muted when TDLib updates its settings.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `chat_id` - The id of the chat.
+/// * `mute_for` - The time to mute the chat for, in seconds, or 0 to unmute
+///   it.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn set_chat_mute_for(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    chat_id: i64,
+    mute_for: i32,
+) -> Result<(), AppError<Action>> {
+    if let Err(e) = tg_backend.set_chat_mute_for(chat_id, mute_for).await {
+        let title = if mute_for > 0 {
+            "Mute chat"
+        } else {
+            "Unmute chat"
+        };
+        return show_error(app_context, title, e.message);
+    }
+    Ok(())
+}
+
+/// Show the kinds of chats the user can create, the first step of the
+/// creation of a chat.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_new_chat(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    let ask_title = |title: &str, kind| {
+        Action::ShowPopup(Popup::Input(
+            title.to_string(),
+            Box::new(Action::SetNewChatTitle(kind, String::new())),
+        ))
+    };
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "New chat".to_string(),
+        vec![
+            PopupItem::new(
+                "New group",
+                ask_title("Title of the new group", NewChatKind::Group),
+            ),
+            PopupItem::new(
+                "New channel",
+                ask_title("Title of the new channel", NewChatKind::Channel),
+            ),
+        ],
+    )))?;
+    Ok(())
+}
+
+/// Continue the creation of a chat once its title is typed: the members of
+/// a group are picked from the contacts, and the description of a channel
+/// is typed.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `kind` - The kind of the chat.
+/// * `title` - The title of the chat.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn set_new_chat_title(
+    app_context: &AppContext,
+    kind: NewChatKind,
+    title: &str,
+) -> Result<(), AppError<Action>> {
+    let title = title.trim();
+    if title.is_empty() {
+        return show_error(app_context, "New chat", "The title can not be empty.");
+    }
+    let new_chat = NewChat {
+        kind,
+        title: title.to_string(),
+        member_ids: vec![],
+        description: String::new(),
+    };
+    let action = match kind {
+        NewChatKind::Group => Action::ShowNewChatMembers(new_chat),
+        NewChatKind::Channel => Action::ShowPopup(Popup::Input(
+            "Description of the channel (can be empty)".to_string(),
+            Box::new(Action::CreateNewChat(new_chat)),
+        )),
+    };
+    app_context.action_tx().send(action)?;
+    Ok(())
+}
+
+/// Show the contacts to pick the members of a group being created. Picking
+/// a contact adds it to the members or removes it from them, and the first
+/// item creates the group.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `new_chat` - The group being created.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_new_chat_members(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    new_chat: &NewChat,
+) -> Result<(), AppError<Action>> {
+    // The contacts are loaded once, when the picker is first shown.
+    if new_chat.member_ids.is_empty() {
+        load_contacts(app_context, tg_backend).await?;
+    }
+    let mut items = vec![PopupItem::new(
+        format!(
+            "Create {} with {} members",
+            new_chat.title,
+            new_chat.member_ids.len()
+        ),
+        Action::CreateNewChat(new_chat.clone()),
+    )];
+    let tg_context = app_context.tg_context();
+    let users = tg_context.users();
+    items.extend(tg_context.contacts().iter().map(|user_id| {
+        let mut toggled = new_chat.clone();
+        let mark = if new_chat.member_ids.contains(user_id) {
+            toggled.member_ids.retain(|id| id != user_id);
+            "[x]"
+        } else {
+            toggled.member_ids.push(*user_id);
+            "[ ]"
+        };
+        let name = users.get(user_id).map_or_else(
+            || user_id.to_string(),
+            |user| format!("{} {}", user.first_name, user.last_name),
+        );
+        PopupItem::new(
+            format!("{} {}", mark, name.trim()),
+            Action::ShowNewChatMembers(toggled),
+        )
+    }));
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        format!("Members of {}", new_chat.title),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Create a group or a channel and open it.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `new_chat` - The chat to create.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn create_new_chat(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    new_chat: &NewChat,
+) -> Result<(), AppError<Action>> {
+    let result = match new_chat.kind {
+        NewChatKind::Group => {
+            tg_backend
+                .create_new_basic_group_chat(new_chat.member_ids.clone(), new_chat.title.clone())
+                .await
+        }
+        NewChatKind::Channel => {
+            tg_backend
+                .create_new_supergroup_chat(
+                    new_chat.title.clone(),
+                    true,
+                    new_chat.description.trim().to_string(),
+                )
+                .await
+        }
+    };
+    match result {
+        Ok(chat_id) => open_chat(app_context, chat_id),
+        Err(e) => show_error(app_context, "New chat", e.message),
+    }
+}
+
+/// Request the open chat and its recent messages again from Telegram, for
+/// when they look stale. The messages shown are replaced by the ones loaded.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn refresh_chat(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let chat_id = tg_context.open_chat_id();
+    if chat_id == 0 {
+        return show_error(app_context, "Refresh chat", "Open a chat to refresh it.");
+    }
+    match tg_backend.get_chat(chat_id).await {
+        Ok(chat) => {
+            tg_context.chats().insert(chat_id, chat);
+        }
+        Err(e) => return show_error(app_context, "Refresh chat", e.message),
+    }
+    tg_context.clear_open_chat_messages();
+    tg_context.set_from_message_id(0);
+    tg_context.set_jump_message_id(0);
+    tg_backend.get_chat_history(chat_id);
+    app_context.action_tx().send(Action::LoadPinnedMessage)?;
+    Ok(())
+}
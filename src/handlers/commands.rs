@@ -0,0 +1,94 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    commands::{CommandExpansion, ARGS_PLACEHOLDER},
+    handlers::show_error,
+    utils::{fuzzy_match, shell_command, shell_quote},
+};
+
+/// Run a slash command typed in the prompt.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `name` - The name of the command.
+/// * `args` - The text typed after the name of the command.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn run_command(
+    app_context: &AppContext,
+    name: &str,
+    args: &str,
+) -> Result<(), AppError<Action>> {
+    let command = app_context.command_registry().get(name).cloned();
+    let Some(command) = command else {
+        return show_error(
+            app_context,
+            "Command",
+            format!("Unknown command /{}.", name),
+        );
+    };
+    match command.expansion {
+        CommandExpansion::Actions(actions) => {
+            for action in actions {
+                app_context.action_tx().send(action)?;
+            }
+        }
+        CommandExpansion::Snippet(text) => {
+            app_context
+                .action_tx()
+                .send(Action::InsertText(text.replace(ARGS_PLACEHOLDER, args)))?;
+        }
+        CommandExpansion::Exec(command_line) => {
+            let command_line = command_line.replace(ARGS_PLACEHOLDER, &shell_quote(args));
+            tracing::info!("Running command /{}: {}", name, command_line);
+            match shell_command(&command_line).output().await {
+                Ok(output) if output.status.success() => {
+                    let text = String::from_utf8_lossy(&output.stdout)
+                        .trim_end()
+                        .to_string();
+                    app_context.action_tx().send(Action::InsertText(text))?;
+                }
+                Ok(output) => {
+                    return show_error(
+                        app_context,
+                        format!("/{}", name),
+                        String::from_utf8_lossy(&output.stderr),
+                    )
+                }
+                Err(e) => return show_error(app_context, format!("/{}", name), e.to_string()),
+            }
+        }
+        CommandExpansion::OpenChat(chat) => {
+            let chat = chat.replace(ARGS_PLACEHOLDER, args);
+            let chat_id = {
+                let tg_context = app_context.tg_context();
+                let chats = tg_context.chats();
+                let chat_id = chats
+                    .values()
+                    .find(|c| c.title.eq_ignore_ascii_case(&chat))
+                    .or_else(|| {
+                        chats
+                            .values()
+                            .filter_map(|c| fuzzy_match(&chat, &c.title).map(|score| (c, score)))
+                            .max_by_key(|(_, score)| *score)
+                            .map(|(c, _)| c)
+                    })
+                    .map(|c| c.id);
+                chat_id
+            };
+            match chat_id {
+                Some(chat_id) => app_context.action_tx().send(Action::OpenChat(chat_id))?,
+                None => {
+                    return show_error(
+                        app_context,
+                        format!("/{}", name),
+                        format!("No chat matches {}.", chat),
+                    )
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::Popup,
+    handlers::{chats::open_chat, show_error},
+    tg::tg_backend::TgBackend,
+};
+use tdlib_rs::enums::ChatType;
+
+/// Edit the personal note of the user of the open private or secret chat,
+/// starting from the current note.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn edit_contact_note(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let user_id = tg_context
+        .chats()
+        .get(&tg_context.open_chat_id())
+        .and_then(|chat| match &chat.r#type {
+            ChatType::Private(private) => Some(private.user_id),
+            ChatType::Secret(secret) => Some(secret.user_id),
+            _ => None,
+        });
+    let Some(user_id) = user_id else {
+        return show_error(
+            app_context,
+            "Contact note",
+            "Open a private chat to edit the note of its contact.",
+        );
+    };
+    let note = app_context
+        .local_state()
+        .contact_note(user_id)
+        .unwrap_or_default()
+        .to_string();
+    app_context
+        .action_tx()
+        .send(Action::ShowPopup(Popup::Input(
+            "Personal note of the contact (empty to remove it)".to_string(),
+            Box::new(Action::SetContactNote(user_id, note)),
+        )))?;
+    Ok(())
+}
+
+/// Load the contacts of the user, sorted by name, to show them in place of
+/// the chat.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn load_contacts(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+) -> Result<(), AppError<Action>> {
+    let mut user_ids = match tg_backend.get_contacts().await {
+        Ok(user_ids) => user_ids,
+        Err(e) => return show_error(app_context, "Contacts", e.message),
+    };
+    let tg_context = app_context.tg_context();
+    {
+        let users = tg_context.users();
+        user_ids.sort_by_cached_key(|user_id| {
+            users
+                .get(user_id)
+                .map(|user| format!("{} {}", user.first_name, user.last_name).to_lowercase())
+                .unwrap_or_default()
+        });
+    }
+    *tg_context.contacts() = user_ids;
+    Ok(())
+}
+
+/// Add a contact by phone number, with the name it is saved with.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `text` - The phone number and the name, like `+441234567890 Ada
+///   Lovelace`.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn add_contact(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    text: &str,
+) -> Result<(), AppError<Action>> {
+    let Some((phone_number, first_name, last_name)) = parse_new_contact(text) else {
+        return show_error(
+            app_context,
+            "Add contact",
+            "Type the phone number and the name, like +441234567890 Ada Lovelace.",
+        );
+    };
+    match tg_backend
+        .import_contact(phone_number.clone(), first_name, last_name)
+        .await
+    {
+        Ok(Some(_)) => load_contacts(app_context, tg_backend).await,
+        Ok(None) => show_error(
+            app_context,
+            "Add contact",
+            format!(
+                "Nobody uses Telegram with the phone number {}.",
+                phone_number
+            ),
+        ),
+        Err(e) => show_error(app_context, "Add contact", e.message),
+    }
+}
+
+/// Delete a contact of the user.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `user_id` - The id of the user of the contact.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn delete_contact(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    user_id: i64,
+) -> Result<(), AppError<Action>> {
+    if let Err(e) = tg_backend.remove_contacts(vec![user_id]).await {
+        return show_error(app_context, "Delete contact", e.message);
+    }
+    app_context
+        .tg_context()
+        .contacts()
+        .retain(|id| *id != user_id);
+    Ok(())
+}
+
+/// Open the private chat with a contact, which is created if there is none.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `user_id` - The id of the user of the contact.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn open_contact_chat(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    user_id: i64,
+) -> Result<(), AppError<Action>> {
+    match tg_backend.create_private_chat(user_id).await {
+        Ok(chat_id) => open_chat(app_context, chat_id),
+        Err(e) => show_error(app_context, "Open chat", e.message),
+    }
+}
+
+/// Parse the phone number and the name of a new contact, typed like
+/// `+441234567890 Ada Lovelace`. The first word of the name is the first
+/// name and the rest is the last name.
+///
+/// # Arguments
+/// * `text` - The typed text.
+///
+/// # Returns
+/// * `Option<(String, String, String)>` - The phone number, the first name
+///   and the last name, `None` if the phone number or the name is missing.
+fn parse_new_contact(text: &str) -> Option<(String, String, String)> {
+    let (phone_number, name) = text.trim().split_once(char::is_whitespace)?;
+    if !phone_number.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let name = name.trim();
+    let (first_name, last_name) = name.split_once(' ').unwrap_or((name, ""));
+    Some((
+        phone_number.to_string(),
+        first_name.to_string(),
+        last_name.trim().to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_new_contact() {
+        assert_eq!(
+            parse_new_contact(" +441234567890  Ada King Lovelace "),
+            Some((
+                "+441234567890".to_string(),
+                "Ada".to_string(),
+                "King Lovelace".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_new_contact("+441234567890 Ada"),
+            Some((
+                "+441234567890".to_string(),
+                "Ada".to_string(),
+                String::new()
+            ))
+        );
+        assert_eq!(parse_new_contact("+441234567890"), None);
+        assert_eq!(parse_new_contact("Ada Lovelace"), None);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    action::{Action, ChatFolderEdit, ChatFolderIncludeType},
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::{Popup, PopupItem},
+    handlers::{show_chat_picker, show_error},
+    tg::tg_backend::TgBackend,
+};
+use tdlib_rs::types::ChatFolder;
+
+/// Show the chat folders of the user. A folder can be selected to be edited
+/// and a new folder can be created.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_chat_folders(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    let mut items: Vec<PopupItem> = app_context
+        .tg_context()
+        .chat_folders()
+        .iter()
+        .map(|folder| PopupItem::new(&folder.title, Action::ShowChatFolder(folder.id)))
+        .collect();
+    items.push(PopupItem::new(
+        "New folder",
+        Action::ShowPopup(Popup::Input(
+            "Name of the new folder".to_string(),
+            Box::new(Action::CreateChatFolder(String::new(), None)),
+        )),
+    ));
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "Chat folders".to_string(),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Show the editor of a chat folder: its name, the types of chats it
+/// includes and the chats included in it or excluded from it.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `chat_folder_id` - The identifier of the folder.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_chat_folder(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    chat_folder_id: i32,
+) -> Result<(), AppError<Action>> {
+    let mut folder = match tg_backend.get_chat_folder(chat_folder_id).await {
+        Ok(folder) => folder,
+        Err(e) => return show_error(app_context, "Chat folder", e.message),
+    };
+    let edit = |edit| Action::EditChatFolder(chat_folder_id, edit);
+    let mut items = vec![PopupItem::new(
+        format!("Name: {}", folder.title),
+        Action::ShowPopup(Popup::Input(
+            "New name of the folder".to_string(),
+            Box::new(edit(ChatFolderEdit::Rename(String::new()))),
+        )),
+    )];
+    items.extend(ChatFolderIncludeType::ALL.into_iter().map(|include_type| {
+        let included = if *include_flag(&mut folder, include_type) {
+            "included"
+        } else {
+            "not included"
+        };
+        PopupItem::new(
+            format!("All {}: {}", include_type.label().to_lowercase(), included),
+            edit(ChatFolderEdit::ToggleIncludeType(include_type)),
+        )
+    }));
+    items.push(PopupItem::new(
+        "Include a chat",
+        Action::ShowChatFolderPicker(chat_folder_id, true),
+    ));
+    items.push(PopupItem::new(
+        "Exclude a chat",
+        Action::ShowChatFolderPicker(chat_folder_id, false),
+    ));
+    let tg_context = app_context.tg_context();
+    let name = |chat_id: &i64| {
+        tg_context
+            .name_from_chats(*chat_id)
+            .unwrap_or_else(|| chat_id.to_string())
+    };
+    for (label, chat_ids) in [
+        ("Included", &folder.included_chat_ids),
+        ("Excluded", &folder.excluded_chat_ids),
+    ] {
+        items.extend(chat_ids.iter().map(|chat_id| {
+            PopupItem::new(
+                format!("{}: {} (select to remove)", label, name(chat_id)),
+                edit(ChatFolderEdit::RemoveChat(*chat_id)),
+            )
+        }));
+    }
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        format!("Chat folder {}", folder.title),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Get the flag of a chat folder that includes a type of chats.
+///
+/// # Arguments
+/// * `folder` - The chat folder.
+/// * `include_type` - The type of chats.
+///
+/// # Returns
+/// * `&mut bool` - The flag.
+fn include_flag(folder: &mut ChatFolder, include_type: ChatFolderIncludeType) -> &mut bool {
+    match include_type {
+        ChatFolderIncludeType::Contacts => &mut folder.include_contacts,
+        ChatFolderIncludeType::NonContacts => &mut folder.include_non_contacts,
+        ChatFolderIncludeType::Groups => &mut folder.include_groups,
+        ChatFolderIncludeType::Channels => &mut folder.include_channels,
+        ChatFolderIncludeType::Bots => &mut folder.include_bots,
+    }
+}
+
+/// Create a chat folder. A folder must include at least a chat, so the first
+/// chat is picked after the name is typed.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `name` - The name of the folder.
+/// * `chat_id` - The first chat included in the folder, if already picked.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn create_chat_folder(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    name: &str,
+    chat_id: Option<i64>,
+) -> Result<(), AppError<Action>> {
+    let name = name.trim();
+    if name.is_empty() {
+        return show_error(app_context, "Chat folder", "The name can not be empty.");
+    }
+    let Some(chat_id) = chat_id else {
+        return show_chat_picker(app_context, "First chat of the folder", |chat_id| {
+            Action::CreateChatFolder(name.to_string(), Some(chat_id))
+        });
+    };
+    let folder = ChatFolder {
+        title: name.to_string(),
+        icon: None,
+        color_id: -1,
+        is_shareable: false,
+        pinned_chat_ids: vec![],
+        included_chat_ids: vec![chat_id],
+        excluded_chat_ids: vec![],
+        exclude_muted: false,
+        exclude_read: false,
+        exclude_archived: false,
+        include_contacts: false,
+        include_non_contacts: false,
+        include_bots: false,
+        include_groups: false,
+        include_channels: false,
+    };
+    match tg_backend.create_chat_folder(folder).await {
+        Ok(chat_folder_id) => {
+            app_context
+                .action_tx()
+                .send(Action::ShowChatFolder(chat_folder_id))?;
+            Ok(())
+        }
+        Err(e) => show_error(app_context, "Chat folder", e.message),
+    }
+}
+
+/// Change a chat folder and show its editor again.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `chat_folder_id` - The identifier of the folder.
+/// * `edit` - The change.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn edit_chat_folder(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    chat_folder_id: i32,
+    edit: &ChatFolderEdit,
+) -> Result<(), AppError<Action>> {
+    let mut folder = match tg_backend.get_chat_folder(chat_folder_id).await {
+        Ok(folder) => folder,
+        Err(e) => return show_error(app_context, "Chat folder", e.message),
+    };
+    match edit {
+        ChatFolderEdit::Rename(name) if name.trim().is_empty() => {
+            return show_error(app_context, "Chat folder", "The name can not be empty.");
+        }
+        ChatFolderEdit::Rename(name) => folder.title = name.trim().to_string(),
+        ChatFolderEdit::ToggleIncludeType(include_type) => {
+            let flag = include_flag(&mut folder, *include_type);
+            *flag = !*flag;
+        }
+        ChatFolderEdit::IncludeChat(chat_id) => {
+            folder.excluded_chat_ids.retain(|id| id != chat_id);
+            if !folder.included_chat_ids.contains(chat_id) {
+                folder.included_chat_ids.push(*chat_id);
+            }
+        }
+        ChatFolderEdit::ExcludeChat(chat_id) => {
+            folder.included_chat_ids.retain(|id| id != chat_id);
+            folder.pinned_chat_ids.retain(|id| id != chat_id);
+            if !folder.excluded_chat_ids.contains(chat_id) {
+                folder.excluded_chat_ids.push(*chat_id);
+            }
+        }
+        ChatFolderEdit::RemoveChat(chat_id) => {
+            folder.included_chat_ids.retain(|id| id != chat_id);
+            folder.excluded_chat_ids.retain(|id| id != chat_id);
+            folder.pinned_chat_ids.retain(|id| id != chat_id);
+        }
+    }
+    if let Err(e) = tg_backend.edit_chat_folder(chat_folder_id, folder).await {
+        return show_error(app_context, "Chat folder", e.message);
+    }
+    app_context
+        .action_tx()
+        .send(Action::ShowChatFolder(chat_folder_id))?;
+    Ok(())
+}
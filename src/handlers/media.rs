@@ -0,0 +1,263 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::{Popup, PopupItem},
+    handlers::show_error,
+    image_cache::{ImageState, MAX_IMAGE_SIZE},
+    tg::{attachments::SelfDestruct, tg_backend::TgBackend},
+    url_preview::{self, UrlPreviewState},
+    utils::{expand_shell_placeholders, shell_command},
+};
+use std::sync::Arc;
+use tdlib_rs::enums::{MessageContent, MessageSelfDestructType};
+
+/// Get the file of the photo or the video of a message, and the name of its
+/// kind.
+///
+/// # Arguments
+/// * `content` - The content of the message.
+///
+/// # Returns
+/// * `Option<(i32, &str)>` - The id of the file, the largest size of a photo,
+///   and "photo" or "video", or `None` if the message has no such media.
+fn media_file(content: &MessageContent) -> Option<(i32, &'static str)> {
+    match content {
+        MessageContent::MessagePhoto(content) => content
+            .photo
+            .sizes
+            .last()
+            .map(|size| (size.photo.id, "photo")),
+        MessageContent::MessageVideo(content) => Some((content.video.video.id, "video")),
+        _ => None,
+    }
+}
+
+/// Open the photo or the video of a message of the open chat. A view-once or
+/// self-destructing media is deleted once viewed, so it is opened only after
+/// a confirmation.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the message.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_open_media(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_id: i64,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    let Some(message) = tg_backend.get_message(chat_id, message_id).await else {
+        return Ok(());
+    };
+    let kind = match &message.content {
+        MessageContent::MessageExpiredPhoto | MessageContent::MessageExpiredVideo => {
+            return show_error(app_context, "Open media", "The media has expired.");
+        }
+        content => match media_file(content) {
+            Some((_, kind)) => kind,
+            None => {
+                return show_error(
+                    app_context,
+                    "Open media",
+                    "Only the photos and the videos can be opened.",
+                )
+            }
+        },
+    };
+    if app_context.app_config().media_viewer_command.is_empty() {
+        return show_error(
+            app_context,
+            "Open media",
+            "Set `media_viewer_command` in app.toml to open the photos and the videos.",
+        );
+    }
+    let confirmation = match message.self_destruct_type {
+        Some(MessageSelfDestructType::Immediately) => "it is deleted once viewed".to_string(),
+        Some(MessageSelfDestructType::Timer(timer)) => format!(
+            "it is deleted {} after opening",
+            SelfDestruct::Timer(timer.self_destruct_time).label()
+        ),
+        None => {
+            app_context
+                .action_tx()
+                .send(Action::OpenMedia(message_id))?;
+            return Ok(());
+        }
+    };
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        format!("Open the self-destructing {}?", kind),
+        vec![
+            PopupItem::new(
+                format!("Open it, {}", confirmation),
+                Action::OpenMedia(message_id),
+            ),
+            PopupItem::new("Cancel", Action::HidePopup),
+        ],
+    )))?;
+    Ok(())
+}
+
+/// Download the photo or the video of a message of the open chat in the
+/// background and open it with the media viewer command.
+/// TDLib is informed that the content is opened only once it is downloaded,
+/// since it starts the timer of a self-destructing media.
+///
+/// # Arguments
+/// * `app_context` - An Arc wrapped AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the message.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn open_media(
+    app_context: Arc<AppContext>,
+    tg_backend: &TgBackend,
+    message_id: i64,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    let Some(file_id) = tg_backend
+        .get_message(chat_id, message_id)
+        .await
+        .and_then(|message| media_file(&message.content))
+        .map(|(file_id, _)| file_id)
+    else {
+        return show_error(&app_context, "Open media", "The media is not available.");
+    };
+    let command_line = app_context.app_config().media_viewer_command.clone();
+    let download = tg_backend.download_file(file_id);
+    let open = tg_backend.open_message_content(chat_id, message_id);
+    tokio::spawn(async move {
+        let Some(path) = download.await else {
+            if let Err(e) = show_error(&app_context, "Open media", "The download failed.") {
+                tracing::error!("Failed to open the media: {}", e);
+            }
+            return;
+        };
+        if let Err(e) = open.await {
+            tracing::warn!("Failed to open the content of {}: {:?}", message_id, e);
+        }
+        let command_line = expand_shell_placeholders(&command_line, &[("{file}", &path)]);
+        tracing::info!("Opening a media with: {}", command_line);
+        let result = match shell_command(&command_line).output().await {
+            Ok(output) if output.status.success() => Ok(()),
+            Ok(output) => show_error(
+                &app_context,
+                "Open media",
+                String::from_utf8_lossy(&output.stderr),
+            ),
+            Err(e) => show_error(&app_context, "Open media", e.to_string()),
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to open the media: {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// Open a link in the background with the URL open command.
+///
+/// # Arguments
+/// * `app_context` - An Arc wrapped AppContext struct.
+/// * `url` - The link.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn open_url(app_context: Arc<AppContext>, url: String) -> Result<(), AppError<Action>> {
+    let command_line = app_context.app_config().url_open_command.clone();
+    if command_line.is_empty() {
+        return show_error(
+            &app_context,
+            "Open link",
+            format!("Set `url_open_command` in app.toml to open {}", url),
+        );
+    }
+    tokio::spawn(async move {
+        let command_line = expand_shell_placeholders(&command_line, &[("{url}", &url)]);
+        tracing::info!("Opening a link with: {}", command_line);
+        let result = match shell_command(&command_line).output().await {
+            Ok(output) if output.status.success() => Ok(()),
+            Ok(output) => show_error(
+                &app_context,
+                "Open link",
+                String::from_utf8_lossy(&output.stderr),
+            ),
+            Err(e) => show_error(&app_context, "Open link", e.to_string()),
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to open the link: {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// Fetch the preview of a link in the background, if its domain is allowed.
+/// The preview is stored in the application context and shown at the next
+/// render.
+///
+/// # Arguments
+/// * `app_context` - An Arc wrapped AppContext struct.
+/// * `url` - The URL of the link.
+pub fn fetch_url_preview(app_context: Arc<AppContext>, url: String) {
+    let (allowed, allowlist, denylist) = {
+        let app_config = app_context.app_config();
+        let allowlist = app_config.url_preview_allowlist.clone();
+        let denylist = app_config.url_preview_denylist.clone();
+        let allowed = app_config.url_preview_fetch
+            && !url_preview::is_internal(&url)
+            && url_preview::is_allowed(&url, &allowlist, &denylist);
+        (allowed, allowlist, denylist)
+    };
+    if !allowed {
+        app_context
+            .url_previews()
+            .insert(url, UrlPreviewState::Unavailable);
+        return;
+    }
+    tokio::spawn(async move {
+        let state = match url_preview::fetch(&url, &allowlist, &denylist).await {
+            Some(preview) => UrlPreviewState::Ready(preview),
+            None => UrlPreviewState::Unavailable,
+        };
+        app_context.url_previews().insert(url, state);
+    });
+}
+
+/// Download and decode an image in the background, to draw it inline in the
+/// chat.
+/// The image is stored in the application context, scaled down, and the
+/// chat is rendered again once it is ready.
+///
+/// # Arguments
+/// * `app_context` - An Arc wrapped AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `file_id` - The id of the file of the image.
+pub fn load_image(app_context: Arc<AppContext>, tg_backend: &TgBackend, file_id: i32) {
+    app_context.images().insert(file_id, ImageState::Loading);
+    let download = tg_backend.download_file(file_id);
+    tokio::spawn(async move {
+        let image = match download.await {
+            Some(path) => tokio::task::spawn_blocking(move || {
+                image::open(&path)
+                    .map(|image| image.thumbnail(MAX_IMAGE_SIZE, MAX_IMAGE_SIZE))
+                    .map_err(|e| tracing::warn!("Failed to decode the image {}: {}", path, e))
+                    .ok()
+            })
+            .await
+            .ok()
+            .flatten(),
+            None => None,
+        };
+        let state = match image {
+            Some(image) => ImageState::Ready(image),
+            None => ImageState::Failed,
+        };
+        app_context.images().insert(file_id, state);
+        if let Err(e) = app_context.action_tx().send(Action::Render) {
+            tracing::error!("Failed to draw the image {}: {}", file_id, e);
+        }
+    });
+}
@@ -0,0 +1,419 @@
+use crate::{
+    action::{Action, ChatMemberEdit},
+    app_context::AppContext,
+    app_error::AppError,
+    component_name::ComponentName,
+    components::{
+        members_window::{member_name, member_role},
+        popup_window::{Popup, PopupItem},
+    },
+    handlers::show_error,
+    tg::{tg_backend::TgBackend, tg_context::TgContext},
+};
+use tdlib_rs::enums::{ChatMemberStatus, ChatType, MessageSender};
+use tdlib_rs::types::{
+    ChatAdministratorRights, ChatMember, ChatMemberStatusAdministrator, ChatMemberStatusRestricted,
+    ChatPermissions, MessageSenderUser,
+};
+
+/// The maximum number of members of a supergroup shown in the members panel,
+/// the most recently active ones.
+const MEMBERS_LIMIT: i32 = 200;
+
+/// Load the members of the open group and show them in the members panel.
+/// Nothing is loaded when the panel of the open group is shown already,
+/// since it is being hidden.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn load_members(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let chat_id = tg_context.open_chat_id();
+    let chat_type = tg_context
+        .chats()
+        .get(&chat_id)
+        .map(|chat| chat.r#type.clone());
+    let members = match chat_type {
+        Some(ChatType::BasicGroup(_) | ChatType::Supergroup(_))
+            if chat_id == tg_context.members_chat_id() =>
+        {
+            return Ok(());
+        }
+        Some(ChatType::BasicGroup(group)) => {
+            tg_backend
+                .get_basic_group_members(group.basic_group_id)
+                .await
+        }
+        Some(ChatType::Supergroup(supergroup)) => {
+            tg_backend
+                .get_supergroup_members(supergroup.supergroup_id, MEMBERS_LIMIT)
+                .await
+        }
+        _ => return show_error(app_context, "Members", "Open a group to show its members."),
+    };
+    let mut members = match members {
+        Ok(members) => members,
+        Err(e) => return show_error(app_context, "Members", e.message),
+    };
+    // The owner and the admins come first, then the members in the order
+    // of Telegram.
+    members.sort_by_key(|member| match member.status {
+        ChatMemberStatus::Creator(_) => 0,
+        ChatMemberStatus::Administrator(_) => 1,
+        _ => 2,
+    });
+    *tg_context.chat_members() = members;
+    tg_context.set_members_chat_id(chat_id);
+    app_context
+        .action_tx()
+        .send(Action::FocusComponent(ComponentName::Members))?;
+    Ok(())
+}
+
+/// Show what can be done with a member of the members panel: their profile
+/// can always be viewed, and the admins can promote, restrict or remove
+/// them, depending on their rights.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `user_id` - The id of the user of the member.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_chat_member(app_context: &AppContext, user_id: i64) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let Some(member) = find_member(&tg_context, user_id) else {
+        return Ok(());
+    };
+    let mut items = vec![PopupItem::new(
+        "View the profile",
+        Action::ShowMemberProfile(user_id),
+    )];
+    let own_status = own_status(&tg_context, tg_context.members_chat_id());
+    if let Some(own_status) = own_status.filter(|_| user_id != tg_context.me()) {
+        items.extend(
+            member_edits(&own_status, &member.status)
+                .into_iter()
+                .map(|edit| {
+                    let label = match edit {
+                        ChatMemberEdit::Promote => "Make admin",
+                        ChatMemberEdit::Restrict => "Restrict to reading the messages",
+                        ChatMemberEdit::Remove => "Remove from the group",
+                    };
+                    PopupItem::new(label, Action::EditChatMember(user_id, edit))
+                }),
+        );
+    }
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        member_name(&tg_context, &member.member_id),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Show the profile of a member of the members panel: their name, username,
+/// phone number and bio, and their role in the group.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `user_id` - The id of the user of the member.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_member_profile(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    user_id: i64,
+) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let Some(user) = tg_context.users().get(&user_id).cloned() else {
+        return show_error(app_context, "Profile", "The user is not known yet.");
+    };
+    let mut lines = vec![format!("Name: {} {}", user.first_name, user.last_name)
+        .trim_end()
+        .to_string()];
+    if let Some(username) = user
+        .usernames
+        .as_ref()
+        .and_then(|usernames| usernames.active_usernames.first())
+    {
+        lines.push(format!("Username: @{}", username));
+    }
+    if !user.phone_number.is_empty() {
+        lines.push(format!("Phone number: +{}", user.phone_number));
+    }
+    if let Some(bio) = tg_backend
+        .get_user_full_info(user_id)
+        .await
+        .and_then(|full_info| full_info.bio)
+        .filter(|bio| !bio.text.is_empty())
+    {
+        lines.push(format!("Bio: {}", bio.text));
+    }
+    if let Some(member) = find_member(&tg_context, user_id) {
+        lines.push(format!("Role: {}", member_role(&member.status)));
+    }
+    app_context.action_tx().send(Action::ShowPopup(Popup::Info(
+        "Profile".to_string(),
+        lines.join("\n"),
+    )))?;
+    Ok(())
+}
+
+/// Promote, restrict or remove a member of the group of the members panel.
+/// The panel shows the new status of the member at once.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `user_id` - The id of the user of the member.
+/// * `edit` - The action on the member.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn edit_chat_member(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    user_id: i64,
+    edit: ChatMemberEdit,
+) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let chat_id = tg_context.members_chat_id();
+    let status = match edit {
+        ChatMemberEdit::Promote => ChatMemberStatus::Administrator(ChatMemberStatusAdministrator {
+            custom_title: String::new(),
+            can_be_edited: true,
+            rights: moderator_rights(),
+        }),
+        ChatMemberEdit::Restrict => {
+            let permissions = tg_context
+                .chats()
+                .get(&chat_id)
+                .map(|chat| read_only_permissions(&chat.permissions));
+            let Some(permissions) = permissions else {
+                return Ok(());
+            };
+            ChatMemberStatus::Restricted(ChatMemberStatusRestricted {
+                is_member: true,
+                restricted_until_date: 0,
+                permissions,
+            })
+        }
+        ChatMemberEdit::Remove => ChatMemberStatus::Left,
+    };
+    let member_id = MessageSender::User(MessageSenderUser { user_id });
+    if let Err(e) = tg_backend
+        .set_chat_member_status(chat_id, member_id, status.clone())
+        .await
+    {
+        return show_error(app_context, "Members", e.message);
+    }
+    let mut members = tg_context.chat_members();
+    if edit == ChatMemberEdit::Remove {
+        members.retain(|member| !is_user(member, user_id));
+    } else if let Some(member) = members.iter_mut().find(|member| is_user(member, user_id)) {
+        member.status = status;
+    }
+    Ok(())
+}
+
+/// Find a member of the members panel.
+///
+/// # Arguments
+/// * `tg_context` - The telegram context.
+/// * `user_id` - The id of the user of the member.
+///
+/// # Returns
+/// * `Option<ChatMember>` - The member, if they are in the panel.
+fn find_member(tg_context: &TgContext, user_id: i64) -> Option<ChatMember> {
+    tg_context
+        .chat_members()
+        .iter()
+        .find(|member| is_user(member, user_id))
+        .cloned()
+}
+
+/// Check whether a member is a user.
+///
+/// # Arguments
+/// * `member` - The member.
+/// * `user_id` - The id of the user.
+///
+/// # Returns
+/// * `bool` - Whether the member is the user.
+fn is_user(member: &ChatMember, user_id: i64) -> bool {
+    matches!(&member.member_id, MessageSender::User(user) if user.user_id == user_id)
+}
+
+/// Get the status of the current user in a group.
+///
+/// # Arguments
+/// * `tg_context` - The telegram context.
+/// * `chat_id` - The id of the chat of the group.
+///
+/// # Returns
+/// * `Option<ChatMemberStatus>` - The status, `None` if the chat is not a
+///   known group.
+fn own_status(tg_context: &TgContext, chat_id: i64) -> Option<ChatMemberStatus> {
+    let chat_type = tg_context.chats().get(&chat_id)?.r#type.clone();
+    match chat_type {
+        ChatType::BasicGroup(group) => tg_context
+            .basic_groups()
+            .get(&group.basic_group_id)
+            .map(|group| group.status.clone()),
+        ChatType::Supergroup(supergroup) => tg_context
+            .supergroups()
+            .get(&supergroup.supergroup_id)
+            .map(|supergroup| supergroup.status.clone()),
+        _ => None,
+    }
+}
+
+/// Get the actions the current user can do on a member of a group. The
+/// owner can not be changed, nor the admins promoted by another admin.
+///
+/// # Arguments
+/// * `own_status` - The status of the current user in the group.
+/// * `member_status` - The status of the member.
+///
+/// # Returns
+/// * `Vec<ChatMemberEdit>` - The actions, in the order they are shown.
+fn member_edits(
+    own_status: &ChatMemberStatus,
+    member_status: &ChatMemberStatus,
+) -> Vec<ChatMemberEdit> {
+    let (can_promote, can_restrict) = match own_status {
+        ChatMemberStatus::Creator(_) => (true, true),
+        ChatMemberStatus::Administrator(admin) => (
+            admin.rights.can_promote_members,
+            admin.rights.can_restrict_members,
+        ),
+        _ => (false, false),
+    };
+    let (editable, is_admin) = match member_status {
+        ChatMemberStatus::Creator(_) => (false, false),
+        ChatMemberStatus::Administrator(admin) => (admin.can_be_edited, true),
+        _ => (true, false),
+    };
+    let is_restricted = matches!(member_status, ChatMemberStatus::Restricted(_));
+    let mut edits = vec![];
+    if !editable {
+        return edits;
+    }
+    if can_promote && !is_admin {
+        edits.push(ChatMemberEdit::Promote);
+    }
+    if can_restrict && !is_admin && !is_restricted {
+        edits.push(ChatMemberEdit::Restrict);
+    }
+    if can_restrict {
+        edits.push(ChatMemberEdit::Remove);
+    }
+    edits
+}
+
+/// Get the rights of a member promoted from the members panel: they can
+/// manage the group, delete the messages, restrict the members, invite users
+/// and pin messages, but not promote other members.
+///
+/// # Returns
+/// * `ChatAdministratorRights` - The rights.
+fn moderator_rights() -> ChatAdministratorRights {
+    ChatAdministratorRights {
+        can_manage_chat: true,
+        can_change_info: false,
+        can_post_messages: false,
+        can_edit_messages: false,
+        can_delete_messages: true,
+        can_invite_users: true,
+        can_restrict_members: true,
+        can_pin_messages: true,
+        can_manage_topics: false,
+        can_promote_members: false,
+        can_manage_video_chats: true,
+        can_post_stories: false,
+        can_edit_stories: false,
+        can_delete_stories: false,
+        is_anonymous: false,
+    }
+}
+
+/// Get the permissions of a member restricted from the members panel: they
+/// can not send anything, and keep the other permissions of the group.
+///
+/// # Arguments
+/// * `permissions` - The permissions of the members of the group.
+///
+/// # Returns
+/// * `ChatPermissions` - The permissions of the restricted member.
+fn read_only_permissions(permissions: &ChatPermissions) -> ChatPermissions {
+    let mut permissions = permissions.clone();
+    permissions.can_send_basic_messages = false;
+    permissions.can_send_audios = false;
+    permissions.can_send_documents = false;
+    permissions.can_send_photos = false;
+    permissions.can_send_videos = false;
+    permissions.can_send_video_notes = false;
+    permissions.can_send_voice_notes = false;
+    permissions.can_send_polls = false;
+    permissions.can_send_other_messages = false;
+    permissions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tdlib_rs::types::ChatMemberStatusCreator;
+
+    fn creator() -> ChatMemberStatus {
+        ChatMemberStatus::Creator(ChatMemberStatusCreator {
+            custom_title: String::new(),
+            is_anonymous: false,
+            is_member: true,
+        })
+    }
+
+    fn admin(can_be_edited: bool) -> ChatMemberStatus {
+        ChatMemberStatus::Administrator(ChatMemberStatusAdministrator {
+            custom_title: String::new(),
+            can_be_edited,
+            rights: moderator_rights(),
+        })
+    }
+
+    #[test]
+    fn test_member_edits_of_the_owner() {
+        assert_eq!(
+            member_edits(&creator(), &ChatMemberStatus::Member),
+            vec![
+                ChatMemberEdit::Promote,
+                ChatMemberEdit::Restrict,
+                ChatMemberEdit::Remove
+            ]
+        );
+        assert_eq!(
+            member_edits(&creator(), &admin(true)),
+            vec![ChatMemberEdit::Remove]
+        );
+    }
+
+    #[test]
+    fn test_member_edits_depend_on_the_rights() {
+        // The admins promoted from the panel can not promote other members.
+        assert_eq!(
+            member_edits(&admin(true), &ChatMemberStatus::Member),
+            vec![ChatMemberEdit::Restrict, ChatMemberEdit::Remove]
+        );
+        assert!(member_edits(&admin(true), &admin(false)).is_empty());
+        assert!(member_edits(&admin(true), &creator()).is_empty());
+        assert!(member_edits(&ChatMemberStatus::Member, &ChatMemberStatus::Member).is_empty());
+    }
+}
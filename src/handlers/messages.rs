@@ -0,0 +1,906 @@
+use crate::{
+    action::{Action, Moderation},
+    app_context::AppContext,
+    app_error::AppError,
+    component_name::ComponentName,
+    components::popup_window::{Popup, PopupItem},
+    handlers::show_error,
+    tg::{
+        inline_keyboard::InlineButtonKind,
+        invoice::{format_amount, order_info_lines},
+        message_entry::{DateTimeEntry, MessageEntry},
+        tg_backend::TgBackend,
+    },
+};
+use tdlib_rs::enums::{
+    ChatAvailableReactions, ChatType, MessageContent, MessageSender, MessageSendingState,
+    ReactionType,
+};
+
+/// The maximum number of messages found by a search of all the chats or of
+/// the open chat.
+const SEARCH_RESULTS_LIMIT: i32 = 100;
+
+/// Get the sender of a message of the open chat that can be moderated, that
+/// is a message sent by another member of a group.
+/// An error is shown if the message can not be moderated.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the message.
+///
+/// # Returns
+/// * `Result<Option<(MessageSender, String)>, AppError>` - The sender of the
+///   message and its name, or `None` if the message can not be moderated.
+async fn moderated_sender(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_id: i64,
+) -> Result<Option<(MessageSender, String)>, AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let chat_id = tg_context.open_chat_id();
+    let is_group = tg_context
+        .chats()
+        .get(&chat_id)
+        .is_some_and(|chat| match &chat.r#type {
+            ChatType::BasicGroup(_) => true,
+            ChatType::Supergroup(supergroup) => !supergroup.is_channel,
+            _ => false,
+        });
+    if !is_group {
+        show_error(
+            app_context,
+            "Moderation",
+            "The moderation actions are only available in groups.",
+        )?;
+        return Ok(None);
+    }
+    let Some(message) = tg_backend.get_message(chat_id, message_id).await else {
+        show_error(app_context, "Moderation", "The message was not found.")?;
+        return Ok(None);
+    };
+    let (sender_id, name) = match &message.sender_id {
+        MessageSender::User(user) => (
+            user.user_id,
+            tg_context.try_name_from_chats_or_users(user.user_id),
+        ),
+        MessageSender::Chat(chat) => (chat.chat_id, tg_context.name_from_chats(chat.chat_id)),
+    };
+    if sender_id == tg_context.me() {
+        show_error(
+            app_context,
+            "Moderation",
+            "Your own messages can not be moderated.",
+        )?;
+        return Ok(None);
+    }
+    let name = name.unwrap_or_else(|| sender_id.to_string());
+    Ok(Some((message.sender_id, name)))
+}
+
+/// Show the moderation actions of a message of a group, each of them is
+/// confirmed before being applied.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the message.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_moderation(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_id: i64,
+) -> Result<(), AppError<Action>> {
+    let Some((_, name)) = moderated_sender(app_context, tg_backend, message_id).await? else {
+        return Ok(());
+    };
+    let confirm = |title: String, label: String, moderation: Moderation| {
+        Action::ShowPopup(Popup::List(
+            title,
+            vec![
+                PopupItem::new(label, Action::ModerateMessage(message_id, moderation)),
+                PopupItem::new("Cancel", Action::HidePopup),
+            ],
+        ))
+    };
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        format!("Moderate the message of {}", name),
+        vec![
+            PopupItem::new(
+                format!("Delete the message and ban {}", name),
+                confirm(
+                    format!("Ban {}?", name),
+                    format!(
+                        "Delete the message for everyone and ban {} from the group",
+                        name
+                    ),
+                    Moderation::DeleteAndBan,
+                ),
+            ),
+            PopupItem::new(
+                format!("Delete all the messages of {}", name),
+                confirm(
+                    format!("Delete all the messages of {}?", name),
+                    "Delete them for everyone, they can not be restored".to_string(),
+                    Moderation::DeleteAllFromSender,
+                ),
+            ),
+            PopupItem::new(
+                "Report the message to Telegram",
+                confirm(
+                    "Report the message?".to_string(),
+                    "Report the message to Telegram as spam".to_string(),
+                    Moderation::Report,
+                ),
+            ),
+        ],
+    )))?;
+    Ok(())
+}
+
+/// The reactions proposed when a chat allows all the reactions, in the order
+/// of the Telegram apps.
+const DEFAULT_REACTIONS: [&str; 20] = [
+    "👍", "👎", "❤", "🔥", "🥰", "👏", "😁", "🤔", "🤯", "😱", "🤬", "😢", "🎉", "🤩", "🤮", "💩",
+    "🙏", "👌", "🕊", "🤡",
+];
+
+/// Show a popup to pick a reaction for a message of the open chat, among the
+/// reactions allowed in the chat. The reactions already added by the user
+/// are proposed first, to remove them.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `message_id` - The id of the message.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_reactions(app_context: &AppContext, message_id: i64) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let chat_id = tg_context.open_chat_id();
+    let available: Vec<String> = match tg_context
+        .chats()
+        .get(&chat_id)
+        .map(|chat| &chat.available_reactions)
+    {
+        Some(ChatAvailableReactions::Some(available)) => available
+            .reactions
+            .iter()
+            .filter_map(|reaction| match reaction {
+                ReactionType::Emoji(reaction) => Some(reaction.emoji.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => DEFAULT_REACTIONS
+            .iter()
+            .map(|emoji| emoji.to_string())
+            .collect(),
+    };
+    let chosen: Vec<String> = tg_context
+        .open_chat_messages()
+        .iter()
+        .find(|message| message.id() == message_id)
+        .map(|message| {
+            message
+                .reactions()
+                .iter()
+                .filter(|reaction| reaction.is_chosen)
+                .map(|reaction| reaction.emoji.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    let name = |emoji: &str| emojis::get(emoji).map_or("", |emoji| emoji.name());
+    let mut items: Vec<PopupItem> = chosen
+        .iter()
+        .map(|emoji| {
+            PopupItem::new(
+                format!("{}  remove {}", emoji, name(emoji)),
+                Action::SendReaction(message_id, emoji.clone()),
+            )
+        })
+        .collect();
+    items.extend(
+        available
+            .into_iter()
+            .filter(|emoji| !chosen.contains(emoji))
+            .map(|emoji| {
+                PopupItem::new(
+                    format!("{}  {}", emoji, name(&emoji)),
+                    Action::SendReaction(message_id, emoji),
+                )
+            }),
+    );
+    let popup = if items.is_empty() {
+        Popup::Info(
+            "Reactions".to_string(),
+            "The reactions are disabled in this chat.".to_string(),
+        )
+    } else {
+        Popup::List("React to the message".to_string(), items)
+    };
+    app_context.action_tx().send(Action::ShowPopup(popup))?;
+    Ok(())
+}
+
+/// Add a reaction to a message of the open chat, or remove it if it was
+/// already added by the user. The errors are shown in a popup.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the message.
+/// * `emoji` - The emoji of the reaction.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn send_reaction(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_id: i64,
+    emoji: &str,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    let is_chosen = app_context
+        .tg_context()
+        .open_chat_messages()
+        .iter()
+        .find(|message| message.id() == message_id)
+        .is_some_and(|message| {
+            message
+                .reactions()
+                .iter()
+                .any(|reaction| reaction.is_chosen && reaction.emoji == emoji)
+        });
+    match tg_backend
+        .set_message_reaction(chat_id, message_id, emoji.to_string(), !is_chosen)
+        .await
+    {
+        Ok(()) => Ok(()),
+        Err(e) => show_error(app_context, "Reactions", e.message),
+    }
+}
+
+/// Show a popup to confirm the deletion of messages of the open chat, for the
+/// current user or for everyone, as allowed by Telegram for all of them.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_ids` - The ids of the messages.
+/// * `revoke` - Whether the deletion for everyone is proposed first.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_message_deletion(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_ids: &[i64],
+    revoke: bool,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    let (mut for_self, mut for_all) = (!message_ids.is_empty(), !message_ids.is_empty());
+    for message_id in message_ids {
+        let Some(message) = tg_backend.get_message(chat_id, *message_id).await else {
+            return show_error(app_context, "Delete", "The message was not found.");
+        };
+        for_self &= message.can_be_deleted_only_for_self || message.can_be_deleted_for_all_users;
+        for_all &= message.can_be_deleted_for_all_users;
+    }
+    let what = match message_ids.len() {
+        1 => "the message".to_string(),
+        n => format!("the {} messages", n),
+    };
+    let confirm = |title: String, label: String, revoke: bool| {
+        Action::ShowPopup(Popup::List(
+            title,
+            vec![
+                PopupItem::new(label, Action::DeleteMessages(message_ids.to_vec(), revoke)),
+                PopupItem::new("Cancel", Action::HidePopup),
+            ],
+        ))
+    };
+    let mut items = vec![];
+    if for_self {
+        items.push(PopupItem::new(
+            format!("Delete {} for me", what),
+            confirm(
+                format!("Delete {} for me?", what),
+                "Delete for me, the other members still see it".to_string(),
+                false,
+            ),
+        ));
+    }
+    if for_all {
+        items.push(PopupItem::new(
+            format!("Delete {} for everyone", what),
+            confirm(
+                format!("Delete {} for everyone?", what),
+                "Delete for me and for the other members, it can not be restored".to_string(),
+                true,
+            ),
+        ));
+    }
+    if revoke {
+        items.reverse();
+    }
+    let popup = if items.is_empty() {
+        Popup::Info(
+            "Delete".to_string(),
+            format!("You can not delete {}.", what),
+        )
+    } else {
+        Popup::List(format!("Delete {}", what), items)
+    };
+    app_context.action_tx().send(Action::ShowPopup(popup))?;
+    Ok(())
+}
+
+/// Forward messages to a chat. The errors, like a chat where the user can
+/// not write, are shown in a popup.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `chat_id` - The id of the chat the messages are forwarded to.
+/// * `from_chat_id` - The id of the chat of the messages.
+/// * `message_ids` - The ids of the messages.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn forward_messages(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    chat_id: i64,
+    from_chat_id: i64,
+    mut message_ids: Vec<i64>,
+) -> Result<(), AppError<Action>> {
+    // The messages keep the order they were sent in.
+    message_ids.sort_unstable();
+    match tg_backend
+        .forward_messages(chat_id, from_chat_id, message_ids)
+        .await
+    {
+        Ok(()) => {
+            tracing::info!("Messages forwarded to {}", chat_id);
+            Ok(())
+        }
+        Err(e) => show_error(app_context, "Forward", e.message),
+    }
+}
+
+/// Apply a confirmed moderation action to a message of the open chat and its
+/// sender. The errors, like missing admin rights, are shown in a popup.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the message.
+/// * `moderation` - The moderation action.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn moderate_message(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_id: i64,
+    moderation: Moderation,
+) -> Result<(), AppError<Action>> {
+    let Some((sender, name)) = moderated_sender(app_context, tg_backend, message_id).await? else {
+        return Ok(());
+    };
+    let chat_id = app_context.tg_context().open_chat_id();
+    let (result, done) = match moderation {
+        Moderation::DeleteAndBan => {
+            tg_backend
+                .delete_messages(chat_id, vec![message_id], true)
+                .await;
+            (
+                tg_backend.ban_chat_member(chat_id, sender).await,
+                format!("The message was deleted and {} was banned.", name),
+            )
+        }
+        Moderation::DeleteAllFromSender => (
+            tg_backend
+                .delete_chat_messages_by_sender(chat_id, sender)
+                .await,
+            format!("All the messages of {} were deleted.", name),
+        ),
+        Moderation::Report => (
+            tg_backend.report_spam(chat_id, vec![message_id]).await,
+            "The message was reported to Telegram.".to_string(),
+        ),
+    };
+    match result {
+        Ok(()) => {
+            app_context.action_tx().send(Action::ShowPopup(Popup::Info(
+                "Moderation".to_string(),
+                done,
+            )))?;
+            Ok(())
+        }
+        Err(e) => show_error(app_context, "Moderation", e.message),
+    }
+}
+
+/// Confirm the pinning of a message of the open chat, or its unpinning if it
+/// is pinned. In groups and channels, the members can be notified of the
+/// pinned message or not.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the message.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_pin_message(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_id: i64,
+) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let chat_id = tg_context.open_chat_id();
+    let Some(message) = tg_backend.get_message(chat_id, message_id).await else {
+        return show_error(app_context, "Pin message", "The message was not found.");
+    };
+    let popup = if message.is_pinned {
+        Popup::List(
+            "Unpin the message?".to_string(),
+            vec![
+                PopupItem::new("Unpin the message", Action::UnpinMessage(message_id)),
+                PopupItem::new("Cancel", Action::HidePopup),
+            ],
+        )
+    } else if tg_context.chats().get(&chat_id).is_some_and(|chat| {
+        matches!(
+            chat.r#type,
+            ChatType::BasicGroup(_) | ChatType::Supergroup(_)
+        )
+    }) {
+        Popup::List(
+            "Pin the message".to_string(),
+            vec![
+                PopupItem::new(
+                    "Pin and notify all the members",
+                    Action::PinMessage(message_id, true),
+                ),
+                PopupItem::new("Pin silently", Action::PinMessage(message_id, false)),
+                PopupItem::new("Cancel", Action::HidePopup),
+            ],
+        )
+    } else {
+        Popup::List(
+            "Pin the message?".to_string(),
+            vec![
+                PopupItem::new("Pin the message", Action::PinMessage(message_id, true)),
+                PopupItem::new("Cancel", Action::HidePopup),
+            ],
+        )
+    };
+    app_context.action_tx().send(Action::ShowPopup(popup))?;
+    Ok(())
+}
+
+/// Pin a message of the open chat. The pinned bar is updated once Telegram
+/// tells the message is pinned.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the message.
+/// * `notify` - Whether the members are notified.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn pin_message(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_id: i64,
+    notify: bool,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    match tg_backend
+        .pin_chat_message(chat_id, message_id, !notify)
+        .await
+    {
+        Ok(()) => Ok(()),
+        Err(e) => show_error(app_context, "Pin message", e.message),
+    }
+}
+
+/// Unpin a message of the open chat. The pinned bar is updated once Telegram
+/// tells the message is unpinned.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the message.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn unpin_message(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_id: i64,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    match tg_backend.unpin_chat_message(chat_id, message_id).await {
+        Ok(()) => Ok(()),
+        Err(e) => show_error(app_context, "Unpin message", e.message),
+    }
+}
+
+/// Edit the caption of a media message of the open chat in the prompt, like
+/// the text of a message. The entities of the caption are written as
+/// Markdown, which is parsed again when the caption is sent.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the message.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn edit_caption(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_id: i64,
+) -> Result<(), AppError<Action>> {
+    let caption = app_context
+        .tg_context()
+        .open_chat_messages()
+        .iter()
+        .find(|message_entry| message_entry.id() == message_id)
+        .and_then(|message_entry| message_entry.caption().cloned());
+    let Some(caption) = caption else {
+        return Ok(());
+    };
+    let text = tg_backend.get_markdown_text(caption).await;
+    // The prompt is focused before editing, since the actions are sent to
+    // the focused component.
+    app_context
+        .action_tx()
+        .send(Action::FocusComponent(ComponentName::Prompt))?;
+    app_context
+        .action_tx()
+        .send(Action::EditMessage(message_id, text))?;
+    Ok(())
+}
+
+/// Load the most recently pinned message of the open chat, shown in the
+/// pinned bar above its messages.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+pub async fn load_pinned_message(app_context: &AppContext, tg_backend: &TgBackend) {
+    let chat_id = app_context.tg_context().open_chat_id();
+    // Telegram answers with an error when the chat has no pinned message.
+    let pinned_message = match tg_backend.get_chat_pinned_message(chat_id).await {
+        Ok(message) => Some(MessageEntry::from(&message)),
+        Err(_) => None,
+    };
+    // The chat may have been closed while the message was loaded.
+    if app_context.tg_context().open_chat_id() == chat_id {
+        app_context.tg_context().set_pinned_message(pinned_message);
+    }
+}
+
+/// Show the details of a message of the open chat: the exact send and edit
+/// times in the configured timezone, the delivery state, the forward origin
+/// and, for channel posts, the number of views and forwards.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the message.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_message_details(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_id: i64,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    let Some(message) = tg_backend.get_message(chat_id, message_id).await else {
+        return show_error(
+            app_context,
+            "Message details",
+            "The message is not accessible.",
+        );
+    };
+    let entry = MessageEntry::from(&message);
+    let mut lines = vec![format!(
+        "Sent: {}",
+        DateTimeEntry::convert_full_time(message.date)
+    )];
+    if message.edit_date != 0 {
+        lines.push(format!(
+            "Edited: {}",
+            DateTimeEntry::convert_full_time(message.edit_date)
+        ));
+    }
+    if message.is_outgoing {
+        let tg_context = app_context.tg_context();
+        let is_failed = tg_context
+            .failed_messages()
+            .get(&chat_id)
+            .is_some_and(|message_ids| message_ids.contains(&message_id));
+        let is_read = tg_context
+            .chats()
+            .get(&chat_id)
+            .is_some_and(|chat| chat.last_read_outbox_message_id >= message_id);
+        let state = match &message.sending_state {
+            Some(MessageSendingState::Pending(_)) => "sending".to_string(),
+            Some(MessageSendingState::Failed(failed)) => {
+                format!("failed ({})", failed.error.message)
+            }
+            None if is_failed => "failed".to_string(),
+            None if is_read => "sent, read".to_string(),
+            None => "sent, not read yet".to_string(),
+        };
+        lines.push(format!("Delivery: {}", state));
+    }
+    if let (Some(forward_info), Some(origin)) =
+        (entry.forward_info(), entry.forward_origin_name(app_context))
+    {
+        lines.push(format!("Forwarded from: {}", origin));
+        lines.push(format!(
+            "Originally sent: {}",
+            DateTimeEntry::convert_full_time(forward_info.date)
+        ));
+    }
+    if message.is_channel_post {
+        if let Some(interaction_info) = &message.interaction_info {
+            lines.push(format!("Views: {}", interaction_info.view_count));
+            lines.push(format!("Forwards: {}", interaction_info.forward_count));
+            if let Some(reply_info) = &interaction_info.reply_info {
+                lines.push(format!("Comments: {}", reply_info.reply_count));
+            }
+        }
+    }
+    let receipt_message_id = match &message.content {
+        MessageContent::MessageInvoice(invoice) => {
+            lines.push(format!("Invoice: {}", invoice.title));
+            lines.push(format!(
+                "Total: {}",
+                format_amount(invoice.total_amount, &invoice.currency)
+            ));
+            if invoice.is_test {
+                lines.push("Test invoice, no real payment".to_string());
+            }
+            if invoice.need_shipping_address {
+                lines.push("Shipping address needed".to_string());
+            }
+            invoice.receipt_message_id
+        }
+        MessageContent::MessagePaymentSuccessful(_) => message_id,
+        _ => 0,
+    };
+    // The order info given by the user is in the receipt of the payment.
+    if receipt_message_id != 0 {
+        match tg_backend
+            .get_payment_receipt(chat_id, receipt_message_id)
+            .await
+        {
+            Ok(receipt) => {
+                lines.push(format!(
+                    "Paid: {}",
+                    DateTimeEntry::convert_full_time(receipt.date)
+                ));
+                if let Some(order_info) = &receipt.order_info {
+                    lines.extend(order_info_lines(order_info));
+                }
+            }
+            Err(e) => tracing::warn!("Failed to get the payment receipt: {}", e.message),
+        }
+    }
+    app_context.action_tx().send(Action::ShowPopup(Popup::Info(
+        "Message details".to_string(),
+        lines.join("\n"),
+    )))?;
+    Ok(())
+}
+
+/// Search the messages of all the chats. The results are shown by the
+/// search component once they arrive.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `query` - The text searched.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn search_messages(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    query: &str,
+) -> Result<(), AppError<Action>> {
+    match tg_backend
+        .search_messages(query.to_string(), SEARCH_RESULTS_LIMIT)
+        .await
+    {
+        Ok(messages) => {
+            *app_context.tg_context().search_results() = messages
+                .iter()
+                .map(|message| (message.chat_id, MessageEntry::from(message)))
+                .collect();
+            Ok(())
+        }
+        Err(e) => show_error(app_context, "Search", e.message),
+    }
+}
+
+/// Show the unread mentions of the user in all the chats, including the
+/// replies to their messages, the most recent first. They are shown by the
+/// search component, to open or dismiss them one by one.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_mentions(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+) -> Result<(), AppError<Action>> {
+    let chat_ids: Vec<i64> = app_context
+        .tg_context()
+        .chats()
+        .values()
+        .filter(|chat| chat.unread_mention_count > 0)
+        .map(|chat| chat.id)
+        .collect();
+    let mut mentions = vec![];
+    for chat_id in chat_ids {
+        match tg_backend
+            .get_unread_mentions(chat_id, SEARCH_RESULTS_LIMIT)
+            .await
+        {
+            Ok(messages) => mentions.extend(messages),
+            Err(e) => tracing::error!("Failed to get the mentions of {}: {}", chat_id, e.message),
+        }
+    }
+    mentions.sort_by_key(|message| std::cmp::Reverse((message.date, message.id)));
+    *app_context.tg_context().search_results() = mentions
+        .iter()
+        .map(|message| (message.chat_id, MessageEntry::from(message)))
+        .collect();
+    Ok(())
+}
+
+/// Dismiss an unread mention of the user, which is marked as read without
+/// opening its chat.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `chat_id` - The id of the chat of the mention.
+/// * `message_id` - The id of the message.
+pub async fn dismiss_mention(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    chat_id: i64,
+    message_id: i64,
+) {
+    tg_backend.view_messages(chat_id, vec![message_id]).await;
+    app_context
+        .tg_context()
+        .search_results()
+        .retain(|(id, message_entry)| *id != chat_id || message_entry.id() != message_id);
+}
+
+/// Search the messages of the open chat. The results are stored in the
+/// telegram context and jumped to from the chat, even when they are not
+/// loaded.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `query` - The searched text.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn search_chat_messages(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    query: &str,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    if chat_id == 0 {
+        return Ok(());
+    }
+    match tg_backend
+        .search_chat_messages(chat_id, query.to_string(), SEARCH_RESULTS_LIMIT)
+        .await
+    {
+        // The results are dropped if another chat was opened meanwhile.
+        Ok(messages) if app_context.tg_context().open_chat_id() == chat_id => {
+            *app_context.tg_context().chat_search_results() =
+                messages.iter().map(|message| message.id).collect();
+            Ok(())
+        }
+        Ok(_) => Ok(()),
+        Err(e) => show_error(app_context, "Search", e.message),
+    }
+}
+
+/// Press a button of the inline keyboard of a message of the open chat.
+/// The answer of the bot to a callback button is shown in a popup, a link is
+/// opened with the URL open command, after a confirmation for a login link.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `message_id` - The id of the message.
+/// * `row` - The row of the button.
+/// * `column` - The column of the button.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn press_inline_button(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    message_id: i64,
+    row: usize,
+    column: usize,
+) -> Result<(), AppError<Action>> {
+    let (chat_id, button) = {
+        let tg_context = app_context.tg_context();
+        let button = tg_context
+            .open_chat_messages()
+            .iter()
+            .find(|message_entry| message_entry.id() == message_id)
+            .and_then(|message_entry| message_entry.inline_keyboard().get(row)?.get(column))
+            .cloned();
+        (tg_context.open_chat_id(), button)
+    };
+    let Some(button) = button else {
+        return show_error(app_context, "Button", "The button is no longer available.");
+    };
+    match button.kind {
+        InlineButtonKind::Callback(data) => {
+            let answer = match tg_backend
+                .get_callback_query_answer(chat_id, message_id, data)
+                .await
+            {
+                Ok(answer) => answer,
+                Err(e) => return show_error(app_context, &button.text, e.message),
+            };
+            // The short answers shown by Telegram as a notification and the
+            // alerts are both shown in a popup.
+            if !answer.text.is_empty() {
+                app_context
+                    .action_tx()
+                    .send(Action::ShowPopup(Popup::Info(button.text, answer.text)))?;
+            }
+            if !answer.url.is_empty() {
+                app_context.action_tx().send(Action::OpenUrl(answer.url))?;
+            }
+        }
+        InlineButtonKind::Url(url) => app_context.action_tx().send(Action::OpenUrl(url))?,
+        InlineButtonKind::LoginUrl(url) => {
+            app_context.action_tx().send(Action::ShowPopup(Popup::List(
+                format!("Log in with Telegram on {}?", url),
+                vec![
+                    PopupItem::new("Open the login link", Action::OpenUrl(url)),
+                    PopupItem::new("Cancel", Action::HidePopup),
+                ],
+            )))?
+        }
+        InlineButtonKind::Unsupported => {
+            return show_error(
+                app_context,
+                &button.text,
+                "This kind of button can not be pressed in tgt.",
+            )
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,86 @@
+pub mod accounts;
+pub mod animations;
+pub mod attachments;
+pub mod audio;
+pub mod blocklist;
+pub mod chat_logs;
+pub mod chats;
+pub mod commands;
+pub mod contacts;
+pub mod folders;
+pub mod media;
+pub mod members;
+pub mod messages;
+pub mod notifications;
+pub mod profile;
+pub mod profile_viewer;
+pub mod settings;
+pub mod stickers;
+pub mod storage;
+pub mod templates;
+pub mod updates;
+pub mod uploads;
+
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::{Popup, PopupItem},
+};
+
+/// Show an error to the user in a popup.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `title` - The title of the popup.
+/// * `message` - The error message.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_error(
+    app_context: &AppContext,
+    title: impl AsRef<str>,
+    message: impl AsRef<str>,
+) -> Result<(), AppError<Action>> {
+    app_context.action_tx().send(Action::ShowPopup(Popup::Info(
+        title.as_ref().to_string(),
+        message.as_ref().to_string(),
+    )))?;
+    Ok(())
+}
+
+/// Show a popup to pick a chat of the main chat list, in the order of the
+/// chat list.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `title` - The title of the popup.
+/// * `action` - The function that builds the action sent for a chat.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_chat_picker(
+    app_context: &AppContext,
+    title: &str,
+    action: impl Fn(i64) -> Action,
+) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let chat_ids: Vec<i64> = tg_context
+        .chats_index()
+        .iter()
+        .map(|chat| chat.chat_id)
+        .collect();
+    let items = chat_ids
+        .into_iter()
+        .map(|chat_id| {
+            let name = tg_context
+                .name_from_chats(chat_id)
+                .unwrap_or_else(|| chat_id.to_string());
+            PopupItem::new(name, action(chat_id))
+        })
+        .collect();
+    app_context
+        .action_tx()
+        .send(Action::ShowPopup(Popup::List(title.to_string(), items)))?;
+    Ok(())
+}
@@ -0,0 +1,353 @@
+use crate::{
+    app_context::AppContext,
+    configs::raw::app_raw::FolderNotificationEntry,
+    tg::{message_entry::DateTimeEntry, td_enums::TdChatList},
+    utils::{expand_shell_placeholders, shell_command},
+};
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Weekday};
+use tdlib_rs::types::Chat;
+
+/// The text given to the notification command instead of the text of the
+/// message, when the preview hides it.
+const HIDDEN_TEXT: &str = "New message";
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `NotificationPreview` is an enum that represents how much of a message is
+/// given to the notification command, for the notifications seen by other
+/// people.
+pub enum NotificationPreview {
+    /// The chat, the sender and the text of the message.
+    Full,
+    /// The chat and the sender of the message, but not its text.
+    Sender,
+    /// Nothing but the fact that a message was received.
+    Hidden,
+}
+impl NotificationPreview {
+    /// Parse the notification preview from the application configuration.
+    ///
+    /// # Arguments
+    /// * `value` - The preview as written in the configuration.
+    ///
+    /// # Returns
+    /// * `Self` - The preview, or `Full` if it is invalid.
+    pub fn from_config(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "sender" => Self::Sender,
+            "none" => Self::Hidden,
+            _ => Self::Full,
+        }
+    }
+}
+
+/// Run the configured notification command for an incoming message in the
+/// background. Messages of muted chats and of the open chat are ignored.
+/// A chat with its own notification settings is muted as they say, the other
+/// ones as the folder rules say, or as their default settings say.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `chat_id` - The id of the chat of the message.
+/// * `sender_id` - The id of the sender of the message.
+/// * `text` - The text of the message.
+pub fn run_notification_command(
+    app_context: &AppContext,
+    chat_id: i64,
+    sender_id: i64,
+    text: &str,
+) {
+    let command_line = app_context.app_config().notification_command.clone();
+    if command_line.is_empty() {
+        return;
+    }
+    let tg_context = app_context.tg_context();
+    let is_muted = tg_context.chats().get(&chat_id).is_some_and(|chat| {
+        let settings = &chat.notification_settings;
+        let is_muted = settings.mute_for > 0;
+        if settings.use_default_mute_for {
+            folder_notification_policy(app_context, chat).map_or(is_muted, |loud| !loud)
+        } else {
+            is_muted
+        }
+    });
+    if is_muted || tg_context.open_chat_id() == chat_id {
+        return;
+    }
+    let chat = tg_context.name_from_chats(chat_id).unwrap_or_default();
+    let sender = tg_context
+        .try_name_from_chats_or_users(sender_id)
+        .unwrap_or_default();
+    let (chat, sender, text) = {
+        let app_config = app_context.app_config();
+        redact_notification(
+            NotificationPreview::from_config(&app_config.notification_preview),
+            app_config.notification_preview_length,
+            chat,
+            sender,
+            text,
+        )
+    };
+    let command_line = expand_shell_placeholders(
+        &command_line,
+        &[
+            ("{chat}", chat.as_str()),
+            ("{sender}", sender.as_str()),
+            ("{text}", text.as_str()),
+        ],
+    );
+    tokio::spawn(async move {
+        match shell_command(&command_line)
+            .stdin(std::process::Stdio::null())
+            .output()
+            .await
+        {
+            Ok(output) if output.status.success() => {}
+            Ok(output) => tracing::warn!(
+                "Notification command failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ),
+            Err(e) => tracing::warn!("Failed to run the notification command: {}", e),
+        }
+    });
+}
+
+/// Hide the details of a message in its notification as the preview says,
+/// and cut its text to the maximum length.
+///
+/// # Arguments
+/// * `preview` - How much of the message is shown.
+/// * `max_length` - The maximum number of characters of the text, zero for
+///   no limit.
+/// * `chat` - The name of the chat of the message.
+/// * `sender` - The name of the sender of the message.
+/// * `text` - The text of the message.
+///
+/// # Returns
+/// * `(String, String, String)` - The chat, the sender and the text shown.
+fn redact_notification(
+    preview: NotificationPreview,
+    max_length: usize,
+    chat: String,
+    sender: String,
+    text: &str,
+) -> (String, String, String) {
+    match preview {
+        NotificationPreview::Full => {
+            let text = if max_length > 0 && text.chars().count() > max_length {
+                let mut cut: String = text.chars().take(max_length.saturating_sub(1)).collect();
+                cut.push('…');
+                cut
+            } else {
+                text.to_string()
+            };
+            (chat, sender, text)
+        }
+        NotificationPreview::Sender => (chat, sender, HIDDEN_TEXT.to_string()),
+        NotificationPreview::Hidden => ("tgt".to_string(), String::new(), HIDDEN_TEXT.to_string()),
+    }
+}
+
+/// Get the notification policy of a chat from the rules of its folders.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `chat` - The chat.
+///
+/// # Returns
+/// * `Option<bool>` - Whether the messages of the chat are notified, or `None`
+///   if no rule of its folders applies now.
+fn folder_notification_policy(app_context: &AppContext, chat: &Chat) -> Option<bool> {
+    let rules = app_context.app_config().folder_notifications.clone();
+    if rules.is_empty() {
+        return None;
+    }
+    let folders = app_context.tg_context().chat_folders().clone();
+    let now = DateTimeEntry::now();
+    rules.iter().find_map(|rule| {
+        let folder = folders.iter().find(|folder| folder.title == rule.folder)?;
+        let is_in_folder = chat.positions.iter().any(|position| {
+            TdChatList::from(position.list.clone()) == TdChatList::Folder(folder.id)
+        });
+        if !is_in_folder || !folder_rule_applies(rule, now) {
+            return None;
+        }
+        match rule.policy.as_str() {
+            "loud" => Some(true),
+            "silent" => Some(false),
+            policy => {
+                tracing::warn!("Unknown notification policy of {}: {}", rule.folder, policy);
+                None
+            }
+        }
+    })
+}
+
+/// Check whether a folder notification rule applies at a time, from its
+/// `days` and its `hours`. The hours can go across midnight, and then the
+/// part after midnight belongs to the day the range started on.
+///
+/// # Arguments
+/// * `rule` - The rule.
+/// * `now` - The time in the configured timezone.
+///
+/// # Returns
+/// * `bool` - Whether the rule applies.
+fn folder_rule_applies(rule: &FolderNotificationEntry, now: NaiveDateTime) -> bool {
+    let mut day = now.date();
+    if let Some(hours) = &rule.hours {
+        let range = hours.split_once('-').and_then(|(from, to)| {
+            Some((
+                NaiveTime::parse_from_str(from.trim(), "%H:%M").ok()?,
+                NaiveTime::parse_from_str(to.trim(), "%H:%M").ok()?,
+            ))
+        });
+        let Some((from, to)) = range else {
+            tracing::warn!("Invalid notification hours of {}: {}", rule.folder, hours);
+            return false;
+        };
+        let time = now.time();
+        if from <= to {
+            if time < from || time >= to {
+                return false;
+            }
+        } else if time < to {
+            day = day.pred_opt().unwrap_or(day);
+        } else if time < from {
+            return false;
+        }
+    }
+    let Some(days) = &rule.days else {
+        return true;
+    };
+    days.iter().any(|name| {
+        name.trim()
+            .parse::<Weekday>()
+            .is_ok_and(|weekday| weekday == day.weekday())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn rule(hours: Option<&str>, days: Option<&[&str]>) -> FolderNotificationEntry {
+        FolderNotificationEntry {
+            folder: "Work".to_string(),
+            policy: "silent".to_string(),
+            hours: hours.map(str::to_string),
+            days: days.map(|days| days.iter().map(|day| day.to_string()).collect()),
+        }
+    }
+
+    /// 2024-03-15 is a Friday.
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_redact_notification_as_the_preview_says() {
+        let redact = |preview, max_length| {
+            redact_notification(
+                preview,
+                max_length,
+                "Family".to_string(),
+                "Ada".to_string(),
+                "see you at 5",
+            )
+        };
+        assert_eq!(
+            redact(NotificationPreview::Full, 0),
+            (
+                "Family".to_string(),
+                "Ada".to_string(),
+                "see you at 5".to_string()
+            )
+        );
+        assert_eq!(redact(NotificationPreview::Full, 8).2, "see you…");
+        assert_eq!(redact(NotificationPreview::Full, 12).2, "see you at 5");
+        assert_eq!(
+            redact(NotificationPreview::Sender, 0),
+            (
+                "Family".to_string(),
+                "Ada".to_string(),
+                "New message".to_string()
+            )
+        );
+        assert_eq!(
+            redact(NotificationPreview::Hidden, 0),
+            ("tgt".to_string(), String::new(), "New message".to_string())
+        );
+        assert_eq!(
+            NotificationPreview::from_config(" Sender "),
+            NotificationPreview::Sender
+        );
+        assert_eq!(
+            NotificationPreview::from_config("unknown"),
+            NotificationPreview::Full
+        );
+    }
+
+    #[test]
+    fn test_folder_rule_applies_without_limits() {
+        assert!(folder_rule_applies(&rule(None, None), at(15, 12, 0)));
+    }
+
+    #[test]
+    fn test_folder_rule_applies_in_hours() {
+        let rule = rule(Some("09:00-18:00"), None);
+        assert!(!folder_rule_applies(&rule, at(15, 8, 59)));
+        assert!(folder_rule_applies(&rule, at(15, 9, 0)));
+        assert!(folder_rule_applies(&rule, at(15, 17, 59)));
+        assert!(!folder_rule_applies(&rule, at(15, 18, 0)));
+    }
+
+    #[test]
+    fn test_folder_rule_applies_across_midnight() {
+        let rule = rule(Some("22:00-07:00"), None);
+        assert!(!folder_rule_applies(&rule, at(15, 21, 59)));
+        assert!(folder_rule_applies(&rule, at(15, 22, 0)));
+        assert!(folder_rule_applies(&rule, at(15, 23, 59)));
+        assert!(folder_rule_applies(&rule, at(16, 0, 0)));
+        assert!(folder_rule_applies(&rule, at(16, 6, 59)));
+        assert!(!folder_rule_applies(&rule, at(16, 7, 0)));
+    }
+
+    #[test]
+    fn test_folder_rule_applies_on_days() {
+        let rule = rule(None, Some(&["sat", "Sunday"]));
+        assert!(!folder_rule_applies(&rule, at(15, 12, 0)));
+        assert!(folder_rule_applies(&rule, at(16, 12, 0)));
+        assert!(folder_rule_applies(&rule, at(17, 12, 0)));
+        assert!(!folder_rule_applies(&rule, at(18, 0, 0)));
+    }
+
+    #[test]
+    fn test_folder_rule_applies_across_midnight_on_days() {
+        // The night of Friday goes on after midnight, into Saturday.
+        let rule = rule(Some("22:00-02:00"), Some(&["fri"]));
+        assert!(!folder_rule_applies(&rule, at(15, 1, 0)));
+        assert!(folder_rule_applies(&rule, at(15, 23, 0)));
+        assert!(folder_rule_applies(&rule, at(16, 1, 0)));
+        assert!(!folder_rule_applies(&rule, at(16, 23, 0)));
+    }
+
+    #[test]
+    fn test_folder_rule_applies_invalid_hours() {
+        assert!(!folder_rule_applies(
+            &rule(Some("9-18"), None),
+            at(15, 12, 0)
+        ));
+        assert!(!folder_rule_applies(
+            &rule(Some("09:00"), None),
+            at(15, 12, 0)
+        ));
+        assert!(!folder_rule_applies(
+            &rule(Some("25:00-26:00"), None),
+            at(15, 12, 0)
+        ));
+    }
+}
@@ -0,0 +1,443 @@
+use crate::{
+    action::{Action, CloudPasswordEdit},
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::{Popup, PopupItem},
+    handlers::show_error,
+    tg::tg_backend::TgBackend,
+};
+use tdlib_rs::{enums::CheckChatUsernameResult, types::PasswordState};
+
+/// Show the "My Profile" settings screen.
+/// It lists the username, the bio, the emoji status, the online visibility
+/// and the two-step verification of the current user, each of them can be
+/// selected to be edited.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A mutable reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_my_profile(
+    app_context: &AppContext,
+    tg_backend: &mut TgBackend,
+) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let me = tg_context.me();
+    let user = tg_context.users().get(&me).cloned();
+    let username = user
+        .as_ref()
+        .and_then(|user| user.usernames.as_ref())
+        .map(|usernames| usernames.editable_username.clone())
+        .unwrap_or_default();
+    let emoji_status = match user.as_ref().and_then(|user| user.emoji_status.as_ref()) {
+        Some(_) => "set",
+        None => "none",
+    };
+    let bio = tg_backend
+        .get_user_full_info(me)
+        .await
+        .and_then(|full_info| full_info.bio)
+        .map(|bio| bio.text)
+        .unwrap_or_default();
+    let online = if tg_backend.is_online_visible().await {
+        "everybody"
+    } else {
+        "nobody"
+    };
+    let two_step_verification = match tg_backend.get_password_state().await {
+        Ok(state) if state.has_password => "on",
+        _ => "off",
+    };
+
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "My Profile".to_string(),
+        vec![
+            PopupItem::new(
+                format!("Username: @{}", username),
+                Action::ShowPopup(Popup::Input(
+                    "New username".to_string(),
+                    Box::new(Action::SetUsername(String::new())),
+                )),
+            ),
+            PopupItem::new(
+                format!("Bio: {}", bio),
+                Action::ShowPopup(Popup::Input(
+                    "New bio".to_string(),
+                    Box::new(Action::SetBio(String::new())),
+                )),
+            ),
+            PopupItem::new(
+                format!("Emoji status: {}", emoji_status),
+                Action::ShowEmojiStatuses,
+            ),
+            PopupItem::new(
+                format!("Online visible to: {}", online),
+                Action::ToggleOnlineVisibility,
+            ),
+            PopupItem::new(
+                format!("Two-step verification: {}", two_step_verification),
+                Action::ShowCloudPassword,
+            ),
+        ],
+    )))?;
+    Ok(())
+}
+
+/// Show the default emoji statuses in a popup, to set one of them as the
+/// emoji status of the current user. Emoji statuses are available only for
+/// Telegram Premium accounts.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_emoji_statuses(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+) -> Result<(), AppError<Action>> {
+    if !app_context.tg_context().me_is_premium() {
+        return show_error(
+            app_context,
+            "Emoji status",
+            "Emoji statuses are available only for Telegram Premium accounts.",
+        );
+    }
+    let mut items = vec![PopupItem::new(
+        "No emoji status",
+        Action::SetEmojiStatus(None),
+    )];
+    items.extend(
+        tg_backend
+            .get_default_emoji_statuses()
+            .await
+            .into_iter()
+            .map(|(custom_emoji_id, emoji)| {
+                PopupItem::new(emoji, Action::SetEmojiStatus(Some(custom_emoji_id)))
+            }),
+    );
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "Emoji status".to_string(),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Show the two-step verification settings of the current user, to set,
+/// change or remove the cloud password and its recovery email address.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_cloud_password(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+) -> Result<(), AppError<Action>> {
+    let state = match tg_backend.get_password_state().await {
+        Ok(state) => state,
+        Err(e) => return show_error(app_context, "Two-step verification", e.message),
+    };
+    let mut items = vec![];
+    if state.has_password {
+        let hint = if state.password_hint.is_empty() {
+            "none"
+        } else {
+            state.password_hint.as_str()
+        };
+        let recovery_email = if state.has_recovery_email_address {
+            "set"
+        } else {
+            "none"
+        };
+        items.push(PopupItem::new(
+            format!("Change the password (hint: {})", hint),
+            cloud_password_input(CloudPasswordEdit::Change, vec![]),
+        ));
+        items.push(PopupItem::new(
+            "Remove the password",
+            cloud_password_input(CloudPasswordEdit::Remove, vec![]),
+        ));
+        items.push(PopupItem::new(
+            format!("Recovery email: {}", recovery_email),
+            cloud_password_input(CloudPasswordEdit::SetRecoveryEmail, vec![]),
+        ));
+    } else {
+        items.push(PopupItem::new(
+            "Set a password",
+            cloud_password_input(CloudPasswordEdit::Set, vec![]),
+        ));
+    }
+    if let Some(code_info) = &state.recovery_email_address_code_info {
+        items.push(PopupItem::new(
+            format!("Enter the code sent to {}", code_info.email_address_pattern),
+            recovery_email_code_input(&code_info.email_address_pattern),
+        ));
+    }
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "Two-step verification".to_string(),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Continue a change of the cloud password with the field just entered.
+/// The next field is asked for, and the change is made when all of them are
+/// entered.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `edit` - The change of the password.
+/// * `fields` - The fields entered before.
+/// * `text` - The field just entered.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn edit_cloud_password(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    edit: CloudPasswordEdit,
+    fields: &[String],
+    text: &str,
+) -> Result<(), AppError<Action>> {
+    let mut fields = fields.to_vec();
+    fields.push(text.to_string());
+    if fields.len() < edit.fields().len() {
+        app_context
+            .action_tx()
+            .send(cloud_password_input(edit, fields))?;
+        return Ok(());
+    }
+    let result = match (edit, fields.as_slice()) {
+        (CloudPasswordEdit::Set, [new, repeated, hint, email]) => {
+            if let Some(problem) = new_password_problem(new, repeated) {
+                return show_error(app_context, "Two-step verification", problem);
+            }
+            let email = email.trim();
+            tg_backend
+                .set_password(
+                    String::new(),
+                    new.clone(),
+                    hint.clone(),
+                    (!email.is_empty()).then(|| email.to_string()),
+                )
+                .await
+        }
+        (CloudPasswordEdit::Change, [old, new, repeated, hint]) => {
+            if let Some(problem) = new_password_problem(new, repeated) {
+                return show_error(app_context, "Two-step verification", problem);
+            }
+            tg_backend
+                .set_password(old.clone(), new.clone(), hint.clone(), None)
+                .await
+        }
+        (CloudPasswordEdit::Remove, [old]) => {
+            tg_backend
+                .set_password(old.clone(), String::new(), String::new(), None)
+                .await
+        }
+        (CloudPasswordEdit::SetRecoveryEmail, [password, email]) => {
+            tg_backend
+                .set_recovery_email_address(password.clone(), email.trim().to_string())
+                .await
+        }
+        _ => return Ok(()),
+    };
+    match result {
+        Ok(state) => password_state_changed(app_context, &state),
+        Err(e) => show_error(app_context, "Two-step verification", e.message),
+    }
+}
+
+/// Check the code sent to the new recovery email address of the cloud
+/// password.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `code` - The code entered by the user.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn check_recovery_email_code(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    code: &str,
+) -> Result<(), AppError<Action>> {
+    match tg_backend
+        .check_recovery_email_address_code(code.trim().to_string())
+        .await
+    {
+        Ok(state) => password_state_changed(app_context, &state),
+        Err(e) => show_error(app_context, "Recovery email", e.message),
+    }
+}
+
+/// Ask for the code sent to the new recovery email address if it has to be
+/// confirmed, otherwise show the two-step verification settings again.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `state` - The state of the password after a change.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+fn password_state_changed(
+    app_context: &AppContext,
+    state: &PasswordState,
+) -> Result<(), AppError<Action>> {
+    let action = match &state.recovery_email_address_code_info {
+        Some(code_info) => recovery_email_code_input(&code_info.email_address_pattern),
+        None => Action::ShowCloudPassword,
+    };
+    app_context.action_tx().send(action)?;
+    Ok(())
+}
+
+/// Check the new cloud password entered twice by the user.
+///
+/// # Arguments
+/// * `new` - The new password.
+/// * `repeated` - The new password, entered again.
+///
+/// # Returns
+/// * `Option<&str>` - The problem of the new password, if any.
+fn new_password_problem(new: &str, repeated: &str) -> Option<&'static str> {
+    if new.is_empty() {
+        Some("The password can not be empty, remove it instead.")
+    } else if new != repeated {
+        Some("The passwords do not match.")
+    } else {
+        None
+    }
+}
+
+/// Get the action that asks for the next field of a change of the cloud
+/// password.
+///
+/// # Arguments
+/// * `edit` - The change of the password.
+/// * `fields` - The fields entered before.
+///
+/// # Returns
+/// * `Action` - The action that shows the input popup of the next field.
+fn cloud_password_input(edit: CloudPasswordEdit, fields: Vec<String>) -> Action {
+    let title = edit
+        .fields()
+        .get(fields.len())
+        .map_or("", |(title, _)| title);
+    Action::ShowPopup(Popup::Input(
+        title.to_string(),
+        Box::new(Action::EditCloudPassword(edit, fields, String::new())),
+    ))
+}
+
+/// Get the action that asks for the code sent to the new recovery email
+/// address.
+///
+/// # Arguments
+/// * `email_address_pattern` - The pattern of the email address.
+///
+/// # Returns
+/// * `Action` - The action that shows the input popup of the code.
+fn recovery_email_code_input(email_address_pattern: &str) -> Action {
+    Action::ShowPopup(Popup::Input(
+        format!("Code sent to {}", email_address_pattern),
+        Box::new(Action::CheckRecoveryEmailCode(String::new())),
+    ))
+}
+
+/// Set the username of the current user.
+/// The availability of the username is checked before it is set.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A mutable reference to the TgBackend struct.
+/// * `username` - The new username, with or without the leading `@`.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn set_username(
+    app_context: &AppContext,
+    tg_backend: &mut TgBackend,
+    username: &str,
+) -> Result<(), AppError<Action>> {
+    let username = username.trim().trim_start_matches('@').to_string();
+    // An empty username removes the current one, so there is nothing to
+    // check.
+    if !username.is_empty() {
+        match tg_backend.check_username(username.clone()).await {
+            Ok(CheckChatUsernameResult::Ok) => {}
+            Ok(result) => {
+                return show_error(
+                    app_context,
+                    "Username",
+                    format!("@{} {}.", username, username_problem(&result)),
+                )
+            }
+            Err(e) => return show_error(app_context, "Username", e.message),
+        }
+    }
+    if let Err(e) = tg_backend.set_username(username).await {
+        return show_error(app_context, "Username", e.message);
+    }
+    app_context.action_tx().send(Action::ShowMyProfile)?;
+    Ok(())
+}
+
+/// Check the availability of a username while the user is typing it and
+/// show the result under the text of the input popup.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A mutable reference to the TgBackend struct.
+/// * `text` - The text typed by the user.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn check_username(
+    app_context: &AppContext,
+    tg_backend: &mut TgBackend,
+    text: &str,
+) -> Result<(), AppError<Action>> {
+    let username = text.trim().trim_start_matches('@');
+    let status = if username.is_empty() {
+        "An empty username removes the current one.".to_string()
+    } else {
+        match tg_backend.check_username(username.to_string()).await {
+            Ok(CheckChatUsernameResult::Ok) => format!("@{} is available.", username),
+            Ok(result) => format!("@{} {}.", username, username_problem(&result)),
+            Err(e) => e.message,
+        }
+    };
+    app_context
+        .action_tx()
+        .send(Action::SetPopupStatus(text.to_string(), status))?;
+    Ok(())
+}
+
+/// Describe why a username can not be used.
+///
+/// # Arguments
+/// * `result` - The result of the availability check.
+///
+/// # Returns
+/// * `&str` - The description of the problem.
+fn username_problem(result: &CheckChatUsernameResult) -> &'static str {
+    match result {
+        CheckChatUsernameResult::Ok => "is available",
+        CheckChatUsernameResult::UsernameInvalid => "is invalid",
+        CheckChatUsernameResult::UsernameOccupied => "is already taken",
+        CheckChatUsernameResult::UsernamePurchasable => "can be purchased on fragment.com",
+        CheckChatUsernameResult::PublicChatsTooMany => {
+            "can not be used: too many public chats are owned"
+        }
+        CheckChatUsernameResult::PublicGroupsUnavailable => "can not be used",
+    }
+}
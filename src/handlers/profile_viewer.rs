@@ -0,0 +1,269 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::Popup,
+    handlers::show_error,
+    tg::{tg_backend::TgBackend, tg_context::TgContext},
+};
+use image::{imageops::FilterType, DynamicImage};
+use tdlib_rs::{enums::ChatType, types::Chat};
+
+/// The width of the photo of a profile, in characters.
+const PHOTO_WIDTH: u32 = 32;
+/// The characters the photo of a profile is drawn with, from the darkest to
+/// the lightest.
+const PHOTO_RAMP: &[u8] = b" .:-=+*#%@";
+/// The maximum number of groups in common with a user listed in their
+/// profile.
+const COMMON_GROUPS_LIMIT: i32 = 20;
+
+/// Show the profile of a user or of a chat in a popup, with its full info
+/// and its photo drawn with characters. The profile of a private chat is the
+/// profile of its user.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `id` - The id of the chat, or of the user.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_profile(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    id: i64,
+) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    let chat = tg_context.chats().get(&id).cloned();
+    let user_id = match chat.as_ref().map(|chat| &chat.r#type) {
+        Some(ChatType::Private(private)) => Some(private.user_id),
+        Some(ChatType::Secret(secret)) => Some(secret.user_id),
+        Some(_) => None,
+        None => tg_context.users().contains_key(&id).then_some(id),
+    };
+    let profile = match (user_id, chat) {
+        (Some(user_id), _) => user_profile(&tg_context, tg_backend, user_id).await,
+        (None, Some(chat)) => Some(chat_profile(&tg_context, tg_backend, &chat).await),
+        (None, None) => None,
+    };
+    let Some((title, mut lines, photo_file_id)) = profile else {
+        return show_error(
+            app_context,
+            "Profile",
+            "The user or the chat is not known yet.",
+        );
+    };
+    if let Some(photo) = match photo_file_id {
+        Some(file_id) => profile_photo(tg_backend, file_id).await,
+        None => None,
+    } {
+        lines.insert(0, photo);
+        lines.insert(1, String::new());
+    }
+    app_context
+        .action_tx()
+        .send(Action::ShowPopup(Popup::Info(title, lines.join("\n"))))?;
+    Ok(())
+}
+
+/// Get the profile of a user: their name, username, phone number, bio and
+/// groups in common.
+///
+/// # Arguments
+/// * `tg_context` - The telegram context.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `user_id` - The id of the user.
+///
+/// # Returns
+/// * `Option<(String, Vec<String>, Option<i32>)>` - The title of the
+///   profile, its lines and the id of the file of the photo, or `None` if
+///   the user is not known.
+async fn user_profile(
+    tg_context: &TgContext,
+    tg_backend: &TgBackend,
+    user_id: i64,
+) -> Option<(String, Vec<String>, Option<i32>)> {
+    let user = tg_context.users().get(&user_id).cloned()?;
+    let name = format!("{} {}", user.first_name, user.last_name)
+        .trim_end()
+        .to_string();
+    let mut lines = vec![format!("Name: {}", name)];
+    if let Some(username) = user
+        .usernames
+        .as_ref()
+        .and_then(|usernames| usernames.active_usernames.first())
+    {
+        lines.push(format!("Username: @{}", username));
+    }
+    if !user.phone_number.is_empty() {
+        lines.push(format!("Phone number: +{}", user.phone_number));
+    }
+    let full_info = tg_backend.get_user_full_info(user_id).await;
+    if let Some(bio) = full_info
+        .as_ref()
+        .and_then(|full_info| full_info.bio.as_ref())
+        .filter(|bio| !bio.text.is_empty())
+    {
+        lines.push(format!("Bio: {}", bio.text));
+    }
+    let common_count = full_info.map_or(0, |full_info| full_info.group_in_common_count);
+    if common_count > 0 {
+        let groups = match tg_backend
+            .get_groups_in_common(user_id, COMMON_GROUPS_LIMIT)
+            .await
+        {
+            Ok(chat_ids) => chat_ids
+                .iter()
+                .filter_map(|chat_id| tg_context.name_from_chats(*chat_id))
+                .collect::<Vec<String>>()
+                .join(", "),
+            Err(e) => {
+                tracing::warn!("Failed to get the groups in common with {}: {e:?}", user_id);
+                String::new()
+            }
+        };
+        lines.push(format!("Groups in common ({}): {}", common_count, groups));
+    }
+    let photo_file_id = user.profile_photo.as_ref().map(|photo| photo.small.id);
+    Some((format!("Profile of {}", name), lines, photo_file_id))
+}
+
+/// Get the profile of a group or of a channel: its title, kind, username,
+/// description and number of members.
+///
+/// # Arguments
+/// * `tg_context` - The telegram context.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `chat` - The chat.
+///
+/// # Returns
+/// * `(String, Vec<String>, Option<i32>)` - The title of the profile, its
+///   lines and the id of the file of the photo.
+async fn chat_profile(
+    tg_context: &TgContext,
+    tg_backend: &TgBackend,
+    chat: &Chat,
+) -> (String, Vec<String>, Option<i32>) {
+    let mut lines = vec![format!("Title: {}", chat.title)];
+    let (description, member_count) = match &chat.r#type {
+        ChatType::BasicGroup(basic_group) => {
+            lines.push("Type: group".to_string());
+            match tg_backend
+                .get_basic_group_full_info(basic_group.basic_group_id)
+                .await
+            {
+                Ok(full_info) => (full_info.description, Some(full_info.members.len())),
+                Err(e) => {
+                    tracing::warn!("Failed to get the info of the group {}: {e:?}", chat.id);
+                    (String::new(), None)
+                }
+            }
+        }
+        ChatType::Supergroup(supergroup) => {
+            lines.push(
+                if supergroup.is_channel {
+                    "Type: channel"
+                } else {
+                    "Type: supergroup"
+                }
+                .to_string(),
+            );
+            if let Some(username) = tg_context
+                .supergroups()
+                .get(&supergroup.supergroup_id)
+                .and_then(|supergroup| supergroup.usernames.as_ref())
+                .and_then(|usernames| usernames.active_usernames.first())
+            {
+                lines.push(format!("Username: @{}", username));
+            }
+            match tg_backend
+                .get_supergroup_full_info(supergroup.supergroup_id)
+                .await
+            {
+                Ok(full_info) => (full_info.description, Some(full_info.member_count as usize)),
+                Err(e) => {
+                    tracing::warn!("Failed to get the info of the chat {}: {e:?}", chat.id);
+                    (String::new(), None)
+                }
+            }
+        }
+        _ => (String::new(), None),
+    };
+    if !description.is_empty() {
+        lines.push(format!("Description: {}", description));
+    }
+    if let Some(member_count) = member_count {
+        lines.push(format!("Members: {}", member_count));
+    }
+    let photo_file_id = chat.photo.as_ref().map(|photo| photo.small.id);
+    (format!("Profile of {}", chat.title), lines, photo_file_id)
+}
+
+/// Download the photo of a profile and draw it with characters.
+///
+/// # Arguments
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `file_id` - The id of the file of the photo.
+///
+/// # Returns
+/// * `Option<String>` - The lines of the photo, or `None` if it can not be
+///   downloaded or decoded.
+async fn profile_photo(tg_backend: &TgBackend, file_id: i32) -> Option<String> {
+    let path = tg_backend.download_file(file_id).await?;
+    tokio::task::spawn_blocking(move || {
+        image::open(&path)
+            .map(|image| photo_lines(&image, PHOTO_WIDTH))
+            .map_err(|e| tracing::warn!("Failed to decode the photo {}: {}", path, e))
+            .ok()
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Draw an image with characters, the lighter a pixel the denser its
+/// character. A character is about twice as high as it is wide, so a line
+/// is drawn for every two columns of the same height.
+///
+/// # Arguments
+/// * `image` - The image.
+/// * `width` - The width of the drawing, in characters.
+///
+/// # Returns
+/// * `String` - The lines of the drawing.
+fn photo_lines(image: &DynamicImage, width: u32) -> String {
+    let height = (image.height() * width / image.width().max(1) / 2).max(1);
+    let image = image
+        .resize_exact(width, height, FilterType::Triangle)
+        .to_luma8();
+    image
+        .rows()
+        .map(|row| {
+            row.map(|pixel| PHOTO_RAMP[pixel.0[0] as usize * (PHOTO_RAMP.len() - 1) / 255] as char)
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    #[test]
+    fn test_photo_lines() {
+        let white = GrayImage::from_pixel(8, 8, Luma([255]));
+        assert_eq!(
+            photo_lines(&DynamicImage::ImageLuma8(white), 4),
+            "@@@@\n@@@@"
+        );
+        // A line is drawn for every two columns of the same height.
+        let black = GrayImage::from_pixel(16, 4, Luma([0]));
+        assert_eq!(
+            photo_lines(&DynamicImage::ImageLuma8(black), 8),
+            " ".repeat(8)
+        );
+    }
+}
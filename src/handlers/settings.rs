@@ -0,0 +1,85 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    color_scheme::{self, ColorScheme},
+    configs::{
+        config_diagnostics::{self, ConfigDiagnostic},
+        custom::theme_custom::ThemeConfig,
+    },
+    handlers::show_error,
+};
+
+/// Show the problems found in the configuration files when the application
+/// started, grouped by file.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `always` - Whether to show the popup even if there are no problems.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_config_diagnostics(
+    app_context: &AppContext,
+    always: bool,
+) -> Result<(), AppError<Action>> {
+    let diagnostics = config_diagnostics::diagnostics();
+    if diagnostics.is_empty() {
+        if always {
+            show_error(
+                app_context,
+                "Configuration",
+                "No problems were found in the configuration files.",
+            )?;
+        }
+        return Ok(());
+    }
+    let mut files: Vec<(String, Vec<ConfigDiagnostic>)> = vec![];
+    for diagnostic in diagnostics {
+        match files.iter_mut().find(|(file, _)| *file == diagnostic.file) {
+            Some((_, file_diagnostics)) => file_diagnostics.push(diagnostic),
+            None => files.push((diagnostic.file.clone(), vec![diagnostic])),
+        }
+    }
+    let mut lines = vec![];
+    for (file, file_diagnostics) in files {
+        if config_diagnostics::has_errors(&file_diagnostics) {
+            lines.push(format!(
+                "{} was not loaded, the default configuration is used.",
+                file
+            ));
+        } else {
+            lines.push(format!("{} was loaded, the following is ignored.", file));
+        }
+        for diagnostic in file_diagnostics {
+            let position = match (diagnostic.line, diagnostic.column) {
+                (Some(line), Some(column)) => format!("{}:{}", line, column),
+                (Some(line), None) => line.to_string(),
+                _ => "-".to_string(),
+            };
+            lines.push(format!(
+                "  {} {}: {}",
+                position, diagnostic.severity, diagnostic.message
+            ));
+            if let Some(suggestion) = diagnostic.suggestion {
+                lines.push(format!("    help: {}", suggestion));
+            }
+        }
+    }
+    show_error(app_context, "Configuration problems", lines.join("\n"))
+}
+
+/// Switch to the theme configured for a color scheme.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `scheme` - The color scheme of the terminal or of the system.
+pub fn apply_color_scheme(app_context: &AppContext, scheme: ColorScheme) {
+    let filename = color_scheme::theme_filename(&app_context.app_config(), scheme).to_string();
+    tracing::info!(
+        "Using the theme {} for the {:?} color scheme",
+        filename,
+        scheme
+    );
+    *app_context.theme_config() = ThemeConfig::from_filename(&filename);
+}
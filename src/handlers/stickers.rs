@@ -0,0 +1,410 @@
+use crate::{
+    action::{Action, StickerSetEdit},
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::{MediaItem, Popup, PopupItem},
+    handlers::show_error,
+    tg::tg_backend::TgBackend,
+};
+use std::collections::HashMap;
+use tdlib_rs::{
+    enums::{StickerFormat, ThumbnailFormat},
+    types::{Sticker, StickerSet},
+};
+
+/// The number of stickers in a row of the preview of a sticker set.
+const STICKER_PREVIEW_COLUMNS: usize = 8;
+
+/// Show the installed sticker sets. A set can be selected to be managed and
+/// a new set can be added from a link.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_sticker_sets(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+) -> Result<(), AppError<Action>> {
+    let sticker_sets = match tg_backend.get_installed_sticker_sets().await {
+        Ok(sticker_sets) => sticker_sets,
+        Err(e) => return show_error(app_context, "Sticker sets", e.message),
+    };
+    let mut items: Vec<PopupItem> = sticker_sets
+        .iter()
+        .map(|sticker_set| {
+            PopupItem::new(
+                format!("{} ({} stickers)", sticker_set.title, sticker_set.size),
+                Action::ShowStickerSet(sticker_set.id),
+            )
+        })
+        .collect();
+    items.push(PopupItem::new(
+        "Add a set from a link",
+        Action::ShowPopup(Popup::Input(
+            "Link of the sticker set, like t.me/addstickers/name".to_string(),
+            Box::new(Action::AddStickerSet(String::new())),
+        )),
+    ));
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "Sticker sets".to_string(),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Show the actions of a sticker set: preview it, install it or, if it is
+/// installed, move or remove it.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `sticker_set` - The sticker set.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_sticker_set(
+    app_context: &AppContext,
+    sticker_set: &StickerSet,
+) -> Result<(), AppError<Action>> {
+    let set_id = sticker_set.id;
+    let mut items = vec![PopupItem::new(
+        format!("Preview the {} stickers", sticker_set.stickers.len()),
+        Action::ShowStickerSetPreview(set_id),
+    )];
+    if sticker_set.is_installed && !sticker_set.is_archived {
+        items.push(PopupItem::new(
+            "Move up",
+            Action::EditStickerSet(set_id, StickerSetEdit::MoveUp),
+        ));
+        items.push(PopupItem::new(
+            "Move down",
+            Action::EditStickerSet(set_id, StickerSetEdit::MoveDown),
+        ));
+        items.push(PopupItem::new(
+            "Remove",
+            Action::EditStickerSet(set_id, StickerSetEdit::Remove),
+        ));
+    } else {
+        items.push(PopupItem::new(
+            "Install",
+            Action::EditStickerSet(set_id, StickerSetEdit::Install),
+        ));
+    }
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        format!("Sticker set {}", sticker_set.title),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Show the stickers of a set in a grid, each one as its emoji.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `set_id` - The identifier of the sticker set.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_sticker_set_preview(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    set_id: i64,
+) -> Result<(), AppError<Action>> {
+    let sticker_set = match tg_backend.get_sticker_set(set_id).await {
+        Ok(sticker_set) => sticker_set,
+        Err(e) => return show_error(app_context, "Sticker set", e.message),
+    };
+    let emojis = sticker_set
+        .stickers
+        .iter()
+        .map(|sticker| {
+            if sticker.emoji.is_empty() {
+                "🎨".to_string()
+            } else {
+                sticker.emoji.clone()
+            }
+        })
+        .collect::<Vec<String>>();
+    let grid = emojis
+        .chunks(STICKER_PREVIEW_COLUMNS)
+        .map(|row| row.join("  "))
+        .collect::<Vec<String>>()
+        .join("\n\n");
+    app_context.action_tx().send(Action::ShowPopup(Popup::Info(
+        format!("{} ({})", sticker_set.title, sticker_set.name),
+        grid,
+    )))?;
+    Ok(())
+}
+
+/// Get the short name of a sticker set from a link like
+/// `https://t.me/addstickers/name`, or from the name itself.
+///
+/// # Arguments
+/// * `link` - The link or the name.
+///
+/// # Returns
+/// * `Option<String>` - The name of the sticker set.
+fn sticker_set_name(link: &str) -> Option<String> {
+    let link = link.trim();
+    let name = link
+        .split_once("addstickers/")
+        .map(|(_, name)| name)
+        .or_else(|| link.split_once("addstickers?set=").map(|(_, name)| name))
+        .unwrap_or(link);
+    let name = name.split(['?', '/', '#']).next().unwrap_or_default();
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Some(name.to_string())
+    } else {
+        None
+    }
+}
+
+/// Find a sticker set from a link and show its actions, so that it can be
+/// previewed and installed.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `link` - The link of the sticker set or its name.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn add_sticker_set(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    link: &str,
+) -> Result<(), AppError<Action>> {
+    let Some(name) = sticker_set_name(link) else {
+        return show_error(
+            app_context,
+            "Sticker set",
+            format!("{} is not a link of a sticker set.", link.trim()),
+        );
+    };
+    match tg_backend.search_sticker_set(name).await {
+        Ok(sticker_set) => show_sticker_set(app_context, &sticker_set),
+        Err(e) => show_error(app_context, "Sticker set", e.message),
+    }
+}
+
+/// Install, remove or move a sticker set and show the sticker sets again.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `set_id` - The identifier of the sticker set.
+/// * `edit` - The change.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn edit_sticker_set(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    set_id: i64,
+    edit: StickerSetEdit,
+) -> Result<(), AppError<Action>> {
+    let result = match edit {
+        StickerSetEdit::Install => tg_backend.set_sticker_set_installed(set_id, true).await,
+        StickerSetEdit::Remove => tg_backend.set_sticker_set_installed(set_id, false).await,
+        StickerSetEdit::MoveUp | StickerSetEdit::MoveDown => {
+            match tg_backend.get_installed_sticker_sets().await {
+                Ok(sticker_sets) => {
+                    let mut set_ids = sticker_sets
+                        .iter()
+                        .map(|sticker_set| sticker_set.id)
+                        .collect::<Vec<i64>>();
+                    let index = set_ids.iter().position(|id| *id == set_id);
+                    let other = match (edit, index) {
+                        (StickerSetEdit::MoveUp, Some(i)) => i.checked_sub(1).map(|j| (i, j)),
+                        (_, Some(i)) if i + 1 < set_ids.len() => Some((i, i + 1)),
+                        _ => None,
+                    };
+                    match other {
+                        Some((i, j)) => {
+                            set_ids.swap(i, j);
+                            tg_backend.reorder_sticker_sets(set_ids).await
+                        }
+                        None => Ok(()),
+                    }
+                }
+                Err(e) => Err(e),
+            }
+        }
+    };
+    if let Err(e) = result {
+        return show_error(app_context, "Sticker set", e.message);
+    }
+    app_context.action_tx().send(Action::ShowStickerSets)?;
+    Ok(())
+}
+
+/// Get the file of the image of a sticker that can be decoded: its thumbnail
+/// if it is a still image, or else the sticker itself if it is not animated.
+///
+/// # Arguments
+/// * `sticker` - The sticker.
+///
+/// # Returns
+/// * `Option<i32>` - The id of the file, or `None` if the sticker has no
+///   still image.
+fn sticker_image_file_id(sticker: &Sticker) -> Option<i32> {
+    sticker
+        .thumbnail
+        .as_ref()
+        .filter(|thumbnail| {
+            matches!(
+                thumbnail.format,
+                ThumbnailFormat::Webp | ThumbnailFormat::Jpeg | ThumbnailFormat::Png
+            )
+        })
+        .map(|thumbnail| thumbnail.file.id)
+        .or_else(|| matches!(sticker.format, StickerFormat::Webp).then_some(sticker.sticker.id))
+}
+
+/// Get the label of a sticker in the sticker picker: its section, its emoji
+/// with its name, so that it can be searched by name, and its set.
+///
+/// # Arguments
+/// * `section` - The mark of the section of the sticker, like ⭐ for the
+///   favorite stickers, if any.
+/// * `emoji` - The emoji of the sticker.
+/// * `set_title` - The title of the set of the sticker, if it is known.
+///
+/// # Returns
+/// * `String` - The label.
+fn sticker_label(section: Option<&str>, emoji: &str, set_title: Option<&str>) -> String {
+    let mut label = section.map_or_else(String::new, |section| format!("{} ", section));
+    label.push_str(if emoji.is_empty() { "🎨" } else { emoji });
+    if let Some(emoji) = emojis::get(emoji) {
+        label.push_str(&format!(" {}", emoji.name()));
+    }
+    if let Some(set_title) = set_title {
+        label.push_str(&format!(" · {}", set_title));
+    }
+    label
+}
+
+/// Show the sticker picker, to search a sticker and send it to the open
+/// chat. The favorite stickers are shown first, then the recent stickers and
+/// the stickers of the installed sets.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_sticker_picker(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+) -> Result<(), AppError<Action>> {
+    if app_context.tg_context().open_chat_id() == 0 {
+        return show_error(
+            app_context,
+            "Stickers",
+            "Open a chat before sending a sticker.",
+        );
+    }
+    let sticker_sets = match tg_backend.get_installed_sticker_sets().await {
+        Ok(sticker_sets) => sticker_sets,
+        Err(e) => return show_error(app_context, "Stickers", e.message),
+    };
+    let set_titles: HashMap<i64, String> = sticker_sets
+        .iter()
+        .map(|sticker_set| (sticker_set.id, sticker_set.title.clone()))
+        .collect();
+    let favorites = tg_backend
+        .get_favorite_stickers()
+        .await
+        .map_err(|e| tracing::warn!("Failed to get the favorite stickers: {e:?}"))
+        .unwrap_or_default();
+    let recents = tg_backend
+        .get_recent_stickers()
+        .await
+        .map_err(|e| tracing::warn!("Failed to get the recent stickers: {e:?}"))
+        .unwrap_or_default();
+    let mut stickers: Vec<(Option<&str>, Sticker)> = favorites
+        .into_iter()
+        .map(|sticker| (Some("⭐"), sticker))
+        .chain(recents.into_iter().map(|sticker| (Some("🕘"), sticker)))
+        .collect();
+    for sticker_set in &sticker_sets {
+        match tg_backend.get_sticker_set(sticker_set.id).await {
+            Ok(sticker_set) => stickers.extend(
+                sticker_set
+                    .stickers
+                    .into_iter()
+                    .map(|sticker| (None, sticker)),
+            ),
+            Err(e) => tracing::warn!("Failed to get the sticker set {}: {e:?}", sticker_set.id),
+        }
+    }
+    if stickers.is_empty() {
+        return show_error(
+            app_context,
+            "Stickers",
+            "There are no favorite, recent or installed stickers. Add a sticker set with /stickers.",
+        );
+    }
+    let items = stickers
+        .into_iter()
+        .map(|(section, sticker)| MediaItem {
+            label: sticker_label(
+                section,
+                &sticker.emoji,
+                set_titles.get(&sticker.set_id).map(String::as_str),
+            ),
+            image_file_id: sticker_image_file_id(&sticker),
+            action: Action::SendSticker(sticker.sticker.id, sticker.emoji.clone()),
+            fallback: sticker.emoji,
+        })
+        .collect();
+    app_context
+        .action_tx()
+        .send(Action::ShowPopup(Popup::Media(
+            "Stickers".to_string(),
+            items,
+        )))?;
+    Ok(())
+}
+
+/// Send a sticker to the open chat.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+/// * `file_id` - The id of the file of the sticker.
+/// * `emoji` - The emoji of the sticker.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn send_sticker(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+    file_id: i32,
+    emoji: String,
+) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    if let Err(e) = tg_backend.send_sticker(chat_id, file_id, emoji).await {
+        return show_error(app_context, "Send sticker", e.message);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sticker_label() {
+        assert_eq!(
+            sticker_label(Some("⭐"), "😂", Some("Cats")),
+            "⭐ 😂 face with tears of joy · Cats"
+        );
+        assert_eq!(sticker_label(None, "", None), "🎨");
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::Popup,
+    handlers::show_error,
+    tg::{tg_backend::TgBackend, tg_context::MIN_CHAT_MESSAGES},
+    utils::{human_size, resident_memory},
+};
+
+/// Show the space used by the cache of the downloaded files and by the
+/// database, with the maximum size of the cache.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn show_storage(
+    app_context: &AppContext,
+    tg_backend: &TgBackend,
+) -> Result<(), AppError<Action>> {
+    let statistics = match tg_backend.get_storage_statistics().await {
+        Ok(statistics) => statistics,
+        Err(e) => return show_error(app_context, "Storage", e.message),
+    };
+    let max_cache_size = app_context.app_config().max_cache_size;
+    let limit = if max_cache_size == 0 {
+        "none, set max_cache_size in app.toml to limit it".to_string()
+    } else {
+        format!(
+            "{}, the least recently used files are deleted above it",
+            human_size(max_cache_size * 1024 * 1024)
+        )
+    };
+    let text = format!(
+        "Cache: {} in {} files\nCache limit: {}\nDatabase: {}\nLogs: {}",
+        human_size(statistics.files_size.max(0) as u64),
+        statistics.file_count,
+        limit,
+        human_size(
+            (statistics.database_size + statistics.language_pack_database_size).max(0) as u64
+        ),
+        human_size(statistics.log_size.max(0) as u64),
+    );
+    app_context
+        .action_tx()
+        .send(Action::ShowPopup(Popup::Info("Storage".to_string(), text)))?;
+    Ok(())
+}
+
+/// Show the memory used by the process and the number of messages, chats and
+/// users kept in memory.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_memory(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    let max_chat_messages = app_context.app_config().max_chat_messages;
+    let tg_context = app_context.tg_context();
+    let process = match resident_memory() {
+        Some(bytes) => human_size(bytes),
+        None => "unknown".to_string(),
+    };
+    let limit = if max_chat_messages == 0 {
+        "none, set max_chat_messages in app.toml to limit it".to_string()
+    } else {
+        format!("{} messages", max_chat_messages.max(MIN_CHAT_MESSAGES))
+    };
+    let newer = if tg_context.has_newer_messages() {
+        ", the newer ones are loaded when scrolled to"
+    } else {
+        ""
+    };
+    let text = format!(
+        "Process: {}\nOpen chat: {} messages{}\nPreview: {} messages\nLimit of a chat: {}\nChats: {}\nUsers: {}",
+        process,
+        tg_context.open_chat_messages().len(),
+        newer,
+        tg_context.preview_chat_messages().len(),
+        limit,
+        tg_context.chats().len(),
+        tg_context.users().len(),
+    );
+    app_context
+        .action_tx()
+        .send(Action::ShowPopup(Popup::Info("Memory".to_string(), text)))?;
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::{Popup, PopupItem},
+    handlers::show_error,
+    templates::{self, ChatKind},
+};
+
+/// The maximum number of characters of the text of a template shown in the
+/// template picker.
+const TEMPLATE_PREVIEW_LENGTH: usize = 60;
+
+/// Show the templates of the messages available in the open chat. The picked
+/// template is inserted in the prompt, and its variables are replaced when
+/// the message is sent.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_templates(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    let chat_id = app_context.tg_context().open_chat_id();
+    let Some(kind) = app_context
+        .tg_context()
+        .chats()
+        .get(&chat_id)
+        .map(|chat| ChatKind::from_chat_type(&chat.r#type))
+    else {
+        return show_error(
+            app_context,
+            "Templates",
+            "Open a chat to insert a template.",
+        );
+    };
+    let items: Vec<PopupItem> = app_context
+        .app_config()
+        .templates
+        .iter()
+        .filter(|entry| templates::is_available(entry, kind))
+        .map(|entry| {
+            // The preview is the start of the first line of the text.
+            let preview = entry
+                .text
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .take(TEMPLATE_PREVIEW_LENGTH)
+                .collect::<String>();
+            let ellipsis = if preview.len() < entry.text.len() {
+                "…"
+            } else {
+                ""
+            };
+            PopupItem::new(
+                format!("{}: {}{}", entry.name, preview, ellipsis),
+                Action::InsertTemplate(entry.text.clone()),
+            )
+        })
+        .collect();
+    if items.is_empty() {
+        return show_error(
+            app_context,
+            "Templates",
+            format!(
+                "No template is available in the {} chats. Add them with `templates` in app.toml.",
+                kind.name()
+            ),
+        );
+    }
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "Templates".to_string(),
+        items,
+    )))?;
+    Ok(())
+}
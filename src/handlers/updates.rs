@@ -0,0 +1,87 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    handlers::show_error,
+    update_check::{self, ReleaseInfo},
+};
+use std::sync::Arc;
+
+/// Check GitHub for a newer version of `tgt` in the background.
+/// A newer version is shown in the status bar. When the check is requested
+/// by the user, the result is shown in a popup, with the changelog of the
+/// newer version.
+///
+/// # Arguments
+/// * `app_context` - An Arc wrapped AppContext struct.
+/// * `manual` - Whether the check was requested by the user.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn check_for_updates(
+    app_context: Arc<AppContext>,
+    manual: bool,
+) -> Result<(), AppError<Action>> {
+    let available_update = app_context.available_update().clone();
+    if let (true, Some(release)) = (manual, available_update) {
+        return show_release(&app_context, &release);
+    }
+    tokio::spawn(async move {
+        let result = match update_check::check().await {
+            Ok(Some(release)) => {
+                *app_context.available_update() = Some(release.clone());
+                if manual {
+                    show_release(&app_context, &release)
+                } else {
+                    Ok(())
+                }
+            }
+            Ok(None) if manual => show_error(
+                &app_context,
+                "Updates",
+                format!(
+                    "tgt {} is the latest version.",
+                    update_check::current_version()
+                ),
+            ),
+            Ok(None) => Ok(()),
+            Err(e) => {
+                tracing::warn!("Failed to check for updates: {}", e);
+                if manual {
+                    show_error(
+                        &app_context,
+                        "Updates",
+                        format!("Failed to check for updates: {}", e),
+                    )
+                } else {
+                    Ok(())
+                }
+            }
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to show the result of the update check: {}", e);
+        }
+    });
+    Ok(())
+}
+
+/// Show a popup with the changelog of a newer release of `tgt`.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `release` - The newer release.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+fn show_release(app_context: &AppContext, release: &ReleaseInfo) -> Result<(), AppError<Action>> {
+    show_error(
+        app_context,
+        format!("tgt {} is available", release.version),
+        format!(
+            "You are using tgt {}.\n{}\n\n{}",
+            update_check::current_version(),
+            release.url,
+            release.changelog.trim()
+        ),
+    )
+}
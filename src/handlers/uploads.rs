@@ -0,0 +1,377 @@
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    app_error::AppError,
+    components::popup_window::{Popup, PopupItem},
+    handlers::show_error,
+    local_state::UploadDescriptor,
+    tg::{attachments::SelfDestruct, tg_backend::TgBackend, tg_context::UPLOAD_SIZE_LIMIT_PREMIUM},
+    utils::{human_size, shell_command, shell_quote},
+};
+use std::sync::Arc;
+use tdlib_rs::enums::{MessageContent, MessageSendingState};
+
+/// Check a file and add it to the upload queue of a chat.
+/// If the file exceeds the upload size limit, the user is offered to split
+/// it with the configured external command.
+/// The upload is started as soon as a lane of the queue is free, so that
+/// text messages are never blocked by large uploads.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `chat_id` - The id of the chat to send the file to.
+/// * `path` - The path of the file to send.
+/// * `caption` - The caption sent with the file, if any.
+/// * `self_destruct` - The self-destruction of a photo or a video, if any.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn send_file(
+    app_context: &AppContext,
+    chat_id: i64,
+    path: &str,
+    caption: Option<String>,
+    self_destruct: Option<SelfDestruct>,
+) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    if chat_id == 0 {
+        return show_error(
+            app_context,
+            "Send file",
+            "Open a chat before sending a file.",
+        );
+    }
+    let size = match std::fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() => metadata.len(),
+        _ => return show_error(app_context, "Send file", format!("{} is not a file.", path)),
+    };
+    let limit = tg_context.upload_size_limit();
+    if size > limit {
+        let title = format!(
+            "File too large: {} exceeds the {} limit",
+            human_size(size),
+            human_size(limit)
+        );
+        if app_context.app_config().file_split_command.is_empty() {
+            let mut message = format!("{} can not be uploaded.", path);
+            if !tg_context.me_is_premium() {
+                message.push_str(&format!(
+                    " Telegram Premium accounts can upload files up to {}.",
+                    human_size(UPLOAD_SIZE_LIMIT_PREMIUM)
+                ));
+            }
+            message.push_str(" Set `file_split_command` in app.toml to split large files.");
+            return show_error(app_context, title, message);
+        }
+        app_context.action_tx().send(Action::ShowPopup(Popup::List(
+            title,
+            vec![
+                PopupItem::new(
+                    "Split the file and send the parts",
+                    Action::SplitAndSendFile(path.to_string()),
+                ),
+                PopupItem::new("Cancel", Action::HidePopup),
+            ],
+        )))?;
+        return Ok(());
+    }
+    tg_context
+        .send_queue()
+        .enqueue_upload(chat_id, path.to_string(), size, caption, self_destruct);
+    app_context.action_tx().send(Action::ProcessSendQueue)?;
+    Ok(())
+}
+
+/// Start the queued uploads while a lane of the queue is free.
+/// TDLib returns the message as soon as the upload is started, so the uploads
+/// proceed concurrently and their progress is reported by the file updates.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A mutable reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn process_send_queue(
+    app_context: &AppContext,
+    tg_backend: &mut TgBackend,
+) -> Result<(), AppError<Action>> {
+    let tg_context = app_context.tg_context();
+    loop {
+        let Some(upload) = tg_context.send_queue().next_ready() else {
+            save_pending_uploads(app_context);
+            return Ok(());
+        };
+        match tg_backend
+            .send_file(
+                upload.path.clone(),
+                upload.chat_id,
+                upload.caption.clone(),
+                upload.self_destruct,
+            )
+            .await
+        {
+            Ok(message) => {
+                let file = match message.content {
+                    MessageContent::MessageDocument(content) => Some(content.document.document),
+                    MessageContent::MessageVideo(content) => Some(content.video.video),
+                    MessageContent::MessagePhoto(content) => content
+                        .photo
+                        .sizes
+                        .into_iter()
+                        .last()
+                        .map(|size| size.photo),
+                    _ => None,
+                };
+                match file {
+                    // A file already uploaded is sent without a new upload.
+                    Some(file) if !file.remote.is_uploading_completed => {
+                        tg_context
+                            .send_queue()
+                            .set_started(upload.id, message.id, Some(file.id));
+                    }
+                    _ => tg_context.send_queue().remove(upload.id),
+                }
+            }
+            Err(e) => {
+                tg_context.send_queue().remove(upload.id);
+                show_error(
+                    app_context,
+                    "Send file",
+                    format!("{}: {}", upload.path, e.message),
+                )?;
+            }
+        }
+    }
+}
+
+/// Store the uploads of the send queue in the local state, so that they can
+/// be resumed if the application is closed before they are completed.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+fn save_pending_uploads(app_context: &AppContext) {
+    let pending_uploads: Vec<UploadDescriptor> = app_context
+        .tg_context()
+        .send_queue()
+        .uploads()
+        .map(|upload| UploadDescriptor {
+            chat_id: upload.chat_id,
+            path: upload.path.clone(),
+            size: upload.size,
+            message_id: upload.message_id,
+            caption: upload.caption.clone(),
+            self_destruct: upload.self_destruct,
+        })
+        .collect();
+    {
+        let mut local_state = app_context.local_state();
+        if local_state.pending_uploads == pending_uploads {
+            return;
+        }
+        local_state.pending_uploads = pending_uploads;
+    }
+    app_context.save_local_state();
+}
+
+/// Verify the uploads that were not completed when the application was last
+/// closed and, if any, offer to resume or discard them.
+/// An upload whose message has been sent in the meantime, or is still being
+/// sent by TDLib, is not interrupted and is forgotten.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A mutable reference to the TgBackend struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn check_interrupted_uploads(
+    app_context: &AppContext,
+    tg_backend: &mut TgBackend,
+) -> Result<(), AppError<Action>> {
+    let pending_uploads = std::mem::take(&mut app_context.local_state().pending_uploads);
+    let mut interrupted = vec![];
+    for upload in pending_uploads {
+        let message = match upload.message_id {
+            Some(message_id) => tg_backend.get_message(upload.chat_id, message_id).await,
+            None => None,
+        };
+        match message.map(|message| message.sending_state) {
+            Some(None) | Some(Some(MessageSendingState::Pending(_))) => {
+                tracing::info!("Upload of {} is not interrupted", upload.path);
+            }
+            _ => interrupted.push(upload),
+        }
+    }
+    let has_interrupted = {
+        let mut local_state = app_context.local_state();
+        for upload in interrupted {
+            if !local_state.interrupted_uploads.contains(&upload) {
+                local_state.interrupted_uploads.push(upload);
+            }
+        }
+        !local_state.interrupted_uploads.is_empty()
+    };
+    app_context.save_local_state();
+    if has_interrupted {
+        app_context
+            .action_tx()
+            .send(Action::ShowInterruptedUploads)?;
+    }
+    Ok(())
+}
+
+/// Show the uploads that were interrupted, each of them can be resumed, or
+/// all of them can be resumed or discarded together.
+/// A file that no longer exists or whose size has changed is marked.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub fn show_interrupted_uploads(app_context: &AppContext) -> Result<(), AppError<Action>> {
+    let uploads = app_context.local_state().interrupted_uploads.clone();
+    if uploads.is_empty() {
+        return Ok(());
+    }
+    let tg_context = app_context.tg_context();
+    let mut items: Vec<PopupItem> = uploads
+        .iter()
+        .map(|upload| {
+            let chat = tg_context
+                .name_from_chats(upload.chat_id)
+                .unwrap_or_else(|| upload.chat_id.to_string());
+            let note = match std::fs::metadata(&upload.path) {
+                Ok(metadata) if metadata.len() == upload.size => "",
+                Ok(_) => ", changed",
+                Err(_) => ", missing",
+            };
+            PopupItem::new(
+                format!(
+                    "Resume {} ({}{}) in {}",
+                    upload.path,
+                    human_size(upload.size),
+                    note,
+                    chat
+                ),
+                Action::ResumeUploads(vec![upload.clone()]),
+            )
+        })
+        .collect();
+    if uploads.len() > 1 {
+        items.push(PopupItem::new(
+            "Resume all",
+            Action::ResumeUploads(uploads.clone()),
+        ));
+    }
+    items.push(PopupItem::new(
+        "Discard all",
+        Action::DiscardUploads(uploads),
+    ));
+    items.push(PopupItem::new("Decide later", Action::HidePopup));
+    app_context.action_tx().send(Action::ShowPopup(Popup::List(
+        "Interrupted uploads".to_string(),
+        items,
+    )))?;
+    Ok(())
+}
+
+/// Resume interrupted uploads.
+/// The message of a failed upload is deleted and the file is sent again:
+/// TDLib resumes the upload of a file from the parts already uploaded.
+/// The other interrupted uploads, if any, are shown again.
+///
+/// # Arguments
+/// * `app_context` - A reference to the AppContext struct.
+/// * `tg_backend` - A mutable reference to the TgBackend struct.
+/// * `uploads` - The uploads to resume.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+pub async fn resume_uploads(
+    app_context: &AppContext,
+    tg_backend: &mut TgBackend,
+    uploads: &[UploadDescriptor],
+) -> Result<(), AppError<Action>> {
+    let has_remaining = {
+        let mut local_state = app_context.local_state();
+        local_state
+            .interrupted_uploads
+            .retain(|upload| !uploads.contains(upload));
+        !local_state.interrupted_uploads.is_empty()
+    };
+    app_context.save_local_state();
+    for upload in uploads {
+        if let Some(message_id) = upload.message_id {
+            tg_backend
+                .delete_messages(upload.chat_id, vec![message_id], true)
+                .await;
+        }
+        send_file(
+            app_context,
+            upload.chat_id,
+            &upload.path,
+            upload.caption.clone(),
+            upload.self_destruct,
+        )?;
+    }
+    if has_remaining {
+        app_context
+            .action_tx()
+            .send(Action::ShowInterruptedUploads)?;
+    }
+    Ok(())
+}
+
+/// Split a file with the configured external command in the background and
+/// add the parts to the upload queue of the chat open when it was requested.
+/// The command must print the paths of the parts, one per line.
+///
+/// # Arguments
+/// * `app_context` - An Arc wrapped AppContext struct.
+/// * `path` - The path of the file to split.
+pub fn split_and_send_file(app_context: Arc<AppContext>, path: String) {
+    let chat_id = app_context.tg_context().open_chat_id();
+    let limit = app_context.tg_context().upload_size_limit();
+    let command_line = app_context
+        .app_config()
+        .file_split_command
+        .replace("{file}", &shell_quote(&path))
+        .replace("{limit}", &limit.to_string());
+    tracing::info!("Splitting file with: {}", command_line);
+    tokio::spawn(async move {
+        let result = match shell_command(&command_line).output().await {
+            Ok(output) if output.status.success() => {
+                let parts: Vec<String> = String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                if parts.is_empty() {
+                    show_error(
+                        &app_context,
+                        "Split file",
+                        "The split command did not print the paths of the parts.",
+                    )
+                } else {
+                    // Each part is checked against the limit before it is
+                    // queued.
+                    parts
+                        .iter()
+                        .try_for_each(|part| send_file(&app_context, chat_id, part, None, None))
+                }
+            }
+            Ok(output) => show_error(
+                &app_context,
+                "Split file",
+                String::from_utf8_lossy(&output.stderr),
+            ),
+            Err(e) => show_error(&app_context, "Split file", e.to_string()),
+        };
+        if let Err(e) = result {
+            tracing::error!("Failed to send the parts of {}: {}", path, e);
+        }
+    });
+}
@@ -0,0 +1,69 @@
+use image::DynamicImage;
+use std::collections::{HashMap, VecDeque};
+
+/// The maximum number of decoded images kept in memory. The images used
+/// least recently are dropped first, and downloaded again from the cache of
+/// TDLib when they are shown again.
+const MAX_IMAGES: usize = 64;
+/// The maximum width and height of a decoded image, in pixels. Larger images
+/// are scaled down when decoded, since they are drawn in a few cells.
+pub const MAX_IMAGE_SIZE: u32 = 480;
+
+#[derive(Debug, Clone)]
+/// `ImageState` is an enum that represents the state of an image shown
+/// inline in the chat.
+pub enum ImageState {
+    /// The image is being downloaded and decoded.
+    Loading,
+    /// The image is decoded and ready to be drawn.
+    Ready(DynamicImage),
+    /// The image can not be downloaded or decoded.
+    Failed,
+}
+
+#[derive(Debug, Default)]
+/// `ImageCache` is a struct that represents the images shown inline in the
+/// chat, by id of their Telegram file.
+pub struct ImageCache {
+    /// The states of the images.
+    images: HashMap<i32, ImageState>,
+    /// The ids of the files, from the least to the most recently used.
+    order: VecDeque<i32>,
+}
+/// Implementation of the `ImageCache` struct.
+impl ImageCache {
+    /// Get the state of an image, and mark it as the most recently used.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file of the image.
+    ///
+    /// # Returns
+    /// * `Option<&ImageState>` - The state of the image, or `None` if it has
+    ///   not been requested yet.
+    pub fn get(&mut self, file_id: i32) -> Option<&ImageState> {
+        if self.images.contains_key(&file_id) {
+            self.touch(file_id);
+        }
+        self.images.get(&file_id)
+    }
+    /// Set the state of an image, dropping the least recently used image if
+    /// the cache is full.
+    ///
+    /// # Arguments
+    /// * `file_id` - The id of the file of the image.
+    /// * `state` - The state of the image.
+    pub fn insert(&mut self, file_id: i32, state: ImageState) {
+        self.images.insert(file_id, state);
+        self.touch(file_id);
+        while self.order.len() > MAX_IMAGES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.images.remove(&oldest);
+            }
+        }
+    }
+    /// Move an image at the end of the order of use.
+    fn touch(&mut self, file_id: i32) {
+        self.order.retain(|id| *id != file_id);
+        self.order.push_back(file_id);
+    }
+}
@@ -0,0 +1,292 @@
+use crate::{action::Action, configs::custom::keymap_custom::ActionBinding, event::Event};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// The time after which an incomplete key sequence, like the first `g` of
+/// `gg` or a count, is forgotten.
+const KEY_SEQUENCE_TIMEOUT: Duration = Duration::from_secs(1);
+/// The maximum count that can be typed before a key, so that a long run of
+/// digits does not flood the actions.
+const MAX_COUNT: usize = 999;
+
+/// The outcome of a key fed to a `KeySequence`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum KeyOutcome {
+    /// The key starts or continues a sequence, more keys are expected.
+    Pending,
+    /// The sequence is complete. The action is repeated `count` times.
+    Action(Action, usize),
+    /// The key is not bound.
+    Unbound,
+}
+
+/// `KeySequence` is a struct that keeps the keys typed in a component until
+/// they are resolved to an action of the keymap, like vim does: a binding of
+/// several keys, like `gg`, and a count typed before a key, like `5j`.
+#[derive(Debug, Default)]
+pub struct KeySequence {
+    /// The count typed before the keys, if any.
+    count: Option<usize>,
+    /// The bindings that can follow the keys already typed, if a binding of
+    /// several keys has been started.
+    pending: Option<HashMap<Event, ActionBinding>>,
+    /// When the last key was typed.
+    last_key: Option<Instant>,
+}
+
+impl KeySequence {
+    /// Forget the keys typed so far.
+    pub fn reset(&mut self) {
+        self.count = None;
+        self.pending = None;
+    }
+
+    /// Feed a key to the sequence.
+    ///
+    /// # Arguments
+    /// * `event` - The key event.
+    /// * `map` - The keymap of the focused component.
+    /// * `allow_count` - Whether the digits that are not bound start a count.
+    ///
+    /// # Returns
+    /// * `KeyOutcome` - The outcome of the key.
+    pub fn feed(
+        &mut self,
+        event: &Event,
+        map: &HashMap<Event, ActionBinding>,
+        allow_count: bool,
+    ) -> KeyOutcome {
+        if self
+            .last_key
+            .is_some_and(|last_key| last_key.elapsed() > KEY_SEQUENCE_TIMEOUT)
+        {
+            self.reset();
+        }
+        self.last_key = Some(Instant::now());
+        let binding = match self.pending.take() {
+            Some(pending) => match lookup(&pending, event) {
+                Some(binding) => Some(binding.clone()),
+                // A key that does not continue the sequence starts a new one,
+                // with the count typed before the abandoned sequence.
+                None => return self.feed(event, map, allow_count),
+            },
+            None => {
+                if let Some(digit) = count_digit(event).filter(|_| allow_count) {
+                    // A leading zero or a bound digit does not start a count,
+                    // so that they can be bound, but any digit continues one.
+                    if self.count.is_some() || (digit != 0 && lookup(map, event).is_none()) {
+                        let count = self.count.unwrap_or(0) * 10 + digit;
+                        self.count = Some(count.min(MAX_COUNT));
+                        return KeyOutcome::Pending;
+                    }
+                }
+                lookup(map, event).cloned()
+            }
+        };
+        match binding {
+            Some(ActionBinding::Single { action, .. }) => {
+                let count = self.count.take().unwrap_or(1);
+                KeyOutcome::Action(action, count)
+            }
+            Some(ActionBinding::Multiple(next)) => {
+                self.pending = Some(next);
+                KeyOutcome::Pending
+            }
+            None => {
+                self.reset();
+                KeyOutcome::Unbound
+            }
+        }
+    }
+}
+
+/// Find the binding of a key. An uppercase letter is bound without the shift
+/// modifier, like `G`, but some terminals report it with the modifier.
+///
+/// # Arguments
+/// * `map` - The keymap.
+/// * `event` - The key event.
+///
+/// # Returns
+/// * `Option<&ActionBinding>` - The binding of the key, if any.
+fn lookup<'a>(map: &'a HashMap<Event, ActionBinding>, event: &Event) -> Option<&'a ActionBinding> {
+    map.get(event).or_else(|| match event {
+        Event::Key(KeyCode::Char(c), modifiers) if modifiers.contains(KeyModifiers::SHIFT) => map
+            .get(&Event::Key(
+                KeyCode::Char(*c),
+                modifiers.difference(KeyModifiers::SHIFT),
+            )),
+        _ => None,
+    })
+}
+
+/// Get the digit of a key typed without modifiers.
+///
+/// # Arguments
+/// * `event` - The key event.
+///
+/// # Returns
+/// * `Option<usize>` - The digit, if the key is a digit.
+fn count_digit(event: &Event) -> Option<usize> {
+    match event {
+        Event::Key(KeyCode::Char(c), KeyModifiers::NONE) => {
+            c.to_digit(10).map(|digit| digit as usize)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(c: char) -> Event {
+        Event::Key(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn single(action: Action) -> ActionBinding {
+        ActionBinding::Single {
+            action,
+            description: None,
+        }
+    }
+
+    /// A keymap with `j`, `0` and `gg` bound.
+    fn keymap() -> HashMap<Event, ActionBinding> {
+        HashMap::from([
+            (key('j'), single(Action::ChatListNext)),
+            (key('0'), single(Action::ChatListLast)),
+            (
+                key('g'),
+                ActionBinding::Multiple(HashMap::from([(key('g'), single(Action::ChatListFirst))])),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_single_key() {
+        let mut sequence = KeySequence::default();
+        assert_eq!(
+            sequence.feed(&key('j'), &keymap(), true),
+            KeyOutcome::Action(Action::ChatListNext, 1)
+        );
+        assert_eq!(
+            sequence.feed(&key('x'), &keymap(), true),
+            KeyOutcome::Unbound
+        );
+    }
+
+    #[test]
+    fn test_prefix_and_complete() {
+        let mut sequence = KeySequence::default();
+        assert_eq!(
+            sequence.feed(&key('g'), &keymap(), true),
+            KeyOutcome::Pending
+        );
+        assert_eq!(
+            sequence.feed(&key('g'), &keymap(), true),
+            KeyOutcome::Action(Action::ChatListFirst, 1)
+        );
+    }
+
+    #[test]
+    fn test_count() {
+        let mut sequence = KeySequence::default();
+        assert_eq!(
+            sequence.feed(&key('1'), &keymap(), true),
+            KeyOutcome::Pending
+        );
+        assert_eq!(
+            sequence.feed(&key('0'), &keymap(), true),
+            KeyOutcome::Pending
+        );
+        assert_eq!(
+            sequence.feed(&key('j'), &keymap(), true),
+            KeyOutcome::Action(Action::ChatListNext, 10)
+        );
+        // A leading zero is bound, and the digits are not a count when it is
+        // not allowed.
+        assert_eq!(
+            sequence.feed(&key('0'), &keymap(), true),
+            KeyOutcome::Action(Action::ChatListLast, 1)
+        );
+        assert_eq!(
+            sequence.feed(&key('5'), &keymap(), false),
+            KeyOutcome::Unbound
+        );
+    }
+
+    #[test]
+    fn test_mismatch_replays_the_key() {
+        let mut sequence = KeySequence::default();
+        assert_eq!(
+            sequence.feed(&key('g'), &keymap(), true),
+            KeyOutcome::Pending
+        );
+        assert_eq!(
+            sequence.feed(&key('j'), &keymap(), true),
+            KeyOutcome::Action(Action::ChatListNext, 1)
+        );
+        assert_eq!(
+            sequence.feed(&key('g'), &keymap(), true),
+            KeyOutcome::Pending
+        );
+        assert_eq!(
+            sequence.feed(&key('x'), &keymap(), true),
+            KeyOutcome::Unbound
+        );
+        assert_eq!(
+            sequence.feed(&key('g'), &keymap(), true),
+            KeyOutcome::Pending
+        );
+    }
+
+    #[test]
+    fn test_mismatch_keeps_the_count() {
+        let mut sequence = KeySequence::default();
+        assert_eq!(
+            sequence.feed(&key('3'), &keymap(), true),
+            KeyOutcome::Pending
+        );
+        assert_eq!(
+            sequence.feed(&key('g'), &keymap(), true),
+            KeyOutcome::Pending
+        );
+        assert_eq!(
+            sequence.feed(&key('j'), &keymap(), true),
+            KeyOutcome::Action(Action::ChatListNext, 3)
+        );
+        // The count is used once.
+        assert_eq!(
+            sequence.feed(&key('j'), &keymap(), true),
+            KeyOutcome::Action(Action::ChatListNext, 1)
+        );
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut sequence = KeySequence::default();
+        assert_eq!(
+            sequence.feed(&key('3'), &keymap(), true),
+            KeyOutcome::Pending
+        );
+        assert_eq!(
+            sequence.feed(&key('g'), &keymap(), true),
+            KeyOutcome::Pending
+        );
+        sequence.last_key = Instant::now().checked_sub(KEY_SEQUENCE_TIMEOUT * 2);
+        // The prefix and the count are forgotten, so `g` starts a new
+        // sequence instead of completing `gg`.
+        assert_eq!(
+            sequence.feed(&key('g'), &keymap(), true),
+            KeyOutcome::Pending
+        );
+        assert_eq!(
+            sequence.feed(&key('g'), &keymap(), true),
+            KeyOutcome::Action(Action::ChatListFirst, 1)
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{tg::attachments::SelfDestruct, utils::tgt_data_dir};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::PathBuf};
+
+/// The name of the file where the local state is stored.
+pub const LOCAL_STATE_FILENAME: &str = "local_state.toml";
+/// The maximum number of recently closed chats that are remembered.
+pub const MAX_RECENT_CHATS: usize = 20;
+/// The number of recently closed chats shown in the popup.
+pub const RECENT_CHATS_POPUP_SIZE: usize = 10;
+/// The maximum number of recently used emojis that are remembered.
+pub const MAX_RECENT_EMOJIS: usize = 32;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+/// `UploadDescriptor` is a struct that represents a file upload that is
+/// stored on disk, so that it can be resumed if the application is closed
+/// before the upload is completed.
+pub struct UploadDescriptor {
+    /// The id of the chat the file is sent to.
+    pub chat_id: i64,
+    /// The path of the file.
+    pub path: String,
+    /// The size of the file in bytes when the upload was requested.
+    pub size: u64,
+    /// The temporary id of the message, if the upload was started.
+    pub message_id: Option<i64>,
+    /// The caption sent with the file, if any.
+    #[serde(default)]
+    pub caption: Option<String>,
+    /// The self-destruction of the photo or the video, if any.
+    #[serde(default)]
+    pub self_destruct: Option<SelfDestruct>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+/// `ChatNote` is a struct that represents a note written about a chat, like
+/// an order number or a to-do. It is local to `tgt` and never sent.
+pub struct ChatNote {
+    /// The id of the chat.
+    pub chat_id: i64,
+    /// The text of the note.
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+/// `ContactNote` is a struct that represents a personal note written about a
+/// contact, like how you met. It is local to `tgt` and never sent.
+pub struct ContactNote {
+    /// The id of the user.
+    pub user_id: i64,
+    /// The text of the note.
+    pub text: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+/// `ChatLogPosition` is a struct that represents the last message of a chat
+/// appended to its log, so that the messages received while `tgt` is closed
+/// are appended at the next start.
+pub struct ChatLogPosition {
+    /// The id of the chat.
+    pub chat_id: i64,
+    /// The id of the last message appended to the log.
+    pub message_id: i64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+/// `LocalState` is a struct that represents the state of the application that
+/// is stored on disk and restored at the next start.
+/// It is independent of the TDLib database and contains only data that is
+/// local to `tgt`.
+pub struct LocalState {
+    /// The ids of the chats that were recently closed or switched away from.
+    /// The last element is the most recent one.
+    pub recent_chats: Vec<i64>,
+    /// The uploads in progress or queued, updated while files are sent.
+    pub pending_uploads: Vec<UploadDescriptor>,
+    /// The uploads that were not completed when the application was closed,
+    /// waiting for the user to resume or discard them.
+    pub interrupted_uploads: Vec<UploadDescriptor>,
+    /// The ids of the chats marked as favorite, in the order they were added.
+    /// They are local to `tgt` and independent of the pinned chats.
+    pub favorite_chats: Vec<i64>,
+    /// The emojis recently picked in the emoji picker, the most recent first.
+    pub recent_emojis: Vec<String>,
+    /// The notes written about the chats, shown above their messages.
+    pub chat_notes: Vec<ChatNote>,
+    /// The personal notes written about the contacts, shown in the info of
+    /// their chats.
+    pub contact_notes: Vec<ContactNote>,
+    /// The last messages appended to the logs of the chats.
+    pub chat_log_positions: Vec<ChatLogPosition>,
+    /// The size of the chat list, in percent of the width, last set by
+    /// dragging its border or with the key bindings.
+    pub chat_list_size: Option<u16>,
+    /// The name of the account used last time, among the accounts of
+    /// `telegram.toml`.
+    pub active_account: Option<String>,
+}
+/// Implementation of the `LocalState` struct.
+impl LocalState {
+    /// Get the path of the local state file.
+    ///
+    /// # Returns
+    /// * `io::Result<PathBuf>` - The path of the local state file.
+    pub fn path() -> io::Result<PathBuf> {
+        Ok(tgt_data_dir()?.join(LOCAL_STATE_FILENAME))
+    }
+    /// Load the local state from disk.
+    /// If the file does not exist or can not be parsed, the default local state
+    /// is returned.
+    ///
+    /// # Returns
+    /// * `Self` - The local state.
+    pub fn load() -> Self {
+        let content = match Self::path().and_then(fs::read_to_string) {
+            Ok(content) => content,
+            Err(_) => return Self::default(),
+        };
+        toml::from_str(&content).unwrap_or_else(|e| {
+            tracing::error!("Failed to parse the local state: {}", e);
+            Self::default()
+        })
+    }
+    /// Save the local state to disk.
+    ///
+    /// # Returns
+    /// * `io::Result<()>` - An Ok result or an error.
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, content)
+    }
+    /// Push a chat on top of the recently closed chats.
+    /// If the chat is already present, it is moved on top.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    pub fn push_recent_chat(&mut self, chat_id: i64) {
+        self.recent_chats.retain(|id| *id != chat_id);
+        self.recent_chats.push(chat_id);
+        if self.recent_chats.len() > MAX_RECENT_CHATS {
+            self.recent_chats.remove(0);
+        }
+    }
+    /// Remove a chat from the recently closed chats.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    pub fn remove_recent_chat(&mut self, chat_id: i64) {
+        self.recent_chats.retain(|id| *id != chat_id);
+    }
+    /// Pop the most recently closed chat.
+    ///
+    /// # Returns
+    /// * `Option<i64>` - The id of the chat, if any.
+    pub fn pop_recent_chat(&mut self) -> Option<i64> {
+        self.recent_chats.pop()
+    }
+    /// Get the most recently closed chats, the most recent first.
+    ///
+    /// # Arguments
+    /// * `limit` - The maximum number of chats to return.
+    ///
+    /// # Returns
+    /// * `Vec<i64>` - The ids of the chats.
+    pub fn recent_chats(&self, limit: usize) -> Vec<i64> {
+        self.recent_chats
+            .iter()
+            .rev()
+            .take(limit)
+            .copied()
+            .collect()
+    }
+    /// Add a chat to the favorites or remove it, if it is already a
+    /// favorite.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the chat is now a favorite.
+    pub fn toggle_favorite_chat(&mut self, chat_id: i64) -> bool {
+        if self.is_favorite_chat(chat_id) {
+            self.favorite_chats.retain(|id| *id != chat_id);
+            false
+        } else {
+            self.favorite_chats.push(chat_id);
+            true
+        }
+    }
+    /// Push an emoji on top of the recently used emojis.
+    /// If the emoji is already present, it is moved on top.
+    ///
+    /// # Arguments
+    /// * `emoji` - The emoji.
+    pub fn push_recent_emoji(&mut self, emoji: &str) {
+        self.recent_emojis.retain(|e| e != emoji);
+        self.recent_emojis.insert(0, emoji.to_string());
+        self.recent_emojis.truncate(MAX_RECENT_EMOJIS);
+    }
+    /// Check whether a chat is a favorite.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the chat is a favorite.
+    pub fn is_favorite_chat(&self, chat_id: i64) -> bool {
+        self.favorite_chats.contains(&chat_id)
+    }
+    /// Get the note of a chat.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    ///
+    /// # Returns
+    /// * `Option<&str>` - The note of the chat, if any.
+    pub fn chat_note(&self, chat_id: i64) -> Option<&str> {
+        self.chat_notes
+            .iter()
+            .find(|note| note.chat_id == chat_id)
+            .map(|note| note.text.as_str())
+    }
+    /// Set the note of a chat, replacing the previous one.
+    /// An empty note removes it.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    /// * `text` - The text of the note.
+    pub fn set_chat_note(&mut self, chat_id: i64, text: &str) {
+        self.chat_notes.retain(|note| note.chat_id != chat_id);
+        let text = text.trim();
+        if !text.is_empty() {
+            self.chat_notes.push(ChatNote {
+                chat_id,
+                text: text.to_string(),
+            });
+        }
+    }
+    /// Get the personal note of a contact.
+    ///
+    /// # Arguments
+    /// * `user_id` - The id of the user.
+    ///
+    /// # Returns
+    /// * `Option<&str>` - The note of the contact, if any.
+    pub fn contact_note(&self, user_id: i64) -> Option<&str> {
+        self.contact_notes
+            .iter()
+            .find(|note| note.user_id == user_id)
+            .map(|note| note.text.as_str())
+    }
+    /// Set the personal note of a contact, replacing the previous one.
+    /// An empty note removes it.
+    ///
+    /// # Arguments
+    /// * `user_id` - The id of the user.
+    /// * `text` - The text of the note.
+    pub fn set_contact_note(&mut self, user_id: i64, text: &str) {
+        self.contact_notes.retain(|note| note.user_id != user_id);
+        let text = text.trim();
+        if !text.is_empty() {
+            self.contact_notes.push(ContactNote {
+                user_id,
+                text: text.to_string(),
+            });
+        }
+    }
+    /// Get the id of the last message appended to the log of a chat.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    ///
+    /// # Returns
+    /// * `Option<i64>` - The id of the message, or `None` if nothing has been
+    ///   appended yet.
+    pub fn chat_log_position(&self, chat_id: i64) -> Option<i64> {
+        self.chat_log_positions
+            .iter()
+            .find(|position| position.chat_id == chat_id)
+            .map(|position| position.message_id)
+    }
+    /// Set the id of the last message appended to the log of a chat.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    /// * `message_id` - The id of the message.
+    pub fn set_chat_log_position(&mut self, chat_id: i64, message_id: i64) {
+        match self
+            .chat_log_positions
+            .iter_mut()
+            .find(|position| position.chat_id == chat_id)
+        {
+            Some(position) => position.message_id = message_id,
+            None => self.chat_log_positions.push(ChatLogPosition {
+                chat_id,
+                message_id,
+            }),
+        }
+    }
+}
@@ -1,17 +1,38 @@
 pub mod action;
 pub mod app_context;
 pub mod app_error;
+pub mod audio_player;
+pub mod backup;
+pub mod bidi;
+pub mod chat_list_rules;
+pub mod chat_log;
 pub mod cli;
+pub mod color_scheme;
+pub mod commands;
 pub mod component_name;
+pub mod emoji;
 pub mod event;
+pub mod image_cache;
+pub mod key_sequence;
+pub mod local_state;
 pub mod logger;
+pub mod markdown;
+pub mod speech;
+pub mod templates;
+pub mod text_diff;
+pub mod text_width;
+pub mod title_template;
 pub mod tui;
 pub mod tui_backend;
+pub mod update_check;
+pub mod url_display;
+pub mod url_preview;
 pub mod utils;
 
 // Folders
 pub mod components;
 pub mod configs;
+pub mod handlers;
 pub mod run;
 pub mod tg;
 
@@ -24,8 +45,9 @@ use crate::configs::{
         palette_custom::PaletteConfig, theme_custom::ThemeConfig,
     },
 };
+use crate::local_state::LocalState;
 use crate::logger::Logger;
-use crate::tg::{tg_backend::TgBackend, tg_context::TgContext};
+use crate::tg::{accounts::AccountManager, tg_backend::TgBackend, tg_context::TgContext};
 use crate::tui::Tui;
 use crate::tui_backend::TuiBackend;
 use clap::Parser;
@@ -51,6 +73,11 @@ lazy_static! {
 async fn tokio_main() -> Result<(), AppError<()>> {
     tracing::info!("Starting tokio main");
 
+    // The CLI arguments are parsed first, since the safe mode changes how the
+    // configuration files are read.
+    let cli_args = cli::CliArgs::parse();
+    configs::config_file::set_safe_mode(cli_args.safe_mode());
+
     // Initialize the lazy static variables
     // This is done to ensure that the configuration files are read only once
     // and the values are shared across the application.
@@ -61,12 +88,24 @@ async fn tokio_main() -> Result<(), AppError<()>> {
     lazy_static::initialize(&THEME_CONFIG);
     lazy_static::initialize(&TELEGRAM_CONFIG);
 
-    let cli_args = cli::CliArgs::parse();
-    tracing::info!("Parsed CLI arguments: {:?}", cli_args);
-
-    let logger = Logger::from_config(LOGGER_CONFIG.clone());
+    let mut logger_config = LOGGER_CONFIG.clone();
+    if cli_args.safe_mode() {
+        logger_config.log_level = "trace".to_string();
+    }
+    let logger = Logger::from_config(logger_config);
     logger.init();
     tracing::info!("Logger initialized with config: {:?}", logger);
+    tracing::info!("Parsed CLI arguments: {:?}", cli_args);
+
+    // Back up or restore the local data without starting TDLib.
+    let local_data_cli = cli_args.local_data_cli();
+    if local_data_cli.backup().is_some() || local_data_cli.restore().is_some() {
+        std::process::exit(backup::run_cli(
+            local_data_cli,
+            &TELEGRAM_CONFIG,
+            &LOGGER_CONFIG,
+        ));
+    }
 
     let keymap_config = KEYMAP_CONFIG.clone();
     tracing::info!("Keymap config: {:?}", keymap_config);
@@ -82,13 +121,28 @@ async fn tokio_main() -> Result<(), AppError<()>> {
 
     let mut telegram_config = TELEGRAM_CONFIG.clone();
     tracing::info!("Telegram config: {:?}", telegram_config);
-    // This is used to disable the message database when running the application as a CLI.
+    // This is used to disable the message database when running a one-shot
+    // command, like `--send-message`.
     // This is done to avoid that deleting a message other application in
     // a chats causes the `--send-message` to resend the messages that were deleted.
-    telegram_config.use_message_database = std::env::args().count() <= 1;
+    if cli_args.telegram_cli().is_one_shot() {
+        telegram_config.use_message_database = false;
+    }
 
     let tg_context = TgContext::default();
     tracing::info!("Telegram context: {:?}", tg_context);
+
+    let local_state = LocalState::load();
+    tracing::info!("Local state: {:?}", local_state);
+    // The database of the active account is used, if accounts are configured.
+    let account_manager = AccountManager::new(
+        telegram_config.accounts.clone(),
+        &telegram_config.database_dir,
+        local_state.active_account.as_deref(),
+    );
+    telegram_config.database_dir = account_manager.database_dir();
+    std::fs::create_dir_all(&telegram_config.database_dir)?;
+    tracing::info!("Account manager: {:?}", account_manager);
     let app_context = Arc::new(AppContext::new(
         app_config,
         keymap_config,
@@ -97,6 +151,8 @@ async fn tokio_main() -> Result<(), AppError<()>> {
         telegram_config,
         tg_context,
         cli_args,
+        local_state,
+        account_manager,
     )?);
     tracing::info!("App context: {:?}", app_context);
 
@@ -116,6 +172,10 @@ async fn tokio_main() -> Result<(), AppError<()>> {
     )
     .await
     {
+        Ok(_) if app_context.restart_acquire() => {
+            tracing::info!("Restarting the application");
+            restart();
+        }
         Ok(_) => {
             tracing::info!("Application exited successfully");
             std::process::exit(0);
@@ -127,6 +187,30 @@ async fn tokio_main() -> Result<(), AppError<()>> {
     }
 }
 
+/// Start the application again with the same arguments, replacing the
+/// current process where possible, like after switching the account.
+fn restart() -> ! {
+    let mut command = std::process::Command::new(
+        std::env::current_exe().expect("Failed to get the path of the executable"),
+    );
+    command.args(std::env::args_os().skip(1));
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        let error = command.exec();
+        tracing::error!("Failed to restart the application: {}", error);
+        std::process::exit(1);
+    }
+    #[cfg(not(unix))]
+    match command.status() {
+        Ok(status) => std::process::exit(status.code().unwrap_or(0)),
+        Err(error) => {
+            tracing::error!("Failed to restart the application: {}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
 /// Initialize the panic hook to exit the `TuiBackend` and log the panic stack
 /// backtrace.
 ///
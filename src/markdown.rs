@@ -0,0 +1,276 @@
+use tdlib_rs::{
+    enums::TextEntityType,
+    types::{FormattedText, TextEntity, TextEntityTypePreCode, TextEntityTypeTextUrl},
+};
+
+/// The markers written around a text to format it, like `**bold**`.
+const MARKERS: [&str; 4] = ["**", "__", "~~", "||"];
+/// The characters that are written literally when escaped with a backslash,
+/// like `\*`.
+const ESCAPED: &str = "\\`*_~|[]()";
+
+/// Get the type of the entity of a marker.
+///
+/// # Arguments
+/// * `marker` - The marker, one of `MARKERS`.
+///
+/// # Returns
+/// * `TextEntityType` - The type of the entity of the text it wraps.
+fn marker_type(marker: &str) -> TextEntityType {
+    match marker {
+        "**" => TextEntityType::Bold,
+        "__" => TextEntityType::Italic,
+        "~~" => TextEntityType::Strikethrough,
+        _ => TextEntityType::Spoiler,
+    }
+}
+
+/// Check whether some characters start with a text.
+///
+/// # Arguments
+/// * `chars` - The characters.
+/// * `text` - The text.
+///
+/// # Returns
+/// * `bool` - Whether the characters start with the text.
+fn starts_with(chars: &[char], text: &str) -> bool {
+    let mut chars = chars.iter();
+    text.chars().all(|c| chars.next() == Some(&c))
+}
+
+/// Find a text in some characters.
+///
+/// # Arguments
+/// * `chars` - The characters.
+/// * `text` - The text.
+///
+/// # Returns
+/// * `Option<usize>` - The index of the first character of the first
+///   occurrence of the text, if any.
+fn find(chars: &[char], text: &str) -> Option<usize> {
+    (0..chars.len()).find(|i| starts_with(&chars[*i..], text))
+}
+
+/// Find the bounds of a link like `[text](url)` at the start of some
+/// characters.
+///
+/// # Arguments
+/// * `chars` - The characters, starting with `[`.
+///
+/// # Returns
+/// * `Option<(usize, usize)>` - The indexes of the `]` closing the text and
+///   of the `)` closing the URL, if the characters start with a link.
+fn link_bounds(chars: &[char]) -> Option<(usize, usize)> {
+    let text_end = chars.iter().position(|c| *c == ']' || *c == '\n')?;
+    if chars[text_end] != ']' || text_end == 1 || chars.get(text_end + 1) != Some(&'(') {
+        return None;
+    }
+    let url_end = text_end
+        + 2
+        + chars[text_end + 2..]
+            .iter()
+            .position(|c| *c == ')' || c.is_whitespace())?;
+    (chars[url_end] == ')' && url_end > text_end + 2).then_some((text_end, url_end))
+}
+
+#[derive(Debug, Default)]
+/// `Parser` is a struct that represents the text and the entities of a
+/// message parsed so far.
+struct Parser {
+    /// The text, without the markers.
+    text: String,
+    /// The entities of the text.
+    entities: Vec<TextEntity>,
+    /// The length of the text, in UTF-16 code units like the offsets of the
+    /// entities.
+    offset: i32,
+}
+/// Implementation of the `Parser` struct.
+impl Parser {
+    /// Add a character to the text.
+    fn push(&mut self, c: char) {
+        self.text.push(c);
+        self.offset += c.len_utf16() as i32;
+    }
+    /// Add an entity from an offset to the end of the text, if it is not
+    /// empty.
+    fn add_entity(&mut self, offset: i32, r#type: TextEntityType) {
+        if self.offset > offset {
+            self.entities.push(TextEntity {
+                offset,
+                length: self.offset - offset,
+                r#type,
+            });
+        }
+    }
+    /// Parse some characters and add them to the text.
+    /// A marker is opened only if it is closed later, so that a lone `**` is
+    /// kept as it is.
+    ///
+    /// # Arguments
+    /// * `chars` - The characters.
+    fn parse(&mut self, chars: &[char]) {
+        // The markers opened and not closed yet, with the offsets they were
+        // opened at.
+        let mut open: Vec<(&str, i32)> = vec![];
+        let mut i = 0;
+        while i < chars.len() {
+            let rest = &chars[i..];
+            if rest[0] == '\\' && rest.get(1).is_some_and(|c| ESCAPED.contains(*c)) {
+                self.push(rest[1]);
+                i += 2;
+                continue;
+            }
+            if starts_with(rest, "```") {
+                if let Some(end) = find(&rest[3..], "```") {
+                    let content = rest[3..3 + end].iter().collect::<String>();
+                    // The first line is the language of the code, if it is
+                    // a single word.
+                    let (language, code) = match content.split_once('\n') {
+                        Some((language, code))
+                            if !language.is_empty() && !language.contains(char::is_whitespace) =>
+                        {
+                            (Some(language.to_string()), code.to_string())
+                        }
+                        _ => (None, content.trim_start_matches('\n').to_string()),
+                    };
+                    let offset = self.offset;
+                    code.chars().for_each(|c| self.push(c));
+                    self.add_entity(
+                        offset,
+                        match language {
+                            Some(language) => {
+                                TextEntityType::PreCode(TextEntityTypePreCode { language })
+                            }
+                            None => TextEntityType::Pre,
+                        },
+                    );
+                    i += end + 6;
+                    continue;
+                }
+            }
+            if rest[0] == '`' {
+                if let Some(end) = rest[1..].iter().position(|c| *c == '`') {
+                    let offset = self.offset;
+                    rest[1..1 + end].iter().for_each(|c| self.push(*c));
+                    self.add_entity(offset, TextEntityType::Code);
+                    i += end + 2;
+                    continue;
+                }
+            }
+            if rest[0] == '[' {
+                if let Some((text_end, url_end)) = link_bounds(rest) {
+                    let offset = self.offset;
+                    self.parse(&rest[1..text_end]);
+                    let url = rest[text_end + 2..url_end].iter().collect::<String>();
+                    self.add_entity(
+                        offset,
+                        TextEntityType::TextUrl(TextEntityTypeTextUrl { url }),
+                    );
+                    i += url_end + 1;
+                    continue;
+                }
+            }
+            if let Some(marker) = MARKERS.iter().find(|marker| starts_with(rest, marker)) {
+                if let Some(j) = open.iter().rposition(|(open, _)| open == marker) {
+                    let (_, offset) = open.remove(j);
+                    self.add_entity(offset, marker_type(marker));
+                    i += marker.len();
+                    continue;
+                }
+                if find(&rest[marker.len()..], marker).is_some() {
+                    open.push((*marker, self.offset));
+                    i += marker.len();
+                    continue;
+                }
+            }
+            self.push(rest[0]);
+            i += 1;
+        }
+    }
+}
+
+/// Parse a message written with Markdown, like `**bold**`, `__italic__`,
+/// `~~strikethrough~~`, `||spoiler||`, `` `code` ``, ```` ```pre``` ```` and
+/// `[text](url)`, into a formatted text. A character is written literally
+/// when escaped with a backslash, like `\*`.
+///
+/// # Arguments
+/// * `text` - The message.
+///
+/// # Returns
+/// * `FormattedText` - The text without the markers and its entities,
+///   sorted by offset.
+pub fn parse(text: &str) -> FormattedText {
+    let mut parser = Parser::default();
+    parser.parse(&text.chars().collect::<Vec<char>>());
+    // The outer entities come before the ones they contain.
+    parser
+        .entities
+        .sort_by_key(|entity| (entity.offset, -entity.length));
+    FormattedText {
+        text: parser.text,
+        entities: parser.entities,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(offset: i32, length: i32, r#type: TextEntityType) -> TextEntity {
+        TextEntity {
+            offset,
+            length,
+            r#type,
+        }
+    }
+
+    #[test]
+    fn test_parse_markers() {
+        let formatted = parse("**bold __both__** and ||🐱 spoiler||");
+        assert_eq!(formatted.text, "bold both and 🐱 spoiler");
+        assert_eq!(
+            formatted.entities,
+            vec![
+                entity(0, 9, TextEntityType::Bold),
+                entity(5, 4, TextEntityType::Italic),
+                // The cat is two UTF-16 code units.
+                entity(14, 10, TextEntityType::Spoiler),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_code_and_links() {
+        let formatted = parse("`a**b` [site](https://example.com)\n```rust\nfn main() {}```");
+        assert_eq!(formatted.text, "a**b site\nfn main() {}");
+        assert_eq!(
+            formatted.entities,
+            vec![
+                entity(0, 4, TextEntityType::Code),
+                entity(
+                    5,
+                    4,
+                    TextEntityType::TextUrl(TextEntityTypeTextUrl {
+                        url: "https://example.com".to_string()
+                    })
+                ),
+                entity(
+                    10,
+                    12,
+                    TextEntityType::PreCode(TextEntityTypePreCode {
+                        language: "rust".to_string()
+                    })
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_literal_text() {
+        let formatted = parse("2 ** 3, \\*\\*not bold\\*\\* and [no link]");
+        assert_eq!(formatted.text, "2 ** 3, **not bold** and [no link]");
+        assert!(formatted.entities.is_empty());
+    }
+}
@@ -1,14 +1,75 @@
-use crate::component_name::ComponentName::Prompt;
 use crate::{
-    action::Action, app_context::AppContext, app_error::AppError,
-    configs::custom::keymap_custom::ActionBinding, event::Event, tg::tg_backend::TgBackend,
-    tui::Tui, tui_backend::TuiBackend,
+    action::{Action, ChatFolderEdit},
+    app_context::AppContext,
+    app_error::AppError,
+    audio_player::AudioPlayer,
+    color_scheme,
+    component_name::ComponentName::Prompt,
+    components::popup_window::Popup,
+    configs::custom::keymap_custom::ActionBinding,
+    event::Event,
+    handlers::{
+        accounts::{show_accounts, switch_account},
+        animations::{search_animations, send_animation, show_animation_picker},
+        attachments::{
+            record_voice_note, send_attachments, show_attachments, show_self_destruct,
+            stage_attachment, stage_clipboard_image,
+        },
+        audio::{play_audio_track, queue_audio, speak_next, start_audio},
+        blocklist::{export_blocklist, import_blocklist, show_blocklist},
+        chat_logs::{log_message, sync_chat_logs},
+        chats::{
+            batch_chats, create_new_chat, delete_chat, jump_to_date, load_draft_reply, open_chat,
+            open_comments, open_message, open_next_favorite_chat, refresh_chat, set_chat_mute_for,
+            set_chat_pinned, set_message_sender, set_new_chat_title, show_chat_deletion,
+            show_chat_info, show_message_senders, show_new_chat, show_new_chat_members,
+            show_recent_chats,
+        },
+        commands::run_command,
+        contacts::{
+            add_contact, delete_contact, edit_contact_note, load_contacts, open_contact_chat,
+        },
+        folders::{create_chat_folder, edit_chat_folder, show_chat_folder, show_chat_folders},
+        media::{fetch_url_preview, load_image, open_media, open_url, show_open_media},
+        members::{edit_chat_member, load_members, show_chat_member, show_member_profile},
+        messages::{
+            dismiss_mention, edit_caption, forward_messages, load_pinned_message, moderate_message,
+            pin_message, press_inline_button, search_chat_messages, search_messages, send_reaction,
+            show_mentions, show_message_deletion, show_message_details, show_moderation,
+            show_pin_message, show_reactions, unpin_message,
+        },
+        notifications::run_notification_command,
+        profile::{
+            check_recovery_email_code, check_username, edit_cloud_password, set_username,
+            show_cloud_password, show_emoji_statuses, show_my_profile,
+        },
+        profile_viewer::show_profile,
+        settings::{apply_color_scheme, show_config_diagnostics},
+        show_chat_picker, show_error,
+        stickers::{
+            add_sticker_set, edit_sticker_set, send_sticker, show_sticker_picker, show_sticker_set,
+            show_sticker_set_preview, show_sticker_sets,
+        },
+        storage::{show_memory, show_storage},
+        templates::show_templates,
+        updates::check_for_updates,
+        uploads::{
+            check_interrupted_uploads, process_send_queue, resume_uploads, send_file,
+            show_interrupted_uploads, split_and_send_file,
+        },
+    },
+    tg::{presence::PresenceChange, td_enums::TdTextQuote, tg_backend::TgBackend},
+    tui::Tui,
+    tui_backend::TuiBackend,
 };
 use ratatui::layout::Rect;
 use std::{collections::HashMap, io, sync::Arc, time::Instant};
 use tdlib_rs::enums::ChatList;
 use tokio::sync::mpsc::UnboundedSender;
 
+/// The number of messages shown in the preview of a chat.
+const CHAT_PREVIEW_SIZE: usize = 20;
+
 /// Run the main event loop for the application.
 /// This function will process events and actions for the tui and the backend.
 ///
@@ -51,10 +112,26 @@ pub async fn run_app(
     }
 
     tg_backend.online().await;
-    tg_backend.disable_animated_emoji(true).await;
+    // Single-emoji messages are received as animated emoji, so that they are
+    // shown as large emoji.
+    tg_backend.disable_animated_emoji(false).await;
+    tg_backend.start_cache_eviction();
+    // The terminal is asked for its background before its events are read.
+    if color_scheme::is_enabled(&app_context.app_config()) {
+        if let Some(scheme) = color_scheme::detect().await {
+            apply_color_scheme(&app_context, scheme);
+        }
+        color_scheme::watch(Arc::clone(&app_context));
+    }
 
     tui_backend.enter()?;
     tui.register_action_handler(app_context.action_tx().clone())?;
+    check_interrupted_uploads(&app_context, tg_backend).await?;
+    show_config_diagnostics(&app_context, false)?;
+    if app_context.app_config().check_for_updates {
+        check_for_updates(Arc::clone(&app_context), false)?;
+    }
+    sync_chat_logs(&app_context, tg_backend).await;
 
     // Main loop
     while tg_backend.have_authorization {
@@ -71,6 +148,7 @@ pub async fn run_app(
 
     Ok(())
 }
+
 /// Handle incoming events from the Telegram backend and produce actions if
 /// necessary.
 ///
@@ -104,6 +182,12 @@ async fn handle_tg_backend_events(
             Event::GetChatHistory => {
                 app_context.action_tx().send(Action::GetChatHistory)?;
             }
+            Event::GetNewerChatHistory => {
+                app_context.action_tx().send(Action::GetNewerChatHistory)?;
+            }
+            Event::GetLatestChatHistory => {
+                app_context.action_tx().send(Action::GetLatestChatHistory)?;
+            }
             Event::DeleteMessages(message_ids, revoke) => {
                 app_context
                     .action_tx()
@@ -120,18 +204,65 @@ async fn handle_tg_backend_events(
                     .action_tx()
                     .send(Action::EditMessage(message_id, message))?;
             }
-            Event::ReplyMessage(message_id, message) => {
+            Event::EditCaption(message_id) => {
+                edit_caption(&app_context, tg_backend, message_id).await?;
+            }
+            Event::ReplyMessage(message_id, message, quote) => {
                 app_context
                     .action_tx()
                     .send(Action::FocusComponent(Prompt))?;
 
                 app_context
                     .action_tx()
-                    .send(Action::ReplyMessage(message_id, message))?;
+                    .send(Action::ReplyMessage(message_id, message, quote))?;
             }
             Event::ViewAllMessages => {
                 app_context.action_tx().send(Action::ViewAllMessages)?;
             }
+            Event::ProcessSendQueue => {
+                app_context.action_tx().send(Action::ProcessSendQueue)?;
+            }
+            Event::UploadFailed(upload) => {
+                let is_new = {
+                    let mut local_state = app_context.local_state();
+                    let is_new = !local_state.interrupted_uploads.contains(&upload);
+                    if is_new {
+                        local_state.interrupted_uploads.push(upload);
+                    }
+                    is_new
+                };
+                if is_new {
+                    app_context.save_local_state();
+                    app_context
+                        .action_tx()
+                        .send(Action::ShowInterruptedUploads)?;
+                }
+            }
+            Event::NewMessage(chat_id, sender_id, text) => {
+                run_notification_command(&app_context, chat_id, sender_id, &text);
+            }
+            Event::LogMessage(chat_id, message_id) => {
+                log_message(&app_context, tg_backend, chat_id, message_id).await;
+            }
+            Event::UpdateChatAction(chat_id, user_id, typing) => {
+                app_context
+                    .tg_context()
+                    .typing_users()
+                    .set_typing(chat_id, user_id, typing);
+            }
+            Event::Reconnected(chat_id, last_message_id) => {
+                tg_backend.reconcile_offline_gap(chat_id, last_message_id);
+            }
+            Event::ChatDraftChanged(chat_id) => {
+                app_context
+                    .action_tx()
+                    .send(Action::ChatDraftChanged(chat_id))?;
+            }
+            Event::PinnedMessageChanged(chat_id)
+                if chat_id == app_context.tg_context().open_chat_id() =>
+            {
+                app_context.action_tx().send(Action::LoadPinnedMessage)?;
+            }
             _ => {}
         }
     }
@@ -156,11 +287,21 @@ async fn handle_tui_backend_events(
 ) -> Result<(), AppError<Action>> {
     if let Some(event) = tui_backend.next().await {
         match event {
-            Event::Render => app_context.action_tx().send(Action::Render)?,
+            Event::Render => {
+                let idle = app_context
+                    .tg_context()
+                    .presence()
+                    .check_idle(Instant::now());
+                if idle == Some(PresenceChange::Away) {
+                    app_context.action_tx().send(Action::SetOnline(false))?;
+                }
+                app_context.action_tx().send(Action::Render)?
+            }
             Event::Resize(width, height) => app_context
                 .action_tx()
                 .send(Action::Resize(width, height))?,
             Event::Key(key, modifiers) => {
+                record_activity(&app_context)?;
                 app_context
                     .action_tx()
                     .send(Action::from_key_event(key, modifiers))?;
@@ -193,7 +334,10 @@ async fn handle_tui_backend_events(
             }
             Event::FocusLost => app_context.action_tx().send(Action::FocusLost)?,
             Event::FocusGained => app_context.action_tx().send(Action::FocusGained)?,
-            Event::Paste(ref text) => app_context.action_tx().send(Action::Paste(text.clone()))?,
+            Event::Paste(ref text) => {
+                record_activity(&app_context)?;
+                app_context.action_tx().send(Action::Paste(text.clone()))?
+            }
             _ => {}
         }
 
@@ -205,6 +349,26 @@ async fn handle_tui_backend_events(
     }
     Ok(())
 }
+
+/// Record an input of the user in the terminal, so that they are online
+/// again if they were away.
+///
+/// # Arguments
+/// * `app_context` - An Arc wrapped AppContext struct.
+///
+/// # Returns
+/// * `Result<(), AppError>` - An Ok result or an error.
+fn record_activity(app_context: &Arc<AppContext>) -> Result<(), AppError<Action>> {
+    let change = app_context
+        .tg_context()
+        .presence()
+        .record_activity(Instant::now());
+    if change == Some(PresenceChange::Online) {
+        app_context.action_tx().send(Action::SetOnline(true))?;
+    }
+    Ok(())
+}
+
 /// Consume events until a single action is produced.
 /// This function is used to consume events until a single action is produced
 /// from a map of events to actions.
@@ -234,6 +398,7 @@ async fn consume_until_single_action(
         }
     }
 }
+
 #[allow(clippy::await_holding_lock)]
 /// Handle incoming actions from the application.
 ///
@@ -268,7 +433,15 @@ pub async fn handle_app_actions(
             }
             Action::FocusLost => tui_backend.suspend()?,
             Action::FocusGained => tui_backend.resume()?,
+            Action::SetOnline(online) => {
+                if online {
+                    tg_backend.online().await;
+                } else {
+                    tg_backend.offline().await;
+                }
+            }
             Action::Quit => {
+                app_context.audio_player().clear();
                 app_context.quit_store(true);
             }
             Action::LoadChats(chat_list, limit) => {
@@ -289,10 +462,21 @@ pub async fn handle_app_actions(
                     .await;
             }
             Action::GetChatHistory => {
+                tg_backend.get_chat_history(app_context.tg_context().open_chat_id());
+            }
+            Action::GetNewerChatHistory => {
                 tg_backend
-                    .get_chat_history(app_context.tg_context().open_chat_id())
+                    .get_newer_chat_history(app_context.tg_context().open_chat_id())
                     .await;
             }
+            Action::GetLatestChatHistory => {
+                let tg_context = app_context.tg_context();
+                if tg_context.has_newer_messages() {
+                    tg_context.clear_open_chat_messages();
+                    tg_context.set_from_message_id(0);
+                    tg_backend.get_chat_history(tg_context.open_chat_id());
+                }
+            }
             Action::DeleteMessages(ref message_ids, revoke) => {
                 tg_backend
                     .delete_messages(
@@ -301,15 +485,539 @@ pub async fn handle_app_actions(
                         revoke,
                     )
                     .await;
+                for message_id in message_ids {
+                    app_context.tg_context().delete_message(*message_id);
+                }
             }
-            Action::ReplyMessage(message_id, ref message) => {
+            Action::ReplyMessage(message_id, ref message, _) => {
                 app_context
                     .tg_context()
                     .set_reply_message(message_id, message.to_string());
             }
+            // An empty quote falls through, so that the action still
+            // reaches the components.
+            Action::QuoteReplyMessage(message_id, position, ref quote)
+                if !quote.trim().is_empty() =>
+            {
+                // The reply bar is shown once the prompt is focused, so that
+                // the selected message is not replied as a whole.
+                app_context
+                    .action_tx()
+                    .send(Action::FocusComponent(Prompt))?;
+                app_context.action_tx().send(Action::ShowChatWindowReply)?;
+                app_context.action_tx().send(Action::ReplyMessage(
+                    message_id,
+                    format!("❝{}❞", quote),
+                    Some(TdTextQuote {
+                        text: quote.clone(),
+                        position,
+                    }),
+                ))?;
+            }
             Action::ViewAllMessages => {
                 tg_backend.view_all_messages().await;
             }
+            Action::ViewMessages(ref message_ids) => {
+                tg_backend
+                    .view_messages(
+                        app_context.tg_context().open_chat_id(),
+                        message_ids.to_vec(),
+                    )
+                    .await;
+            }
+            Action::OpenChat(chat_id) => {
+                open_chat(&app_context, chat_id)?;
+            }
+            Action::PreviewChat(chat_id) => {
+                let tg_context = app_context.tg_context();
+                // The selection may have moved while the action was queued.
+                if tg_context.preview_chat_id() == chat_id {
+                    let messages = tg_backend
+                        .get_last_messages(chat_id, CHAT_PREVIEW_SIZE)
+                        .await;
+                    if tg_context.preview_chat_id() == chat_id {
+                        *tg_context.preview_chat_messages() = messages;
+                    }
+                }
+            }
+            Action::BatchChats(ref chat_ids, operation) => {
+                batch_chats(&app_context, tg_backend, chat_ids, operation).await?;
+            }
+            Action::ShowEmojiPicker => {
+                app_context
+                    .action_tx()
+                    .send(Action::ShowPopup(Popup::Emoji("Emoji".to_string())))?;
+            }
+            Action::ShowTemplates => {
+                show_templates(&app_context)?;
+            }
+            Action::InsertTemplate(_) => {
+                app_context
+                    .action_tx()
+                    .send(Action::FocusComponent(Prompt))?;
+            }
+            Action::PickEmoji(ref emoji) => {
+                app_context.local_state().push_recent_emoji(emoji);
+                app_context.save_local_state();
+                app_context
+                    .action_tx()
+                    .send(Action::FocusComponent(Prompt))?;
+                app_context
+                    .action_tx()
+                    .send(Action::InsertText(emoji.clone()))?;
+            }
+            Action::ShowStickerSets => {
+                show_sticker_sets(&app_context, tg_backend).await?;
+            }
+            Action::ShowStickerSet(set_id) => match tg_backend.get_sticker_set(set_id).await {
+                Ok(sticker_set) => show_sticker_set(&app_context, &sticker_set)?,
+                Err(e) => show_error(&app_context, "Sticker set", e.message)?,
+            },
+            Action::ShowStickerSetPreview(set_id) => {
+                show_sticker_set_preview(&app_context, tg_backend, set_id).await?;
+            }
+            Action::AddStickerSet(ref link) => {
+                add_sticker_set(&app_context, tg_backend, link).await?;
+            }
+            Action::EditStickerSet(set_id, edit) => {
+                edit_sticker_set(&app_context, tg_backend, set_id, edit).await?;
+            }
+            Action::ShowStickerPicker => {
+                show_sticker_picker(&app_context, tg_backend).await?;
+            }
+            Action::SendSticker(file_id, ref emoji) => {
+                send_sticker(&app_context, tg_backend, file_id, emoji.clone()).await?;
+            }
+            Action::ShowAnimationPicker => {
+                show_animation_picker(&app_context, tg_backend).await?;
+            }
+            Action::SearchAnimations(ref query) => {
+                search_animations(&app_context, tg_backend, query).await?;
+            }
+            Action::SendAnimation(file_id) => {
+                send_animation(&app_context, tg_backend, file_id).await?;
+            }
+            Action::ShowChatFolders => {
+                show_chat_folders(&app_context)?;
+            }
+            Action::ShowChatDeletion(chat_id) => {
+                show_chat_deletion(&app_context, chat_id)?;
+            }
+            Action::DeleteChat(chat_id, deletion) => {
+                delete_chat(&app_context, tg_backend, chat_id, deletion).await?;
+            }
+            Action::SetChatPinned(chat_id, chat_list, is_pinned) => {
+                set_chat_pinned(&app_context, tg_backend, chat_id, chat_list, is_pinned).await?;
+            }
+            Action::SetChatMuteFor(chat_id, mute_for) => {
+                set_chat_mute_for(&app_context, tg_backend, chat_id, mute_for).await?;
+            }
+            Action::ShowBlocklist => {
+                show_blocklist(&app_context)?;
+            }
+            Action::ExportBlocklist(ref path) => {
+                export_blocklist(&app_context, tg_backend, path).await?;
+            }
+            Action::ImportBlocklist(ref path) => {
+                import_blocklist(&app_context, tg_backend, path)?;
+            }
+            Action::ShowMemory => {
+                show_memory(&app_context)?;
+            }
+            Action::ShowStorage => {
+                show_storage(&app_context, tg_backend).await?;
+            }
+            Action::ColorSchemeChanged(scheme) => {
+                apply_color_scheme(&app_context, scheme);
+            }
+            Action::SetChatNote(chat_id, ref text) => {
+                app_context.local_state().set_chat_note(chat_id, text);
+                app_context.save_local_state();
+            }
+            Action::EditContactNote => {
+                edit_contact_note(&app_context)?;
+            }
+            Action::SetContactNote(user_id, ref text) => {
+                app_context.local_state().set_contact_note(user_id, text);
+                app_context.save_local_state();
+            }
+            Action::ShowChatFolder(chat_folder_id) => {
+                show_chat_folder(&app_context, tg_backend, chat_folder_id).await?;
+            }
+            Action::ShowChatFolderPicker(chat_folder_id, included) => {
+                let title = if included {
+                    "Include a chat"
+                } else {
+                    "Exclude a chat"
+                };
+                show_chat_picker(&app_context, title, |chat_id| {
+                    let edit = if included {
+                        ChatFolderEdit::IncludeChat(chat_id)
+                    } else {
+                        ChatFolderEdit::ExcludeChat(chat_id)
+                    };
+                    Action::EditChatFolder(chat_folder_id, edit)
+                })?;
+            }
+            Action::CreateChatFolder(ref name, chat_id) => {
+                create_chat_folder(&app_context, tg_backend, name, chat_id).await?;
+            }
+            Action::EditChatFolder(chat_folder_id, ref edit) => {
+                edit_chat_folder(&app_context, tg_backend, chat_folder_id, edit).await?;
+            }
+            Action::ShowMessageDetails(message_id) => {
+                show_message_details(&app_context, tg_backend, message_id).await?;
+            }
+            Action::ShowModeration(message_id) => {
+                show_moderation(&app_context, tg_backend, message_id).await?;
+            }
+            Action::ForwardMessages(ref message_ids) => {
+                let from_chat_id = app_context.tg_context().open_chat_id();
+                show_chat_picker(&app_context, "Forward to", |chat_id| {
+                    Action::SendForwardedMessages(chat_id, from_chat_id, message_ids.clone())
+                })?;
+            }
+            Action::ShowReactions(message_id) => {
+                show_reactions(&app_context, message_id)?;
+            }
+            Action::ShowOpenMedia(message_id) => {
+                show_open_media(&app_context, tg_backend, message_id).await?;
+            }
+            Action::OpenMedia(message_id) => {
+                open_media(Arc::clone(&app_context), tg_backend, message_id).await?;
+            }
+            Action::SendReaction(message_id, ref emoji) => {
+                send_reaction(&app_context, tg_backend, message_id, emoji).await?;
+            }
+            Action::ShowMessageDeletion(ref message_ids, revoke) => {
+                show_message_deletion(&app_context, tg_backend, message_ids, revoke).await?;
+            }
+            Action::SendForwardedMessages(chat_id, from_chat_id, ref message_ids) => {
+                forward_messages(
+                    &app_context,
+                    tg_backend,
+                    chat_id,
+                    from_chat_id,
+                    message_ids.clone(),
+                )
+                .await?;
+            }
+            Action::ModerateMessage(message_id, moderation) => {
+                moderate_message(&app_context, tg_backend, message_id, moderation).await?;
+            }
+            Action::ShowProfile(id) => {
+                show_profile(&app_context, tg_backend, id).await?;
+            }
+            Action::ShowPinMessage(message_id) => {
+                show_pin_message(&app_context, tg_backend, message_id).await?;
+            }
+            Action::PinMessage(message_id, notify) => {
+                pin_message(&app_context, tg_backend, message_id, notify).await?;
+            }
+            Action::UnpinMessage(message_id) => {
+                unpin_message(&app_context, tg_backend, message_id).await?;
+            }
+            Action::LoadPinnedMessage => {
+                load_pinned_message(&app_context, tg_backend).await;
+            }
+            Action::OpenMessage(chat_id, message_id) => {
+                open_message(&app_context, tg_backend, chat_id, message_id).await?;
+            }
+            Action::SearchMessages(ref query) => {
+                search_messages(&app_context, tg_backend, query).await?;
+            }
+            Action::ShowMentions => {
+                show_mentions(&app_context, tg_backend).await?;
+            }
+            Action::DismissMention(chat_id, message_id) => {
+                dismiss_mention(&app_context, tg_backend, chat_id, message_id).await;
+            }
+            Action::ShowContacts => {
+                load_contacts(&app_context, tg_backend).await?;
+            }
+            Action::AddContact(ref text) => {
+                add_contact(&app_context, tg_backend, text).await?;
+            }
+            Action::DeleteContact(user_id) => {
+                delete_contact(&app_context, tg_backend, user_id).await?;
+            }
+            Action::OpenContactChat(user_id) => {
+                open_contact_chat(&app_context, tg_backend, user_id).await?;
+            }
+            Action::ShowMembers => {
+                load_members(&app_context, tg_backend).await?;
+            }
+            Action::RefreshChat => {
+                refresh_chat(&app_context, tg_backend).await?;
+            }
+            Action::ShowChatMember(user_id) => {
+                show_chat_member(&app_context, user_id)?;
+            }
+            Action::ShowMemberProfile(user_id) => {
+                show_member_profile(&app_context, tg_backend, user_id).await?;
+            }
+            Action::EditChatMember(user_id, edit) => {
+                edit_chat_member(&app_context, tg_backend, user_id, edit).await?;
+            }
+            Action::ShowNewChat => {
+                show_new_chat(&app_context)?;
+            }
+            Action::SetNewChatTitle(kind, ref title) => {
+                set_new_chat_title(&app_context, kind, title)?;
+            }
+            Action::ShowNewChatMembers(ref new_chat) => {
+                show_new_chat_members(&app_context, tg_backend, new_chat).await?;
+            }
+            Action::CreateNewChat(ref new_chat) => {
+                create_new_chat(&app_context, tg_backend, new_chat).await?;
+            }
+            Action::JumpToDate(ref date) => {
+                jump_to_date(&app_context, tg_backend, date).await?;
+            }
+            Action::OpenComments(message_id) => {
+                open_comments(&app_context, tg_backend, message_id).await?;
+            }
+            Action::SearchChatMessages(ref query) => {
+                search_chat_messages(&app_context, tg_backend, query).await?;
+            }
+            Action::PressInlineButton(message_id, row, column) => {
+                press_inline_button(&app_context, tg_backend, message_id, row, column).await?;
+            }
+            Action::OpenUrl(ref url) => {
+                open_url(Arc::clone(&app_context), url.clone())?;
+            }
+            Action::SaveChatDraft(chat_id, ref text, ref reply_to) => {
+                if let Err(e) = tg_backend
+                    .set_chat_draft(chat_id, text.clone(), reply_to.clone())
+                    .await
+                {
+                    tracing::error!("Failed to save the draft of {}: {}", chat_id, e.message);
+                }
+            }
+            Action::SendTypingAction(chat_id) => {
+                if let Err(e) = tg_backend.send_typing_action(chat_id).await {
+                    tracing::warn!("Failed to send the typing action: {}", e.message);
+                }
+            }
+            Action::LoadDraftReply(chat_id, message_id) => {
+                load_draft_reply(&app_context, tg_backend, chat_id, message_id).await?;
+            }
+            Action::NextFavoriteChat => {
+                open_next_favorite_chat(&app_context)?;
+            }
+            Action::ReopenLastChat => {
+                let chat_id = app_context.local_state().pop_recent_chat();
+                if let Some(chat_id) = chat_id {
+                    app_context.action_tx().send(Action::OpenChat(chat_id))?;
+                }
+            }
+            Action::ShowRecentChats => {
+                show_recent_chats(&app_context)?;
+            }
+            Action::ShowSendFile => {
+                app_context
+                    .action_tx()
+                    .send(Action::ShowPopup(Popup::Files(
+                        "File to send".to_string(),
+                        Box::new(Action::SendFile(String::new(), None)),
+                    )))?;
+            }
+            Action::SendFile(ref path, ref caption) => {
+                send_file(
+                    &app_context,
+                    app_context.tg_context().open_chat_id(),
+                    path,
+                    caption.clone(),
+                    None,
+                )?;
+            }
+            Action::SplitAndSendFile(ref path) => {
+                split_and_send_file(Arc::clone(&app_context), path.clone());
+            }
+            Action::ShowAttachments => {
+                show_attachments(&app_context)?;
+            }
+            Action::StageAttachment(ref path) => {
+                stage_attachment(&app_context, path)?;
+            }
+            Action::StageClipboardImage => {
+                stage_clipboard_image(&app_context)?;
+            }
+            Action::RecordVoiceNote => {
+                record_voice_note(Arc::clone(&app_context))?;
+            }
+            Action::UnstageAttachment(index) => {
+                app_context.tg_context().attachments().remove(index);
+            }
+            Action::ClearAttachments => {
+                app_context.tg_context().attachments().take();
+            }
+            Action::ShowSelfDestruct => {
+                show_self_destruct(&app_context)?;
+            }
+            Action::SetSelfDestruct(self_destruct) => {
+                app_context
+                    .tg_context()
+                    .attachments()
+                    .set_self_destruct(self_destruct);
+            }
+            Action::SendAttachments(ref caption) => {
+                send_attachments(&app_context, caption)?;
+            }
+            Action::ProcessSendQueue => {
+                process_send_queue(&app_context, tg_backend).await?;
+            }
+            Action::ShowMessageSenders => {
+                show_message_senders(&app_context, tg_backend).await?;
+            }
+            Action::SetMessageSender(chat_id, sender) => {
+                set_message_sender(&app_context, tg_backend, chat_id, sender).await?;
+            }
+            Action::ShowAccounts => {
+                show_accounts(&app_context)?;
+            }
+            Action::SwitchAccount(ref name) => {
+                switch_account(&app_context, name.clone())?;
+            }
+            Action::ShowChatInfo => {
+                show_chat_info(&app_context, tg_backend).await?;
+            }
+            Action::ShowConfigDiagnostics => {
+                show_config_diagnostics(&app_context, true)?;
+            }
+            Action::CheckForUpdates => {
+                check_for_updates(Arc::clone(&app_context), true)?;
+            }
+            Action::ShowInterruptedUploads => {
+                show_interrupted_uploads(&app_context)?;
+            }
+            Action::ResumeUploads(ref uploads) => {
+                resume_uploads(&app_context, tg_backend, uploads).await?;
+            }
+            Action::DiscardUploads(ref uploads) => {
+                app_context
+                    .local_state()
+                    .interrupted_uploads
+                    .retain(|upload| !uploads.contains(upload));
+                app_context.save_local_state();
+            }
+            Action::ShowMyProfile => {
+                show_my_profile(&app_context, tg_backend).await?;
+            }
+            Action::SetUsername(ref username) => {
+                set_username(&app_context, tg_backend, username).await?;
+            }
+            Action::CheckUsername(ref text) => {
+                check_username(&app_context, tg_backend, text).await?;
+            }
+            Action::SetBio(ref bio) => {
+                if let Err(e) = tg_backend.set_bio(bio.clone()).await {
+                    show_error(&app_context, "Bio", e.message)?;
+                } else {
+                    app_context.action_tx().send(Action::ShowMyProfile)?;
+                }
+            }
+            Action::ShowEmojiStatuses => {
+                show_emoji_statuses(&app_context, tg_backend).await?;
+            }
+            Action::SetEmojiStatus(custom_emoji_id) => {
+                if let Err(e) = tg_backend.set_emoji_status(custom_emoji_id).await {
+                    show_error(&app_context, "Emoji status", e.message)?;
+                } else {
+                    app_context.action_tx().send(Action::ShowMyProfile)?;
+                }
+            }
+            Action::RunCommand(ref name, ref args) => {
+                run_command(&app_context, name, args).await?;
+            }
+            Action::QueueAudio(ref track) => {
+                queue_audio(Arc::clone(&app_context), tg_backend, track.clone())?;
+            }
+            Action::AudioNext => {
+                play_audio_track(
+                    Arc::clone(&app_context),
+                    tg_backend,
+                    AudioPlayer::next_track,
+                )?;
+            }
+            Action::AudioPrevious => {
+                play_audio_track(
+                    Arc::clone(&app_context),
+                    tg_backend,
+                    AudioPlayer::previous_track,
+                )?;
+            }
+            Action::AudioStop => {
+                app_context.audio_player().clear();
+            }
+            Action::AudioTogglePause => {
+                let resumed = {
+                    let mut audio_player = app_context.audio_player();
+                    if audio_player.pause() {
+                        None
+                    } else {
+                        audio_player.resume()
+                    }
+                };
+                if let Some((generation, path)) = resumed {
+                    start_audio(&app_context, generation, &path)?;
+                }
+            }
+            Action::AudioSeekForward | Action::AudioSeekBackward => {
+                let forward = matches!(action, Action::AudioSeekForward);
+                let restarted = app_context.audio_player().seek(forward);
+                if let Some((generation, path)) = restarted {
+                    start_audio(&app_context, generation, &path)?;
+                }
+            }
+            Action::Speak(ref text) => {
+                let start = app_context.speaker().enqueue(text.clone());
+                if start {
+                    speak_next(Arc::clone(&app_context))?;
+                }
+            }
+            Action::SpeechStop => {
+                app_context.speaker().stop();
+            }
+            Action::SpeechFinished(generation) => {
+                let is_current = app_context.speaker().is_current_generation(generation);
+                if is_current {
+                    speak_next(Arc::clone(&app_context))?;
+                }
+            }
+            Action::AudioTrackFinished(generation) => {
+                let is_current = app_context.audio_player().is_current_generation(generation);
+                if is_current {
+                    play_audio_track(
+                        Arc::clone(&app_context),
+                        tg_backend,
+                        AudioPlayer::next_track,
+                    )?;
+                }
+            }
+            Action::FetchUrlPreview(ref url) => {
+                fetch_url_preview(Arc::clone(&app_context), url.clone());
+            }
+            Action::LoadImage(file_id) => {
+                load_image(Arc::clone(&app_context), tg_backend, file_id);
+            }
+            Action::ToggleOnlineVisibility => {
+                let visible = tg_backend.is_online_visible().await;
+                if let Err(e) = tg_backend.set_online_visible(!visible).await {
+                    show_error(&app_context, "Online visibility", e.message)?;
+                } else {
+                    app_context.action_tx().send(Action::ShowMyProfile)?;
+                }
+            }
+            Action::ShowCloudPassword => {
+                show_cloud_password(&app_context, tg_backend).await?;
+            }
+            Action::EditCloudPassword(edit, ref fields, ref text) => {
+                edit_cloud_password(&app_context, tg_backend, edit, fields, text).await?;
+            }
+            Action::CheckRecoveryEmailCode(ref code) => {
+                check_recovery_email_code(&app_context, tg_backend, code).await?;
+            }
             _ => {}
         }
 
@@ -0,0 +1,118 @@
+use crate::utils::{expand_shell_placeholders, shell_command};
+use std::collections::VecDeque;
+use tokio::sync::oneshot;
+
+/// The placeholder replaced by the text to speak in the command of the
+/// speech synthesizer.
+pub const TEXT_PLACEHOLDER: &str = "{text}";
+
+#[derive(Debug, Default)]
+/// `Speaker` is a struct that represents a queue of texts read aloud one
+/// after the other with an external speech synthesizer, like `espeak-ng` or
+/// `say`.
+pub struct Speaker {
+    /// The texts waiting to be read, in order.
+    queue: VecDeque<String>,
+    /// Indicates whether a text is being read.
+    speaking: bool,
+    /// The generation of the speech, increased every time a text is started
+    /// or the speech is stopped, so that the end of an old text is ignored.
+    generation: u64,
+    /// The channel used to stop the command of the text being read.
+    stop_tx: Option<oneshot::Sender<()>>,
+}
+/// Implementation of the `Speaker` struct.
+impl Speaker {
+    /// Add a text at the end of the queue.
+    ///
+    /// # Arguments
+    /// * `text` - The text to read.
+    ///
+    /// # Returns
+    /// * `bool` - Whether nothing is being read, so that the text must be
+    ///   started.
+    pub fn enqueue(&mut self, text: String) -> bool {
+        self.queue.push_back(text);
+        !self.speaking
+    }
+    /// Take the next text of the queue and mark it as being read.
+    ///
+    /// # Returns
+    /// * `Option<(String, u64)>` - The text and the generation of its
+    ///   speech, or `None` if the queue is empty.
+    pub fn next_text(&mut self) -> Option<(String, u64)> {
+        self.stop_tx = None;
+        self.generation += 1;
+        self.speaking = false;
+        let text = self.queue.pop_front()?;
+        self.speaking = true;
+        Some((text, self.generation))
+    }
+    /// Stop the text being read, if any, and empty the queue.
+    /// The end of the texts started before is ignored from now on.
+    pub fn stop(&mut self) {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        self.queue.clear();
+        self.speaking = false;
+        self.generation += 1;
+    }
+    /// Start reading a text with the command of the speech synthesizer.
+    ///
+    /// # Arguments
+    /// * `generation` - The generation returned by `next_text`.
+    /// * `command_line` - The command of the synthesizer, with `{text}`.
+    /// * `text` - The text to read.
+    /// * `on_end` - The function called with the generation of the speech
+    ///   when the command exits by itself.
+    ///
+    /// # Returns
+    /// * `std::io::Result<()>` - An Ok result or an error if the command can
+    ///   not be started.
+    pub fn speak(
+        &mut self,
+        generation: u64,
+        command_line: &str,
+        text: &str,
+        on_end: impl FnOnce(u64) + Send + 'static,
+    ) -> std::io::Result<()> {
+        if !self.is_current_generation(generation) {
+            return Ok(());
+        }
+        let command_line = expand_shell_placeholders(command_line, &[(TEXT_PLACEHOLDER, text)]);
+        let mut child = shell_command(&command_line)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .spawn()?;
+        let (stop_tx, stop_rx) = oneshot::channel();
+        self.stop_tx = Some(stop_tx);
+        tokio::spawn(async move {
+            tokio::select! {
+                status = child.wait() => {
+                    tracing::info!("Speech synthesizer exited with {:?}", status);
+                    on_end(generation);
+                }
+                _ = stop_rx => {
+                    if let Err(e) = child.kill().await {
+                        tracing::warn!("Failed to stop the speech synthesizer: {}", e);
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+    /// Check whether a speech is still the current one, so that its end must
+    /// start the next text.
+    ///
+    /// # Arguments
+    /// * `generation` - The generation of the speech.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the speech is still the current one.
+    pub fn is_current_generation(&self, generation: u64) -> bool {
+        self.generation == generation
+    }
+}
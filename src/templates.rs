@@ -0,0 +1,165 @@
+use crate::{app_context::AppContext, configs::raw::app_raw::TemplateEntry};
+use tdlib_rs::{enums::ChatType, types::User};
+
+/// The variables that can be used in the text of a template.
+pub const VARIABLES: [&str; 6] = [
+    "{first_name}",
+    "{last_name}",
+    "{full_name}",
+    "{username}",
+    "{chat_title}",
+    "{my_first_name}",
+];
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `ChatKind` is an enum that represents the kinds of chats a template can be
+/// limited to.
+pub enum ChatKind {
+    /// A private chat with a user.
+    Private,
+    /// A basic group or a supergroup that is not a channel.
+    Group,
+    /// A channel.
+    Channel,
+    /// A secret chat with a user.
+    Secret,
+}
+impl ChatKind {
+    /// Get the kind of a chat from its type.
+    ///
+    /// # Arguments
+    /// * `chat_type` - The type of the chat.
+    ///
+    /// # Returns
+    /// * `Self` - The kind of the chat.
+    pub fn from_chat_type(chat_type: &ChatType) -> Self {
+        match chat_type {
+            ChatType::Private(_) => Self::Private,
+            ChatType::BasicGroup(_) => Self::Group,
+            ChatType::Supergroup(supergroup) if supergroup.is_channel => Self::Channel,
+            ChatType::Supergroup(_) => Self::Group,
+            ChatType::Secret(_) => Self::Secret,
+        }
+    }
+    /// Get the name of the kind, as written in `chat_types` in the
+    /// configuration.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Private => "private",
+            Self::Group => "group",
+            Self::Channel => "channel",
+            Self::Secret => "secret",
+        }
+    }
+}
+
+/// Check whether a template is available in a kind of chat.
+///
+/// # Arguments
+/// * `entry` - The template.
+/// * `kind` - The kind of the chat.
+///
+/// # Returns
+/// * `bool` - Whether the template has no `chat_types` or they include the
+///   kind of the chat.
+pub fn is_available(entry: &TemplateEntry, kind: ChatKind) -> bool {
+    match &entry.chat_types {
+        Some(chat_types) => chat_types
+            .iter()
+            .any(|chat_type| chat_type.trim().eq_ignore_ascii_case(kind.name())),
+        None => true,
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// `TemplateVariables` is a struct that represents the values of the
+/// variables of the templates in a chat.
+pub struct TemplateVariables {
+    /// The first name of the peer.
+    pub first_name: String,
+    /// The last name of the peer.
+    pub last_name: String,
+    /// The username of the peer, with the leading `@`, if any.
+    pub username: String,
+    /// The title of the chat.
+    pub chat_title: String,
+    /// The first name of the current user.
+    pub my_first_name: String,
+}
+/// Implementation of the `TemplateVariables` struct.
+impl TemplateVariables {
+    /// Get the values of the variables in a chat.
+    /// The peer is the sender of the replied message, if any, and otherwise
+    /// the user of a private or a secret chat. The variables of the peer are
+    /// empty in the groups and the channels when no message is replied.
+    ///
+    /// # Arguments
+    /// * `app_context` - The application context.
+    /// * `chat_id` - The id of the chat.
+    /// * `reply_sender_id` - The id of the sender of the replied message.
+    ///
+    /// # Returns
+    /// * `Self` - The values of the variables.
+    pub fn of_chat(app_context: &AppContext, chat_id: i64, reply_sender_id: Option<i64>) -> Self {
+        let tg_context = app_context.tg_context();
+        let (chat_title, peer_id) = match tg_context.chats().get(&chat_id) {
+            Some(chat) => (
+                chat.title.clone(),
+                match &chat.r#type {
+                    ChatType::Private(private) => Some(private.user_id),
+                    ChatType::Secret(secret) => Some(secret.user_id),
+                    _ => None,
+                },
+            ),
+            None => (String::new(), None),
+        };
+        let users = tg_context.users();
+        let peer = reply_sender_id
+            .or(peer_id)
+            .and_then(|user_id| users.get(&user_id));
+        let username = |user: &User| {
+            user.usernames
+                .as_ref()
+                .and_then(|usernames| usernames.active_usernames.first())
+                .map(|username| format!("@{}", username))
+                .unwrap_or_default()
+        };
+        Self {
+            first_name: peer.map(|user| user.first_name.clone()).unwrap_or_default(),
+            last_name: peer.map(|user| user.last_name.clone()).unwrap_or_default(),
+            username: peer.map(username).unwrap_or_default(),
+            chat_title,
+            my_first_name: users
+                .get(&tg_context.me())
+                .map(|user| user.first_name.clone())
+                .unwrap_or_default(),
+        }
+    }
+    /// Replace the variables of a text with their values.
+    /// The other text between braces is kept as it is.
+    ///
+    /// # Arguments
+    /// * `text` - The text of the template.
+    ///
+    /// # Returns
+    /// * `String` - The expanded text.
+    pub fn expand(&self, text: &str) -> String {
+        let full_name = format!("{} {}", self.first_name, self.last_name)
+            .trim()
+            .to_string();
+        let values = [
+            &self.first_name,
+            &self.last_name,
+            &full_name,
+            &self.username,
+            &self.chat_title,
+            &self.my_first_name,
+        ];
+        VARIABLES
+            .iter()
+            .zip(values)
+            .fold(text.to_string(), |text, (variable, value)| {
+                text.replace(variable, value)
+            })
+    }
+}
@@ -0,0 +1,100 @@
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// `DiffLine` is an enum that represents a line of the difference between two
+/// texts.
+pub enum DiffLine {
+    /// A line of both texts.
+    Same(String),
+    /// A line of the first text only.
+    Removed(String),
+    /// A line of the second text only.
+    Added(String),
+}
+
+impl DiffLine {
+    /// Get the line prefixed with `  `, `- ` or `+ `, like in a unified diff.
+    ///
+    /// # Returns
+    /// * `String` - The prefixed line.
+    pub fn to_prefixed(&self) -> String {
+        match self {
+            DiffLine::Same(line) => format!("  {}", line),
+            DiffLine::Removed(line) => format!("- {}", line),
+            DiffLine::Added(line) => format!("+ {}", line),
+        }
+    }
+}
+
+/// Compute the difference between two texts, line by line, keeping the
+/// longest common subsequence of lines. The removed lines come before the
+/// added ones where both change.
+///
+/// # Arguments
+/// * `old` - The first text.
+/// * `new` - The second text.
+///
+/// # Returns
+/// * `Vec<DiffLine>` - The lines of the difference, in order.
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    // `common[i][j]` is the length of the longest common subsequence of the
+    // lines of `old` from `i` and the lines of `new` from `j`.
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut diff = vec![];
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff_keeps_the_common_lines() {
+        let diff = line_diff("hello\nsee you at 5\nbye", "hello\nsee you at 6\nbye");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("hello".to_string()),
+                DiffLine::Removed("see you at 5".to_string()),
+                DiffLine::Added("see you at 6".to_string()),
+                DiffLine::Same("bye".to_string()),
+            ]
+        );
+        assert_eq!(diff[1].to_prefixed(), "- see you at 5");
+    }
+
+    #[test]
+    fn test_line_diff_of_an_empty_text() {
+        assert_eq!(
+            line_diff("", "new"),
+            vec![DiffLine::Added("new".to_string())]
+        );
+        assert_eq!(
+            line_diff("same", "same"),
+            vec![DiffLine::Same("same".to_string())]
+        );
+    }
+}
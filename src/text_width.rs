@@ -0,0 +1,144 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
+/// The variation selector that asks for the emoji presentation of the
+/// previous character, which is then two columns wide.
+const EMOJI_PRESENTATION_SELECTOR: char = '\u{FE0F}';
+/// The range of the regional indicators, whose pairs are rendered as flags.
+const REGIONAL_INDICATORS: std::ops::RangeInclusive<char> = '\u{1F1E6}'..='\u{1F1FF}';
+/// The character appended to a truncated text.
+const ELLIPSIS: &str = "…";
+
+/// Get the number of terminal columns used by a grapheme cluster, that is a
+/// character as perceived by the user.
+/// A cluster is rendered as a single glyph, so combining marks do not add
+/// columns and the emoji joined with a zero width joiner, like a family, use
+/// the two columns of a single emoji.
+///
+/// # Arguments
+/// * `grapheme` - The grapheme cluster.
+///
+/// # Returns
+/// * `usize` - The number of columns.
+pub fn grapheme_width(grapheme: &str) -> usize {
+    let mut chars = grapheme.chars();
+    let Some(first) = chars.next() else {
+        return 0;
+    };
+    if REGIONAL_INDICATORS.contains(&first) || grapheme.contains(EMOJI_PRESENTATION_SELECTOR) {
+        return 2;
+    }
+    std::iter::once(first)
+        .chain(chars)
+        .filter_map(|c| c.width())
+        .find(|width| *width > 0)
+        .unwrap_or(0)
+}
+
+/// Get the number of terminal columns used by a text.
+///
+/// # Arguments
+/// * `text` - The text, without newlines.
+///
+/// # Returns
+/// * `usize` - The number of columns.
+pub fn display_width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+/// Truncate a text so that it fits in a number of terminal columns.
+/// The text is cut between two grapheme clusters and ends with an ellipsis
+/// when it is truncated.
+///
+/// # Arguments
+/// * `text` - The text, without newlines.
+/// * `max_width` - The maximum number of columns.
+///
+/// # Returns
+/// * `String` - The text that fits in `max_width` columns.
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if display_width(text) <= max_width {
+        return text.to_string();
+    }
+    let max_width = max_width.saturating_sub(display_width(ELLIPSIS));
+    let mut width = 0;
+    let mut truncated = String::new();
+    for grapheme in text.graphemes(true) {
+        width += grapheme_width(grapheme);
+        if width > max_width {
+            break;
+        }
+        truncated.push_str(grapheme);
+    }
+    truncated.push_str(ELLIPSIS);
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{display_width, grapheme_width, truncate_to_width};
+
+    #[test]
+    fn test_ascii_width() {
+        assert_eq!(display_width("hello"), 5);
+        assert_eq!(display_width(""), 0);
+    }
+
+    #[test]
+    fn test_cjk_width() {
+        assert_eq!(display_width("你好"), 4);
+        assert_eq!(display_width("こんにちは world"), 16);
+        assert_eq!(display_width("한국어"), 6);
+    }
+
+    #[test]
+    fn test_emoji_width() {
+        assert_eq!(display_width("😀"), 2);
+        assert_eq!(display_width("a😀b"), 4);
+    }
+
+    #[test]
+    fn test_zwj_sequence_width() {
+        // Man, woman, girl and boy joined by zero width joiners.
+        let family = "👨\u{200D}👩\u{200D}👧\u{200D}👦";
+        assert_eq!(grapheme_width(family), 2);
+        assert_eq!(display_width(family), 2);
+        // Woman and laptop, with a skin tone modifier.
+        assert_eq!(display_width("👩🏽\u{200D}💻"), 2);
+        // Rainbow flag, with the emoji presentation selector.
+        assert_eq!(display_width("🏳\u{FE0F}\u{200D}🌈"), 2);
+    }
+
+    #[test]
+    fn test_flag_and_keycap_width() {
+        assert_eq!(display_width("🇮🇹"), 2);
+        assert_eq!(display_width("🇮🇹🇯🇵"), 4);
+        assert_eq!(display_width("1\u{FE0F}\u{20E3}"), 2);
+        assert_eq!(display_width("❤\u{FE0F}"), 2);
+    }
+
+    #[test]
+    fn test_combining_marks_width() {
+        // "e" followed by a combining acute accent.
+        assert_eq!(display_width("e\u{0301}"), 1);
+        assert_eq!(display_width("cafe\u{0301}"), 4);
+        // Several combining marks on the same base character.
+        assert_eq!(display_width("a\u{0300}\u{0316}\u{0317}"), 1);
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("hello", 5), "hello");
+        assert_eq!(truncate_to_width("hello world", 6), "hello…");
+        // A wide character that does not fit is not cut in half.
+        assert_eq!(truncate_to_width("你好世界", 6), "你好…");
+        assert_eq!(truncate_to_width("你好世界", 5), "你好…");
+        // Clusters are never split.
+        let family = "👨\u{200D}👩\u{200D}👧";
+        assert_eq!(
+            truncate_to_width(&format!("{family}{family}x"), 4),
+            format!("{family}…")
+        );
+        assert_eq!(truncate_to_width("cafe\u{0301}s!", 5), "cafe\u{0301}…");
+    }
+}
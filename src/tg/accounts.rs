@@ -0,0 +1,109 @@
+use crate::{configs::raw::telegram_raw::AccountEntry, utils};
+use std::path::Path;
+
+#[derive(Clone, Debug, Default)]
+/// `AccountManager` is a struct that represents the accounts of
+/// `telegram.toml` and the active one, whose database is used by TDLib.
+/// Only the active account is logged in, and switching restarts `tgt` with
+/// the database of the other account.
+pub struct AccountManager {
+    /// The accounts, in the order of the configuration.
+    accounts: Vec<AccountEntry>,
+    /// The directory of the database used when no account is configured,
+    /// that contains the databases of the accounts without their own.
+    database_dir: String,
+    /// The index of the active account, `None` if no account is configured.
+    active: Option<usize>,
+}
+
+impl AccountManager {
+    /// Create a new instance of the `AccountManager` struct.
+    ///
+    /// # Arguments
+    /// * `accounts` - The accounts of the configuration.
+    /// * `database_dir` - The directory of the database of the configuration.
+    /// * `active` - The name of the account used last time, if any. The first
+    ///   account is active if it is not configured anymore.
+    ///
+    /// # Returns
+    /// * `Self` - The new instance of the `AccountManager` struct.
+    pub fn new(accounts: Vec<AccountEntry>, database_dir: &str, active: Option<&str>) -> Self {
+        let active = active
+            .and_then(|name| accounts.iter().position(|account| account.name == name))
+            .or((!accounts.is_empty()).then_some(0));
+        Self {
+            accounts,
+            database_dir: database_dir.to_string(),
+            active,
+        }
+    }
+
+    /// Get the accounts.
+    ///
+    /// # Returns
+    /// * `&[AccountEntry]` - The accounts, in the order of the configuration.
+    pub fn accounts(&self) -> &[AccountEntry] {
+        &self.accounts
+    }
+
+    /// Get the name of the active account.
+    ///
+    /// # Returns
+    /// * `Option<&str>` - The name, `None` if no account is configured.
+    pub fn active_name(&self) -> Option<&str> {
+        self.active.map(|i| self.accounts[i].name.as_str())
+    }
+
+    /// Get the directory of the database of the active account.
+    ///
+    /// # Returns
+    /// * `String` - The directory, the one of the configuration if no account
+    ///   is configured.
+    pub fn database_dir(&self) -> String {
+        let Some(account) = self.active.map(|i| &self.accounts[i]) else {
+            return self.database_dir.clone();
+        };
+        let database_dir = match &account.database_dir {
+            Some(database_dir) => utils::tgt_dir()
+                .map(|tgt_dir| tgt_dir.join(database_dir))
+                .unwrap_or_else(|_| Path::new(database_dir).to_path_buf()),
+            None => Path::new(&self.database_dir).join(&account.name),
+        };
+        database_dir.to_string_lossy().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(name: &str, database_dir: Option<&str>) -> AccountEntry {
+        AccountEntry {
+            name: name.to_string(),
+            database_dir: database_dir.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_database_dir_of_the_active_account() {
+        let accounts = vec![
+            account("personal", Some("/data/personal")),
+            account("work", None),
+        ];
+        let manager = AccountManager::new(accounts.clone(), "/data/tg", Some("work"));
+        assert_eq!(manager.active_name(), Some("work"));
+        assert_eq!(manager.database_dir(), "/data/tg/work");
+        let manager = AccountManager::new(accounts, "/data/tg", Some("personal"));
+        assert_eq!(manager.database_dir(), "/data/personal");
+    }
+
+    #[test]
+    fn test_first_account_is_active_when_the_last_one_is_not_configured() {
+        let accounts = vec![account("personal", None), account("work", None)];
+        let manager = AccountManager::new(accounts, "/data/tg", Some("old"));
+        assert_eq!(manager.active_name(), Some("personal"));
+        let manager = AccountManager::new(vec![], "/data/tg", Some("old"));
+        assert_eq!(manager.active_name(), None);
+        assert_eq!(manager.database_dir(), "/data/tg");
+    }
+}
@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The extensions of the images sent as photos instead of documents.
+const PHOTO_EXTENSIONS: [&str; 3] = ["jpg", "jpeg", "png"];
+/// The maximum size of an image sent as a photo. Larger images are sent as
+/// documents, since Telegram does not accept them as photos.
+const PHOTO_SIZE_LIMIT: u64 = 10 * 1024 * 1024;
+/// The extensions of the videos sent as videos instead of documents.
+const VIDEO_EXTENSIONS: [&str; 2] = ["mp4", "mov"];
+/// The timers, in seconds, that can be chosen for the self-destructing
+/// media.
+pub const SELF_DESTRUCT_TIMERS: [i32; 4] = [10, 30, 60, 300];
+
+/// Check whether a file is sent as a photo, compressed and shown inline by
+/// the Telegram clients, rather than as a document.
+///
+/// # Arguments
+/// * `path` - The path of the file.
+///
+/// # Returns
+/// * `bool` - Whether the file is a small enough JPEG or PNG image.
+pub fn is_photo(path: &str) -> bool {
+    let is_image = Path::new(path)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .is_some_and(|extension| PHOTO_EXTENSIONS.contains(&extension.as_str()));
+    is_image && std::fs::metadata(path).is_ok_and(|metadata| metadata.len() <= PHOTO_SIZE_LIMIT)
+}
+
+/// Check whether a file is sent as a video, played inline by the Telegram
+/// clients, rather than as a document.
+///
+/// # Arguments
+/// * `path` - The path of the file.
+///
+/// # Returns
+/// * `bool` - Whether the file is an MP4 or a QuickTime video.
+pub fn is_video(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .is_some_and(|extension| VIDEO_EXTENSIONS.contains(&extension.as_str()))
+}
+
+/// The self-destruction of a photo or a video sent to a private chat.
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq, Deserialize, Serialize)]
+pub enum SelfDestruct {
+    /// The media can be viewed only once.
+    ViewOnce,
+    /// The media is deleted the given number of seconds after it is viewed.
+    Timer(i32),
+}
+
+impl SelfDestruct {
+    /// Get the label of the self-destruction, like "view once" or "30s".
+    ///
+    /// # Returns
+    /// * `String` - The label of the self-destruction.
+    pub fn label(&self) -> String {
+        match self {
+            Self::ViewOnce => "view once".to_string(),
+            Self::Timer(seconds) if seconds % 60 == 0 => format!("{}m", seconds / 60),
+            Self::Timer(seconds) => format!("{}s", seconds),
+        }
+    }
+}
+
+/// The kind of an attachment, shown in its chip.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum AttachmentKind {
+    /// A file chosen by its path.
+    File,
+    /// An image pasted from the clipboard.
+    Image,
+    /// A recorded voice note.
+    VoiceNote,
+}
+
+/// A file staged to be sent with the next message.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Attachment {
+    /// The path of the file.
+    pub path: String,
+    /// The kind of the attachment.
+    pub kind: AttachmentKind,
+}
+
+impl Attachment {
+    /// Get the text of the chip shown above the prompt, like "📎 report.pdf".
+    ///
+    /// # Returns
+    /// * `String` - The text of the chip.
+    pub fn chip(&self) -> String {
+        let icon = match self.kind {
+            AttachmentKind::File => "📎",
+            AttachmentKind::Image => "🖼",
+            AttachmentKind::VoiceNote => "🎤",
+        };
+        let name = Path::new(&self.path)
+            .file_name()
+            .map_or(self.path.clone(), |name| name.to_string_lossy().to_string());
+        format!("{} {}", icon, name)
+    }
+}
+
+/// The attachments staged before sending a message. They are sent together
+/// when the message is sent, the typed text being the caption of the last
+/// one.
+#[derive(Debug, Default)]
+pub struct StagedAttachments {
+    /// The staged attachments, in the order they are sent.
+    items: Vec<Attachment>,
+    /// Whether a voice note is being recorded.
+    recording: bool,
+    /// The self-destruction of the photos and the videos, if any.
+    self_destruct: Option<SelfDestruct>,
+}
+
+impl StagedAttachments {
+    /// Add an attachment at the end of the list.
+    pub fn push(&mut self, path: String, kind: AttachmentKind) {
+        self.items.push(Attachment { path, kind });
+    }
+
+    /// Remove an attachment, if the index is valid.
+    pub fn remove(&mut self, index: usize) {
+        if index < self.items.len() {
+            self.items.remove(index);
+        }
+    }
+
+    /// Take all the attachments, leaving the list empty.
+    /// The self-destruction is reset, so it is never applied by mistake to
+    /// the next attachments.
+    pub fn take(&mut self) -> Vec<Attachment> {
+        self.self_destruct = None;
+        std::mem::take(&mut self.items)
+    }
+
+    /// Get the staged attachments.
+    pub fn items(&self) -> &[Attachment] {
+        &self.items
+    }
+
+    /// Check whether no attachment is staged.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Set whether a voice note is being recorded.
+    pub fn set_recording(&mut self, recording: bool) {
+        self.recording = recording;
+    }
+
+    /// Check whether a voice note is being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Set the self-destruction of the photos and the videos.
+    pub fn set_self_destruct(&mut self, self_destruct: Option<SelfDestruct>) {
+        self.self_destruct = self_destruct;
+    }
+
+    /// Get the self-destruction of the photos and the videos, if any.
+    pub fn self_destruct(&self) -> Option<SelfDestruct> {
+        self.self_destruct
+    }
+}
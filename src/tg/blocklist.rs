@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+/// The prefix of the message of the errors returned when a request is rate
+/// limited, followed by the number of seconds to wait.
+const RETRY_AFTER_PREFIX: &str = "Too Many Requests: retry after ";
+
+/// An entry of a blocklist file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BlocklistEntry {
+    /// A user or a chat identified by its id. The ids of the users are
+    /// positive and the ids of the chats are negative.
+    Id(i64),
+    /// A user or a chat identified by its public username, without `@`.
+    Username(String),
+}
+
+/// Parse a blocklist file.
+/// Each line contains an id or a username, with or without `@`, or a
+/// `t.me` link. The text after `#` is a comment, like the name exported with
+/// the id, and empty lines are ignored.
+///
+/// # Arguments
+/// * `text` - The content of the file.
+///
+/// # Returns
+/// * `Vec<BlocklistEntry>` - The entries, in the order of the file, without
+///   duplicates.
+pub fn parse_blocklist(text: &str) -> Vec<BlocklistEntry> {
+    let mut entries = Vec::new();
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let entry = match line.parse::<i64>() {
+            Ok(id) => BlocklistEntry::Id(id),
+            Err(_) => {
+                let username = line
+                    .trim_start_matches("https://")
+                    .trim_start_matches("t.me/")
+                    .trim_start_matches('@');
+                BlocklistEntry::Username(username.to_string())
+            }
+        };
+        if !entries.contains(&entry) {
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+/// Format a line of an exported blocklist. The username is preferred to the
+/// id, since the ids of the users are not valid across accounts for the
+/// users that are not known to the account, and the name is added as a
+/// comment.
+///
+/// # Arguments
+/// * `id` - The id of the user or the chat.
+/// * `username` - The public username, if any.
+/// * `name` - The name of the user or the title of the chat.
+///
+/// # Returns
+/// * `String` - The line, without newline.
+pub fn format_blocklist_line(id: i64, username: Option<&str>, name: &str) -> String {
+    let entry = match username {
+        Some(username) => format!("@{}", username),
+        None => id.to_string(),
+    };
+    if name.is_empty() {
+        entry
+    } else {
+        format!("{} # {}", entry, name.replace('\n', " "))
+    }
+}
+
+/// Get the time to wait before retrying a request that was rate limited.
+///
+/// # Arguments
+/// * `error` - The error returned by TDLib.
+///
+/// # Returns
+/// * `Option<Duration>` - The time to wait, or `None` if the error is not a
+///   rate limit.
+pub fn retry_after(error: &tdlib_rs::types::Error) -> Option<Duration> {
+    if error.code != 429 {
+        return None;
+    }
+    let seconds = error
+        .message
+        .strip_prefix(RETRY_AFTER_PREFIX)
+        .and_then(|seconds| seconds.trim().parse::<u64>().ok())
+        .unwrap_or(1);
+    Some(Duration::from_secs(seconds))
+}
@@ -0,0 +1,207 @@
+/// The number of messages of a page of the history of a chat.
+pub const HISTORY_PAGE_SIZE: i32 = 50;
+/// The number of pages loaded each time older messages are asked for.
+const PAGES_PER_REQUEST: usize = 2;
+
+/// The pages of older messages of the open chat loaded in the background.
+/// Asking for older messages while pages are loaded adds pages to load, so
+/// that the next page is requested as soon as the previous one arrives.
+/// The pages not requested yet are dropped when the messages are scrolled
+/// the other way, and all of them when another chat is opened.
+#[derive(Debug, Default)]
+pub struct HistoryPages {
+    /// The generation of the loading, increased when it is cancelled so
+    /// that the page being loaded for the previous chat is dropped.
+    generation: u64,
+    /// The number of pages left to load.
+    pending: usize,
+    /// Whether a task is loading the pages.
+    running: bool,
+    /// Whether the oldest message of the chat is loaded, so that there is no
+    /// page left to ask for.
+    at_oldest: bool,
+}
+
+impl HistoryPages {
+    /// Ask for older messages.
+    ///
+    /// # Returns
+    /// * `Option<u64>` - The generation of the task to start to load the
+    ///   pages, or `None` if the running task loads them.
+    pub fn request(&mut self) -> Option<u64> {
+        if self.at_oldest {
+            return None;
+        }
+        self.pending += PAGES_PER_REQUEST;
+        if self.running {
+            return None;
+        }
+        self.running = true;
+        Some(self.generation)
+    }
+
+    /// Take the next page to load by the task of a generation. The task
+    /// stops once there is no page left or its loading was cancelled.
+    ///
+    /// # Arguments
+    /// * `generation` - The generation of the task.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the task loads another page.
+    pub fn next_page(&mut self, generation: u64) -> bool {
+        if generation != self.generation {
+            return false;
+        }
+        if self.pending == 0 {
+            self.running = false;
+            return false;
+        }
+        self.pending -= 1;
+        true
+    }
+
+    /// Check whether older messages are being loaded, to show it at the top
+    /// of the chat.
+    ///
+    /// # Returns
+    /// * `bool` - Whether a task is loading the pages.
+    pub fn is_loading(&self) -> bool {
+        self.running
+    }
+
+    /// Check whether the oldest message of the chat is loaded.
+    ///
+    /// # Returns
+    /// * `bool` - Whether there are no older messages.
+    pub fn is_at_oldest(&self) -> bool {
+        self.at_oldest
+    }
+
+    /// Check whether the pages loaded by the task of a generation are still
+    /// wanted.
+    ///
+    /// # Arguments
+    /// * `generation` - The generation of the task.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the loading was not cancelled.
+    pub fn is_current(&self, generation: u64) -> bool {
+        generation == self.generation
+    }
+
+    /// Drop the pages not requested yet, when the messages are scrolled
+    /// towards the newest ones. The page being loaded is kept.
+    pub fn stop(&mut self) {
+        self.pending = 0;
+    }
+
+    /// Cancel the loading, dropping the page being loaded, when another chat
+    /// is opened or the loaded messages are cleared.
+    pub fn cancel(&mut self) {
+        self.generation += 1;
+        self.pending = 0;
+        self.running = false;
+        self.at_oldest = false;
+    }
+
+    /// Ask for the older pages again once the oldest loaded messages are
+    /// dropped to limit the memory.
+    pub fn drop_oldest(&mut self) {
+        self.at_oldest = false;
+    }
+
+    /// Stop the task of a generation once the oldest message of the chat is
+    /// loaded. No page is asked for anymore, until the loading is cancelled.
+    ///
+    /// # Arguments
+    /// * `generation` - The generation of the task.
+    pub fn reach_oldest(&mut self, generation: u64) {
+        if generation == self.generation {
+            self.finish(generation);
+            self.at_oldest = true;
+        }
+    }
+
+    /// Stop the task of a generation early, when the older messages failed
+    /// to load.
+    ///
+    /// # Arguments
+    /// * `generation` - The generation of the task.
+    pub fn finish(&mut self, generation: u64) {
+        if generation == self.generation {
+            self.pending = 0;
+            self.running = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_while_loading_adds_pages() {
+        let mut pages = HistoryPages::default();
+        let generation = pages.request().unwrap();
+        assert!(pages.next_page(generation));
+        assert_eq!(pages.request(), None);
+        assert!(pages.next_page(generation));
+        assert!(pages.next_page(generation));
+        assert!(pages.next_page(generation));
+        assert!(!pages.next_page(generation));
+        // The task has stopped, so a new one is started.
+        assert_eq!(pages.request(), Some(generation));
+    }
+
+    #[test]
+    fn test_stop_keeps_the_loading_page() {
+        let mut pages = HistoryPages::default();
+        let generation = pages.request().unwrap();
+        assert!(pages.next_page(generation));
+        pages.stop();
+        assert!(pages.is_current(generation));
+        assert!(!pages.next_page(generation));
+    }
+
+    #[test]
+    fn test_cancel_drops_the_previous_generation() {
+        let mut pages = HistoryPages::default();
+        let previous = pages.request().unwrap();
+        assert!(pages.next_page(previous));
+        pages.cancel();
+        assert!(!pages.is_current(previous));
+        let generation = pages.request().unwrap();
+        assert_ne!(generation, previous);
+        // The task of the previous chat neither loads nor stops the new one.
+        assert!(!pages.next_page(previous));
+        pages.finish(previous);
+        assert!(pages.next_page(generation));
+        assert_eq!(pages.request(), None);
+    }
+
+    #[test]
+    fn test_finish_stops_the_task() {
+        let mut pages = HistoryPages::default();
+        let generation = pages.request().unwrap();
+        assert!(pages.next_page(generation));
+        pages.finish(generation);
+        assert!(!pages.next_page(generation));
+        assert_eq!(pages.request(), Some(generation));
+    }
+
+    #[test]
+    fn test_no_request_once_at_the_oldest_message() {
+        let mut pages = HistoryPages::default();
+        let generation = pages.request().unwrap();
+        assert!(pages.next_page(generation));
+        assert!(pages.is_loading());
+        pages.reach_oldest(generation);
+        assert!(!pages.is_loading());
+        assert!(pages.is_at_oldest());
+        assert_eq!(pages.request(), None);
+        // Another chat is opened.
+        pages.cancel();
+        assert!(!pages.is_at_oldest());
+        assert!(pages.request().is_some());
+    }
+}
@@ -0,0 +1,145 @@
+use tdlib_rs::enums::{InlineKeyboardButtonType, ReplyMarkup};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `InlineButtonKind` is an enum that represents what pressing a button of
+/// an inline keyboard does.
+pub enum InlineButtonKind {
+    /// Send a callback query to the bot, with the data of the button.
+    Callback(String),
+    /// Open a link.
+    Url(String),
+    /// Open a link that logs the user in on a website, after a confirmation.
+    LoginUrl(String),
+    /// A button that can not be pressed in `tgt`, like a game or a payment.
+    Unsupported,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `InlineButton` is a struct that represents a button of the inline keyboard
+/// attached to a message of a bot.
+pub struct InlineButton {
+    /// The text of the button.
+    pub text: String,
+    /// What pressing the button does.
+    pub kind: InlineButtonKind,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `GridMove` is an enum that represents a move of the selected button in an
+/// inline keyboard.
+pub enum GridMove {
+    /// The button on the left, in the same row.
+    Left,
+    /// The button on the right, in the same row.
+    Right,
+    /// The closest button of the row above.
+    Up,
+    /// The closest button of the row below.
+    Down,
+}
+
+/// Get the buttons of the inline keyboard of a message, row by row.
+///
+/// # Arguments
+/// * `reply_markup` - The reply markup of the message, if any.
+///
+/// # Returns
+/// * `Vec<Vec<InlineButton>>` - The rows of buttons, empty if the message
+///   has no inline keyboard.
+pub fn inline_keyboard(reply_markup: Option<&ReplyMarkup>) -> Vec<Vec<InlineButton>> {
+    let Some(ReplyMarkup::InlineKeyboard(keyboard)) = reply_markup else {
+        return vec![];
+    };
+    keyboard
+        .rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|button| InlineButton {
+                    text: button.text.clone(),
+                    kind: match &button.r#type {
+                        InlineKeyboardButtonType::Callback(callback) => {
+                            InlineButtonKind::Callback(callback.data.clone())
+                        }
+                        InlineKeyboardButtonType::Url(url) => {
+                            InlineButtonKind::Url(url.url.clone())
+                        }
+                        InlineKeyboardButtonType::LoginUrl(login_url) => {
+                            InlineButtonKind::LoginUrl(login_url.url.clone())
+                        }
+                        _ => InlineButtonKind::Unsupported,
+                    },
+                })
+                .collect()
+        })
+        .filter(|row: &Vec<InlineButton>| !row.is_empty())
+        .collect()
+}
+
+/// Move the selected button of an inline keyboard. The selection stays in
+/// the keyboard, and moving to a shorter row selects its last button.
+///
+/// # Arguments
+/// * `rows` - The rows of buttons.
+/// * `cursor` - The row and the column of the selected button.
+/// * `direction` - The move.
+///
+/// # Returns
+/// * `(usize, usize)` - The row and the column of the button selected.
+pub fn move_cursor(
+    rows: &[Vec<InlineButton>],
+    cursor: (usize, usize),
+    direction: GridMove,
+) -> (usize, usize) {
+    let (row, column) = cursor;
+    let last_column = |row: usize| rows.get(row).map_or(0, |r| r.len().saturating_sub(1));
+    match direction {
+        GridMove::Left => (row, column.saturating_sub(1)),
+        GridMove::Right => (row, (column + 1).min(last_column(row))),
+        GridMove::Up => {
+            let row = row.saturating_sub(1);
+            (row, column.min(last_column(row)))
+        }
+        GridMove::Down => {
+            let row = (row + 1).min(rows.len().saturating_sub(1));
+            (row, column.min(last_column(row)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(lengths: &[usize]) -> Vec<Vec<InlineButton>> {
+        lengths
+            .iter()
+            .map(|len| {
+                (0..*len)
+                    .map(|i| InlineButton {
+                        text: i.to_string(),
+                        kind: InlineButtonKind::Unsupported,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_move_in_a_row_stays_in_it() {
+        let rows = rows(&[3]);
+        assert_eq!(move_cursor(&rows, (0, 0), GridMove::Left), (0, 0));
+        assert_eq!(move_cursor(&rows, (0, 1), GridMove::Right), (0, 2));
+        assert_eq!(move_cursor(&rows, (0, 2), GridMove::Right), (0, 2));
+        assert_eq!(move_cursor(&rows, (0, 2), GridMove::Up), (0, 2));
+        assert_eq!(move_cursor(&rows, (0, 2), GridMove::Down), (0, 2));
+    }
+
+    #[test]
+    fn test_move_to_a_shorter_row_selects_its_last_button() {
+        let rows = rows(&[3, 1, 2]);
+        assert_eq!(move_cursor(&rows, (0, 2), GridMove::Down), (1, 0));
+        assert_eq!(move_cursor(&rows, (2, 1), GridMove::Up), (1, 0));
+        assert_eq!(move_cursor(&rows, (1, 0), GridMove::Down), (2, 0));
+    }
+}
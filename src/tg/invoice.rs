@@ -0,0 +1,126 @@
+use tdlib_rs::types::OrderInfo;
+
+/// The currencies whose amounts have no fractional part, like the Japanese
+/// yen. Telegram sends the amounts in the smallest units of the currency.
+const ZERO_DECIMAL_CURRENCIES: [&str; 14] = [
+    "BIF", "CLP", "DJF", "GNF", "ISK", "JPY", "KMF", "KRW", "PYG", "RWF", "UGX", "VND", "VUV",
+    "XAF",
+];
+/// The currencies whose amounts have three decimal digits.
+const THREE_DECIMAL_CURRENCIES: [&str; 5] = ["BHD", "IQD", "JOD", "KWD", "OMR"];
+/// The currency of the Telegram Stars, paid in whole stars.
+const STARS_CURRENCY: &str = "XTR";
+
+/// Get the number of decimal digits of the amounts of a currency.
+///
+/// # Arguments
+/// * `currency` - The ISO 4217 code of the currency.
+///
+/// # Returns
+/// * `u32` - The number of decimal digits.
+fn currency_exponent(currency: &str) -> u32 {
+    if currency == STARS_CURRENCY || ZERO_DECIMAL_CURRENCIES.contains(&currency) {
+        0
+    } else if THREE_DECIMAL_CURRENCIES.contains(&currency) {
+        3
+    } else {
+        2
+    }
+}
+
+/// Format the amount of an invoice or a payment.
+///
+/// # Arguments
+/// * `total_amount` - The amount, in the smallest units of the currency.
+/// * `currency` - The ISO 4217 code of the currency.
+///
+/// # Returns
+/// * `String` - The formatted amount, for example `12.50 USD` or `50 ⭐`.
+pub fn format_amount(total_amount: i64, currency: &str) -> String {
+    let exponent = currency_exponent(currency);
+    let sign = if total_amount < 0 { "-" } else { "" };
+    let amount = total_amount.unsigned_abs();
+    let value = if exponent == 0 {
+        amount.to_string()
+    } else {
+        let unit = 10u64.pow(exponent);
+        format!(
+            "{}.{:0width$}",
+            amount / unit,
+            amount % unit,
+            width = exponent as usize
+        )
+    };
+    if currency == STARS_CURRENCY {
+        format!("{}{} ⭐", sign, value)
+    } else {
+        format!("{}{} {}", sign, value, currency)
+    }
+}
+
+/// Get the lines of the order info given with a payment, without the
+/// missing fields.
+///
+/// # Arguments
+/// * `order_info` - The order info.
+///
+/// # Returns
+/// * `Vec<String>` - The lines of the order info.
+pub fn order_info_lines(order_info: &OrderInfo) -> Vec<String> {
+    let mut lines = vec![];
+    for (label, value) in [
+        ("Name", &order_info.name),
+        ("Phone", &order_info.phone_number),
+        ("Email", &order_info.email_address),
+    ] {
+        if !value.is_empty() {
+            lines.push(format!("{}: {}", label, value));
+        }
+    }
+    if let Some(address) = &order_info.shipping_address {
+        let address = [
+            &address.street_line1,
+            &address.street_line2,
+            &address.city,
+            &address.state,
+            &address.postal_code,
+            &address.country_code,
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .map(String::as_str)
+        .collect::<Vec<&str>>()
+        .join(", ");
+        lines.push(format!("Shipping address: {}", address));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_amount_with_the_exponent_of_the_currency() {
+        assert_eq!(format_amount(1250, "USD"), "12.50 USD");
+        assert_eq!(format_amount(5, "EUR"), "0.05 EUR");
+        assert_eq!(format_amount(1500, "JPY"), "1500 JPY");
+        assert_eq!(format_amount(12345, "KWD"), "12.345 KWD");
+        assert_eq!(format_amount(50, "XTR"), "50 ⭐");
+        assert_eq!(format_amount(-1250, "USD"), "-12.50 USD");
+    }
+
+    #[test]
+    fn test_order_info_lines_skip_the_missing_fields() {
+        let order_info = OrderInfo {
+            name: "Ada".to_string(),
+            phone_number: String::new(),
+            email_address: "ada@example.com".to_string(),
+            shipping_address: None,
+        };
+        assert_eq!(
+            order_info_lines(&order_info),
+            vec!["Name: Ada", "Email: ada@example.com"]
+        );
+    }
+}
@@ -1,12 +1,78 @@
-use crate::app_context::AppContext;
-use chrono::{DateTime, Local};
+use crate::{
+    action::Action,
+    app_context::AppContext,
+    audio_player::AudioMetadata,
+    bidi::{is_rtl_paragraph, visual_order},
+    text_width::{display_width, grapheme_width},
+    tg::{
+        inline_keyboard::{inline_keyboard, InlineButton},
+        invoice::format_amount,
+    },
+    url_display::{self, UrlDisplay},
+    url_preview::UrlPreviewState,
+};
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, Locale, NaiveDate, NaiveDateTime, NaiveTime, TimeZone,
+    Utc, Weekday,
+};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span, Text};
-use std::time::{Duration, UNIX_EPOCH};
-use tdlib_rs::enums::{MessageContent, MessageReplyTo, MessageSender};
-use tdlib_rs::types::FormattedText;
+use std::fmt::Display;
+use tdlib_rs::enums::{
+    MessageContent, MessageReplyTo, MessageSender, ReactionType, ReplyMarkup, StickerFormat,
+    TextEntityType, ThumbnailFormat,
+};
+use tdlib_rs::types::{Birthdate, FormattedText, MessageInteractionInfo};
+use unicode_segmentation::UnicodeSegmentation;
 
-use super::td_enums::{TdMessageReplyTo, TdMessageSender};
+use super::td_enums::{TdMessageForwardInfo, TdMessageOrigin, TdMessageReplyTo, TdMessageSender};
+use crate::APP_CONFIG;
+
+lazy_static::lazy_static! {
+    static ref TIME_ZONE: TimeZoneEntry = TimeZoneEntry::from_config(&APP_CONFIG.timezone);
+    static ref LOCALE: Locale = Locale::try_from(APP_CONFIG.locale.as_str()).unwrap_or_else(|_| {
+        tracing::warn!("Invalid locale {}, falling back to en_US", APP_CONFIG.locale);
+        Locale::en_US
+    });
+    static ref URL_DISPLAY: UrlDisplay = UrlDisplay::from_config(&APP_CONFIG.url_display);
+}
+
+/// The timezone used to display dates and times.
+#[derive(Debug, Clone, Copy)]
+pub enum TimeZoneEntry {
+    /// The system local timezone.
+    Local,
+    /// A fixed offset from UTC, for example `+02:00`.
+    Fixed(FixedOffset),
+    /// A named timezone, for example `Europe/Rome`.
+    Named(chrono_tz::Tz),
+}
+impl TimeZoneEntry {
+    /// Parse the timezone from the application configuration.
+    /// An empty string selects the system local timezone.
+    ///
+    /// # Arguments
+    /// * `timezone` - The timezone as written in the configuration.
+    ///
+    /// # Returns
+    /// The parsed timezone, or the system local timezone if it is invalid.
+    pub fn from_config(timezone: &str) -> Self {
+        let timezone = timezone.trim();
+        if timezone.is_empty() || timezone.eq_ignore_ascii_case("local") {
+            return Self::Local;
+        }
+        if let Ok(offset) = timezone.parse::<FixedOffset>() {
+            return Self::Fixed(offset);
+        }
+        match timezone.parse::<chrono_tz::Tz>() {
+            Ok(tz) => Self::Named(tz),
+            Err(_) => {
+                tracing::warn!("Invalid timezone {}, falling back to local", timezone);
+                Self::Local
+            }
+        }
+    }
+}
 
 #[derive(Debug, Default, Clone)]
 pub struct DateTimeEntry {
@@ -14,18 +80,266 @@ pub struct DateTimeEntry {
 }
 impl DateTimeEntry {
     pub fn convert_time(timestamp: i32) -> String {
-        let d = UNIX_EPOCH + Duration::from_secs(timestamp as u64);
-        let datetime = DateTime::<Local>::from(d);
-        if datetime.date_naive() == Local::now().date_naive() {
-            return datetime.format("%H:%M").to_string();
+        match *TIME_ZONE {
+            TimeZoneEntry::Local => Self::convert_time_in(timestamp, &Local),
+            TimeZoneEntry::Fixed(offset) => Self::convert_time_in(timestamp, &offset),
+            TimeZoneEntry::Named(tz) => Self::convert_time_in(timestamp, &tz),
+        }
+    }
+
+    fn convert_time_in<Tz: TimeZone>(timestamp: i32, tz: &Tz) -> String
+    where
+        Tz::Offset: Display,
+    {
+        let datetime = DateTime::<Utc>::from_timestamp(timestamp as i64, 0)
+            .unwrap_or_default()
+            .with_timezone(tz);
+        let now = Utc::now().with_timezone(tz);
+        if datetime.date_naive() == now.date_naive() {
+            return datetime.format_localized("%H:%M", *LOCALE).to_string();
+        }
+        if datetime.date_naive() == (now.clone() - chrono::Duration::days(1)).date_naive() {
+            return format!(
+                "{} {}",
+                Self::yesterday_label(&APP_CONFIG.locale),
+                datetime.format_localized("%H:%M", *LOCALE)
+            );
+        }
+        // Earlier days of the current week are shown with the name of the day.
+        let first_day_of_week = Self::first_day_of_week();
+        if datetime.date_naive().week(first_day_of_week).first_day()
+            == now.date_naive().week(first_day_of_week).first_day()
+        {
+            return datetime.format_localized("%A %H:%M", *LOCALE).to_string();
+        }
+        datetime
+            .format_localized("%Y-%m-%d %H:%M", *LOCALE)
+            .to_string() // :%S
+    }
+
+    /// Convert a timestamp to the time of the day, like `14:03`, used by the
+    /// compact mode of the chat.
+    ///
+    /// # Arguments
+    /// * `timestamp` - The Unix timestamp.
+    ///
+    /// # Returns
+    /// The formatted time.
+    pub fn convert_short_time(timestamp: i32) -> String {
+        match *TIME_ZONE {
+            TimeZoneEntry::Local => Self::convert_short_time_in(timestamp, &Local),
+            TimeZoneEntry::Fixed(offset) => Self::convert_short_time_in(timestamp, &offset),
+            TimeZoneEntry::Named(tz) => Self::convert_short_time_in(timestamp, &tz),
+        }
+    }
+
+    fn convert_short_time_in<Tz: TimeZone>(timestamp: i32, tz: &Tz) -> String
+    where
+        Tz::Offset: Display,
+    {
+        DateTime::<Utc>::from_timestamp(timestamp as i64, 0)
+            .unwrap_or_default()
+            .with_timezone(tz)
+            .format_localized("%H:%M", *LOCALE)
+            .to_string()
+    }
+
+    /// Convert a timestamp to the exact date and time, with the seconds and
+    /// the offset of the configured timezone, like
+    /// `2024-05-01 14:03:22 +02:00 (Europe/Rome)`.
+    ///
+    /// # Arguments
+    /// * `timestamp` - The Unix timestamp.
+    ///
+    /// # Returns
+    /// The formatted date and time.
+    pub fn convert_full_time(timestamp: i32) -> String {
+        match *TIME_ZONE {
+            TimeZoneEntry::Local => Self::convert_full_time_in(timestamp, &Local),
+            TimeZoneEntry::Fixed(offset) => Self::convert_full_time_in(timestamp, &offset),
+            TimeZoneEntry::Named(tz) => format!(
+                "{} ({})",
+                Self::convert_full_time_in(timestamp, &tz),
+                tz.name()
+            ),
         }
-        if datetime.date_naive() == (Local::now() - chrono::Duration::days(1)).date_naive() {
-            return datetime.format("Yesterday %H:%M").to_string();
+    }
+
+    fn convert_full_time_in<Tz: TimeZone>(timestamp: i32, tz: &Tz) -> String
+    where
+        Tz::Offset: Display,
+    {
+        DateTime::<Utc>::from_timestamp(timestamp as i64, 0)
+            .unwrap_or_default()
+            .with_timezone(tz)
+            .format_localized("%Y-%m-%d %H:%M:%S %:z", *LOCALE)
+            .to_string()
+    }
+
+    /// Parse a date, like `2024-05-01`, or a date and a time, like
+    /// `2024-05-01 14:03`, in the configured timezone. A date alone is the
+    /// start of the day.
+    ///
+    /// # Arguments
+    /// * `text` - The date typed by the user.
+    ///
+    /// # Returns
+    /// The Unix timestamp, or `None` if the date is not valid.
+    pub fn parse_date_time(text: &str) -> Option<i32> {
+        match *TIME_ZONE {
+            TimeZoneEntry::Local => Self::parse_date_time_in(text, &Local),
+            TimeZoneEntry::Fixed(offset) => Self::parse_date_time_in(text, &offset),
+            TimeZoneEntry::Named(tz) => Self::parse_date_time_in(text, &tz),
         }
-        datetime.format("%Y-%m-%d %H:%M").to_string() // :%S
     }
 
-    pub fn get_span_styled(&self, app_context: &AppContext) -> Span {
+    fn parse_date_time_in<Tz: TimeZone>(text: &str, tz: &Tz) -> Option<i32> {
+        let text = text.trim();
+        let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M")
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                    .ok()
+                    .map(|date| date.and_time(NaiveTime::MIN))
+            })?;
+        let timestamp = tz.from_local_datetime(&naive).earliest()?.timestamp();
+        i32::try_from(timestamp).ok()
+    }
+
+    /// Convert a timestamp to the date, like `2024-05-01`, in the configured
+    /// timezone.
+    ///
+    /// # Arguments
+    /// * `timestamp` - The Unix timestamp.
+    ///
+    /// # Returns
+    /// The formatted date.
+    pub fn convert_date(timestamp: i32) -> String {
+        let datetime = DateTime::<Utc>::from_timestamp(timestamp as i64, 0).unwrap_or_default();
+        let date = match *TIME_ZONE {
+            TimeZoneEntry::Local => datetime.with_timezone(&Local).date_naive(),
+            TimeZoneEntry::Fixed(offset) => datetime.with_timezone(&offset).date_naive(),
+            TimeZoneEntry::Named(tz) => datetime.with_timezone(&tz).date_naive(),
+        };
+        date.format("%Y-%m-%d").to_string()
+    }
+
+    /// Get the current date in the configured timezone.
+    ///
+    /// # Returns
+    /// The date of today.
+    pub fn today() -> NaiveDate {
+        match *TIME_ZONE {
+            TimeZoneEntry::Local => Local::now().date_naive(),
+            TimeZoneEntry::Fixed(offset) => Utc::now().with_timezone(&offset).date_naive(),
+            TimeZoneEntry::Named(tz) => Utc::now().with_timezone(&tz).date_naive(),
+        }
+    }
+
+    /// Get the current date and time in the configured timezone.
+    ///
+    /// # Returns
+    /// The date and time of now.
+    pub fn now() -> NaiveDateTime {
+        match *TIME_ZONE {
+            TimeZoneEntry::Local => Local::now().naive_local(),
+            TimeZoneEntry::Fixed(offset) => Utc::now().with_timezone(&offset).naive_local(),
+            TimeZoneEntry::Named(tz) => Utc::now().with_timezone(&tz).naive_local(),
+        }
+    }
+
+    /// Format a birthday, like `14 March 1990`, or `14 March` when the year
+    /// is not shared.
+    ///
+    /// # Arguments
+    /// * `birthdate` - The birthdate of a user.
+    ///
+    /// # Returns
+    /// The formatted birthday, with a cake when it is today.
+    pub fn convert_birthdate(birthdate: &Birthdate) -> String {
+        // The leap year 2000 is used to format the birthdays on February 29
+        // without the year.
+        let year = if birthdate.year > 0 {
+            birthdate.year
+        } else {
+            2000
+        };
+        let Some(date) =
+            NaiveDate::from_ymd_opt(year, birthdate.month as u32, birthdate.day as u32)
+        else {
+            return format!("{}/{}", birthdate.day, birthdate.month);
+        };
+        let format = if birthdate.year > 0 {
+            "%-d %B %Y"
+        } else {
+            "%-d %B"
+        };
+        let mut text = date
+            .and_time(NaiveTime::MIN)
+            .and_utc()
+            .format_localized(format, *LOCALE)
+            .to_string();
+        if Self::is_birthday_today(birthdate) {
+            text.push_str(" 🎂 today");
+        }
+        text
+    }
+
+    /// Check whether a birthday is today, in the configured timezone.
+    ///
+    /// # Arguments
+    /// * `birthdate` - The birthdate of a user.
+    ///
+    /// # Returns
+    /// Whether the day and the month of the birthday are those of today.
+    pub fn is_birthday_today(birthdate: &Birthdate) -> bool {
+        let today = Self::today();
+        today.day() == birthdate.day as u32 && today.month() == birthdate.month as u32
+    }
+
+    /// Get the first day of the week used in calendars.
+    ///
+    /// # Returns
+    /// The configured first day of the week, or Monday if it is invalid.
+    pub fn first_day_of_week() -> Weekday {
+        APP_CONFIG
+            .first_day_of_week
+            .parse::<Weekday>()
+            .unwrap_or(Weekday::Mon)
+    }
+
+    /// Get the word for "yesterday" in the language of a locale, since the
+    /// locales of chrono only name the days and the months.
+    ///
+    /// # Arguments
+    /// * `locale` - The locale, like `it_IT`.
+    ///
+    /// # Returns
+    /// The word in the language of the locale, or in English if it is not
+    /// known.
+    pub fn yesterday_label(locale: &str) -> &'static str {
+        let language = locale.split(['_', '-']).next().unwrap_or_default();
+        match language {
+            "it" => "Ieri",
+            "de" => "Gestern",
+            "fr" => "Hier",
+            "es" => "Ayer",
+            "pt" => "Ontem",
+            "nl" => "Gisteren",
+            "pl" => "Wczoraj",
+            "ru" => "Вчера",
+            "uk" => "Учора",
+            "tr" => "Dün",
+            "fa" => "دیروز",
+            "ar" => "أمس",
+            "zh" => "昨天",
+            "ja" => "昨日",
+            "ko" => "어제",
+            _ => "Yesterday",
+        }
+    }
+
+    pub fn get_span_styled(&self, app_context: &AppContext) -> Span<'_> {
         Span::styled(
             Self::convert_time(self.timestamp),
             app_context.style_timestamp(),
@@ -33,6 +347,22 @@ impl DateTimeEntry {
     }
 }
 
+/// The placeholder shown for the reactions with a custom emoji, which can not
+/// be drawn in the terminal.
+const CUSTOM_EMOJI_REACTION: &str = "✨";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `ReactionCount` is a struct that represents a reaction added to a message
+/// and the number of users who added it.
+pub struct ReactionCount {
+    /// The emoji of the reaction.
+    pub emoji: String,
+    /// The number of users who added the reaction.
+    pub count: i32,
+    /// Indicates whether the reaction was added by the current user.
+    pub is_chosen: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct MessageEntry {
     id: i64,
@@ -41,8 +371,45 @@ pub struct MessageEntry {
     reply_to: Option<TdMessageReplyTo>,
     timestamp: DateTimeEntry,
     is_edited: bool,
+    /// The first link of a text message without a Telegram preview, whose
+    /// preview can be fetched locally.
+    preview_url: Option<String>,
+    /// The emoji of a single-emoji message, shown as a large emoji.
+    big_emoji: Option<String>,
+    /// The origin of a forwarded message.
+    forward_info: Option<TdMessageForwardInfo>,
+    /// The metadata of an audio message.
+    audio: Option<AudioMetadata>,
+    /// The reactions added to the message.
+    reactions: Vec<ReactionCount>,
+    /// Whether the message is a post of a channel.
+    is_channel_post: bool,
+    /// The number of comments of a channel post with a discussion group.
+    comment_count: Option<i32>,
+    /// The id of the file of the photo or the sticker of the message, drawn
+    /// inline in the chat.
+    image_file: Option<i32>,
+    /// The buttons of the inline keyboard of a message of a bot, row by row.
+    inline_keyboard: Vec<Vec<InlineButton>>,
+    /// The id of the message pinned by a service message pinning one.
+    pinned_message_id: Option<i64>,
+    /// The caption of a media message, which can be edited.
+    caption: Option<FormattedText>,
 }
 
+/// The duration of the animation of a large emoji after the message is sent,
+/// in milliseconds.
+const EMOJI_ANIMATION_DURATION: i64 = 3000;
+/// The duration of a frame of the animation of a large emoji, in
+/// milliseconds.
+const EMOJI_ANIMATION_FRAME: i64 = 150;
+/// The minimum width of the size of a photo drawn inline, in pixels.
+const IMAGE_MIN_WIDTH: i32 = 200;
+/// The sparkles drawn at the corners of an animated large emoji.
+const EMOJI_ANIMATION_SPARKLES: [char; 4] = ['✦', '✧', '·', '✧'];
+/// The width inside the box of a large emoji, in columns.
+const BIG_EMOJI_BOX_WIDTH: usize = 8;
+
 impl MessageEntry {
     pub fn id(&self) -> i64 {
         self.id
@@ -52,6 +419,18 @@ impl MessageEntry {
         &self.timestamp
     }
 
+    pub fn forward_info(&self) -> Option<&TdMessageForwardInfo> {
+        self.forward_info.as_ref()
+    }
+
+    pub fn audio(&self) -> Option<&AudioMetadata> {
+        self.audio.as_ref()
+    }
+
+    pub fn image_file_id(&self) -> Option<i32> {
+        self.image_file
+    }
+
     pub fn sender_id(&self) -> i64 {
         match self.sender_id {
             TdMessageSender::User(user_id) => user_id,
@@ -60,6 +439,9 @@ impl MessageEntry {
     }
 
     pub fn message_content_to_string(&self) -> String {
+        if let Some(emoji) = &self.big_emoji {
+            return emoji.clone();
+        }
         self.message_content
             .iter()
             .map(|l| l.iter().map(|s| s.content.clone()).collect::<String>())
@@ -69,12 +451,47 @@ impl MessageEntry {
 
     pub fn set_message_content(&mut self, content: &MessageContent) {
         self.message_content = Self::message_content_lines(content);
+        self.preview_url = Self::preview_url(content);
+        self.big_emoji = Self::big_emoji(content);
+        self.audio = Self::audio_metadata(content);
+        self.image_file = Self::image_file(content);
+        self.caption = Self::caption_of(content);
     }
 
     pub fn set_is_edited(&mut self, is_edited: bool) {
         self.is_edited = is_edited;
     }
 
+    pub fn reactions(&self) -> &[ReactionCount] {
+        &self.reactions
+    }
+
+    pub fn comment_count(&self) -> Option<i32> {
+        self.comment_count
+    }
+
+    pub fn set_interaction_info(&mut self, interaction_info: Option<&MessageInteractionInfo>) {
+        self.reactions = Self::reaction_counts(interaction_info);
+        self.comment_count = Self::comment_count_of(self.is_channel_post, interaction_info);
+    }
+
+    pub fn inline_keyboard(&self) -> &[Vec<InlineButton>] {
+        &self.inline_keyboard
+    }
+
+    pub fn pinned_message_id(&self) -> Option<i64> {
+        self.pinned_message_id
+    }
+
+    pub fn caption(&self) -> Option<&FormattedText> {
+        self.caption.as_ref()
+    }
+
+    pub fn set_inline_keyboard(&mut self, reply_markup: Option<&ReplyMarkup>) {
+        self.inline_keyboard = inline_keyboard(reply_markup);
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn get_text_styled(
         &self,
         myself: bool,
@@ -83,7 +500,8 @@ impl MessageEntry {
         name_style: Style,
         content_style: Style,
         wrap_width: i32,
-    ) -> Text {
+        with_header: bool,
+    ) -> Text<'_> {
         let (message_reply_name, message_reply_content) = if myself {
             (
                 app_context.style_chat_message_myself_reply_name(),
@@ -124,8 +542,23 @@ impl MessageEntry {
                                 message_reply_name,
                             ),
                         ])]);
-                        entry.extend(
-                            match app_context
+                        // A partial quote replaces the content of the replied
+                        // message.
+                        entry.extend(match &message.quote {
+                            Some(quote) => quote
+                                .text
+                                .split('\n')
+                                .map(|line| {
+                                    Line::from(vec![
+                                        Span::styled(
+                                            "❝ ",
+                                            app_context.style_chat_message_reply_text(),
+                                        ),
+                                        Span::styled(line.to_string(), message_reply_content),
+                                    ])
+                                })
+                                .collect(),
+                            None => match app_context
                                 .tg_context()
                                 .open_chat_messages()
                                 .iter()
@@ -136,7 +569,7 @@ impl MessageEntry {
                                 }
                                 None => vec![Line::from("")],
                             },
-                        );
+                        });
                         Some(entry)
                     } else {
                         None
@@ -155,55 +588,578 @@ impl MessageEntry {
         };
 
         let mut entry = Text::default();
-        entry.extend(vec![Line::from(vec![
-            Span::styled(
-                match self.sender_id {
-                    TdMessageSender::User(user_id) => app_context
-                        .tg_context()
-                        .try_name_from_chats_or_users(user_id)
-                        .unwrap_or_default(),
-                    TdMessageSender::Chat(chat_id) => app_context
-                        .tg_context()
-                        .name_from_chats(chat_id)
-                        .unwrap_or_default(),
-                },
-                name_style,
-            ),
-            Span::raw(" "),
-            Span::raw(if self.is_edited { "✏️" } else { "" }),
-            Span::raw(" "),
-            Span::raw(match myself {
-                true => {
-                    if is_unread {
-                        "📤"
-                    } else {
-                        "👀"
+        // The header is hidden when the message is grouped with the previous
+        // one of the same sender.
+        if with_header {
+            entry.extend(vec![Line::from(vec![
+                Span::styled(self.sender_name(app_context), name_style),
+                Span::raw(" "),
+                Span::raw(if self.is_edited { "✏️" } else { "" }),
+                Span::raw(" "),
+                Span::raw(match myself {
+                    true => {
+                        if is_unread {
+                            "✓"
+                        } else {
+                            "✓✓"
+                        }
                     }
-                }
-                false => "",
-            }),
-            Span::raw(" "),
-            self.timestamp.get_span_styled(app_context),
-        ])]);
+                    false => "",
+                }),
+                Span::raw(" "),
+                self.timestamp.get_span_styled(app_context),
+            ])]);
+        }
+        entry.extend(self.forward_lines(app_context));
         entry.extend(reply_text.unwrap_or_default());
-        entry.extend(self.get_lines_styled_with_style(content_style, wrap_width));
+        match (&self.big_emoji, self.emoji_animation_frame(app_context)) {
+            (Some(emoji), Some(frame)) => entry.extend(
+                Self::big_emoji_lines(emoji, Some(frame))
+                    .into_iter()
+                    .map(|line| line.style(content_style)),
+            ),
+            _ => entry.extend(self.get_lines_styled_with_style(content_style, wrap_width)),
+        }
+        // Without the header, the edited indicator is shown after the content.
+        if !with_header && self.is_edited {
+            entry.extend(vec![Line::from(Span::styled("✏️ edited", name_style))]);
+        }
+        entry.extend(self.url_footnote_lines(app_context));
+        entry.extend(self.url_preview_lines(app_context));
+        entry.extend(self.reaction_lines(app_context));
         entry
     }
 
+    /// Get the text of the message in the compact mode of the chat, like IRC
+    /// clients: a line `HH:MM <nick> text`, whose continuation lines are
+    /// indented under the text, without the header of the bubble.
+    ///
+    /// # Arguments
+    /// * `app_context` - The application context.
+    /// * `name_style` - The style of the name of the sender.
+    /// * `content_style` - The style of the content.
+    /// * `width` - The width of the chat.
+    /// * `with_header` - Whether the time and the name are shown, or the
+    ///   message is grouped with the previous one of the same sender.
+    ///
+    /// # Returns
+    /// * `Text` - The text of the message.
+    pub fn get_compact_text_styled(
+        &self,
+        app_context: &AppContext,
+        name_style: Style,
+        content_style: Style,
+        width: i32,
+        with_header: bool,
+    ) -> Text<'_> {
+        let prefix = vec![
+            Span::styled(
+                format!(
+                    "{} ",
+                    DateTimeEntry::convert_short_time(self.timestamp.timestamp)
+                ),
+                app_context.style_timestamp(),
+            ),
+            Span::styled(format!("<{}> ", self.sender_name(app_context)), name_style),
+        ];
+        let indent = prefix
+            .iter()
+            .map(|span| display_width(&span.content))
+            .sum::<usize>();
+        let wrap_width = (width - indent as i32).max(1);
+        // The origin of a forwarded message is shown on the first line.
+        let mut lines = self
+            .forward_origin_name(app_context)
+            .map(|origin| {
+                Line::from(Span::styled(
+                    format!("↪ {}", origin),
+                    app_context.style_chat_message_reply_text(),
+                ))
+            })
+            .into_iter()
+            .collect::<Vec<Line>>();
+        lines.extend(self.get_lines_styled_with_style(content_style, wrap_width));
+        if self.is_edited {
+            if let Some(last) = lines.last_mut() {
+                last.spans
+                    .push(Span::styled(" ✏️", app_context.style_timestamp()));
+            }
+        }
+        lines.extend(self.url_footnote_lines(app_context));
+        lines.extend(self.reaction_lines(app_context));
+        let mut entry = Text::default();
+        for (i, mut line) in lines.into_iter().enumerate() {
+            let head = if i == 0 && with_header {
+                prefix.clone()
+            } else {
+                vec![Span::raw(" ".repeat(indent))]
+            };
+            line.spans.splice(0..0, head);
+            entry.lines.push(line);
+        }
+        entry
+    }
+
+    /// Get the name of the sender of the message.
+    pub fn sender_name(&self, app_context: &AppContext) -> String {
+        match self.sender_id {
+            TdMessageSender::User(user_id) => app_context
+                .tg_context()
+                .try_name_from_chats_or_users(user_id)
+                .unwrap_or_default(),
+            TdMessageSender::Chat(chat_id) => app_context
+                .tg_context()
+                .name_from_chats(chat_id)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Get the name of the origin of a forwarded message, with the signature
+    /// of the author, if any.
+    pub fn forward_origin_name(&self, app_context: &AppContext) -> Option<String> {
+        let forward_info = self.forward_info.as_ref()?;
+        let tg_context = app_context.tg_context();
+        let with_signature = |name: String, author_signature: &String| {
+            if author_signature.is_empty() {
+                name
+            } else {
+                format!("{} ({})", name, author_signature)
+            }
+        };
+        let origin = match &forward_info.origin {
+            TdMessageOrigin::User(user_id) => tg_context
+                .try_name_from_chats_or_users(*user_id)
+                .unwrap_or_else(|| "Unknown user".to_string()),
+            TdMessageOrigin::HiddenUser(sender_name) => {
+                format!("{} (hidden account)", sender_name)
+            }
+            TdMessageOrigin::Chat {
+                sender_chat_id,
+                author_signature,
+            } => with_signature(
+                tg_context
+                    .name_from_chats(*sender_chat_id)
+                    .unwrap_or_else(|| "Unknown chat".to_string()),
+                author_signature,
+            ),
+            TdMessageOrigin::Channel {
+                chat_id,
+                author_signature,
+                ..
+            } => with_signature(
+                tg_context
+                    .name_from_chats(*chat_id)
+                    .unwrap_or_else(|| "Unknown channel".to_string()),
+                author_signature,
+            ),
+        };
+        Some(origin)
+    }
+
+    /// Get the header of a forwarded message, like "Forwarded from X".
+    /// The origin can be opened with `chat_window_open_origin` when the
+    /// original message is accessible.
+    fn forward_lines(&self, app_context: &AppContext) -> Vec<Line<'static>> {
+        let (Some(forward_info), Some(origin)) =
+            (&self.forward_info, self.forward_origin_name(app_context))
+        else {
+            return vec![];
+        };
+        let mut spans = vec![
+            Span::styled(
+                "↪ Forwarded from: ",
+                app_context.style_chat_message_reply_text(),
+            ),
+            Span::styled(origin, app_context.style_chat_message_other_reply_name()),
+        ];
+        // The original message can be opened.
+        if forward_info.origin_message().is_some() {
+            spans.push(Span::styled(
+                " ↗",
+                app_context.style_chat_message_reply_text(),
+            ));
+        }
+        vec![Line::from(spans)]
+    }
+
+    /// Get the line of the reactions of the message and their counts, and of
+    /// the comments of a channel post, if any. The reactions added by the
+    /// current user are highlighted.
+    fn reaction_lines(&self, app_context: &AppContext) -> Vec<Line<'static>> {
+        if self.reactions.is_empty() && self.comment_count.is_none() {
+            return vec![];
+        }
+        let mut spans = vec![];
+        for (i, reaction) in self.reactions.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::raw("  "));
+            }
+            let style = if reaction.is_chosen {
+                app_context.style_item_selected()
+            } else {
+                app_context.style_timestamp()
+            };
+            spans.push(Span::styled(
+                format!("{} {}", reaction.emoji, reaction.count),
+                style,
+            ));
+        }
+        if let Some(comment_count) = self.comment_count {
+            if !spans.is_empty() {
+                spans.push(Span::raw("  "));
+            }
+            let comments = match comment_count {
+                0 => "💬 Leave a comment".to_string(),
+                1 => "💬 1 comment".to_string(),
+                n => format!("💬 {} comments", n),
+            };
+            spans.push(Span::styled(comments, app_context.style_timestamp()));
+        }
+        vec![Line::from(spans)]
+    }
+
+    /// Get the links of the message listed at its end, with their numbers,
+    /// when the links are shown as footnotes.
+    fn url_footnote_lines(&self, app_context: &AppContext) -> Vec<Line<'static>> {
+        let (_, footnotes) = url_display::display_lines(&self.message_content, *URL_DISPLAY);
+        footnotes
+            .into_iter()
+            .enumerate()
+            .map(|(i, url)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("[{}] ", i + 1),
+                        app_context.style_chat_message_reply_text(),
+                    ),
+                    Span::styled(url, Style::default().add_modifier(Modifier::UNDERLINED)),
+                ])
+            })
+            .collect()
+    }
+
+    /// Get the lines of the locally fetched preview of the link of the
+    /// message, if any.
+    /// If the preview has not been requested yet, it is requested and it will
+    /// be shown once fetched.
+    fn url_preview_lines(&self, app_context: &AppContext) -> Vec<Line<'static>> {
+        let Some(url) = &self.preview_url else {
+            return vec![];
+        };
+        if !app_context.app_config().url_preview_fetch {
+            return vec![];
+        }
+        let state = app_context.url_previews().get(url).cloned();
+        match state {
+            Some(UrlPreviewState::Ready(preview)) => {
+                let mut lines = vec![Line::from(vec![
+                    Span::styled("┃ ", app_context.style_chat_message_reply_text()),
+                    Span::styled(preview.title, Style::default().add_modifier(Modifier::BOLD)),
+                ])];
+                if !preview.description.is_empty() {
+                    lines.push(Line::from(vec![
+                        Span::styled("┃ ", app_context.style_chat_message_reply_text()),
+                        Span::styled(
+                            preview.description,
+                            Style::default().add_modifier(Modifier::DIM),
+                        ),
+                    ]));
+                }
+                lines
+            }
+            Some(_) => vec![],
+            None => {
+                app_context
+                    .url_previews()
+                    .insert(url.clone(), UrlPreviewState::Pending);
+                if let Err(e) = app_context
+                    .action_tx()
+                    .send(Action::FetchUrlPreview(url.clone()))
+                {
+                    tracing::error!("Failed to request the preview of {}: {}", url, e);
+                }
+                vec![]
+            }
+        }
+    }
+
+    /// Get the current frame of the animation of the large emoji of the
+    /// message, if the message is a single-emoji message sent in the last
+    /// seconds and the animation is enabled.
+    fn emoji_animation_frame(&self, app_context: &AppContext) -> Option<usize> {
+        if self.big_emoji.is_none() || !app_context.app_config().emoji_animation {
+            return None;
+        }
+        let elapsed = Utc::now().timestamp_millis() - self.timestamp.timestamp as i64 * 1000;
+        if (0..EMOJI_ANIMATION_DURATION).contains(&elapsed) {
+            Some((elapsed / EMOJI_ANIMATION_FRAME) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Get the number of comments of a channel post, if it has a discussion
+    /// group.
+    fn comment_count_of(
+        is_channel_post: bool,
+        interaction_info: Option<&MessageInteractionInfo>,
+    ) -> Option<i32> {
+        interaction_info
+            .filter(|_| is_channel_post)
+            .and_then(|info| info.reply_info.as_ref())
+            .map(|reply_info| reply_info.reply_count)
+    }
+
+    /// Get the reactions of a message and their counts.
+    fn reaction_counts(interaction_info: Option<&MessageInteractionInfo>) -> Vec<ReactionCount> {
+        let Some(reactions) = interaction_info.and_then(|info| info.reactions.as_ref()) else {
+            return vec![];
+        };
+        reactions
+            .reactions
+            .iter()
+            .map(|reaction| ReactionCount {
+                emoji: match &reaction.r#type {
+                    ReactionType::Emoji(reaction_type) => reaction_type.emoji.clone(),
+                    ReactionType::CustomEmoji(_) => CUSTOM_EMOJI_REACTION.to_string(),
+                },
+                count: reaction.total_count,
+                is_chosen: reaction.is_chosen,
+            })
+            .collect()
+    }
+
+    /// Get the metadata of an audio or a voice message.
+    fn audio_metadata(content: &MessageContent) -> Option<AudioMetadata> {
+        match content {
+            MessageContent::MessageAudio(m) => Some(AudioMetadata::from(&m.audio)),
+            MessageContent::MessageVoiceNote(m) => Some(AudioMetadata::from(&m.voice_note)),
+            _ => None,
+        }
+    }
+
+    /// Get the id of the file of the image drawn inline for a photo, a
+    /// sticker or an animation.
+    /// The smallest size of a photo that is still sharp is used, and the
+    /// thumbnail of a sticker that is animated or a video, or of an
+    /// animation. A self-destructing photo is never drawn, it is only opened
+    /// on demand.
+    fn image_file(content: &MessageContent) -> Option<i32> {
+        match content {
+            MessageContent::MessagePhoto(m) if m.is_secret => None,
+            MessageContent::MessagePhoto(m) => m
+                .photo
+                .sizes
+                .iter()
+                .find(|size| size.width >= IMAGE_MIN_WIDTH)
+                .or_else(|| m.photo.sizes.last())
+                .map(|size| size.photo.id),
+            MessageContent::MessageSticker(m) => match m.sticker.format {
+                StickerFormat::Webp => Some(m.sticker.sticker.id),
+                _ => m
+                    .sticker
+                    .thumbnail
+                    .as_ref()
+                    .filter(|thumbnail| {
+                        matches!(
+                            thumbnail.format,
+                            ThumbnailFormat::Jpeg | ThumbnailFormat::Png | ThumbnailFormat::Webp
+                        )
+                    })
+                    .map(|thumbnail| thumbnail.file.id),
+            },
+            // The first frame of an animation is drawn from its thumbnail.
+            MessageContent::MessageAnimation(m) if !m.is_secret => m
+                .animation
+                .thumbnail
+                .as_ref()
+                .filter(|thumbnail| {
+                    matches!(
+                        thumbnail.format,
+                        ThumbnailFormat::Jpeg
+                            | ThumbnailFormat::Png
+                            | ThumbnailFormat::Webp
+                            | ThumbnailFormat::Gif
+                    )
+                })
+                .map(|thumbnail| thumbnail.file.id),
+            _ => None,
+        }
+    }
+
+    /// Get the emoji of a single-emoji message.
+    fn big_emoji(content: &MessageContent) -> Option<String> {
+        match content {
+            MessageContent::MessageAnimatedEmoji(m) => Some(m.emoji.clone()),
+            _ => None,
+        }
+    }
+
+    /// Get the lines of a large emoji, framed in a box.
+    /// When animated, sparkles blink at the corners of the box.
+    ///
+    /// # Arguments
+    /// * `emoji` - The emoji.
+    /// * `frame` - The frame of the animation, or `None` for the static
+    ///   presentation.
+    fn big_emoji_lines(emoji: &str, frame: Option<usize>) -> Vec<Line<'static>> {
+        let corner = |default: char, phase: usize| match frame {
+            Some(frame) => {
+                EMOJI_ANIMATION_SPARKLES[(frame + phase) % EMOJI_ANIMATION_SPARKLES.len()]
+            }
+            None => default,
+        };
+        // The emoji is centered by its width, so that a narrow emoji or a
+        // sequence joined with a zero width joiner keeps the box straight.
+        let emoji_width = display_width(emoji);
+        let width = BIG_EMOJI_BOX_WIDTH.max(emoji_width + 2);
+        let left = (width - emoji_width) / 2;
+        let right = width - emoji_width - left;
+        let border = "─".repeat(width);
+        let blank = " ".repeat(width);
+        vec![
+            Line::from(format!("{}{}{}", corner('╭', 0), border, corner('╮', 1))),
+            Line::from(format!("│{}│", blank)),
+            Line::from(format!(
+                "│{}{}{}│",
+                " ".repeat(left),
+                emoji,
+                " ".repeat(right)
+            )),
+            Line::from(format!("│{}│", blank)),
+            Line::from(format!("{}{}{}", corner('╰', 3), border, corner('╯', 2))),
+        ]
+    }
+
+    /// Get the first link of a text message that has no Telegram preview.
+    fn preview_url(content: &MessageContent) -> Option<String> {
+        let MessageContent::MessageText(m) = content else {
+            return None;
+        };
+        if m.web_page.is_some() {
+            return None;
+        }
+        m.text.entities.iter().find_map(|e| match &e.r#type {
+            TextEntityType::Url => Some(
+                m.text
+                    .text
+                    .encode_utf16()
+                    .skip(e.offset as usize)
+                    .take(e.length as usize)
+                    .collect::<Vec<u16>>(),
+            )
+            .map(|url| String::from_utf16_lossy(&url)),
+            TextEntityType::TextUrl(text_url) => Some(text_url.url.clone()),
+            _ => None,
+        })
+    }
+
     fn message_content_lines(content: &MessageContent) -> Vec<Line<'static>> {
         match content {
             MessageContent::MessageText(m) => Self::format_message_content(&m.text),
-            MessageContent::MessageAudio(_) => vec![Line::from("🎵 Audio")],
-            MessageContent::MessagePhoto(_) => vec![Line::from("📷 Photo")],
-            MessageContent::MessageSticker(_) => vec![Line::from("🎨 Sticker")],
-            MessageContent::MessageVideo(_) => vec![Line::from("🎥 Video")],
-            MessageContent::MessageAnimation(_) => vec![Line::from("🎞️ Animation")],
-            MessageContent::MessageVoiceNote(_) => vec![Line::from("🎤 Voice Note")],
-            MessageContent::MessageDocument(_) => vec![Line::from("📄 Document")],
+            MessageContent::MessageAudio(m) => {
+                let audio = AudioMetadata::from(&m.audio);
+                let mut lines = vec![Line::from(format!(
+                    "🎵 {} ({})",
+                    audio.name(),
+                    audio.duration()
+                ))];
+                if !m.caption.text.is_empty() {
+                    lines.extend(Self::format_message_content(&m.caption));
+                }
+                lines
+            }
+            MessageContent::MessagePhoto(m) if m.is_secret => {
+                vec![Line::from("🔥 Self-destructing photo, open it to view it")]
+            }
+            MessageContent::MessagePhoto(m) => Self::media_lines("📷 Photo", &m.caption),
+            MessageContent::MessageExpiredPhoto => vec![Line::from("🔥 Photo expired")],
+            // The image of a sticker is drawn from its file or its still
+            // thumbnail, like the first frame of an animated sticker, so its
+            // emoji and format are the text shown with it.
+            MessageContent::MessageSticker(m) => vec![Line::from(format!(
+                "🎨 Sticker {}{}",
+                m.sticker.emoji,
+                match m.sticker.format {
+                    StickerFormat::Tgs => " (animated)",
+                    StickerFormat::Webm => " (video)",
+                    StickerFormat::Webp => "",
+                }
+            ))],
+            MessageContent::MessageVideo(m) if m.is_secret => {
+                vec![Line::from("🔥 Self-destructing video, open it to view it")]
+            }
+            MessageContent::MessageVideo(m) => Self::media_lines("🎥 Video", &m.caption),
+            MessageContent::MessageExpiredVideo => vec![Line::from("🔥 Video expired")],
+            MessageContent::MessageAnimation(m) => Self::media_lines("🎞️ Animation", &m.caption),
+            MessageContent::MessageVoiceNote(m) => {
+                let voice_note = AudioMetadata::from(&m.voice_note);
+                let mut lines = vec![Line::from(format!(
+                    "🎤 Voice Note ({})",
+                    voice_note.duration()
+                ))];
+                if !m.caption.text.is_empty() {
+                    lines.extend(Self::format_message_content(&m.caption));
+                }
+                lines
+            }
+            MessageContent::MessageDocument(m) => Self::media_lines("📄 Document", &m.caption),
+            MessageContent::MessageInvoice(m) => {
+                let test = if m.is_test { " (test)" } else { "" };
+                let mut lines = vec![Line::from(format!("🧾 Invoice{}: {}", test, m.title))];
+                if !m.description.text.is_empty() {
+                    lines.extend(Self::format_message_content(&m.description));
+                }
+                lines.push(Line::from(format!(
+                    "Total: {}",
+                    format_amount(m.total_amount, &m.currency)
+                )));
+                lines.push(Line::from(if m.receipt_message_id != 0 {
+                    "Paid, the receipt is in the message details"
+                } else {
+                    "Pay it in the Telegram app of another device"
+                }));
+                lines
+            }
+            MessageContent::MessagePaymentSuccessful(m) => vec![Line::from(format!(
+                "💳 Payment of {}{}",
+                format_amount(m.total_amount, &m.currency),
+                if m.invoice_name.is_empty() {
+                    String::new()
+                } else {
+                    format!(" for {}", m.invoice_name)
+                }
+            ))],
+            MessageContent::MessageAnimatedEmoji(m) => Self::big_emoji_lines(&m.emoji, None),
+            MessageContent::MessagePinMessage(_) => vec![Line::from("📌 Pinned a message")],
             _ => vec![Line::from("")],
         }
     }
 
+    /// Get the lines of a photo, a video, an animation or a document: its
+    /// kind, and its caption under it if it has one.
+    fn media_lines(kind: &'static str, caption: &FormattedText) -> Vec<Line<'static>> {
+        let mut lines = vec![Line::from(kind)];
+        if !caption.text.is_empty() {
+            lines.extend(Self::format_message_content(caption));
+        }
+        lines
+    }
+
+    /// Get the caption of a media message, which can be edited. It is empty
+    /// if the message has none, and `None` if the message is not a media
+    /// message, or a self-destructing one.
+    fn caption_of(content: &MessageContent) -> Option<FormattedText> {
+        match content {
+            MessageContent::MessagePhoto(m) if !m.is_secret => Some(m.caption.clone()),
+            MessageContent::MessageVideo(m) if !m.is_secret => Some(m.caption.clone()),
+            MessageContent::MessageAnimation(m) if !m.is_secret => Some(m.caption.clone()),
+            MessageContent::MessageDocument(m) => Some(m.caption.clone()),
+            MessageContent::MessageAudio(m) => Some(m.caption.clone()),
+            MessageContent::MessageVoiceNote(m) => Some(m.caption.clone()),
+            _ => None,
+        }
+    }
+
     fn from_span_to_lines(span: Span) -> Vec<Line<'static>> {
         span.content
             .split('\n')
@@ -211,17 +1167,21 @@ impl MessageEntry {
             .collect::<Vec<Line>>()
     }
 
+    /// Build lines from spans, splitting the spans at their line breaks, so
+    /// that the spans between two line breaks make a line.
     fn from_spans_to_lines(spans: Vec<Span>) -> Vec<Line<'static>> {
-        let vec = spans
-            .iter()
-            .filter(|s| !s.content.is_empty())
-            .flat_map(|s| Self::from_span_to_lines(s.clone()))
-            .collect::<Vec<Line>>();
-        if vec.is_empty() {
-            vec![Line::from("")]
-        } else {
-            vec
+        let mut lines = vec![Line::default()];
+        for span in spans.iter().filter(|s| !s.content.is_empty()) {
+            for (i, part) in span.content.split('\n').enumerate() {
+                if i > 0 {
+                    lines.push(Line::default());
+                }
+                if let Some(line) = lines.last_mut().filter(|_| !part.is_empty()) {
+                    line.spans.push(Span::styled(part.to_owned(), span.style));
+                }
+            }
         }
+        lines
     }
 
     fn merge_two_style(a: Style, b: Style) -> Style {
@@ -239,49 +1199,118 @@ impl MessageEntry {
         content_style: Style,
         wrap_width: i32,
     ) -> Vec<Line<'static>> {
+        // The links are shown according to the configuration, while the
+        // message keeps them whole.
+        let (message_content, _) = url_display::display_lines(&self.message_content, *URL_DISPLAY);
         if wrap_width == -1 {
             // No wrap
-            self.message_content
+            message_content
                 .iter()
                 .map(|l| {
-                    l.iter()
-                        .map(|s| {
-                            Span::styled(
-                                s.content.clone(),
-                                Self::merge_two_style(s.style, content_style),
-                            )
+                    let text = l.iter().map(|s| s.content.as_ref()).collect::<String>();
+                    let graphemes = l
+                        .iter()
+                        .flat_map(|s| {
+                            let style = Self::merge_two_style(s.style, content_style);
+                            s.content
+                                .graphemes(true)
+                                .map(move |g| Span::styled(g.to_string(), style))
+                                .collect::<Vec<Span>>()
                         })
-                        .collect()
+                        .collect();
+                    Self::visual_line(graphemes, is_rtl_paragraph(&text), -1)
                 })
                 .collect::<Vec<Line>>()
         } else {
             // Wrap the text
             let mut lines = Vec::new();
-            let mut current_line = Line::default();
+            let mut current_line = Vec::new();
             let mut current_line_length = 0;
-            // for span in self.message_content.iter().flat_map(|l| l.iter()) {
-            for span in self.message_content.iter().flat_map(|l| l.iter()) {
-                for c in span.content.chars() {
-                    if c == ' ' && current_line_length >= wrap_width {
-                        lines.push(current_line);
-                        current_line = Line::default();
-                        current_line_length = 0;
+            // The spans of a line, like a bold word in a sentence, are
+            // wrapped together.
+            for line in message_content.iter() {
+                let text = line.iter().map(|s| s.content.as_ref()).collect::<String>();
+                let rtl = is_rtl_paragraph(&text);
+                for span in line.iter() {
+                    for grapheme in span.content.graphemes(true) {
+                        let width = grapheme_width(grapheme) as i32;
+                        // Words are wrapped on spaces, while wide characters,
+                        // like CJK ones, can be wrapped anywhere.
+                        if (grapheme == " " && current_line_length >= wrap_width)
+                            || (width > 1 && current_line_length + width > wrap_width)
+                        {
+                            lines.push(Self::visual_line(
+                                std::mem::take(&mut current_line),
+                                rtl,
+                                wrap_width,
+                            ));
+                            current_line_length = 0;
+                        }
+                        current_line.push(Span::styled(
+                            grapheme.to_string(),
+                            Self::merge_two_style(span.style, content_style),
+                        ));
+                        current_line_length += width;
                     }
-                    current_line.spans.push(Span::styled(
-                        c.to_string(),
-                        Self::merge_two_style(span.style, content_style),
-                    ));
-                    current_line_length += 1;
                 }
-                lines.push(current_line);
-                current_line = Line::default();
+                lines.push(Self::visual_line(
+                    std::mem::take(&mut current_line),
+                    rtl,
+                    wrap_width,
+                ));
                 current_line_length = 0;
             }
             lines
         }
     }
 
-    fn format_message_content(message: &FormattedText) -> Vec<Line<'static>> {
+    /// Build a line in visual order from its grapheme clusters in logical
+    /// order, by applying the bidirectional algorithm.
+    /// A right to left line is aligned to the right of the message, padding
+    /// it on the left up to the wrap width.
+    ///
+    /// # Arguments
+    /// * `graphemes` - The grapheme clusters of the line, one per span.
+    /// * `rtl` - Whether the paragraph of the line is right to left.
+    /// * `wrap_width` - The wrap width of the message, or -1 if not wrapped.
+    ///
+    /// # Returns
+    /// * `Line<'static>` - The line in visual order.
+    fn visual_line(graphemes: Vec<Span<'static>>, rtl: bool, wrap_width: i32) -> Line<'static> {
+        let order = visual_order(
+            &graphemes
+                .iter()
+                .map(|g| g.content.as_ref())
+                .collect::<Vec<&str>>(),
+            rtl,
+        );
+        let mut spans = Vec::with_capacity(graphemes.len() + 1);
+        if rtl && wrap_width > 0 {
+            let width = graphemes
+                .iter()
+                .map(|g| grapheme_width(&g.content))
+                .sum::<usize>();
+            let padding = (wrap_width as usize).saturating_sub(width);
+            if padding > 0 {
+                spans.push(Span::raw(" ".repeat(padding)));
+            }
+        }
+        let mut graphemes = graphemes.into_iter().map(Some).collect::<Vec<_>>();
+        spans.extend(order.into_iter().filter_map(|(i, _)| graphemes[i].take()));
+        Line::from(spans)
+    }
+
+    /// Build the lines of a formatted text, styling each character with all
+    /// the entities that cover it, like a bold link. The offsets of the
+    /// entities are in UTF-16 code units. A custom emoji covers its unicode
+    /// fallback, which is shown in its place.
+    ///
+    /// # Arguments
+    /// * `message` - The formatted text.
+    ///
+    /// # Returns
+    /// * `Vec<Line<'static>>` - The lines of the text.
+    pub fn format_message_content(message: &FormattedText) -> Vec<Line<'static>> {
         let text = &message.text;
         let entities = &message.entities;
 
@@ -290,126 +1319,100 @@ impl MessageEntry {
         }
 
         let mut message_vec = Vec::new();
-        entities.iter().for_each(|e| {
-            let offset = e.offset as usize;
-            let length = e.length as usize;
-            message_vec.push(Span::raw(text.chars().take(offset).collect::<String>()));
-            match &e.r#type {
-                tdlib_rs::enums::TextEntityType::Italic => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::ITALIC),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::Bold => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::BOLD),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::Underline => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::UNDERLINED),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::Strikethrough => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::CROSSED_OUT),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::Url => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::UNDERLINED),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::TextUrl(text_url) => {
-                    message_vec.push(Span::styled(
-                        text_url.url.clone(),
-                        Style::default().add_modifier(Modifier::UNDERLINED),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::EmailAddress => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::UNDERLINED),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::Mention => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::BOLD),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::Hashtag => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::BOLD),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::PhoneNumber => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::UNDERLINED),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::MentionName(mention_name) => {
-                    message_vec.push(Span::styled(
-                        // TODO: Fix from user_id to username
-                        mention_name.user_id.to_string(),
-                        Style::default().add_modifier(Modifier::BOLD),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::Code => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::DIM),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::Pre => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::DIM),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::PreCode(_pre_code) => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::DIM),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::Cashtag => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::BOLD),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::BankCardNumber => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::UNDERLINED),
-                    ));
-                }
-                tdlib_rs::enums::TextEntityType::BlockQuote => {
-                    message_vec.push(Span::styled(
-                        text.chars().skip(offset).take(length).collect::<String>(),
-                        Style::default().add_modifier(Modifier::DIM),
-                    ));
+        let mut current = String::new();
+        let mut current_style = Style::default();
+        let mut offset = 0;
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            let covering = entities
+                .iter()
+                .filter(|e| e.offset <= offset && offset < e.offset + e.length)
+                .collect::<Vec<_>>();
+            let style = covering.iter().fold(Style::default(), |style, e| {
+                style.patch(Self::entity_style(&e.r#type))
+            });
+            if style != current_style && !current.is_empty() {
+                message_vec.push(Span::styled(std::mem::take(&mut current), current_style));
+            }
+            current_style = style;
+            offset += c.len_utf16() as i32;
+            // The text of some entities is replaced, like a text link by its
+            // URL.
+            let replacement = covering.into_iter().find_map(|e| {
+                Self::entity_replacement(&e.r#type).map(|replacement| (e, replacement))
+            });
+            let Some((entity, replacement)) = replacement else {
+                current.push(c);
+                continue;
+            };
+            if !current.is_empty() {
+                message_vec.push(Span::styled(std::mem::take(&mut current), current_style));
+            }
+            message_vec.push(Span::styled(replacement, style));
+            while offset < entity.offset + entity.length {
+                match chars.next() {
+                    Some(c) => offset += c.len_utf16() as i32,
+                    None => break,
                 }
-                tdlib_rs::enums::TextEntityType::Spoiler => {}
-                tdlib_rs::enums::TextEntityType::MediaTimestamp(_) => {}
-                tdlib_rs::enums::TextEntityType::CustomEmoji(_) => {}
-                tdlib_rs::enums::TextEntityType::BotCommand => {}
             }
-            message_vec.push(Span::raw(
-                text.chars().skip(offset + length).collect::<String>(),
-            ));
-        });
+        }
+        message_vec.push(Span::styled(current, current_style));
 
         Self::from_spans_to_lines(message_vec)
     }
+
+    /// Get the style of the text of an entity.
+    ///
+    /// # Arguments
+    /// * `entity_type` - The type of the entity.
+    ///
+    /// # Returns
+    /// * `Style` - The style, applied over the styles of the other entities
+    ///   covering the same text.
+    fn entity_style(entity_type: &TextEntityType) -> Style {
+        let modifier = match entity_type {
+            TextEntityType::Italic => Modifier::ITALIC,
+            TextEntityType::Bold
+            | TextEntityType::Mention
+            | TextEntityType::Hashtag
+            | TextEntityType::MentionName(_)
+            | TextEntityType::Cashtag => Modifier::BOLD,
+            TextEntityType::Underline
+            | TextEntityType::Url
+            | TextEntityType::TextUrl(_)
+            | TextEntityType::EmailAddress
+            | TextEntityType::PhoneNumber
+            | TextEntityType::BankCardNumber => Modifier::UNDERLINED,
+            TextEntityType::Strikethrough => Modifier::CROSSED_OUT,
+            TextEntityType::Code
+            | TextEntityType::Pre
+            | TextEntityType::PreCode(_)
+            | TextEntityType::BlockQuote => Modifier::DIM,
+            TextEntityType::Spoiler => Modifier::HIDDEN,
+            TextEntityType::MediaTimestamp(_)
+            | TextEntityType::CustomEmoji(_)
+            | TextEntityType::BotCommand => Modifier::empty(),
+        };
+        Style::default().add_modifier(modifier)
+    }
+
+    /// Get the text shown in place of the text of an entity, if it is
+    /// replaced.
+    ///
+    /// # Arguments
+    /// * `entity_type` - The type of the entity.
+    ///
+    /// # Returns
+    /// * `Option<String>` - The URL of a text link, or the id of the user of
+    ///   a mention by name, `None` if the text is shown.
+    fn entity_replacement(entity_type: &TextEntityType) -> Option<String> {
+        match entity_type {
+            TextEntityType::TextUrl(text_url) => Some(text_url.url.clone()),
+            // TODO: Fix from user_id to username
+            TextEntityType::MentionName(mention_name) => Some(mention_name.user_id.to_string()),
+            _ => None,
+        }
+    }
 }
 impl From<&tdlib_rs::types::Message> for MessageEntry {
     fn from(message: &tdlib_rs::types::Message) -> Self {
@@ -433,6 +1436,100 @@ impl From<&tdlib_rs::types::Message> for MessageEntry {
                 timestamp: message.date,
             },
             is_edited: message.edit_date != 0,
+            preview_url: Self::preview_url(&message.content),
+            big_emoji: Self::big_emoji(&message.content),
+            forward_info: message.forward_info.as_ref().map(|info| info.into()),
+            audio: Self::audio_metadata(&message.content),
+            reactions: Self::reaction_counts(message.interaction_info.as_ref()),
+            is_channel_post: message.is_channel_post,
+            comment_count: Self::comment_count_of(
+                message.is_channel_post,
+                message.interaction_info.as_ref(),
+            ),
+            image_file: Self::image_file(&message.content),
+            inline_keyboard: inline_keyboard(message.reply_markup.as_ref()),
+            pinned_message_id: match &message.content {
+                MessageContent::MessagePinMessage(m) => Some(m.message_id),
+                _ => None,
+            },
+            caption: Self::caption_of(&message.content),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date_time_in_the_timezone() {
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        assert_eq!(
+            DateTimeEntry::parse_date_time_in("2024-05-01", &offset),
+            Some(1714514400)
+        );
+        assert_eq!(
+            DateTimeEntry::parse_date_time_in(" 2024-05-01 14:03 ", &offset),
+            Some(1714564980)
+        );
+        assert_eq!(
+            DateTimeEntry::parse_date_time_in("2024-13-01", &offset),
+            None
+        );
+        assert_eq!(
+            DateTimeEntry::parse_date_time_in("yesterday", &offset),
+            None
+        );
+    }
+
+    #[test]
+    fn test_big_emoji_lines_fit_the_width_of_the_emoji() {
+        let lines = |emoji: &str| {
+            MessageEntry::big_emoji_lines(emoji, None)
+                .iter()
+                .map(|line| line.to_string())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(lines("😀")[2], "│   😀   │");
+        // A narrow emoji is centered in the same box.
+        assert_eq!(lines("☺")[2], "│   ☺    │");
+        for emoji in ["😀", "☺", "👨‍👩‍👧"] {
+            assert!(lines(emoji)
+                .iter()
+                .all(|line| display_width(line) == BIG_EMOJI_BOX_WIDTH + 2));
+        }
+    }
+
+    #[test]
+    fn test_format_message_content_with_custom_emojis() {
+        use tdlib_rs::types::{TextEntity, TextEntityTypeCustomEmoji};
+        // The offsets are in UTF-16 code units, and the cat is two of them.
+        let message = FormattedText {
+            text: "Hi 🐱 bold\nend".to_string(),
+            entities: vec![
+                TextEntity {
+                    offset: 3,
+                    length: 2,
+                    r#type: TextEntityType::CustomEmoji(TextEntityTypeCustomEmoji {
+                        custom_emoji_id: 1,
+                    }),
+                },
+                TextEntity {
+                    offset: 6,
+                    length: 4,
+                    r#type: TextEntityType::Bold,
+                },
+            ],
+        };
+        assert_eq!(
+            MessageEntry::format_message_content(&message),
+            vec![
+                Line::from(vec![
+                    Span::raw("Hi 🐱 "),
+                    Span::styled("bold", Style::default().add_modifier(Modifier::BOLD)),
+                ]),
+                Line::from("end"),
+            ]
+        );
+    }
+}
@@ -1,5 +1,16 @@
+pub mod accounts;
+pub mod attachments;
+pub mod blocklist;
+pub mod history_pages;
+pub mod inline_keyboard;
+pub mod invoice;
 pub mod message_entry;
+pub mod offline_gap;
 pub mod ordered_chat;
+pub mod presence;
+pub mod send_queue;
+pub mod sync_state;
 pub mod td_enums;
 pub mod tg_backend;
 pub mod tg_context;
+pub mod typing;
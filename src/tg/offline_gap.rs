@@ -0,0 +1,71 @@
+use super::message_entry::MessageEntry;
+
+/// The divider shown above the messages of the open chat received while
+/// `tgt` was offline.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct OfflineGap {
+    /// The id of the chat.
+    pub chat_id: i64,
+    /// The id of the oldest message received while offline, above which the
+    /// divider is shown.
+    pub first_message_id: i64,
+    /// The number of messages received while offline.
+    pub count: usize,
+    /// Whether the whole gap was fetched. When it is too long, only its most
+    /// recent messages are fetched.
+    pub complete: bool,
+}
+
+impl OfflineGap {
+    /// Get the text of the divider, like "12 new messages while offline".
+    ///
+    /// # Returns
+    /// * `String` - The text of the divider.
+    pub fn text(&self) -> String {
+        format!(
+            "{}{} new message{} while offline",
+            self.count,
+            if self.complete { "" } else { "+" },
+            if self.count == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Insert the messages of a gap in the messages of the open chat, keeping
+/// them sorted from the most recent. The messages that are already present,
+/// for example because TDLib sent them while updating, are not duplicated.
+///
+/// # Arguments
+/// * `messages` - The messages of the open chat, the most recent first.
+/// * `missed` - The messages fetched for the gap.
+/// * `last_message_id` - The id of the last message known before going
+///   offline.
+///
+/// # Returns
+/// * `Option<(i64, usize)>` - The id of the oldest message received while
+///   offline and the number of those messages, or `None` if nothing was
+///   missed.
+pub fn merge_gap(
+    messages: &mut Vec<MessageEntry>,
+    missed: Vec<MessageEntry>,
+    last_message_id: i64,
+) -> Option<(i64, usize)> {
+    for entry in missed {
+        if messages.iter().any(|m| m.id() == entry.id()) {
+            continue;
+        }
+        let index = messages
+            .iter()
+            .position(|m| m.id() < entry.id())
+            .unwrap_or(messages.len());
+        messages.insert(index, entry);
+    }
+    let new_messages = messages
+        .iter()
+        .filter(|m| m.id() > last_message_id)
+        .map(|m| m.id());
+    let count = new_messages.clone().count();
+    new_messages
+        .min()
+        .map(|first_message_id| (first_message_id, count))
+}
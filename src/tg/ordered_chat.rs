@@ -28,6 +28,15 @@ impl PartialOrd for OrderedChat {
 
 impl Ord for OrderedChat {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // The pinned chats are first, in the order of TDLib, even if a chat
+        // not pinned has a more recent message.
+        if self.position.is_pinned != other.position.is_pinned {
+            if self.position.is_pinned {
+                return core::cmp::Ordering::Less;
+            } else {
+                return core::cmp::Ordering::Greater;
+            }
+        }
         if self.position.order != other.position.order {
             if self.position.order > other.position.order {
                 return core::cmp::Ordering::Less;
@@ -0,0 +1,129 @@
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `PresenceChange` is an enum that represents a change of the online status
+/// sent to Telegram.
+pub enum PresenceChange {
+    /// The user came back, so they are online again.
+    Online,
+    /// The user has been idle for too long, so they are no longer online.
+    Away,
+}
+
+#[derive(Debug)]
+/// `Presence` is a struct that represents the activity of the user in the
+/// terminal. The user is away after some time without input, and back at
+/// the next input.
+pub struct Presence {
+    /// The time of the last input of the user.
+    last_activity: Instant,
+    /// The time without input after which the user is away, `None` to never
+    /// be away.
+    away_after: Option<Duration>,
+    /// Indicates whether the user is away.
+    away: bool,
+}
+
+impl Default for Presence {
+    fn default() -> Self {
+        Self {
+            last_activity: Instant::now(),
+            away_after: None,
+            away: false,
+        }
+    }
+}
+
+impl Presence {
+    /// Set the time without input after which the user is away.
+    ///
+    /// # Arguments
+    /// * `minutes` - The number of minutes, `0` to never be away.
+    pub fn set_away_after_minutes(&mut self, minutes: u64) {
+        self.away_after = (minutes > 0).then(|| Duration::from_secs(minutes * 60));
+    }
+
+    /// Check whether the user is away.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the user is away.
+    pub fn is_away(&self) -> bool {
+        self.away
+    }
+
+    /// Record an input of the user.
+    ///
+    /// # Arguments
+    /// * `now` - The time of the input.
+    ///
+    /// # Returns
+    /// * `Option<PresenceChange>` - `Online` if the user was away.
+    pub fn record_activity(&mut self, now: Instant) -> Option<PresenceChange> {
+        self.last_activity = now;
+        if self.away {
+            self.away = false;
+            Some(PresenceChange::Online)
+        } else {
+            None
+        }
+    }
+
+    /// Check whether the user has just become away.
+    ///
+    /// # Arguments
+    /// * `now` - The current time.
+    ///
+    /// # Returns
+    /// * `Option<PresenceChange>` - `Away` if the user has been idle for too
+    ///   long and was not away yet.
+    pub fn check_idle(&mut self, now: Instant) -> Option<PresenceChange> {
+        let away_after = self.away_after?;
+        if self.away || now.saturating_duration_since(self.last_activity) < away_after {
+            return None;
+        }
+        self.away = true;
+        Some(PresenceChange::Away)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_away_after_the_idle_time_and_back_at_the_next_input() {
+        let start = Instant::now();
+        let mut presence = Presence::default();
+        presence.set_away_after_minutes(5);
+        presence.record_activity(start);
+        assert_eq!(presence.check_idle(start + Duration::from_secs(299)), None);
+        assert_eq!(
+            presence.check_idle(start + Duration::from_secs(300)),
+            Some(PresenceChange::Away)
+        );
+        assert!(presence.is_away());
+        assert_eq!(presence.check_idle(start + Duration::from_secs(400)), None);
+        assert_eq!(
+            presence.record_activity(start + Duration::from_secs(500)),
+            Some(PresenceChange::Online)
+        );
+        assert_eq!(
+            presence.record_activity(start + Duration::from_secs(501)),
+            None
+        );
+        assert!(!presence.is_away());
+    }
+
+    #[test]
+    fn test_never_away_when_disabled() {
+        let start = Instant::now();
+        let mut presence = Presence::default();
+        presence.set_away_after_minutes(0);
+        presence.record_activity(start);
+        assert_eq!(
+            presence.check_idle(start + Duration::from_secs(86400)),
+            None
+        );
+        assert!(!presence.is_away());
+    }
+}
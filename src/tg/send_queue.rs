@@ -0,0 +1,208 @@
+use super::attachments::SelfDestruct;
+use std::collections::VecDeque;
+
+/// A file upload of the outgoing pipeline.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PendingUpload {
+    /// The identifier of the upload in the queue.
+    pub id: u64,
+    /// The id of the chat the file is sent to.
+    pub chat_id: i64,
+    /// The path of the file.
+    pub path: String,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// The caption sent with the file, if any.
+    pub caption: Option<String>,
+    /// The self-destruction of the photo or the video, if any.
+    pub self_destruct: Option<SelfDestruct>,
+    /// The number of bytes already uploaded.
+    pub uploaded: u64,
+    /// The temporary id of the message, once the upload is started.
+    pub message_id: Option<i64>,
+    /// The TDLib id of the file, once the upload is started.
+    pub file_id: Option<i32>,
+}
+
+/// The outgoing pipeline of the messages.
+/// Text messages do not go through the queue and are sent immediately, so
+/// that they are never blocked by large uploads. Uploads are started
+/// concurrently up to `max_parallel_uploads`, the others wait in order.
+#[derive(Debug, Default)]
+pub struct SendQueue {
+    /// The identifier of the next upload.
+    next_id: u64,
+    /// The uploads waiting to be started.
+    queued: VecDeque<PendingUpload>,
+    /// The uploads in progress.
+    active: Vec<PendingUpload>,
+    /// The maximum number of uploads in progress at the same time.
+    max_parallel_uploads: usize,
+}
+
+impl SendQueue {
+    /// Set the maximum number of uploads in progress at the same time.
+    /// It is always at least one.
+    pub fn set_max_parallel_uploads(&mut self, max_parallel_uploads: usize) {
+        self.max_parallel_uploads = max_parallel_uploads.max(1);
+    }
+
+    /// Add an upload at the end of the queue and return its identifier.
+    pub fn enqueue_upload(
+        &mut self,
+        chat_id: i64,
+        path: String,
+        size: u64,
+        caption: Option<String>,
+        self_destruct: Option<SelfDestruct>,
+    ) -> u64 {
+        self.next_id += 1;
+        self.queued.push_back(PendingUpload {
+            id: self.next_id,
+            chat_id,
+            path,
+            size,
+            caption,
+            self_destruct,
+            uploaded: 0,
+            message_id: None,
+            file_id: None,
+        });
+        self.next_id
+    }
+
+    /// Take the next upload that can be started, if a lane is free.
+    /// The upload is marked as in progress.
+    pub fn next_ready(&mut self) -> Option<PendingUpload> {
+        if self.active.len() >= self.max_parallel_uploads.max(1) {
+            return None;
+        }
+        let upload = self.queued.pop_front()?;
+        self.active.push(upload.clone());
+        Some(upload)
+    }
+
+    /// Record the message and the file of a started upload.
+    pub fn set_started(&mut self, id: u64, message_id: i64, file_id: Option<i32>) {
+        if let Some(upload) = self.active.iter_mut().find(|upload| upload.id == id) {
+            upload.message_id = Some(message_id);
+            upload.file_id = file_id;
+        }
+    }
+
+    /// Update the progress of the upload of a file.
+    pub fn set_progress(&mut self, file_id: i32, uploaded: u64) {
+        if let Some(upload) = self
+            .active
+            .iter_mut()
+            .find(|upload| upload.file_id == Some(file_id))
+        {
+            upload.uploaded = uploaded;
+        }
+    }
+
+    /// Remove an upload that could not be started.
+    pub fn remove(&mut self, id: u64) {
+        self.active.retain(|upload| upload.id != id);
+        self.queued.retain(|upload| upload.id != id);
+    }
+
+    /// Remove the upload of a message that has been sent or failed.
+    /// Returns the upload, if the message was an upload of the queue, so that
+    /// the next upload can be started.
+    pub fn finish(&mut self, message_id: i64) -> Option<PendingUpload> {
+        let index = self
+            .active
+            .iter()
+            .position(|upload| upload.message_id == Some(message_id))?;
+        Some(self.active.remove(index))
+    }
+
+    /// Get the uploads in progress.
+    pub fn active(&self) -> &[PendingUpload] {
+        &self.active
+    }
+
+    /// Get all the uploads, in progress and waiting to be started.
+    pub fn uploads(&self) -> impl Iterator<Item = &PendingUpload> {
+        self.active.iter().chain(self.queued.iter())
+    }
+
+    /// Get the number of uploads waiting to be started.
+    pub fn queued_len(&self) -> usize {
+        self.queued.len()
+    }
+
+    /// Get the progress of all the uploads in progress, as the number of
+    /// uploaded bytes and the total number of bytes.
+    pub fn progress(&self) -> (u64, u64) {
+        self.active.iter().fold((0, 0), |(uploaded, size), upload| {
+            (uploaded + upload.uploaded, size + upload.size)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(max_parallel_uploads: usize, len: u64) -> SendQueue {
+        let mut send_queue = SendQueue::default();
+        send_queue.set_max_parallel_uploads(max_parallel_uploads);
+        for i in 0..len {
+            send_queue.enqueue_upload(1, format!("file{}", i), 100, None, None);
+        }
+        send_queue
+    }
+
+    #[test]
+    fn test_uploads_start_in_order_up_to_the_lanes() {
+        let mut send_queue = queue(2, 3);
+        assert_eq!(send_queue.next_ready().map(|upload| upload.id), Some(1));
+        assert_eq!(send_queue.next_ready().map(|upload| upload.id), Some(2));
+        // Both lanes are busy, so the third upload waits.
+        assert_eq!(send_queue.next_ready(), None);
+        assert_eq!(send_queue.active().len(), 2);
+        assert_eq!(send_queue.queued_len(), 1);
+        assert_eq!(
+            send_queue
+                .uploads()
+                .map(|upload| upload.path.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["file0", "file1", "file2"]
+        );
+    }
+
+    #[test]
+    fn test_at_least_one_lane() {
+        let mut send_queue = queue(0, 2);
+        assert!(send_queue.next_ready().is_some());
+        assert_eq!(send_queue.next_ready(), None);
+    }
+
+    #[test]
+    fn test_finish_frees_a_lane() {
+        let mut send_queue = queue(1, 2);
+        let upload = send_queue.next_ready().unwrap();
+        send_queue.set_started(upload.id, 42, Some(7));
+        send_queue.set_progress(7, 60);
+        assert_eq!(send_queue.progress(), (60, 100));
+        assert_eq!(send_queue.next_ready(), None);
+        // A message that is not an upload of the queue frees nothing.
+        assert_eq!(send_queue.finish(43), None);
+        assert_eq!(send_queue.finish(42).map(|upload| upload.id), Some(1));
+        assert_eq!(send_queue.next_ready().map(|upload| upload.id), Some(2));
+    }
+
+    #[test]
+    fn test_remove_frees_a_lane() {
+        let mut send_queue = queue(1, 3);
+        let upload = send_queue.next_ready().unwrap();
+        // A waiting upload is removed without starting it.
+        send_queue.remove(2);
+        assert_eq!(send_queue.queued_len(), 1);
+        send_queue.remove(upload.id);
+        assert!(send_queue.active().is_empty());
+        assert_eq!(send_queue.next_ready().map(|upload| upload.id), Some(3));
+    }
+}
@@ -0,0 +1,106 @@
+use tdlib_rs::enums::ConnectionState;
+
+#[derive(Debug, Default)]
+/// `SyncState` is a struct that represents how far the chat list and the read
+/// states of the chats are from being in sync with Telegram. They are syncing
+/// from the start until the connection is ready for the first time, and while
+/// Telegram sends the updates missed while offline.
+pub struct SyncState {
+    /// Indicates whether the connection has been ready since the start.
+    synced_once: bool,
+    /// Indicates whether Telegram is sending the updates missed while
+    /// offline.
+    updating: bool,
+    /// The number of read states of the chats received since the syncing
+    /// started, like the messages read on another device.
+    read_states: usize,
+}
+
+impl SyncState {
+    /// Record a change of the state of the connection to Telegram.
+    ///
+    /// # Arguments
+    /// * `state` - The new state of the connection.
+    pub fn set_connection_state(&mut self, state: &ConnectionState) {
+        let updating = matches!(state, ConnectionState::Updating);
+        if updating && !self.updating && self.synced_once {
+            self.read_states = 0;
+        }
+        self.updating = updating;
+        if matches!(state, ConnectionState::Ready) {
+            self.synced_once = true;
+        }
+    }
+
+    /// Record a read state of a chat received from Telegram, like its unread
+    /// messages or mentions.
+    pub fn record_read_state(&mut self) {
+        if self.is_syncing() {
+            self.read_states += 1;
+        }
+    }
+
+    /// Check whether the chat list and the read states are still syncing.
+    ///
+    /// # Returns
+    /// * `bool` - Whether they are syncing.
+    pub fn is_syncing(&self) -> bool {
+        !self.synced_once || self.updating
+    }
+
+    /// Get the progress of the syncing, as shown in the status bar.
+    ///
+    /// # Arguments
+    /// * `chats` - The number of chats of the chat list loaded.
+    ///
+    /// # Returns
+    /// * `Option<String>` - The progress, `None` if nothing is syncing.
+    pub fn progress(&self, chats: usize) -> Option<String> {
+        self.is_syncing().then(|| {
+            format!(
+                "chats loaded: {}, read states updated: {}",
+                chats, self.read_states
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syncing_until_the_connection_is_ready() {
+        let mut sync_state = SyncState::default();
+        assert!(sync_state.is_syncing());
+        sync_state.set_connection_state(&ConnectionState::Connecting);
+        sync_state.set_connection_state(&ConnectionState::Updating);
+        sync_state.record_read_state();
+        sync_state.record_read_state();
+        assert_eq!(
+            sync_state.progress(30).as_deref(),
+            Some("chats loaded: 30, read states updated: 2")
+        );
+        sync_state.set_connection_state(&ConnectionState::Ready);
+        sync_state.record_read_state();
+        assert_eq!(sync_state.progress(30), None);
+    }
+
+    #[test]
+    fn test_syncing_again_after_a_reconnection() {
+        let mut sync_state = SyncState::default();
+        sync_state.set_connection_state(&ConnectionState::Updating);
+        sync_state.record_read_state();
+        sync_state.set_connection_state(&ConnectionState::Ready);
+        // Losing the connection does not sync anything until Telegram sends
+        // the missed updates.
+        sync_state.set_connection_state(&ConnectionState::WaitingForNetwork);
+        assert!(!sync_state.is_syncing());
+        sync_state.set_connection_state(&ConnectionState::Updating);
+        sync_state.record_read_state();
+        assert_eq!(
+            sync_state.progress(50).as_deref(),
+            Some("chats loaded: 50, read states updated: 1")
+        );
+    }
+}
@@ -27,6 +27,8 @@ pub struct TdMessageReplyToMessage {
     pub chat_id: i64,
     /// The identifier of the replied message
     pub message_id: i64,
+    /// The part of the replied message that is quoted, if the quote was chosen manually
+    pub quote: Option<TdTextQuote>,
 }
 
 impl From<&TdMessageReplyToMessage> for tdlib_rs::types::InputMessageReplyToMessage {
@@ -34,7 +36,7 @@ impl From<&TdMessageReplyToMessage> for tdlib_rs::types::InputMessageReplyToMess
         tdlib_rs::types::InputMessageReplyToMessage {
             chat_id: reply_to_message.chat_id,
             message_id: reply_to_message.message_id,
-            quote: None,
+            quote: reply_to_message.quote.as_ref().map(|quote| quote.into()),
         }
     }
 }
@@ -44,6 +46,120 @@ impl From<&tdlib_rs::types::MessageReplyToMessage> for TdMessageReplyToMessage {
         TdMessageReplyToMessage {
             chat_id: reply_to_message.chat_id,
             message_id: reply_to_message.message_id,
+            quote: reply_to_message
+                .quote
+                .as_ref()
+                .filter(|quote| quote.is_manual)
+                .map(|quote| quote.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct TdTextQuote {
+    /// The text of the quote
+    pub text: String,
+    /// The approximate position of the quote in the quoted message, in UTF-16 code units
+    pub position: i32,
+}
+
+impl From<&TdTextQuote> for tdlib_rs::types::InputTextQuote {
+    fn from(quote: &TdTextQuote) -> Self {
+        tdlib_rs::types::InputTextQuote {
+            text: tdlib_rs::types::FormattedText {
+                text: quote.text.clone(),
+                entities: vec![],
+            },
+            position: quote.position,
+        }
+    }
+}
+
+impl From<&tdlib_rs::types::TextQuote> for TdTextQuote {
+    fn from(quote: &tdlib_rs::types::TextQuote) -> Self {
+        TdTextQuote {
+            text: quote.text.text.clone(),
+            position: quote.position,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum TdMessageOrigin {
+    /// The message was originally sent by a known user
+    User(i64),
+    /// The message was originally sent by a user, who hid their account
+    HiddenUser(String),
+    /// The message was originally sent on behalf of a chat
+    Chat {
+        sender_chat_id: i64,
+        author_signature: String,
+    },
+    /// The message was originally a post in a channel
+    Channel {
+        chat_id: i64,
+        message_id: i64,
+        author_signature: String,
+    },
+}
+
+impl From<&tdlib_rs::enums::MessageOrigin> for TdMessageOrigin {
+    fn from(origin: &tdlib_rs::enums::MessageOrigin) -> Self {
+        match origin {
+            tdlib_rs::enums::MessageOrigin::User(user) => {
+                TdMessageOrigin::User(user.sender_user_id)
+            }
+            tdlib_rs::enums::MessageOrigin::HiddenUser(user) => {
+                TdMessageOrigin::HiddenUser(user.sender_name.clone())
+            }
+            tdlib_rs::enums::MessageOrigin::Chat(chat) => TdMessageOrigin::Chat {
+                sender_chat_id: chat.sender_chat_id,
+                author_signature: chat.author_signature.clone(),
+            },
+            tdlib_rs::enums::MessageOrigin::Channel(channel) => TdMessageOrigin::Channel {
+                chat_id: channel.chat_id,
+                message_id: channel.message_id,
+                author_signature: channel.author_signature.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct TdMessageForwardInfo {
+    /// The origin of the forwarded message
+    pub origin: TdMessageOrigin,
+    /// The point in time (Unix timestamp) when the message was originally sent
+    pub date: i32,
+    /// The chat and the message the message was forwarded from, known only for messages forwarded to Saved Messages
+    pub source: Option<(i64, i64)>,
+}
+
+impl TdMessageForwardInfo {
+    /// Get the chat and the message that can be opened to see the original message, if any
+    pub fn origin_message(&self) -> Option<(i64, i64)> {
+        match &self.origin {
+            TdMessageOrigin::Channel {
+                chat_id,
+                message_id,
+                ..
+            } => Some((*chat_id, *message_id)),
+            _ => self
+                .source
+                .filter(|(chat_id, message_id)| *chat_id != 0 && *message_id != 0),
+        }
+    }
+}
+
+impl From<&tdlib_rs::types::MessageForwardInfo> for TdMessageForwardInfo {
+    fn from(forward_info: &tdlib_rs::types::MessageForwardInfo) -> Self {
+        TdMessageForwardInfo {
+            origin: (&forward_info.origin).into(),
+            date: forward_info.date,
+            source: forward_info
+                .source
+                .as_ref()
+                .map(|source| (source.chat_id, source.message_id)),
         }
     }
 }
@@ -1,20 +1,128 @@
 use crate::action::Action;
+use crate::components::popup_window::Popup;
 use crate::event::Event;
-use crate::{app_context::AppContext, tg::ordered_chat::OrderedChat};
-use std::collections::{BTreeSet, VecDeque};
+use crate::{
+    app_context::AppContext, configs::custom::telegram_custom::MESSAGE_UNLOAD_DELAY_RANGE,
+    local_state::UploadDescriptor, markdown, tg::ordered_chat::OrderedChat, utils::render_qr_code,
+};
+use std::collections::{BTreeSet, HashSet, VecDeque};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, MutexGuard};
+use std::time::{Duration, Instant};
 use tdlib_rs::enums::{
-    self, AuthorizationState, ChatList, InputMessageContent, InputMessageReplyTo, LogStream,
-    Messages, OptionValue, Update, User,
+    self, AuthorizationState, ChatList, CheckChatUsernameResult, ConnectionState, InputFile,
+    InputMessageContent, InputMessageReplyTo, LogStream, MessageSelfDestructType, Messages,
+    OptionValue, SearchMessagesFilter, StickerFullType, Update, User, UserPrivacySetting,
+    UserPrivacySettingRule,
 };
 use tdlib_rs::functions;
-use tdlib_rs::types::{Chat, ChatPosition, InputMessageText, LogStreamFile, OptionValueBoolean};
+use tdlib_rs::types::{
+    Animation, BasicGroupFullInfo, CallbackQueryAnswer, CallbackQueryPayloadData, Chat, ChatFolder,
+    ChatMember, ChatMemberStatusBanned, ChatMessageSender, ChatPosition, Contact, DraftMessage,
+    EmojiStatus, FormattedText, InputFileId, InputFileLocal, InputMessageAnimation,
+    InputMessageDocument, InputMessagePhoto, InputMessageSticker, InputMessageText,
+    InputMessageVideo, LogStreamFile, Message, MessageSelfDestructTypeTimer, MessageSenderChat,
+    MessageSenderUser, MessageThreadInfo, OptionValueBoolean, OptionValueInteger, PasswordState,
+    PaymentReceipt, Sticker, StickerSet, StickerSetInfo, StorageStatisticsFast, SupergroupFullInfo,
+    UserFullInfo, UserPrivacySettingRules,
+};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tokio::task::JoinHandle;
 
-use super::message_entry::MessageEntry;
-use super::td_enums::TdMessageReplyToMessage;
+use super::attachments::{self, SelfDestruct};
+use super::blocklist::{self, BlocklistEntry};
+use super::history_pages::HISTORY_PAGE_SIZE;
+use super::message_entry::{DateTimeEntry, MessageEntry};
+use super::offline_gap::{self, OfflineGap};
+use super::td_enums::{TdChatList, TdMessageReplyToMessage};
+use super::tg_context::TgContext;
+
+/// The time for which a chat is muted to mute it forever, in seconds.
+/// TDLib considers every time longer than a year as forever.
+pub const MUTE_FOREVER: i32 = i32::MAX;
+/// The time offline after which the history of the open chat is reconciled
+/// when the connection is back. Shorter gaps are filled by TDLib updates.
+const OFFLINE_GAP_THRESHOLD: Duration = Duration::from_secs(30);
+/// The maximum number of messages fetched to fill a gap in the open chat.
+const OFFLINE_GAP_MAX_MESSAGES: usize = 500;
+/// The time waited before each request of a blocklist import, so that the
+/// requests are not rate limited.
+const BLOCKLIST_IMPORT_INTERVAL: Duration = Duration::from_secs(1);
+/// The number of blocked users and chats fetched at once.
+const BLOCKED_SENDERS_PAGE_SIZE: i32 = 100;
+/// The time between two evictions of the files of the cache above its
+/// maximum size.
+const CACHE_EVICTION_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Get all the users and the chats blocked by the user.
+///
+/// # Arguments
+/// * `client_id` - The id of the TDLib client.
+///
+/// # Returns
+/// * `Result<Vec<enums::MessageSender>, Error>` - The blocked users and chats.
+async fn blocked_senders(
+    client_id: i32,
+) -> Result<Vec<enums::MessageSender>, tdlib_rs::types::Error> {
+    let mut senders = vec![];
+    loop {
+        let enums::MessageSenders::MessageSenders(page) = functions::get_blocked_message_senders(
+            enums::BlockList::Main,
+            senders.len() as i32,
+            BLOCKED_SENDERS_PAGE_SIZE,
+            client_id,
+        )
+        .await?;
+        if page.senders.is_empty() {
+            return Ok(senders);
+        }
+        senders.extend(page.senders);
+        if senders.len() as i32 >= page.total_count {
+            return Ok(senders);
+        }
+    }
+}
+
+/// Find the user or the chat of an entry of a blocklist.
+/// A username is resolved to the user of a private chat, or to the chat.
+///
+/// # Arguments
+/// * `entry` - The entry of the blocklist.
+/// * `client_id` - The id of the TDLib client.
+///
+/// # Returns
+/// * `Result<enums::MessageSender, Error>` - The user or the chat.
+async fn blocklist_sender(
+    entry: &BlocklistEntry,
+    client_id: i32,
+) -> Result<enums::MessageSender, tdlib_rs::types::Error> {
+    match entry {
+        BlocklistEntry::Id(id) if *id > 0 => {
+            // The user must be known to TDLib before it can be blocked.
+            functions::get_user(*id, client_id).await?;
+            Ok(enums::MessageSender::User(MessageSenderUser {
+                user_id: *id,
+            }))
+        }
+        BlocklistEntry::Id(id) => Ok(enums::MessageSender::Chat(MessageSenderChat {
+            chat_id: *id,
+        })),
+        BlocklistEntry::Username(username) => {
+            let enums::Chat::Chat(chat) =
+                functions::search_public_chat(username.clone(), client_id).await?;
+            match chat.r#type {
+                enums::ChatType::Private(private) => {
+                    Ok(enums::MessageSender::User(MessageSenderUser {
+                        user_id: private.user_id,
+                    }))
+                }
+                _ => Ok(enums::MessageSender::Chat(MessageSenderChat {
+                    chat_id: chat.id,
+                })),
+            }
+        }
+    }
+}
 
 pub struct TgBackend {
     pub handle_updates: JoinHandle<()>,
@@ -26,7 +134,8 @@ pub struct TgBackend {
     pub have_authorization: bool,
     pub can_quit: Arc<AtomicBool>,
     pub app_context: Arc<AppContext>,
-    full_chats_list: bool,
+    /// The chat lists whose chats are all loaded.
+    full_chat_lists: HashSet<TdChatList>,
 }
 
 impl TgBackend {
@@ -38,7 +147,7 @@ impl TgBackend {
         let client_id = tdlib_rs::create_client();
         let have_authorization = false;
         let can_quit = Arc::new(AtomicBool::new(false));
-        let full_chats_list = false;
+        let full_chat_lists = HashSet::new();
         app_context.tg_context().set_event_tx(event_tx.clone());
         tracing::info!("Created TDLib client with client_id: {}", client_id);
 
@@ -52,205 +161,1609 @@ impl TgBackend {
             have_authorization,
             can_quit,
             app_context,
-            full_chats_list,
+            full_chat_lists,
         })
     }
 
-    pub async fn get_me(&mut self) {
-        match functions::get_me(self.client_id).await {
-            Ok(User::User(me)) => {
-                self.app_context.tg_context().set_me(me.id);
-            }
-            Err(error) => tracing::error!("Failed to get me: {error:?}"),
-        }
+    pub async fn get_me(&mut self) {
+        match functions::get_me(self.client_id).await {
+            Ok(User::User(me)) => {
+                self.app_context.tg_context().set_me(me.id);
+            }
+            Err(error) => tracing::error!("Failed to get me: {error:?}"),
+        }
+    }
+
+    pub async fn load_chats(&mut self, chat_list: ChatList, limit: i32) {
+        let td_chat_list = TdChatList::from(chat_list.clone());
+        if self.full_chat_lists.contains(&td_chat_list) {
+            return;
+        }
+
+        if let Err(e) = functions::load_chats(Some(chat_list), limit, self.client_id).await {
+            tracing::error!("Failed to load chats: {e:?}");
+            self.full_chat_lists.insert(td_chat_list);
+        }
+    }
+
+    pub async fn load_all_chats(&mut self) {
+        while !self.full_chat_lists.contains(&TdChatList::Main) {
+            self.load_chats(ChatList::Main, 50).await;
+        }
+    }
+
+    pub async fn search_chats(
+        &self,
+        username: String,
+    ) -> Result<tdlib_rs::types::Chats, tdlib_rs::types::Error> {
+        match functions::search_chats(username.clone(), 50, self.client_id).await {
+            Ok(tdlib_rs::enums::Chats::Chats(chats)) => Ok(chats),
+            Err(e) => {
+                tracing::error!("Failed to search chats: {:?} with query: {}", e, username);
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn close(&self) {
+        match functions::close(self.client_id).await {
+            Ok(me) => tracing::info!("TDLib client closed: {:?}", me),
+            Err(error) => tracing::error!("Error closing TDLib client: {:?}", error),
+        }
+    }
+
+    pub async fn view_all_messages(&self) {
+        if let Err(e) = functions::view_messages(
+            self.app_context.tg_context().open_chat_id(),
+            self.app_context.tg_context().unread_messages(),
+            None,
+            true,
+            self.client_id,
+        )
+        .await
+        {
+            tracing::error!("Failed to view all messages: {e:?}");
+        }
+    }
+
+    /// Mark messages of a chat as read, when they are shown.
+    pub async fn view_messages(&self, chat_id: i64, message_ids: Vec<i64>) {
+        if let Err(e) =
+            functions::view_messages(chat_id, message_ids, None, true, self.client_id).await
+        {
+            tracing::error!("Failed to view the messages of {}: {e:?}", chat_id);
+        }
+    }
+
+    #[allow(clippy::await_holding_lock)]
+    // By default telegram send us only one message the first time
+    pub async fn prepare_to_get_chat_history(&mut self, chat_id: i64) {
+        match functions::get_chat_history(chat_id, 0, 0, 100, false, self.client_id).await {
+            Ok(_m) => {}
+            Err(_e) => {}
+        }
+    }
+
+    #[allow(clippy::await_holding_lock)]
+    /// Load older messages of a chat in the background, a page at a time.
+    /// Asking again while pages are loaded adds pages to load, which are
+    /// requested as soon as the previous one arrives, and the pages loaded
+    /// for a chat that is no longer open are dropped.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    pub fn get_chat_history(&self, chat_id: i64) {
+        let Some(generation) = self.app_context.tg_context().history_pages().request() else {
+            return;
+        };
+        let app_context = Arc::clone(&self.app_context);
+        let client_id = self.client_id;
+        tokio::spawn(async move {
+            let tg_context = app_context.tg_context();
+            while tg_context.history_pages().next_page(generation) {
+                let from_message_id = tg_context.from_message_id();
+                let messages = match functions::get_chat_history(
+                    chat_id,
+                    from_message_id,
+                    0,
+                    HISTORY_PAGE_SIZE,
+                    false,
+                    client_id,
+                )
+                .await
+                {
+                    Ok(Messages::Messages(messages)) => {
+                        messages.messages.into_iter().flatten().collect::<Vec<_>>()
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to get chat history: {e:?}");
+                        tg_context.history_pages().finish(generation);
+                        return;
+                    }
+                };
+                if !tg_context.history_pages().is_current(generation) {
+                    return;
+                }
+                let Some(oldest) = messages.last() else {
+                    tracing::info!("No more messages to get");
+                    tg_context.history_pages().reach_oldest(generation);
+                    if let Err(e) = app_context.action_tx().send(Action::Render) {
+                        tracing::error!("Failed to draw the start of the chat: {}", e);
+                    }
+                    return;
+                };
+                tg_context.set_from_message_id(oldest.id);
+                tg_context.extend_older_messages(messages.iter().map(MessageEntry::from));
+                let max_chat_messages = app_context.app_config().max_chat_messages;
+                tg_context.trim_open_chat_messages(max_chat_messages, false);
+                if let Err(e) = app_context.action_tx().send(Action::Render) {
+                    tracing::error!("Failed to draw the loaded messages: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Load the messages of a chat newer than the loaded ones, which were
+    /// dropped to limit the memory or not loaded yet, like after a chat is
+    /// opened at an old message.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    pub async fn get_newer_chat_history(&mut self, chat_id: i64) {
+        let tg_context = self.app_context.tg_context();
+        if !tg_context.has_newer_messages() {
+            return;
+        }
+        let Some(newest_id) = tg_context
+            .open_chat_messages()
+            .first()
+            .map(MessageEntry::id)
+        else {
+            return;
+        };
+        // A negative offset gets the messages newer than `from_message_id`,
+        // followed by it and the older ones up to the limit.
+        let newer_limit = 49;
+        let newer = match functions::get_chat_history(
+            chat_id,
+            newest_id,
+            -newer_limit,
+            newer_limit + 1,
+            false,
+            self.client_id,
+        )
+        .await
+        {
+            Ok(Messages::Messages(messages)) => messages
+                .messages
+                .into_iter()
+                .flatten()
+                .filter(|message| message.id > newest_id)
+                .map(|message| MessageEntry::from(&message))
+                .collect::<Vec<MessageEntry>>(),
+            Err(e) => {
+                tracing::error!("Failed to get the newer messages: {e:?}");
+                return;
+            }
+        };
+        if tg_context.open_chat_id() != chat_id {
+            return;
+        }
+        if newer.len() < newer_limit as usize {
+            tg_context.set_has_newer_messages(false);
+        }
+        tg_context.open_chat_messages().splice(0..0, newer);
+        let max_chat_messages = self.app_context.app_config().max_chat_messages;
+        tg_context.trim_open_chat_messages(max_chat_messages, true);
+    }
+
+    #[allow(clippy::await_holding_lock)]
+    pub async fn send_message(
+        &mut self,
+        message: String,
+        chat_id: i64,
+        reply_to: Option<TdMessageReplyToMessage>,
+    ) -> Result<tdlib_rs::types::Message, tdlib_rs::types::Error> {
+        self.app_context
+            .tg_context()
+            .set_reply_message(-1, "".to_string());
+
+        self.app_context
+            .action_tx()
+            .send(Action::HideChatWindowReply)
+            .unwrap();
+
+        let text = InputMessageContent::InputMessageText(InputMessageText {
+            text: markdown::parse(&message),
+            link_preview_options: None,
+            clear_draft: true,
+        });
+        let reply_to: Option<InputMessageReplyTo> =
+            reply_to.map(|reply_to| InputMessageReplyTo::Message((&reply_to).into()));
+        match functions::send_message(chat_id, 0, reply_to, None, text, self.client_id).await {
+            Ok(tdlib_rs::enums::Message::Message(message)) => Ok(message),
+            Err(e) => {
+                tracing::error!("Failed to send message: {e:?}");
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn send_file(
+        &mut self,
+        path: String,
+        chat_id: i64,
+        caption: Option<String>,
+        self_destruct: Option<SelfDestruct>,
+    ) -> Result<tdlib_rs::types::Message, tdlib_rs::types::Error> {
+        let caption = caption.map(|text| markdown::parse(&text));
+        let self_destruct_type = self_destruct.map(|self_destruct| match self_destruct {
+            SelfDestruct::ViewOnce => MessageSelfDestructType::Immediately,
+            SelfDestruct::Timer(self_destruct_time) => {
+                MessageSelfDestructType::Timer(MessageSelfDestructTypeTimer { self_destruct_time })
+            }
+        });
+        // The images and the videos are sent as photos and videos, with their
+        // size detected by TDLib. Only they can self-destruct.
+        let content = if attachments::is_photo(&path) {
+            InputMessageContent::InputMessagePhoto(InputMessagePhoto {
+                photo: InputFile::Local(InputFileLocal { path }),
+                thumbnail: None,
+                added_sticker_file_ids: vec![],
+                width: 0,
+                height: 0,
+                caption,
+                self_destruct_type,
+                has_spoiler: false,
+            })
+        } else if attachments::is_video(&path) {
+            InputMessageContent::InputMessageVideo(InputMessageVideo {
+                video: InputFile::Local(InputFileLocal { path }),
+                thumbnail: None,
+                added_sticker_file_ids: vec![],
+                duration: 0,
+                width: 0,
+                height: 0,
+                supports_streaming: true,
+                caption,
+                self_destruct_type,
+                has_spoiler: false,
+            })
+        } else {
+            InputMessageContent::InputMessageDocument(InputMessageDocument {
+                document: InputFile::Local(InputFileLocal { path }),
+                thumbnail: None,
+                disable_content_type_detection: false,
+                caption,
+            })
+        };
+        match functions::send_message(chat_id, 0, None, None, content, self.client_id).await {
+            Ok(tdlib_rs::enums::Message::Message(message)) => Ok(message),
+            Err(e) => {
+                tracing::error!("Failed to send file: {e:?}");
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn send_message_edited(&self, message_id: i64, message: String) {
+        // The caption of a media message is edited instead of its text, with
+        // its Markdown parsed into entities.
+        let is_caption = self
+            .app_context
+            .tg_context()
+            .open_chat_messages()
+            .iter()
+            .any(|message_entry| {
+                message_entry.id() == message_id && message_entry.caption().is_some()
+            });
+        if is_caption {
+            let caption = self.parse_markdown(message).await;
+            match functions::edit_message_caption(
+                self.app_context.tg_context().open_chat_id(),
+                message_id,
+                Some(caption),
+                self.client_id,
+            )
+            .await
+            {
+                Ok(_) => tracing::info!("Caption edited"),
+                Err(e) => tracing::error!("Failed to edit caption: {e:?}"),
+            }
+            return;
+        }
+        let text = InputMessageContent::InputMessageText(InputMessageText {
+            text: markdown::parse(&message),
+            link_preview_options: None,
+            clear_draft: true,
+        });
+        match functions::edit_message_text(
+            self.app_context.tg_context().open_chat_id(),
+            message_id,
+            text,
+            self.client_id,
+        )
+        .await
+        {
+            Ok(_) => tracing::info!("Message edited"),
+            Err(e) => tracing::error!("Failed to edit message: {e:?}"),
+        }
+    }
+
+    /// Write the entities of a text, like the caption of a media message, as
+    /// Markdown, to edit it as text. The entities that can not be written as
+    /// Markdown are dropped.
+    ///
+    /// # Arguments
+    /// * `text` - The text and its entities.
+    ///
+    /// # Returns
+    /// * `String` - The text with its Markdown.
+    pub async fn get_markdown_text(&self, text: FormattedText) -> String {
+        match functions::get_markdown_text(text.clone(), self.client_id).await {
+            Ok(enums::FormattedText::FormattedText(markdown)) => markdown.text,
+            Err(e) => {
+                tracing::warn!("Failed to write the text as Markdown: {e:?}");
+                text.text
+            }
+        }
+    }
+
+    /// Parse the Markdown of a text into entities, ignoring the markup
+    /// errors. It is the reverse of `get_markdown_text`.
+    ///
+    /// # Arguments
+    /// * `text` - The text with its Markdown.
+    ///
+    /// # Returns
+    /// * `FormattedText` - The text and its entities.
+    pub async fn parse_markdown(&self, text: String) -> FormattedText {
+        let text = FormattedText {
+            text,
+            entities: vec![],
+        };
+        match functions::parse_markdown(text.clone(), self.client_id).await {
+            Ok(enums::FormattedText::FormattedText(parsed)) => parsed,
+            Err(e) => {
+                tracing::warn!("Failed to parse the Markdown of the text: {e:?}");
+                text
+            }
+        }
+    }
+
+    /// Get the last messages of a chat, without opening it, so that they are
+    /// not marked as read.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    /// * `limit` - The maximum number of messages.
+    ///
+    /// # Returns
+    /// * `Vec<MessageEntry>` - The messages, the most recent first.
+    pub async fn get_last_messages(&self, chat_id: i64, limit: usize) -> Vec<MessageEntry> {
+        let mut entries: Vec<MessageEntry> = vec![];
+        // TDLib may return fewer messages than requested, for example only the
+        // cached ones, so the history is requested until the limit is reached.
+        while entries.len() < limit {
+            let from_message_id = entries.last().map(|entry| entry.id()).unwrap_or(0);
+            match functions::get_chat_history(
+                chat_id,
+                from_message_id,
+                0,
+                (limit - entries.len()) as i32,
+                false,
+                self.client_id,
+            )
+            .await
+            {
+                Ok(Messages::Messages(messages)) => {
+                    if messages.messages.is_empty() {
+                        break;
+                    }
+                    entries.extend(messages.messages.iter().flatten().map(MessageEntry::from));
+                }
+                Err(e) => {
+                    tracing::error!("Failed to get the last messages: {e:?}");
+                    break;
+                }
+            }
+        }
+        entries
+    }
+
+    /// Fetch in the background the messages of a chat received while offline
+    /// and insert them in the open chat, with a divider above them.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    /// * `last_message_id` - The id of the last message known before going
+    ///   offline.
+    pub fn reconcile_offline_gap(&self, chat_id: i64, last_message_id: i64) {
+        let client_id = self.client_id;
+        let tg_context = self.app_context.tg_context();
+        tokio::spawn(async move {
+            let mut missed: Vec<MessageEntry> = vec![];
+            let mut complete = false;
+            while missed.len() < OFFLINE_GAP_MAX_MESSAGES {
+                let from_message_id = missed.last().map(|entry| entry.id()).unwrap_or(0);
+                match functions::get_chat_history(
+                    chat_id,
+                    from_message_id,
+                    0,
+                    100,
+                    false,
+                    client_id,
+                )
+                .await
+                {
+                    Ok(Messages::Messages(messages)) => {
+                        if messages.messages.is_empty() {
+                            complete = true;
+                            break;
+                        }
+                        for message in messages.messages.iter().flatten() {
+                            if message.id <= last_message_id {
+                                complete = true;
+                                break;
+                            }
+                            missed.push(MessageEntry::from(message));
+                        }
+                        if complete {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to fetch the messages received offline: {e:?}");
+                        return;
+                    }
+                }
+            }
+            if tg_context.open_chat_id() != chat_id {
+                return;
+            }
+            tracing::info!("Fetched {} messages received offline", missed.len());
+            let gap = offline_gap::merge_gap(
+                &mut tg_context.open_chat_messages(),
+                missed,
+                last_message_id,
+            );
+            if let Some((first_message_id, count)) = gap {
+                *tg_context.offline_gap() = Some(OfflineGap {
+                    chat_id,
+                    first_message_id,
+                    count,
+                    complete,
+                });
+            }
+        });
+    }
+
+    pub async fn get_message(&self, chat_id: i64, message_id: i64) -> Option<Message> {
+        match functions::get_message(chat_id, message_id, self.client_id).await {
+            Ok(tdlib_rs::enums::Message::Message(message)) => Some(message),
+            Err(e) => {
+                tracing::warn!("Failed to get message {message_id}: {e:?}");
+                None
+            }
+        }
+    }
+
+    /// Search the messages of all the chats of the main list, the most
+    /// recent first.
+    pub async fn search_messages(
+        &self,
+        query: String,
+        limit: i32,
+    ) -> Result<Vec<Message>, tdlib_rs::types::Error> {
+        let enums::FoundMessages::FoundMessages(found) = functions::search_messages(
+            Some(ChatList::Main),
+            false,
+            query,
+            String::new(),
+            limit,
+            None,
+            0,
+            0,
+            self.client_id,
+        )
+        .await?;
+        Ok(found.messages)
+    }
+
+    /// Search the messages of a chat, the most recent first.
+    pub async fn search_chat_messages(
+        &self,
+        chat_id: i64,
+        query: String,
+        limit: i32,
+    ) -> Result<Vec<Message>, tdlib_rs::types::Error> {
+        let enums::FoundChatMessages::FoundChatMessages(found) = functions::search_chat_messages(
+            chat_id,
+            query,
+            None,
+            0,
+            0,
+            limit,
+            None,
+            0,
+            0,
+            self.client_id,
+        )
+        .await?;
+        Ok(found.messages)
+    }
+
+    /// Get the unread mentions of the user in a chat, including the replies
+    /// to their messages, the most recent first.
+    pub async fn get_unread_mentions(
+        &self,
+        chat_id: i64,
+        limit: i32,
+    ) -> Result<Vec<Message>, tdlib_rs::types::Error> {
+        let enums::FoundChatMessages::FoundChatMessages(found) = functions::search_chat_messages(
+            chat_id,
+            String::new(),
+            None,
+            0,
+            0,
+            limit,
+            Some(SearchMessagesFilter::UnreadMention),
+            0,
+            0,
+            self.client_id,
+        )
+        .await?;
+        Ok(found.messages)
+    }
+
+    /// Get the last message of a chat sent before a date.
+    pub async fn get_chat_message_by_date(
+        &self,
+        chat_id: i64,
+        date: i32,
+    ) -> Result<Message, tdlib_rs::types::Error> {
+        let enums::Message::Message(message) =
+            functions::get_chat_message_by_date(chat_id, date, self.client_id).await?;
+        Ok(message)
+    }
+
+    /// Get the thread of the comments of a channel post, in the discussion
+    /// group of the channel.
+    pub async fn get_message_thread(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+    ) -> Result<MessageThreadInfo, tdlib_rs::types::Error> {
+        let enums::MessageThreadInfo::MessageThreadInfo(thread) =
+            functions::get_message_thread(chat_id, message_id, self.client_id).await?;
+        Ok(thread)
+    }
+
+    /// Get the receipt of a payment, with the order info given by the user.
+    /// The message is the one of the successful payment.
+    pub async fn get_payment_receipt(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+    ) -> Result<PaymentReceipt, tdlib_rs::types::Error> {
+        let enums::PaymentReceipt::PaymentReceipt(receipt) =
+            functions::get_payment_receipt(chat_id, message_id, self.client_id).await?;
+        Ok(receipt)
+    }
+
+    /// Send the callback query of a button of the inline keyboard of a
+    /// message to its bot, and wait for the answer of the bot.
+    pub async fn get_callback_query_answer(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+        data: String,
+    ) -> Result<CallbackQueryAnswer, tdlib_rs::types::Error> {
+        let payload = enums::CallbackQueryPayload::Data(CallbackQueryPayloadData { data });
+        let enums::CallbackQueryAnswer::CallbackQueryAnswer(answer) =
+            functions::get_callback_query_answer(chat_id, message_id, payload, self.client_id)
+                .await?;
+        Ok(answer)
+    }
+
+    /// Inform TDLib that the content of a message has been opened, which
+    /// starts the timer of a self-destructing media.
+    /// The returned future does not borrow the backend, so that the content
+    /// can be opened once its file is downloaded in the background.
+    pub fn open_message_content(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+    ) -> impl std::future::Future<Output = Result<(), tdlib_rs::types::Error>> {
+        let client_id = self.client_id;
+        async move { functions::open_message_content(chat_id, message_id, client_id).await }
+    }
+
+    /// Download a file and wait until the download is completed.
+    /// The returned future does not borrow the backend, so that large files
+    /// can be downloaded in the background.
+    pub fn download_file(&self, file_id: i32) -> impl std::future::Future<Output = Option<String>> {
+        let client_id = self.client_id;
+        async move {
+            match functions::download_file(file_id, 1, 0, 0, true, client_id).await {
+                Ok(enums::File::File(file)) if file.local.is_downloading_completed => {
+                    Some(file.local.path)
+                }
+                Ok(_) => {
+                    tracing::warn!("Download of file {file_id} not completed");
+                    None
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to download file {file_id}: {e:?}");
+                    None
+                }
+            }
+        }
+    }
+
+    /// Set the draft of a chat, which is synchronized with the other clients.
+    /// An empty text clears the draft.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    /// * `text` - The text of the draft.
+    /// * `reply_to` - The message the draft replies to, if any.
+    pub async fn set_chat_draft(
+        &self,
+        chat_id: i64,
+        text: String,
+        reply_to: Option<TdMessageReplyToMessage>,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        let draft_message = (!text.trim().is_empty()).then(|| DraftMessage {
+            reply_to: reply_to.map(|reply_to| InputMessageReplyTo::Message((&reply_to).into())),
+            date: 0,
+            input_message_text: InputMessageContent::InputMessageText(InputMessageText {
+                text: tdlib_rs::types::FormattedText {
+                    text,
+                    entities: vec![],
+                },
+                link_preview_options: None,
+                clear_draft: false,
+            }),
+        });
+        functions::set_chat_draft_message(chat_id, 0, draft_message, self.client_id).await
+    }
+
+    /// Show to the other members of a chat that the user is typing, for a
+    /// few seconds.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    pub async fn send_typing_action(&self, chat_id: i64) -> Result<(), tdlib_rs::types::Error> {
+        functions::send_chat_action(chat_id, 0, Some(enums::ChatAction::Typing), self.client_id)
+            .await
+    }
+
+    /// Mark all the messages of a chat as read, and remove the unread mark of
+    /// the chat, if any.
+    pub async fn mark_chat_as_read(&self, chat_id: i64) -> Result<(), tdlib_rs::types::Error> {
+        let (last_message_id, is_marked_as_unread) =
+            match self.app_context.tg_context().chats().get(&chat_id) {
+                Some(chat) => (
+                    chat.last_message.as_ref().map(|message| message.id),
+                    chat.is_marked_as_unread,
+                ),
+                None => (None, false),
+            };
+        if let Some(last_message_id) = last_message_id {
+            functions::view_messages(chat_id, vec![last_message_id], None, true, self.client_id)
+                .await?;
+        }
+        if is_marked_as_unread {
+            functions::toggle_chat_is_marked_as_unread(chat_id, false, self.client_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Mute or unmute the notifications of a chat, keeping the other
+    /// notification settings.
+    pub async fn set_chat_muted(
+        &self,
+        chat_id: i64,
+        muted: bool,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        self.set_chat_mute_for(chat_id, if muted { MUTE_FOREVER } else { 0 })
+            .await
+    }
+
+    /// Mute the notifications of a chat for a time, or unmute them, keeping
+    /// the other notification settings.
+    pub async fn set_chat_mute_for(
+        &self,
+        chat_id: i64,
+        mute_for: i32,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        let settings = self
+            .app_context
+            .tg_context()
+            .chats()
+            .get(&chat_id)
+            .map(|chat| chat.notification_settings.clone());
+        let Some(mut settings) = settings else {
+            return Ok(());
+        };
+        settings.use_default_mute_for = false;
+        settings.mute_for = mute_for;
+        functions::set_chat_notification_settings(chat_id, settings, self.client_id).await
+    }
+
+    /// Add a chat to a chat list, like the archive or a folder.
+    /// A chat is removed from the main list when it is archived.
+    pub async fn add_chat_to_list(
+        &self,
+        chat_id: i64,
+        chat_list: ChatList,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::add_chat_to_list(chat_id, chat_list, self.client_id).await
+    }
+
+    pub async fn get_chat_folder(
+        &self,
+        chat_folder_id: i32,
+    ) -> Result<ChatFolder, tdlib_rs::types::Error> {
+        let enums::ChatFolder::ChatFolder(folder) =
+            functions::get_chat_folder(chat_folder_id, self.client_id).await?;
+        Ok(folder)
+    }
+
+    /// Create a chat folder and return its identifier.
+    pub async fn create_chat_folder(
+        &self,
+        folder: ChatFolder,
+    ) -> Result<i32, tdlib_rs::types::Error> {
+        let enums::ChatFolderInfo::ChatFolderInfo(info) =
+            functions::create_chat_folder(folder, self.client_id).await?;
+        Ok(info.id)
+    }
+
+    pub async fn edit_chat_folder(
+        &self,
+        chat_folder_id: i32,
+        folder: ChatFolder,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::edit_chat_folder(chat_folder_id, folder, self.client_id).await?;
+        Ok(())
+    }
+
+    /// Get the installed sticker sets, in the order they are shown.
+    pub async fn get_installed_sticker_sets(
+        &self,
+    ) -> Result<Vec<StickerSetInfo>, tdlib_rs::types::Error> {
+        let enums::StickerSets::StickerSets(sticker_sets) =
+            functions::get_installed_sticker_sets(enums::StickerType::Regular, self.client_id)
+                .await?;
+        Ok(sticker_sets.sets)
+    }
+
+    pub async fn get_sticker_set(&self, set_id: i64) -> Result<StickerSet, tdlib_rs::types::Error> {
+        let enums::StickerSet::StickerSet(sticker_set) =
+            functions::get_sticker_set(set_id, self.client_id).await?;
+        Ok(sticker_set)
+    }
+
+    /// Search a sticker set by its short name, like the one of a
+    /// `t.me/addstickers/...` link.
+    pub async fn search_sticker_set(
+        &self,
+        name: String,
+    ) -> Result<StickerSet, tdlib_rs::types::Error> {
+        let enums::StickerSet::StickerSet(sticker_set) =
+            functions::search_sticker_set(name, self.client_id).await?;
+        Ok(sticker_set)
+    }
+
+    /// Install or remove a sticker set.
+    pub async fn set_sticker_set_installed(
+        &self,
+        set_id: i64,
+        is_installed: bool,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::change_sticker_set(set_id, is_installed, false, self.client_id).await
+    }
+
+    /// Get the stickers recently sent by the user, the most recent first.
+    pub async fn get_recent_stickers(&self) -> Result<Vec<Sticker>, tdlib_rs::types::Error> {
+        let enums::Stickers::Stickers(stickers) =
+            functions::get_recent_stickers(false, self.client_id).await?;
+        Ok(stickers.stickers)
+    }
+
+    /// Get the stickers marked as favorite by the user.
+    pub async fn get_favorite_stickers(&self) -> Result<Vec<Sticker>, tdlib_rs::types::Error> {
+        let enums::Stickers::Stickers(stickers) =
+            functions::get_favorite_stickers(self.client_id).await?;
+        Ok(stickers.stickers)
+    }
+
+    /// Send a sticker to a chat. TDLib adds it to the recent stickers.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    /// * `file_id` - The id of the file of the sticker.
+    /// * `emoji` - The emoji the sticker is associated with.
+    pub async fn send_sticker(
+        &self,
+        chat_id: i64,
+        file_id: i32,
+        emoji: String,
+    ) -> Result<Message, tdlib_rs::types::Error> {
+        let content = InputMessageContent::InputMessageSticker(InputMessageSticker {
+            sticker: InputFile::Id(InputFileId { id: file_id }),
+            thumbnail: None,
+            width: 0,
+            height: 0,
+            emoji,
+        });
+        let enums::Message::Message(message) =
+            functions::send_message(chat_id, 0, None, None, content, self.client_id).await?;
+        Ok(message)
+    }
+
+    /// Get the animations saved by the user, the most recent first.
+    pub async fn get_saved_animations(&self) -> Result<Vec<Animation>, tdlib_rs::types::Error> {
+        let enums::Animations::Animations(animations) =
+            functions::get_saved_animations(self.client_id).await?;
+        Ok(animations.animations)
+    }
+
+    /// Search animations with an inline bot, like `@gif`.
+    ///
+    /// # Arguments
+    /// * `bot_username` - The username of the bot, without the `@`.
+    /// * `chat_id` - The id of the chat the animations are searched for.
+    /// * `query` - The text of the search.
+    ///
+    /// # Returns
+    /// * `Result<Vec<Animation>, Error>` - The animations found, in the order
+    ///   of the bot.
+    pub async fn search_inline_animations(
+        &self,
+        bot_username: &str,
+        chat_id: i64,
+        query: String,
+    ) -> Result<Vec<Animation>, tdlib_rs::types::Error> {
+        let enums::Chat::Chat(bot) =
+            functions::search_public_chat(bot_username.to_string(), self.client_id).await?;
+        let enums::ChatType::Private(private) = bot.r#type else {
+            tracing::warn!("@{} is not a bot", bot_username);
+            return Ok(vec![]);
+        };
+        let enums::InlineQueryResults::InlineQueryResults(results) =
+            functions::get_inline_query_results(
+                private.user_id,
+                chat_id,
+                None,
+                query,
+                String::new(),
+                self.client_id,
+            )
+            .await?;
+        Ok(results
+            .results
+            .into_iter()
+            .filter_map(|result| match result {
+                enums::InlineQueryResult::Animation(result) => Some(result.animation),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Send an animation, like a GIF, to a chat.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    /// * `file_id` - The id of the file of the animation.
+    pub async fn send_animation(
+        &self,
+        chat_id: i64,
+        file_id: i32,
+    ) -> Result<Message, tdlib_rs::types::Error> {
+        let content = InputMessageContent::InputMessageAnimation(InputMessageAnimation {
+            animation: InputFile::Id(InputFileId { id: file_id }),
+            thumbnail: None,
+            added_sticker_file_ids: vec![],
+            duration: 0,
+            width: 0,
+            height: 0,
+            caption: None,
+            has_spoiler: false,
+        });
+        let enums::Message::Message(message) =
+            functions::send_message(chat_id, 0, None, None, content, self.client_id).await?;
+        Ok(message)
+    }
+
+    /// Change the order of the installed sticker sets.
+    pub async fn reorder_sticker_sets(
+        &self,
+        sticker_set_ids: Vec<i64>,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::reorder_installed_sticker_sets(
+            enums::StickerType::Regular,
+            sticker_set_ids,
+            self.client_id,
+        )
+        .await
+    }
+
+    /// Get the users and the chats blocked by the user.
+    pub async fn get_blocked_senders(
+        &self,
+    ) -> Result<Vec<enums::MessageSender>, tdlib_rs::types::Error> {
+        blocked_senders(self.client_id).await
+    }
+
+    /// Block the users and the chats of a blocklist in the background.
+    /// The entries are blocked one at a time, waiting between the requests
+    /// and as long as asked by Telegram when a request is rate limited, and
+    /// the entries already blocked are skipped. A summary is shown at the
+    /// end.
+    ///
+    /// # Arguments
+    /// * `entries` - The entries of the blocklist.
+    pub fn import_blocklist(&self, entries: Vec<BlocklistEntry>) {
+        let client_id = self.client_id;
+        let app_context = Arc::clone(&self.app_context);
+        tokio::spawn(async move {
+            let already_blocked: Vec<enums::MessageSender> =
+                blocked_senders(client_id).await.unwrap_or_default();
+            let mut blocked = 0;
+            let mut skipped = 0;
+            let mut failures = vec![];
+            for entry in entries.iter() {
+                let result = loop {
+                    tokio::time::sleep(BLOCKLIST_IMPORT_INTERVAL).await;
+                    let result = match blocklist_sender(entry, client_id).await {
+                        Ok(sender) if already_blocked.contains(&sender) => Ok(false),
+                        Ok(sender) => functions::set_message_sender_block_list(
+                            sender,
+                            Some(enums::BlockList::Main),
+                            client_id,
+                        )
+                        .await
+                        .map(|_| true),
+                        Err(e) => Err(e),
+                    };
+                    match result {
+                        Err(e) => match blocklist::retry_after(&e) {
+                            Some(delay) => {
+                                tracing::info!("Blocklist import rate limited for {:?}", delay);
+                                tokio::time::sleep(delay).await;
+                            }
+                            None => break Err(e),
+                        },
+                        Ok(is_new) => break Ok(is_new),
+                    }
+                };
+                match result {
+                    Ok(true) => blocked += 1,
+                    Ok(false) => skipped += 1,
+                    Err(e) => {
+                        tracing::error!("Failed to block {:?}: {:?}", entry, e);
+                        let entry = match entry {
+                            BlocklistEntry::Id(id) => id.to_string(),
+                            BlocklistEntry::Username(username) => format!("@{}", username),
+                        };
+                        failures.push(format!("{}: {}", entry, e.message));
+                    }
+                }
+            }
+            let mut message = format!("{} blocked, {} already blocked.", blocked, skipped);
+            if !failures.is_empty() {
+                message.push_str(&format!(
+                    "\n{} could not be blocked:\n{}",
+                    failures.len(),
+                    failures.join("\n")
+                ));
+            }
+            let _ = app_context.action_tx().send(Action::ShowPopup(Popup::Info(
+                "Import blocklist".to_string(),
+                message,
+            )));
+        });
+    }
+
+    /// Get the space used by the files and the database of TDLib.
+    pub async fn get_storage_statistics(
+        &self,
+    ) -> Result<StorageStatisticsFast, tdlib_rs::types::Error> {
+        let enums::StorageStatisticsFast::StorageStatisticsFast(statistics) =
+            functions::get_storage_statistics_fast(self.client_id).await?;
+        Ok(statistics)
+    }
+
+    /// Delete the least recently used files of the cache in the background,
+    /// at regular intervals, so that it does not grow above the maximum size
+    /// of the configuration. Nothing is deleted if the size is not limited.
+    pub fn start_cache_eviction(&self) {
+        let max_cache_size = self.app_context.app_config().max_cache_size;
+        if max_cache_size == 0 {
+            return;
+        }
+        let client_id = self.client_id;
+        tokio::spawn(async move {
+            loop {
+                // Only the size limits the files: the files are kept for any
+                // time and in any number below it.
+                match functions::optimize_storage(
+                    (max_cache_size * 1024 * 1024) as i64,
+                    i32::MAX,
+                    i32::MAX,
+                    -1,
+                    vec![],
+                    vec![],
+                    vec![],
+                    false,
+                    0,
+                    client_id,
+                )
+                .await
+                {
+                    Ok(enums::StorageStatistics::StorageStatistics(statistics)) => {
+                        tracing::info!("Cache evicted down to {} bytes", statistics.size)
+                    }
+                    Err(error) => tracing::error!("Error evicting the cache: {error:?}"),
+                }
+                tokio::time::sleep(CACHE_EVICTION_INTERVAL).await;
+            }
+        });
+    }
+
+    pub async fn delete_messages(&self, chat_id: i64, message_ids: Vec<i64>, revoke: bool) {
+        match functions::delete_messages(chat_id, message_ids, revoke, self.client_id).await {
+            Ok(_) => tracing::info!("Messages deleted"),
+            Err(e) => tracing::error!("Failed to delete messages: {e:?}"),
+        }
+    }
+
+    /// Delete the messages of a chat, for the user only or, if `revoke` is
+    /// true, for the other members too.
+    pub async fn delete_chat_history(
+        &self,
+        chat_id: i64,
+        remove_from_chat_list: bool,
+        revoke: bool,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::delete_chat_history(chat_id, remove_from_chat_list, revoke, self.client_id).await
+    }
+
+    /// Get the most recently pinned message of a chat.
+    pub async fn get_chat_pinned_message(
+        &self,
+        chat_id: i64,
+    ) -> Result<Message, tdlib_rs::types::Error> {
+        functions::get_chat_pinned_message(chat_id, self.client_id)
+            .await
+            .map(|enums::Message::Message(message)| message)
+    }
+
+    /// Pin a message of a chat, notifying its members unless
+    /// `disable_notification` is true.
+    pub async fn pin_chat_message(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+        disable_notification: bool,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::pin_chat_message(
+            chat_id,
+            message_id,
+            disable_notification,
+            false,
+            self.client_id,
+        )
+        .await
+    }
+
+    /// Unpin a message of a chat.
+    pub async fn unpin_chat_message(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::unpin_chat_message(chat_id, message_id, self.client_id).await
+    }
+
+    /// Pin a chat at the top of a chat list or unpin it.
+    pub async fn toggle_chat_is_pinned(
+        &self,
+        chat_list: ChatList,
+        chat_id: i64,
+        is_pinned: bool,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::toggle_chat_is_pinned(chat_list, chat_id, is_pinned, self.client_id).await
+    }
+
+    /// Get the identities the messages of a chat can be sent as, like the
+    /// channels of the user or the group itself for its anonymous admins.
+    pub async fn get_chat_available_message_senders(
+        &self,
+        chat_id: i64,
+    ) -> Result<Vec<ChatMessageSender>, tdlib_rs::types::Error> {
+        let enums::ChatMessageSenders::ChatMessageSenders(senders) =
+            functions::get_chat_available_message_senders(chat_id, self.client_id).await?;
+        Ok(senders.senders)
+    }
+
+    /// Set the identity the next messages of a chat are sent as.
+    pub async fn set_chat_message_sender(
+        &self,
+        chat_id: i64,
+        sender: enums::MessageSender,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::set_chat_message_sender(chat_id, sender, self.client_id).await
+    }
+
+    /// Leave a group or a channel.
+    pub async fn leave_chat(&self, chat_id: i64) -> Result<(), tdlib_rs::types::Error> {
+        functions::leave_chat(chat_id, self.client_id).await
+    }
+
+    /// Ban a member of a group, that can not join it again.
+    pub async fn ban_chat_member(
+        &self,
+        chat_id: i64,
+        member_id: enums::MessageSender,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::set_chat_member_status(
+            chat_id,
+            member_id,
+            enums::ChatMemberStatus::Banned(ChatMemberStatusBanned {
+                banned_until_date: 0,
+            }),
+            self.client_id,
+        )
+        .await
+    }
+
+    /// Delete all the messages sent by a member of a supergroup.
+    pub async fn delete_chat_messages_by_sender(
+        &self,
+        chat_id: i64,
+        sender_id: enums::MessageSender,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::delete_chat_messages_by_sender(chat_id, sender_id, self.client_id).await
+    }
+
+    /// Add a reaction with an emoji to a message, or remove it if `add` is
+    /// false.
+    pub async fn set_message_reaction(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+        emoji: String,
+        add: bool,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        let reaction_type =
+            enums::ReactionType::Emoji(tdlib_rs::types::ReactionTypeEmoji { emoji });
+        if add {
+            functions::add_message_reaction(
+                chat_id,
+                message_id,
+                reaction_type,
+                false,
+                true,
+                self.client_id,
+            )
+            .await
+        } else {
+            functions::remove_message_reaction(chat_id, message_id, reaction_type, self.client_id)
+                .await
+        }
+    }
+
+    /// Forward messages to a chat, in the order of their ids.
+    pub async fn forward_messages(
+        &self,
+        chat_id: i64,
+        from_chat_id: i64,
+        message_ids: Vec<i64>,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::forward_messages(
+            chat_id,
+            0,
+            from_chat_id,
+            message_ids,
+            None,
+            false,
+            false,
+            self.client_id,
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Report messages of a chat to Telegram as spam.
+    pub async fn report_spam(
+        &self,
+        chat_id: i64,
+        message_ids: Vec<i64>,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::report_chat(
+            chat_id,
+            message_ids,
+            enums::ReportReason::Spam,
+            String::new(),
+            self.client_id,
+        )
+        .await
+    }
+
+    /// Set the options of TDLib from the telegram configuration, once its
+    /// parameters are set. A message unload delay out of range is ignored,
+    /// like `0`, so that the default of TDLib is used.
+    ///
+    /// # Arguments
+    /// * `ignore_file_names` - Whether the original names of the files are
+    ///   ignored.
+    /// * `message_unload_delay` - The delay after which the messages of the
+    ///   closed chats are unloaded, in seconds.
+    async fn set_tdlib_options(&self, ignore_file_names: bool, message_unload_delay: i64) {
+        let mut options = vec![(
+            "ignore_file_names",
+            OptionValue::Boolean(OptionValueBoolean {
+                value: ignore_file_names,
+            }),
+        )];
+        if MESSAGE_UNLOAD_DELAY_RANGE.contains(&message_unload_delay) {
+            options.push((
+                "message_unload_delay",
+                OptionValue::Integer(OptionValueInteger {
+                    value: message_unload_delay,
+                }),
+            ));
+        }
+        for (name, value) in options {
+            if let Err(e) =
+                functions::set_option(name.to_string(), Some(value), self.client_id).await
+            {
+                tracing::error!("Failed to set the option {}: {}", name, e.message);
+            }
+        }
+    }
+
+    pub async fn use_quick_ack(&self) {
+        match functions::set_option(
+            String::from("use_quick_ack"),
+            Some(OptionValue::Boolean(OptionValueBoolean { value: true })),
+            self.client_id,
+        )
+        .await
+        {
+            Ok(_) => {
+                tracing::info!("Quick ack enabled");
+            }
+            Err(e) => {
+                tracing::error!("Failed to enable quick ack: {e:?}");
+            }
+        }
+    }
+
+    pub async fn get_user_full_info(&self, user_id: i64) -> Option<UserFullInfo> {
+        match functions::get_user_full_info(user_id, self.client_id).await {
+            Ok(enums::UserFullInfo::UserFullInfo(full_info)) => {
+                self.app_context
+                    .tg_context()
+                    .users_full_info()
+                    .insert(user_id, full_info.clone());
+                Some(full_info)
+            }
+            Err(e) => {
+                tracing::error!("Failed to get user full info: {e:?}");
+                None
+            }
+        }
+    }
+
+    /// Get the ids of the users who are contacts of the current user.
+    pub async fn get_contacts(&self) -> Result<Vec<i64>, tdlib_rs::types::Error> {
+        functions::get_contacts(self.client_id)
+            .await
+            .map(|enums::Users::Users(users)| users.user_ids)
+    }
+
+    /// Add a contact by phone number.
+    ///
+    /// # Returns
+    /// * `Result<Option<i64>, Error>` - The id of the user, `None` if nobody
+    ///   uses Telegram with the phone number.
+    pub async fn import_contact(
+        &self,
+        phone_number: String,
+        first_name: String,
+        last_name: String,
+    ) -> Result<Option<i64>, tdlib_rs::types::Error> {
+        let contact = Contact {
+            phone_number,
+            first_name,
+            last_name,
+            vcard: String::new(),
+            user_id: 0,
+        };
+        functions::import_contacts(vec![contact], self.client_id)
+            .await
+            .map(|enums::ImportedContacts::ImportedContacts(imported)| {
+                imported.user_ids.first().copied().filter(|id| *id != 0)
+            })
+    }
+
+    pub async fn remove_contacts(&self, user_ids: Vec<i64>) -> Result<(), tdlib_rs::types::Error> {
+        functions::remove_contacts(user_ids, self.client_id).await
+    }
+
+    /// Get the private chat with a user, which is created if there is none.
+    ///
+    /// # Returns
+    /// * `Result<i64, Error>` - The id of the chat.
+    pub async fn create_private_chat(&self, user_id: i64) -> Result<i64, tdlib_rs::types::Error> {
+        functions::create_private_chat(user_id, false, self.client_id)
+            .await
+            .map(|enums::Chat::Chat(chat)| chat.id)
+    }
+
+    /// Create a basic group with some users as its first members.
+    ///
+    /// # Returns
+    /// * `Result<i64, Error>` - The id of the chat of the group.
+    pub async fn create_new_basic_group_chat(
+        &self,
+        user_ids: Vec<i64>,
+        title: String,
+    ) -> Result<i64, tdlib_rs::types::Error> {
+        functions::create_new_basic_group_chat(user_ids, title, 0, self.client_id)
+            .await
+            .map(|enums::CreatedBasicGroupChat::CreatedBasicGroupChat(created)| created.chat_id)
+    }
+
+    /// Create a supergroup or a channel.
+    ///
+    /// # Returns
+    /// * `Result<i64, Error>` - The id of the chat of the supergroup.
+    pub async fn create_new_supergroup_chat(
+        &self,
+        title: String,
+        is_channel: bool,
+        description: String,
+    ) -> Result<i64, tdlib_rs::types::Error> {
+        functions::create_new_supergroup_chat(
+            title,
+            false,
+            is_channel,
+            description,
+            None,
+            0,
+            false,
+            self.client_id,
+        )
+        .await
+        .map(|enums::Chat::Chat(chat)| chat.id)
+    }
+
+    /// Get a chat from Telegram, rather than from the updates received.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    pub async fn get_chat(&self, chat_id: i64) -> Result<Chat, tdlib_rs::types::Error> {
+        functions::get_chat(chat_id, self.client_id)
+            .await
+            .map(|enums::Chat::Chat(chat)| chat)
+    }
+
+    /// Get the full info of a basic group, like its description and its
+    /// members.
+    pub async fn get_basic_group_full_info(
+        &self,
+        basic_group_id: i64,
+    ) -> Result<BasicGroupFullInfo, tdlib_rs::types::Error> {
+        functions::get_basic_group_full_info(basic_group_id, self.client_id)
+            .await
+            .map(|enums::BasicGroupFullInfo::BasicGroupFullInfo(full_info)| full_info)
     }
 
-    pub async fn load_chats(&mut self, chat_list: ChatList, limit: i32) {
-        if self.full_chats_list {
-            return;
-        }
+    /// Get the full info of a supergroup or of a channel, like its
+    /// description and its number of members.
+    pub async fn get_supergroup_full_info(
+        &self,
+        supergroup_id: i64,
+    ) -> Result<SupergroupFullInfo, tdlib_rs::types::Error> {
+        functions::get_supergroup_full_info(supergroup_id, self.client_id)
+            .await
+            .map(|enums::SupergroupFullInfo::SupergroupFullInfo(full_info)| full_info)
+    }
 
-        if let Err(e) = functions::load_chats(Some(chat_list), limit, self.client_id).await {
-            tracing::error!("Failed to load chats: {e:?}");
-            self.full_chats_list = true;
-        }
+    /// Get the ids of the groups in common with a user, at most `limit`.
+    pub async fn get_groups_in_common(
+        &self,
+        user_id: i64,
+        limit: i32,
+    ) -> Result<Vec<i64>, tdlib_rs::types::Error> {
+        functions::get_groups_in_common(user_id, 0, limit, self.client_id)
+            .await
+            .map(|enums::Chats::Chats(chats)| chats.chat_ids)
     }
 
-    pub async fn load_all_chats(&mut self) {
-        while !self.full_chats_list {
-            self.load_chats(ChatList::Main, 50).await;
-        }
+    /// Get the members of a basic group.
+    pub async fn get_basic_group_members(
+        &self,
+        basic_group_id: i64,
+    ) -> Result<Vec<ChatMember>, tdlib_rs::types::Error> {
+        functions::get_basic_group_full_info(basic_group_id, self.client_id)
+            .await
+            .map(|enums::BasicGroupFullInfo::BasicGroupFullInfo(full_info)| full_info.members)
     }
 
-    pub async fn search_chats(
+    /// Get the members of a supergroup or of a channel, the most recently
+    /// active first.
+    ///
+    /// # Arguments
+    /// * `supergroup_id` - The id of the supergroup.
+    /// * `limit` - The maximum number of members, at most 200.
+    pub async fn get_supergroup_members(
+        &self,
+        supergroup_id: i64,
+        limit: i32,
+    ) -> Result<Vec<ChatMember>, tdlib_rs::types::Error> {
+        functions::get_supergroup_members(supergroup_id, None, 0, limit, self.client_id)
+            .await
+            .map(|enums::ChatMembers::ChatMembers(members)| members.members)
+    }
+
+    /// Change the status of a member of a group, like to promote them to
+    /// admin, to restrict them or to remove them from the group.
+    pub async fn set_chat_member_status(
+        &self,
+        chat_id: i64,
+        member_id: enums::MessageSender,
+        status: enums::ChatMemberStatus,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        functions::set_chat_member_status(chat_id, member_id, status, self.client_id).await
+    }
+
+    pub async fn check_username(
         &self,
         username: String,
-    ) -> Result<tdlib_rs::types::Chats, tdlib_rs::types::Error> {
-        match functions::search_chats(username.clone(), 50, self.client_id).await {
-            Ok(tdlib_rs::enums::Chats::Chats(chats)) => Ok(chats),
-            Err(e) => {
-                tracing::error!("Failed to search chats: {:?} with query: {}", e, username);
-                Err(e)
-            }
-        }
+    ) -> Result<CheckChatUsernameResult, tdlib_rs::types::Error> {
+        // The private chat with the current user has the same identifier of
+        // the user.
+        let me = self.app_context.tg_context().me();
+        functions::check_chat_username(me, username, self.client_id).await
     }
 
-    pub async fn close(&self) {
-        match functions::close(self.client_id).await {
-            Ok(me) => tracing::info!("TDLib client closed: {:?}", me),
-            Err(error) => tracing::error!("Error closing TDLib client: {:?}", error),
-        }
+    pub async fn set_username(&self, username: String) -> Result<(), tdlib_rs::types::Error> {
+        functions::set_username(username, self.client_id).await
     }
 
-    pub async fn view_all_messages(&self) {
-        if let Err(e) = functions::view_messages(
-            self.app_context.tg_context().open_chat_id(),
-            self.app_context.tg_context().unread_messages(),
-            None,
-            true,
+    pub async fn set_bio(&self, bio: String) -> Result<(), tdlib_rs::types::Error> {
+        functions::set_bio(bio, self.client_id).await
+    }
+
+    pub async fn get_password_state(&self) -> Result<PasswordState, tdlib_rs::types::Error> {
+        functions::get_password_state(self.client_id)
+            .await
+            .map(|enums::PasswordState::PasswordState(state)| state)
+    }
+
+    /// Set, change or remove the cloud password of the current user.
+    ///
+    /// # Arguments
+    /// * `old_password` - The current password, empty if there is none.
+    /// * `new_password` - The new password, empty to remove the password.
+    /// * `new_hint` - The hint of the new password.
+    /// * `new_recovery_email_address` - The new recovery email address, if
+    ///   it is changed.
+    ///
+    /// # Returns
+    /// * `Result<PasswordState, Error>` - The new state of the password.
+    pub async fn set_password(
+        &self,
+        old_password: String,
+        new_password: String,
+        new_hint: String,
+        new_recovery_email_address: Option<String>,
+    ) -> Result<PasswordState, tdlib_rs::types::Error> {
+        functions::set_password(
+            old_password,
+            new_password,
+            new_hint,
+            new_recovery_email_address.is_some(),
+            new_recovery_email_address.unwrap_or_default(),
             self.client_id,
         )
         .await
-        {
-            tracing::error!("Failed to view all messages: {e:?}");
-        }
+        .map(|enums::PasswordState::PasswordState(state)| state)
     }
 
-    #[allow(clippy::await_holding_lock)]
-    // By default telegram send us only one message the first time
-    pub async fn prepare_to_get_chat_history(&mut self, chat_id: i64) {
-        match functions::get_chat_history(chat_id, 0, 0, 100, false, self.client_id).await {
-            Ok(_m) => {}
-            Err(_e) => {}
-        }
+    pub async fn set_recovery_email_address(
+        &self,
+        password: String,
+        new_recovery_email_address: String,
+    ) -> Result<PasswordState, tdlib_rs::types::Error> {
+        functions::set_recovery_email_address(password, new_recovery_email_address, self.client_id)
+            .await
+            .map(|enums::PasswordState::PasswordState(state)| state)
     }
 
-    #[allow(clippy::await_holding_lock)]
-    pub async fn get_chat_history(&mut self, chat_id: i64) {
-        let start_open_chat_messages_len = self.app_context.tg_context().open_chat_messages().len();
-        let mut mut_open_chat_messages_len =
-            self.app_context.tg_context().open_chat_messages().len();
-        let win_size = 100;
-
-        while mut_open_chat_messages_len < start_open_chat_messages_len + win_size {
-            let from_message_id = self.app_context.tg_context().from_message_id();
-            match functions::get_chat_history(
-                chat_id,
-                from_message_id,
-                0,
-                50,
-                false,
-                self.client_id,
-            )
+    pub async fn check_recovery_email_address_code(
+        &self,
+        code: String,
+    ) -> Result<PasswordState, tdlib_rs::types::Error> {
+        functions::check_recovery_email_address_code(code, self.client_id)
             .await
-            {
-                Ok(Messages::Messages(messages)) => {
-                    if messages.messages.is_empty() {
-                        tracing::info!("No more messages to get");
-                        break;
-                    }
+            .map(|enums::PasswordState::PasswordState(state)| state)
+    }
 
-                    let message_flatten = messages.messages.into_iter().flatten();
-                    for message in message_flatten.clone() {
-                        self.app_context
-                            .tg_context()
-                            .open_chat_messages()
-                            .push(MessageEntry::from(&message));
-                        mut_open_chat_messages_len += 1;
-                    }
-                    if let Some(message) = message_flatten.last() {
-                        self.app_context
-                            .tg_context()
-                            .set_from_message_id(message.id);
+    pub async fn get_default_emoji_statuses(&self) -> Vec<(i64, String)> {
+        let custom_emoji_ids = match functions::get_default_emoji_statuses(self.client_id).await {
+            Ok(enums::EmojiStatuses::EmojiStatuses(statuses)) => statuses.custom_emoji_ids,
+            Err(e) => {
+                tracing::error!("Failed to get default emoji statuses: {e:?}");
+                return vec![];
+            }
+        };
+        match functions::get_custom_emoji_stickers(custom_emoji_ids, self.client_id).await {
+            Ok(enums::Stickers::Stickers(stickers)) => stickers
+                .stickers
+                .into_iter()
+                .filter_map(|sticker| match sticker.full_type {
+                    StickerFullType::CustomEmoji(custom_emoji) => {
+                        Some((custom_emoji.custom_emoji_id, sticker.emoji))
                     }
-                }
-                Err(e) => {
-                    tracing::error!("Failed to get chat history: {e:?}");
-                    break;
-                }
+                    _ => None,
+                })
+                .collect(),
+            Err(e) => {
+                tracing::error!("Failed to get custom emoji stickers: {e:?}");
+                vec![]
             }
         }
     }
 
-    #[allow(clippy::await_holding_lock)]
-    pub async fn send_message(
-        &mut self,
-        message: String,
-        chat_id: i64,
-        reply_to: Option<TdMessageReplyToMessage>,
-    ) -> Result<tdlib_rs::types::Message, tdlib_rs::types::Error> {
-        self.app_context
-            .tg_context()
-            .set_reply_message(-1, "".to_string());
-
-        self.app_context
-            .action_tx()
-            .send(Action::HideChatWindowReply)
-            .unwrap();
-
-        let text = InputMessageContent::InputMessageText(InputMessageText {
-            text: tdlib_rs::types::FormattedText {
-                text: message,
-                entities: vec![], // TODO: Add entities
-            },
-            link_preview_options: None,
-            clear_draft: true,
+    pub async fn set_emoji_status(
+        &self,
+        custom_emoji_id: Option<i64>,
+    ) -> Result<(), tdlib_rs::types::Error> {
+        let emoji_status = custom_emoji_id.map(|custom_emoji_id| EmojiStatus {
+            custom_emoji_id,
+            expiration_date: 0,
         });
-        let reply_to: Option<InputMessageReplyTo> =
-            reply_to.map(|reply_to| InputMessageReplyTo::Message((&reply_to).into()));
-        match functions::send_message(chat_id, 0, reply_to, None, text, self.client_id).await {
-            Ok(tdlib_rs::enums::Message::Message(message)) => Ok(message),
-            Err(e) => {
-                tracing::error!("Failed to send message: {e:?}");
-                Err(e)
-            }
-        }
+        functions::set_emoji_status(emoji_status, self.client_id).await
     }
 
-    pub async fn send_message_edited(&self, message_id: i64, message: String) {
-        let text = InputMessageContent::InputMessageText(InputMessageText {
-            text: tdlib_rs::types::FormattedText {
-                text: message,
-                entities: vec![],
-            },
-            link_preview_options: None,
-            clear_draft: true,
-        });
-        match functions::edit_message_text(
-            self.app_context.tg_context().open_chat_id(),
-            message_id,
-            text,
+    pub async fn is_online_visible(&self) -> bool {
+        match functions::get_user_privacy_setting_rules(
+            UserPrivacySetting::ShowStatus,
             self.client_id,
         )
         .await
         {
-            Ok(_) => tracing::info!("Message edited"),
-            Err(e) => tracing::error!("Failed to edit message: {e:?}"),
-        }
-    }
-
-    pub async fn delete_messages(&self, chat_id: i64, message_ids: Vec<i64>, revoke: bool) {
-        match functions::delete_messages(chat_id, message_ids, revoke, self.client_id).await {
-            Ok(_) => tracing::info!("Messages deleted"),
-            Err(e) => tracing::error!("Failed to delete messages: {e:?}"),
+            Ok(enums::UserPrivacySettingRules::UserPrivacySettingRules(rules)) => !rules
+                .rules
+                .iter()
+                .any(|rule| matches!(rule, UserPrivacySettingRule::RestrictAll)),
+            Err(e) => {
+                tracing::error!("Failed to get the online visibility: {e:?}");
+                true
+            }
         }
     }
 
-    pub async fn use_quick_ack(&self) {
-        match functions::set_option(
-            String::from("use_quick_ack"),
-            Some(OptionValue::Boolean(OptionValueBoolean { value: true })),
+    pub async fn set_online_visible(&self, visible: bool) -> Result<(), tdlib_rs::types::Error> {
+        let rule = if visible {
+            UserPrivacySettingRule::AllowAll
+        } else {
+            UserPrivacySettingRule::RestrictAll
+        };
+        functions::set_user_privacy_setting_rules(
+            UserPrivacySetting::ShowStatus,
+            UserPrivacySettingRules { rules: vec![rule] },
             self.client_id,
         )
         .await
-        {
-            Ok(_) => {
-                tracing::info!("Quick ack enabled");
-            }
-            Err(e) => {
-                tracing::error!("Failed to enable quick ack: {e:?}");
-            }
-        }
     }
 
     async fn set_online(&self, online: bool) -> Result<(), tdlib_rs::types::Error> {
@@ -345,12 +1858,15 @@ impl TgBackend {
         let use_message_database = telegram_config.use_message_database;
         let system_language_code = telegram_config.system_language_code.clone();
         let device_model = telegram_config.device_model.clone();
+        let use_test_dc = telegram_config.use_test_dc;
+        let ignore_file_names = telegram_config.ignore_file_names;
+        let message_unload_delay = telegram_config.message_unload_delay;
 
         while let Some(state) = self.auth_rx.recv().await {
             match state {
                 AuthorizationState::WaitTdlibParameters => {
                     let response = functions::set_tdlib_parameters(
-                        false,
+                        use_test_dc,
                         database_dir.clone(),
                         String::new(),
                         String::new(),
@@ -368,29 +1884,47 @@ impl TgBackend {
                     )
                     .await;
 
-                    if let Err(error) = response {
-                        println!("{}", error.message);
+                    match response {
+                        Ok(_) => {
+                            self.set_tdlib_options(ignore_file_names, message_unload_delay)
+                                .await
+                        }
+                        Err(error) => println!("{}", error.message),
                     }
                 }
                 AuthorizationState::WaitPhoneNumber => loop {
-                    let phone_number =
-                        ask_user("Enter your phone number (include the country calling code):");
-                    let response = functions::set_authentication_phone_number(
-                        phone_number,
-                        None,
-                        self.client_id,
-                    )
-                    .await;
+                    let phone_number = ask_user(
+                        "Enter your phone number (include the country calling code), or `qr` to log in by scanning a QR code with another device:",
+                    );
+                    let response = if phone_number.eq_ignore_ascii_case("qr") {
+                        functions::request_qr_code_authentication(vec![], self.client_id).await
+                    } else {
+                        functions::set_authentication_phone_number(
+                            phone_number,
+                            None,
+                            self.client_id,
+                        )
+                        .await
+                    };
                     match response {
                         Ok(_) => break,
                         Err(e) => println!("{}", e.message),
                     }
                 },
                 AuthorizationState::WaitOtherDeviceConfirmation(x) => {
-                    println!(
-                        "Please confirm this login link on another device: {}",
-                        x.link
-                    );
+                    // TDLib sends this state again with a new link when the
+                    // previous one expires, so the QR code is redrawn.
+                    match render_qr_code(&x.link) {
+                        Some(qr_code) => {
+                            print!("\x1b[2J\x1b[1;1H{}", qr_code);
+                            println!("Scan this QR code with Telegram on another device (Settings > Devices > Link Desktop Device).");
+                            println!("The QR code is refreshed automatically when it expires.");
+                        }
+                        None => println!(
+                            "Please confirm this login link on another device: {}",
+                            x.link
+                        ),
+                    }
                 }
                 AuthorizationState::WaitEmailAddress(_x) => {
                     let email_address = ask_user("Please enter email address: ");
@@ -433,12 +1967,31 @@ impl TgBackend {
                         .await
                         .unwrap();
                 }
-                AuthorizationState::WaitPassword(_x) => {
-                    let password = ask_user("Please enter password: ");
-                    functions::check_authentication_password(password, self.client_id)
-                        .await
-                        .unwrap();
-                }
+                AuthorizationState::WaitPassword(x) => loop {
+                    if !x.password_hint.is_empty() {
+                        println!("Password hint: {}", x.password_hint);
+                    }
+                    let password = if x.has_recovery_email_address {
+                        ask_secret("Please enter password (or nothing if you forgot it): ")
+                    } else {
+                        ask_secret("Please enter password: ")
+                    };
+                    // An empty password is never valid, so it asks to reset the
+                    // password with the recovery email address.
+                    if password.is_empty() && x.has_recovery_email_address {
+                        if self
+                            .recover_authentication_password(&x.recovery_email_address_pattern)
+                            .await
+                        {
+                            break;
+                        }
+                        continue;
+                    }
+                    match functions::check_authentication_password(password, self.client_id).await {
+                        Ok(_) => break,
+                        Err(e) => println!("{}", e.message),
+                    }
+                },
                 AuthorizationState::Ready => {
                     // Maybe block all until this state is reached
                     self.have_authorization = true;
@@ -461,6 +2014,72 @@ impl TgBackend {
         }
     }
 
+    /// Reset the forgotten cloud password at login, with a code sent to the
+    /// recovery email address.
+    ///
+    /// # Arguments
+    /// * `email_address_pattern` - The pattern of the recovery email address.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the password has been reset and the user is logged
+    ///   in.
+    async fn recover_authentication_password(&self, email_address_pattern: &str) -> bool {
+        if let Err(e) = functions::request_authentication_password_recovery(self.client_id).await {
+            println!("{}", e.message);
+            return false;
+        }
+        println!("A recovery code has been sent to {}", email_address_pattern);
+        let code = ask_user("Enter the recovery code:");
+        let new_password = ask_secret("Enter a new password (or nothing to remove it): ");
+        let new_hint = if new_password.is_empty() {
+            String::new()
+        } else {
+            ask_user("Enter a hint for the new password (can be empty):")
+        };
+        match functions::recover_authentication_password(
+            code,
+            new_password,
+            new_hint,
+            self.client_id,
+        )
+        .await
+        {
+            Ok(_) => true,
+            Err(e) => {
+                println!("{}", e.message);
+                false
+            }
+        }
+    }
+
+    /// Remove a sent or failed message from the uploads in progress and, if
+    /// it was an upload, ask to start the next queued one.
+    /// A failed upload is reported, so that it can be retried.
+    ///
+    /// # Arguments
+    /// * `tg_context` - A reference to the Telegram context.
+    /// * `message_id` - The temporary id of the message.
+    /// * `failed_message_id` - The id of the failed message, if the message
+    ///   could not be sent.
+    fn upload_finished(tg_context: &TgContext, message_id: i64, failed_message_id: Option<i64>) {
+        let Some(upload) = tg_context.send_queue().finish(message_id) else {
+            return;
+        };
+        if let Some(event_tx) = tg_context.event_tx().as_ref() {
+            if let Some(failed_message_id) = failed_message_id {
+                let _ = event_tx.send(Event::UploadFailed(UploadDescriptor {
+                    chat_id: upload.chat_id,
+                    path: upload.path,
+                    size: upload.size,
+                    message_id: Some(failed_message_id),
+                    caption: upload.caption,
+                    self_destruct: upload.self_destruct,
+                }));
+            }
+            let _ = event_tx.send(Event::ProcessSendQueue);
+        }
+    }
+
     fn set_chat_positions(
         mut chats_index: MutexGuard<'_, BTreeSet<OrderedChat>>,
         chat: &mut Chat,
@@ -524,9 +2143,14 @@ impl TgBackend {
         let auth_tx = self.auth_tx.clone();
         let can_quit = self.can_quit.clone();
         let tg_context = self.app_context.tg_context();
+        let max_chat_messages = self.app_context.app_config().max_chat_messages;
+        let birthday_reminder = self.app_context.app_config().birthday_reminder;
 
         self.handle_updates = tokio::spawn(async move {
             tracing::info!("Starting handling updates from TDLib");
+            // The open chat, its last message and the time when the
+            // connection was lost, used to reconcile the gap when it is back.
+            let mut offline_since: Option<(i64, i64, Instant)> = None;
             while !can_quit.load(Ordering::Acquire) {
                 let mut update_dequeue: VecDeque<Update> = VecDeque::new();
                 if let Some((update, _client_id)) = tdlib_rs::receive() {
@@ -535,6 +2159,85 @@ impl TgBackend {
                     match update.clone() {
                         Update::MessageSendSucceeded(update_message) => {
                             tracing::info!("Message sent: {:?}", update_message);
+                            tg_context.remove_failed_message(
+                                update_message.message.chat_id,
+                                update_message.old_message_id,
+                            );
+                            Self::upload_finished(&tg_context, update_message.old_message_id, None);
+                            if let Some(event_tx) = tg_context.event_tx().as_ref() {
+                                let _ = event_tx.send(Event::LogMessage(
+                                    update_message.message.chat_id,
+                                    update_message.message.id,
+                                ));
+                            }
+                        }
+                        Update::MessageSendFailed(update_message) => {
+                            tracing::error!(
+                                "Failed to send message {} in chat {}: {:?}",
+                                update_message.old_message_id,
+                                update_message.message.chat_id,
+                                update_message.error
+                            );
+                            tg_context.add_failed_message(
+                                update_message.message.chat_id,
+                                update_message.message.id,
+                            );
+                            Self::upload_finished(
+                                &tg_context,
+                                update_message.old_message_id,
+                                Some(update_message.message.id),
+                            );
+                        }
+                        Update::File(update_file) => {
+                            let file = update_file.file;
+                            if file.remote.is_uploading_active || file.remote.is_uploading_completed
+                            {
+                                tg_context
+                                    .send_queue()
+                                    .set_progress(file.id, file.remote.uploaded_size as u64);
+                            }
+                        }
+                        Update::ConnectionState(update_connection_state) => {
+                            *tg_context.connection_state() =
+                                Some(update_connection_state.state.clone());
+                            tg_context
+                                .sync_state()
+                                .set_connection_state(&update_connection_state.state);
+                            match update_connection_state.state {
+                                ConnectionState::Ready => {
+                                    if let Some((chat_id, last_message_id, since)) =
+                                        offline_since.take()
+                                    {
+                                        if chat_id != 0
+                                            && tg_context.open_chat_id() == chat_id
+                                            && since.elapsed() >= OFFLINE_GAP_THRESHOLD
+                                        {
+                                            if let Some(event_tx) = tg_context.event_tx().as_ref() {
+                                                let _ = event_tx.send(Event::Reconnected(
+                                                    chat_id,
+                                                    last_message_id,
+                                                ));
+                                            }
+                                        }
+                                    }
+                                }
+                                state => {
+                                    tracing::info!("Connection state: {:?}", state);
+                                    if offline_since.is_none() {
+                                        let last_message_id = tg_context
+                                            .open_chat_messages()
+                                            .iter()
+                                            .map(|m| m.id())
+                                            .max()
+                                            .unwrap_or(0);
+                                        offline_since = Some((
+                                            tg_context.open_chat_id(),
+                                            last_message_id,
+                                            Instant::now(),
+                                        ));
+                                    }
+                                }
+                            }
                         }
                         Update::MessageSendAcknowledged(update_message) => {
                             tg_context.set_last_acknowledged_message_id(update_message.message_id);
@@ -651,6 +2354,23 @@ impl TgBackend {
                                     }
                                     None => update_dequeue.push_back(update),
                                 }
+                            } else {
+                                // The positions in the other lists, like the
+                                // folders, are kept to show the chats of a
+                                // folder in its order.
+                                match tg_context.chats().get_mut(&update_chat.chat_id) {
+                                    Some(chat) => {
+                                        let list =
+                                            TdChatList::from(update_chat.position.list.clone());
+                                        chat.positions.retain(|position| {
+                                            TdChatList::from(position.list.clone()) != list
+                                        });
+                                        if update_chat.position.order != 0 {
+                                            chat.positions.push(update_chat.position);
+                                        }
+                                    }
+                                    None => update_dequeue.push_back(update),
+                                }
                             }
                         }
                         Update::ChatReadInbox(update_chat) => {
@@ -659,6 +2379,7 @@ impl TgBackend {
                                     chat.last_read_inbox_message_id =
                                         update_chat.last_read_inbox_message_id;
                                     chat.unread_count = update_chat.unread_count;
+                                    tg_context.sync_state().record_read_state();
                                 }
                                 None => update_dequeue.push_back(update),
                             }
@@ -672,6 +2393,9 @@ impl TgBackend {
                                 None => update_dequeue.push_back(update),
                             }
                         }
+                        Update::ChatFolders(update_chat_folders) => {
+                            *tg_context.chat_folders() = update_chat_folders.chat_folders;
+                        }
                         Update::ChatActionBar(update_chat) => {
                             match tg_context.chats().get_mut(&update_chat.chat_id) {
                                 Some(chat) => {
@@ -680,6 +2404,17 @@ impl TgBackend {
                                 None => update_dequeue.push_back(update),
                             }
                         }
+                        Update::ChatAction(update_chat) => {
+                            if let (enums::MessageSender::User(user), Some(event_tx)) =
+                                (update_chat.sender_id, tg_context.event_tx().as_ref())
+                            {
+                                let _ = event_tx.send(Event::UpdateChatAction(
+                                    update_chat.chat_id,
+                                    user.user_id,
+                                    matches!(update_chat.action, enums::ChatAction::Typing),
+                                ));
+                            }
+                        }
                         Update::ChatAvailableReactions(update_chat) => {
                             match tg_context.chats().get_mut(&update_chat.chat_id) {
                                 Some(chat) => {
@@ -692,6 +2427,7 @@ impl TgBackend {
                             match tg_context.chats().get_mut(&update_chat.chat_id) {
                                 Some(chat) => {
                                     chat.unread_mention_count = update_chat.unread_mention_count;
+                                    tg_context.sync_state().record_read_state();
                                 }
                                 None => update_dequeue.push_back(update),
                             }
@@ -722,6 +2458,14 @@ impl TgBackend {
                                         chat,
                                         update_chat.positions,
                                     );
+                                    // The prompt checks whether the draft
+                                    // conflicts with the text being typed.
+                                    if update_chat.chat_id == tg_context.open_chat_id() {
+                                        if let Some(event_tx) = tg_context.event_tx().as_ref() {
+                                            let _ = event_tx
+                                                .send(Event::ChatDraftChanged(update_chat.chat_id));
+                                        }
+                                    }
                                 }
                                 None => update_dequeue.push_back(update),
                             }
@@ -796,6 +2540,7 @@ impl TgBackend {
                             match tg_context.chats().get_mut(&update_chat.chat_id) {
                                 Some(chat) => {
                                     chat.is_marked_as_unread = update_chat.is_marked_as_unread;
+                                    tg_context.sync_state().record_read_state();
                                 }
                                 None => update_dequeue.push_back(update),
                             }
@@ -823,6 +2568,21 @@ impl TgBackend {
                                 None => update_dequeue.push_back(update),
                             }
                         }
+                        // The contacts whose birthday is close, from yesterday
+                        // to tomorrow in some time zone, are sent when `tgt`
+                        // starts and when the day changes.
+                        Update::ContactCloseBirthdays(update) if birthday_reminder => {
+                            *tg_context.birthdays_today() = update
+                                .close_birthday_users
+                                .iter()
+                                .filter(|user| DateTimeEntry::is_birthday_today(&user.birthdate))
+                                .map(|user| user.user_id)
+                                .collect();
+                            tracing::info!(
+                                "{} contacts have their birthday today",
+                                tg_context.birthdays_today().len()
+                            );
+                        }
                         Update::UserFullInfo(update_user_full_info) => {
                             tg_context.users_full_info().insert(
                                 update_user_full_info.user_id,
@@ -846,24 +2606,75 @@ impl TgBackend {
                             // in real time the message displayed
                             let message = update_new_message.message;
                             let chat_id = message.chat_id;
-                            if tg_context.open_chat_id() == chat_id {
-                                tg_context
-                                    .open_chat_messages()
-                                    .insert(0, MessageEntry::from(&message));
+                            let entry = MessageEntry::from(&message);
+                            if let Some(event_tx) = tg_context.event_tx().as_ref() {
+                                if !message.is_outgoing {
+                                    let _ = event_tx.send(Event::NewMessage(
+                                        chat_id,
+                                        entry.sender_id(),
+                                        entry.message_content_to_string(),
+                                    ));
+                                }
+                                // The messages being sent are logged once
+                                // they have their final id.
+                                if message.sending_state.is_none() {
+                                    let _ = event_tx.send(Event::LogMessage(chat_id, message.id));
+                                }
+                            }
+                            // A new message is loaded with the newer ones if
+                            // they are not all loaded.
+                            if tg_context.open_chat_id() == chat_id
+                                && !tg_context.has_newer_messages()
+                            {
+                                tg_context.open_chat_messages().insert(0, entry);
+                                tg_context.trim_open_chat_messages(max_chat_messages, true);
                             }
                         }
-                        Update::MessageEdited(_) => {}
-                        Update::MessageContent(message) => {
-                            if tg_context.open_chat_id() == message.chat_id {
-                                for m in tg_context.open_chat_messages().iter_mut() {
-                                    if m.id() == message.message_id {
-                                        m.set_message_content(&message.new_content);
-                                        m.set_is_edited(true);
-                                    }
+                        Update::MessageEdited(message)
+                            if tg_context.open_chat_id() == message.chat_id =>
+                        {
+                            for m in tg_context.open_chat_messages().iter_mut() {
+                                if m.id() == message.message_id {
+                                    m.set_inline_keyboard(message.reply_markup.as_ref());
+                                }
+                            }
+                        }
+                        Update::MessageContent(message)
+                            if tg_context.open_chat_id() == message.chat_id =>
+                        {
+                            for m in tg_context.open_chat_messages().iter_mut() {
+                                if m.id() == message.message_id {
+                                    m.set_message_content(&message.new_content);
+                                    m.set_is_edited(true);
+                                }
+                            }
+                        }
+                        Update::MessageIsPinned(update_message)
+                            if tg_context.open_chat_id() == update_message.chat_id =>
+                        {
+                            if let Some(event_tx) = tg_context.event_tx().as_ref() {
+                                let _ = event_tx
+                                    .send(Event::PinnedMessageChanged(update_message.chat_id));
+                            }
+                        }
+                        Update::MessageInteractionInfo(update_interaction_info)
+                            if tg_context.open_chat_id() == update_interaction_info.chat_id =>
+                        {
+                            for m in tg_context.open_chat_messages().iter_mut() {
+                                if m.id() == update_interaction_info.message_id {
+                                    m.set_interaction_info(
+                                        update_interaction_info.interaction_info.as_ref(),
+                                    );
                                 }
                             }
                         }
                         Update::DeleteMessages(update_delete_messages) => {
+                            for message_id in update_delete_messages.message_ids.iter() {
+                                tg_context.remove_failed_message(
+                                    update_delete_messages.chat_id,
+                                    *message_id,
+                                );
+                            }
                             if tg_context.open_chat_id() == update_delete_messages.chat_id {
                                 let mut i = 0;
                                 while i < tg_context.open_chat_messages().len() {
@@ -898,3 +2709,47 @@ fn ask_user(string: &str) -> String {
     std::io::stdin().read_line(&mut input).unwrap();
     input.trim().to_string()
 }
+
+/// Ask the user for a secret, like a password, without showing what is typed.
+///
+/// # Arguments
+/// * `string` - The question.
+///
+/// # Returns
+/// * `String` - The answer.
+fn ask_secret(string: &str) -> String {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use std::io::Write;
+
+    print!("{}", string);
+    let _ = std::io::stdout().flush();
+    // Without the raw mode, the terminal would echo the typed characters.
+    if crossterm::terminal::enable_raw_mode().is_err() {
+        println!();
+        return ask_user("");
+    }
+    let mut input = String::new();
+    while let Ok(event) = event::read() {
+        let Event::Key(key) = event else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter => break,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let _ = crossterm::terminal::disable_raw_mode();
+                std::process::exit(130);
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+    }
+    let _ = crossterm::terminal::disable_raw_mode();
+    println!();
+    input.trim().to_string()
+}
@@ -1,23 +1,49 @@
 use super::message_entry::MessageEntry;
+use super::offline_gap::OfflineGap;
 use crate::tg::message_entry::DateTimeEntry;
 use crate::{
-    app_error::AppError, components::chat_list_window::ChatListEntry, event::Event,
-    tg::ordered_chat::OrderedChat,
+    app_error::AppError,
+    chat_list_rules::ChatKind,
+    components::chat_list_window::ChatListEntry,
+    event::Event,
+    tg::{
+        attachments::StagedAttachments,
+        history_pages::HistoryPages,
+        ordered_chat::OrderedChat,
+        presence::Presence,
+        send_queue::SendQueue,
+        sync_state::SyncState,
+        td_enums::TdChatList,
+        typing::{self, TypingUsers},
+    },
 };
-use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeSet, HashMap, HashSet},
+    ops::Range,
     sync::{Mutex, MutexGuard},
+    time::{SystemTime, UNIX_EPOCH},
 };
 use tdlib_rs::{
-    enums::ChatType,
+    enums::{
+        ChatType, ConnectionState, InputMessageContent, InputMessageReplyTo, MessageSender,
+        UserType,
+    },
     types::{
-        BasicGroup, BasicGroupFullInfo, Chat, SecretChat, Supergroup, SupergroupFullInfo, User,
-        UserFullInfo,
+        BasicGroup, BasicGroupFullInfo, Chat, ChatFolderInfo, ChatMember, SecretChat, Supergroup,
+        SupergroupFullInfo, User, UserFullInfo,
     },
 };
 use tokio::sync::mpsc::UnboundedSender;
 
+/// The minimum number of messages of the open chat kept in memory, so that
+/// the selected message is not dropped when older messages are loaded.
+pub const MIN_CHAT_MESSAGES: usize = 200;
+/// The maximum size of a file that can be uploaded by a regular account.
+pub const UPLOAD_SIZE_LIMIT: u64 = 2000 * 1024 * 1024;
+/// The maximum size of a file that can be uploaded by a premium account.
+pub const UPLOAD_SIZE_LIMIT_PREMIUM: u64 = 4000 * 1024 * 1024;
+
 #[derive(Debug, Default)]
 pub struct TgContext {
     users: Mutex<HashMap<i64, User>>,
@@ -40,16 +66,81 @@ pub struct TgContext {
     // in the chat window.
     open_chat_messages: Mutex<Vec<MessageEntry>>,
     open_chat_user: Mutex<Option<User>>,
+    /// The most recently pinned message of the open chat, if any.
+    pinned_message: Mutex<Option<MessageEntry>>,
 
     last_acknowledged_message_id: AtomicI64,
 
     /// The message id from which to start loading the chat history.
     from_message_id: AtomicI64,
+    /// Whether messages newer than the loaded ones of the open chat were
+    /// dropped or not loaded yet, so that they are loaded when scrolled to.
+    has_newer_messages: AtomicBool,
+    /// The pages of older messages of the open chat loaded in the
+    /// background.
+    history_pages: Mutex<HistoryPages>,
+    /// The message selected in the chat window, or 0, which is never dropped
+    /// to limit the memory.
+    selected_message_id: AtomicI64,
+    /// The last read incoming message of the open chat when it was opened,
+    /// the unread separator is shown after it, or 0 if there were no unread
+    /// messages.
+    unread_boundary_message_id: AtomicI64,
 
     /// reply message id
     reply_message_id: AtomicI64,
     /// reply message text
     reply_message_text: Mutex<String>,
+
+    /// The outgoing messages that failed to be sent, grouped by chat id.
+    failed_messages: Mutex<HashMap<i64, HashSet<i64>>>,
+
+    /// The outgoing pipeline of the file uploads.
+    send_queue: Mutex<SendQueue>,
+    /// The attachments staged to be sent with the next message.
+    attachments: Mutex<StagedAttachments>,
+
+    /// The chat highlighted in the chat list that is previewed, or 0.
+    preview_chat_id: AtomicI64,
+    /// The last messages of the previewed chat, the most recent first.
+    preview_chat_messages: Mutex<Vec<MessageEntry>>,
+
+    /// The users that are typing in the chats.
+    typing_users: Mutex<TypingUsers>,
+    /// The activity of the user, that decides whether they are online.
+    presence: Mutex<Presence>,
+
+    /// The chat folders of the user, in the order they are shown.
+    chat_folders: Mutex<Vec<ChatFolderInfo>>,
+
+    /// The messages of the open chat received while offline, if any.
+    offline_gap: Mutex<Option<OfflineGap>>,
+
+    /// The contacts whose birthday is today.
+    birthdays_today: Mutex<Vec<i64>>,
+
+    /// The messages of all the chats found by the last global search, with
+    /// the id of their chat.
+    search_results: Mutex<Vec<(i64, MessageEntry)>>,
+    /// The ids of the messages of the open chat found by the search of the
+    /// chat, the newest first.
+    chat_search_results: Mutex<Vec<i64>>,
+    /// The ids of the users who are contacts of the current user, loaded
+    /// when the contacts are shown.
+    contacts: Mutex<Vec<i64>>,
+    /// The chat whose members are shown in the members panel, or 0.
+    members_chat_id: AtomicI64,
+    /// The members of the chat shown in the members panel, loaded when the
+    /// panel is shown.
+    chat_members: Mutex<Vec<ChatMember>>,
+    /// The message to select in the chat once it is loaded, like a result
+    /// of the global search, or 0.
+    jump_message_id: AtomicI64,
+
+    /// The last state of the connection to Telegram, if any was received.
+    connection_state: Mutex<Option<ConnectionState>>,
+    /// How far the chat list and the read states are from being in sync.
+    sync_state: Mutex<SyncState>,
 }
 
 impl TgContext {
@@ -102,16 +193,128 @@ impl TgContext {
         self.last_acknowledged_message_id.load(Ordering::Relaxed)
     }
 
+    pub fn preview_chat_id(&self) -> i64 {
+        self.preview_chat_id.load(Ordering::Relaxed)
+    }
+    pub fn preview_chat_messages(&self) -> MutexGuard<'_, Vec<MessageEntry>> {
+        self.preview_chat_messages.lock().unwrap()
+    }
+
+    /// Set the chat to preview, or 0 to stop the preview.
+    /// The messages of the previous preview are cleared.
+    pub fn set_preview_chat_id(&self, chat_id: i64) {
+        if self.preview_chat_id.swap(chat_id, Ordering::Relaxed) != chat_id {
+            self.preview_chat_messages().clear();
+        }
+    }
+
     pub fn set_open_chat_user(&self, user: Option<User>) {
         *self.open_chat_user() = user;
     }
 
+    pub fn pinned_message(&self) -> MutexGuard<'_, Option<MessageEntry>> {
+        self.pinned_message.lock().unwrap()
+    }
+
+    pub fn set_pinned_message(&self, message: Option<MessageEntry>) {
+        *self.pinned_message() = message;
+    }
+
     pub fn set_open_chat_id(&self, chat_id: i64) {
         self.open_chat_id.store(chat_id, Ordering::Relaxed);
     }
 
     pub fn clear_open_chat_messages(&self) {
         *self.open_chat_messages() = Vec::new();
+        *self.offline_gap() = None;
+        self.set_has_newer_messages(false);
+        self.history_pages().cancel();
+    }
+
+    pub fn history_pages(&self) -> MutexGuard<'_, HistoryPages> {
+        self.history_pages.lock().unwrap()
+    }
+
+    pub fn has_newer_messages(&self) -> bool {
+        self.has_newer_messages.load(Ordering::Relaxed)
+    }
+
+    pub fn set_has_newer_messages(&self, has_newer_messages: bool) {
+        self.has_newer_messages
+            .store(has_newer_messages, Ordering::Relaxed);
+    }
+
+    pub fn selected_message_id(&self) -> i64 {
+        self.selected_message_id.load(Ordering::Relaxed)
+    }
+
+    pub fn set_selected_message_id(&self, message_id: i64) {
+        self.selected_message_id
+            .store(message_id, Ordering::Relaxed);
+    }
+
+    pub fn jump_message_id(&self) -> i64 {
+        self.jump_message_id.load(Ordering::Relaxed)
+    }
+
+    pub fn set_jump_message_id(&self, message_id: i64) {
+        self.jump_message_id.store(message_id, Ordering::Relaxed);
+    }
+
+    pub fn unread_boundary_message_id(&self) -> i64 {
+        self.unread_boundary_message_id.load(Ordering::Relaxed)
+    }
+
+    pub fn set_unread_boundary_message_id(&self, message_id: i64) {
+        self.unread_boundary_message_id
+            .store(message_id, Ordering::Relaxed);
+    }
+
+    /// Drop the loaded messages of the open chat beyond a limit, on the side
+    /// far from the view. They stay in the database of TDLib and are loaded
+    /// again when scrolled to. The selected message is never dropped.
+    ///
+    /// # Arguments
+    /// * `limit` - The maximum number of messages, `0` for no limit.
+    /// * `keep_newest` - Whether the newest messages are kept, after newer
+    ///   messages are loaded, or the oldest ones, after older messages are
+    ///   loaded.
+    pub fn trim_open_chat_messages(&self, limit: usize, keep_newest: bool) {
+        if limit == 0 {
+            return;
+        }
+        let limit = limit.max(MIN_CHAT_MESSAGES);
+        let selected_message_id = self.selected_message_id();
+        let mut messages = self.open_chat_messages();
+        let selected = messages
+            .iter()
+            .position(|message| selected_message_id != 0 && message.id() == selected_message_id);
+        let dropped = messages_to_drop(messages.len(), limit, selected, keep_newest);
+        if dropped.is_empty() {
+            return;
+        }
+        messages.drain(dropped);
+        // The messages are sorted from the newest to the oldest.
+        if keep_newest {
+            if let Some(oldest) = messages.last() {
+                self.set_from_message_id(oldest.id());
+            }
+            self.history_pages().drop_oldest();
+        } else {
+            self.set_has_newer_messages(true);
+        }
+    }
+
+    /// Add a page of older messages after the loaded messages of the open
+    /// chat. The messages already loaded, like those received while the page
+    /// was loaded, are skipped.
+    ///
+    /// # Arguments
+    /// * `page` - The messages, from the newest to the oldest.
+    pub fn extend_older_messages(&self, page: impl IntoIterator<Item = MessageEntry>) {
+        let mut messages = self.open_chat_messages();
+        let oldest_id = messages.last().map_or(i64::MAX, MessageEntry::id);
+        messages.extend(page.into_iter().filter(|message| message.id() < oldest_id));
     }
 
     pub fn set_from_message_id(&self, from_message_id: i64) {
@@ -138,11 +341,133 @@ impl TgContext {
         *self.reply_message_text.lock().unwrap() = text;
     }
 
+    pub fn send_queue(&self) -> MutexGuard<'_, SendQueue> {
+        self.send_queue.lock().unwrap()
+    }
+    pub fn attachments(&self) -> MutexGuard<'_, StagedAttachments> {
+        self.attachments.lock().unwrap()
+    }
+    pub fn typing_users(&self) -> MutexGuard<'_, TypingUsers> {
+        self.typing_users.lock().unwrap()
+    }
+    pub fn presence(&self) -> MutexGuard<'_, Presence> {
+        self.presence.lock().unwrap()
+    }
+    pub fn chat_folders(&self) -> MutexGuard<'_, Vec<ChatFolderInfo>> {
+        self.chat_folders.lock().unwrap()
+    }
+    pub fn offline_gap(&self) -> MutexGuard<'_, Option<OfflineGap>> {
+        self.offline_gap.lock().unwrap()
+    }
+    pub fn birthdays_today(&self) -> MutexGuard<'_, Vec<i64>> {
+        self.birthdays_today.lock().unwrap()
+    }
+    pub fn search_results(&self) -> MutexGuard<'_, Vec<(i64, MessageEntry)>> {
+        self.search_results.lock().unwrap()
+    }
+    pub fn chat_search_results(&self) -> MutexGuard<'_, Vec<i64>> {
+        self.chat_search_results.lock().unwrap()
+    }
+    pub fn contacts(&self) -> MutexGuard<'_, Vec<i64>> {
+        self.contacts.lock().unwrap()
+    }
+    pub fn members_chat_id(&self) -> i64 {
+        self.members_chat_id.load(Ordering::Relaxed)
+    }
+    pub fn set_members_chat_id(&self, chat_id: i64) {
+        self.members_chat_id.store(chat_id, Ordering::Relaxed);
+    }
+    pub fn chat_members(&self) -> MutexGuard<'_, Vec<ChatMember>> {
+        self.chat_members.lock().unwrap()
+    }
+    pub fn connection_state(&self) -> MutexGuard<'_, Option<ConnectionState>> {
+        self.connection_state.lock().unwrap()
+    }
+    pub fn sync_state(&self) -> MutexGuard<'_, SyncState> {
+        self.sync_state.lock().unwrap()
+    }
+
+    /// Get the state of the connection to Telegram, as shown to the user.
+    ///
+    /// # Returns
+    /// * `&'static str` - The state of the connection.
+    pub fn connection_label(&self) -> &'static str {
+        match *self.connection_state() {
+            Some(ConnectionState::Ready) => "online",
+            Some(ConnectionState::Updating) => "updating",
+            Some(ConnectionState::WaitingForNetwork) => "waiting for network",
+            Some(ConnectionState::ConnectingToProxy) => "connecting to proxy",
+            Some(ConnectionState::Connecting) | None => "connecting",
+        }
+    }
+
+    /// Get the number of unread messages of the chats of the main list.
+    ///
+    /// # Returns
+    /// * `i32` - The number of unread messages.
+    pub fn unread_total(&self) -> i32 {
+        let chats = self.chats();
+        self.chats_index()
+            .iter()
+            .filter_map(|ord_chat| chats.get(&ord_chat.chat_id))
+            .map(|chat| chat.unread_count)
+            .sum()
+    }
+
+    pub fn failed_messages(&self) -> MutexGuard<'_, HashMap<i64, HashSet<i64>>> {
+        self.failed_messages.lock().unwrap()
+    }
+
+    /// Mark an outgoing message as failed to be sent.
+    pub fn add_failed_message(&self, chat_id: i64, message_id: i64) {
+        self.failed_messages()
+            .entry(chat_id)
+            .or_default()
+            .insert(message_id);
+    }
+
+    /// Forget a failed outgoing message, for example because it has been
+    /// resent successfully or deleted.
+    pub fn remove_failed_message(&self, chat_id: i64, message_id: i64) {
+        let mut failed_messages = self.failed_messages();
+        if let Some(message_ids) = failed_messages.get_mut(&chat_id) {
+            message_ids.remove(&message_id);
+            if message_ids.is_empty() {
+                failed_messages.remove(&chat_id);
+            }
+        }
+    }
+
+    pub fn has_failed_messages(&self, chat_id: i64) -> bool {
+        self.failed_messages().contains_key(&chat_id)
+    }
+
     pub fn delete_message(&self, message_id: i64) {
         let mut open_chat_messages = self.open_chat_messages();
         open_chat_messages.retain(|message| message.id() != message_id);
     }
 
+    /// Get the draft of a chat, if it has a text.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    ///
+    /// # Returns
+    /// * `Option<(String, Option<i64>)>` - The text of the draft and the id of
+    ///   the message it replies to, if any.
+    pub fn chat_draft(&self, chat_id: i64) -> Option<(String, Option<i64>)> {
+        let chats = self.chats();
+        let draft = chats.get(&chat_id)?.draft_message.as_ref()?;
+        let InputMessageContent::InputMessageText(content) = &draft.input_message_text else {
+            return None;
+        };
+        let reply_to = match &draft.reply_to {
+            Some(InputMessageReplyTo::Message(reply_to)) => Some(reply_to.message_id),
+            _ => None,
+        };
+        Some((content.text.text.clone(), reply_to))
+    }
+
     pub fn open_chat_user_status(&self) -> String {
         if let Some(user) = self.open_chat_user().as_ref() {
             return match &user.status {
@@ -162,6 +487,39 @@ impl TgContext {
         "".to_string()
     }
 
+    /// Get the line that lists the users typing in a chat, with an animated
+    /// ellipsis.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    ///
+    /// # Returns
+    /// * `Option<String>` - The line or `None` if nobody is typing.
+    pub fn typing_text(&self, chat_id: i64) -> Option<String> {
+        let user_ids = self.typing_users().typing_users(chat_id);
+        let names = {
+            let users = self.users();
+            user_ids
+                .iter()
+                .filter(|user_id| **user_id != self.me())
+                .filter_map(|user_id| users.get(user_id))
+                .map(|user| {
+                    if user.first_name.is_empty() {
+                        user.last_name.clone()
+                    } else {
+                        user.first_name.clone()
+                    }
+                })
+                .collect::<Vec<String>>()
+        };
+        typing::typing_text(
+            &names,
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default(),
+        )
+    }
+
     pub fn unread_messages(&self) -> Vec<i64> {
         let mut unread_messages: Vec<i64> = Vec::new();
         for message in self.open_chat_messages().iter() {
@@ -208,6 +566,46 @@ impl TgContext {
         None
     }
 
+    /// Get the name of an identity the messages of a chat can be sent as.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat the messages are sent to.
+    /// * `sender` - The identity.
+    ///
+    /// # Returns
+    /// * `String` - The name of the identity, like the title of a channel.
+    pub fn message_sender_name(&self, chat_id: i64, sender: &MessageSender) -> String {
+        match sender {
+            MessageSender::User(user) if user.user_id == self.me() => "yourself".to_string(),
+            MessageSender::User(user) => self
+                .try_name_from_chats_or_users(user.user_id)
+                .unwrap_or_else(|| user.user_id.to_string()),
+            MessageSender::Chat(chat) if chat.chat_id == chat_id => "anonymous admin".to_string(),
+            MessageSender::Chat(chat) => self
+                .name_from_chats(chat.chat_id)
+                .unwrap_or_else(|| chat.chat_id.to_string()),
+        }
+    }
+
+    /// Get the name of the identity the messages of a chat are sent as, when
+    /// it is not the user.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    ///
+    /// # Returns
+    /// * `Option<String>` - The name of the identity, if it is not the user.
+    pub fn send_as_name(&self, chat_id: i64) -> Option<String> {
+        let sender = self
+            .chats()
+            .get(&chat_id)
+            .and_then(|chat| chat.message_sender_id.clone())?;
+        match &sender {
+            MessageSender::User(user) if user.user_id == self.me() => None,
+            _ => Some(self.message_sender_name(chat_id, &sender)),
+        }
+    }
+
     pub fn name_from_chats(&self, chat_id: i64) -> Option<String> {
         if let Some(chat) = self.chats().get(&chat_id) {
             return Some(chat.title.clone());
@@ -223,6 +621,27 @@ impl TgContext {
         self.reply_message_text.lock().unwrap()
     }
 
+    pub fn me_is_premium(&self) -> bool {
+        self.users().get(&self.me()).is_some_and(|me| me.is_premium)
+    }
+
+    pub fn upload_size_limit(&self) -> u64 {
+        if self.me_is_premium() {
+            UPLOAD_SIZE_LIMIT_PREMIUM
+        } else {
+            UPLOAD_SIZE_LIMIT
+        }
+    }
+
+    pub fn user_of_chat(&self, chat_id: i64) -> Option<User> {
+        if let Some(chat) = self.chats().get(&chat_id) {
+            if let ChatType::Private(p) = &chat.r#type {
+                return self.users().get(&p.user_id).cloned();
+            }
+        }
+        None
+    }
+
     pub fn name_of_open_chat_id(&self) -> Option<String> {
         if let Some(chat) = self.chats().get(&self.open_chat_id()) {
             return Some(chat.title.clone());
@@ -233,46 +652,190 @@ impl TgContext {
     pub fn get_chats_index(&self) -> Result<Option<Vec<ChatListEntry>>, AppError<Event>> {
         let chats_index = self.chats_index();
         let chats = self.chats();
-        let mut chat_list: Vec<ChatListEntry> = Vec::new();
-        for ord_chat in chats_index.iter() {
-            let mut chat_list_item = ChatListEntry::new();
-            chat_list_item.set_chat_id(ord_chat.chat_id);
-            if let Some(chat) = chats.get(&ord_chat.chat_id) {
-                chat_list_item.set_is_marked_as_unread(chat.unread_count > 0);
-                chat_list_item.set_chat_name(chat.title.clone());
-                chat_list_item.set_last_read_inbox_message_id(chat.last_read_inbox_message_id);
-                chat_list_item.set_last_read_outbox_message_id(chat.last_read_outbox_message_id);
-                chat_list_item.set_unread_count(chat.unread_count);
-                if let Some(chat_message) = &chat.last_message {
-                    chat_list_item.set_last_message(MessageEntry::from(chat_message));
-                }
-                match &chat.r#type {
-                    ChatType::Private(p) => {
-                        if let Some(user) = self.users().get(&p.user_id) {
-                            chat_list_item.set_user(user.clone());
+        let chat_list = chats_index
+            .iter()
+            .map(|ord_chat| {
+                let mut entry = self.chat_list_entry(ord_chat.chat_id, &chats);
+                entry.set_is_pinned(ord_chat.position.is_pinned);
+                entry
+            })
+            .collect();
+        Ok(Some(chat_list))
+    }
+
+    /// Get the chats of a folder, in the order of the folder. Only the chats
+    /// whose position in the folder is known are returned, so the chats of
+    /// the folder are loaded when it is shown.
+    ///
+    /// # Arguments
+    /// * `folder_id` - The id of the folder.
+    ///
+    /// # Returns
+    /// * `Result<Option<Vec<ChatListEntry>>, AppError<Event>>` - The chats.
+    pub fn get_chat_folder_index(
+        &self,
+        folder_id: i32,
+    ) -> Result<Option<Vec<ChatListEntry>>, AppError<Event>> {
+        let chats = self.chats();
+        // The pinned chats are first.
+        let mut ordered: Vec<(bool, i64, i64)> = chats
+            .values()
+            .filter_map(|chat| {
+                chat.positions
+                    .iter()
+                    .find(|position| {
+                        TdChatList::from(position.list.clone()) == TdChatList::Folder(folder_id)
+                    })
+                    .filter(|position| position.order != 0)
+                    .map(|position| (position.is_pinned, position.order, chat.id))
+            })
+            .collect();
+        ordered.sort_unstable_by(|a, b| b.cmp(a));
+        let chat_list = ordered
+            .into_iter()
+            .map(|(is_pinned, _, chat_id)| {
+                let mut entry = self.chat_list_entry(chat_id, &chats);
+                entry.set_is_pinned(is_pinned);
+                entry
+            })
+            .collect();
+        Ok(Some(chat_list))
+    }
+
+    /// Get the entry of a chat in the chat list.
+    ///
+    /// # Arguments
+    /// * `chat_id` - The id of the chat.
+    /// * `chats` - The chats, locked by the caller.
+    ///
+    /// # Returns
+    /// * `ChatListEntry` - The entry.
+    fn chat_list_entry(&self, chat_id: i64, chats: &HashMap<i64, Chat>) -> ChatListEntry {
+        let mut chat_list_item = ChatListEntry::new();
+        chat_list_item.set_chat_id(chat_id);
+        if let Some(chat) = chats.get(&chat_id) {
+            chat_list_item.set_is_marked_as_unread(chat.unread_count > 0);
+            chat_list_item.set_chat_name(chat.title.clone());
+            chat_list_item.set_last_read_inbox_message_id(chat.last_read_inbox_message_id);
+            chat_list_item.set_last_read_outbox_message_id(chat.last_read_outbox_message_id);
+            chat_list_item.set_unread_count(chat.unread_count);
+            chat_list_item.set_unread_mention_count(chat.unread_mention_count);
+            chat_list_item.set_has_failed_messages(self.has_failed_messages(chat.id));
+            chat_list_item.set_is_muted(chat.notification_settings.mute_for > 0);
+            if let Some(chat_message) = &chat.last_message {
+                chat_list_item.set_last_message(MessageEntry::from(chat_message));
+            }
+            match &chat.r#type {
+                ChatType::Private(p) => {
+                    if let Some(user) = self.users().get(&p.user_id) {
+                        if matches!(user.r#type, UserType::Bot(_)) {
+                            chat_list_item.set_kind(ChatKind::Bot);
                         }
-                    }
-                    ChatType::BasicGroup(bg) => {
-                        if let Some(_basic_group) = self.basic_groups().get(&bg.basic_group_id) {
-                            chat_list_item.set_chat_name(chat.title.clone());
+                        if let Some(username) = user
+                            .usernames
+                            .as_ref()
+                            .and_then(|usernames| usernames.active_usernames.first())
+                        {
+                            chat_list_item.set_username(username.clone());
                         }
+                        chat_list_item.set_user(user.clone());
                     }
-                    ChatType::Supergroup(sg) => {
-                        if let Some(_supergroup) = self.supergroups().get(&sg.supergroup_id) {
-                            chat_list_item.set_chat_name(chat.title.clone());
-                        }
+                }
+                ChatType::BasicGroup(bg) => {
+                    chat_list_item.set_kind(ChatKind::Group);
+                    if let Some(_basic_group) = self.basic_groups().get(&bg.basic_group_id) {
+                        chat_list_item.set_chat_name(chat.title.clone());
                     }
-                    ChatType::Secret(s) => {
-                        if let Some(_secret_chat) = self.secret_chats().get(&s.secret_chat_id) {
-                            chat_list_item.set_chat_name(chat.title.clone());
+                }
+                ChatType::Supergroup(sg) => {
+                    chat_list_item.set_kind(if sg.is_channel {
+                        ChatKind::Channel
+                    } else {
+                        ChatKind::Group
+                    });
+                    if let Some(supergroup) = self.supergroups().get(&sg.supergroup_id) {
+                        chat_list_item.set_chat_name(chat.title.clone());
+                        if let Some(username) = supergroup
+                            .usernames
+                            .as_ref()
+                            .and_then(|usernames| usernames.active_usernames.first())
+                        {
+                            chat_list_item.set_username(username.clone());
                         }
                     }
                 }
+                ChatType::Secret(s) => {
+                    chat_list_item.set_kind(ChatKind::Secret);
+                    if let Some(_secret_chat) = self.secret_chats().get(&s.secret_chat_id) {
+                        chat_list_item.set_chat_name(chat.title.clone());
+                    }
+                }
             }
-
-            chat_list.push(chat_list_item);
         }
 
-        Ok(Some(chat_list))
+        chat_list_item
+    }
+}
+
+/// Get the range of the messages dropped to keep at most `limit` of them,
+/// sorted from the newest to the oldest, without dropping the selected one.
+///
+/// # Arguments
+/// * `len` - The number of messages.
+/// * `limit` - The maximum number of messages.
+/// * `selected` - The index of the selected message, if any.
+/// * `keep_newest` - Whether the oldest messages are dropped, at the end, or
+///   the newest ones, at the start.
+///
+/// # Returns
+/// * `Range<usize>` - The indexes of the dropped messages, possibly empty.
+fn messages_to_drop(
+    len: usize,
+    limit: usize,
+    selected: Option<usize>,
+    keep_newest: bool,
+) -> Range<usize> {
+    if len <= limit {
+        return 0..0;
+    }
+    if keep_newest {
+        let kept = selected.map_or(limit, |selected| limit.max(selected + 1));
+        kept.min(len)..len
+    } else {
+        let excess = len - limit;
+        0..selected.map_or(excess, |selected| excess.min(selected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_to_drop_within_limit() {
+        assert!(messages_to_drop(10, 10, None, true).is_empty());
+        assert!(messages_to_drop(5, 10, Some(4), false).is_empty());
+    }
+
+    #[test]
+    fn test_messages_to_drop_oldest() {
+        assert_eq!(messages_to_drop(12, 10, None, true), 10..12);
+        assert_eq!(messages_to_drop(12, 10, Some(3), true), 10..12);
+    }
+
+    #[test]
+    fn test_messages_to_drop_newest() {
+        assert_eq!(messages_to_drop(12, 10, None, false), 0..2);
+        assert_eq!(messages_to_drop(12, 10, Some(5), false), 0..2);
+    }
+
+    #[test]
+    fn test_messages_to_drop_keeps_selected() {
+        // A new message arrives while the oldest message is selected.
+        assert!(messages_to_drop(11, 10, Some(10), true).is_empty());
+        assert_eq!(messages_to_drop(13, 10, Some(11), true), 12..13);
+        // Older messages are loaded while the newest message is selected.
+        assert!(messages_to_drop(12, 10, Some(0), false).is_empty());
+        assert_eq!(messages_to_drop(14, 10, Some(1), false), 0..1);
     }
 }
@@ -0,0 +1,87 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// The time after which a typing user is no longer shown, if TDLib does not
+/// send a new chat action.
+const TYPING_TIMEOUT: Duration = Duration::from_secs(6);
+/// The interval at which the typing action of the user is sent while they
+/// type, since the other clients show it for a few seconds only.
+pub const TYPING_ACTION_INTERVAL: Duration = Duration::from_secs(5);
+/// The duration of a frame of the animated ellipsis.
+const ELLIPSIS_FRAME: Duration = Duration::from_millis(400);
+/// The maximum number of names shown before the others are counted.
+const MAX_NAMES: usize = 2;
+
+/// The users that are typing in the chats.
+#[derive(Debug, Default)]
+pub struct TypingUsers {
+    /// The typing users of each chat, with the time when their action
+    /// expires, in the order they started typing.
+    chats: HashMap<i64, Vec<(i64, Instant)>>,
+}
+
+impl TypingUsers {
+    /// Record that a user started or stopped typing in a chat.
+    /// A user that keeps typing is refreshed, so it keeps its position.
+    pub fn set_typing(&mut self, chat_id: i64, user_id: i64, typing: bool) {
+        let users = self.chats.entry(chat_id).or_default();
+        let expires_at = Instant::now() + TYPING_TIMEOUT;
+        match (users.iter().position(|(id, _)| *id == user_id), typing) {
+            (Some(index), true) => users[index].1 = expires_at,
+            (Some(index), false) => {
+                users.remove(index);
+            }
+            (None, true) => users.push((user_id, expires_at)),
+            (None, false) => {}
+        }
+        if users.is_empty() {
+            self.chats.remove(&chat_id);
+        }
+    }
+
+    /// Get the users that are typing in a chat, removing the expired ones.
+    pub fn typing_users(&mut self, chat_id: i64) -> Vec<i64> {
+        let Some(users) = self.chats.get_mut(&chat_id) else {
+            return vec![];
+        };
+        let now = Instant::now();
+        users.retain(|(_, expires_at)| *expires_at > now);
+        let users = users.iter().map(|(id, _)| *id).collect::<Vec<i64>>();
+        if users.is_empty() {
+            self.chats.remove(&chat_id);
+        }
+        users
+    }
+}
+
+/// Build the line that lists the typing users, like "Alice, Bob and 2 others
+/// are typing...", with an ellipsis animated over time.
+///
+/// # Arguments
+/// * `names` - The names of the typing users.
+/// * `elapsed` - The time elapsed since any fixed instant, used to animate
+///   the ellipsis.
+///
+/// # Returns
+/// * `Option<String>` - The line or `None` if nobody is typing.
+pub fn typing_text(names: &[String], elapsed: Duration) -> Option<String> {
+    let subject = match names {
+        [] => return None,
+        [name] => format!("{} is", name),
+        [first, second] => format!("{} and {} are", first, second),
+        [first, second, third] => format!("{}, {} and {} are", first, second, third),
+        _ => format!(
+            "{} and {} others are",
+            names[..MAX_NAMES].join(", "),
+            names.len() - MAX_NAMES
+        ),
+    };
+    let dots = (elapsed.as_millis() / ELLIPSIS_FRAME.as_millis()) % 3 + 1;
+    Some(format!(
+        "{} typing{:<3}",
+        subject,
+        ".".repeat(dots as usize)
+    ))
+}
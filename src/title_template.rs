@@ -0,0 +1,173 @@
+/// The placeholders that can be used in the template of the title bar.
+pub const PLACEHOLDERS: [&str; 5] = [
+    "{app_name}",
+    "{chat_title}",
+    "{unread_total}",
+    "{connection}",
+    "{time}",
+];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+/// `TitlePart` is an enum that represents a part of the template of the
+/// title bar, either text or a placeholder.
+pub enum TitlePart {
+    /// Text shown as it is.
+    Text(String),
+    /// The name of the application.
+    AppName,
+    /// The title of the open chat.
+    ChatTitle,
+    /// The number of unread messages of the chats of the main list.
+    UnreadTotal,
+    /// The state of the connection to Telegram.
+    Connection,
+    /// The current time, in the configured timezone.
+    Time,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+/// `TitleTemplate` is a struct that represents the template of the title bar.
+/// It is parsed once, when the configuration is loaded, and filled at each
+/// frame.
+pub struct TitleTemplate {
+    /// The parts of the template, in order.
+    parts: Vec<TitlePart>,
+}
+
+#[derive(Clone, Debug, Default)]
+/// `TitleValues` is a struct that represents the values the placeholders of
+/// the template of the title bar are replaced with.
+pub struct TitleValues {
+    /// The name of the application.
+    pub app_name: String,
+    /// The title of the open chat, empty if no chat is open.
+    pub chat_title: String,
+    /// The number of unread messages of the chats of the main list.
+    pub unread_total: i32,
+    /// The state of the connection to Telegram.
+    pub connection: String,
+    /// The current time.
+    pub time: String,
+}
+
+impl TitleTemplate {
+    /// Parse the template of the title bar. The unknown placeholders are kept
+    /// as text.
+    ///
+    /// # Arguments
+    /// * `template` - The template, like `{app_name} - {chat_title}`.
+    ///
+    /// # Returns
+    /// * `Self` - The parsed template.
+    pub fn parse(template: &str) -> Self {
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut rest = template;
+        while !rest.is_empty() {
+            let placeholder = PLACEHOLDERS
+                .iter()
+                .find(|placeholder| rest.starts_with(**placeholder));
+            let part = match placeholder.copied() {
+                Some("{app_name}") => TitlePart::AppName,
+                Some("{chat_title}") => TitlePart::ChatTitle,
+                Some("{unread_total}") => TitlePart::UnreadTotal,
+                Some("{connection}") => TitlePart::Connection,
+                Some("{time}") => TitlePart::Time,
+                _ => {
+                    let c = rest.chars().next().unwrap();
+                    text.push(c);
+                    rest = &rest[c.len_utf8()..];
+                    continue;
+                }
+            };
+            if !text.is_empty() {
+                parts.push(TitlePart::Text(std::mem::take(&mut text)));
+            }
+            rest = &rest[placeholder.unwrap().len()..];
+            parts.push(part);
+        }
+        if !text.is_empty() {
+            parts.push(TitlePart::Text(text));
+        }
+        Self { parts }
+    }
+
+    /// Check whether the template uses a placeholder, so that its value is
+    /// computed only when it is shown.
+    ///
+    /// # Arguments
+    /// * `part` - The placeholder.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the template uses the placeholder.
+    pub fn uses(&self, part: &TitlePart) -> bool {
+        self.parts.contains(part)
+    }
+
+    /// Fill the template with the values of its placeholders.
+    ///
+    /// # Arguments
+    /// * `values` - The values of the placeholders.
+    ///
+    /// # Returns
+    /// * `String` - The text of the title bar.
+    pub fn fill(&self, values: &TitleValues) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                TitlePart::Text(text) => text.clone(),
+                TitlePart::AppName => values.app_name.clone(),
+                TitlePart::ChatTitle => values.chat_title.clone(),
+                TitlePart::UnreadTotal => values.unread_total.to_string(),
+                TitlePart::Connection => values.connection.clone(),
+                TitlePart::Time => values.time.clone(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_placeholders_and_text() {
+        let template = TitleTemplate::parse("{app_name} - {chat_title} ({unread_total})");
+        assert_eq!(
+            template.parts,
+            vec![
+                TitlePart::AppName,
+                TitlePart::Text(" - ".to_string()),
+                TitlePart::ChatTitle,
+                TitlePart::Text(" (".to_string()),
+                TitlePart::UnreadTotal,
+                TitlePart::Text(")".to_string()),
+            ]
+        );
+        assert!(template.uses(&TitlePart::ChatTitle));
+        assert!(!template.uses(&TitlePart::Time));
+    }
+
+    #[test]
+    fn test_unknown_placeholders_are_text() {
+        let template = TitleTemplate::parse("{unknown} é {time");
+        assert_eq!(
+            template.parts,
+            vec![TitlePart::Text("{unknown} é {time".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_fill() {
+        let template = TitleTemplate::parse("{app_name} {connection} {time} {unread_total}");
+        let values = TitleValues {
+            app_name: "tgt".to_string(),
+            chat_title: "Saved Messages".to_string(),
+            unread_total: 3,
+            connection: "connected".to_string(),
+            time: "09:30".to_string(),
+        };
+        assert_eq!(template.fill(&values), "tgt connected 09:30 3");
+        assert_eq!(TitleTemplate::parse("").fill(&values), "");
+    }
+}
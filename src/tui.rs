@@ -10,9 +10,9 @@ use crate::{
     event::Event,
 };
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use std::hash::{DefaultHasher, Hash};
 use std::{collections::HashMap, hash::Hasher, sync::Arc};
 use tokio::sync::mpsc::UnboundedSender;
-use std::hash::{Hash, DefaultHasher};
 
 /// `Tui` is a struct that represents the main user interface for the
 /// application. It is responsible for managing the layout and rendering of all
@@ -159,7 +159,12 @@ impl Tui {
                 Constraint::Min(SMALL_AREA_HEIGHT),
                 Constraint::Length(if self.app_context.app_config().show_status_bar {
                     if area.height > SMALL_AREA_HEIGHT + 5 {
-                        3
+                        // The key hints are shown on a second line.
+                        if self.app_context.app_config().show_key_hints {
+                            4
+                        } else {
+                            3
+                        }
                     } else {
                         0
                     }
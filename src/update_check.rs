@@ -0,0 +1,114 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+/// The GitHub API endpoint of the latest release of `tgt`.
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/FedericoBruzzone/tgt/releases/latest";
+/// The maximum time spent to check for updates, so that air-gapped setups
+/// are not slowed down.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+/// `ReleaseInfo` is a struct that represents a release of `tgt` published on
+/// GitHub.
+pub struct ReleaseInfo {
+    /// The version of the release, without the leading `v`.
+    pub version: String,
+    /// The changelog of the release, as written in its description.
+    pub changelog: String,
+    /// The URL of the page of the release.
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+/// The fields of a release returned by the GitHub API that are used.
+struct GithubRelease {
+    tag_name: String,
+    body: Option<String>,
+    html_url: String,
+}
+
+/// Get the version of the running `tgt`.
+///
+/// # Returns
+/// * `&'static str` - The version, like `1.0.0`.
+pub fn current_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// Parse a version like `v1.2.3` or `1.2.3-beta` into its numbers.
+/// A pre-release suffix is ignored.
+///
+/// # Arguments
+/// * `version` - The version.
+///
+/// # Returns
+/// * `Option<(u64, u64, u64)>` - The major, minor and patch numbers.
+pub fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split(['-', '+']).next()?;
+    let mut numbers = version.split('.').map(|number| number.parse::<u64>());
+    let major = numbers.next()?.ok()?;
+    let minor = numbers.next().unwrap_or(Ok(0)).ok()?;
+    let patch = numbers.next().unwrap_or(Ok(0)).ok()?;
+    Some((major, minor, patch))
+}
+
+/// Check whether a version is newer than another one.
+///
+/// # Arguments
+/// * `latest` - The version of the latest release.
+/// * `current` - The version of the running `tgt`.
+///
+/// # Returns
+/// * `bool` - Whether `latest` is newer than `current`. Versions that can
+///   not be parsed are never newer.
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+/// Fetch the latest release of `tgt` from GitHub.
+///
+/// # Returns
+/// * `Result<ReleaseInfo, String>` - The latest release or the reason why it
+///   can not be fetched.
+pub async fn fetch_latest_release() -> Result<ReleaseInfo, String> {
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(concat!("tgt/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .get(LATEST_RELEASE_URL)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("GitHub answered with {}", response.status()));
+    }
+    let text = response.text().await.map_err(|e| e.to_string())?;
+    let release: GithubRelease = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    Ok(ReleaseInfo {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        changelog: release.body.unwrap_or_default().replace("\r\n", "\n"),
+        url: release.html_url,
+    })
+}
+
+/// Check whether a newer release of `tgt` is available.
+///
+/// # Returns
+/// * `Result<Option<ReleaseInfo>, String>` - The newer release, `None` if
+///   the running version is the latest, or the reason why the check failed.
+pub async fn check() -> Result<Option<ReleaseInfo>, String> {
+    let release = fetch_latest_release().await?;
+    if is_newer(&release.version, current_version()) {
+        Ok(Some(release))
+    } else {
+        Ok(None)
+    }
+}
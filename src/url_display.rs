@@ -0,0 +1,149 @@
+use crate::url_preview::domain_of;
+use ratatui::text::{Line, Span};
+use std::ops::Range;
+
+/// The characters that end a sentence and are not part of a link written
+/// before them, like the final dot of "see https://example.com.".
+const TRAILING_PUNCTUATION: [char; 9] = ['.', ',', ')', ';', ':', '!', '?', '"', '\''];
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// `UrlDisplay` is an enum that represents how the links are shown in the
+/// messages. The message always keeps the full links, so that they are
+/// copied and opened whole.
+pub enum UrlDisplay {
+    /// The links are shown whole.
+    Full,
+    /// The links are shown by their domain, like `example.com/…`.
+    Domain,
+    /// The links are replaced by numbers, like `[1]`, and listed at the end
+    /// of the message.
+    Footnotes,
+}
+impl UrlDisplay {
+    /// Parse the display mode of the links from the application
+    /// configuration.
+    ///
+    /// # Arguments
+    /// * `value` - The mode as written in the configuration.
+    ///
+    /// # Returns
+    /// * `Self` - The mode, or `Full` if it is invalid.
+    pub fn from_config(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "domain" => Self::Domain,
+            "footnotes" => Self::Footnotes,
+            _ => Self::Full,
+        }
+    }
+}
+
+/// Find the http(s) links in a text.
+///
+/// # Arguments
+/// * `text` - The text.
+///
+/// # Returns
+/// * `Vec<Range<usize>>` - The byte ranges of the links, in order.
+pub fn find_urls(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut word_start = None;
+    for (i, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        if !c.is_whitespace() {
+            word_start.get_or_insert(i);
+            continue;
+        }
+        let Some(start) = word_start.take() else {
+            continue;
+        };
+        // A link can follow an opening punctuation, like "(https://...)".
+        let word = &text[start..i];
+        if let Some(offset) = word.find("http") {
+            let url = word[offset..].trim_end_matches(TRAILING_PUNCTUATION);
+            if domain_of(url).is_some() {
+                ranges.push(start + offset..start + offset + url.len());
+            }
+        }
+    }
+    ranges
+}
+
+/// Shorten a link to its domain, followed by an ellipsis if the link has a
+/// path, like `example.com/…`.
+///
+/// # Arguments
+/// * `url` - The link.
+///
+/// # Returns
+/// * `String` - The shortened link, or the link itself if it is not a
+///   http(s) link.
+pub fn shorten_url(url: &str) -> String {
+    let Some(domain) = domain_of(url) else {
+        return url.to_string();
+    };
+    let rest = url.split_once("://").map_or("", |(_, rest)| rest);
+    let path = rest.find(['/', '?', '#']).map_or("", |i| &rest[i..]);
+    if path.len() > 1 {
+        format!("{}/…", domain)
+    } else {
+        domain
+    }
+}
+
+/// Apply a display mode to the links of the lines of a message.
+///
+/// # Arguments
+/// * `lines` - The lines of the message.
+/// * `mode` - The display mode of the links.
+///
+/// # Returns
+/// * `(Vec<Line<'static>>, Vec<String>)` - The lines to show and, in the
+///   footnotes mode, the links in the order of their numbers.
+pub fn display_lines(
+    lines: &[Line<'static>],
+    mode: UrlDisplay,
+) -> (Vec<Line<'static>>, Vec<String>) {
+    let mut footnotes = Vec::new();
+    if mode == UrlDisplay::Full {
+        return (lines.to_vec(), footnotes);
+    }
+    let lines = lines
+        .iter()
+        .map(|line| {
+            let mut spans = Vec::with_capacity(line.spans.len());
+            for span in line.spans.iter() {
+                let mut start = 0;
+                for range in find_urls(&span.content) {
+                    let url = &span.content[range.clone()];
+                    let shown = match mode {
+                        UrlDisplay::Footnotes => {
+                            footnotes.push(url.to_string());
+                            format!("[{}]", footnotes.len())
+                        }
+                        _ => shorten_url(url),
+                    };
+                    if range.start > start {
+                        spans.push(Span::styled(
+                            span.content[start..range.start].to_string(),
+                            span.style,
+                        ));
+                    }
+                    spans.push(Span::styled(shown, span.style));
+                    start = range.end;
+                }
+                if start == 0 {
+                    spans.push(span.clone());
+                } else if start < span.content.len() {
+                    spans.push(Span::styled(span.content[start..].to_string(), span.style));
+                }
+            }
+            Line {
+                spans,
+                ..line.clone()
+            }
+        })
+        .collect();
+    (lines, footnotes)
+}
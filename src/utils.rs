@@ -31,6 +31,349 @@ pub fn tgt_dir() -> io::Result<PathBuf> {
 pub fn tgt_config_dir() -> io::Result<PathBuf> {
     Ok(tgt_dir()?.join("config"))
 }
+/// Get the directory where the local data of the application is stored.
+///
+/// # Returns
+/// The local data directory.
+pub fn tgt_data_dir() -> io::Result<PathBuf> {
+    Ok(tgt_dir()?.join(".data"))
+}
+
+/// Quote a string so that it is passed as a single argument to the shell.
+///
+/// # Arguments
+/// * `s` - The string to quote.
+///
+/// # Returns
+/// * `String` - The quoted string.
+pub fn shell_quote(s: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        format!("'{}'", s.replace('\'', "'\\''"))
+    }
+}
+
+/// Replace the placeholders of a text with their values.
+/// The text is scanned once, so that a value that contains a placeholder is
+/// never expanded again.
+///
+/// # Arguments
+/// * `text` - The text with the placeholders.
+/// * `values` - The placeholders, like `{text}`, and their values.
+///
+/// # Returns
+/// * `String` - The text with the values.
+pub fn expand_placeholders(text: &str, values: &[(&str, &str)]) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        match values
+            .iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            Some((placeholder, value)) => {
+                expanded.push_str(value);
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                let c = rest.chars().next().unwrap();
+                expanded.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    expanded
+}
+
+/// Replace the placeholders of a command line with quoted values.
+/// The command line is scanned once, so that a value that contains a
+/// placeholder is never expanded again.
+///
+/// # Arguments
+/// * `command_line` - The command line with the placeholders.
+/// * `values` - The placeholders, like `{text}`, and their values.
+///
+/// # Returns
+/// * `String` - The command line with the quoted values.
+pub fn expand_shell_placeholders(command_line: &str, values: &[(&str, &str)]) -> String {
+    let quoted: Vec<(&str, String)> = values
+        .iter()
+        .map(|(placeholder, value)| (*placeholder, shell_quote(value)))
+        .collect();
+    let quoted: Vec<(&str, &str)> = quoted
+        .iter()
+        .map(|(placeholder, value)| (*placeholder, value.as_str()))
+        .collect();
+    expand_placeholders(command_line, &quoted)
+}
+
+/// Create a command that runs a command line with the system shell.
+/// It uses `sh -c` on Unix and `cmd /C` on Windows.
+///
+/// # Arguments
+/// * `command_line` - The command line to run.
+///
+/// # Returns
+/// * `tokio::process::Command` - The command, ready to be spawned.
+pub fn shell_command(command_line: &str) -> tokio::process::Command {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut command = tokio::process::Command::new(shell);
+    command.arg(flag).arg(command_line);
+    command
+}
+
+/// Format a size in bytes in a human readable way.
+///
+/// # Arguments
+/// * `bytes` - The size in bytes.
+///
+/// # Returns
+/// * `String` - The formatted size, for example `1.5 GB`.
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Get the memory of the process resident in RAM, read from
+/// `/proc/self/statm`.
+///
+/// # Returns
+/// * `Option<u64>` - The size in bytes or `None` if it is not available,
+///   like on macOS and Windows.
+pub fn resident_memory() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages * page_size()?)
+}
+
+/// Get the size of a page of memory of the system.
+///
+/// # Returns
+/// * `Option<u64>` - The size in bytes or `None` if it is not available.
+#[cfg(unix)]
+fn page_size() -> Option<u64> {
+    // SAFETY: `sysconf` only reads a configuration value of the system.
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    u64::try_from(size).ok().filter(|size| *size > 0)
+}
+
+#[cfg(not(unix))]
+fn page_size() -> Option<u64> {
+    None
+}
+
+/// Format a duration in a human readable form, using the largest unit that
+/// fits in it.
+///
+/// # Arguments
+/// * `seconds` - The duration in seconds.
+///
+/// # Returns
+/// * `String` - The formatted duration, for example `1 week`.
+pub fn human_duration(seconds: u64) -> String {
+    const UNITS: [(&str, u64); 7] = [
+        ("year", 365 * 86400),
+        ("month", 30 * 86400),
+        ("week", 7 * 86400),
+        ("day", 86400),
+        ("hour", 3600),
+        ("minute", 60),
+        ("second", 1),
+    ];
+    let (unit, size) = UNITS
+        .into_iter()
+        .find(|(_, size)| seconds >= *size)
+        .unwrap_or(UNITS[UNITS.len() - 1]);
+    let count = seconds / size;
+    format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+}
+
+/// Decode a base64 string, as used by TDLib for the `bytes` fields.
+///
+/// # Arguments
+/// * `data` - The base64 string, with or without padding.
+///
+/// # Returns
+/// * `Option<Vec<u8>>` - The decoded bytes or `None` if the string is not
+///   valid base64.
+pub fn decode_base64(data: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in data.trim_end_matches('=').bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+/// Render the visualization of the key of a secret chat, that can be compared
+/// with the one shown on the device of the other user.
+/// As described by TDLib, the hash is split into groups of 2 bits, each of
+/// them is a pixel of one of 4 colors of a 12x12 square, filled from left to
+/// right and from top to bottom. Each color is drawn with a shade of similar
+/// lightness.
+///
+/// # Arguments
+/// * `key_hash` - The hash of the key of the secret chat.
+///
+/// # Returns
+/// * `String` - The lines of the visualization.
+pub fn render_key_visualization(key_hash: &[u8]) -> String {
+    // The size of the square, in pixels.
+    const SIZE: usize = 12;
+    // The shades of the colors FFFFFF, D5E6F3, 2D5775 and 2F99C9.
+    const SHADES: [&str; 4] = ["  ", "░░", "██", "▒▒"];
+    let mut lines = String::new();
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let bit = (y * SIZE + x) * 2;
+            let pixel = key_hash
+                .get(bit / 8)
+                .map(|byte| (byte >> (bit % 8)) & 3)
+                .unwrap_or_default();
+            lines.push_str(SHADES[pixel as usize]);
+        }
+        lines.push('\n');
+    }
+    lines
+}
+
+/// Format the key of a secret chat as the hexadecimal numbers of the first 32
+/// bytes of its hash, 8 numbers per line.
+///
+/// # Arguments
+/// * `key_hash` - The hash of the key of the secret chat.
+///
+/// # Returns
+/// * `String` - The lines of the hexadecimal fingerprint.
+pub fn format_key_fingerprint(key_hash: &[u8]) -> String {
+    key_hash
+        .iter()
+        .take(32)
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<String>>()
+        .chunks(8)
+        .map(|line| line.join(" "))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Render a QR code in the terminal using unicode block characters.
+/// Each character represents two modules of the QR code, one on top of the
+/// other. Light modules are drawn as filled blocks, so that the code can be
+/// scanned from a terminal with a dark background.
+///
+/// # Arguments
+/// * `data` - The data encoded in the QR code.
+///
+/// # Returns
+/// * `Option<String>` - The lines of the QR code or `None` if the data can not
+///   be encoded.
+pub fn render_qr_code(data: &str) -> Option<String> {
+    // The quiet zone around the code, in modules.
+    const QUIET_ZONE: usize = 2;
+    let code = qrcode::QrCode::new(data.as_bytes()).ok()?;
+    let width = code.width();
+    let colors = code.to_colors();
+    let size = width + 2 * QUIET_ZONE;
+    let is_light = |x: usize, y: usize| {
+        if x < QUIET_ZONE || y < QUIET_ZONE || x >= width + QUIET_ZONE || y >= width + QUIET_ZONE {
+            return true;
+        }
+        colors[(y - QUIET_ZONE) * width + (x - QUIET_ZONE)] == qrcode::Color::Light
+    };
+
+    let mut lines = String::new();
+    for y in (0..size).step_by(2) {
+        for x in 0..size {
+            let top = is_light(x, y);
+            let bottom = y + 1 >= size || is_light(x, y + 1);
+            lines.push(match (top, bottom) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            });
+        }
+        lines.push('\n');
+    }
+    Some(lines)
+}
+
+/// Match a pattern against a text using a fuzzy search.
+/// The characters of the pattern must appear in the text in the same order,
+/// but not necessarily next to each other. The comparison is case
+/// insensitive. Consecutive matches and matches at the start of a word give
+/// a higher score.
+///
+/// # Arguments
+/// * `pattern` - The pattern to search for.
+/// * `text` - The text to search in.
+///
+/// # Returns
+/// * `Option<i64>` - The score of the match or `None` if the text does not
+///   match the pattern.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<i64> {
+    let mut score = 0;
+    let mut text_chars = text.chars().flat_map(char::to_lowercase);
+    let mut previous: Option<char> = None;
+    let mut consecutive = false;
+    for p in pattern.chars().flat_map(char::to_lowercase) {
+        if p.is_whitespace() {
+            continue;
+        }
+        loop {
+            let c = text_chars.next()?;
+            let word_start = match previous {
+                Some(prev) => !prev.is_alphanumeric(),
+                None => true,
+            };
+            previous = Some(c);
+            if c == p {
+                score += 1;
+                if consecutive {
+                    score += 5;
+                }
+                if word_start {
+                    score += 10;
+                }
+                consecutive = true;
+                break;
+            }
+            consecutive = false;
+            score -= 1;
+        }
+    }
+    Some(score)
+}
 
 /// Fail with an error message and exit the application.
 ///
@@ -45,6 +388,39 @@ fn fail_with<E: std::fmt::Debug>(msg: &str, e: E) -> ! {
     std::process::exit(1);
 }
 
+/// Find the occurrences of a query in a text, ignoring the case.
+///
+/// # Arguments
+/// * `text` - The text to search in.
+/// * `query` - The text to search for.
+///
+/// # Returns
+/// * `Vec<std::ops::Range<usize>>` - The byte ranges of the occurrences in
+///   the text, in order and not overlapping.
+pub fn find_ignore_case(text: &str, query: &str) -> Vec<std::ops::Range<usize>> {
+    let fold = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let query = query.chars().map(fold).collect::<Vec<char>>();
+    let mut ranges = Vec::new();
+    if query.is_empty() {
+        return ranges;
+    }
+    let chars = text.char_indices().collect::<Vec<(usize, char)>>();
+    let mut i = 0;
+    while i + query.len() <= chars.len() {
+        let window = &chars[i..i + query.len()];
+        if window.iter().zip(&query).all(|((_, c), q)| fold(*c) == *q) {
+            let end = chars
+                .get(i + query.len())
+                .map_or(text.len(), |(start, _)| *start);
+            ranges.push(chars[i].0..end);
+            i += query.len();
+        } else {
+            i += 1;
+        }
+    }
+    ranges
+}
+
 /// Unwrap a result or fail with an error message.
 /// This function will unwrap a result and return the value if it is Ok.
 /// If the result is an error, this function will fail with an error message.